@@ -0,0 +1,553 @@
+//! Aggregated statistics for the overviews on the home and training pages.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::{
+    avg_weekly_change, current_cycle, cycles, total_stimulus_per_muscle, training_streak, trend,
+    weekly_summary, BodyWeight, Clock, CurrentCycle, Exercise, Interval, LoadRecommendation,
+    Period, TrainingSession, TrainingStats, TrainingStreak, Trend, WeeklyChange, WeeklySummary,
+};
+
+/// Data of a user from which the dashboard is computed.
+pub struct UserData<'a> {
+    pub training_sessions: &'a BTreeMap<u32, TrainingSession>,
+    pub exercises: &'a BTreeMap<u32, Exercise>,
+    /// Statistics of all training sessions, which are expected to be kept up to date by the
+    /// caller, as a complete recalculation is expensive (cf. `TrainingStats::update`).
+    pub training_stats: &'a TrainingStats,
+    pub avg_body_weight: &'a BTreeMap<NaiveDate, BodyWeight>,
+    pub period: &'a BTreeMap<NaiveDate, Period>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    pub weekly_session_target: u32,
+    /// Number of weeks on which the load recommendation is based.
+    pub recommendation_weeks: usize,
+    /// Maximum gap in days between two body weight values which are considered for the trend.
+    pub max_gap_days: i64,
+    /// Number of days over which the trends are determined.
+    pub trend_window_days: i64,
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct Dashboard {
+    pub last_training_session: Option<NaiveDate>,
+    /// Ratio of the short-term to the long-term load on the latest day.
+    pub load_ratio: Option<f32>,
+    pub load_recommendation: Option<LoadRecommendation>,
+    /// Short-term load in the interval.
+    pub short_term_load: Vec<(NaiveDate, f32)>,
+    /// Long-term load in the interval.
+    pub long_term_load: Vec<(NaiveDate, f32)>,
+    /// Centered moving total of the set volume over seven days in the interval.
+    pub total_7day_set_volume: Vec<(NaiveDate, f32)>,
+    /// Centered moving average of the RPE over seven days in the interval.
+    pub average_7day_rpe: Vec<Vec<(NaiveDate, f32)>>,
+    /// Change of the load ratio over the trend window.
+    pub load_ratio_trend: Option<Trend>,
+    /// Change of the set volume of the last seven days over the trend window.
+    pub set_volume_trend: Option<Trend>,
+    /// Summary of the current week.
+    pub weekly_summary: Option<WeeklySummary>,
+    pub previous_weekly_summary: Option<WeeklySummary>,
+    pub training_streak: TrainingStreak,
+    /// Stimulus per muscle of the last seven days up to today.
+    pub stimulus_per_muscle_7_days: BTreeMap<u8, u32>,
+    /// Stimulus per muscle of the last 28 days up to today.
+    pub stimulus_per_muscle_28_days: BTreeMap<u8, u32>,
+    pub latest_avg_body_weight: Option<BodyWeight>,
+    /// Weekly change of the latest average body weight.
+    pub body_weight_trend: Option<WeeklyChange>,
+    /// Change of the average body weight over the trend window.
+    pub body_weight_window_trend: Option<Trend>,
+    pub current_cycle: Option<CurrentCycle>,
+}
+
+/// Compute all statistics shown on the home and training pages.
+///
+/// Training sessions after today are not considered for the weekly summaries, the training streak
+/// and the stimulus per muscle. The interval only affects the time series.
+#[must_use]
+pub fn compute(
+    user_data: &UserData,
+    interval: &Interval,
+    options: &Options,
+    clock: &impl Clock,
+) -> Dashboard {
+    let today = clock.today();
+    let training_sessions = user_data.training_sessions.values().collect::<Vec<_>>();
+    let in_interval =
+        |(date, _): &&(NaiveDate, f32)| *date >= interval.first && *date <= interval.last;
+
+    #[allow(clippy::cast_precision_loss)]
+    let total_7day_set_volume = crate::centered_moving_total(
+        &training_sessions
+            .iter()
+            .map(|t| (t.date, t.set_volume() as f32))
+            .collect::<Vec<_>>(),
+        interval,
+        3,
+    );
+    let average_7day_rpe = crate::centered_moving_average(
+        &training_sessions
+            .iter()
+            .filter_map(|t| t.avg_rpe().map(|rpe| (t.date, rpe)))
+            .collect::<Vec<_>>(),
+        interval,
+        3,
+    );
+
+    let latest_avg_body_weight = user_data
+        .avg_body_weight
+        .last_key_value()
+        .map(|(_, avg_body_weight)| avg_body_weight.clone());
+
+    Dashboard {
+        last_training_session: training_sessions.iter().map(|t| t.date).max(),
+        load_ratio: user_data.training_stats.load_ratio(),
+        load_recommendation: user_data
+            .training_stats
+            .load_recommendation(options.recommendation_weeks),
+        load_ratio_trend: trend(
+            &user_data.training_stats.load_ratios(),
+            options.trend_window_days,
+        ),
+        set_volume_trend: set_volume_trend(&training_sessions, options.trend_window_days, today),
+        short_term_load: user_data
+            .training_stats
+            .short_term_load
+            .iter()
+            .filter(in_interval)
+            .copied()
+            .collect(),
+        long_term_load: user_data
+            .training_stats
+            .long_term_load
+            .iter()
+            .filter(in_interval)
+            .copied()
+            .collect(),
+        total_7day_set_volume,
+        average_7day_rpe,
+        weekly_summary: weekly_summary(
+            &training_sessions,
+            user_data.exercises,
+            today.iso_week(),
+            today,
+        ),
+        previous_weekly_summary: weekly_summary(
+            &training_sessions,
+            user_data.exercises,
+            (today - Duration::days(7)).iso_week(),
+            today,
+        ),
+        training_streak: training_streak(
+            &training_sessions.iter().map(|t| t.date).collect::<Vec<_>>(),
+            options.weekly_session_target,
+            today,
+        ),
+        stimulus_per_muscle_7_days: stimulus_per_muscle(
+            &training_sessions,
+            user_data.exercises,
+            7,
+            today,
+        ),
+        stimulus_per_muscle_28_days: stimulus_per_muscle(
+            &training_sessions,
+            user_data.exercises,
+            28,
+            today,
+        ),
+        body_weight_trend: avg_weekly_change(
+            user_data.avg_body_weight,
+            latest_avg_body_weight.as_ref(),
+            options.max_gap_days,
+        ),
+        body_weight_window_trend: trend(
+            &user_data
+                .avg_body_weight
+                .values()
+                .map(|bw| (bw.date, bw.weight))
+                .collect::<Vec<_>>(),
+            options.trend_window_days,
+        ),
+        latest_avg_body_weight,
+        current_cycle: current_cycle(user_data.period, &cycles(user_data.period), clock),
+    }
+}
+
+fn stimulus_per_muscle(
+    training_sessions: &[&TrainingSession],
+    exercises: &BTreeMap<u32, Exercise>,
+    days: i64,
+    today: NaiveDate,
+) -> BTreeMap<u8, u32> {
+    total_stimulus_per_muscle(
+        training_sessions,
+        exercises,
+        &Interval {
+            first: today - Duration::days(days - 1),
+            last: today,
+        },
+    )
+}
+
+/// Compare the set volume of the last seven days up to today with the set volume of the seven
+/// days up to the start of the window.
+fn set_volume_trend(
+    training_sessions: &[&TrainingSession],
+    window_days: i64,
+    today: NaiveDate,
+) -> Option<Trend> {
+    let set_volume_7_days = |last: NaiveDate| {
+        #[allow(clippy::cast_precision_loss)]
+        let set_volume = training_sessions
+            .iter()
+            .filter(|t| t.date > last - Duration::days(7) && t.date <= last)
+            .map(|t| t.set_volume())
+            .sum::<u32>() as f32;
+        (last, set_volume)
+    };
+    trend(
+        &[
+            set_volume_7_days(today - Duration::days(window_days)),
+            set_volume_7_days(today),
+        ],
+        window_days,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        training_stats, ExerciseMuscle, FixedClock, Laterality, Muscle, TrainingSessionElement,
+        TrendDirection,
+    };
+
+    const OPTIONS: Options = Options {
+        weekly_session_target: 1,
+        recommendation_weeks: 4,
+        max_gap_days: 30,
+        trend_window_days: 28,
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn exercises() -> BTreeMap<u32, Exercise> {
+        BTreeMap::from([(
+            1,
+            Exercise {
+                id: 1,
+                name: String::from("Squat"),
+                weight_convention: None,
+                base_weight: None,
+                laterality: Laterality::Bilateral,
+                archived: false,
+                image: None,
+                muscles: vec![ExerciseMuscle {
+                    muscle_id: Muscle::Quads.id(),
+                    stimulus: 100,
+                }],
+            },
+        )])
+    }
+
+    fn training_session(id: u32, date: NaiveDate, sets: usize) -> TrainingSession {
+        TrainingSession {
+            id,
+            routine_id: None,
+            date,
+            notes: None,
+            start_time: None,
+            end_time: None,
+            elements: vec![
+                TrainingSessionElement::Set {
+                    exercise_id: 1,
+                    reps: Some(5),
+                    time: None,
+                    weight: Some(100.0),
+                    rpe: Some(8.0),
+                    target_reps: None,
+                    target_time: None,
+                    target_weight: None,
+                    target_rpe: None,
+                    automatic: false,
+                    partial_reps: None,
+                    to_failure: false,
+                    side: None,
+                    comment: None,
+                };
+                sets
+            ],
+        }
+    }
+
+    fn training_sessions() -> BTreeMap<u32, TrainingSession> {
+        [
+            training_session(1, date(1, 2), 3),
+            training_session(2, date(1, 24), 2),
+            training_session(3, date(1, 29), 4),
+            training_session(4, date(2, 1), 3),
+            training_session(5, date(2, 3), 1),
+        ]
+        .into_iter()
+        .map(|t| (t.id, t))
+        .collect()
+    }
+
+    fn body_weight(month: u32, day: u32, weight: f32) -> (NaiveDate, BodyWeight) {
+        (
+            date(month, day),
+            BodyWeight {
+                date: date(month, day),
+                weight,
+            },
+        )
+    }
+
+    fn period(month: u32, day: u32) -> (NaiveDate, Period) {
+        (
+            date(month, day),
+            Period {
+                date: date(month, day),
+                intensity: 2,
+            },
+        )
+    }
+
+    #[test]
+    fn test_compute_empty() {
+        let clock = FixedClock(date(2, 2));
+        let training_sessions = BTreeMap::new();
+        let training_stats = training_stats(&[], &clock);
+        let empty = BTreeMap::new();
+
+        let dashboard = compute(
+            &UserData {
+                training_sessions: &training_sessions,
+                exercises: &BTreeMap::new(),
+                training_stats: &training_stats,
+                avg_body_weight: &empty,
+                period: &BTreeMap::new(),
+            },
+            &Interval {
+                first: date(1, 1),
+                last: date(2, 2),
+            },
+            &OPTIONS,
+            &clock,
+        );
+
+        assert_eq!(dashboard.last_training_session, None);
+        assert_eq!(dashboard.load_ratio, None);
+        assert_eq!(dashboard.load_recommendation, None);
+        assert_eq!(dashboard.load_ratio_trend, None);
+        assert_eq!(dashboard.set_volume_trend, None);
+        assert_eq!(dashboard.weekly_summary, None);
+        assert_eq!(dashboard.previous_weekly_summary, None);
+        assert_eq!(dashboard.training_streak, TrainingStreak::default());
+        assert!(dashboard
+            .stimulus_per_muscle_7_days
+            .values()
+            .all(|s| *s == 0));
+        assert!(dashboard
+            .stimulus_per_muscle_28_days
+            .values()
+            .all(|s| *s == 0));
+        assert_eq!(dashboard.latest_avg_body_weight, None);
+        assert_eq!(dashboard.body_weight_trend, None);
+        assert_eq!(dashboard.body_weight_window_trend, None);
+        assert_eq!(dashboard.current_cycle, None);
+    }
+
+    #[test]
+    fn test_compute_training() {
+        let clock = FixedClock(date(2, 2));
+        let training_sessions = training_sessions();
+        let exercises = exercises();
+        let training_stats =
+            training_stats(&training_sessions.values().collect::<Vec<_>>(), &clock);
+        let interval = Interval {
+            first: date(1, 28),
+            last: date(2, 3),
+        };
+
+        let dashboard = compute(
+            &UserData {
+                training_sessions: &training_sessions,
+                exercises: &exercises,
+                training_stats: &training_stats,
+                avg_body_weight: &BTreeMap::new(),
+                period: &BTreeMap::new(),
+            },
+            &interval,
+            &OPTIONS,
+            &clock,
+        );
+
+        assert_eq!(dashboard.last_training_session, Some(date(2, 3)));
+        assert!(dashboard.load_ratio.is_some());
+        assert_eq!(dashboard.load_ratio, training_stats.load_ratio());
+        assert_eq!(
+            dashboard.load_recommendation,
+            training_stats.load_recommendation(OPTIONS.recommendation_weeks)
+        );
+        assert_eq!(
+            dashboard.load_ratio_trend,
+            trend(&training_stats.load_ratios(), OPTIONS.trend_window_days)
+        );
+        assert_eq!(
+            dashboard
+                .short_term_load
+                .iter()
+                .map(|(date, _)| *date)
+                .collect::<Vec<_>>(),
+            date(1, 28)
+                .iter_days()
+                .take_while(|d| *d <= date(2, 3))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            dashboard.long_term_load.first().map(|(date, _)| *date),
+            Some(date(1, 29))
+        );
+        assert_eq!(
+            dashboard.long_term_load.last().map(|(date, _)| *date),
+            Some(date(2, 3))
+        );
+        assert_eq!(
+            dashboard.total_7day_set_volume,
+            vec![
+                (date(1, 28), 4.0),
+                (date(1, 29), 7.0),
+                (date(1, 30), 7.0),
+                (date(1, 31), 8.0),
+                (date(2, 1), 8.0),
+                (date(2, 2), 4.0),
+                (date(2, 3), 4.0),
+            ]
+        );
+        assert_eq!(
+            dashboard.average_7day_rpe,
+            vec![date(1, 28)
+                .iter_days()
+                .take_while(|d| *d <= date(2, 3))
+                .map(|d| (d, 8.0))
+                .collect::<Vec<_>>()]
+        );
+        assert_eq!(
+            dashboard.weekly_summary.map(|s| (s.sessions, s.hard_sets)),
+            Some((2, 7))
+        );
+        assert_eq!(
+            dashboard
+                .previous_weekly_summary
+                .map(|s| (s.sessions, s.hard_sets)),
+            Some((1, 2))
+        );
+        assert_eq!(
+            dashboard.training_streak,
+            TrainingStreak {
+                current: 2,
+                longest: 2
+            }
+        );
+        assert_eq!(
+            dashboard.stimulus_per_muscle_7_days[&Muscle::Quads.id()],
+            700
+        );
+        assert_eq!(
+            dashboard.stimulus_per_muscle_28_days[&Muscle::Quads.id()],
+            900
+        );
+        assert_eq!(dashboard.stimulus_per_muscle_28_days[&Muscle::Pecs.id()], 0);
+    }
+
+    #[test]
+    fn test_set_volume_trend() {
+        let training_sessions = training_sessions();
+        let training_sessions = training_sessions.values().collect::<Vec<_>>();
+
+        let trend = set_volume_trend(&training_sessions, 28, date(2, 2)).unwrap();
+
+        assert_eq!(trend.direction, TrendDirection::Up);
+        assert_approx_eq!(trend.percent, 133.333, 0.001);
+        assert_eq!(set_volume_trend(&training_sessions, 14, date(2, 2)), None);
+        assert_eq!(set_volume_trend(&training_sessions, 0, date(2, 2)), None);
+    }
+
+    #[test]
+    fn test_compute_body_weight_and_cycle() {
+        let clock = FixedClock(date(2, 2));
+        let training_sessions = BTreeMap::new();
+        let training_stats = training_stats(&[], &clock);
+        let avg_body_weight = BTreeMap::from([
+            body_weight(1, 20, 81.0),
+            body_weight(1, 24, 80.5),
+            body_weight(1, 27, 80.0),
+        ]);
+        let period = BTreeMap::from([period(1, 3), period(1, 4), period(1, 31), period(2, 1)]);
+
+        let dashboard = compute(
+            &UserData {
+                training_sessions: &training_sessions,
+                exercises: &BTreeMap::new(),
+                training_stats: &training_stats,
+                avg_body_weight: &avg_body_weight,
+                period: &period,
+            },
+            &Interval {
+                first: date(1, 1),
+                last: date(2, 2),
+            },
+            &Options {
+                trend_window_days: 7,
+                ..OPTIONS
+            },
+            &clock,
+        );
+
+        assert_eq!(
+            dashboard.latest_avg_body_weight,
+            Some(BodyWeight {
+                date: date(1, 27),
+                weight: 80.0
+            })
+        );
+        assert_eq!(
+            dashboard.body_weight_trend,
+            avg_weekly_change(
+                &avg_body_weight,
+                avg_body_weight.get(&date(1, 27)),
+                OPTIONS.max_gap_days
+            )
+        );
+        assert!(dashboard.body_weight_trend.unwrap().weight < 0.0);
+        assert_eq!(
+            dashboard.body_weight_window_trend.map(|t| t.direction),
+            Some(TrendDirection::Down)
+        );
+        assert_approx_eq!(
+            dashboard.body_weight_window_trend.unwrap().percent,
+            -1.235,
+            0.001
+        );
+        assert_eq!(
+            dashboard.current_cycle,
+            Some(CurrentCycle {
+                begin: date(1, 31),
+                time_left: Some(Duration::days(25)),
+                time_left_variation: Duration::days(0),
+            })
+        );
+    }
+}
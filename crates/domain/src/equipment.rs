@@ -0,0 +1,572 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{Exercise, WeightConvention};
+
+/// Training location and the equipment available there.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Location {
+    pub name: String,
+    /// Weights of the available dumbbells.
+    #[serde(default)]
+    pub dumbbells: Option<WeightRange>,
+    /// Weights of the available pairs of plates. Each pair allows loading one plate on each side
+    /// of a bar.
+    #[serde(default)]
+    pub plates: Vec<f32>,
+    /// Steps of the weight stacks of the machines.
+    #[serde(default)]
+    pub machine_stack: Option<WeightRange>,
+}
+
+impl Location {
+    /// Weights which can be set up for the exercise with the available equipment, in ascending
+    /// order and in the weight convention of the exercise.
+    ///
+    /// The equipment is derived from the weight convention. Dumbbells are used for the per
+    /// dumbbell convention and plates for the per side convention. An exercise with total weight
+    /// is assumed to be performed with a loaded bar if it has a base weight and on a machine
+    /// otherwise. No weights are returned if the location lacks the required equipment.
+    #[must_use]
+    pub fn weights(&self, exercise: &Exercise) -> Option<Vec<f32>> {
+        let base_weight = exercise.base_weight.unwrap_or(0.0);
+        match exercise.weight_convention {
+            Some(WeightConvention::PerDumbbell) => self.dumbbells.map(|range| range.weights()),
+            Some(WeightConvention::PerSide) if !self.plates.is_empty() => {
+                Some(plate_loads(&self.plates))
+            }
+            Some(WeightConvention::Total) if base_weight > 0.0 => {
+                if self.plates.is_empty() {
+                    None
+                } else {
+                    Some(
+                        plate_loads(&self.plates)
+                            .into_iter()
+                            .map(|load| round_to_grams(base_weight + 2.0 * load))
+                            .collect(),
+                    )
+                }
+            }
+            None | Some(WeightConvention::Total) => self.machine_stack.map(|range| range.weights()),
+            Some(WeightConvention::PerSide) => None,
+        }
+    }
+
+    /// Round the weight to the weights which can be set up for the exercise. No result is
+    /// returned if the location lacks the required equipment.
+    #[must_use]
+    pub fn round(&self, exercise: &Exercise, weight: f32) -> Option<Achievable> {
+        self.weights(exercise)
+            .map(|weights| round(weight, &weights))
+    }
+
+    /// Plates to be loaded on each side of the bar to set up the weight for the exercise. No
+    /// plates are returned if the exercise is not performed with a loaded bar or if the weight
+    /// cannot be achieved.
+    #[must_use]
+    pub fn plates_per_side(&self, exercise: &Exercise, weight: f32) -> Option<Vec<f32>> {
+        let base_weight = exercise.base_weight.unwrap_or(0.0);
+        let load = match exercise.weight_convention {
+            Some(WeightConvention::PerSide) => weight,
+            Some(WeightConvention::Total) if base_weight > 0.0 => (weight - base_weight) / 2.0,
+            _ => return None,
+        };
+        plates_per_side(load, &self.plates)
+    }
+}
+
+/// Weights from a minimum to a maximum weight in constant steps.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WeightRange {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+impl WeightRange {
+    /// Create a range of weights.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the minimum exceeds the maximum, the weights are out of range or the
+    /// step is not positive.
+    pub fn new(min: f32, max: f32, step: f32) -> Result<Self, EquipmentError> {
+        if !(0.0..=MAX_WEIGHT).contains(&min) || !(min..=MAX_WEIGHT).contains(&max) {
+            return Err(EquipmentError::InvalidRange);
+        }
+        if !(step > 0.0 && step <= MAX_WEIGHT) {
+            return Err(EquipmentError::InvalidStep);
+        }
+        Ok(Self { min, max, step })
+    }
+
+    /// All weights of the range in ascending order.
+    #[must_use]
+    pub fn weights(&self) -> Vec<f32> {
+        if self.step <= 0.0 || self.min > self.max {
+            return vec![];
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let steps = ((self.max - self.min) / self.step + EPSILON).floor() as u32;
+        #[allow(clippy::cast_precision_loss)]
+        (0..=steps)
+            .map(|i| round_to_grams(self.min + i as f32 * self.step))
+            .collect()
+    }
+}
+
+/// Parse a list of plate weights separated by spaces or semicolons, e.g. "20 20 10 5 2.5".
+///
+/// # Errors
+///
+/// Returns an error if any plate weight is invalid.
+pub fn parse_plates(input: &str) -> Result<Vec<f32>, EquipmentError> {
+    let mut plates = input
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.replace(',', ".").parse::<f32>() {
+            Ok(weight) if weight > 0.0 && weight <= MAX_WEIGHT => Ok(weight),
+            _ => Err(EquipmentError::InvalidPlate),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    plates.sort_by(|a, b| b.total_cmp(a));
+    Ok(plates)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquipmentError {
+    InvalidRange,
+    InvalidStep,
+    InvalidPlate,
+}
+
+impl fmt::Display for EquipmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            EquipmentError::InvalidRange => {
+                "Weights must be between 0 and 999.9 kg with the minimum not exceeding the maximum"
+            }
+            EquipmentError::InvalidStep => "Step must be greater than 0 kg",
+            EquipmentError::InvalidPlate => {
+                "Plate weights must be numbers between 0.1 and 999.9 kg separated by spaces"
+            }
+        })
+    }
+}
+
+/// Result of rounding a weight to the achievable weights.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Achievable {
+    /// The weight can be achieved.
+    Exact(f32),
+    /// The weight cannot be achieved. The nearest achievable weights below and above are given,
+    /// if there are any.
+    Unreachable {
+        below: Option<f32>,
+        above: Option<f32>,
+    },
+}
+
+impl Achievable {
+    /// The achievable weight closest to the weight. The lower weight is preferred if both
+    /// options are equally close.
+    #[must_use]
+    pub fn nearest(self, weight: f32) -> Option<f32> {
+        match self {
+            Achievable::Exact(weight) => Some(weight),
+            Achievable::Unreachable {
+                below: Some(below),
+                above: Some(above),
+            } => Some(if above - weight < weight - below - EPSILON {
+                above
+            } else {
+                below
+            }),
+            Achievable::Unreachable { below, above } => below.or(above),
+        }
+    }
+
+    /// The lowest achievable weight not below the weight. The highest achievable weight is
+    /// returned if the weight exceeds all achievable weights.
+    #[must_use]
+    pub fn at_least(self) -> Option<f32> {
+        match self {
+            Achievable::Exact(weight) => Some(weight),
+            Achievable::Unreachable { below, above } => above.or(below),
+        }
+    }
+}
+
+/// Round a weight to the achievable weights, which must be sorted in ascending order.
+#[must_use]
+pub fn round(weight: f32, weights: &[f32]) -> Achievable {
+    if let Some(exact) = weights.iter().find(|w| (*w - weight).abs() < EPSILON) {
+        return Achievable::Exact(*exact);
+    }
+    Achievable::Unreachable {
+        below: weights.iter().rev().find(|w| **w < weight).copied(),
+        above: weights.iter().find(|w| **w > weight).copied(),
+    }
+}
+
+/// The next achievable weight above the weight, if any.
+#[must_use]
+pub fn next_weight(weight: f32, weights: &[f32]) -> Option<f32> {
+    weights.iter().find(|w| **w > weight + EPSILON).copied()
+}
+
+/// The next achievable weight below the weight, if any.
+#[must_use]
+pub fn previous_weight(weight: f32, weights: &[f32]) -> Option<f32> {
+    weights
+        .iter()
+        .rev()
+        .find(|w| **w < weight - EPSILON)
+        .copied()
+}
+
+/// All loads per side which can be achieved with the pairs of plates, in ascending order. An
+/// empty bar is included as a load of zero.
+#[must_use]
+pub fn plate_loads(plates: &[f32]) -> Vec<f32> {
+    #[allow(clippy::cast_precision_loss)]
+    plate_combinations(plates)
+        .into_keys()
+        .map(|load| load as f32 / 1000.0)
+        .collect()
+}
+
+/// Plates to be loaded on each side of a bar to achieve the load per side, starting with the
+/// heaviest plate. No plates are returned if the load cannot be achieved with the pairs of plates.
+#[must_use]
+pub fn plates_per_side(load: f32, plates: &[f32]) -> Option<Vec<f32>> {
+    if load < -EPSILON {
+        return None;
+    }
+    plate_combinations(plates).remove(&grams(load))
+}
+
+/// Map each achievable load in grams to the plates used for it. Heavier plates are preferred if
+/// a load can be achieved in several ways.
+fn plate_combinations(plates: &[f32]) -> BTreeMap<u32, Vec<f32>> {
+    let mut plates = plates.to_vec();
+    plates.sort_by(|a, b| b.total_cmp(a));
+    let mut combinations = BTreeMap::from([(0, vec![])]);
+    for plate in plates {
+        let extended = combinations
+            .iter()
+            .map(|(load, used)| {
+                let mut used = used.clone();
+                used.push(plate);
+                (load + grams(plate), used)
+            })
+            .collect::<Vec<_>>();
+        for (load, used) in extended {
+            combinations.entry(load).or_insert(used);
+        }
+    }
+    combinations
+}
+
+const MAX_WEIGHT: f32 = 999.9;
+const EPSILON: f32 = 0.001;
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn grams(weight: f32) -> u32 {
+    (weight.max(0.0) * 1000.0).round() as u32
+}
+
+fn round_to_grams(weight: f32) -> f32 {
+    (weight * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::Laterality;
+
+    const PLATES: [f32; 6] = [20.0, 20.0, 10.0, 5.0, 2.5, 1.25];
+
+    fn exercise(weight_convention: Option<WeightConvention>, base_weight: Option<f32>) -> Exercise {
+        Exercise {
+            id: 1,
+            name: String::from("A"),
+            muscles: vec![],
+            weight_convention,
+            base_weight,
+            laterality: Laterality::Bilateral,
+            archived: false,
+            image: None,
+        }
+    }
+
+    fn location() -> Location {
+        Location {
+            name: String::from("Gym"),
+            dumbbells: Some(WeightRange::new(2.0, 30.0, 2.0).unwrap()),
+            plates: PLATES.to_vec(),
+            machine_stack: Some(WeightRange::new(5.0, 100.0, 5.0).unwrap()),
+        }
+    }
+
+    #[rstest]
+    #[case::valid(2.0, 30.0, 2.0, Ok(WeightRange { min: 2.0, max: 30.0, step: 2.0 }))]
+    #[case::single_weight(10.0, 10.0, 1.0, Ok(WeightRange { min: 10.0, max: 10.0, step: 1.0 }))]
+    #[case::min_exceeds_max(30.0, 2.0, 2.0, Err(EquipmentError::InvalidRange))]
+    #[case::negative(-2.0, 30.0, 2.0, Err(EquipmentError::InvalidRange))]
+    #[case::too_heavy(2.0, 1000.0, 2.0, Err(EquipmentError::InvalidRange))]
+    #[case::zero_step(2.0, 30.0, 0.0, Err(EquipmentError::InvalidStep))]
+    fn test_weight_range_new(
+        #[case] min: f32,
+        #[case] max: f32,
+        #[case] step: f32,
+        #[case] expected: Result<WeightRange, EquipmentError>,
+    ) {
+        assert_eq!(WeightRange::new(min, max, step), expected);
+    }
+
+    #[rstest]
+    #[case::dumbbells(2.0, 10.0, 2.0, vec![2.0, 4.0, 6.0, 8.0, 10.0])]
+    #[case::incomplete_step(2.0, 9.0, 2.0, vec![2.0, 4.0, 6.0, 8.0])]
+    #[case::fractional_step(1.0, 2.0, 0.25, vec![1.0, 1.25, 1.5, 1.75, 2.0])]
+    #[case::inaccurate_step(0.0, 0.3, 0.1, vec![0.0, 0.1, 0.2, 0.3])]
+    #[case::single_weight(10.0, 10.0, 5.0, vec![10.0])]
+    fn test_weight_range_weights(
+        #[case] min: f32,
+        #[case] max: f32,
+        #[case] step: f32,
+        #[case] expected: Vec<f32>,
+    ) {
+        assert_eq!(WeightRange { min, max, step }.weights(), expected);
+    }
+
+    #[rstest]
+    #[case::empty("", Ok(vec![]))]
+    #[case::spaces("2.5 20  10", Ok(vec![20.0, 10.0, 2.5]))]
+    #[case::semicolons("1,25;5; 20", Ok(vec![20.0, 5.0, 1.25]))]
+    #[case::invalid("20 x", Err(EquipmentError::InvalidPlate))]
+    #[case::zero("20 0", Err(EquipmentError::InvalidPlate))]
+    fn test_parse_plates(#[case] input: &str, #[case] expected: Result<Vec<f32>, EquipmentError>) {
+        assert_eq!(parse_plates(input), expected);
+    }
+
+    #[rstest]
+    #[case::exact(6.0, Achievable::Exact(6.0))]
+    #[case::between(7.0, Achievable::Unreachable { below: Some(6.0), above: Some(8.0) })]
+    #[case::below_all(1.0, Achievable::Unreachable { below: None, above: Some(2.0) })]
+    #[case::above_all(31.0, Achievable::Unreachable { below: Some(30.0), above: None })]
+    fn test_round_dumbbells(#[case] weight: f32, #[case] expected: Achievable) {
+        let weights = WeightRange::new(2.0, 30.0, 2.0).unwrap().weights();
+        assert_eq!(round(weight, &weights), expected);
+    }
+
+    #[rstest]
+    #[case::exact(45.0, Achievable::Exact(45.0))]
+    #[case::between(42.5, Achievable::Unreachable { below: Some(40.0), above: Some(45.0) })]
+    #[case::nearly_exact(45.0001, Achievable::Exact(45.0))]
+    fn test_round_machine_stack(#[case] weight: f32, #[case] expected: Achievable) {
+        let weights = WeightRange::new(5.0, 100.0, 5.0).unwrap().weights();
+        assert_eq!(round(weight, &weights), expected);
+    }
+
+    #[test]
+    fn test_round_without_weights() {
+        assert_eq!(
+            round(10.0, &[]),
+            Achievable::Unreachable {
+                below: None,
+                above: None
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::exact(Achievable::Exact(6.0), 6.0, Some(6.0), Some(6.0))]
+    #[case::closer_below(
+        Achievable::Unreachable { below: Some(40.0), above: Some(45.0) },
+        41.0,
+        Some(40.0),
+        Some(45.0)
+    )]
+    #[case::closer_above(
+        Achievable::Unreachable { below: Some(40.0), above: Some(45.0) },
+        44.0,
+        Some(45.0),
+        Some(45.0)
+    )]
+    #[case::tie(
+        Achievable::Unreachable { below: Some(40.0), above: Some(45.0) },
+        42.5,
+        Some(40.0),
+        Some(45.0)
+    )]
+    #[case::above_all(
+        Achievable::Unreachable { below: Some(30.0), above: None },
+        31.0,
+        Some(30.0),
+        Some(30.0)
+    )]
+    #[case::below_all(
+        Achievable::Unreachable { below: None, above: Some(2.0) },
+        1.0,
+        Some(2.0),
+        Some(2.0)
+    )]
+    #[case::none(Achievable::Unreachable { below: None, above: None }, 1.0, None, None)]
+    fn test_achievable(
+        #[case] achievable: Achievable,
+        #[case] weight: f32,
+        #[case] nearest: Option<f32>,
+        #[case] at_least: Option<f32>,
+    ) {
+        assert_eq!(achievable.nearest(weight), nearest);
+        assert_eq!(achievable.at_least(), at_least);
+    }
+
+    #[rstest]
+    #[case::exact(6.0, Some(8.0), Some(4.0))]
+    #[case::between(7.0, Some(8.0), Some(6.0))]
+    #[case::min(2.0, Some(4.0), None)]
+    #[case::max(30.0, None, Some(28.0))]
+    fn test_next_and_previous_weight(
+        #[case] weight: f32,
+        #[case] next: Option<f32>,
+        #[case] previous: Option<f32>,
+    ) {
+        let weights = WeightRange::new(2.0, 30.0, 2.0).unwrap().weights();
+        assert_eq!(next_weight(weight, &weights), next);
+        assert_eq!(previous_weight(weight, &weights), previous);
+    }
+
+    #[test]
+    fn test_plate_loads() {
+        assert_eq!(plate_loads(&[]), vec![0.0]);
+        assert_eq!(
+            plate_loads(&[10.0, 5.0, 5.0]),
+            vec![0.0, 5.0, 10.0, 15.0, 20.0]
+        );
+        assert_eq!(plate_loads(&[2.5, 1.25]), vec![0.0, 1.25, 2.5, 3.75]);
+    }
+
+    #[rstest]
+    #[case::empty_bar(0.0, Some(vec![]))]
+    #[case::single_plate(20.0, Some(vec![20.0]))]
+    #[case::several_plates(28.75, Some(vec![20.0, 5.0, 2.5, 1.25]))]
+    #[case::heavier_plates_preferred(40.0, Some(vec![20.0, 20.0]))]
+    #[case::unreachable(1.0, None)]
+    #[case::too_heavy(60.0, None)]
+    fn test_plates_per_side(#[case] load: f32, #[case] expected: Option<Vec<f32>>) {
+        assert_eq!(plates_per_side(load, &PLATES), expected);
+    }
+
+    #[rstest]
+    #[case::dumbbell(Some(WeightConvention::PerDumbbell), None, Some(15))]
+    #[case::plates_per_side(Some(WeightConvention::PerSide), Some(20.0), Some(48))]
+    #[case::barbell(Some(WeightConvention::Total), Some(20.0), Some(48))]
+    #[case::machine(Some(WeightConvention::Total), None, Some(20))]
+    #[case::no_convention(None, None, Some(20))]
+    fn test_location_weights(
+        #[case] weight_convention: Option<WeightConvention>,
+        #[case] base_weight: Option<f32>,
+        #[case] expected_count: Option<usize>,
+    ) {
+        assert_eq!(
+            location()
+                .weights(&exercise(weight_convention, base_weight))
+                .map(|weights| weights.len()),
+            expected_count
+        );
+    }
+
+    #[test]
+    fn test_location_weights_missing_equipment() {
+        let location = Location {
+            name: String::from("Home"),
+            ..Location::default()
+        };
+        for (weight_convention, base_weight) in [
+            (Some(WeightConvention::PerDumbbell), None),
+            (Some(WeightConvention::PerSide), Some(20.0)),
+            (Some(WeightConvention::Total), Some(20.0)),
+            (Some(WeightConvention::Total), None),
+            (None, None),
+        ] {
+            assert_eq!(
+                location.weights(&exercise(weight_convention, base_weight)),
+                None
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::barbell_exact(
+        Some(WeightConvention::Total),
+        Some(20.0),
+        77.5,
+        Achievable::Exact(77.5)
+    )]
+    #[case::barbell_unreachable(
+        Some(WeightConvention::Total),
+        Some(20.0),
+        21.0,
+        Achievable::Unreachable { below: Some(20.0), above: Some(22.5) }
+    )]
+    #[case::per_side(
+        Some(WeightConvention::PerSide),
+        Some(20.0),
+        59.0,
+        Achievable::Unreachable { below: Some(58.75), above: None }
+    )]
+    #[case::dumbbell(
+        Some(WeightConvention::PerDumbbell),
+        None,
+        13.0,
+        Achievable::Unreachable { below: Some(12.0), above: Some(14.0) }
+    )]
+    #[case::machine(
+        None,
+        None,
+        47.5,
+        Achievable::Unreachable { below: Some(45.0), above: Some(50.0) }
+    )]
+    fn test_location_round(
+        #[case] weight_convention: Option<WeightConvention>,
+        #[case] base_weight: Option<f32>,
+        #[case] weight: f32,
+        #[case] expected: Achievable,
+    ) {
+        assert_eq!(
+            location().round(&exercise(weight_convention, base_weight), weight),
+            Some(expected)
+        );
+    }
+
+    #[rstest]
+    #[case::barbell(Some(WeightConvention::Total), Some(20.0), 70.0, Some(vec![20.0, 5.0]))]
+    #[case::per_side(Some(WeightConvention::PerSide), Some(20.0), 12.5, Some(vec![10.0, 2.5]))]
+    #[case::unreachable(Some(WeightConvention::Total), Some(20.0), 71.0, None)]
+    #[case::lighter_than_bar(Some(WeightConvention::Total), Some(20.0), 10.0, None)]
+    #[case::dumbbell(Some(WeightConvention::PerDumbbell), None, 10.0, None)]
+    #[case::machine(Some(WeightConvention::Total), None, 70.0, None)]
+    fn test_location_plates_per_side(
+        #[case] weight_convention: Option<WeightConvention>,
+        #[case] base_weight: Option<f32>,
+        #[case] weight: f32,
+        #[case] expected: Option<Vec<f32>>,
+    ) {
+        assert_eq!(
+            location().plates_per_side(&exercise(weight_convention, base_weight), weight),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_equipment_error_display() {
+        assert_eq!(
+            EquipmentError::InvalidStep.to_string(),
+            "Step must be greater than 0 kg"
+        );
+    }
+}
@@ -1,28 +1,125 @@
 #![warn(clippy::pedantic)]
 
 use std::{
+    cmp::Ordering,
     collections::{BTreeMap, BTreeSet, HashSet},
-    iter::zip,
+    fmt,
     slice::Iter,
+    str::FromStr,
 };
 
-use chrono::{Days, Duration, Local, NaiveDate};
+use chrono::{Datelike, Days, Duration, IsoWeek, NaiveDate, NaiveDateTime, Weekday};
 
-#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub mod dashboard;
+#[allow(clippy::module_name_repetitions)]
+pub mod equipment;
+#[allow(clippy::module_name_repetitions)]
+pub mod period;
+#[allow(clippy::module_name_repetitions)]
+pub mod progression;
+pub mod summary;
+
+/// Source of the current date.
+///
+/// All date computations relative to the current day get the date from a clock instead of
+/// determining it on their own, so that a frontend can define the relevant time zone and tests
+/// can use a fixed date. The computations only use calendar dates, so time zone offsets and
+/// daylight saving time are exclusively handled by the clock.
+pub trait Clock {
+    fn today(&self) -> NaiveDate;
+}
+
+/// Clock which always returns the same date.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub NaiveDate);
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.0
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct User {
     pub id: u32,
     pub name: String,
     pub sex: u8,
+    /// Body height in cm.
+    #[serde(default)]
+    pub height: Option<f32>,
+    #[serde(default)]
+    pub birthdate: Option<NaiveDate>,
+    #[serde(default)]
+    pub has_pin: bool,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+impl User {
+    /// Age in years at the given date, or [`DEFAULT_AGE`] if the birthdate is unknown.
+    #[must_use]
+    pub fn age(&self, date: NaiveDate) -> u32 {
+        self.birthdate
+            .map_or(DEFAULT_AGE, |birthdate| age(birthdate, date))
+    }
+}
+
+/// Age assumed in calculations if the birthdate of a user is unknown.
+pub const DEFAULT_AGE: u32 = 30;
+
+/// Age in completed years at the given date. Dates before the birthdate result in an age of zero.
+#[must_use]
+pub fn age(birthdate: NaiveDate, date: NaiveDate) -> u32 {
+    date.years_since(birthdate).unwrap_or(0)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct Exercise {
     pub id: u32,
     pub name: String,
     pub muscles: Vec<ExerciseMuscle>,
+    /// Meaning of the weight recorded for sets of the exercise.
+    #[serde(default)]
+    pub weight_convention: Option<WeightConvention>,
+    /// Weight of the implement (e.g., the bar or the dumbbell handle) which is not included in the
+    /// recorded weight.
+    #[serde(default)]
+    pub base_weight: Option<f32>,
+    /// Unilateral exercises are recorded separately for the left and the right side.
+    #[serde(default)]
+    pub laterality: Laterality,
+    /// Archived exercises are kept for the history, but are not offered for selection.
+    #[serde(default)]
+    pub archived: bool,
+    /// Digest of the attached image, which changes whenever the image is replaced.
+    #[serde(default)]
+    pub image: Option<String>,
 }
 
 impl Exercise {
+    /// Convert a recorded weight into the total moved weight.
+    ///
+    /// The base weight is ignored for the total convention, as it is already included in the
+    /// recorded weight.
+    #[must_use]
+    pub fn total_weight(&self, weight: f32) -> f32 {
+        let base_weight = self.base_weight.unwrap_or(0.0);
+        match self.weight_convention {
+            None | Some(WeightConvention::Total) => weight,
+            Some(WeightConvention::PerSide) => 2.0 * weight + base_weight,
+            Some(WeightConvention::PerDumbbell) => 2.0 * (weight + base_weight),
+        }
+    }
+
+    /// Convert a total moved weight into the weight which has to be recorded.
+    #[must_use]
+    pub fn recorded_weight(&self, total_weight: f32) -> f32 {
+        let base_weight = self.base_weight.unwrap_or(0.0);
+        match self.weight_convention {
+            None | Some(WeightConvention::Total) => total_weight,
+            Some(WeightConvention::PerSide) => (total_weight - base_weight) / 2.0,
+            Some(WeightConvention::PerDumbbell) => total_weight / 2.0 - base_weight,
+        }
+    }
+
     #[must_use]
     pub fn muscle_stimulus(&self) -> BTreeMap<u8, u8> {
         self.muscles
@@ -30,6 +127,326 @@ impl Exercise {
             .map(|m| (m.muscle_id, m.stimulus))
             .collect()
     }
+
+    /// Apply the changes between two muscle stimulus mappings to the muscles of the exercise.
+    ///
+    /// Muscles with the same stimulus in both mappings are kept unchanged.
+    #[must_use]
+    pub fn with_changed_muscle_stimulus(
+        &self,
+        before: &BTreeMap<u8, u8>,
+        after: &BTreeMap<u8, u8>,
+    ) -> Exercise {
+        let mut muscle_stimulus = self.muscle_stimulus();
+        for muscle_id in before.keys().chain(after.keys()) {
+            if before.get(muscle_id) != after.get(muscle_id) {
+                match after.get(muscle_id) {
+                    Some(stimulus) => {
+                        muscle_stimulus.insert(*muscle_id, *stimulus);
+                    }
+                    None => {
+                        muscle_stimulus.remove(muscle_id);
+                    }
+                }
+            }
+        }
+        Exercise {
+            id: self.id,
+            name: self.name.clone(),
+            weight_convention: self.weight_convention,
+            base_weight: self.base_weight,
+            laterality: self.laterality,
+            archived: self.archived,
+            image: self.image.clone(),
+            muscles: muscle_stimulus
+                .into_iter()
+                .map(|(muscle_id, stimulus)| ExerciseMuscle {
+                    muscle_id,
+                    stimulus,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightConvention {
+    /// Weight of the whole implement including the bar.
+    Total,
+    /// Weight loaded on each side of a bar.
+    PerSide,
+    /// Weight of each of two dumbbells.
+    PerDumbbell,
+}
+
+impl WeightConvention {
+    pub fn iter() -> Iter<'static, WeightConvention> {
+        static WEIGHT_CONVENTIONS: [WeightConvention; 3] = [
+            WeightConvention::Total,
+            WeightConvention::PerSide,
+            WeightConvention::PerDumbbell,
+        ];
+        WEIGHT_CONVENTIONS.iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            WeightConvention::Total => "Total",
+            WeightConvention::PerSide => "Per side",
+            WeightConvention::PerDumbbell => "Per dumbbell",
+        }
+    }
+
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            WeightConvention::Total => "Enter the total weight including the bar",
+            WeightConvention::PerSide => "Enter the weight on one side of the bar",
+            WeightConvention::PerDumbbell => "Enter the weight of one dumbbell",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Laterality {
+    /// Both sides of the body are trained at the same time.
+    #[default]
+    Bilateral,
+    /// One side of the body is trained after the other.
+    Unilateral,
+}
+
+impl Laterality {
+    pub fn iter() -> Iter<'static, Laterality> {
+        static LATERALITIES: [Laterality; 2] = [Laterality::Bilateral, Laterality::Unilateral];
+        LATERALITIES.iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Laterality::Bilateral => "Bilateral",
+            Laterality::Unilateral => "Unilateral",
+        }
+    }
+}
+
+/// Side of the body trained in a set of a unilateral exercise.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Side::Left => "Left",
+            Side::Right => "Right",
+        }
+    }
+
+    #[must_use]
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Side::Left => "L",
+            Side::Right => "R",
+        }
+    }
+}
+
+/// Image attached to an exercise, e.g., a photo of the machine settings.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExerciseImage {
+    pub mime_type: String,
+    /// Base64-encoded image data.
+    pub data: String,
+}
+
+impl ExerciseImage {
+    /// Maximum size of the decoded image data in bytes.
+    pub const MAX_SIZE: usize = 2 * 1024 * 1024;
+    pub const MIME_TYPES: [&'static str; 4] =
+        ["image/jpeg", "image/png", "image/gif", "image/webp"];
+
+    /// Create an image from base64-encoded data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image type is not supported, the data is not valid base64 or the
+    /// image is too large.
+    pub fn new(mime_type: &str, data: String) -> Result<Self, ExerciseImageError> {
+        if !Self::MIME_TYPES.contains(&mime_type) {
+            return Err(ExerciseImageError::UnsupportedType);
+        }
+        let padding = data.bytes().rev().take_while(|b| *b == b'=').count();
+        if data.is_empty()
+            || data.len() % 4 != 0
+            || padding > 2
+            || !data[..data.len() - padding]
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+        {
+            return Err(ExerciseImageError::InvalidData);
+        }
+        let image = Self {
+            mime_type: mime_type.to_string(),
+            data,
+        };
+        if image.size() > Self::MAX_SIZE {
+            return Err(ExerciseImageError::TooLarge);
+        }
+        Ok(image)
+    }
+
+    /// Create an image from a data URL, e.g., "data:image/jpeg;base64,/9j/4AAQ".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is not a base64 data URL or the image is invalid.
+    pub fn from_data_url(url: &str) -> Result<Self, ExerciseImageError> {
+        let (mime_type, data) = url
+            .strip_prefix("data:")
+            .and_then(|url| url.split_once(";base64,"))
+            .ok_or(ExerciseImageError::InvalidData)?;
+        Self::new(mime_type, data.to_string())
+    }
+
+    #[must_use]
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
+
+    /// Size of the decoded image data in bytes.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        let padding = self.data.bytes().rev().take_while(|b| *b == b'=').count();
+        (self.data.len() / 4 * 3).saturating_sub(padding)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExerciseImageError {
+    UnsupportedType,
+    InvalidData,
+    TooLarge,
+}
+
+impl fmt::Display for ExerciseImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExerciseImageError::UnsupportedType => {
+                f.write_str("Image must be a JPEG, PNG, GIF or WebP file")
+            }
+            ExerciseImageError::InvalidData => f.write_str("Image data is invalid"),
+            ExerciseImageError::TooLarge => write!(
+                f,
+                "Image must not be larger than {} MB",
+                ExerciseImage::MAX_SIZE / 1024 / 1024
+            ),
+        }
+    }
+}
+
+/// Return the muscle stimuli which are equal for all exercises.
+#[must_use]
+pub fn common_muscle_stimulus(exercises: &[&Exercise]) -> BTreeMap<u8, u8> {
+    let mut muscle_stimuli = exercises.iter().map(|e| e.muscle_stimulus());
+    let Some(first) = muscle_stimuli.next() else {
+        return BTreeMap::new();
+    };
+    muscle_stimuli.fold(first, |common, muscle_stimulus| {
+        common
+            .into_iter()
+            .filter(|(muscle_id, stimulus)| muscle_stimulus.get(muscle_id) == Some(stimulus))
+            .collect()
+    })
+}
+
+/// Routines and training sessions in which the references to a source exercise have been
+/// replaced by references to a target exercise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseMerge {
+    pub routines: Vec<Routine>,
+    pub training_sessions: Vec<TrainingSession>,
+}
+
+/// Replace all references to the source exercise by references to the target exercise.
+///
+/// Only the routines and training sessions which reference the source exercise are returned.
+#[must_use]
+pub fn merge_exercises(
+    source_id: u32,
+    target_id: u32,
+    routines: &[&Routine],
+    training_sessions: &[&TrainingSession],
+) -> ExerciseMerge {
+    ExerciseMerge {
+        routines: routines
+            .iter()
+            .filter(|r| r.exercises().contains(&source_id))
+            .map(|r| {
+                let mut routine = (*r).clone();
+                for section in &mut routine.sections {
+                    section.replace_exercise(source_id, target_id);
+                }
+                routine
+            })
+            .collect(),
+        training_sessions: training_sessions
+            .iter()
+            .filter(|t| t.exercises().contains(&source_id))
+            .map(|t| {
+                let mut training_session = (*t).clone();
+                for element in &mut training_session.elements {
+                    if let TrainingSessionElement::Set { exercise_id, .. } = element {
+                        if *exercise_id == source_id {
+                            *exercise_id = target_id;
+                        }
+                    }
+                }
+                training_session
+            })
+            .collect(),
+    }
+}
+
+/// Routines and training sessions which refer to an exercise.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExerciseUsage {
+    /// IDs of the routines containing the exercise.
+    pub routines: Vec<u32>,
+    /// Number of training sessions containing sets of the exercise.
+    pub training_sessions: usize,
+}
+
+impl ExerciseUsage {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.routines.is_empty() && self.training_sessions == 0
+    }
+}
+
+/// Determine the routines and training sessions which would be affected by deleting an exercise.
+#[must_use]
+pub fn exercise_usage(
+    exercise_id: u32,
+    routines: &[&Routine],
+    training_sessions: &[&TrainingSession],
+) -> ExerciseUsage {
+    ExerciseUsage {
+        routines: routines
+            .iter()
+            .filter(|r| r.exercises().contains(&exercise_id))
+            .map(|r| r.id)
+            .collect(),
+        training_sessions: training_sessions
+            .iter()
+            .filter(|t| t.exercises().contains(&exercise_id))
+            .count(),
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -45,6 +462,12 @@ pub struct Routine {
     pub notes: Option<String>,
     pub archived: bool,
     pub sections: Vec<RoutinePart>,
+    /// Days of the week on which the routine is planned to be trained.
+    #[serde(default)]
+    pub schedule: Vec<Weekday>,
+    /// User-defined labels, ordered by name.
+    #[serde(default)]
+    pub tags: Vec<Tag>,
 }
 
 impl Routine {
@@ -75,8 +498,151 @@ impl Routine {
             .flat_map(RoutinePart::exercises)
             .collect::<BTreeSet<_>>()
     }
+
+    #[must_use]
+    pub fn sets_per_exercise(&self) -> BTreeMap<u32, u32> {
+        let mut result: BTreeMap<u32, u32> = BTreeMap::new();
+        for section in &self.sections {
+            for (id, sets) in section.sets_per_exercise() {
+                *result.entry(id).or_insert(0) += sets;
+            }
+        }
+        result
+    }
+
+    /// Determine whether the routine is due on the given date according to its schedule.
+    ///
+    /// The routine is overdue if no training session took place since its last planned day before
+    /// the given date. A routine that was never trained is not considered overdue.
+    #[must_use]
+    pub fn due(&self, date: NaiveDate, last_training_session: Option<NaiveDate>) -> Option<Due> {
+        if self.schedule.is_empty() {
+            return None;
+        }
+        let planned = |date: NaiveDate| self.schedule.contains(&date.weekday());
+        if last_training_session != Some(date) {
+            if let Some(last_training_session) = last_training_session {
+                let last_planned_day = (1..=7)
+                    .filter_map(|days| date.checked_sub_days(Days::new(days)))
+                    .find(|date| planned(*date));
+                if last_planned_day.is_some_and(|day| last_training_session < day) {
+                    return Some(Due::Overdue);
+                }
+            }
+            if planned(date) {
+                return Some(Due::Today);
+            }
+        }
+        if date.succ_opt().is_some_and(planned) {
+            return Some(Due::Tomorrow);
+        }
+        None
+    }
+
+    #[must_use]
+    pub fn has_tag(&self, name: &str) -> bool {
+        self.tags.iter().any(|t| t.name == name)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub color: TagColor,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TagColor {
+    #[default]
+    Gray,
+    Turquoise,
+    Blue,
+    Cyan,
+    Green,
+    Yellow,
+    Red,
+}
+
+impl TagColor {
+    pub fn iter() -> Iter<'static, TagColor> {
+        static TAG_COLORS: [TagColor; 7] = [
+            TagColor::Gray,
+            TagColor::Turquoise,
+            TagColor::Blue,
+            TagColor::Cyan,
+            TagColor::Green,
+            TagColor::Yellow,
+            TagColor::Red,
+        ];
+        TAG_COLORS.iter()
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            TagColor::Gray => "Gray",
+            TagColor::Turquoise => "Turquoise",
+            TagColor::Blue => "Blue",
+            TagColor::Cyan => "Cyan",
+            TagColor::Green => "Green",
+            TagColor::Yellow => "Yellow",
+            TagColor::Red => "Red",
+        }
+    }
+}
+
+/// All tags used by the given routines, ordered by name.
+///
+/// If a tag is used with different colors, the color of its first occurrence is taken.
+pub fn tags<'a>(routines: impl IntoIterator<Item = &'a Routine>) -> Vec<Tag> {
+    let mut result: BTreeMap<&str, TagColor> = BTreeMap::new();
+    for routine in routines {
+        for tag in &routine.tags {
+            result.entry(&tag.name).or_insert(tag.color);
+        }
+    }
+    result
+        .into_iter()
+        .map(|(name, color)| Tag {
+            name: name.to_string(),
+            color,
+        })
+        .collect()
+}
+
+/// Add a tag to a list of tags ordered by name. An existing tag with the same name is replaced.
+pub fn add_tag(tags: &mut Vec<Tag>, tag: Tag) {
+    match tags.binary_search_by(|t| t.name.cmp(&tag.name)) {
+        Ok(idx) => tags[idx] = tag,
+        Err(idx) => tags.insert(idx, tag),
+    }
+}
+
+/// Remove a tag from all routines, including archived routines.
+///
+/// The result contains all routines which had the tag, with the tag removed. Its length is the
+/// number of affected routines.
+pub fn remove_tag<'a>(routines: impl IntoIterator<Item = &'a Routine>, name: &str) -> Vec<Routine> {
+    routines
+        .into_iter()
+        .filter(|r| r.has_tag(name))
+        .map(|r| Routine {
+            tags: r.tags.iter().filter(|t| t.name != name).cloned().collect(),
+            ..r.clone()
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Due {
+    Overdue,
+    Today,
+    Tomorrow,
 }
 
+/// Assumed duration of a repetition in seconds, if no time is given.
+pub const DEFAULT_REP_TIME: u32 = 4;
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum RoutinePart {
@@ -86,15 +652,33 @@ pub enum RoutinePart {
     },
     RoutineActivity {
         exercise_id: Option<u32>,
-        reps: u32,
+        reps: RepsRange,
         time: u32,
         weight: f32,
-        rpe: f32,
+        rpe: RpeRange,
         automatic: bool,
+        /// Target weight as percentage of the estimated one-repetition maximum.
+        #[serde(default)]
+        target_percent_1rm: Option<f32>,
     },
 }
 
 impl RoutinePart {
+    fn replace_exercise(&mut self, source_id: u32, target_id: u32) {
+        match self {
+            RoutinePart::RoutineSection { parts, .. } => {
+                for part in parts {
+                    part.replace_exercise(source_id, target_id);
+                }
+            }
+            RoutinePart::RoutineActivity { exercise_id, .. } => {
+                if *exercise_id == Some(source_id) {
+                    *exercise_id = Some(target_id);
+                }
+            }
+        }
+    }
+
     pub fn duration(&self) -> Duration {
         match self {
             RoutinePart::RoutineSection { rounds, parts } => {
@@ -102,8 +686,8 @@ impl RoutinePart {
                     * (*rounds).try_into().unwrap_or(1)
             }
             RoutinePart::RoutineActivity { reps, time, .. } => {
-                let r = if *reps > 0 { *reps } else { 1 };
-                let t = if *time > 0 { *time } else { 4 };
+                let r = if reps.is_set() { reps.max } else { 1 };
+                let t = if *time > 0 { *time } else { DEFAULT_REP_TIME };
                 Duration::seconds(i64::from(r * t))
             }
         }
@@ -158,40 +742,325 @@ impl RoutinePart {
         }
         result
     }
+
+    fn sets_per_exercise(&self) -> BTreeMap<u32, u32> {
+        let mut result: BTreeMap<u32, u32> = BTreeMap::new();
+        match self {
+            RoutinePart::RoutineSection { rounds, parts } => {
+                for part in parts {
+                    for (id, sets) in part.sets_per_exercise() {
+                        *result.entry(id).or_insert(0) += sets * rounds;
+                    }
+                }
+            }
+            RoutinePart::RoutineActivity { exercise_id, .. } => {
+                if let Some(id) = exercise_id {
+                    result.insert(*id, 1);
+                }
+            }
+        }
+        result
+    }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
-pub struct TrainingSession {
-    pub id: u32,
-    pub routine_id: Option<u32>,
-    pub date: NaiveDate,
-    pub notes: Option<String>,
-    pub elements: Vec<TrainingSessionElement>,
+/// Tree of routine parts in which sections contain further parts.
+pub trait PartTree: Sized {
+    /// Return the parts contained in a section or `None` for an activity.
+    fn parts_mut(&mut self) -> Option<&mut [Self]>;
 }
 
-impl TrainingSession {
-    #[must_use]
-    pub fn exercises(&self) -> BTreeSet<u32> {
-        self.elements
-            .iter()
-            .filter_map(|e| match e {
-                TrainingSessionElement::Set { exercise_id, .. } => Some(*exercise_id),
-                TrainingSessionElement::Rest { .. } => None,
-            })
-            .collect::<BTreeSet<_>>()
+impl PartTree for RoutinePart {
+    fn parts_mut(&mut self) -> Option<&mut [Self]> {
+        match self {
+            RoutinePart::RoutineSection { parts, .. } => Some(parts),
+            RoutinePart::RoutineActivity { .. } => None,
+        }
     }
+}
 
-    #[must_use]
-    pub fn avg_reps(&self) -> Option<f32> {
-        let sets = &self
-            .elements
-            .iter()
-            .filter_map(|e| match e {
-                TrainingSessionElement::Set { reps, .. } => *reps,
-                TrainingSessionElement::Rest { .. } => None,
-            })
-            .collect::<Vec<_>>();
-        if sets.is_empty() {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// Move the part at the given path by one position.
+///
+/// The path contains the index of the part at each nesting level, starting with the outermost
+/// level. A part is only moved within its enclosing section. Moving the first part up, moving the
+/// last part down or using an invalid path has no effect. Returns whether the part was moved.
+pub fn move_part<T: PartTree>(sections: &mut [T], path: &[usize], direction: Direction) -> bool {
+    let Some((&index, parent_path)) = path.split_last() else {
+        return false;
+    };
+    let mut parts = sections;
+    for &i in parent_path {
+        match parts.get_mut(i).and_then(PartTree::parts_mut) {
+            Some(p) => parts = p,
+            None => return false,
+        }
+    }
+    let target = match direction {
+        Direction::Up => index.checked_sub(1),
+        Direction::Down => index.checked_add(1),
+    };
+    match target {
+        Some(target) if index < parts.len() && target < parts.len() => {
+            parts.swap(index, target);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Round of an enclosing section in which an element of an expanded routine is performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    /// One-based number of the round.
+    pub number: u32,
+    pub total: u32,
+}
+
+/// Element of a routine expanded into the sequence of a training session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpandedElement {
+    /// Rounds of all enclosing sections, starting with the outermost section.
+    pub rounds: Vec<Round>,
+    pub element: TrainingSessionElement,
+}
+
+/// Expand routine parts into the sequence of elements a training session would contain.
+///
+/// The parts of each section are repeated according to the number of rounds, so a section with
+/// zero rounds results in no elements. Activities without exercise become rests. The target weight
+/// of an activity with a target percentage of the one-repetition maximum is determined by
+/// `target_weight`, which gets the exercise ID and the percentage.
+#[must_use]
+pub fn expand_routine_parts(
+    parts: &[RoutinePart],
+    target_weight: &impl Fn(u32, f32) -> Option<f32>,
+) -> Vec<ExpandedElement> {
+    let mut result = vec![];
+    expand_routine_parts_into(parts, &[], target_weight, &mut result);
+    result
+}
+
+fn expand_routine_parts_into(
+    parts: &[RoutinePart],
+    rounds: &[Round],
+    target_weight: &impl Fn(u32, f32) -> Option<f32>,
+    result: &mut Vec<ExpandedElement>,
+) {
+    for part in parts {
+        match part {
+            RoutinePart::RoutineSection {
+                rounds: total,
+                parts,
+            } => {
+                for number in 1..=*total {
+                    let rounds = [
+                        rounds,
+                        &[Round {
+                            number,
+                            total: *total,
+                        }],
+                    ]
+                    .concat();
+                    expand_routine_parts_into(parts, &rounds, target_weight, result);
+                }
+            }
+            RoutinePart::RoutineActivity {
+                exercise_id,
+                reps,
+                time,
+                weight,
+                rpe,
+                automatic,
+                target_percent_1rm,
+            } => {
+                let element = if let Some(exercise_id) = exercise_id {
+                    TrainingSessionElement::Set {
+                        exercise_id: *exercise_id,
+                        reps: None,
+                        time: None,
+                        weight: None,
+                        rpe: None,
+                        target_reps: if reps.is_set() { Some(*reps) } else { None },
+                        target_time: if *time > 0 { Some(*time) } else { None },
+                        target_weight: if let Some(percent_1rm) = target_percent_1rm {
+                            target_weight(*exercise_id, *percent_1rm)
+                        } else if *weight > 0.0 {
+                            Some(*weight)
+                        } else {
+                            None
+                        },
+                        target_rpe: if rpe.is_set() { Some(*rpe) } else { None },
+                        automatic: *automatic,
+                        partial_reps: None,
+                        to_failure: false,
+                        side: None,
+                        comment: None,
+                    }
+                } else {
+                    TrainingSessionElement::Rest {
+                        target_time: if *time > 0 { Some(*time) } else { None },
+                        automatic: *automatic,
+                    }
+                };
+                result.push(ExpandedElement {
+                    rounds: rounds.to_vec(),
+                    element,
+                });
+            }
+        }
+    }
+}
+
+/// Determine the rounds of the enclosing sections for each element of a training session.
+///
+/// The elements are matched against the expansion of the routine parts the training session was
+/// created from. The matching stops at the first element which does not correspond to the routine,
+/// e.g., due to a set added or an exercise replaced by the user, so that no rounds are determined
+/// for this and all subsequent elements. The right side of a unilateral set pair corresponds to
+/// the same routine activity as the left side.
+#[must_use]
+pub fn training_session_rounds(
+    elements: &[TrainingSessionElement],
+    parts: &[RoutinePart],
+) -> Vec<Vec<Round>> {
+    let mut expanded_elements = expand_routine_parts(parts, &|_, _| None).into_iter();
+    let mut result: Vec<Vec<Round>> = vec![];
+    for (idx, element) in elements.iter().enumerate() {
+        if is_paired_right_side(elements, idx) {
+            result.push(result.last().cloned().unwrap_or_default());
+            continue;
+        }
+        let Some(expanded) = expanded_elements.next() else {
+            break;
+        };
+        let matching = match (element, &expanded.element) {
+            (
+                TrainingSessionElement::Set { exercise_id, .. },
+                TrainingSessionElement::Set {
+                    exercise_id: expected_exercise_id,
+                    ..
+                },
+            ) => exercise_id == expected_exercise_id,
+            (TrainingSessionElement::Rest { .. }, TrainingSessionElement::Rest { .. }) => true,
+            _ => false,
+        };
+        if !matching {
+            break;
+        }
+        result.push(expanded.rounds);
+    }
+    result.resize(elements.len(), vec![]);
+    result
+}
+
+/// Replace each set of a unilateral exercise, which has no side yet, by a set for the left side
+/// directly followed by a set for the right side.
+#[must_use]
+pub fn split_unilateral_sets(
+    elements: Vec<TrainingSessionElement>,
+    exercises: &BTreeMap<u32, Exercise>,
+) -> Vec<TrainingSessionElement> {
+    elements
+        .into_iter()
+        .flat_map(|element| match element {
+            TrainingSessionElement::Set {
+                exercise_id,
+                side: None,
+                ..
+            } if exercises
+                .get(&exercise_id)
+                .is_some_and(|e| e.laterality == Laterality::Unilateral) =>
+            {
+                [Side::Left, Side::Right]
+                    .into_iter()
+                    .map(|side| {
+                        let mut element = element.clone();
+                        if let TrainingSessionElement::Set { side: s, .. } = &mut element {
+                            *s = Some(side);
+                        }
+                        element
+                    })
+                    .collect()
+            }
+            _ => vec![element],
+        })
+        .collect()
+}
+
+/// Check whether the element is the right side of a set pair of a unilateral exercise, i.e., it
+/// directly follows the left side of the same exercise.
+#[must_use]
+pub fn is_paired_right_side(elements: &[TrainingSessionElement], idx: usize) -> bool {
+    match (idx.checked_sub(1).map(|i| &elements[i]), elements.get(idx)) {
+        (
+            Some(TrainingSessionElement::Set {
+                exercise_id: previous_exercise_id,
+                side: Some(Side::Left),
+                ..
+            }),
+            Some(TrainingSessionElement::Set {
+                exercise_id,
+                side: Some(Side::Right),
+                ..
+            }),
+        ) => exercise_id == previous_exercise_id,
+        _ => false,
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct TrainingSession {
+    pub id: u32,
+    pub routine_id: Option<u32>,
+    pub date: NaiveDate,
+    pub notes: Option<String>,
+    /// Begin of a guided training session or of a manually entered duration (UTC).
+    #[serde(default)]
+    pub start_time: Option<NaiveDateTime>,
+    /// End of a guided training session or of a manually entered duration (UTC).
+    #[serde(default)]
+    pub end_time: Option<NaiveDateTime>,
+    pub elements: Vec<TrainingSessionElement>,
+}
+
+impl TrainingSession {
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        match (self.start_time, self.end_time) {
+            (Some(start_time), Some(end_time)) if end_time >= start_time => {
+                Some(end_time - start_time)
+            }
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn exercises(&self) -> BTreeSet<u32> {
+        self.elements
+            .iter()
+            .filter_map(|e| match e {
+                TrainingSessionElement::Set { exercise_id, .. } => Some(*exercise_id),
+                TrainingSessionElement::Rest { .. } => None,
+            })
+            .collect::<BTreeSet<_>>()
+    }
+
+    #[must_use]
+    pub fn avg_reps(&self) -> Option<f32> {
+        let sets = &self
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                TrainingSessionElement::Set { reps, .. } => *reps,
+                TrainingSessionElement::Rest { .. } => None,
+            })
+            .collect::<Vec<_>>();
+        if sets.is_empty() {
             None
         } else {
             #[allow(clippy::cast_precision_loss)]
@@ -298,27 +1167,31 @@ impl TrainingSession {
         sets.iter().sum::<u32>()
     }
 
+    /// Sum of the products of reps and total moved weight of all sets.
+    ///
+    /// The recorded weights are normalized based on the weight convention of the exercise. Partial
+    /// reps are counted at half value (see [`effective_reps`]).
     #[must_use]
-    pub fn volume_load(&self) -> u32 {
+    pub fn volume_load(&self, exercises: &BTreeMap<u32, Exercise>) -> u32 {
         let sets = &self
             .elements
             .iter()
             .filter_map(|e| match e {
-                TrainingSessionElement::Set { reps, weight, .. } => {
-                    if let Some(reps) = reps {
-                        #[allow(
-                            clippy::cast_possible_truncation,
-                            clippy::cast_precision_loss,
-                            clippy::cast_sign_loss
-                        )]
-                        if let Some(weight) = weight {
-                            Some((*reps as f32 * weight).round() as u32)
-                        } else {
-                            Some(*reps)
-                        }
-                    } else {
-                        None
-                    }
+                TrainingSessionElement::Set {
+                    exercise_id,
+                    reps,
+                    partial_reps,
+                    weight,
+                    ..
+                } => {
+                    let reps = effective_reps((*reps)?, *partial_reps);
+                    let weight = weight.map_or(1.0, |weight| {
+                        exercises
+                            .get(exercise_id)
+                            .map_or(weight, |e| e.total_weight(weight))
+                    });
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    Some((reps * weight).round() as u32)
                 }
                 TrainingSessionElement::Rest { .. } => None,
             })
@@ -383,11 +1256,22 @@ pub enum TrainingSessionElement {
         time: Option<u32>,
         weight: Option<f32>,
         rpe: Option<f32>,
-        target_reps: Option<u32>,
+        target_reps: Option<RepsRange>,
         target_time: Option<u32>,
         target_weight: Option<f32>,
-        target_rpe: Option<f32>,
+        target_rpe: Option<RpeRange>,
         automatic: bool,
+        /// Additional partial repetitions performed after the full repetitions.
+        #[serde(default)]
+        partial_reps: Option<u32>,
+        /// The set was performed to muscular failure.
+        #[serde(default)]
+        to_failure: bool,
+        /// Trained side of a unilateral exercise, or `None` if both sides were trained at once.
+        #[serde(default)]
+        side: Option<Side>,
+        #[serde(default)]
+        comment: Option<String>,
     },
     Rest {
         target_time: Option<u32>,
@@ -395,956 +1279,4920 @@ pub enum TrainingSessionElement {
     },
 }
 
-#[cfg_attr(test, derive(Debug, PartialEq))]
-pub struct TrainingStats {
-    pub short_term_load: Vec<(NaiveDate, f32)>,
-    pub long_term_load: Vec<(NaiveDate, f32)>,
+/// Number of repetitions used for volume computations.
+///
+/// A partial rep covers only a part of the range of motion, so it is counted as half a rep.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn effective_reps(reps: u32, partial_reps: Option<u32>) -> f32 {
+    reps as f32 + partial_reps.unwrap_or(0) as f32 / 2.0
 }
 
-impl TrainingStats {
-    pub const LOAD_RATIO_LOW: f32 = 0.8;
-    pub const LOAD_RATIO_HIGH: f32 = 1.5;
+/// Target value, given either as a single value or as a range like 8–12.
+///
+/// A single value is represented by a range whose bounds are equal. It is serialized as a plain
+/// number, so that data stored before the introduction of ranges can still be read.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(
+    from = "TargetRangeRepr<T>",
+    into = "TargetRangeRepr<T>",
+    bound(
+        serialize = "T: Copy + PartialOrd + serde::Serialize",
+        deserialize = "T: Copy + PartialOrd + serde::Deserialize<'de>"
+    )
+)]
+pub struct TargetRange<T> {
+    pub min: T,
+    pub max: T,
+}
+
+/// Target reps, given either as a single value or as a range like 8–12.
+pub type RepsRange = TargetRange<u32>;
+
+/// Target RPE, given either as a single value or as a range like 7–8.
+pub type RpeRange = TargetRange<f32>;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum TargetRangeRepr<T> {
+    Single(T),
+    Range(T, T),
+}
+
+impl<T: Copy + PartialOrd> From<TargetRangeRepr<T>> for TargetRange<T> {
+    fn from(value: TargetRangeRepr<T>) -> Self {
+        match value {
+            TargetRangeRepr::Single(value) => TargetRange::single(value),
+            TargetRangeRepr::Range(min, max) => TargetRange::new(min, max),
+        }
+    }
+}
 
+impl<T: Copy + PartialOrd> From<TargetRange<T>> for TargetRangeRepr<T> {
+    fn from(value: TargetRange<T>) -> Self {
+        if value.is_single() {
+            TargetRangeRepr::Single(value.min)
+        } else {
+            TargetRangeRepr::Range(value.min, value.max)
+        }
+    }
+}
+
+impl<T: Copy + PartialOrd> TargetRange<T> {
     #[must_use]
-    pub fn load_ratio(&self) -> Option<f32> {
-        let long_term_load = self.long_term_load.last().map_or(0., |(_, l)| *l);
-        if long_term_load > 0. {
-            let short_term_load = self.short_term_load.last().map_or(0., |(_, l)| *l);
-            Some(short_term_load / long_term_load)
+    pub fn new(min: T, max: T) -> Self {
+        if min <= max {
+            Self { min, max }
+        } else {
+            Self { min: max, max: min }
+        }
+    }
+
+    #[must_use]
+    pub fn single(value: T) -> Self {
+        Self {
+            min: value,
+            max: value,
+        }
+    }
+
+    #[must_use]
+    pub fn is_single(&self) -> bool {
+        self.min == self.max
+    }
+
+    /// The value if the range consists of a single value.
+    #[must_use]
+    pub fn value(&self) -> Option<T> {
+        if self.is_single() {
+            Some(self.min)
         } else {
             None
         }
     }
 
-    pub fn clear(&mut self) {
-        self.short_term_load.clear();
-        self.long_term_load.clear();
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        self.min <= value && value <= self.max
     }
 }
 
-#[must_use]
-pub fn training_stats(training_sessions: &[&TrainingSession]) -> TrainingStats {
-    let short_term_load = weighted_sum_of_load(training_sessions, 7);
-    let long_term_load = average_weighted_sum_of_load(&short_term_load, 28);
-    TrainingStats {
-        short_term_load,
-        long_term_load,
+impl<T: Copy + PartialOrd + Default> TargetRange<T> {
+    /// Check whether a target is defined. A value of zero is considered as not set.
+    #[must_use]
+    pub fn is_set(&self) -> bool {
+        self.max > T::default()
     }
 }
 
-fn weighted_sum_of_load(
-    training_sessions: &[&TrainingSession],
-    window_size: usize,
-) -> Vec<(NaiveDate, f32)> {
-    let mut result: BTreeMap<NaiveDate, f32> = BTreeMap::new();
+impl RepsRange {
+    /// Value within the range which is nearest to the reps.
+    #[must_use]
+    pub fn clamp(&self, reps: u32) -> u32 {
+        reps.clamp(self.min, self.max)
+    }
 
-    let today = Local::now().date_naive();
-    let mut day = training_sessions.first().map_or(today, |t| t.date);
-    while day <= today {
-        result.insert(day, 0.0);
-        day += Duration::days(1);
+    /// Difference between the reps and the nearest bound, which is zero if the reps are within the
+    /// range.
+    #[must_use]
+    pub fn deviation(&self, reps: u32) -> i64 {
+        i64::from(reps) - i64::from(self.clamp(reps))
     }
+}
 
-    for t in training_sessions {
-        #[allow(clippy::cast_precision_loss)]
-        result
-            .entry(t.date)
-            .and_modify(|e| *e += t.load() as f32)
-            .or_insert(t.load() as f32);
+impl<T: Copy + PartialOrd + fmt::Display> fmt::Display for TargetRange<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_single() {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}–{}", self.min, self.max)
+        }
     }
+}
 
-    #[allow(clippy::cast_precision_loss)]
-    let weighting: Vec<f32> = (0..window_size)
-        .map(|i| 1. - 1. / window_size as f32 * i as f32)
-        .collect();
-    let mut window: Vec<f32> = (0..window_size).map(|_| 0.).collect();
+impl FromStr for RpeRange {
+    type Err = SetValueError;
+
+    /// Parse a single value like "8" or a range like "7-8" or "7–8".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f32>()
+                .map_err(|_| SetValueError::RPE)
+                .and_then(validate_rpe)
+        };
+        match s.split_once(['-', '–']) {
+            Some((min, max)) => {
+                let (min, max) = (parse(min)?, parse(max)?);
+                if min > max {
+                    return Err(SetValueError::RPE);
+                }
+                Ok(RpeRange { min, max })
+            }
+            None => Ok(RpeRange::single(parse(s)?)),
+        }
+    }
+}
 
-    result
-        .into_iter()
-        .map(|(date, load)| {
-            window.rotate_right(1);
-            window[0] = load;
-            (
-                date,
-                zip(&window, &weighting)
-                    .map(|(load, weight)| load * weight)
-                    .sum(),
-            )
-        })
-        .collect()
+/// Value of a set which is out of range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetValueError {
+    Reps,
+    RepsRange,
+    Time,
+    Weight,
+    RPE,
 }
 
-fn average_weighted_sum_of_load(
-    weighted_sum_of_load: &[(NaiveDate, f32)],
-    window_size: usize,
-) -> Vec<(NaiveDate, f32)> {
-    #[allow(clippy::cast_precision_loss)]
-    weighted_sum_of_load
-        .windows(window_size)
-        .map(|window| {
-            (
-                window.last().unwrap().0,
-                window.iter().map(|(_, l)| l).sum::<f32>() / window_size as f32,
-            )
+impl fmt::Display for SetValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            SetValueError::Reps => "Reps must be a whole number between 1 and 999",
+            SetValueError::RepsRange => "Minimum reps must not exceed maximum reps",
+            SetValueError::Time => "Time must be a whole number of seconds between 1 and 999",
+            SetValueError::Weight => "Weight must be between 0.1 and 999.9 kg in steps of 0.1",
+            SetValueError::RPE => "RPE must be between 0 and 10 in steps of 0.5",
         })
-        .collect::<Vec<_>>()
+    }
 }
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
-#[cfg_attr(test, derive(Debug))]
-pub enum Muscle {
-    // Neck
-    Neck = 1,
-    // Chest
-    Pecs = 11,
-    // Back
-    Traps = 21,
-    Lats = 22,
-    // Shoulders
-    FrontDelts = 31,
-    SideDelts = 32,
-    RearDelts = 33,
-    // Upper arms
-    Biceps = 41,
-    Triceps = 42,
-    // Forearms
-    Forearms = 51,
-    // Waist
-    Abs = 61,
-    ErectorSpinae = 62,
-    // Hips
-    Glutes = 71,
-    Abductors = 72,
-    // Thighs
-    Quads = 81,
-    Hamstrings = 82,
-    Adductors = 83,
-    // Calves
-    Calves = 91,
+/// Check that the number of repetitions is within the allowed range.
+///
+/// # Errors
+///
+/// Returns an error if the value is out of range.
+pub fn validate_reps(reps: u32) -> Result<u32, SetValueError> {
+    if reps > 0 && reps < 1000 {
+        Ok(reps)
+    } else {
+        Err(SetValueError::Reps)
+    }
 }
 
-impl Muscle {
-    pub fn iter() -> Iter<'static, Muscle> {
-        static MUSCLES: [Muscle; 18] = [
-            Muscle::Neck,
-            Muscle::Pecs,
-            Muscle::Traps,
-            Muscle::Lats,
-            Muscle::FrontDelts,
-            Muscle::SideDelts,
-            Muscle::RearDelts,
-            Muscle::Biceps,
-            Muscle::Triceps,
-            Muscle::Forearms,
-            Muscle::Abs,
-            Muscle::ErectorSpinae,
-            Muscle::Glutes,
-            Muscle::Abductors,
-            Muscle::Quads,
-            Muscle::Hamstrings,
-            Muscle::Adductors,
-            Muscle::Calves,
-        ];
-        MUSCLES.iter()
-    }
-
-    #[must_use]
-    pub fn id(self) -> u8 {
-        self as u8
-    }
-
-    #[must_use]
-    pub fn from_repr(repr: u8) -> Option<Muscle> {
-        match repr {
-            x if x == Muscle::Neck as u8 => Some(Muscle::Neck),
-            x if x == Muscle::Pecs as u8 => Some(Muscle::Pecs),
-            x if x == Muscle::Traps as u8 => Some(Muscle::Traps),
-            x if x == Muscle::Lats as u8 => Some(Muscle::Lats),
-            x if x == Muscle::FrontDelts as u8 => Some(Muscle::FrontDelts),
-            x if x == Muscle::SideDelts as u8 => Some(Muscle::SideDelts),
-            x if x == Muscle::RearDelts as u8 => Some(Muscle::RearDelts),
-            x if x == Muscle::Biceps as u8 => Some(Muscle::Biceps),
-            x if x == Muscle::Triceps as u8 => Some(Muscle::Triceps),
-            x if x == Muscle::Forearms as u8 => Some(Muscle::Forearms),
-            x if x == Muscle::Abs as u8 => Some(Muscle::Abs),
-            x if x == Muscle::ErectorSpinae as u8 => Some(Muscle::ErectorSpinae),
-            x if x == Muscle::Glutes as u8 => Some(Muscle::Glutes),
-            x if x == Muscle::Abductors as u8 => Some(Muscle::Abductors),
-            x if x == Muscle::Quads as u8 => Some(Muscle::Quads),
-            x if x == Muscle::Hamstrings as u8 => Some(Muscle::Hamstrings),
-            x if x == Muscle::Adductors as u8 => Some(Muscle::Adductors),
-            x if x == Muscle::Calves as u8 => Some(Muscle::Calves),
-            _ => None,
-        }
-    }
-
-    #[must_use]
-    pub fn name(self) -> &'static str {
-        match self {
-            Muscle::Neck => "Neck",
-            Muscle::Pecs => "Pecs",
-            Muscle::Traps => "Traps",
-            Muscle::Lats => "Lats",
-            Muscle::FrontDelts => "Front Delts",
-            Muscle::SideDelts => "Side Delts",
-            Muscle::RearDelts => "Rear Delts",
-            Muscle::Biceps => "Biceps",
-            Muscle::Triceps => "Triceps",
-            Muscle::Forearms => "Forearms",
-            Muscle::Abs => "Abs",
-            Muscle::ErectorSpinae => "Erector Spinae",
-            Muscle::Glutes => "Glutes",
-            Muscle::Abductors => "Abductors",
-            Muscle::Quads => "Quads",
-            Muscle::Hamstrings => "Hamstrings",
-            Muscle::Adductors => "Adductors",
-            Muscle::Calves => "Calves",
-        }
-    }
-
-    #[must_use]
-    pub fn description(self) -> &'static str {
-        #[allow(clippy::match_same_arms)]
-        match self {
-            Muscle::Neck => "",
-            Muscle::Pecs => "Chest",
-            Muscle::Traps => "Upper back",
-            Muscle::Lats => "Sides of back",
-            Muscle::FrontDelts => "Anterior shoulders",
-            Muscle::SideDelts => "Mid shoulders",
-            Muscle::RearDelts => "Posterior shoulders",
-            Muscle::Biceps => "Front of upper arms",
-            Muscle::Triceps => "Back of upper arms",
-            Muscle::Forearms => "",
-            Muscle::Abs => "Belly",
-            Muscle::ErectorSpinae => "Lower back and spine",
-            Muscle::Glutes => "Buttocks",
-            Muscle::Abductors => "Outside of hips",
-            Muscle::Quads => "Front of thighs",
-            Muscle::Hamstrings => "Back of thighs",
-            Muscle::Adductors => "Inner thighs",
-            Muscle::Calves => "Back of lower legs",
-        }
+/// Check that both bounds of a reps range are within the allowed range and that the minimum does
+/// not exceed the maximum. A missing maximum results in a single value.
+///
+/// # Errors
+///
+/// Returns an error if a bound is out of range or the bounds are in the wrong order.
+pub fn validate_reps_range(min: u32, max: Option<u32>) -> Result<RepsRange, SetValueError> {
+    let min = validate_reps(min)?;
+    let max = validate_reps(max.unwrap_or(min))?;
+    if min > max {
+        return Err(SetValueError::RepsRange);
     }
+    Ok(RepsRange { min, max })
 }
 
-#[derive(Default, PartialEq)]
-pub struct ExerciseFilter {
-    pub muscles: HashSet<Muscle>,
+/// Check that the time in seconds is within the allowed range.
+///
+/// # Errors
+///
+/// Returns an error if the value is out of range.
+pub fn validate_time(time: u32) -> Result<u32, SetValueError> {
+    if time > 0 && time < 1000 {
+        Ok(time)
+    } else {
+        Err(SetValueError::Time)
+    }
 }
 
-impl ExerciseFilter {
-    #[must_use]
-    pub fn is_empty(&self) -> bool {
-        self.muscles.is_empty()
+/// Check that the weight in kilograms is within the allowed range and has at most one decimal
+/// place.
+///
+/// # Errors
+///
+/// Returns an error if the value is out of range or too precise.
+pub fn validate_weight(weight: f32) -> Result<f32, SetValueError> {
+    if weight > 0.0 && weight < 1000.0 && (weight * 10.0 % 1.0).abs() < f32::EPSILON {
+        Ok(weight)
+    } else {
+        Err(SetValueError::Weight)
     }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
-pub struct BodyWeight {
-    pub date: NaiveDate,
-    pub weight: f32,
+/// Check that the RPE is within the allowed range and a multiple of 0.5.
+///
+/// # Errors
+///
+/// Returns an error if the value is out of range or not a multiple of 0.5.
+pub fn validate_rpe(rpe: f32) -> Result<f32, SetValueError> {
+    if (0.0..=10.0).contains(&rpe) && (rpe % 0.5).abs() < f32::EPSILON {
+        Ok(rpe)
+    } else {
+        Err(SetValueError::RPE)
+    }
 }
 
+/// Determine the previous training session from which the values of each exercise of the given
+/// training session are taken.
+///
+/// For a training session of a routine, the values of all exercises are taken from the latest
+/// previous training session of the same routine. For an ad-hoc training session, i.e. a training
+/// session without routine, the values of each exercise are taken from the latest previous ad-hoc
+/// training session containing the exercise. Training sessions of routines are never used for
+/// ad-hoc training sessions and vice versa.
+///
+/// The result maps the ID of each exercise to the previous training session. Exercises without a
+/// matching previous training session are omitted.
 #[must_use]
-pub fn avg_body_weight(
-    body_weight: &BTreeMap<NaiveDate, BodyWeight>,
-) -> BTreeMap<NaiveDate, BodyWeight> {
-    let data = body_weight
-        .values()
-        .map(|bw| (bw.date, bw.weight))
+pub fn previous_training_sessions<'a>(
+    training_session: &TrainingSession,
+    training_sessions: impl IntoIterator<Item = &'a TrainingSession>,
+) -> BTreeMap<u32, &'a TrainingSession> {
+    let candidates = training_sessions
+        .into_iter()
+        .filter(|t| {
+            t.id != training_session.id
+                && t.date <= training_session.date
+                && t.routine_id == training_session.routine_id
+        })
         .collect::<Vec<_>>();
-    value_based_centered_moving_average(&data, 4)
+    let latest = |exercise_id: u32| {
+        candidates
+            .iter()
+            .filter(|t| {
+                training_session.routine_id.is_some() || t.exercises().contains(&exercise_id)
+            })
+            .max_by_key(|t| (t.date, t.id))
+            .copied()
+    };
+    training_session
+        .exercises()
         .into_iter()
-        .map(|(date, weight)| (date, BodyWeight { date, weight }))
+        .filter_map(|exercise_id| {
+            let previous = latest(exercise_id)?;
+            previous
+                .exercises()
+                .contains(&exercise_id)
+                .then_some((exercise_id, previous))
+        })
         .collect()
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
-pub struct BodyFat {
-    pub date: NaiveDate,
-    pub chest: Option<u8>,
-    pub abdominal: Option<u8>,
-    pub thigh: Option<u8>,
-    pub tricep: Option<u8>,
-    pub subscapular: Option<u8>,
-    pub suprailiac: Option<u8>,
-    pub midaxillary: Option<u8>,
-}
-
-impl BodyFat {
-    #[must_use]
-    pub fn jp3(&self, sex: u8) -> Option<f32> {
-        if sex == 0 {
-            Some(Self::jackson_pollock(
-                f32::from(self.tricep?) + f32::from(self.suprailiac?) + f32::from(self.thigh?),
-                1.099_492_1,
-                0.000_992_9,
-                0.000_002_3,
-                0.000_139_2,
-            ))
-        } else if sex == 1 {
-            Some(Self::jackson_pollock(
-                f32::from(self.chest?) + f32::from(self.abdominal?) + f32::from(self.thigh?),
-                1.109_38,
-                0.000_826_7,
-                0.000_001_6,
-                0.000_257_4,
-            ))
-        } else {
-            None
+/// Find the sets of the previous training sessions which correspond to the given elements.
+///
+/// The sets of each exercise are taken from the training session assigned to the exercise (cf.
+/// [`previous_training_sessions`]). The result contains an entry for each element, which is `None`
+/// for rests and for sets without a corresponding set.
+#[must_use]
+pub fn previous_sets<'a>(
+    elements: &[TrainingSessionElement],
+    previous_training_sessions: &BTreeMap<u32, &'a TrainingSession>,
+) -> Vec<Option<&'a TrainingSessionElement>> {
+    let mut sets = vec![None; elements.len()];
+    let mut session_ids = BTreeSet::new();
+    for previous_training_session in previous_training_sessions.values() {
+        if !session_ids.insert(previous_training_session.id) {
+            continue;
+        }
+        let corresponding_sets = corresponding_sets(elements, &previous_training_session.elements);
+        for ((set, element), corresponding_set) in
+            sets.iter_mut().zip(elements).zip(corresponding_sets)
+        {
+            if let TrainingSessionElement::Set { exercise_id, .. } = element {
+                if previous_training_sessions
+                    .get(exercise_id)
+                    .is_some_and(|t| t.id == previous_training_session.id)
+                {
+                    *set = corresponding_set;
+                }
+            }
         }
     }
+    sets
+}
 
-    #[must_use]
-    pub fn jp7(&self, sex: u8) -> Option<f32> {
-        if sex == 0 {
-            Some(Self::jackson_pollock(
-                f32::from(self.chest?)
-                    + f32::from(self.abdominal?)
-                    + f32::from(self.thigh?)
-                    + f32::from(self.tricep?)
-                    + f32::from(self.subscapular?)
-                    + f32::from(self.suprailiac?)
-                    + f32::from(self.midaxillary?),
-                1.097,
-                0.000_469_71,
-                0.000_000_56,
-                0.000_128_28,
-            ))
-        } else if sex == 1 {
-            Some(Self::jackson_pollock(
-                f32::from(self.chest?)
-                    + f32::from(self.abdominal?)
-                    + f32::from(self.thigh?)
-                    + f32::from(self.tricep?)
-                    + f32::from(self.subscapular?)
-                    + f32::from(self.suprailiac?)
-                    + f32::from(self.midaxillary?),
-                1.112,
-                0.000_434_99,
-                0.000_000_55,
-                0.000_288_26,
-            ))
-        } else {
-            None
+/// Find the sets of a previous training session which correspond to the given elements.
+///
+/// Each set is assigned to a slot, which consists of the exercise and its position in the block of
+/// consecutive sets between two rests, e.g. the first or second exercise of a superset. The n-th
+/// set of a slot corresponds to the n-th performed set of the same slot in the previous training
+/// session. Sets without any recorded reps or time are not considered as performed. If the slot
+/// contains more sets than were performed previously, the last performed set is used for the
+/// remaining sets. Sets of other slots are never used.
+///
+/// The result contains an entry for each element, which is `None` for rests and for sets without a
+/// corresponding set.
+fn corresponding_sets<'a>(
+    elements: &[TrainingSessionElement],
+    previous_elements: &'a [TrainingSessionElement],
+) -> Vec<Option<&'a TrainingSessionElement>> {
+    let mut performed: BTreeMap<(u32, usize), Vec<&TrainingSessionElement>> = BTreeMap::new();
+    for (element, slot) in previous_elements.iter().zip(slots(previous_elements)) {
+        if let (Some(slot), TrainingSessionElement::Set { reps, time, .. }) = (slot, element) {
+            if reps.is_some() || time.is_some() {
+                performed.entry(slot).or_default().push(element);
+            }
         }
     }
 
-    fn jackson_pollock(sum: f32, k0: f32, k1: f32, k2: f32, ka: f32) -> f32 {
-        let age = 30.; // assume an age of 30
-        (495. / (k0 - (k1 * sum) + (k2 * sum * sum) - (ka * age))) - 450.
-    }
+    let mut ordinals: BTreeMap<(u32, usize), usize> = BTreeMap::new();
+    slots(elements)
+        .into_iter()
+        .map(|slot| {
+            let slot = slot?;
+            let ordinal = ordinals.entry(slot).or_insert(0);
+            let sets = performed.get(&slot)?;
+            let set = sets.get(*ordinal).or_else(|| sets.last()).copied();
+            *ordinal += 1;
+            set
+        })
+        .collect()
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
-pub struct Period {
-    pub date: NaiveDate,
-    pub intensity: u8,
+/// Determine the slot of each set, i.e. its exercise and the position of the exercise in the
+/// block of consecutive sets.
+fn slots(elements: &[TrainingSessionElement]) -> Vec<Option<(u32, usize)>> {
+    let mut block: Vec<u32> = vec![];
+    elements
+        .iter()
+        .map(|element| match element {
+            TrainingSessionElement::Set { exercise_id, .. } => {
+                let position = block
+                    .iter()
+                    .position(|id| id == exercise_id)
+                    .unwrap_or_else(|| {
+                        block.push(*exercise_id);
+                        block.len() - 1
+                    });
+                Some((*exercise_id, position))
+            }
+            TrainingSessionElement::Rest { .. } => {
+                block.clear();
+                None
+            }
+        })
+        .collect()
 }
 
+/// Create a routine which repeats the training session.
+///
+/// The performed values of the sets are used as targets. The targets of the training session are
+/// only used for values which were not recorded. A set forms a block together with the directly
+/// following sets of a superset and the subsequent rest. Consecutive identical blocks are merged
+/// into a section with the corresponding number of rounds, where the last block of the training
+/// session may lack the rest. Rests which do not follow a set are omitted, as is a rest at the end
+/// of the routine unless it is part of a section with multiple rounds. A pair of sets for the left
+/// and the right side of a unilateral exercise results in a single activity.
 #[must_use]
-pub fn cycles(period: &BTreeMap<NaiveDate, Period>) -> Vec<Cycle> {
-    if period.is_empty() {
-        return vec![];
+pub fn routine_from_session(training_session: &TrainingSession, name: &str) -> Routine {
+    let mut blocks: Vec<Vec<RoutinePart>> = vec![];
+    let mut block = vec![];
+
+    for (idx, element) in training_session.elements.iter().enumerate() {
+        if is_paired_right_side(&training_session.elements, idx) {
+            continue;
+        }
+        match element {
+            TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time,
+                weight,
+                rpe,
+                target_reps,
+                target_time,
+                target_weight,
+                target_rpe,
+                automatic,
+                ..
+            } => block.push(RoutinePart::RoutineActivity {
+                exercise_id: Some(*exercise_id),
+                reps: reps
+                    .map(RepsRange::single)
+                    .or(*target_reps)
+                    .unwrap_or_default(),
+                time: time.or(*target_time).unwrap_or(0),
+                weight: weight.or(*target_weight).unwrap_or(0.0),
+                rpe: rpe
+                    .map(RpeRange::single)
+                    .or(*target_rpe)
+                    .unwrap_or_default(),
+                automatic: *automatic,
+                target_percent_1rm: None,
+            }),
+            TrainingSessionElement::Rest {
+                target_time,
+                automatic,
+            } => {
+                if !block.is_empty() {
+                    block.push(RoutinePart::RoutineActivity {
+                        exercise_id: None,
+                        reps: RepsRange::default(),
+                        time: target_time.unwrap_or(0),
+                        weight: 0.0,
+                        rpe: RpeRange::default(),
+                        automatic: *automatic,
+                        target_percent_1rm: None,
+                    });
+                    blocks.push(std::mem::take(&mut block));
+                }
+            }
+        }
     }
 
-    let mut result = vec![];
-    let mut begin = period.keys().min().copied().unwrap_or_default();
-    let mut last = begin;
+    if !block.is_empty() {
+        blocks.push(block);
+    }
 
-    let period = period.values().collect::<Vec<_>>();
+    let mut sections: Vec<(u32, Vec<RoutinePart>)> = vec![];
 
-    for p in &period[1..] {
-        if p.date - last > Duration::days(3) {
-            result.push(Cycle {
-                begin,
-                length: p.date - begin,
-            });
-            begin = p.date;
+    for block in blocks {
+        match sections.last_mut() {
+            Some((rounds, parts))
+                if *parts == block
+                    || (parts.len() == block.len() + 1 && parts.starts_with(&block)) =>
+            {
+                *rounds += 1;
+            }
+            _ => sections.push((1, block)),
         }
-        last = p.date;
     }
 
-    result
-}
-
-#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
-pub struct Cycle {
-    pub begin: NaiveDate,
-    pub length: Duration,
+    if let Some((1, parts)) = sections.last_mut() {
+        if matches!(
+            parts.last(),
+            Some(RoutinePart::RoutineActivity {
+                exercise_id: None,
+                ..
+            })
+        ) {
+            parts.pop();
+        }
+    }
+
+    Routine {
+        id: 0,
+        name: name.to_string(),
+        notes: None,
+        archived: false,
+        sections: sections
+            .into_iter()
+            .map(|(rounds, parts)| RoutinePart::RoutineSection { rounds, parts })
+            .collect(),
+        schedule: vec![],
+        tags: vec![],
+    }
+}
+
+/// Create a training session at the date which repeats the training session.
+///
+/// The performed values of the sets are used as targets. The targets of the training session are
+/// only used for values which were not recorded, so that skipped sets keep their original targets.
+/// The new training session refers to the same routine, but contains neither performed values nor
+/// comments.
+#[must_use]
+pub fn repeat_training_session(
+    training_session: &TrainingSession,
+    date: NaiveDate,
+) -> TrainingSession {
+    TrainingSession {
+        id: 0,
+        routine_id: training_session.routine_id,
+        date,
+        notes: None,
+        start_time: None,
+        end_time: None,
+        elements: training_session
+            .elements
+            .iter()
+            .map(|element| match element {
+                TrainingSessionElement::Set {
+                    exercise_id,
+                    reps,
+                    time,
+                    weight,
+                    rpe,
+                    target_reps,
+                    target_time,
+                    target_weight,
+                    target_rpe,
+                    automatic,
+                    side,
+                    ..
+                } => TrainingSessionElement::Set {
+                    exercise_id: *exercise_id,
+                    reps: None,
+                    time: None,
+                    weight: None,
+                    rpe: None,
+                    target_reps: reps.map(RepsRange::single).or(*target_reps),
+                    target_time: time.or(*target_time),
+                    target_weight: weight.or(*target_weight),
+                    target_rpe: rpe.map(RpeRange::single).or(*target_rpe),
+                    automatic: *automatic,
+                    partial_reps: None,
+                    to_failure: false,
+                    side: *side,
+                    comment: None,
+                },
+                TrainingSessionElement::Rest { .. } => element.clone(),
+            })
+            .collect(),
+    }
 }
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
-pub struct CurrentCycle {
-    pub begin: NaiveDate,
-    pub time_left: Duration,
-    pub time_left_variation: Duration,
+pub struct TrainingStats {
+    pub short_term_load: Vec<(NaiveDate, f32)>,
+    pub long_term_load: Vec<(NaiveDate, f32)>,
 }
 
-#[must_use]
-pub fn current_cycle(cycles: &[Cycle]) -> Option<CurrentCycle> {
-    if cycles.is_empty() {
-        return None;
+impl TrainingStats {
+    pub const LOAD_RATIO_LOW: f32 = 0.8;
+    pub const LOAD_RATIO_HIGH: f32 = 1.5;
+    pub const DELOAD_DAYS: u32 = 7;
+    pub const SHORT_TERM_LOAD_WINDOW: usize = 7;
+    pub const LONG_TERM_LOAD_WINDOW: usize = 28;
+
+    #[must_use]
+    pub fn load_ratio(&self) -> Option<f32> {
+        let long_term_load = self.long_term_load.last().map_or(0., |(_, l)| *l);
+        if long_term_load > 0. {
+            let short_term_load = self.short_term_load.last().map_or(0., |(_, l)| *l);
+            Some(short_term_load / long_term_load)
+        } else {
+            None
+        }
     }
 
-    let today = Local::now().date_naive();
-    let cycles = cycles
-        .iter()
-        .filter(|c| (c.begin >= today - Duration::days(182) && c.begin <= today))
-        .collect::<Vec<_>>();
-    let stats = cycle_stats(&cycles);
+    /// Ratio of the short-term to the long-term load of all days with a long-term load.
+    #[must_use]
+    pub fn load_ratios(&self) -> Vec<(NaiveDate, f32)> {
+        let short_term_load = self
+            .short_term_load
+            .iter()
+            .copied()
+            .collect::<BTreeMap<_, _>>();
+        self.long_term_load
+            .iter()
+            .filter(|(_, long_term_load)| *long_term_load > 0.)
+            .filter_map(|(date, long_term_load)| {
+                short_term_load
+                    .get(date)
+                    .map(|l| (*date, l / long_term_load))
+            })
+            .collect()
+    }
+
+    /// Analyse the load ratio of the past weeks.
+    ///
+    /// A deload is recommended if the load ratio was above `LOAD_RATIO_HIGH` for at least
+    /// `DELOAD_DAYS` consecutive days up to the latest day. An increase is recommended if the
+    /// average load ratio is below `LOAD_RATIO_LOW`. No recommendation is given for histories
+    /// shorter than a week.
+    #[must_use]
+    pub fn load_recommendation(&self, weeks: usize) -> Option<LoadRecommendation> {
+        let load_ratios = self
+            .load_ratios()
+            .into_iter()
+            .map(|(_, ratio)| ratio)
+            .collect::<Vec<_>>();
+        let load_ratios = &load_ratios[load_ratios.len().saturating_sub(weeks * 7)..];
+
+        if load_ratios.len() < 7 {
+            return None;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let average_load_ratio = load_ratios.iter().sum::<f32>() / load_ratios.len() as f32;
+        let days_above_high = u32::try_from(
+            load_ratios
+                .iter()
+                .rev()
+                .take_while(|r| **r > Self::LOAD_RATIO_HIGH)
+                .count(),
+        )
+        .unwrap_or(u32::MAX);
 
-    if let Some(last_cycle) = cycles.last() {
-        let begin = last_cycle.begin + last_cycle.length;
-        Some(CurrentCycle {
-            begin,
-            time_left: stats.length_median - (today - begin + Duration::days(1)),
-            time_left_variation: stats.length_variation,
+        Some(LoadRecommendation {
+            recommendation: if days_above_high >= Self::DELOAD_DAYS {
+                Recommendation::Deload
+            } else if average_load_ratio < Self::LOAD_RATIO_LOW {
+                Recommendation::Increase
+            } else {
+                Recommendation::Maintain
+            },
+            average_load_ratio,
+            days_above_high,
         })
-    } else {
-        None
+    }
+
+    pub fn clear(&mut self) {
+        self.short_term_load.clear();
+        self.long_term_load.clear();
+    }
+
+    /// Update the statistics after training sessions were added, changed or removed.
+    ///
+    /// The training sessions must contain all current training sessions. The changed dates must
+    /// contain the dates of all added, changed and removed training sessions, including the
+    /// previous date of training sessions whose date was changed. Only the loads affected by these
+    /// dates are recalculated, unless the range of covered dates has changed. The result is
+    /// identical to a complete recalculation by `training_stats`.
+    pub fn update(
+        &mut self,
+        training_sessions: &[&TrainingSession],
+        changed_dates: &[NaiveDate],
+        clock: &impl Clock,
+    ) {
+        let (first, last) = load_date_range(training_sessions, clock.today());
+
+        if self.short_term_load.first().map(|(date, _)| *date) != Some(first)
+            || self.short_term_load.last().map(|(date, _)| *date) != Some(last)
+        {
+            *self = training_stats(training_sessions, clock);
+            return;
+        }
+
+        let changed_indices = changed_dates
+            .iter()
+            .filter(|date| **date >= first && **date <= last)
+            .map(|date| day_index(first, *date))
+            .collect::<BTreeSet<_>>();
+        let Some(start) = changed_indices.first() else {
+            return;
+        };
+
+        let days = self.short_term_load.len();
+        let mut short_term_affected = vec![false; days];
+        for index in &changed_indices {
+            for affected in
+                &mut short_term_affected[*index..(*index + Self::SHORT_TERM_LOAD_WINDOW).min(days)]
+            {
+                *affected = true;
+            }
+        }
+
+        let offset = start.saturating_sub(Self::SHORT_TERM_LOAD_WINDOW - 1);
+        let daily_load = daily_load(
+            training_sessions,
+            first + Days::new(offset as u64),
+            days - offset,
+        );
+        let weighting = load_weighting(Self::SHORT_TERM_LOAD_WINDOW);
+        for (index, (_, load)) in self.short_term_load.iter_mut().enumerate() {
+            if short_term_affected[index] {
+                *load = weighted_sum_of_load(&daily_load, index - offset, &weighting);
+            }
+        }
+
+        for (index, (_, load)) in self.long_term_load.iter_mut().enumerate() {
+            let window = &self.short_term_load[index..index + Self::LONG_TERM_LOAD_WINDOW];
+            if short_term_affected[index..index + Self::LONG_TERM_LOAD_WINDOW]
+                .iter()
+                .any(|affected| *affected)
+            {
+                *load = average_load(window);
+            }
+        }
     }
 }
 
-#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
-pub struct CycleStats {
-    pub length_median: Duration,
-    pub length_variation: Duration,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recommendation {
+    Deload,
+    Maintain,
+    Increase,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadRecommendation {
+    pub recommendation: Recommendation,
+    pub average_load_ratio: f32,
+    /// Number of consecutive days up to the latest day with a load ratio above
+    /// `TrainingStats::LOAD_RATIO_HIGH`.
+    pub days_above_high: u32,
 }
 
 #[must_use]
-pub fn cycle_stats(cycles: &[&Cycle]) -> CycleStats {
-    let mut cycle_lengths = cycles.iter().map(|c| c.length).collect::<Vec<_>>();
-    cycle_lengths.sort();
-    CycleStats {
-        length_median: quartile(&cycle_lengths, Quartile::Q2),
-        length_variation: (quartile(&cycle_lengths, Quartile::Q3)
-            - quartile(&cycle_lengths, Quartile::Q1))
-            / 2,
+pub fn training_stats(training_sessions: &[&TrainingSession], clock: &impl Clock) -> TrainingStats {
+    let (first, last) = load_date_range(training_sessions, clock.today());
+    let daily_load = daily_load(training_sessions, first, day_index(first, last) + 1);
+    let weighting = load_weighting(TrainingStats::SHORT_TERM_LOAD_WINDOW);
+    let short_term_load = first
+        .iter_days()
+        .enumerate()
+        .take(daily_load.len())
+        .map(|(index, date)| (date, weighted_sum_of_load(&daily_load, index, &weighting)))
+        .collect::<Vec<_>>();
+    let long_term_load =
+        average_weighted_sum_of_load(&short_term_load, TrainingStats::LONG_TERM_LOAD_WINDOW);
+    TrainingStats {
+        short_term_load,
+        long_term_load,
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum Quartile {
-    Q1 = 1,
-    Q2 = 2,
-    Q3 = 3,
+/// Determine the range of days covered by the training statistics.
+///
+/// The range starts at the earliest training session and ends today or at the latest training
+/// session, whatever is later.
+fn load_date_range(
+    training_sessions: &[&TrainingSession],
+    today: NaiveDate,
+) -> (NaiveDate, NaiveDate) {
+    let dates = training_sessions.iter().map(|t| t.date);
+    (
+        dates.clone().min().map_or(today, |first| first.min(today)),
+        dates.max().map_or(today, |last| last.max(today)),
+    )
 }
 
-#[must_use]
-pub fn quartile(durations: &[Duration], quartile_num: Quartile) -> Duration {
-    if durations.is_empty() {
-        return Duration::days(0);
-    }
-    let idx = durations.len() / 2;
-    match quartile_num {
-        Quartile::Q1 => quartile(&durations[..idx], Quartile::Q2),
-        Quartile::Q2 => {
-            if durations.len() % 2 == 0 {
-                (durations[idx - 1] + durations[idx]) / 2
-            } else {
-                durations[idx]
-            }
-        }
-        Quartile::Q3 => {
-            if durations.len() % 2 == 0 {
-                quartile(&durations[idx..], Quartile::Q2)
-            } else {
-                quartile(&durations[idx + 1..], Quartile::Q2)
+fn day_index(first: NaiveDate, date: NaiveDate) -> usize {
+    usize::try_from((date - first).num_days()).unwrap_or(0)
+}
+
+/// Sum up the load of all training sessions per day for the given number of days.
+fn daily_load(training_sessions: &[&TrainingSession], first: NaiveDate, days: usize) -> Vec<f32> {
+    let mut result = vec![0.0; days];
+    for t in training_sessions.iter().filter(|t| t.date >= first) {
+        if let Some(load) = result.get_mut(day_index(first, t.date)) {
+            #[allow(clippy::cast_precision_loss)]
+            {
+                *load += t.load() as f32;
             }
         }
     }
+    result
 }
 
-#[cfg_attr(test, derive(Debug, PartialEq))]
-pub struct Interval {
-    pub first: NaiveDate,
-    pub last: NaiveDate,
+fn load_weighting(window_size: usize) -> Vec<f32> {
+    #[allow(clippy::cast_precision_loss)]
+    (0..window_size)
+        .map(|i| 1. - 1. / window_size as f32 * i as f32)
+        .collect()
 }
 
-impl From<std::ops::RangeInclusive<NaiveDate>> for Interval {
-    fn from(value: std::ops::RangeInclusive<NaiveDate>) -> Self {
-        Interval {
-            first: *value.start(),
-            last: *value.end(),
-        }
-    }
+/// Calculate the weighted sum of the daily load on the given day and the preceding days.
+///
+/// Days before the first day are considered to have no load.
+fn weighted_sum_of_load(daily_load: &[f32], index: usize, weighting: &[f32]) -> f32 {
+    weighting
+        .iter()
+        .enumerate()
+        .map(|(i, weight)| {
+            index
+                .checked_sub(i)
+                .map_or(0., |preceding| daily_load[preceding])
+                * weight
+        })
+        .sum()
 }
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum DefaultInterval {
-    All,
-    _1Y = 365,
-    _6M = 182,
-    _3M = 91,
-    _1M = 30,
+fn average_weighted_sum_of_load(
+    weighted_sum_of_load: &[(NaiveDate, f32)],
+    window_size: usize,
+) -> Vec<(NaiveDate, f32)> {
+    weighted_sum_of_load
+        .windows(window_size)
+        .map(|window| (window.last().unwrap().0, average_load(window)))
+        .collect::<Vec<_>>()
+}
+
+fn average_load(window: &[(NaiveDate, f32)]) -> f32 {
+    #[allow(clippy::cast_precision_loss)]
+    {
+        window.iter().map(|(_, l)| l).sum::<f32>() / window.len() as f32
+    }
 }
 
+/// Sum up the stimulus per muscle of all training sessions in the interval for each ISO week.
+///
+/// All weeks of the interval are included for all muscles, so weeks without training result in
+/// zero values instead of gaps. The current muscle mapping of the exercises is used for all
+/// training sessions.
 #[must_use]
-pub fn init_interval(dates: &[NaiveDate], default_interval: DefaultInterval) -> Interval {
-    let today = Local::now().date_naive();
-    let mut first = dates.iter().copied().min().unwrap_or(today);
-    let mut last = dates.iter().copied().max().unwrap_or(today);
+pub fn weekly_stimulus_per_muscle(
+    training_sessions: &[&TrainingSession],
+    exercises: &BTreeMap<u32, Exercise>,
+    interval: &Interval,
+) -> BTreeMap<(IsoWeek, u8), u32> {
+    let mut result = BTreeMap::new();
 
-    if default_interval != DefaultInterval::All
-        && last >= today - Duration::days(default_interval as i64)
-    {
-        first = today - Duration::days(default_interval as i64);
-    };
+    let mut day = interval.first;
+    while day <= interval.last {
+        for muscle in Muscle::iter() {
+            result.insert((day.iso_week(), muscle.id()), 0);
+        }
+        day += Duration::days(1);
+    }
 
-    last = today;
+    for training_session in training_sessions
+        .iter()
+        .filter(|t| t.date >= interval.first && t.date <= interval.last)
+    {
+        for (muscle_id, stimulus) in training_session.stimulus_per_muscle(exercises) {
+            *result
+                .entry((training_session.date.iso_week(), muscle_id))
+                .or_insert(0) += stimulus;
+        }
+    }
 
-    Interval { first, last }
+    result
 }
 
-/// Group a series of (date, value) pairs.
-///
-/// The `radius` parameter determines the number of days before and after the
-/// center value to include in the calculation.
-///
-/// Only values which have a date within `interval` are used as a center value
-/// for the calculation. Values outside the interval are included in the
-/// calculation if they fall within the radius of a center value.
-///
-/// Two user-provided functions determine how values are combined:
-///
-///  - `group_day` is called to combine values of the *same* day.
-///  - `group_range` is called to combine values of multiple days after all
-///     values for the same day have been combined by `group_day`.
-///
-/// Return `None` in those functions to indicate the absence of a value.
+/// Sum up the stimulus per muscle of all training sessions in the interval.
 ///
-pub fn centered_moving_grouping(
-    data: &Vec<(NaiveDate, f32)>,
+/// All muscles are included, so muscles without training result in zero values.
+#[must_use]
+pub fn total_stimulus_per_muscle(
+    training_sessions: &[&TrainingSession],
+    exercises: &BTreeMap<u32, Exercise>,
     interval: &Interval,
-    radius: u64,
-    group_day: impl Fn(Vec<f32>) -> Option<f32>,
-    group_range: impl Fn(Vec<f32>) -> Option<f32>,
-) -> Vec<Vec<(NaiveDate, f32)>> {
-    let mut date_map: BTreeMap<&NaiveDate, Vec<f32>> = BTreeMap::new();
+) -> BTreeMap<u8, u32> {
+    let mut result = Muscle::iter()
+        .map(|muscle| (muscle.id(), 0))
+        .collect::<BTreeMap<_, _>>();
 
-    for (date, value) in data {
-        date_map.entry(date).or_default().push(*value);
+    for training_session in training_sessions
+        .iter()
+        .filter(|t| t.date >= interval.first && t.date <= interval.last)
+    {
+        for (muscle_id, stimulus) in training_session.stimulus_per_muscle(exercises) {
+            *result.entry(muscle_id).or_insert(0) += stimulus;
+        }
     }
 
-    let mut grouped: BTreeMap<&NaiveDate, f32> = BTreeMap::new();
+    result
+}
 
-    for (date, values) in date_map {
-        if let Some(result) = group_day(values) {
-            grouped.insert(date, result);
-        }
-    }
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExerciseUsageStats {
+    /// Number of performed sets.
+    pub sets: u32,
+    pub volume_load: u32,
+    /// Set with the highest estimated one-repetition maximum.
+    pub best_set: Option<BestSet>,
+    pub last_performed: Option<NaiveDate>,
+    /// Number of performed sets in the last 28 days.
+    pub recent_sets: u32,
+}
 
-    interval
-        .first
-        .iter_days()
-        .take_while(|d| *d <= interval.last)
-        .fold(
-            vec![vec![]],
-            |mut result: Vec<Vec<(NaiveDate, f32)>>, center| {
-                let value = group_range(
-                    center
-                        .checked_sub_days(Days::new(radius))
-                        .unwrap_or(center)
-                        .iter_days()
-                        .take_while(|d| {
-                            *d <= interval.last
-                                && *d
-                                    <= center.checked_add_days(Days::new(radius)).unwrap_or(center)
-                        })
-                        .filter_map(|d| grouped.get(&d))
-                        .copied()
-                        .collect::<Vec<_>>(),
-                );
-                if let Some(last) = result.last_mut() {
-                    match value {
-                        Some(v) => {
-                            last.push((center, v));
-                        }
-                        None => {
-                            if !last.is_empty() {
-                                result.push(vec![]);
-                            }
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BestSet {
+    pub date: NaiveDate,
+    pub reps: u32,
+    /// Recorded weight.
+    pub weight: f32,
+    /// Estimated one-repetition maximum based on the total moved weight.
+    pub one_rep_max: f32,
+}
+
+/// Summarize all performed sets of an exercise.
+///
+/// Sets without any recorded reps or time are not considered as performed. The volume load and the
+/// one-repetition maximum are based on the total moved weight. Partial reps are counted at half
+/// value in the volume load, but are not considered for the one-repetition maximum.
+#[must_use]
+pub fn exercise_usage_stats(
+    training_sessions: &[&TrainingSession],
+    exercise: &Exercise,
+    today: NaiveDate,
+) -> ExerciseUsageStats {
+    let mut stats = ExerciseUsageStats::default();
+
+    for training_session in training_sessions {
+        for element in &training_session.elements {
+            let TrainingSessionElement::Set {
+                exercise_id: id,
+                reps,
+                partial_reps,
+                time,
+                weight,
+                rpe,
+                ..
+            } = element
+            else {
+                continue;
+            };
+            if *id != exercise.id || (reps.is_none() && time.is_none()) {
+                continue;
+            }
+
+            stats.sets += 1;
+            if let Some(reps) = reps {
+                let volume_load = effective_reps(*reps, *partial_reps)
+                    * weight.map_or(1.0, |weight| exercise.total_weight(weight));
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let volume_load = volume_load.round() as u32;
+                stats.volume_load += volume_load;
+                if let Some(weight) = weight {
+                    if let Some(one_rep_max) =
+                        progression::one_rep_max(*reps, exercise.total_weight(*weight), *rpe)
+                    {
+                        if !matches!(stats.best_set, Some(best_set) if best_set.one_rep_max >= one_rep_max)
+                        {
+                            stats.best_set = Some(BestSet {
+                                date: training_session.date,
+                                reps: *reps,
+                                weight: *weight,
+                                one_rep_max,
+                            });
                         }
                     }
                 }
-                result
-            },
-        )
-        .into_iter()
-        .filter(|v| !v.is_empty())
-        .collect::<Vec<_>>()
+            }
+            stats.last_performed = stats.last_performed.max(Some(training_session.date));
+            if training_session.date > today - Duration::days(28) && training_session.date <= today
+            {
+                stats.recent_sets += 1;
+            }
+        }
+    }
+
+    stats
 }
 
-/// Calculate a series of moving totals from a given series of (date, value) pairs.
-///
-/// The radius argument determines the number of days to include into the calculated
-/// total before and after each value within the interval.
+/// Personal record of an exercise achieved in a training session.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PersonalRecord {
+    pub exercise_id: u32,
+    pub kind: RecordKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordKind {
+    /// Highest estimated one-repetition maximum based on the total moved weight.
+    OneRepMax(f32),
+    /// Highest recorded weight.
+    Weight(f32),
+    /// Most reps with the recorded weight or without weight.
+    Reps { reps: u32, weight: Option<f32> },
+    /// Longest time of a set without reps with the recorded weight or without weight.
+    Time { time: u32, weight: Option<f32> },
+}
+
+/// Determine the personal records achieved in a training session.
 ///
-/// Multiple values for the same date will be summed up.
+/// The best values of each exercise are compared with the best values of all training sessions
+/// that took place before, i.e., on an earlier date or on the same date with a lower ID. A value
+/// is only a record if it exceeds the previous best value, a tie is not a record. A record is only
+/// determined if a previous value exists. Consequently, exercises that are performed for the first
+/// time do not yield any records, and reps and time records are only determined for weights that
+/// have been used before.
 ///
-/// An empty result vector may be returned if there is no data within the interval.
+/// Sets without any recorded reps or time are not considered as performed. Partial reps are not
+/// considered. Time records are only determined for sets without reps.
 #[must_use]
-pub fn centered_moving_total(
-    data: &Vec<(NaiveDate, f32)>,
-    interval: &Interval,
-    radius: u64,
-) -> Vec<(NaiveDate, f32)> {
-    centered_moving_grouping(
-        data,
-        interval,
-        radius,
-        |d| Some(d.iter().sum()),
-        |d| Some(d.iter().sum()),
-    )[0]
-    .clone()
+pub fn personal_records(
+    training_session: &TrainingSession,
+    training_sessions: &[&TrainingSession],
+    exercises: &BTreeMap<u32, Exercise>,
+) -> Vec<PersonalRecord> {
+    let current = best_values([training_session], exercises);
+    let previous = best_values(
+        training_sessions
+            .iter()
+            .copied()
+            .filter(|t| (t.date, t.id) < (training_session.date, training_session.id)),
+        exercises,
+    );
+
+    let mut records = vec![];
+    for (exercise_id, current) in current {
+        let Some(previous) = previous.get(&exercise_id) else {
+            continue;
+        };
+        let mut push = |kind| records.push(PersonalRecord { exercise_id, kind });
+        if let (Some(value), Some(previous_value)) = (current.one_rep_max, previous.one_rep_max) {
+            if value > previous_value {
+                push(RecordKind::OneRepMax(value));
+            }
+        }
+        if let (Some(value), Some(previous_value)) = (current.weight, previous.weight) {
+            if value > previous_value {
+                push(RecordKind::Weight(value));
+            }
+        }
+        for (weight, reps) in current.reps {
+            if previous.reps.iter().any(|(w, r)| *w == weight && *r < reps) {
+                push(RecordKind::Reps { reps, weight });
+            }
+        }
+        for (weight, time) in current.time {
+            if previous.time.iter().any(|(w, t)| *w == weight && *t < time) {
+                push(RecordKind::Time { time, weight });
+            }
+        }
+    }
+    records
 }
 
-/// Calculate a series of moving averages from a given series of (date, value) pairs.
-///
-/// The radius argument determines the number of days to include into the calculated
-/// average before and after each value within the interval.
+#[derive(Default)]
+struct BestValues {
+    one_rep_max: Option<f32>,
+    weight: Option<f32>,
+    /// Most reps for each weight.
+    reps: Vec<(Option<f32>, u32)>,
+    /// Longest time for each weight.
+    time: Vec<(Option<f32>, u32)>,
+}
+
+fn best_values<'a>(
+    training_sessions: impl IntoIterator<Item = &'a TrainingSession>,
+    exercises: &BTreeMap<u32, Exercise>,
+) -> BTreeMap<u32, BestValues> {
+    fn update(values: &mut Vec<(Option<f32>, u32)>, weight: Option<f32>, value: u32) {
+        match values.iter_mut().find(|(w, _)| *w == weight) {
+            Some((_, v)) => *v = (*v).max(value),
+            None => values.push((weight, value)),
+        }
+    }
+
+    let mut result: BTreeMap<u32, BestValues> = BTreeMap::new();
+    for training_session in training_sessions {
+        for element in &training_session.elements {
+            let TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time,
+                weight,
+                rpe,
+                ..
+            } = element
+            else {
+                continue;
+            };
+            if reps.is_none() && time.is_none() {
+                continue;
+            }
+            let best = result.entry(*exercise_id).or_default();
+            if let Some(weight) = weight {
+                best.weight = Some(best.weight.map_or(*weight, |w| w.max(*weight)));
+            }
+            match (reps, time) {
+                (Some(reps), _) => {
+                    update(&mut best.reps, *weight, *reps);
+                    let total_weight = weight.map(|weight| {
+                        exercises
+                            .get(exercise_id)
+                            .map_or(weight, |e| e.total_weight(weight))
+                    });
+                    if let Some(value) = total_weight.and_then(|total_weight| {
+                        progression::one_rep_max(*reps, total_weight, *rpe)
+                    }) {
+                        best.one_rep_max = Some(best.one_rep_max.map_or(value, |v| v.max(value)));
+                    }
+                }
+                (None, Some(time)) => update(&mut best.time, *weight, *time),
+                (None, None) => {}
+            }
+        }
+    }
+    result
+}
+
+/// Comparison of the best sets of the left and the right side of a unilateral exercise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SideAsymmetry {
+    pub metric: AsymmetryMetric,
+    pub left: f32,
+    pub right: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsymmetryMetric {
+    /// Estimated one-repetition maximum based on the total moved weight.
+    OneRepMax,
+    /// Number of repetitions, used if no weights were recorded.
+    Reps,
+}
+
+impl SideAsymmetry {
+    /// Difference between the sides relative to the stronger side, between -1 and 1. The value is
+    /// positive if the left side is stronger.
+    #[must_use]
+    pub fn relative_difference(&self) -> f32 {
+        let stronger = self.left.max(self.right);
+        if stronger > 0.0 {
+            (self.left - self.right) / stronger
+        } else {
+            0.0
+        }
+    }
+
+    /// The weaker side, if the sides differ.
+    #[must_use]
+    pub fn weaker_side(&self) -> Option<Side> {
+        match self.left.total_cmp(&self.right) {
+            Ordering::Less => Some(Side::Left),
+            Ordering::Greater => Some(Side::Right),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+/// Compare the best sets of the left and the right side of an exercise.
 ///
-/// Multiple values for the same date will be averaged.
+/// The estimated one-repetition maximum is compared if weighted sets were recorded for both sides,
+/// otherwise the maximum number of repetitions. Sets without side are not considered. No result is
+/// returned unless sets were recorded for both sides.
+#[must_use]
+pub fn side_asymmetry(
+    training_sessions: &[&TrainingSession],
+    exercise: &Exercise,
+) -> Option<SideAsymmetry> {
+    // Best values for the left and the right side
+    let mut one_rep_max: [Option<f32>; 2] = [None, None];
+    let mut reps: [Option<f32>; 2] = [None, None];
+
+    for training_session in training_sessions {
+        for element in &training_session.elements {
+            let TrainingSessionElement::Set {
+                exercise_id,
+                reps: Some(set_reps),
+                weight,
+                rpe,
+                side: Some(side),
+                ..
+            } = element
+            else {
+                continue;
+            };
+            if *exercise_id != exercise.id || *set_reps == 0 {
+                continue;
+            }
+            let idx = match side {
+                Side::Left => 0,
+                Side::Right => 1,
+            };
+            #[allow(clippy::cast_precision_loss)]
+            let set_reps_value = *set_reps as f32;
+            reps[idx] = Some(reps[idx].map_or(set_reps_value, |r| r.max(set_reps_value)));
+            if let Some(value) = weight.and_then(|weight| {
+                progression::one_rep_max(*set_reps, exercise.total_weight(weight), *rpe)
+            }) {
+                one_rep_max[idx] = Some(one_rep_max[idx].map_or(value, |v| v.max(value)));
+            }
+        }
+    }
+
+    if let [Some(left), Some(right)] = one_rep_max {
+        return Some(SideAsymmetry {
+            metric: AsymmetryMetric::OneRepMax,
+            left,
+            right,
+        });
+    }
+    if let [Some(left), Some(right)] = reps {
+        return Some(SideAsymmetry {
+            metric: AsymmetryMetric::Reps,
+            left,
+            right,
+        });
+    }
+    None
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutineAdherence {
+    pub exercises: Vec<ExerciseAdherence>,
+    /// Number of training sessions of the routine without any performed set.
+    pub skipped_sessions: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseAdherence {
+    pub exercise_id: u32,
+    pub planned_sets: u32,
+    pub completed_sets: u32,
+    /// Average difference between performed and target reps.
+    pub reps_deviation: Option<f32>,
+    /// Average difference between performed and target weight.
+    pub weight_deviation: Option<f32>,
+}
+
+impl ExerciseAdherence {
+    #[must_use]
+    pub fn completion(&self) -> Option<f32> {
+        if self.planned_sets == 0 {
+            return None;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        Some(self.completed_sets as f32 / self.planned_sets as f32 * 100.0)
+    }
+}
+
+/// Compare the sets planned in a routine with the sets performed in its training sessions.
 ///
-/// An empty result vector may be returned if there is no data within the interval.
-/// Multiple result vectors may be returned in cases where there are gaps of more than
-/// 2*radius+1 days in the input data within the interval.
+/// Exercises are matched by ID. Sets without target are not considered for the deviations.
+/// Exercises which were performed but not planned are included with zero planned sets.
 #[must_use]
-pub fn centered_moving_average(
-    data: &Vec<(NaiveDate, f32)>,
+pub fn routine_adherence(
+    routine: &Routine,
+    training_sessions: &[&TrainingSession],
     interval: &Interval,
-    radius: u64,
-) -> Vec<Vec<(NaiveDate, f32)>> {
-    #[allow(clippy::cast_precision_loss)]
-    centered_moving_grouping(
-        data,
-        interval,
-        radius,
-        |d| Some(d.iter().sum::<f32>() / d.len() as f32),
-        |d| {
-            if d.is_empty() {
-                None
-            } else {
-                Some(d.iter().sum::<f32>() / d.len() as f32)
+) -> RoutineAdherence {
+    let training_sessions = training_sessions
+        .iter()
+        .filter(|t| {
+            t.routine_id == Some(routine.id) && t.date >= interval.first && t.date <= interval.last
+        })
+        .collect::<Vec<_>>();
+    #[allow(clippy::cast_possible_truncation)]
+    let num_sessions = training_sessions.len() as u32;
+
+    let mut completed_sets: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut reps_deviations: BTreeMap<u32, Vec<f32>> = BTreeMap::new();
+    let mut weight_deviations: BTreeMap<u32, Vec<f32>> = BTreeMap::new();
+    let mut skipped_sessions = 0;
+
+    for training_session in &training_sessions {
+        let mut performed = false;
+        for element in &training_session.elements {
+            let TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time,
+                weight,
+                target_reps,
+                target_weight,
+                ..
+            } = element
+            else {
+                continue;
+            };
+            if reps.is_none() && time.is_none() {
+                continue;
             }
-        },
-    )
+            performed = true;
+            *completed_sets.entry(*exercise_id).or_insert(0) += 1;
+            if let (Some(reps), Some(target_reps)) = (reps, target_reps) {
+                #[allow(clippy::cast_precision_loss)]
+                reps_deviations
+                    .entry(*exercise_id)
+                    .or_default()
+                    .push(target_reps.deviation(*reps) as f32);
+            }
+            if let (Some(weight), Some(target_weight)) = (weight, target_weight) {
+                weight_deviations
+                    .entry(*exercise_id)
+                    .or_default()
+                    .push(weight - target_weight);
+            }
+        }
+        if !performed {
+            skipped_sessions += 1;
+        }
+    }
+
+    let planned_sets = routine.sets_per_exercise();
+    let mut exercise_ids = planned_sets.keys().copied().collect::<Vec<_>>();
+    exercise_ids.extend(
+        completed_sets
+            .keys()
+            .filter(|id| !planned_sets.contains_key(id)),
+    );
+
+    #[allow(clippy::cast_precision_loss)]
+    let mean = |deviations: &Vec<f32>| deviations.iter().sum::<f32>() / deviations.len() as f32;
+
+    RoutineAdherence {
+        exercises: exercise_ids
+            .into_iter()
+            .map(|exercise_id| ExerciseAdherence {
+                exercise_id,
+                planned_sets: planned_sets.get(&exercise_id).copied().unwrap_or(0) * num_sessions,
+                completed_sets: completed_sets.get(&exercise_id).copied().unwrap_or(0),
+                reps_deviation: reps_deviations.get(&exercise_id).map(mean),
+                weight_deviation: weight_deviations.get(&exercise_id).map(mean),
+            })
+            .collect(),
+        skipped_sessions,
+    }
 }
 
-/// Calculate a series of moving averages from a given series of (date, value) pairs.
-///
-/// The data argument must have only one value per day.
+/// Average duration of the training sessions for which a duration is known.
+#[must_use]
+pub fn avg_duration(training_sessions: &[&TrainingSession]) -> Option<Duration> {
+    let durations = training_sessions
+        .iter()
+        .filter_map(|t| t.duration())
+        .collect::<Vec<_>>();
+    if durations.is_empty() {
+        return None;
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    Some(durations.iter().sum::<Duration>() / durations.len() as i32)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedRoutine {
+    pub routine_id: u32,
+    pub due: Due,
+    pub last_training_session: Option<NaiveDate>,
+}
+
+/// Determine the non-archived routines which are overdue or planned for the given date or the
+/// following day.
 ///
-/// The radius argument determines the number of values to include into the calculated
-/// average before and after each value.
+/// The result is ordered by urgency. Routines with the same urgency keep their given order.
 #[must_use]
-pub fn value_based_centered_moving_average(
-    data: &[(NaiveDate, f32)],
-    radius: usize,
-) -> Vec<(NaiveDate, f32)> {
-    let window = 2 * radius + 1;
-    let length = data.len();
-    data.iter()
-        .enumerate()
-        .map(|(i, (date, _))| {
-            #[allow(clippy::cast_precision_loss)]
-            let avg = data[i.saturating_sub(window / 2)..=(i + window / 2).min(length - 1)]
-                .iter()
-                .map(|(_, value)| value)
-                .sum::<f32>()
-                / window
-                    .min(length - (i.saturating_sub(window / 2)))
-                    .min(i + window / 2 + 1) as f32;
-            (*date, avg)
+pub fn planned_routines(
+    routines: &[&Routine],
+    training_sessions: &[&TrainingSession],
+    date: NaiveDate,
+) -> Vec<PlannedRoutine> {
+    let mut last_training_sessions: BTreeMap<u32, NaiveDate> = BTreeMap::new();
+    for training_session in training_sessions {
+        if let Some(routine_id) = training_session.routine_id {
+            if training_session.date <= date {
+                let last = last_training_sessions
+                    .entry(routine_id)
+                    .or_insert(training_session.date);
+                *last = (*last).max(training_session.date);
+            }
+        }
+    }
+
+    let mut result = routines
+        .iter()
+        .filter(|r| !r.archived)
+        .filter_map(|r| {
+            let last_training_session = last_training_sessions.get(&r.id).copied();
+            r.due(date, last_training_session)
+                .map(|due| PlannedRoutine {
+                    routine_id: r.id,
+                    due,
+                    last_training_session,
+                })
         })
-        .collect()
+        .collect::<Vec<_>>();
+    result.sort_by_key(|r| r.due);
+    result
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeeklySummary {
+    pub sessions: u32,
+    pub hard_sets: u32,
+    pub volume_load: u32,
+    pub tut: Option<u32>,
+    /// Hard sets of the three most trained muscles in descending order.
+    pub hard_sets_per_muscle: Vec<(u8, f32)>,
+}
+
+/// Summarize the training sessions of an ISO week.
+///
+/// Training sessions after `today` are ignored. `None` is returned if there is no training session
+/// in the week.
+#[must_use]
+pub fn weekly_summary(
+    training_sessions: &[&TrainingSession],
+    exercises: &BTreeMap<u32, Exercise>,
+    week: IsoWeek,
+    today: NaiveDate,
+) -> Option<WeeklySummary> {
+    let training_sessions = training_sessions
+        .iter()
+        .filter(|t| t.date.iso_week() == week && t.date <= today)
+        .collect::<Vec<_>>();
+
+    if training_sessions.is_empty() {
+        return None;
+    }
+
+    let mut stimulus_per_muscle: BTreeMap<u8, u32> = BTreeMap::new();
+    for training_session in &training_sessions {
+        for (muscle_id, stimulus) in training_session.stimulus_per_muscle(exercises) {
+            *stimulus_per_muscle.entry(muscle_id).or_insert(0) += stimulus;
+        }
+    }
+    let mut stimulus_per_muscle = stimulus_per_muscle
+        .into_iter()
+        .filter(|(_, stimulus)| *stimulus > 0)
+        .collect::<Vec<_>>();
+    stimulus_per_muscle.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let tut = training_sessions
+        .iter()
+        .filter_map(|t| t.tut())
+        .reduce(|a, b| a + b);
+
+    Some(WeeklySummary {
+        sessions: u32::try_from(training_sessions.len()).unwrap_or(u32::MAX),
+        hard_sets: training_sessions.iter().map(|t| t.set_volume()).sum(),
+        volume_load: training_sessions
+            .iter()
+            .map(|t| t.volume_load(exercises))
+            .sum(),
+        tut,
+        #[allow(clippy::cast_precision_loss)]
+        hard_sets_per_muscle: stimulus_per_muscle
+            .into_iter()
+            .take(3)
+            .map(|(muscle_id, stimulus)| (muscle_id, stimulus as f32 / 100.0))
+            .collect(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrainingStreak {
+    /// Number of consecutive weeks up to the current week in which the target was met.
+    pub current: u32,
+    pub longest: u32,
 }
 
-#[cfg(test)]
-mod tests {
-    use pretty_assertions::assert_eq;
-    use rstest::rstest;
-    use serde_json::json;
+/// Determine the number of consecutive ISO weeks in which at least `weekly_target` training
+/// sessions were performed.
+///
+/// The current week is only counted if the target is already met. An unfinished current week
+/// does not interrupt the streak. Dates after `today` are ignored.
+#[must_use]
+pub fn training_streak(
+    dates: &[NaiveDate],
+    weekly_target: u32,
+    today: NaiveDate,
+) -> TrainingStreak {
+    let mut sessions_per_week: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for date in dates.iter().filter(|d| **d <= today) {
+        *sessions_per_week
+            .entry(date.week(Weekday::Mon).first_day())
+            .or_insert(0) += 1;
+    }
+
+    let mut streak = TrainingStreak::default();
+    let Some(mut week) = sessions_per_week.keys().next().copied() else {
+        return streak;
+    };
+    let current_week = today.week(Weekday::Mon).first_day();
+
+    while week <= current_week {
+        if sessions_per_week.get(&week).copied().unwrap_or(0) >= weekly_target {
+            streak.current += 1;
+            streak.longest = streak.longest.max(streak.current);
+        } else if week < current_week {
+            streak.current = 0;
+        }
+        week += Duration::weeks(1);
+    }
+
+    streak
+}
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum Muscle {
+    // Neck
+    Neck = 1,
+    // Chest
+    Pecs = 11,
+    // Back
+    Traps = 21,
+    Lats = 22,
+    // Shoulders
+    FrontDelts = 31,
+    SideDelts = 32,
+    RearDelts = 33,
+    // Upper arms
+    Biceps = 41,
+    Triceps = 42,
+    // Forearms
+    Forearms = 51,
+    // Waist
+    Abs = 61,
+    ErectorSpinae = 62,
+    // Hips
+    Glutes = 71,
+    Abductors = 72,
+    // Thighs
+    Quads = 81,
+    Hamstrings = 82,
+    Adductors = 83,
+    // Calves
+    Calves = 91,
+}
+
+impl Muscle {
+    pub fn iter() -> Iter<'static, Muscle> {
+        static MUSCLES: [Muscle; 18] = [
+            Muscle::Neck,
+            Muscle::Pecs,
+            Muscle::Traps,
+            Muscle::Lats,
+            Muscle::FrontDelts,
+            Muscle::SideDelts,
+            Muscle::RearDelts,
+            Muscle::Biceps,
+            Muscle::Triceps,
+            Muscle::Forearms,
+            Muscle::Abs,
+            Muscle::ErectorSpinae,
+            Muscle::Glutes,
+            Muscle::Abductors,
+            Muscle::Quads,
+            Muscle::Hamstrings,
+            Muscle::Adductors,
+            Muscle::Calves,
+        ];
+        MUSCLES.iter()
+    }
+
+    #[must_use]
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    #[must_use]
+    pub fn from_repr(repr: u8) -> Option<Muscle> {
+        match repr {
+            x if x == Muscle::Neck as u8 => Some(Muscle::Neck),
+            x if x == Muscle::Pecs as u8 => Some(Muscle::Pecs),
+            x if x == Muscle::Traps as u8 => Some(Muscle::Traps),
+            x if x == Muscle::Lats as u8 => Some(Muscle::Lats),
+            x if x == Muscle::FrontDelts as u8 => Some(Muscle::FrontDelts),
+            x if x == Muscle::SideDelts as u8 => Some(Muscle::SideDelts),
+            x if x == Muscle::RearDelts as u8 => Some(Muscle::RearDelts),
+            x if x == Muscle::Biceps as u8 => Some(Muscle::Biceps),
+            x if x == Muscle::Triceps as u8 => Some(Muscle::Triceps),
+            x if x == Muscle::Forearms as u8 => Some(Muscle::Forearms),
+            x if x == Muscle::Abs as u8 => Some(Muscle::Abs),
+            x if x == Muscle::ErectorSpinae as u8 => Some(Muscle::ErectorSpinae),
+            x if x == Muscle::Glutes as u8 => Some(Muscle::Glutes),
+            x if x == Muscle::Abductors as u8 => Some(Muscle::Abductors),
+            x if x == Muscle::Quads as u8 => Some(Muscle::Quads),
+            x if x == Muscle::Hamstrings as u8 => Some(Muscle::Hamstrings),
+            x if x == Muscle::Adductors as u8 => Some(Muscle::Adductors),
+            x if x == Muscle::Calves as u8 => Some(Muscle::Calves),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Muscle::Neck => "Neck",
+            Muscle::Pecs => "Pecs",
+            Muscle::Traps => "Traps",
+            Muscle::Lats => "Lats",
+            Muscle::FrontDelts => "Front Delts",
+            Muscle::SideDelts => "Side Delts",
+            Muscle::RearDelts => "Rear Delts",
+            Muscle::Biceps => "Biceps",
+            Muscle::Triceps => "Triceps",
+            Muscle::Forearms => "Forearms",
+            Muscle::Abs => "Abs",
+            Muscle::ErectorSpinae => "Erector Spinae",
+            Muscle::Glutes => "Glutes",
+            Muscle::Abductors => "Abductors",
+            Muscle::Quads => "Quads",
+            Muscle::Hamstrings => "Hamstrings",
+            Muscle::Adductors => "Adductors",
+            Muscle::Calves => "Calves",
+        }
+    }
+
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        #[allow(clippy::match_same_arms)]
+        match self {
+            Muscle::Neck => "",
+            Muscle::Pecs => "Chest",
+            Muscle::Traps => "Upper back",
+            Muscle::Lats => "Sides of back",
+            Muscle::FrontDelts => "Anterior shoulders",
+            Muscle::SideDelts => "Mid shoulders",
+            Muscle::RearDelts => "Posterior shoulders",
+            Muscle::Biceps => "Front of upper arms",
+            Muscle::Triceps => "Back of upper arms",
+            Muscle::Forearms => "",
+            Muscle::Abs => "Belly",
+            Muscle::ErectorSpinae => "Lower back and spine",
+            Muscle::Glutes => "Buttocks",
+            Muscle::Abductors => "Outside of hips",
+            Muscle::Quads => "Front of thighs",
+            Muscle::Hamstrings => "Back of thighs",
+            Muscle::Adductors => "Inner thighs",
+            Muscle::Calves => "Back of lower legs",
+        }
+    }
+}
+
+#[derive(Default, PartialEq)]
+pub struct ExerciseFilter {
+    pub muscles: HashSet<Muscle>,
+    /// Match archived exercises instead of exercises that are not archived.
+    pub archived: bool,
+}
+
+impl ExerciseFilter {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.muscles.is_empty()
+    }
+
+    /// Check if the exercise has the archive state of the filter and trains all muscles of the
+    /// filter.
+    #[must_use]
+    pub fn matches(&self, exercise: &Exercise) -> bool {
+        if exercise.archived != self.archived {
+            return false;
+        }
+        let muscle_stimulus = exercise.muscle_stimulus();
+        self.muscles
+            .iter()
+            .all(|m| muscle_stimulus.contains_key(&m.id()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchHit {
+    TrainingSession(u32, NaiveDate, Snippet),
+    Routine(u32, Snippet),
+    Exercise(u32, Snippet),
+}
+
+/// Excerpt of a matching text, split into the matched substring and its context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub prefix: String,
+    pub highlight: String,
+    pub suffix: String,
+}
+
+const SNIPPET_CONTEXT: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchSource {
+    TrainingSession(u32, NaiveDate),
+    Routine(u32),
+    Exercise(u32),
+}
+
+#[derive(Debug)]
+struct SearchEntry {
+    source: SearchSource,
+    text: Vec<char>,
+    folded: Vec<char>,
+}
+
+/// Case-folded copy of all searchable texts.
+///
+/// The index should be built once when the underlying data changes, so that a search does not
+/// need to prepare the texts again.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    entries: Vec<SearchEntry>,
+}
+
+impl SearchIndex {
+    #[must_use]
+    pub fn new<'a>(
+        training_sessions: impl IntoIterator<Item = &'a TrainingSession>,
+        routines: impl IntoIterator<Item = &'a Routine>,
+        exercises: impl IntoIterator<Item = &'a Exercise>,
+    ) -> Self {
+        let mut training_sessions = training_sessions.into_iter().collect::<Vec<_>>();
+        training_sessions.sort_by_key(|t| std::cmp::Reverse((t.date, t.id)));
+        let training_sessions = training_sessions.into_iter().filter_map(|t| {
+            t.notes
+                .as_ref()
+                .map(|n| (SearchSource::TrainingSession(t.id, t.date), n.as_str()))
+        });
+        let routines = routines.into_iter().filter_map(|r| {
+            r.notes
+                .as_ref()
+                .map(|n| (SearchSource::Routine(r.id), n.as_str()))
+        });
+        let exercises = exercises
+            .into_iter()
+            .map(|e| (SearchSource::Exercise(e.id), e.name.as_str()));
+        Self {
+            entries: training_sessions
+                .chain(routines)
+                .chain(exercises)
+                .filter(|(_, text)| !text.trim().is_empty())
+                .map(|(source, text)| {
+                    let text = text.chars().collect::<Vec<_>>();
+                    let folded = text.iter().map(|c| fold(*c)).collect();
+                    SearchEntry {
+                        source,
+                        text,
+                        folded,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Find all entries containing every whitespace-separated term of the query.
+    ///
+    /// Training sessions are returned first (most recent first), followed by routines and
+    /// exercises. The snippet of each hit highlights the first occurrence of any term.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let terms = query
+            .split_whitespace()
+            .map(|t| t.chars().map(fold).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        if terms.is_empty() {
+            return vec![];
+        }
+
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                let (position, len) = terms
+                    .iter()
+                    .map(|term| find(&entry.folded, term).map(|p| (p, term.len())))
+                    .collect::<Option<Vec<_>>>()?
+                    .into_iter()
+                    .min()?;
+                let snippet = snippet(&entry.text, position, len);
+                Some(match entry.source {
+                    SearchSource::TrainingSession(id, date) => {
+                        SearchHit::TrainingSession(id, date, snippet)
+                    }
+                    SearchSource::Routine(id) => SearchHit::Routine(id, snippet),
+                    SearchSource::Exercise(id) => SearchHit::Exercise(id, snippet),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Map a character to its lowercase form while keeping a one-to-one correspondence between the
+/// characters of the original and the folded text.
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn find(haystack: &[char], needle: &[char]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn snippet(text: &[char], position: usize, len: usize) -> Snippet {
+    let start = position.saturating_sub(SNIPPET_CONTEXT);
+    let end = (position + len + SNIPPET_CONTEXT).min(text.len());
+    let context = |chars: &[char]| {
+        chars
+            .iter()
+            .map(|c| if c.is_whitespace() { ' ' } else { *c })
+            .collect::<String>()
+    };
+    Snippet {
+        prefix: format!(
+            "{}{}",
+            if start > 0 { "…" } else { "" },
+            context(&text[start..position]).trim_start()
+        ),
+        highlight: text[position..position + len].iter().collect(),
+        suffix: format!(
+            "{}{}",
+            context(&text[position + len..end]).trim_end(),
+            if end < text.len() { "…" } else { "" }
+        ),
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct BodyWeight {
+    pub date: NaiveDate,
+    pub weight: f32,
+}
+
+/// Number of values before and after each value included in the average body weight.
+pub const AVG_BODY_WEIGHT_RADIUS: usize = 4;
+
+#[must_use]
+pub fn avg_body_weight(
+    body_weight: &BTreeMap<NaiveDate, BodyWeight>,
+) -> BTreeMap<NaiveDate, BodyWeight> {
+    let data = body_weight
+        .values()
+        .map(|bw| (bw.date, bw.weight))
+        .collect::<Vec<_>>();
+    value_based_centered_moving_average(&data, AVG_BODY_WEIGHT_RADIUS)
+        .into_iter()
+        .map(|(date, weight)| (date, BodyWeight { date, weight }))
+        .collect()
+}
+
+/// Body mass index for a body weight in kg and a body height in cm.
+///
+/// `None` is returned if any of the values is not a positive finite number.
+#[must_use]
+pub fn bmi(weight: f32, height: f32) -> Option<f32> {
+    if !weight.is_finite() || !height.is_finite() || weight <= 0. || height <= 0. {
+        return None;
+    }
+    let height = height / 100.;
+    Some(weight / (height * height))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeeklyChange {
+    /// Change per week in kg.
+    pub weight: f32,
+    /// Change per week in percent.
+    pub percent: f32,
+    /// Number of days on which the change is based.
+    pub days: i64,
+}
+
+/// Average weekly change of the average body weight up to the given value.
+///
+/// The change is based on the preceding week. Gaps of more than `max_gap_days` days between two
+/// values split the data into segments, and only the segment containing the given value is
+/// considered. The change may therefore be based on less than a week. `None` is returned if the
+/// segment contains no earlier value.
+#[must_use]
+pub fn avg_weekly_change(
+    avg_body_weight: &BTreeMap<NaiveDate, BodyWeight>,
+    current: Option<&BodyWeight>,
+    max_gap_days: i64,
+) -> Option<WeeklyChange> {
+    let current = current?;
+    let prev_date = (current.date - Duration::days(7)).max(segment_start(
+        avg_body_weight,
+        current.date,
+        max_gap_days,
+    ));
+    if prev_date >= current.date {
+        return None;
+    }
+    let prev_avg_bw = if let Some(avg_bw) = avg_body_weight.get(&prev_date) {
+        avg_bw.clone()
+    } else {
+        let (a, b) = neighbors(avg_body_weight, prev_date)?;
+        interpolate_avg_body_weight(a, b, prev_date)
+    };
+    let days = (current.date - prev_date).num_days();
+    let change = current.weight - prev_avg_bw.weight;
+    #[allow(clippy::cast_precision_loss)]
+    let factor = 7. / days as f32;
+    Some(WeeklyChange {
+        weight: change * factor,
+        percent: change / prev_avg_bw.weight * 100. * factor,
+        days,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendDirection {
+    Up,
+    Down,
+    Flat,
+}
+
+/// Change of the values of a time series over a number of days.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trend {
+    pub direction: TrendDirection,
+    /// Change in percent.
+    pub percent: f32,
+}
+
+impl Trend {
+    /// Changes of at most this percentage are considered as flat.
+    pub const FLAT_PERCENT: f32 = 1.0;
+}
+
+/// Determine the change of the latest value of a series compared to the value at the start of
+/// the window.
+///
+/// The series is expected to be sorted by date. The value at the start of the window is the
+/// latest value on or before the day `window_days` before the latest value, so gaps in the series
+/// are bridged. `None` is returned if the series does not reach back to the start of the window,
+/// if the value at the start of the window is more than `window_days` older than the start of the
+/// window, or if it is zero.
+#[must_use]
+pub fn trend(series: &[(NaiveDate, f32)], window_days: i64) -> Option<Trend> {
+    if window_days <= 0 {
+        return None;
+    }
+    let (last_date, last_value) = *series.last()?;
+    let start = last_date - Duration::days(window_days);
+    let (first_date, first_value) = *series.iter().rev().find(|(date, _)| *date <= start)?;
+    if (start - first_date).num_days() > window_days || first_value == 0.0 {
+        return None;
+    }
+    let percent = (last_value - first_value) / first_value * 100.;
+    Some(Trend {
+        direction: if percent > Trend::FLAT_PERCENT {
+            TrendDirection::Up
+        } else if percent < -Trend::FLAT_PERCENT {
+            TrendDirection::Down
+        } else {
+            TrendDirection::Flat
+        },
+        percent,
+    })
+}
+
+/// Return the date of the first value of the contiguous segment which ends at the given date.
+fn segment_start(
+    body_weight: &BTreeMap<NaiveDate, BodyWeight>,
+    date: NaiveDate,
+    max_gap_days: i64,
+) -> NaiveDate {
+    let mut start = date;
+    for prev_date in body_weight.range(..date).rev().map(|(date, _)| *date) {
+        if (start - prev_date).num_days() > max_gap_days {
+            break;
+        }
+        start = prev_date;
+    }
+    start
+}
+
+fn neighbors(
+    body_weight: &BTreeMap<NaiveDate, BodyWeight>,
+    date: NaiveDate,
+) -> Option<(&BodyWeight, &BodyWeight)> {
+    use std::ops::Bound::{Excluded, Unbounded};
+
+    let mut before = body_weight.range((Unbounded, Excluded(date)));
+    let mut after = body_weight.range((Excluded(date), Unbounded));
+
+    Some((
+        before.next_back().map(|(_, v)| v)?,
+        after.next().map(|(_, v)| v)?,
+    ))
+}
+
+fn interpolate_avg_body_weight(a: &BodyWeight, b: &BodyWeight, date: NaiveDate) -> BodyWeight {
+    #[allow(clippy::cast_precision_loss)]
+    BodyWeight {
+        date,
+        weight: a.weight
+            + (b.weight - a.weight)
+                * ((date - a.date).num_days() as f32 / (b.date - a.date).num_days() as f32),
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BodyWeightGoal {
+    pub weight: f32,
+    pub direction: BodyWeightGoalDirection,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyWeightGoalDirection {
+    Lose,
+    Gain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BodyWeightGoalProjection {
+    Reached,
+    /// Goal is expected to be reached at the given date if the weekly change in kg persists.
+    Expected {
+        weekly_change: f32,
+        date: NaiveDate,
+    },
+    /// Goal is not expected to be reached within `MAX_DAYS` at the current weekly change in kg.
+    Distant {
+        weekly_change: f32,
+    },
+}
+
+impl BodyWeightGoalProjection {
+    pub const MAX_DAYS: i64 = 730;
+}
+
+/// Project the date at which the body weight goal is reached by linear extrapolation of the
+/// weekly change of the latest average body weight.
+///
+/// The weekly change is determined by `avg_weekly_change`. `None` is returned if there is not
+/// enough data or if the trend is flat or points away from the goal.
+#[must_use]
+pub fn body_weight_goal_projection(
+    avg_body_weight: &BTreeMap<NaiveDate, BodyWeight>,
+    goal: &BodyWeightGoal,
+    max_gap_days: i64,
+) -> Option<BodyWeightGoalProjection> {
+    let (_, current) = avg_body_weight.last_key_value()?;
+    let remaining = goal.weight - current.weight;
+    let reached = match goal.direction {
+        BodyWeightGoalDirection::Lose => remaining >= 0.,
+        BodyWeightGoalDirection::Gain => remaining <= 0.,
+    };
+    if reached {
+        return Some(BodyWeightGoalProjection::Reached);
+    }
+
+    let weekly_change = avg_weekly_change(avg_body_weight, Some(current), max_gap_days)?.weight;
+    if weekly_change == 0. || weekly_change.signum() != remaining.signum() {
+        return None;
+    }
+
+    let days = f64::from(remaining / weekly_change) * 7.;
+    #[allow(clippy::cast_precision_loss)]
+    if days > BodyWeightGoalProjection::MAX_DAYS as f64 {
+        return Some(BodyWeightGoalProjection::Distant { weekly_change });
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    Some(BodyWeightGoalProjection::Expected {
+        weekly_change,
+        date: current.date + Duration::days(days.ceil() as i64),
+    })
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BodyFat {
+    pub date: NaiveDate,
+    pub chest: Option<u8>,
+    pub abdominal: Option<u8>,
+    pub thigh: Option<u8>,
+    pub tricep: Option<u8>,
+    pub subscapular: Option<u8>,
+    pub suprailiac: Option<u8>,
+    pub midaxillary: Option<u8>,
+}
+
+impl BodyFat {
+    /// Estimate the body fat percentage using the Jackson-Pollock three-site method for a person
+    /// of the given age.
+    #[must_use]
+    pub fn jp3(&self, sex: u8, age: u32) -> Option<f32> {
+        if sex == 0 {
+            Some(Self::jackson_pollock(
+                f32::from(self.tricep?) + f32::from(self.suprailiac?) + f32::from(self.thigh?),
+                age,
+                1.099_492_1,
+                0.000_992_9,
+                0.000_002_3,
+                0.000_139_2,
+            ))
+        } else if sex == 1 {
+            Some(Self::jackson_pollock(
+                f32::from(self.chest?) + f32::from(self.abdominal?) + f32::from(self.thigh?),
+                age,
+                1.109_38,
+                0.000_826_7,
+                0.000_001_6,
+                0.000_257_4,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Estimate the body fat percentage using the Jackson-Pollock seven-site method for a person
+    /// of the given age.
+    #[must_use]
+    pub fn jp7(&self, sex: u8, age: u32) -> Option<f32> {
+        if sex == 0 {
+            Some(Self::jackson_pollock(
+                f32::from(self.chest?)
+                    + f32::from(self.abdominal?)
+                    + f32::from(self.thigh?)
+                    + f32::from(self.tricep?)
+                    + f32::from(self.subscapular?)
+                    + f32::from(self.suprailiac?)
+                    + f32::from(self.midaxillary?),
+                age,
+                1.097,
+                0.000_469_71,
+                0.000_000_56,
+                0.000_128_28,
+            ))
+        } else if sex == 1 {
+            Some(Self::jackson_pollock(
+                f32::from(self.chest?)
+                    + f32::from(self.abdominal?)
+                    + f32::from(self.thigh?)
+                    + f32::from(self.tricep?)
+                    + f32::from(self.subscapular?)
+                    + f32::from(self.suprailiac?)
+                    + f32::from(self.midaxillary?),
+                age,
+                1.112,
+                0.000_434_99,
+                0.000_000_55,
+                0.000_288_26,
+            ))
+        } else {
+            None
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn jackson_pollock(sum: f32, age: u32, k0: f32, k1: f32, k2: f32, ka: f32) -> f32 {
+        let age = age as f32;
+        (495. / (k0 - (k1 * sum) + (k2 * sum * sum) - (ka * age))) - 450.
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct BodyMeasurement {
+    pub date: NaiveDate,
+    pub waist: Option<f32>,
+    pub hips: Option<f32>,
+    pub neck: Option<f32>,
+    pub upper_arm: Option<f32>,
+    pub thigh: Option<f32>,
+    pub chest: Option<f32>,
+}
+
+impl BodyMeasurement {
+    /// Estimate the body fat percentage using the circumference-based U.S. Navy method.
+    ///
+    /// The height and all circumferences are expected in centimeters. The hip circumference is
+    /// only required for females.
+    #[must_use]
+    pub fn navy(&self, sex: u8, height: f32) -> Option<f32> {
+        navy(sex, height, self.waist?, self.neck?, self.hips)
+    }
+}
+
+#[must_use]
+pub fn navy(sex: u8, height: f32, waist: f32, neck: f32, hips: Option<f32>) -> Option<f32> {
+    if height <= 0. || waist <= 0. || neck <= 0. {
+        return None;
+    }
+    let density = if sex == 0 {
+        let circumference = waist + hips? - neck;
+        if circumference <= 0. {
+            return None;
+        }
+        1.295_79 - 0.350_04 * circumference.log10() + 0.221 * height.log10()
+    } else if sex == 1 {
+        let circumference = waist - neck;
+        if circumference <= 0. {
+            return None;
+        }
+        1.032_4 - 0.190_77 * circumference.log10() + 0.154_56 * height.log10()
+    } else {
+        return None;
+    };
+    Some(495. / density - 450.)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Period {
+    pub date: NaiveDate,
+    pub intensity: u8,
+}
+
+/// Return the completed cycles.
+///
+/// A cycle begins with the first day of a period and ends with the day before the next period.
+/// The cycle started by the last recorded period is still ongoing and therefore not included, as
+/// its length is not yet known (cf. `ongoing_cycle_begin`).
+#[must_use]
+pub fn cycles(period: &BTreeMap<NaiveDate, Period>) -> Vec<Cycle> {
+    if period.is_empty() {
+        return vec![];
+    }
+
+    let mut result = vec![];
+    let mut begin = period.keys().min().copied().unwrap_or_default();
+    let mut last = begin;
+
+    let period = period.values().collect::<Vec<_>>();
+
+    for p in &period[1..] {
+        if p.date - last > Duration::days(3) {
+            result.push(Cycle {
+                begin,
+                length: p.date - begin,
+            });
+            begin = p.date;
+        }
+        last = p.date;
+    }
+
+    result
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct Cycle {
+    pub begin: NaiveDate,
+    pub length: Duration,
+}
+
+/// Return the begin of the cycle started by the last recorded period.
+///
+/// Period entries with a gap of up to three days are considered to belong to the same period.
+#[must_use]
+pub fn ongoing_cycle_begin(period: &BTreeMap<NaiveDate, Period>) -> Option<NaiveDate> {
+    let mut dates = period.keys().rev();
+    let mut begin = *dates.next()?;
+
+    for date in dates {
+        if begin - *date > Duration::days(3) {
+            break;
+        }
+        begin = *date;
+    }
+
+    Some(begin)
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct CurrentCycle {
+    pub begin: NaiveDate,
+    /// Expected number of days until the next period, if completed cycles of the last half year
+    /// are available.
+    pub time_left: Option<Duration>,
+    pub time_left_variation: Duration,
+}
+
+#[must_use]
+pub fn current_cycle(
+    period: &BTreeMap<NaiveDate, Period>,
+    cycles: &[Cycle],
+    clock: &impl Clock,
+) -> Option<CurrentCycle> {
+    let begin = ongoing_cycle_begin(period)?;
+    let today = clock.today();
+    let cycles = cycles
+        .iter()
+        .filter(|c| (c.begin >= today - Duration::days(182) && c.begin <= today))
+        .collect::<Vec<_>>();
+    let stats = cycle_stats(&cycles);
+
+    Some(CurrentCycle {
+        begin,
+        time_left: if cycles.is_empty() {
+            None
+        } else {
+            Some(stats.length_median - (today - begin + Duration::days(1)).max(Duration::zero()))
+        },
+        time_left_variation: stats.length_variation,
+    })
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct CycleStats {
+    pub length_median: Duration,
+    pub length_variation: Duration,
+}
+
+#[must_use]
+pub fn cycle_stats(cycles: &[&Cycle]) -> CycleStats {
+    let mut cycle_lengths = cycles.iter().map(|c| c.length).collect::<Vec<_>>();
+    cycle_lengths.sort();
+    CycleStats {
+        length_median: quartile(&cycle_lengths, Quartile::Q2),
+        length_variation: (quartile(&cycle_lengths, Quartile::Q3)
+            - quartile(&cycle_lengths, Quartile::Q1))
+            / 2,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Quartile {
+    Q1 = 1,
+    Q2 = 2,
+    Q3 = 3,
+}
+
+#[must_use]
+pub fn quartile(durations: &[Duration], quartile_num: Quartile) -> Duration {
+    if durations.is_empty() {
+        return Duration::days(0);
+    }
+    let idx = durations.len() / 2;
+    match quartile_num {
+        Quartile::Q1 => quartile(&durations[..idx], Quartile::Q2),
+        Quartile::Q2 => {
+            if durations.len() % 2 == 0 {
+                (durations[idx - 1] + durations[idx]) / 2
+            } else {
+                durations[idx]
+            }
+        }
+        Quartile::Q3 => {
+            if durations.len() % 2 == 0 {
+                quartile(&durations[idx..], Quartile::Q2)
+            } else {
+                quartile(&durations[idx + 1..], Quartile::Q2)
+            }
+        }
+    }
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct Interval {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl From<std::ops::RangeInclusive<NaiveDate>> for Interval {
+    fn from(value: std::ops::RangeInclusive<NaiveDate>) -> Self {
+        Interval {
+            first: *value.start(),
+            last: *value.end(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DefaultInterval {
+    All,
+    _1Y = 365,
+    _6M = 182,
+    _3M = 91,
+    _1M = 30,
+}
+
+/// Determine the initial interval of a chart.
+///
+/// The selected interval takes precedence over the default interval. If the dates end before the
+/// chosen interval, the interval is extended to the first date.
+/// The interval ends today, or at the last date if it is after today.
+#[must_use]
+pub fn init_interval(
+    dates: &[NaiveDate],
+    default_interval: DefaultInterval,
+    selected_interval: Option<DefaultInterval>,
+    clock: &impl Clock,
+) -> Interval {
+    let default_interval = selected_interval.unwrap_or(default_interval);
+    let today = clock.today();
+    let mut first = dates.iter().copied().min().unwrap_or(today);
+    let mut last = dates.iter().copied().max().unwrap_or(today);
+
+    if default_interval != DefaultInterval::All
+        && last >= today - Duration::days(default_interval as i64)
+    {
+        first = today - Duration::days(default_interval as i64);
+    };
+
+    last = last.max(today);
+
+    Interval { first, last }
+}
+
+/// Group a series of (date, value) pairs.
+///
+/// The `radius` parameter determines the number of days before and after the
+/// center value to include in the calculation.
+///
+/// Only values which have a date within `interval` are used as a center value
+/// for the calculation. Values outside the interval are included in the
+/// calculation if they fall within the radius of a center value.
+///
+/// Two user-provided functions determine how values are combined:
+///
+///  - `group_day` is called to combine values of the *same* day.
+///  - `group_range` is called to combine values of multiple days after all
+///     values for the same day have been combined by `group_day`.
+///
+/// Return `None` in those functions to indicate the absence of a value.
+///
+pub fn centered_moving_grouping(
+    data: &Vec<(NaiveDate, f32)>,
+    interval: &Interval,
+    radius: u64,
+    group_day: impl Fn(Vec<f32>) -> Option<f32>,
+    group_range: impl Fn(Vec<f32>) -> Option<f32>,
+) -> Vec<Vec<(NaiveDate, f32)>> {
+    let mut date_map: BTreeMap<&NaiveDate, Vec<f32>> = BTreeMap::new();
+
+    for (date, value) in data {
+        date_map.entry(date).or_default().push(*value);
+    }
+
+    let mut grouped: BTreeMap<&NaiveDate, f32> = BTreeMap::new();
+
+    for (date, values) in date_map {
+        if let Some(result) = group_day(values) {
+            grouped.insert(date, result);
+        }
+    }
+
+    interval
+        .first
+        .iter_days()
+        .take_while(|d| *d <= interval.last)
+        .fold(
+            vec![vec![]],
+            |mut result: Vec<Vec<(NaiveDate, f32)>>, center| {
+                let value = group_range(
+                    center
+                        .checked_sub_days(Days::new(radius))
+                        .unwrap_or(center)
+                        .iter_days()
+                        .take_while(|d| {
+                            *d <= interval.last
+                                && *d
+                                    <= center.checked_add_days(Days::new(radius)).unwrap_or(center)
+                        })
+                        .filter_map(|d| grouped.get(&d))
+                        .copied()
+                        .collect::<Vec<_>>(),
+                );
+                if let Some(last) = result.last_mut() {
+                    match value {
+                        Some(v) => {
+                            last.push((center, v));
+                        }
+                        None => {
+                            if !last.is_empty() {
+                                result.push(vec![]);
+                            }
+                        }
+                    }
+                }
+                result
+            },
+        )
+        .into_iter()
+        .filter(|v| !v.is_empty())
+        .collect::<Vec<_>>()
+}
+
+/// Calculate a series of moving totals from a given series of (date, value) pairs.
+///
+/// The radius argument determines the number of days to include into the calculated
+/// total before and after each value within the interval.
+///
+/// Multiple values for the same date will be summed up.
+///
+/// An empty result vector may be returned if there is no data within the interval.
+#[must_use]
+pub fn centered_moving_total(
+    data: &Vec<(NaiveDate, f32)>,
+    interval: &Interval,
+    radius: u64,
+) -> Vec<(NaiveDate, f32)> {
+    centered_moving_grouping(
+        data,
+        interval,
+        radius,
+        |d| Some(d.iter().sum()),
+        |d| Some(d.iter().sum()),
+    )[0]
+    .clone()
+}
+
+/// Calculate a series of moving averages from a given series of (date, value) pairs.
+///
+/// The radius argument determines the number of days to include into the calculated
+/// average before and after each value within the interval.
+///
+/// Multiple values for the same date will be averaged.
+///
+/// An empty result vector may be returned if there is no data within the interval.
+/// Multiple result vectors may be returned in cases where there are gaps of more than
+/// 2*radius+1 days in the input data within the interval.
+#[must_use]
+pub fn centered_moving_average(
+    data: &Vec<(NaiveDate, f32)>,
+    interval: &Interval,
+    radius: u64,
+) -> Vec<Vec<(NaiveDate, f32)>> {
+    #[allow(clippy::cast_precision_loss)]
+    centered_moving_grouping(
+        data,
+        interval,
+        radius,
+        |d| Some(d.iter().sum::<f32>() / d.len() as f32),
+        |d| {
+            if d.is_empty() {
+                None
+            } else {
+                Some(d.iter().sum::<f32>() / d.len() as f32)
+            }
+        },
+    )
+}
+
+/// Calculate a series of moving averages from a given series of (date, value) pairs.
+///
+/// The data argument must have only one value per day.
+///
+/// The radius argument determines the number of values to include into the calculated
+/// average before and after each value.
+#[must_use]
+pub fn value_based_centered_moving_average(
+    data: &[(NaiveDate, f32)],
+    radius: usize,
+) -> Vec<(NaiveDate, f32)> {
+    let window = 2 * radius + 1;
+    let length = data.len();
+    data.iter()
+        .enumerate()
+        .map(|(i, (date, _))| {
+            #[allow(clippy::cast_precision_loss)]
+            let avg = data[i.saturating_sub(window / 2)..=(i + window / 2).min(length - 1)]
+                .iter()
+                .map(|(_, value)| value)
+                .sum::<f32>()
+                / window
+                    .min(length - (i.saturating_sub(window / 2)))
+                    .min(i + window / 2 + 1) as f32;
+            (*date, avg)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::*;
+
+    static TODAY: std::sync::LazyLock<NaiveDate> =
+        std::sync::LazyLock::new(|| chrono::Local::now().date_naive());
+
+    static EXERCISES: std::sync::LazyLock<BTreeMap<u32, Exercise>> =
+        std::sync::LazyLock::new(|| {
+            BTreeMap::from([(
+                1,
+                Exercise {
+                    id: 1,
+                    name: String::from("A"),
+                    weight_convention: None,
+                    base_weight: None,
+                    laterality: Laterality::Bilateral,
+                    archived: false,
+                    image: None,
+                    muscles: vec![
+                        ExerciseMuscle {
+                            muscle_id: 11,
+                            stimulus: 100,
+                        },
+                        ExerciseMuscle {
+                            muscle_id: 31,
+                            stimulus: 50,
+                        },
+                    ],
+                },
+            )])
+        });
+
+    static ROUTINE: std::sync::LazyLock<Routine> = std::sync::LazyLock::new(|| Routine {
+        id: 1,
+        name: String::from("A"),
+        notes: Some(String::from("B")),
+        archived: false,
+        sections: vec![
+            RoutinePart::RoutineSection {
+                rounds: 2,
+                parts: vec![
+                    RoutinePart::RoutineActivity {
+                        exercise_id: Some(1),
+                        reps: RepsRange::single(10),
+                        time: 2,
+                        weight: 30.0,
+                        rpe: RpeRange::single(10.0),
+                        automatic: false,
+                        target_percent_1rm: None,
+                    },
+                    RoutinePart::RoutineActivity {
+                        exercise_id: None,
+                        reps: RepsRange::default(),
+                        time: 60,
+                        weight: 0.0,
+                        rpe: RpeRange::default(),
+                        automatic: true,
+                        target_percent_1rm: None,
+                    },
+                ],
+            },
+            RoutinePart::RoutineSection {
+                rounds: 2,
+                parts: vec![
+                    RoutinePart::RoutineActivity {
+                        exercise_id: Some(2),
+                        reps: RepsRange::single(10),
+                        time: 0,
+                        weight: 0.0,
+                        rpe: RpeRange::default(),
+                        automatic: false,
+                        target_percent_1rm: None,
+                    },
+                    RoutinePart::RoutineActivity {
+                        exercise_id: None,
+                        reps: RepsRange::default(),
+                        time: 30,
+                        weight: 0.0,
+                        rpe: RpeRange::default(),
+                        automatic: true,
+                        target_percent_1rm: None,
+                    },
+                ],
+            },
+        ],
+        schedule: vec![Weekday::Mon, Weekday::Thu],
+        tags: vec![Tag {
+            name: String::from("Legs"),
+            color: TagColor::Green,
+        }],
+    });
+
+    static TRAINING_SESSION: std::sync::LazyLock<TrainingSession> =
+        std::sync::LazyLock::new(|| TrainingSession {
+            id: 1,
+            routine_id: Some(2),
+            date: *TODAY - Duration::days(10),
+            notes: Some(String::from("A")),
+            start_time: (*TODAY - Duration::days(10)).and_hms_opt(18, 0, 0),
+            end_time: (*TODAY - Duration::days(10)).and_hms_opt(18, 45, 30),
+            elements: vec![
+                TrainingSessionElement::Set {
+                    exercise_id: 1,
+                    reps: Some(10),
+                    time: Some(3),
+                    weight: Some(30.0),
+                    rpe: Some(8.0),
+                    target_reps: Some(RepsRange::single(8)),
+                    target_time: Some(4),
+                    target_weight: Some(40.0),
+                    target_rpe: Some(RpeRange::single(9.0)),
+                    automatic: false,
+                    partial_reps: None,
+                    to_failure: false,
+                    side: None,
+                    comment: Some(String::from("Felt pinch in left knee")),
+                },
+                TrainingSessionElement::Rest {
+                    target_time: Some(60),
+                    automatic: true,
+                },
+                TrainingSessionElement::Set {
+                    exercise_id: 2,
+                    reps: Some(5),
+                    time: Some(4),
+                    weight: None,
+                    rpe: Some(4.0),
+                    target_reps: None,
+                    target_time: None,
+                    target_weight: None,
+                    target_rpe: None,
+                    automatic: false,
+                    partial_reps: None,
+                    to_failure: false,
+                    side: None,
+                    comment: None,
+                },
+                TrainingSessionElement::Rest {
+                    target_time: Some(60),
+                    automatic: true,
+                },
+                TrainingSessionElement::Set {
+                    exercise_id: 2,
+                    reps: None,
+                    time: Some(60),
+                    weight: None,
+                    rpe: None,
+                    target_reps: None,
+                    target_time: None,
+                    target_weight: None,
+                    target_rpe: None,
+                    automatic: false,
+                    partial_reps: None,
+                    to_failure: false,
+                    side: None,
+                    comment: None,
+                },
+                TrainingSessionElement::Rest {
+                    target_time: Some(60),
+                    automatic: true,
+                },
+            ],
+        });
+
+    static EMPTY_TRAINING_SESSION: std::sync::LazyLock<TrainingSession> =
+        std::sync::LazyLock::new(|| {
+            let mut training_session = TRAINING_SESSION.clone();
+            training_session.elements = TRAINING_SESSION
+                .elements
+                .iter()
+                .map(|e| match e {
+                    TrainingSessionElement::Set {
+                        exercise_id,
+                        target_reps,
+                        target_time,
+                        target_weight,
+                        target_rpe,
+                        automatic,
+                        ..
+                    } => TrainingSessionElement::Set {
+                        exercise_id: *exercise_id,
+                        reps: None,
+                        time: None,
+                        weight: None,
+                        rpe: None,
+                        target_reps: *target_reps,
+                        target_time: *target_time,
+                        target_weight: *target_weight,
+                        target_rpe: *target_rpe,
+                        automatic: *automatic,
+                        partial_reps: None,
+                        to_failure: false,
+                        side: None,
+                        comment: None,
+                    },
+                    TrainingSessionElement::Rest { .. } => e.clone(),
+                })
+                .collect::<Vec<_>>();
+            training_session
+        });
+
+    #[test]
+    fn test_training_session_element_deserialize_target_rpe() {
+        let element = |target_rpe| {
+            serde_json::from_value::<TrainingSessionElement>(json!({
+                "exercise_id": 1,
+                "reps": null,
+                "time": null,
+                "weight": null,
+                "rpe": null,
+                "target_reps": 8,
+                "target_time": null,
+                "target_weight": null,
+                "target_rpe": target_rpe,
+                "automatic": false
+            }))
+            .unwrap()
+        };
+        let target_rpe = |element| match element {
+            TrainingSessionElement::Set { target_rpe, .. } => target_rpe,
+            TrainingSessionElement::Rest { .. } => panic!("unexpected rest"),
+        };
+        assert_eq!(target_rpe(element(json!(null))), None);
+        assert_eq!(target_rpe(element(json!(8.0))), Some(RpeRange::single(8.0)));
+        assert_eq!(
+            target_rpe(element(json!([7.0, 8.0]))),
+            Some(RpeRange::new(7.0, 8.0))
+        );
+    }
+
+    #[test]
+    fn test_routine_part_deserialize_rpe() {
+        let deserialized: RoutinePart = serde_json::from_value(json!({
+            "exercise_id": 1,
+            "reps": 8,
+            "time": 0,
+            "weight": 0.0,
+            "rpe": 8.0,
+            "automatic": false
+        }))
+        .unwrap();
+        assert_eq!(
+            deserialized,
+            RoutinePart::RoutineActivity {
+                exercise_id: Some(1),
+                reps: RepsRange::single(8),
+                time: 0,
+                weight: 0.0,
+                rpe: RpeRange::single(8.0),
+                automatic: false,
+                target_percent_1rm: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_routine_part_deserialize_reps_range() {
+        let deserialized: RoutinePart = serde_json::from_value(json!({
+            "exercise_id": 1,
+            "reps": [8, 12],
+            "time": 0,
+            "weight": 0.0,
+            "rpe": 0.0,
+            "automatic": false
+        }))
+        .unwrap();
+        assert_eq!(
+            deserialized,
+            RoutinePart::RoutineActivity {
+                exercise_id: Some(1),
+                reps: RepsRange::new(8, 12),
+                time: 0,
+                weight: 0.0,
+                rpe: RpeRange::default(),
+                automatic: false,
+                target_percent_1rm: None,
+            }
+        );
+        assert_eq!(deserialized.duration(), Duration::seconds(48));
+    }
+
+    #[test]
+    fn test_user_serde() {
+        let obj = User {
+            id: 1,
+            name: String::from("A"),
+            sex: 0,
+            height: Some(172.5),
+            birthdate: NaiveDate::from_ymd_opt(1990, 3, 15),
+            has_pin: true,
+        };
+        let serialized = json!({
+            "id": 1,
+            "name": "A",
+            "sex": 0,
+            "height": 172.5,
+            "birthdate": "1990-03-15",
+            "has_pin": true
+        });
+        let deserialized: User = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, obj);
+    }
+
+    #[rstest]
+    #[case::before_birthday((2020, 3, 14), 29)]
+    #[case::on_birthday((2020, 3, 15), 30)]
+    #[case::after_birthday((2020, 12, 31), 30)]
+    #[case::on_birthdate((1990, 3, 15), 0)]
+    #[case::before_birthdate((1980, 1, 1), 0)]
+    fn test_age(#[case] date: (i32, u32, u32), #[case] expected: u32) {
+        assert_eq!(
+            age(
+                NaiveDate::from_ymd_opt(1990, 3, 15).unwrap(),
+                NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap()
+            ),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::unknown_birthdate(None, DEFAULT_AGE)]
+    #[case::known_birthdate(NaiveDate::from_ymd_opt(2000, 1, 1), 20)]
+    fn test_user_age(#[case] birthdate: Option<NaiveDate>, #[case] expected: u32) {
+        let user = User {
+            id: 1,
+            name: String::from("A"),
+            sex: 0,
+            height: None,
+            birthdate,
+            has_pin: false,
+        };
+        assert_eq!(
+            user.age(NaiveDate::from_ymd_opt(2020, 2, 2).unwrap()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_exercise_serde() {
+        let obj = Exercise {
+            id: 1,
+            name: String::from("A"),
+            weight_convention: None,
+            base_weight: None,
+            laterality: Laterality::Bilateral,
+            archived: false,
+            image: None,
+            muscles: vec![ExerciseMuscle {
+                muscle_id: 2,
+                stimulus: 100,
+            }],
+        };
+        let serialized = json!(obj);
+        let deserialized: Exercise = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, obj);
+    }
+
+    #[rstest]
+    #[case::jpeg("image/jpeg", "AQID", Ok(3))]
+    #[case::gif_padding_1("image/gif", "AQI=", Ok(2))]
+    #[case::png_padding_2("image/png", "AQ==", Ok(1))]
+    #[case::unsupported_type("image/bmp", "AQID", Err(ExerciseImageError::UnsupportedType))]
+    #[case::empty("image/jpeg", "", Err(ExerciseImageError::InvalidData))]
+    #[case::invalid_length("image/jpeg", "AQI", Err(ExerciseImageError::InvalidData))]
+    #[case::invalid_character("image/jpeg", "AQ!D", Err(ExerciseImageError::InvalidData))]
+    #[case::invalid_padding("image/jpeg", "A===", Err(ExerciseImageError::InvalidData))]
+    fn test_exercise_image_new(
+        #[case] mime_type: &str,
+        #[case] data: &str,
+        #[case] expected: Result<usize, ExerciseImageError>,
+    ) {
+        assert_eq!(
+            ExerciseImage::new(mime_type, data.to_string()).map(|image| image.size()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_exercise_image_too_large() {
+        let max = "A".repeat(ExerciseImage::MAX_SIZE / 3 * 4) + "AAA=";
+        assert_eq!(
+            ExerciseImage::new("image/jpeg", max).map(|image| image.size()),
+            Ok(ExerciseImage::MAX_SIZE)
+        );
+        let too_large = "A".repeat(ExerciseImage::MAX_SIZE / 3 * 4) + "AAAA";
+        assert_eq!(
+            ExerciseImage::new("image/jpeg", too_large),
+            Err(ExerciseImageError::TooLarge)
+        );
+    }
+
+    #[rstest]
+    #[case::jpeg(
+        "data:image/jpeg;base64,AQID",
+        Ok(ExerciseImage { mime_type: String::from("image/jpeg"), data: String::from("AQID") })
+    )]
+    #[case::no_data_url("AQID", Err(ExerciseImageError::InvalidData))]
+    #[case::not_base64("data:image/jpeg,abc", Err(ExerciseImageError::InvalidData))]
+    #[case::unsupported_type(
+        "data:text/plain;base64,AQID",
+        Err(ExerciseImageError::UnsupportedType)
+    )]
+    fn test_exercise_image_from_data_url(
+        #[case] url: &str,
+        #[case] expected: Result<ExerciseImage, ExerciseImageError>,
+    ) {
+        assert_eq!(ExerciseImage::from_data_url(url), expected);
+    }
+
+    #[test]
+    fn test_exercise_image_data_url() {
+        let image = ExerciseImage::new("image/png", String::from("AQID")).unwrap();
+        assert_eq!(image.data_url(), "data:image/png;base64,AQID");
+        assert_eq!(ExerciseImage::from_data_url(&image.data_url()), Ok(image));
+    }
+
+    #[test]
+    fn test_exercise_image_error_display() {
+        assert_eq!(
+            ExerciseImageError::TooLarge.to_string(),
+            "Image must not be larger than 2 MB"
+        );
+    }
+
+    #[test]
+    fn test_exercise_muscle_stimulus() {
+        assert_eq!(
+            Exercise {
+                id: 1,
+                name: String::from("A"),
+                weight_convention: None,
+                base_weight: None,
+                laterality: Laterality::Bilateral,
+                archived: false,
+                image: None,
+                muscles: vec![
+                    ExerciseMuscle {
+                        muscle_id: 2,
+                        stimulus: 100,
+                    },
+                    ExerciseMuscle {
+                        muscle_id: 8,
+                        stimulus: 50,
+                    }
+                ],
+            }
+            .muscle_stimulus(),
+            BTreeMap::from([(2, 100), (8, 50)])
+        );
+    }
+
+    fn exercise(id: u32, muscle_stimulus: &[(u8, u8)]) -> Exercise {
+        Exercise {
+            id,
+            name: id.to_string(),
+            weight_convention: None,
+            base_weight: None,
+            laterality: Laterality::Bilateral,
+            archived: false,
+            image: None,
+            muscles: muscle_stimulus
+                .iter()
+                .map(|(muscle_id, stimulus)| ExerciseMuscle {
+                    muscle_id: *muscle_id,
+                    stimulus: *stimulus,
+                })
+                .collect(),
+        }
+    }
+
+    #[rstest]
+    #[case::unchanged(&[(11, 100)], &[(11, 100)], &[(11, 100), (31, 50)])]
+    #[case::added(&[], &[(21, 50)], &[(11, 100), (21, 50), (31, 50)])]
+    #[case::changed(&[(11, 100)], &[(11, 50)], &[(11, 50), (31, 50)])]
+    #[case::removed(&[(31, 50)], &[], &[(11, 100)])]
+    #[case::removed_missing(&[(21, 100)], &[], &[(11, 100), (31, 50)])]
+    fn test_exercise_with_changed_muscle_stimulus(
+        #[case] before: &[(u8, u8)],
+        #[case] after: &[(u8, u8)],
+        #[case] expected: &[(u8, u8)],
+    ) {
+        assert_eq!(
+            exercise(1, &[(11, 100), (31, 50)]).with_changed_muscle_stimulus(
+                &before.iter().copied().collect(),
+                &after.iter().copied().collect()
+            ),
+            exercise(1, expected)
+        );
+    }
+
+    #[rstest]
+    #[case::no_exercises(vec![], &[])]
+    #[case::single_exercise(vec![vec![(11, 100), (31, 50)]], &[(11, 100), (31, 50)])]
+    #[case::common_muscles(
+        vec![vec![(11, 100), (31, 50)], vec![(11, 100), (31, 100), (42, 50)]],
+        &[(11, 100)]
+    )]
+    #[case::no_common_muscles(vec![vec![(11, 100)], vec![(21, 100)]], &[])]
+    fn test_common_muscle_stimulus(
+        #[case] muscle_stimuli: Vec<Vec<(u8, u8)>>,
+        #[case] expected: &[(u8, u8)],
+    ) {
+        let exercises = muscle_stimuli
+            .iter()
+            .zip(1..)
+            .map(|(muscle_stimulus, id)| exercise(id, muscle_stimulus))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            common_muscle_stimulus(&exercises.iter().collect::<Vec<_>>()),
+            expected.iter().copied().collect()
+        );
+    }
+
+    #[test]
+    fn test_merge_exercises() {
+        let mut other_routine = ROUTINE.clone();
+        other_routine.id = 2;
+        other_routine.sections.remove(0);
+        let mut other_training_session = TRAINING_SESSION.clone();
+        other_training_session.id = 2;
+        other_training_session.elements.drain(..2);
+
+        let result = merge_exercises(
+            1,
+            3,
+            &[&ROUTINE, &other_routine],
+            &[&TRAINING_SESSION, &other_training_session],
+        );
+
+        assert_eq!(result.routines.len(), 1);
+        assert_eq!(result.routines[0].id, 1);
+        assert_eq!(result.routines[0].exercises(), BTreeSet::from([2, 3]));
+        assert_eq!(result.training_sessions.len(), 1);
+        assert_eq!(result.training_sessions[0].id, 1);
+        assert_eq!(
+            result.training_sessions[0].exercises(),
+            TRAINING_SESSION
+                .exercises()
+                .into_iter()
+                .map(|id| if id == 1 { 3 } else { id })
+                .collect()
+        );
+        assert_eq!(
+            merge_exercises(4, 3, &[&ROUTINE], &[&TRAINING_SESSION]),
+            ExerciseMerge {
+                routines: vec![],
+                training_sessions: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_exercise_usage() {
+        let mut other_routine = ROUTINE.clone();
+        other_routine.id = 2;
+        other_routine.sections.remove(0);
+        let mut other_training_session = TRAINING_SESSION.clone();
+        other_training_session.id = 2;
+        other_training_session.elements.drain(..2);
+
+        assert_eq!(
+            exercise_usage(
+                1,
+                &[&ROUTINE, &other_routine],
+                &[&TRAINING_SESSION, &other_training_session],
+            ),
+            ExerciseUsage {
+                routines: vec![1],
+                training_sessions: 1,
+            }
+        );
+        assert_eq!(
+            exercise_usage(
+                2,
+                &[&ROUTINE, &other_routine],
+                &[&TRAINING_SESSION, &other_training_session],
+            ),
+            ExerciseUsage {
+                routines: vec![1, 2],
+                training_sessions: 2,
+            }
+        );
+        assert!(exercise_usage(4, &[&ROUTINE], &[&TRAINING_SESSION]).is_empty());
+    }
+
+    #[test]
+    fn test_routine_serde() {
+        let obj = &*ROUTINE;
+        let serialized = json!(obj);
+        let deserialized: Routine = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, *obj);
+    }
+
+    #[test]
+    fn test_routine_duration() {
+        assert_eq!(ROUTINE.duration(), Duration::seconds(300));
+    }
+
+    #[test]
+    fn test_routine_num_sets() {
+        assert_eq!(ROUTINE.num_sets(), 4);
+    }
+
+    #[test]
+    fn test_routine_stimulus_per_muscle() {
+        assert_eq!(
+            ROUTINE.stimulus_per_muscle(&EXERCISES),
+            BTreeMap::from([
+                (1, 0),
+                (11, 200),
+                (21, 0),
+                (22, 0),
+                (31, 100),
+                (32, 0),
+                (33, 0),
+                (41, 0),
+                (42, 0),
+                (51, 0),
+                (61, 0),
+                (62, 0),
+                (71, 0),
+                (72, 0),
+                (81, 0),
+                (82, 0),
+                (83, 0),
+                (91, 0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_routine_exercises() {
+        assert_eq!(ROUTINE.exercises(), BTreeSet::from([1, 2]));
+    }
+
+    #[test]
+    fn test_routine_sets_per_exercise() {
+        assert_eq!(
+            ROUTINE.sets_per_exercise(),
+            BTreeMap::from([(1, 2), (2, 2)])
+        );
+    }
+
+    fn march(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[rstest]
+    #[case::not_planned(march(5), Some(march(4)), None)]
+    #[case::today(march(4), Some(march(1)), Some(Due::Today))]
+    #[case::today_never_trained(march(4), None, Some(Due::Today))]
+    #[case::trained_today(march(4), Some(march(4)), None)]
+    #[case::tomorrow(march(6), Some(march(4)), Some(Due::Tomorrow))]
+    #[case::tomorrow_never_trained(march(6), None, Some(Due::Tomorrow))]
+    #[case::overdue(march(5), Some(march(1)), Some(Due::Overdue))]
+    #[case::overdue_on_planned_day(march(7), Some(march(1)), Some(Due::Overdue))]
+    #[case::overdue_trained_today(march(5), Some(march(5)), None)]
+    #[case::not_overdue_never_trained(march(5), None, None)]
+    fn test_routine_due(
+        #[case] date: NaiveDate,
+        #[case] last_training_session: Option<NaiveDate>,
+        #[case] expected: Option<Due>,
+    ) {
+        assert_eq!(ROUTINE.due(date, last_training_session), expected);
+    }
+
+    #[test]
+    fn test_routine_due_without_schedule() {
+        let routine = Routine {
+            schedule: vec![],
+            ..ROUTINE.clone()
+        };
+
+        assert_eq!(routine.due(march(4), None), None);
+    }
+
+    #[test]
+    fn test_routine_deserialize_without_tags() {
+        let deserialized: Routine = serde_json::from_value(json!({
+            "id": 1,
+            "name": "A",
+            "notes": null,
+            "archived": false,
+            "sections": []
+        }))
+        .unwrap();
+        assert!(deserialized.tags.is_empty());
+    }
+
+    fn tag(name: &str, color: TagColor) -> Tag {
+        Tag {
+            name: name.to_string(),
+            color,
+        }
+    }
+
+    fn tagged_routine(id: u32, archived: bool, tags: Vec<Tag>) -> Routine {
+        Routine {
+            id,
+            archived,
+            tags,
+            ..ROUTINE.clone()
+        }
+    }
+
+    #[test]
+    fn test_routine_has_tag() {
+        assert!(ROUTINE.has_tag("Legs"));
+        assert!(!ROUTINE.has_tag("legs"));
+        assert!(!ROUTINE.has_tag("Push"));
+    }
+
+    #[test]
+    fn test_tags() {
+        let routines = [
+            tagged_routine(1, false, vec![tag("Push", TagColor::Red)]),
+            tagged_routine(
+                2,
+                true,
+                vec![tag("Legs", TagColor::Blue), tag("Push", TagColor::Green)],
+            ),
+            tagged_routine(3, false, vec![]),
+        ];
+
+        assert_eq!(
+            tags(&routines),
+            vec![tag("Legs", TagColor::Blue), tag("Push", TagColor::Red)]
+        );
+        assert!(tags(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_add_tag() {
+        let mut tags = vec![];
+
+        add_tag(&mut tags, tag("Push", TagColor::Red));
+        add_tag(&mut tags, tag("Legs", TagColor::Blue));
+        add_tag(&mut tags, tag("Upper", TagColor::Gray));
+        add_tag(&mut tags, tag("Push", TagColor::Green));
+
+        assert_eq!(
+            tags,
+            vec![
+                tag("Legs", TagColor::Blue),
+                tag("Push", TagColor::Green),
+                tag("Upper", TagColor::Gray)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_tag() {
+        let routines = [
+            tagged_routine(
+                1,
+                false,
+                vec![tag("Legs", TagColor::Blue), tag("Push", TagColor::Red)],
+            ),
+            tagged_routine(2, true, vec![tag("Push", TagColor::Red)]),
+            tagged_routine(3, false, vec![tag("Legs", TagColor::Blue)]),
+        ];
+
+        assert_eq!(
+            remove_tag(&routines, "Push"),
+            vec![
+                tagged_routine(1, false, vec![tag("Legs", TagColor::Blue)]),
+                tagged_routine(2, true, vec![]),
+            ]
+        );
+        assert_eq!(remove_tag(&routines, "Legs").len(), 2);
+        assert!(remove_tag(&routines, "Pull").is_empty());
+    }
+
+    #[test]
+    fn test_tag_color_iter() {
+        assert_eq!(
+            TagColor::iter().map(|c| c.name()).collect::<Vec<_>>(),
+            vec![
+                "Gray",
+                "Turquoise",
+                "Blue",
+                "Cyan",
+                "Green",
+                "Yellow",
+                "Red"
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(0, Err(SetValueError::Reps))]
+    #[case(1, Ok(1))]
+    #[case(999, Ok(999))]
+    #[case(1000, Err(SetValueError::Reps))]
+    fn test_validate_reps(#[case] reps: u32, #[case] expected: Result<u32, SetValueError>) {
+        assert_eq!(validate_reps(reps), expected);
+    }
+
+    #[rstest]
+    #[case(0, Err(SetValueError::Time))]
+    #[case(1, Ok(1))]
+    #[case(999, Ok(999))]
+    #[case(1000, Err(SetValueError::Time))]
+    fn test_validate_time(#[case] time: u32, #[case] expected: Result<u32, SetValueError>) {
+        assert_eq!(validate_time(time), expected);
+    }
+
+    #[rstest]
+    #[case(0.0, Err(SetValueError::Weight))]
+    #[case(0.1, Ok(0.1))]
+    #[case(102.5, Ok(102.5))]
+    #[case(102.25, Err(SetValueError::Weight))]
+    #[case(1000.0, Err(SetValueError::Weight))]
+    fn test_validate_weight(#[case] weight: f32, #[case] expected: Result<f32, SetValueError>) {
+        assert_eq!(validate_weight(weight), expected);
+    }
+
+    #[rstest]
+    #[case(-0.5, Err(SetValueError::RPE))]
+    #[case(0.0, Ok(0.0))]
+    #[case(7.5, Ok(7.5))]
+    #[case(7.3, Err(SetValueError::RPE))]
+    #[case(10.0, Ok(10.0))]
+    #[case(10.5, Err(SetValueError::RPE))]
+    fn test_validate_rpe(#[case] rpe: f32, #[case] expected: Result<f32, SetValueError>) {
+        assert_eq!(validate_rpe(rpe), expected);
+    }
+
+    #[rstest]
+    #[case("8", Ok(RpeRange::single(8.0)))]
+    #[case("7.5", Ok(RpeRange::single(7.5)))]
+    #[case("7-8", Ok(RpeRange::new(7.0, 8.0)))]
+    #[case("7–8", Ok(RpeRange::new(7.0, 8.0)))]
+    #[case(" 7 - 8.5 ", Ok(RpeRange::new(7.0, 8.5)))]
+    #[case("8-8", Ok(RpeRange::single(8.0)))]
+    #[case("8-7", Err(SetValueError::RPE))]
+    #[case("7-10.5", Err(SetValueError::RPE))]
+    #[case("7.3", Err(SetValueError::RPE))]
+    #[case("7-", Err(SetValueError::RPE))]
+    #[case("a", Err(SetValueError::RPE))]
+    fn test_rpe_range_from_str(
+        #[case] input: &str,
+        #[case] expected: Result<RpeRange, SetValueError>,
+    ) {
+        assert_eq!(input.parse::<RpeRange>(), expected);
+    }
+
+    #[rstest]
+    #[case(RpeRange::single(8.0), "8")]
+    #[case(RpeRange::single(7.5), "7.5")]
+    #[case(RpeRange::new(8.0, 7.0), "7–8")]
+    fn test_rpe_range_display(#[case] rpe: RpeRange, #[case] expected: &str) {
+        assert_eq!(rpe.to_string(), expected);
+    }
+
+    #[test]
+    fn test_rpe_range() {
+        let range = RpeRange::new(7.0, 8.0);
+        assert!(range.is_set());
+        assert!(!range.is_single());
+        assert_eq!(range.value(), None);
+        assert!(range.contains(7.0));
+        assert!(range.contains(7.5));
+        assert!(range.contains(8.0));
+        assert!(!range.contains(8.5));
+        assert_eq!(RpeRange::single(8.0).value(), Some(8.0));
+        assert!(!RpeRange::default().is_set());
+    }
+
+    #[rstest]
+    #[case(RpeRange::single(8.0), json!(8.0))]
+    #[case(RpeRange::new(7.0, 8.0), json!([7.0, 8.0]))]
+    fn test_rpe_range_serde(#[case] rpe: RpeRange, #[case] serialized: serde_json::Value) {
+        assert_eq!(json!(rpe), serialized);
+        assert_eq!(serde_json::from_value::<RpeRange>(serialized).unwrap(), rpe);
+    }
+
+    #[rstest]
+    #[case(8, None, Ok(RepsRange::single(8)))]
+    #[case(8, Some(12), Ok(RepsRange::new(8, 12)))]
+    #[case(8, Some(8), Ok(RepsRange::single(8)))]
+    #[case(12, Some(8), Err(SetValueError::RepsRange))]
+    #[case(0, Some(8), Err(SetValueError::Reps))]
+    #[case(8, Some(1000), Err(SetValueError::Reps))]
+    fn test_validate_reps_range(
+        #[case] min: u32,
+        #[case] max: Option<u32>,
+        #[case] expected: Result<RepsRange, SetValueError>,
+    ) {
+        assert_eq!(validate_reps_range(min, max), expected);
+    }
+
+    #[rstest]
+    #[case(RepsRange::single(8), "8")]
+    #[case(RepsRange::new(12, 8), "8–12")]
+    fn test_reps_range_display(#[case] reps: RepsRange, #[case] expected: &str) {
+        assert_eq!(reps.to_string(), expected);
+    }
+
+    #[test]
+    fn test_reps_range() {
+        let range = RepsRange::new(8, 12);
+        assert!(range.is_set());
+        assert!(!range.is_single());
+        assert_eq!(range.value(), None);
+        assert!(range.contains(8));
+        assert!(range.contains(12));
+        assert!(!range.contains(13));
+        assert_eq!(range.clamp(6), 8);
+        assert_eq!(range.clamp(10), 10);
+        assert_eq!(range.clamp(14), 12);
+        assert_eq!(range.deviation(6), -2);
+        assert_eq!(range.deviation(10), 0);
+        assert_eq!(range.deviation(14), 2);
+        assert_eq!(RepsRange::single(8).value(), Some(8));
+        assert!(!RepsRange::default().is_set());
+    }
+
+    #[rstest]
+    #[case(RepsRange::single(8), json!(8))]
+    #[case(RepsRange::new(8, 12), json!([8, 12]))]
+    fn test_reps_range_serde(#[case] reps: RepsRange, #[case] serialized: serde_json::Value) {
+        assert_eq!(json!(reps), serialized);
+        assert_eq!(
+            serde_json::from_value::<RepsRange>(serialized).unwrap(),
+            reps
+        );
+    }
+
+    #[test]
+    fn test_set_value_error_display() {
+        assert_eq!(
+            SetValueError::RPE.to_string(),
+            "RPE must be between 0 and 10 in steps of 0.5"
+        );
+    }
+
+    #[test]
+    fn test_routine_from_session() {
+        let training_session = TrainingSession {
+            elements: vec![
+                session_set(1, Some(5), Some(100.0)),
+                session_rest(120),
+                session_set(1, Some(5), Some(100.0)),
+                session_rest(120),
+                session_set(1, Some(5), Some(100.0)),
+                session_rest(120),
+                session_set(2, Some(10), Some(20.0)),
+                session_rest(60),
+            ],
+            ..TRAINING_SESSION.clone()
+        };
+
+        assert_eq!(
+            routine_from_session(&training_session, "A"),
+            Routine {
+                id: 0,
+                name: String::from("A"),
+                notes: None,
+                archived: false,
+                sections: vec![
+                    RoutinePart::RoutineSection {
+                        rounds: 3,
+                        parts: vec![routine_activity(1, 5, 100.0), routine_rest(120)],
+                    },
+                    RoutinePart::RoutineSection {
+                        rounds: 1,
+                        parts: vec![routine_activity(2, 10, 20.0)],
+                    },
+                ],
+                schedule: vec![],
+                tags: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_routine_from_session_unilateral() {
+        let training_session = TrainingSession {
+            elements: vec![
+                with_side(session_set(1, Some(5), Some(20.0)), Side::Left),
+                with_side(session_set(1, Some(5), Some(20.0)), Side::Right),
+                session_rest(60),
+                with_side(session_set(1, Some(5), Some(20.0)), Side::Left),
+                with_side(session_set(1, Some(5), Some(20.0)), Side::Right),
+                session_rest(60),
+            ],
+            ..TRAINING_SESSION.clone()
+        };
+
+        assert_eq!(
+            routine_from_session(&training_session, "A").sections,
+            vec![RoutinePart::RoutineSection {
+                rounds: 2,
+                parts: vec![routine_activity(1, 5, 20.0), routine_rest(60)],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_split_unilateral_sets() {
+        let exercises = BTreeMap::from([
+            (1, exercise(1, &[])),
+            (
+                2,
+                Exercise {
+                    laterality: Laterality::Unilateral,
+                    ..exercise(2, &[])
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            split_unilateral_sets(
+                vec![
+                    target_set(1, 5, 100.0),
+                    target_set(2, 8, 20.0),
+                    session_rest(60),
+                    with_side(target_set(2, 8, 20.0), Side::Right),
+                    target_set(3, 10, 10.0),
+                ],
+                &exercises
+            ),
+            vec![
+                target_set(1, 5, 100.0),
+                with_side(target_set(2, 8, 20.0), Side::Left),
+                with_side(target_set(2, 8, 20.0), Side::Right),
+                session_rest(60),
+                with_side(target_set(2, 8, 20.0), Side::Right),
+                target_set(3, 10, 10.0),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::pair(vec![Some(Side::Left), Some(Side::Right)], 1, true)]
+    #[case::left_side(vec![Some(Side::Left), Some(Side::Right)], 0, false)]
+    #[case::unpaired(vec![None, Some(Side::Right)], 1, false)]
+    #[case::reversed(vec![Some(Side::Right), Some(Side::Left)], 1, false)]
+    #[case::out_of_range(vec![Some(Side::Left)], 1, false)]
+    fn test_is_paired_right_side(
+        #[case] sides: Vec<Option<Side>>,
+        #[case] idx: usize,
+        #[case] expected: bool,
+    ) {
+        let elements = sides
+            .into_iter()
+            .map(|side| match side {
+                Some(side) => with_side(target_set(1, 5, 20.0), side),
+                None => target_set(1, 5, 20.0),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(is_paired_right_side(&elements, idx), expected);
+    }
+
+    #[test]
+    fn test_is_paired_right_side_different_exercises() {
+        assert!(!is_paired_right_side(
+            &[
+                with_side(target_set(1, 5, 20.0), Side::Left),
+                with_side(target_set(2, 5, 20.0), Side::Right),
+            ],
+            1
+        ));
+    }
+
+    #[test]
+    fn test_repeat_training_session() {
+        let set = |exercise_id, reps, weight, target_reps: Option<u32>, target_weight| {
+            TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time: None,
+                weight,
+                rpe: None,
+                target_reps: target_reps.map(RepsRange::single),
+                target_time: None,
+                target_weight,
+                target_rpe: None,
+                automatic: false,
+                partial_reps: None,
+                to_failure: false,
+                side: None,
+                comment: None,
+            }
+        };
+        let training_session = TrainingSession {
+            elements: vec![
+                TrainingSessionElement::Set {
+                    exercise_id: 1,
+                    reps: Some(8),
+                    time: Some(4),
+                    weight: Some(60.0),
+                    rpe: Some(8.0),
+                    target_reps: Some(RepsRange::single(10)),
+                    target_time: Some(3),
+                    target_weight: Some(57.5),
+                    target_rpe: Some(RpeRange::single(9.0)),
+                    automatic: false,
+                    partial_reps: None,
+                    to_failure: false,
+                    side: None,
+                    comment: Some(String::from("A")),
+                },
+                set(2, Some(12), None, Some(12), Some(20.0)),
+                session_rest(90),
+                set(1, None, None, Some(10), Some(57.5)),
+                set(2, None, None, None, None),
+                TrainingSessionElement::Rest {
+                    target_time: None,
+                    automatic: true,
+                },
+            ],
+            ..TRAINING_SESSION.clone()
+        };
+
+        assert_eq!(
+            repeat_training_session(&training_session, *TODAY),
+            TrainingSession {
+                id: 0,
+                routine_id: Some(2),
+                date: *TODAY,
+                notes: None,
+                start_time: None,
+                end_time: None,
+                elements: vec![
+                    TrainingSessionElement::Set {
+                        exercise_id: 1,
+                        reps: None,
+                        time: None,
+                        weight: None,
+                        rpe: None,
+                        target_reps: Some(RepsRange::single(8)),
+                        target_time: Some(4),
+                        target_weight: Some(60.0),
+                        target_rpe: Some(RpeRange::single(8.0)),
+                        automatic: false,
+                        partial_reps: None,
+                        to_failure: false,
+                        side: None,
+                        comment: None,
+                    },
+                    set(2, None, None, Some(12), Some(20.0)),
+                    session_rest(90),
+                    set(1, None, None, Some(10), Some(57.5)),
+                    set(2, None, None, None, None),
+                    TrainingSessionElement::Rest {
+                        target_time: None,
+                        automatic: true,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::empty(vec![], vec![])]
+    #[case::different_values(
+        vec![
+            session_set(1, Some(5), Some(100.0)),
+            session_rest(120),
+            session_set(1, Some(5), Some(102.5)),
+            session_rest(120),
+        ],
+        vec![
+            (1, vec![routine_activity(1, 5, 100.0), routine_rest(120)]),
+            (1, vec![routine_activity(1, 5, 102.5)]),
+        ]
+    )]
+    #[case::supersets(
+        vec![
+            session_set(1, Some(10), Some(60.0)),
+            session_set(2, Some(12), Some(40.0)),
+            session_rest(90),
+            session_set(1, Some(10), Some(60.0)),
+            session_set(2, Some(12), Some(40.0)),
+            session_rest(90),
+            session_set(1, Some(10), Some(60.0)),
+            session_rest(90),
+        ],
+        vec![
+            (
+                2,
+                vec![
+                    routine_activity(1, 10, 60.0),
+                    routine_activity(2, 12, 40.0),
+                    routine_rest(90),
+                ]
+            ),
+            (1, vec![routine_activity(1, 10, 60.0)]),
+        ]
+    )]
+    #[case::missing_last_rest(
+        vec![
+            session_set(1, Some(5), Some(100.0)),
+            session_rest(120),
+            session_set(1, Some(5), Some(100.0)),
+            session_rest(120),
+            session_set(1, Some(5), Some(100.0)),
+        ],
+        vec![(3, vec![routine_activity(1, 5, 100.0), routine_rest(120)])]
+    )]
+    #[case::rests_without_set(
+        vec![
+            session_rest(60),
+            session_set(1, Some(5), Some(100.0)),
+            session_rest(120),
+            session_rest(60),
+            session_set(2, Some(8), None),
+        ],
+        vec![
+            (1, vec![routine_activity(1, 5, 100.0), routine_rest(120)]),
+            (1, vec![routine_activity(2, 8, 0.0)]),
+        ]
+    )]
+    #[case::missing_values(
+        vec![
+            TrainingSessionElement::Set {
+                exercise_id: 1,
+                reps: None,
+                time: Some(3),
+                weight: None,
+                rpe: Some(8.0),
+                target_reps: Some(RepsRange::single(6)),
+                target_time: Some(4),
+                target_weight: Some(80.0),
+                target_rpe: Some(RpeRange::single(9.0)),
+                automatic: true,
+                partial_reps: None,
+                to_failure: false,
+                side: None,
+                comment: Some(String::from("C")),
+            },
+        ],
+        vec![(
+            1,
+            vec![RoutinePart::RoutineActivity {
+                exercise_id: Some(1),
+                reps: RepsRange::single(6),
+                time: 3,
+                weight: 80.0,
+                rpe: RpeRange::single(8.0),
+                automatic: true,
+                target_percent_1rm: None,
+            }]
+        )]
+    )]
+    fn test_routine_from_session_sections(
+        #[case] elements: Vec<TrainingSessionElement>,
+        #[case] expected: Vec<(u32, Vec<RoutinePart>)>,
+    ) {
+        let training_session = TrainingSession {
+            elements,
+            ..TRAINING_SESSION.clone()
+        };
+
+        assert_eq!(
+            routine_from_session(&training_session, "A").sections,
+            expected
+                .into_iter()
+                .map(|(rounds, parts)| RoutinePart::RoutineSection { rounds, parts })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case::previous_shorter(
+        vec![
+            session_set(1, None, None),
+            session_rest(60),
+            session_set(1, None, None),
+            session_rest(60),
+            session_set(1, None, None),
+        ],
+        vec![
+            session_set(1, Some(5), Some(80.0)),
+            session_rest(60),
+            session_set(1, Some(5), Some(82.5)),
+        ],
+        vec![Some(0), None, Some(2), None, Some(2)]
+    )]
+    #[case::previous_longer(
+        vec![
+            session_set(1, None, None),
+            session_rest(60),
+            session_set(1, None, None),
+        ],
+        vec![
+            session_set(1, Some(5), Some(80.0)),
+            session_rest(60),
+            session_set(1, Some(5), Some(82.5)),
+            session_rest(60),
+            session_set(1, Some(5), Some(85.0)),
+        ],
+        vec![Some(0), None, Some(2)]
+    )]
+    #[case::skipped_sets(
+        vec![
+            session_set(1, None, None),
+            session_rest(60),
+            session_set(1, None, None),
+            session_rest(60),
+            session_set(1, None, None),
+        ],
+        vec![
+            session_set(1, None, Some(80.0)),
+            session_rest(60),
+            session_set(1, Some(5), Some(82.5)),
+            session_rest(60),
+            session_set(1, None, None),
+            session_rest(60),
+            session_set(1, Some(5), Some(85.0)),
+        ],
+        vec![Some(2), None, Some(6), None, Some(6)]
+    )]
+    #[case::separate_blocks(
+        vec![
+            session_set(1, None, None),
+            session_rest(60),
+            session_set(2, None, None),
+            session_rest(60),
+            session_set(2, None, None),
+            session_rest(60),
+            session_set(1, None, None),
+        ],
+        vec![
+            session_set(1, Some(5), Some(80.0)),
+            session_rest(60),
+            session_set(2, Some(10), Some(20.0)),
+            session_rest(60),
+            session_set(1, Some(5), Some(85.0)),
+        ],
+        vec![Some(0), None, Some(2), None, Some(2), None, Some(4)]
+    )]
+    #[case::superset_slots(
+        vec![
+            session_set(1, None, None),
+            session_set(2, None, None),
+            session_rest(60),
+            session_set(1, None, None),
+            session_set(2, None, None),
+        ],
+        vec![
+            session_set(2, Some(12), Some(15.0)),
+            session_rest(60),
+            session_set(1, Some(5), Some(80.0)),
+            session_set(2, Some(10), Some(20.0)),
+        ],
+        vec![Some(2), Some(3), None, Some(2), Some(3)]
+    )]
+    #[case::different_slot_only(
+        vec![session_set(1, None, None), session_set(2, None, None)],
+        vec![session_set(2, Some(10), Some(20.0))],
+        vec![None, None]
+    )]
+    #[case::no_previous_sets(
+        vec![session_set(1, None, None), session_rest(60)],
+        vec![],
+        vec![None, None]
+    )]
+    fn test_corresponding_sets(
+        #[case] elements: Vec<TrainingSessionElement>,
+        #[case] previous_elements: Vec<TrainingSessionElement>,
+        #[case] expected: Vec<Option<usize>>,
+    ) {
+        assert_eq!(
+            corresponding_sets(&elements, &previous_elements),
+            expected
+                .into_iter()
+                .map(|idx| idx.map(|idx| &previous_elements[idx]))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case::ad_hoc(10, None, 0, &[1, 2, 3], &[(1, 1), (2, 3)])]
+    #[case::ad_hoc_excluding_itself(3, None, 3, &[2], &[(2, 1)])]
+    #[case::ad_hoc_excluding_later(10, None, 4, &[1, 2], &[(1, 1), (2, 1)])]
+    #[case::routine(10, Some(1), 0, &[1, 2, 3], &[(1, 4), (2, 4)])]
+    #[case::routine_latest_without_exercise(10, Some(2), 0, &[1, 2], &[(1, 6)])]
+    #[case::routine_without_history(10, Some(3), 0, &[1, 2], &[])]
+    fn test_previous_training_sessions(
+        #[case] id: u32,
+        #[case] routine_id: Option<u32>,
+        #[case] days_ago: i64,
+        #[case] exercises: &[u32],
+        #[case] expected: &[(u32, u32)],
+    ) {
+        let training_sessions = [
+            history_session(1, None, 5, &[1, 2]),
+            history_session(2, Some(1), 4, &[1]),
+            history_session(3, None, 3, &[2]),
+            history_session(4, Some(1), 2, &[1, 2]),
+            history_session(5, None, -1, &[1]),
+            history_session(6, Some(2), 1, &[1]),
+        ];
+        let training_session = history_session(id, routine_id, days_ago, exercises);
+
+        assert_eq!(
+            previous_training_sessions(&training_session, &training_sessions)
+                .into_iter()
+                .map(|(exercise_id, t)| (exercise_id, t.id))
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_previous_sets() {
+        let first = TrainingSession {
+            elements: vec![
+                session_set(1, Some(5), Some(80.0)),
+                session_rest(60),
+                session_set(1, Some(5), Some(82.5)),
+            ],
+            ..history_session(1, None, 2, &[])
+        };
+        let second = TrainingSession {
+            elements: vec![
+                session_set(2, Some(10), Some(20.0)),
+                session_rest(60),
+                session_set(1, Some(3), Some(90.0)),
+            ],
+            ..history_session(2, None, 1, &[])
+        };
+        let elements = vec![
+            session_set(1, None, None),
+            session_rest(60),
+            session_set(2, None, None),
+            session_rest(60),
+            session_set(1, None, None),
+            session_set(3, None, None),
+        ];
+
+        assert_eq!(
+            previous_sets(&elements, &BTreeMap::from([(1, &first), (2, &second)])),
+            vec![
+                Some(&first.elements[0]),
+                None,
+                Some(&second.elements[0]),
+                None,
+                Some(&first.elements[2]),
+                None,
+            ]
+        );
+        assert_eq!(
+            previous_sets(&elements, &BTreeMap::new()),
+            vec![None, None, None, None, None, None]
+        );
+    }
+
+    fn history_session(
+        id: u32,
+        routine_id: Option<u32>,
+        days_ago: i64,
+        exercises: &[u32],
+    ) -> TrainingSession {
+        TrainingSession {
+            id,
+            routine_id,
+            date: *TODAY - Duration::days(days_ago),
+            notes: None,
+            start_time: None,
+            end_time: None,
+            elements: exercises
+                .iter()
+                .map(|exercise_id| session_set(*exercise_id, Some(5), None))
+                .collect(),
+        }
+    }
+
+    fn session_set(
+        exercise_id: u32,
+        reps: Option<u32>,
+        weight: Option<f32>,
+    ) -> TrainingSessionElement {
+        TrainingSessionElement::Set {
+            exercise_id,
+            reps,
+            time: None,
+            weight,
+            rpe: None,
+            target_reps: None,
+            target_time: None,
+            target_weight: None,
+            target_rpe: None,
+            automatic: false,
+            partial_reps: None,
+            to_failure: false,
+            side: None,
+            comment: None,
+        }
+    }
+
+    fn with_side(element: TrainingSessionElement, side: Side) -> TrainingSessionElement {
+        match element {
+            TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time,
+                weight,
+                rpe,
+                target_reps,
+                target_time,
+                target_weight,
+                target_rpe,
+                automatic,
+                partial_reps,
+                to_failure,
+                comment,
+                ..
+            } => TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time,
+                weight,
+                rpe,
+                target_reps,
+                target_time,
+                target_weight,
+                target_rpe,
+                automatic,
+                partial_reps,
+                to_failure,
+                side: Some(side),
+                comment,
+            },
+            TrainingSessionElement::Rest { .. } => element,
+        }
+    }
+
+    fn session_time_set(
+        exercise_id: u32,
+        time: u32,
+        weight: Option<f32>,
+    ) -> TrainingSessionElement {
+        TrainingSessionElement::Set {
+            exercise_id,
+            reps: None,
+            time: Some(time),
+            weight,
+            rpe: None,
+            target_reps: None,
+            target_time: None,
+            target_weight: None,
+            target_rpe: None,
+            automatic: false,
+            partial_reps: None,
+            to_failure: false,
+            side: None,
+            comment: None,
+        }
+    }
+
+    fn session_rest(target_time: u32) -> TrainingSessionElement {
+        TrainingSessionElement::Rest {
+            target_time: Some(target_time),
+            automatic: true,
+        }
+    }
+
+    fn routine_activity(exercise_id: u32, reps: u32, weight: f32) -> RoutinePart {
+        RoutinePart::RoutineActivity {
+            exercise_id: Some(exercise_id),
+            reps: RepsRange::single(reps),
+            time: 0,
+            weight,
+            rpe: RpeRange::default(),
+            automatic: false,
+            target_percent_1rm: None,
+        }
+    }
+
+    fn routine_rest(time: u32) -> RoutinePart {
+        RoutinePart::RoutineActivity {
+            exercise_id: None,
+            reps: RepsRange::default(),
+            time,
+            weight: 0.0,
+            rpe: RpeRange::default(),
+            automatic: true,
+            target_percent_1rm: None,
+        }
+    }
+
+    fn routine_section(rounds: u32, parts: Vec<RoutinePart>) -> RoutinePart {
+        RoutinePart::RoutineSection { rounds, parts }
+    }
+
+    fn routine_sections() -> Vec<RoutinePart> {
+        vec![
+            routine_section(1, vec![routine_activity(1, 5, 100.0), routine_rest(60)]),
+            routine_section(
+                3,
+                vec![
+                    routine_activity(2, 8, 50.0),
+                    routine_section(
+                        2,
+                        vec![
+                            routine_activity(3, 10, 20.0),
+                            routine_activity(4, 12, 10.0),
+                            routine_rest(30),
+                        ],
+                    ),
+                    routine_rest(90),
+                ],
+            ),
+            routine_section(1, vec![routine_activity(5, 15, 0.0)]),
+        ]
+    }
+
+    #[rstest]
+    #[case::first_section_down(&[0], Direction::Down, &[1, 0, 2])]
+    #[case::last_section_up(&[2], Direction::Up, &[0, 2, 1])]
+    #[case::first_section_up(&[0], Direction::Up, &[0, 1, 2])]
+    #[case::last_section_down(&[2], Direction::Down, &[0, 1, 2])]
+    fn test_move_part_sections(
+        #[case] path: &[usize],
+        #[case] direction: Direction,
+        #[case] expected_order: &[usize],
+    ) {
+        let mut sections = routine_sections();
+        let expected = expected_order
+            .iter()
+            .map(|i| routine_sections()[*i].clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            move_part(&mut sections, path, direction),
+            expected_order != [0, 1, 2]
+        );
+        assert_eq!(sections, expected);
+    }
+
+    #[rstest]
+    #[case::activity_down(&[0, 0], Direction::Down, true)]
+    #[case::activity_up(&[0, 1], Direction::Up, true)]
+    #[case::first_activity_up(&[0, 0], Direction::Up, false)]
+    #[case::last_activity_down(&[0, 1], Direction::Down, false)]
+    #[case::last_activity_of_section_down(&[1, 2], Direction::Down, false)]
+    #[case::first_activity_of_section_up(&[2, 0], Direction::Up, false)]
+    fn test_move_part_activities(
+        #[case] path: &[usize],
+        #[case] direction: Direction,
+        #[case] expected_moved: bool,
+    ) {
+        let mut sections = routine_sections();
+
+        assert_eq!(move_part(&mut sections, path, direction), expected_moved);
+        if expected_moved {
+            let (section, index) = (path[0], path[1]);
+            let other = match direction {
+                Direction::Up => index - 1,
+                Direction::Down => index + 1,
+            };
+            let RoutinePart::RoutineSection { parts, .. } = &sections[section] else {
+                panic!("section expected");
+            };
+            let RoutinePart::RoutineSection {
+                parts: orig_parts, ..
+            } = &routine_sections()[section]
+            else {
+                panic!("section expected");
+            };
+            assert_eq!(parts[index], orig_parts[other]);
+            assert_eq!(parts[other], orig_parts[index]);
+        } else {
+            assert_eq!(sections, routine_sections());
+        }
+    }
 
-    use super::*;
+    #[test]
+    fn test_move_part_nested_sections() {
+        let mut sections = routine_sections();
 
-    static TODAY: std::sync::LazyLock<NaiveDate> =
-        std::sync::LazyLock::new(|| Local::now().date_naive());
+        assert!(move_part(&mut sections, &[1, 1, 0], Direction::Down));
+        assert!(move_part(&mut sections, &[1, 1], Direction::Up));
 
-    static EXERCISES: std::sync::LazyLock<BTreeMap<u32, Exercise>> =
-        std::sync::LazyLock::new(|| {
-            BTreeMap::from([(
-                1,
-                Exercise {
-                    id: 1,
-                    name: String::from("A"),
-                    muscles: vec![
-                        ExerciseMuscle {
-                            muscle_id: 11,
-                            stimulus: 100,
-                        },
-                        ExerciseMuscle {
-                            muscle_id: 31,
-                            stimulus: 50,
-                        },
+        assert_eq!(
+            sections,
+            vec![
+                routine_section(1, vec![routine_activity(1, 5, 100.0), routine_rest(60)]),
+                routine_section(
+                    3,
+                    vec![
+                        routine_section(
+                            2,
+                            vec![
+                                routine_activity(4, 12, 10.0),
+                                routine_activity(3, 10, 20.0),
+                                routine_rest(30),
+                            ],
+                        ),
+                        routine_activity(2, 8, 50.0),
+                        routine_rest(90),
                     ],
-                },
-            )])
-        });
+                ),
+                routine_section(1, vec![routine_activity(5, 15, 0.0)]),
+            ]
+        );
 
-    static ROUTINE: std::sync::LazyLock<Routine> = std::sync::LazyLock::new(|| Routine {
-        id: 1,
-        name: String::from("A"),
-        notes: Some(String::from("B")),
-        archived: false,
-        sections: vec![
-            RoutinePart::RoutineSection {
-                rounds: 2,
-                parts: vec![
-                    RoutinePart::RoutineActivity {
-                        exercise_id: Some(1),
-                        reps: 10,
-                        time: 2,
-                        weight: 30.0,
-                        rpe: 10.0,
-                        automatic: false,
-                    },
+        assert!(!move_part(&mut sections, &[1, 0, 2], Direction::Down));
+        assert!(!move_part(&mut sections, &[1, 0, 0], Direction::Up));
+    }
+
+    #[rstest]
+    #[case::empty(&[])]
+    #[case::section_out_of_range(&[3])]
+    #[case::activity_out_of_range(&[0, 2])]
+    #[case::parent_out_of_range(&[3, 0])]
+    #[case::parent_is_activity(&[0, 0, 0])]
+    fn test_move_part_invalid_path(#[case] path: &[usize]) {
+        let mut sections = routine_sections();
+
+        assert!(!move_part(&mut sections, path, Direction::Down));
+        assert!(!move_part(&mut sections, path, Direction::Up));
+        assert_eq!(sections, routine_sections());
+    }
+
+    fn target_set(exercise_id: u32, reps: u32, weight: f32) -> TrainingSessionElement {
+        TrainingSessionElement::Set {
+            exercise_id,
+            reps: None,
+            time: None,
+            weight: None,
+            rpe: None,
+            target_reps: Some(RepsRange::single(reps)),
+            target_time: None,
+            target_weight: Some(weight),
+            target_rpe: None,
+            automatic: false,
+            partial_reps: None,
+            to_failure: false,
+            side: None,
+            comment: None,
+        }
+    }
+
+    fn expanded(rounds: &[(u32, u32)], element: TrainingSessionElement) -> ExpandedElement {
+        ExpandedElement {
+            rounds: rounds
+                .iter()
+                .map(|(number, total)| Round {
+                    number: *number,
+                    total: *total,
+                })
+                .collect(),
+            element,
+        }
+    }
+
+    #[rstest]
+    #[case::empty(vec![], vec![])]
+    #[case::zero_rounds(
+        vec![
+            routine_section(0, vec![routine_activity(1, 5, 100.0), routine_rest(60)]),
+            routine_section(1, vec![routine_activity(2, 8, 50.0)]),
+        ],
+        vec![expanded(&[(1, 1)], target_set(2, 8, 50.0))]
+    )]
+    #[case::rounds(
+        vec![routine_section(3, vec![routine_activity(1, 5, 100.0), routine_rest(60)])],
+        vec![
+            expanded(&[(1, 3)], target_set(1, 5, 100.0)),
+            expanded(&[(1, 3)], session_rest(60)),
+            expanded(&[(2, 3)], target_set(1, 5, 100.0)),
+            expanded(&[(2, 3)], session_rest(60)),
+            expanded(&[(3, 3)], target_set(1, 5, 100.0)),
+            expanded(&[(3, 3)], session_rest(60)),
+        ]
+    )]
+    #[case::nested_sections(
+        vec![routine_section(
+            2,
+            vec![
+                routine_activity(1, 5, 100.0),
+                routine_section(
+                    2,
+                    vec![routine_section(1, vec![routine_activity(2, 8, 50.0)]), routine_rest(30)],
+                ),
+            ],
+        )],
+        vec![
+            expanded(&[(1, 2)], target_set(1, 5, 100.0)),
+            expanded(&[(1, 2), (1, 2), (1, 1)], target_set(2, 8, 50.0)),
+            expanded(&[(1, 2), (1, 2)], session_rest(30)),
+            expanded(&[(1, 2), (2, 2), (1, 1)], target_set(2, 8, 50.0)),
+            expanded(&[(1, 2), (2, 2)], session_rest(30)),
+            expanded(&[(2, 2)], target_set(1, 5, 100.0)),
+            expanded(&[(2, 2), (1, 2), (1, 1)], target_set(2, 8, 50.0)),
+            expanded(&[(2, 2), (1, 2)], session_rest(30)),
+            expanded(&[(2, 2), (2, 2), (1, 1)], target_set(2, 8, 50.0)),
+            expanded(&[(2, 2), (2, 2)], session_rest(30)),
+        ]
+    )]
+    #[case::activities_without_exercise(
+        vec![
+            routine_rest(90),
+            routine_section(
+                1,
+                vec![
                     RoutinePart::RoutineActivity {
                         exercise_id: None,
-                        reps: 0,
-                        time: 60,
-                        weight: 0.0,
-                        rpe: 0.0,
-                        automatic: true,
-                    },
-                ],
-            },
-            RoutinePart::RoutineSection {
-                rounds: 2,
-                parts: vec![
-                    RoutinePart::RoutineActivity {
-                        exercise_id: Some(2),
-                        reps: 10,
+                        reps: RepsRange::single(10),
                         time: 0,
-                        weight: 0.0,
-                        rpe: 0.0,
+                        weight: 20.0,
+                        rpe: RpeRange::single(8.0),
                         automatic: false,
+                        target_percent_1rm: Some(80.0),
                     },
-                    RoutinePart::RoutineActivity {
-                        exercise_id: None,
-                        reps: 0,
-                        time: 30,
-                        weight: 0.0,
-                        rpe: 0.0,
-                        automatic: true,
-                    },
+                    routine_rest(0),
                 ],
-            },
+            ),
         ],
-    });
-
-    static TRAINING_SESSION: std::sync::LazyLock<TrainingSession> =
-        std::sync::LazyLock::new(|| TrainingSession {
-            id: 1,
-            routine_id: Some(2),
-            date: *TODAY - Duration::days(10),
-            notes: Some(String::from("A")),
-            elements: vec![
-                TrainingSessionElement::Set {
-                    exercise_id: 1,
-                    reps: Some(10),
-                    time: Some(3),
-                    weight: Some(30.0),
-                    rpe: Some(8.0),
-                    target_reps: Some(8),
-                    target_time: Some(4),
-                    target_weight: Some(40.0),
-                    target_rpe: Some(9.0),
-                    automatic: false,
-                },
+        vec![
+            expanded(&[], session_rest(90)),
+            expanded(
+                &[(1, 1)],
                 TrainingSessionElement::Rest {
-                    target_time: Some(60),
-                    automatic: true,
-                },
-                TrainingSessionElement::Set {
-                    exercise_id: 2,
-                    reps: Some(5),
-                    time: Some(4),
-                    weight: None,
-                    rpe: Some(4.0),
-                    target_reps: None,
                     target_time: None,
-                    target_weight: None,
-                    target_rpe: None,
                     automatic: false,
-                },
+                }
+            ),
+            expanded(
+                &[(1, 1)],
                 TrainingSessionElement::Rest {
-                    target_time: Some(60),
+                    target_time: None,
                     automatic: true,
-                },
+                }
+            ),
+        ]
+    )]
+    fn test_expand_routine_parts(
+        #[case] parts: Vec<RoutinePart>,
+        #[case] expected: Vec<ExpandedElement>,
+    ) {
+        assert_eq!(expand_routine_parts(&parts, &|_, _| None), expected);
+    }
+
+    #[rstest]
+    #[case::no_routine(
+        vec![target_set(1, 5, 100.0), session_rest(60), target_set(1, 5, 100.0)],
+        vec![],
+        vec![vec![], vec![], vec![]]
+    )]
+    #[case::rounds(
+        vec![
+            target_set(1, 5, 100.0),
+            session_rest(60),
+            target_set(1, 5, 100.0),
+            session_rest(60),
+        ],
+        vec![routine_section(2, vec![routine_activity(1, 5, 100.0), routine_rest(60)])],
+        vec![vec![(1, 2)], vec![(1, 2)], vec![(2, 2)], vec![(2, 2)]]
+    )]
+    #[case::superset(
+        vec![
+            target_set(1, 5, 100.0),
+            target_set(2, 8, 50.0),
+            session_rest(60),
+            target_set(1, 5, 100.0),
+            target_set(2, 8, 50.0),
+            session_rest(60),
+            target_set(3, 10, 20.0),
+        ],
+        vec![
+            routine_section(
+                2,
+                vec![
+                    routine_activity(1, 5, 100.0),
+                    routine_activity(2, 8, 50.0),
+                    routine_rest(60),
+                ],
+            ),
+            routine_section(1, vec![routine_activity(3, 10, 20.0)]),
+        ],
+        vec![
+            vec![(1, 2)],
+            vec![(1, 2)],
+            vec![(1, 2)],
+            vec![(2, 2)],
+            vec![(2, 2)],
+            vec![(2, 2)],
+            vec![(1, 1)],
+        ]
+    )]
+    #[case::added_trailing_set(
+        vec![
+            target_set(1, 5, 100.0),
+            session_rest(60),
+            target_set(1, 5, 100.0),
+            session_rest(60),
+            target_set(1, 5, 100.0),
+        ],
+        vec![routine_section(2, vec![routine_activity(1, 5, 100.0), routine_rest(60)])],
+        vec![vec![(1, 2)], vec![(1, 2)], vec![(2, 2)], vec![(2, 2)], vec![]]
+    )]
+    #[case::missing_trailing_sets(
+        vec![target_set(1, 5, 100.0), session_rest(60)],
+        vec![routine_section(2, vec![routine_activity(1, 5, 100.0), routine_rest(60)])],
+        vec![vec![(1, 2)], vec![(1, 2)]]
+    )]
+    #[case::unilateral(
+        vec![
+            with_side(target_set(1, 5, 100.0), Side::Left),
+            with_side(target_set(1, 5, 100.0), Side::Right),
+            session_rest(60),
+            with_side(target_set(1, 5, 100.0), Side::Left),
+            with_side(target_set(1, 5, 100.0), Side::Right),
+            session_rest(60),
+        ],
+        vec![routine_section(2, vec![routine_activity(1, 5, 100.0), routine_rest(60)])],
+        vec![
+            vec![(1, 2)],
+            vec![(1, 2)],
+            vec![(1, 2)],
+            vec![(2, 2)],
+            vec![(2, 2)],
+            vec![(2, 2)],
+        ]
+    )]
+    #[case::unpaired_right_side(
+        vec![
+            with_side(target_set(1, 5, 100.0), Side::Right),
+            session_rest(60),
+        ],
+        vec![routine_section(1, vec![routine_activity(1, 5, 100.0), routine_rest(60)])],
+        vec![vec![(1, 1)], vec![(1, 1)]]
+    )]
+    #[case::replaced_exercise(
+        vec![
+            target_set(1, 5, 100.0),
+            session_rest(60),
+            target_set(2, 5, 100.0),
+            session_rest(60),
+        ],
+        vec![routine_section(2, vec![routine_activity(1, 5, 100.0), routine_rest(60)])],
+        vec![vec![(1, 2)], vec![(1, 2)], vec![], vec![]]
+    )]
+    fn test_training_session_rounds(
+        #[case] elements: Vec<TrainingSessionElement>,
+        #[case] parts: Vec<RoutinePart>,
+        #[case] expected: Vec<Vec<(u32, u32)>>,
+    ) {
+        assert_eq!(
+            training_session_rounds(&elements, &parts),
+            expected
+                .into_iter()
+                .map(|rounds| expanded(&rounds, session_rest(0)).rounds)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_expand_routine_parts_target_percent_1rm() {
+        let activity = |exercise_id, target_percent_1rm| RoutinePart::RoutineActivity {
+            exercise_id: Some(exercise_id),
+            reps: RepsRange::single(5),
+            time: 0,
+            weight: 60.0,
+            rpe: RpeRange::default(),
+            automatic: false,
+            target_percent_1rm,
+        };
+        let parts = [routine_section(
+            1,
+            vec![
+                activity(1, Some(80.0)),
+                activity(2, Some(80.0)),
+                activity(1, None),
+            ],
+        )];
+
+        assert_eq!(
+            expand_routine_parts(&parts, &|exercise_id, percent_1rm| {
+                (exercise_id == 1).then_some(percent_1rm + 20.0)
+            })
+            .into_iter()
+            .map(|e| e.element)
+            .collect::<Vec<_>>(),
+            vec![
+                target_set(1, 5, 100.0),
                 TrainingSessionElement::Set {
                     exercise_id: 2,
                     reps: None,
-                    time: Some(60),
+                    time: None,
                     weight: None,
                     rpe: None,
-                    target_reps: None,
+                    target_reps: Some(RepsRange::single(5)),
                     target_time: None,
                     target_weight: None,
                     target_rpe: None,
                     automatic: false,
+                    partial_reps: None,
+                    to_failure: false,
+                    side: None,
+                    comment: None,
                 },
-                TrainingSessionElement::Rest {
-                    target_time: Some(60),
-                    automatic: true,
-                },
-            ],
-        });
-
-    static EMPTY_TRAINING_SESSION: std::sync::LazyLock<TrainingSession> =
-        std::sync::LazyLock::new(|| {
-            let mut training_session = TRAINING_SESSION.clone();
-            training_session.elements = TRAINING_SESSION
-                .elements
-                .iter()
-                .map(|e| match e {
-                    TrainingSessionElement::Set {
-                        exercise_id,
-                        target_reps,
-                        target_time,
-                        target_weight,
-                        target_rpe,
-                        automatic,
-                        ..
-                    } => TrainingSessionElement::Set {
-                        exercise_id: *exercise_id,
-                        reps: None,
-                        time: None,
-                        weight: None,
-                        rpe: None,
-                        target_reps: *target_reps,
-                        target_time: *target_time,
-                        target_weight: *target_weight,
-                        target_rpe: *target_rpe,
-                        automatic: *automatic,
-                    },
-                    TrainingSessionElement::Rest { .. } => e.clone(),
-                })
-                .collect::<Vec<_>>();
-            training_session
-        });
+                target_set(1, 5, 60.0),
+            ]
+        );
+    }
 
     #[test]
-    fn test_user_serde() {
-        let obj = User {
-            id: 1,
-            name: String::from("A"),
-            sex: 0,
+    fn test_planned_routines() {
+        let routine = |id, archived, schedule| Routine {
+            id,
+            archived,
+            schedule,
+            tags: vec![],
+            ..ROUTINE.clone()
         };
-        let serialized = json!({
-            "id": 1,
-            "name": "A",
-            "sex": 0
-        });
-        let deserialized: User = serde_json::from_value(serialized).unwrap();
-        assert_eq!(deserialized, obj);
+        let training_session = |id, routine_id, date| TrainingSession {
+            id,
+            routine_id,
+            date,
+            notes: None,
+            start_time: None,
+            end_time: None,
+            elements: vec![],
+        };
+        let routines = [
+            routine(1, false, vec![Weekday::Wed]),
+            routine(2, false, vec![Weekday::Mon, Weekday::Thu]),
+            routine(3, false, vec![Weekday::Tue]),
+            routine(4, true, vec![Weekday::Tue]),
+            routine(5, false, vec![]),
+            routine(6, false, vec![Weekday::Fri]),
+        ];
+        let training_sessions = [
+            training_session(1, Some(2), march(1)),
+            training_session(2, Some(2), march(8)),
+            training_session(3, Some(6), march(1)),
+            training_session(4, None, march(5)),
+        ];
+
+        assert_eq!(
+            planned_routines(
+                &routines.iter().collect::<Vec<_>>(),
+                &training_sessions.iter().collect::<Vec<_>>(),
+                march(5),
+            ),
+            vec![
+                PlannedRoutine {
+                    routine_id: 2,
+                    due: Due::Overdue,
+                    last_training_session: Some(march(1)),
+                },
+                PlannedRoutine {
+                    routine_id: 3,
+                    due: Due::Today,
+                    last_training_session: None,
+                },
+                PlannedRoutine {
+                    routine_id: 1,
+                    due: Due::Tomorrow,
+                    last_training_session: None,
+                },
+            ]
+        );
     }
 
     #[test]
-    fn test_exercise_serde() {
-        let obj = Exercise {
-            id: 1,
-            name: String::from("A"),
-            muscles: vec![ExerciseMuscle {
-                muscle_id: 2,
-                stimulus: 100,
-            }],
+    #[allow(clippy::too_many_lines)]
+    fn test_routine_adherence() {
+        let training_session = |id, routine_id, date, elements| TrainingSession {
+            id,
+            routine_id,
+            date,
+            notes: None,
+            start_time: None,
+            end_time: None,
+            elements,
         };
-        let serialized = json!(obj);
-        let deserialized: Exercise = serde_json::from_value(serialized).unwrap();
-        assert_eq!(deserialized, obj);
-    }
+        let set = |exercise_id, reps, weight, target_reps: Option<u32>, target_weight| {
+            TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time: None,
+                weight,
+                rpe: None,
+                target_reps: target_reps.map(RepsRange::single),
+                target_time: None,
+                target_weight,
+                target_rpe: None,
+                automatic: false,
+                partial_reps: None,
+                to_failure: false,
+                side: None,
+                comment: None,
+            }
+        };
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let training_sessions = [
+            training_session(
+                1,
+                Some(1),
+                date(1),
+                vec![
+                    set(1, Some(10), Some(30.0), Some(10), Some(30.0)),
+                    set(1, Some(8), Some(30.0), Some(10), Some(30.0)),
+                    set(2, Some(10), None, Some(10), None),
+                    set(2, None, None, Some(10), None),
+                ],
+            ),
+            training_session(
+                2,
+                Some(1),
+                date(3),
+                vec![
+                    set(2, Some(12), None, Some(10), None),
+                    set(2, Some(9), None, None, None),
+                    set(1, Some(9), Some(25.0), Some(10), Some(30.0)),
+                    set(3, Some(5), None, None, None),
+                ],
+            ),
+            training_session(
+                3,
+                Some(1),
+                date(5),
+                vec![set(1, None, None, Some(10), None)],
+            ),
+            training_session(
+                4,
+                Some(2),
+                date(6),
+                vec![set(1, Some(10), None, None, None)],
+            ),
+            training_session(
+                5,
+                Some(1),
+                date(20),
+                vec![set(1, Some(10), None, None, None)],
+            ),
+        ];
 
-    #[test]
-    fn test_exercise_muscle_stimulus() {
         assert_eq!(
-            Exercise {
-                id: 1,
-                name: String::from("A"),
-                muscles: vec![
-                    ExerciseMuscle {
-                        muscle_id: 2,
-                        stimulus: 100,
+            routine_adherence(
+                &ROUTINE,
+                &training_sessions.iter().collect::<Vec<_>>(),
+                &Interval {
+                    first: date(1),
+                    last: date(10),
+                }
+            ),
+            RoutineAdherence {
+                exercises: vec![
+                    ExerciseAdherence {
+                        exercise_id: 1,
+                        planned_sets: 6,
+                        completed_sets: 3,
+                        reps_deviation: Some(-1.0),
+                        weight_deviation: Some(-5.0 / 3.0),
+                    },
+                    ExerciseAdherence {
+                        exercise_id: 2,
+                        planned_sets: 6,
+                        completed_sets: 3,
+                        reps_deviation: Some(1.0),
+                        weight_deviation: None,
+                    },
+                    ExerciseAdherence {
+                        exercise_id: 3,
+                        planned_sets: 0,
+                        completed_sets: 1,
+                        reps_deviation: None,
+                        weight_deviation: None,
                     },
-                    ExerciseMuscle {
-                        muscle_id: 8,
-                        stimulus: 50,
-                    }
                 ],
+                skipped_sessions: 1,
             }
-            .muscle_stimulus(),
-            BTreeMap::from([(2, 100), (8, 50)])
+        );
+    }
+
+    #[rstest]
+    #[case(6, 3, Some(50.0))]
+    #[case(4, 4, Some(100.0))]
+    #[case(0, 1, None)]
+    fn test_exercise_adherence_completion(
+        #[case] planned_sets: u32,
+        #[case] completed_sets: u32,
+        #[case] expected: Option<f32>,
+    ) {
+        assert_eq!(
+            ExerciseAdherence {
+                exercise_id: 1,
+                planned_sets,
+                completed_sets,
+                reps_deviation: None,
+                weight_deviation: None,
+            }
+            .completion(),
+            expected
         );
     }
 
     #[test]
-    fn test_routine_serde() {
-        let obj = &*ROUTINE;
+    fn test_training_session_serde() {
+        let obj = &*TRAINING_SESSION;
         let serialized = json!(obj);
-        let deserialized: Routine = serde_json::from_value(serialized).unwrap();
+        let deserialized: TrainingSession = serde_json::from_value(serialized).unwrap();
         assert_eq!(deserialized, *obj);
     }
 
     #[test]
-    fn test_routine_duration() {
-        assert_eq!(ROUTINE.duration(), Duration::seconds(300));
+    fn test_training_session_deserialize_without_times() {
+        let deserialized: TrainingSession = serde_json::from_value(json!({
+            "id": 1,
+            "routine_id": null,
+            "date": "2024-03-04",
+            "notes": null,
+            "elements": []
+        }))
+        .unwrap();
+        assert_eq!(deserialized.start_time, None);
+        assert_eq!(deserialized.end_time, None);
     }
 
     #[test]
-    fn test_routine_num_sets() {
-        assert_eq!(ROUTINE.num_sets(), 4);
+    fn test_training_session_deserialize_times() {
+        let deserialized: TrainingSession = serde_json::from_value(json!({
+            "id": 1,
+            "routine_id": null,
+            "date": "2024-03-04",
+            "notes": null,
+            "start_time": "2024-03-04T18:00:00",
+            "end_time": "2024-03-04T18:45:30.123456",
+            "elements": []
+        }))
+        .unwrap();
+        assert_eq!(
+            deserialized.duration(),
+            Some(Duration::microseconds(2_730_123_456))
+        );
+    }
+
+    #[rstest]
+    #[case::unknown(None, None, None)]
+    #[case::ongoing(Some((18, 0)), None, None)]
+    #[case::only_end(None, Some((19, 0)), None)]
+    #[case::end_before_start(Some((18, 0)), Some((17, 0)), None)]
+    #[case::finished(Some((18, 0)), Some((19, 15)), Some(Duration::minutes(75)))]
+    fn test_training_session_duration(
+        #[case] start_time: Option<(u32, u32)>,
+        #[case] end_time: Option<(u32, u32)>,
+        #[case] expected: Option<Duration>,
+    ) {
+        let time = |(hour, min)| {
+            NaiveDate::from_ymd_opt(2024, 3, 4)
+                .unwrap()
+                .and_hms_opt(hour, min, 0)
+        };
+        let training_session = TrainingSession {
+            start_time: start_time.and_then(time),
+            end_time: end_time.and_then(time),
+            ..TRAINING_SESSION.clone()
+        };
+        assert_eq!(training_session.duration(), expected);
     }
 
     #[test]
-    fn test_routine_stimulus_per_muscle() {
+    fn test_avg_duration() {
+        let training_session = |start_time: Option<(u32, u32)>, end_time: (u32, u32)| {
+            let time = |(hour, min)| {
+                NaiveDate::from_ymd_opt(2024, 3, 4)
+                    .unwrap()
+                    .and_hms_opt(hour, min, 0)
+            };
+            TrainingSession {
+                start_time: start_time.and_then(time),
+                end_time: time(end_time),
+                ..TRAINING_SESSION.clone()
+            }
+        };
+        assert_eq!(avg_duration(&[]), None);
+        assert_eq!(avg_duration(&[&training_session(None, (19, 0))]), None);
         assert_eq!(
-            ROUTINE.stimulus_per_muscle(&EXERCISES),
-            BTreeMap::from([
-                (1, 0),
-                (11, 200),
-                (21, 0),
-                (22, 0),
-                (31, 100),
-                (32, 0),
-                (33, 0),
-                (41, 0),
-                (42, 0),
-                (51, 0),
-                (61, 0),
-                (62, 0),
-                (71, 0),
-                (72, 0),
-                (81, 0),
-                (82, 0),
-                (83, 0),
-                (91, 0),
-            ])
+            avg_duration(&[
+                &training_session(Some((18, 0)), (19, 0)),
+                &training_session(None, (19, 0)),
+                &training_session(Some((18, 0)), (18, 30)),
+            ]),
+            Some(Duration::minutes(45))
         );
     }
 
+    #[rstest]
+    #[case::no_partial_reps(8, None, 8.0)]
+    #[case::zero_partial_reps(8, Some(0), 8.0)]
+    #[case::even_partial_reps(8, Some(2), 9.0)]
+    #[case::odd_partial_reps(5, Some(3), 6.5)]
+    #[case::only_partial_reps(0, Some(4), 2.0)]
+    fn test_effective_reps(
+        #[case] reps: u32,
+        #[case] partial_reps: Option<u32>,
+        #[case] expected: f32,
+    ) {
+        assert_approx_eq!(effective_reps(reps, partial_reps), expected);
+    }
+
     #[test]
-    fn test_routine_exercises() {
-        assert_eq!(ROUTINE.exercises(), BTreeSet::from([1, 2]));
+    fn test_training_session_element_deserialize_without_comment() {
+        let deserialized: TrainingSessionElement = serde_json::from_value(json!({
+            "exercise_id": 1,
+            "reps": 10,
+            "time": null,
+            "weight": 30.0,
+            "rpe": 8.0,
+            "target_reps": null,
+            "target_time": null,
+            "target_weight": null,
+            "target_rpe": null,
+            "automatic": false
+        }))
+        .unwrap();
+        assert_eq!(
+            deserialized,
+            TrainingSessionElement::Set {
+                exercise_id: 1,
+                reps: Some(10),
+                time: None,
+                weight: Some(30.0),
+                rpe: Some(8.0),
+                target_reps: None,
+                target_time: None,
+                target_weight: None,
+                target_rpe: None,
+                automatic: false,
+                partial_reps: None,
+                to_failure: false,
+                side: None,
+                comment: None,
+            }
+        );
     }
 
     #[test]
-    fn test_training_session_serde() {
-        let obj = &*TRAINING_SESSION;
-        let serialized = json!(obj);
-        let deserialized: TrainingSession = serde_json::from_value(serialized).unwrap();
-        assert_eq!(deserialized, *obj);
+    fn test_training_session_element_serialize_partial_reps_and_failure() {
+        let element = TrainingSessionElement::Set {
+            exercise_id: 1,
+            reps: Some(8),
+            time: None,
+            weight: Some(80.0),
+            rpe: None,
+            target_reps: None,
+            target_time: None,
+            target_weight: None,
+            target_rpe: None,
+            automatic: false,
+            partial_reps: Some(2),
+            to_failure: true,
+            side: None,
+            comment: None,
+        };
+        let serialized = serde_json::to_value(&element).unwrap();
+        assert_eq!(serialized["partial_reps"], json!(2));
+        assert_eq!(serialized["to_failure"], json!(true));
+        assert_eq!(
+            serde_json::from_value::<TrainingSessionElement>(serialized).unwrap(),
+            element
+        );
     }
 
     #[test]
@@ -1413,13 +6261,61 @@ mod tests {
     }
 
     #[rstest]
-    #[case(&*TRAINING_SESSION, 305)]
-    #[case(&*EMPTY_TRAINING_SESSION, 0)]
+    #[case::unspecified(&*TRAINING_SESSION, None, None, 305)]
+    #[case::total(&*TRAINING_SESSION, Some(WeightConvention::Total), Some(20.0), 305)]
+    #[case::per_side(&*TRAINING_SESSION, Some(WeightConvention::PerSide), Some(20.0), 805)]
+    #[case::per_dumbbell(&*TRAINING_SESSION, Some(WeightConvention::PerDumbbell), Some(2.5), 655)]
+    #[case::empty(&*EMPTY_TRAINING_SESSION, Some(WeightConvention::PerSide), Some(20.0), 0)]
     fn test_training_session_volume_load(
         #[case] training_session: &TrainingSession,
+        #[case] weight_convention: Option<WeightConvention>,
+        #[case] base_weight: Option<f32>,
         #[case] expected: u32,
     ) {
-        assert_eq!(training_session.volume_load(), expected);
+        let exercises = BTreeMap::from([(
+            1,
+            Exercise {
+                weight_convention,
+                base_weight,
+                archived: false,
+                ..exercise(1, &[])
+            },
+        )]);
+        assert_eq!(training_session.volume_load(&exercises), expected);
+    }
+
+    #[test]
+    fn test_training_session_volume_load_unknown_exercise() {
+        assert_eq!(TRAINING_SESSION.volume_load(&BTreeMap::new()), 305);
+    }
+
+    #[test]
+    fn test_training_session_volume_load_partial_reps() {
+        let set = |reps, partial_reps, weight| TrainingSessionElement::Set {
+            exercise_id: 1,
+            reps: Some(reps),
+            time: None,
+            weight,
+            rpe: None,
+            target_reps: None,
+            target_time: None,
+            target_weight: None,
+            target_rpe: None,
+            automatic: false,
+            partial_reps,
+            to_failure: true,
+            side: None,
+            comment: None,
+        };
+        let training_session = TrainingSession {
+            elements: vec![
+                set(8, Some(2), Some(80.0)),
+                set(5, Some(1), None),
+                set(6, None, Some(10.0)),
+            ],
+            ..TRAINING_SESSION.clone()
+        };
+        assert_eq!(training_session.volume_load(&BTreeMap::new()), 720 + 6 + 60);
     }
 
     #[rstest]
@@ -1444,6 +6340,11 @@ mod tests {
             Exercise {
                 id: 1,
                 name: String::from("A"),
+                weight_convention: None,
+                base_weight: None,
+                laterality: Laterality::Bilateral,
+                archived: false,
+                image: None,
                 muscles: vec![
                     ExerciseMuscle {
                         muscle_id: 11,
@@ -1481,6 +6382,94 @@ mod tests {
         );
     }
 
+    fn load_ratios(ratios: &[f32]) -> TrainingStats {
+        TrainingStats {
+            short_term_load: ratios
+                .iter()
+                .enumerate()
+                .map(|(i, r)| (from_num_days(i32::try_from(i).unwrap()), *r))
+                .collect(),
+            long_term_load: ratios
+                .iter()
+                .enumerate()
+                .map(|(i, _)| (from_num_days(i32::try_from(i).unwrap()), 1.0))
+                .collect(),
+        }
+    }
+
+    #[rstest]
+    #[case::empty(&[], 4, None)]
+    #[case::short_history(&[2.0; 6], 4, None)]
+    #[case::deload(
+        &[2.0; 7],
+        4,
+        Some(LoadRecommendation {
+            recommendation: Recommendation::Deload,
+            average_load_ratio: 2.0,
+            days_above_high: 7,
+        })
+    )]
+    #[case::too_few_days_above_high(
+        &[1.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0],
+        4,
+        Some(LoadRecommendation {
+            recommendation: Recommendation::Maintain,
+            average_load_ratio: 13.0 / 7.0,
+            days_above_high: 6,
+        })
+    )]
+    #[case::not_above_high_on_latest_day(
+        &[2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 1.0],
+        4,
+        Some(LoadRecommendation {
+            recommendation: Recommendation::Maintain,
+            average_load_ratio: 15.0 / 8.0,
+            days_above_high: 0,
+        })
+    )]
+    #[case::exactly_high(
+        &[1.5; 7],
+        4,
+        Some(LoadRecommendation {
+            recommendation: Recommendation::Maintain,
+            average_load_ratio: 1.5,
+            days_above_high: 0,
+        })
+    )]
+    #[case::increase(
+        &[0.5; 7],
+        4,
+        Some(LoadRecommendation {
+            recommendation: Recommendation::Increase,
+            average_load_ratio: 0.5,
+            days_above_high: 0,
+        })
+    )]
+    #[case::only_past_weeks(
+        &[0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+        1,
+        Some(LoadRecommendation {
+            recommendation: Recommendation::Maintain,
+            average_load_ratio: 1.0,
+            days_above_high: 0,
+        })
+    )]
+    fn test_training_stats_load_recommendation(
+        #[case] ratios: &[f32],
+        #[case] weeks: usize,
+        #[case] expected: Option<LoadRecommendation>,
+    ) {
+        assert_eq!(load_ratios(ratios).load_recommendation(weeks), expected);
+    }
+
+    #[test]
+    fn test_training_stats_load_recommendation_without_long_term_load() {
+        let mut training_stats = load_ratios(&[2.0; 7]);
+        training_stats.long_term_load[0].1 = 0.0;
+
+        assert_eq!(training_stats.load_recommendation(4), None);
+    }
+
     #[test]
     fn test_training_stats_clear() {
         let mut training_stats = TrainingStats {
@@ -1488,46 +6477,254 @@ mod tests {
             long_term_load: vec![(from_num_days(0), 8.0)],
         };
 
-        assert!(!training_stats.short_term_load.is_empty());
-        assert!(!training_stats.long_term_load.is_empty());
-
-        training_stats.clear();
+        assert!(!training_stats.short_term_load.is_empty());
+        assert!(!training_stats.long_term_load.is_empty());
+
+        training_stats.clear();
+
+        assert!(training_stats.short_term_load.is_empty());
+        assert!(training_stats.long_term_load.is_empty());
+    }
+
+    #[rstest]
+    #[case::no_sessions(&[], vec![(*TODAY, 0.0)], vec![])]
+    #[case::one_session(
+        &[&*TRAINING_SESSION],
+        vec![
+            (*TODAY - Duration::days(10), 10.0),
+            (*TODAY - Duration::days(9), 8.571_428),
+            (*TODAY - Duration::days(8), 7.142_857_6),
+            (*TODAY - Duration::days(7), 5.714_285_4),
+            (*TODAY - Duration::days(6), 4.285_714),
+            (*TODAY - Duration::days(5), 2.857_142_7),
+            (*TODAY - Duration::days(4), 1.428_570_7),
+            (*TODAY - Duration::days(3), 0.0),
+            (*TODAY - Duration::days(2), 0.0),
+            (*TODAY - Duration::days(1), 0.0),
+            (*TODAY, 0.0),
+        ],
+        vec![]
+    )]
+    fn test_training_stats(
+        #[case] training_sessions: &[&TrainingSession],
+        #[case] short_term_load: Vec<(NaiveDate, f32)>,
+        #[case] long_term_load: Vec<(NaiveDate, f32)>,
+    ) {
+        assert_eq!(
+            training_stats(training_sessions, &FixedClock(*TODAY)),
+            TrainingStats {
+                short_term_load,
+                long_term_load
+            }
+        );
+    }
+
+    #[test]
+    fn test_training_stats_until_today() {
+        let today = *TODAY;
+        let training_sessions = [
+            training_session_with_load(1, today - Duration::days(1), 8.0),
+            training_session_with_load(2, today, 8.0),
+        ];
+
+        let stats = training_stats(
+            &training_sessions.iter().collect::<Vec<_>>(),
+            &FixedClock(today),
+        );
+
+        assert_eq!(
+            stats
+                .short_term_load
+                .iter()
+                .map(|(date, _)| *date)
+                .collect::<Vec<_>>(),
+            vec![today - Duration::days(1), today]
+        );
+        assert!(stats.short_term_load[1].1 > stats.short_term_load[0].1);
+    }
+
+    #[test]
+    fn test_training_stats_session_after_today() {
+        let training_sessions = [training_session_with_load(
+            1,
+            *TODAY + Duration::days(1),
+            8.0,
+        )];
+
+        let stats = training_stats(
+            &training_sessions.iter().collect::<Vec<_>>(),
+            &FixedClock(*TODAY),
+        );
+
+        assert_eq!(
+            stats
+                .short_term_load
+                .iter()
+                .map(|(date, _)| *date)
+                .collect::<Vec<_>>(),
+            vec![*TODAY, *TODAY + Duration::days(1)]
+        );
+        assert!(stats.long_term_load.is_empty());
+    }
+
+    fn training_session_with_load(id: u32, date: NaiveDate, rpe: f32) -> TrainingSession {
+        let mut training_session = TRAINING_SESSION.clone();
+        training_session.id = id;
+        training_session.date = date;
+        for element in &mut training_session.elements {
+            if let TrainingSessionElement::Set { rpe: set_rpe, .. } = element {
+                *set_rpe = Some(rpe);
+            }
+        }
+        training_session
+    }
+
+    fn load_bits(values: &[(NaiveDate, f32)]) -> Vec<(NaiveDate, u32)> {
+        values.iter().map(|(d, v)| (*d, v.to_bits())).collect()
+    }
+
+    fn assert_training_stats_identical(actual: &TrainingStats, expected: &TrainingStats) {
+        assert_eq!(
+            load_bits(&actual.short_term_load),
+            load_bits(&expected.short_term_load)
+        );
+        assert_eq!(
+            load_bits(&actual.long_term_load),
+            load_bits(&expected.long_term_load)
+        );
+    }
+
+    #[test]
+    fn test_training_stats_update_changed_date() {
+        let mut training_sessions = [
+            training_session_with_load(1, *TODAY - Duration::days(60), 8.0),
+            training_session_with_load(2, *TODAY - Duration::days(40), 9.0),
+            training_session_with_load(3, *TODAY - Duration::days(20), 7.0),
+        ];
+        let mut stats = training_stats(
+            &training_sessions.iter().collect::<Vec<_>>(),
+            &FixedClock(*TODAY),
+        );
+
+        training_sessions[1].date = *TODAY - Duration::days(10);
+        stats.update(
+            &training_sessions.iter().collect::<Vec<_>>(),
+            &[*TODAY - Duration::days(40), *TODAY - Duration::days(10)],
+            &FixedClock(*TODAY),
+        );
+
+        assert_training_stats_identical(
+            &stats,
+            &training_stats(
+                &training_sessions.iter().collect::<Vec<_>>(),
+                &FixedClock(*TODAY),
+            ),
+        );
+    }
+
+    #[test]
+    fn test_training_stats_update_changed_range() {
+        let mut training_sessions = vec![training_session_with_load(
+            1,
+            *TODAY - Duration::days(30),
+            8.0,
+        )];
+        let mut stats = training_stats(
+            &training_sessions.iter().collect::<Vec<_>>(),
+            &FixedClock(*TODAY),
+        );
+
+        training_sessions.push(training_session_with_load(
+            2,
+            *TODAY - Duration::days(50),
+            9.0,
+        ));
+        stats.update(
+            &training_sessions.iter().collect::<Vec<_>>(),
+            &[*TODAY - Duration::days(50)],
+            &FixedClock(*TODAY + Duration::days(1)),
+        );
+
+        assert_training_stats_identical(
+            &stats,
+            &training_stats(
+                &training_sessions.iter().collect::<Vec<_>>(),
+                &FixedClock(*TODAY + Duration::days(1)),
+            ),
+        );
+    }
+
+    #[test]
+    fn test_training_stats_update_random_changes() {
+        // Linear congruential generator to get reproducible pseudo-random numbers
+        let mut state: u64 = 0x5eed;
+        let mut random = |n: u64| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 33) % n
+        };
+
+        for _ in 0..50 {
+            let mut training_sessions: BTreeMap<u32, TrainingSession> = BTreeMap::new();
+            let mut next_id = 1;
+            for _ in 0..random(30) {
+                training_sessions.insert(
+                    next_id,
+                    training_session_with_load(
+                        next_id,
+                        *TODAY - Duration::days(i64::try_from(random(100)).unwrap()),
+                        5.0 + f32::from(u8::try_from(random(11)).unwrap()) / 2.0,
+                    ),
+                );
+                next_id += 1;
+            }
+            let mut updated_stats = training_stats(
+                &training_sessions.values().collect::<Vec<_>>(),
+                &FixedClock(*TODAY),
+            );
+
+            for _ in 0..10 {
+                let mut changed_dates = vec![];
+                for _ in 0..=random(3) {
+                    let date = *TODAY - Duration::days(i64::try_from(random(100)).unwrap());
+                    let rpe = 5.0 + f32::from(u8::try_from(random(11)).unwrap()) / 2.0;
+                    let ids = training_sessions.keys().copied().collect::<Vec<_>>();
+                    let existing_id = if ids.is_empty() {
+                        None
+                    } else {
+                        Some(ids[usize::try_from(random(ids.len() as u64)).unwrap()])
+                    };
+                    match (random(3), existing_id) {
+                        (0, Some(id)) => {
+                            let removed = training_sessions.remove(&id).unwrap();
+                            changed_dates.push(removed.date);
+                        }
+                        (1, Some(id)) => {
+                            let previous = training_sessions
+                                .insert(id, training_session_with_load(id, date, rpe))
+                                .unwrap();
+                            changed_dates.push(previous.date);
+                            changed_dates.push(date);
+                        }
+                        _ => {
+                            training_sessions
+                                .insert(next_id, training_session_with_load(next_id, date, rpe));
+                            next_id += 1;
+                            changed_dates.push(date);
+                        }
+                    }
+                }
 
-        assert!(training_stats.short_term_load.is_empty());
-        assert!(training_stats.long_term_load.is_empty());
-    }
+                let training_sessions = training_sessions.values().collect::<Vec<_>>();
+                updated_stats.update(&training_sessions, &changed_dates, &FixedClock(*TODAY));
 
-    #[rstest]
-    #[case::no_sessions(&[], vec![(*TODAY, 0.0)], vec![])]
-    #[case::one_session(
-        &[&*TRAINING_SESSION],
-        vec![
-            (*TODAY - Duration::days(10), 10.0),
-            (*TODAY - Duration::days(9), 8.571_428),
-            (*TODAY - Duration::days(8), 7.142_857_6),
-            (*TODAY - Duration::days(7), 5.714_285_4),
-            (*TODAY - Duration::days(6), 4.285_714),
-            (*TODAY - Duration::days(5), 2.857_142_7),
-            (*TODAY - Duration::days(4), 1.428_570_7),
-            (*TODAY - Duration::days(3), 0.0),
-            (*TODAY - Duration::days(2), 0.0),
-            (*TODAY - Duration::days(1), 0.0),
-            (*TODAY, 0.0),
-        ],
-        vec![]
-    )]
-    fn test_training_stats(
-        #[case] training_sessions: &[&TrainingSession],
-        #[case] short_term_load: Vec<(NaiveDate, f32)>,
-        #[case] long_term_load: Vec<(NaiveDate, f32)>,
-    ) {
-        assert_eq!(
-            training_stats(training_sessions),
-            TrainingStats {
-                short_term_load,
-                long_term_load
+                assert_training_stats_identical(
+                    &updated_stats,
+                    &training_stats(&training_sessions, &FixedClock(*TODAY)),
+                );
             }
-        );
+        }
     }
 
     #[rstest]
@@ -1564,6 +6761,487 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_weekly_stimulus_per_muscle() {
+        let training_session = |date: NaiveDate| {
+            let mut training_session = TRAINING_SESSION.clone();
+            training_session.date = date;
+            training_session
+        };
+        let training_sessions = [
+            training_session(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 7).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 22).unwrap()),
+        ];
+        let interval = Interval {
+            first: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            last: NaiveDate::from_ymd_opt(2024, 1, 21).unwrap(),
+        };
+        let week = |week: u32| {
+            NaiveDate::from_isoywd_opt(2024, week, chrono::Weekday::Mon)
+                .unwrap()
+                .iso_week()
+        };
+
+        let result = weekly_stimulus_per_muscle(
+            &training_sessions.iter().collect::<Vec<_>>(),
+            &EXERCISES,
+            &interval,
+        );
+
+        assert_eq!(result.len(), 3 * Muscle::iter().len());
+        assert_eq!(result[&(week(1), 11)], 200);
+        assert_eq!(result[&(week(1), 31)], 100);
+        assert_eq!(result[&(week(2), 11)], 0);
+        assert_eq!(result[&(week(2), 31)], 0);
+        assert_eq!(result[&(week(3), 11)], 100);
+        assert_eq!(result[&(week(3), 31)], 50);
+        assert_eq!(result[&(week(1), 1)], 0);
+    }
+
+    #[test]
+    fn test_total_stimulus_per_muscle() {
+        let training_session = |date: NaiveDate| {
+            let mut training_session = TRAINING_SESSION.clone();
+            training_session.date = date;
+            training_session
+        };
+        let training_sessions = [
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 7).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()),
+        ];
+        let interval = Interval {
+            first: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            last: NaiveDate::from_ymd_opt(2024, 1, 7).unwrap(),
+        };
+
+        let result = total_stimulus_per_muscle(
+            &training_sessions.iter().collect::<Vec<_>>(),
+            &EXERCISES,
+            &interval,
+        );
+
+        assert_eq!(result.len(), Muscle::iter().len());
+        assert_eq!(result[&11], 200);
+        assert_eq!(result[&31], 100);
+        assert_eq!(result[&1], 0);
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_exercise_usage_stats() {
+        let set = |exercise_id, reps, time, weight| TrainingSessionElement::Set {
+            exercise_id,
+            reps,
+            time,
+            weight,
+            rpe: None,
+            target_reps: Some(RepsRange::single(10)),
+            target_time: None,
+            target_weight: Some(100.0),
+            target_rpe: None,
+            automatic: false,
+            partial_reps: None,
+            to_failure: false,
+            side: None,
+            comment: None,
+        };
+        let training_session = |id, date, elements| TrainingSession {
+            id,
+            routine_id: None,
+            date,
+            notes: None,
+            start_time: None,
+            end_time: None,
+            elements,
+        };
+        let date = |day| NaiveDate::from_ymd_opt(2024, 2, day).unwrap();
+        let training_sessions = [
+            training_session(
+                1,
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                vec![
+                    set(1, Some(5), None, Some(100.0)),
+                    set(2, Some(10), None, Some(50.0)),
+                ],
+            ),
+            training_session(
+                2,
+                date(10),
+                vec![
+                    set(1, Some(8), None, Some(90.0)),
+                    TrainingSessionElement::Rest {
+                        target_time: Some(60),
+                        automatic: true,
+                    },
+                    set(1, None, Some(30), None),
+                    set(1, Some(10), None, None),
+                ],
+            ),
+            training_session(3, date(20), vec![set(1, None, None, None)]),
+            training_session(4, date(25), vec![set(1, Some(1), None, Some(120.0))]),
+        ];
+
+        assert_eq!(
+            exercise_usage_stats(
+                &training_sessions.iter().collect::<Vec<_>>(),
+                &exercise(1, &[]),
+                date(20)
+            ),
+            ExerciseUsageStats {
+                sets: 5,
+                volume_load: 500 + 720 + 10 + 120,
+                best_set: Some(BestSet {
+                    date: date(25),
+                    reps: 1,
+                    weight: 120.0,
+                    one_rep_max: 120.0
+                }),
+                last_performed: Some(date(25)),
+                recent_sets: 3,
+            }
+        );
+        assert_eq!(
+            exercise_usage_stats(
+                &training_sessions.iter().collect::<Vec<_>>(),
+                &Exercise {
+                    weight_convention: Some(WeightConvention::PerSide),
+                    base_weight: Some(20.0),
+                    archived: false,
+                    ..exercise(1, &[])
+                },
+                date(20)
+            ),
+            ExerciseUsageStats {
+                sets: 5,
+                volume_load: 1100 + 1600 + 10 + 260,
+                best_set: Some(BestSet {
+                    date: date(25),
+                    reps: 1,
+                    weight: 120.0,
+                    one_rep_max: 260.0
+                }),
+                last_performed: Some(date(25)),
+                recent_sets: 3,
+            }
+        );
+        assert_eq!(
+            exercise_usage_stats(
+                &training_sessions.iter().collect::<Vec<_>>(),
+                &exercise(3, &[]),
+                date(20)
+            ),
+            ExerciseUsageStats::default()
+        );
+    }
+
+    #[rstest]
+    #[case::one_rep_max(
+        vec![
+            (Side::Left, 5, Some(100.0)),
+            (Side::Right, 5, Some(90.0)),
+            (Side::Left, 8, Some(80.0)),
+            (Side::Right, 10, None),
+        ],
+        Some((AsymmetryMetric::OneRepMax, 116.666_67, 105.0))
+    )]
+    #[case::reps(
+        vec![(Side::Left, 12, None), (Side::Right, 15, None), (Side::Right, 10, Some(5.0))],
+        Some((AsymmetryMetric::Reps, 12.0, 15.0))
+    )]
+    #[case::single_side(vec![(Side::Left, 5, Some(100.0))], None)]
+    #[case::empty(vec![], None)]
+    fn test_side_asymmetry(
+        #[case] sets: Vec<(Side, u32, Option<f32>)>,
+        #[case] expected: Option<(AsymmetryMetric, f32, f32)>,
+    ) {
+        let training_session = TrainingSession {
+            elements: sets
+                .into_iter()
+                .map(|(side, reps, weight)| with_side(session_set(1, Some(reps), weight), side))
+                .chain([
+                    session_set(1, Some(20), Some(200.0)),
+                    with_side(session_set(2, Some(20), Some(200.0)), Side::Left),
+                ])
+                .collect(),
+            ..TRAINING_SESSION.clone()
+        };
+        let result = side_asymmetry(&[&training_session], &exercise(1, &[]));
+        assert_eq!(result.map(|r| r.metric), expected.map(|e| e.0));
+        if let (Some(result), Some((_, left, right))) = (result, expected) {
+            assert_approx_eq!(result.left, left, 0.001);
+            assert_approx_eq!(result.right, right, 0.001);
+        }
+    }
+
+    #[rstest]
+    #[case(100.0, 90.0, 0.1, Some(Side::Right))]
+    #[case(90.0, 100.0, -0.1, Some(Side::Left))]
+    #[case(12.0, 12.0, 0.0, None)]
+    #[case(0.0, 0.0, 0.0, None)]
+    fn test_side_asymmetry_relative_difference(
+        #[case] left: f32,
+        #[case] right: f32,
+        #[case] expected_difference: f32,
+        #[case] expected_weaker_side: Option<Side>,
+    ) {
+        let asymmetry = SideAsymmetry {
+            metric: AsymmetryMetric::OneRepMax,
+            left,
+            right,
+        };
+        assert_approx_eq!(asymmetry.relative_difference(), expected_difference);
+        assert_eq!(asymmetry.weaker_side(), expected_weaker_side);
+    }
+
+    #[rstest]
+    #[case::first_ever_exercise(
+        vec![session_set(2, Some(5), Some(80.0))],
+        vec![session_set(1, Some(5), Some(80.0))],
+        vec![]
+    )]
+    #[case::tie(
+        vec![session_set(1, Some(5), Some(80.0))],
+        vec![session_set(1, Some(5), Some(80.0)), session_set(1, Some(4), Some(80.0))],
+        vec![]
+    )]
+    #[case::heavier_weight(
+        vec![session_set(1, Some(5), Some(80.0))],
+        vec![session_set(1, Some(5), Some(85.0))],
+        vec![
+            RecordKind::OneRepMax(progression::one_rep_max(5, 85.0, None).unwrap()),
+            RecordKind::Weight(85.0),
+        ]
+    )]
+    #[case::more_reps(
+        vec![session_set(1, Some(5), Some(80.0)), session_set(1, Some(8), Some(60.0))],
+        vec![session_set(1, Some(6), Some(80.0)), session_set(1, Some(7), Some(60.0))],
+        vec![
+            RecordKind::OneRepMax(progression::one_rep_max(6, 80.0, None).unwrap()),
+            RecordKind::Reps { reps: 6, weight: Some(80.0) },
+        ]
+    )]
+    #[case::more_reps_without_weight(
+        vec![session_set(1, Some(10), None)],
+        vec![session_set(1, Some(12), None)],
+        vec![RecordKind::Reps { reps: 12, weight: None }]
+    )]
+    #[case::first_weight(
+        vec![session_set(1, Some(10), None)],
+        vec![session_set(1, Some(8), Some(10.0))],
+        vec![]
+    )]
+    #[case::longer_time(
+        vec![session_time_set(1, 30, None)],
+        vec![session_time_set(1, 45, None), session_time_set(1, 30, None)],
+        vec![RecordKind::Time { time: 45, weight: None }]
+    )]
+    #[case::longer_time_with_other_weight(
+        vec![session_time_set(1, 30, None)],
+        vec![session_time_set(1, 45, Some(10.0))],
+        vec![]
+    )]
+    #[case::time_without_previous_time(
+        vec![session_set(1, Some(10), None)],
+        vec![session_time_set(1, 45, None)],
+        vec![]
+    )]
+    #[case::unperformed_set(
+        vec![session_set(1, Some(5), Some(80.0))],
+        vec![session_set(1, None, Some(100.0))],
+        vec![]
+    )]
+    fn test_personal_records(
+        #[case] previous_elements: Vec<TrainingSessionElement>,
+        #[case] elements: Vec<TrainingSessionElement>,
+        #[case] expected: Vec<RecordKind>,
+    ) {
+        let previous_training_session = TrainingSession {
+            elements: previous_elements,
+            ..history_session(1, None, 7, &[])
+        };
+        let training_session = TrainingSession {
+            elements,
+            ..history_session(2, None, 0, &[])
+        };
+
+        assert_eq!(
+            personal_records(
+                &training_session,
+                &[&previous_training_session, &training_session],
+                &BTreeMap::new()
+            ),
+            expected
+                .into_iter()
+                .map(|kind| PersonalRecord {
+                    exercise_id: 1,
+                    kind
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_personal_records_history() {
+        let session = |id, days_ago, reps, weight| TrainingSession {
+            elements: vec![session_set(1, Some(reps), Some(weight))],
+            ..history_session(id, None, days_ago, &[])
+        };
+        let training_session = session(3, 1, 6, 80.0);
+        let training_sessions = [
+            session(1, 2, 5, 80.0),
+            session(2, 1, 5, 60.0),
+            session(4, 1, 10, 80.0),
+            session(5, 0, 10, 90.0),
+            training_session.clone(),
+        ];
+
+        assert_eq!(
+            personal_records(
+                &training_session,
+                &training_sessions.iter().collect::<Vec<_>>(),
+                &EXERCISES
+            ),
+            vec![
+                PersonalRecord {
+                    exercise_id: 1,
+                    kind: RecordKind::OneRepMax(progression::one_rep_max(6, 80.0, None).unwrap())
+                },
+                PersonalRecord {
+                    exercise_id: 1,
+                    kind: RecordKind::Reps {
+                        reps: 6,
+                        weight: Some(80.0)
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly_summary() {
+        let training_session = |date: NaiveDate| {
+            let mut training_session = TRAINING_SESSION.clone();
+            training_session.date = date;
+            training_session
+        };
+        let training_sessions = [
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 7).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 10).unwrap()),
+            training_session(NaiveDate::from_ymd_opt(2024, 1, 12).unwrap()),
+        ];
+        let training_sessions = training_sessions.iter().collect::<Vec<_>>();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 11).unwrap();
+
+        assert_eq!(
+            weekly_summary(&training_sessions, &EXERCISES, today.iso_week(), today),
+            Some(WeeklySummary {
+                sessions: 2,
+                hard_sets: 2 * TRAINING_SESSION.set_volume(),
+                volume_load: 2 * TRAINING_SESSION.volume_load(&EXERCISES),
+                tut: Some(2 * TRAINING_SESSION.tut().unwrap()),
+                hard_sets_per_muscle: vec![(11, 2.0), (31, 1.0)],
+            })
+        );
+        assert_eq!(
+            weekly_summary(
+                &training_sessions,
+                &EXERCISES,
+                (today - Duration::days(7)).iso_week(),
+                today
+            ),
+            Some(WeeklySummary {
+                sessions: 1,
+                hard_sets: TRAINING_SESSION.set_volume(),
+                volume_load: TRAINING_SESSION.volume_load(&EXERCISES),
+                tut: TRAINING_SESSION.tut(),
+                hard_sets_per_muscle: vec![(11, 1.0), (31, 0.5)],
+            })
+        );
+        assert_eq!(
+            weekly_summary(
+                &training_sessions,
+                &EXERCISES,
+                (today + Duration::days(7)).iso_week(),
+                today
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_weekly_summary_only_future_sessions() {
+        let mut training_session = TRAINING_SESSION.clone();
+        training_session.date = NaiveDate::from_ymd_opt(2024, 1, 12).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 11).unwrap();
+
+        assert_eq!(
+            weekly_summary(&[&training_session], &EXERCISES, today.iso_week(), today),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::no_sessions(&[], 1, (2021, 1, 13), 0, 0)]
+    #[case::current_week_pending(
+        &[(2020, 12, 21), (2020, 12, 31), (2021, 1, 2), (2021, 1, 5)], 1, (2021, 1, 13), 3, 3
+    )]
+    #[case::current_week_met(
+        &[(2020, 12, 21), (2020, 12, 31), (2021, 1, 5), (2021, 1, 11)], 1, (2021, 1, 13), 4, 4
+    )]
+    #[case::missed_week(
+        &[(2020, 12, 21), (2020, 12, 31), (2021, 1, 5), (2021, 1, 19)], 1, (2021, 1, 20), 1, 3
+    )]
+    #[case::week_missed_before_current_week(
+        &[(2020, 12, 21), (2020, 12, 31), (2021, 1, 5)], 1, (2021, 1, 18), 0, 3
+    )]
+    #[case::week_across_new_year(
+        &[(2020, 12, 28), (2020, 12, 29), (2020, 12, 30), (2020, 12, 31), (2021, 1, 1), (2021, 1, 2), (2021, 1, 3)],
+        7,
+        (2021, 1, 3),
+        1,
+        1
+    )]
+    #[case::week_across_new_year_incomplete(
+        &[(2020, 12, 28), (2020, 12, 29), (2020, 12, 30), (2020, 12, 31), (2021, 1, 1), (2021, 1, 2), (2021, 1, 3)],
+        7,
+        (2021, 1, 2),
+        0,
+        0
+    )]
+    #[case::week_across_new_year_followed_by_pending_week(
+        &[(2020, 12, 28), (2020, 12, 29), (2020, 12, 30), (2020, 12, 31), (2021, 1, 1), (2021, 1, 2), (2021, 1, 3), (2021, 1, 4)],
+        7,
+        (2021, 1, 10),
+        1,
+        1
+    )]
+    #[case::sessions_on_same_day(
+        &[(2020, 12, 28), (2020, 12, 28), (2021, 1, 4)], 2, (2021, 1, 5), 1, 1
+    )]
+    fn test_training_streak(
+        #[case] dates: &[(i32, u32, u32)],
+        #[case] weekly_target: u32,
+        #[case] today: (i32, u32, u32),
+        #[case] current: u32,
+        #[case] longest: u32,
+    ) {
+        let date = |(y, m, d)| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            training_streak(
+                &dates.iter().copied().map(date).collect::<Vec<_>>(),
+                weekly_target,
+                date(today)
+            ),
+            TrainingStreak { current, longest }
+        );
+    }
+
     #[test]
     fn test_muscle_id() {
         for muscle in Muscle::iter() {
@@ -1602,12 +7280,185 @@ mod tests {
 
     #[test]
     fn test_exercise_filter_is_empty() {
-        assert!(ExerciseFilter {
-            muscles: HashSet::new()
+        assert!(ExerciseFilter::default().is_empty());
+        assert!(!ExerciseFilter {
+            muscles: HashSet::from([Muscle::Pecs]),
+            archived: false,
         }
         .is_empty());
     }
 
+    #[rstest]
+    #[case::empty(HashSet::new(), true)]
+    #[case::one_muscle(HashSet::from([Muscle::Pecs]), true)]
+    #[case::all_muscles(HashSet::from([Muscle::Pecs, Muscle::FrontDelts]), true)]
+    #[case::other_muscle(HashSet::from([Muscle::Lats]), false)]
+    #[case::partially_matching(HashSet::from([Muscle::Pecs, Muscle::Lats]), false)]
+    fn test_exercise_filter_matches(#[case] muscles: HashSet<Muscle>, #[case] expected: bool) {
+        assert_eq!(
+            ExerciseFilter {
+                muscles,
+                archived: false
+            }
+            .matches(&EXERCISES[&1]),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::not_archived(false, false, true)]
+    #[case::archived(true, false, false)]
+    #[case::archived_filter(true, true, true)]
+    #[case::archived_filter_not_archived(false, true, false)]
+    fn test_exercise_filter_matches_archived(
+        #[case] archived: bool,
+        #[case] filter_archived: bool,
+        #[case] expected: bool,
+    ) {
+        let exercise = Exercise {
+            archived,
+            ..EXERCISES[&1].clone()
+        };
+        assert_eq!(
+            ExerciseFilter {
+                muscles: HashSet::new(),
+                archived: filter_archived
+            }
+            .matches(&exercise),
+            expected
+        );
+    }
+
+    static SEARCH_INDEX: std::sync::LazyLock<SearchIndex> = std::sync::LazyLock::new(|| {
+        SearchIndex::new(
+            &[
+                TrainingSession {
+                    id: 1,
+                    routine_id: None,
+                    date: from_num_days(1),
+                    notes: Some(String::from("Felt strong today, new PR on Squat")),
+                    start_time: None,
+                    end_time: None,
+                    elements: vec![],
+                },
+                TrainingSession {
+                    id: 2,
+                    routine_id: None,
+                    date: from_num_days(2),
+                    notes: Some(String::from("squat felt heavy")),
+                    start_time: None,
+                    end_time: None,
+                    elements: vec![],
+                },
+                TrainingSession {
+                    id: 3,
+                    routine_id: None,
+                    date: from_num_days(3),
+                    notes: None,
+                    start_time: None,
+                    end_time: None,
+                    elements: vec![],
+                },
+            ],
+            &[Routine {
+                id: 1,
+                name: String::from("Legs"),
+                notes: Some(String::from("Focus on squat depth")),
+                archived: false,
+                sections: vec![],
+                schedule: vec![],
+                tags: vec![],
+            }],
+            &[
+                Exercise {
+                    id: 1,
+                    name: String::from("Squat"),
+                    weight_convention: None,
+                    base_weight: None,
+                    laterality: Laterality::Bilateral,
+                    archived: false,
+                    image: None,
+                    muscles: vec![],
+                },
+                Exercise {
+                    id: 2,
+                    name: String::from("Bench Press"),
+                    weight_convention: None,
+                    base_weight: None,
+                    laterality: Laterality::Bilateral,
+                    archived: false,
+                    image: None,
+                    muscles: vec![],
+                },
+            ],
+        )
+    });
+
+    fn search_snippet(prefix: &str, highlight: &str, suffix: &str) -> Snippet {
+        Snippet {
+            prefix: prefix.to_string(),
+            highlight: highlight.to_string(),
+            suffix: suffix.to_string(),
+        }
+    }
+
+    #[rstest]
+    #[case::empty_query("", vec![])]
+    #[case::whitespace_query("  ", vec![])]
+    #[case::case_insensitive(
+        "SQUAT",
+        vec![
+            SearchHit::TrainingSession(2, from_num_days(2), search_snippet("", "squat", " felt heavy")),
+            SearchHit::TrainingSession(1, from_num_days(1), search_snippet("Felt strong today, new PR on ", "Squat", "")),
+            SearchHit::Routine(1, search_snippet("Focus on ", "squat", " depth")),
+            SearchHit::Exercise(1, search_snippet("", "Squat", "")),
+        ]
+    )]
+    #[case::multiple_terms(
+        "squat felt",
+        vec![
+            SearchHit::TrainingSession(2, from_num_days(2), search_snippet("", "squat", " felt heavy")),
+            SearchHit::TrainingSession(1, from_num_days(1), search_snippet("", "Felt", " strong today, new PR on Squat")),
+        ]
+    )]
+    #[case::partial_word(
+        "pres",
+        vec![SearchHit::Exercise(2, search_snippet("Bench ", "Pres", "s"))]
+    )]
+    #[case::not_all_terms_matching("squat bench", vec![])]
+    fn test_search_index_search(#[case] query: &str, #[case] expected: Vec<SearchHit>) {
+        assert_eq!(SEARCH_INDEX.search(query), expected);
+    }
+
+    #[test]
+    fn test_search_index_search_long_text() {
+        let index = SearchIndex::new(
+            &[],
+            &[],
+            &[Exercise {
+                id: 1,
+                name: format!("{}\nneedle {}", "a".repeat(40), "b".repeat(40)),
+                weight_convention: None,
+                base_weight: None,
+                laterality: Laterality::Bilateral,
+                archived: false,
+                image: None,
+                muscles: vec![],
+            }],
+        );
+        assert_eq!(
+            index.search("NEEDLE"),
+            vec![SearchHit::Exercise(
+                1,
+                search_snippet(
+                    &format!("…{} ", "a".repeat(29)),
+                    "needle",
+                    &format!(" {}…", "b".repeat(29))
+                )
+            )]
+        );
+    }
+
     #[test]
     fn test_body_weight_serde() {
         let obj = BodyWeight {
@@ -1673,6 +7524,284 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::normal(70.0, 175.0, Some(22.857_143))]
+    #[case::fractional_height(80.0, 182.5, Some(24.019_516))]
+    #[case::zero_height(70.0, 0.0, None)]
+    #[case::negative_height(70.0, -175.0, None)]
+    #[case::infinite_height(70.0, f32::INFINITY, None)]
+    #[case::nan_height(70.0, f32::NAN, None)]
+    #[case::zero_weight(0.0, 175.0, None)]
+    fn test_bmi(#[case] weight: f32, #[case] height: f32, #[case] expected: Option<f32>) {
+        assert_eq!(bmi(weight, height), expected);
+    }
+
+    #[test]
+    fn test_avg_weekly_change() {
+        assert_eq!(
+            avg_weekly_change(
+                &BTreeMap::new(),
+                Some(&BodyWeight {
+                    date: from_num_days(1),
+                    weight: 70.0
+                }),
+                14
+            ),
+            None
+        );
+        assert_eq!(
+            avg_weekly_change(
+                &BTreeMap::from([(
+                    from_num_days(0),
+                    BodyWeight {
+                        date: from_num_days(0),
+                        weight: 70.0
+                    }
+                )]),
+                Some(&BodyWeight {
+                    date: from_num_days(7),
+                    weight: 70.0
+                }),
+                14
+            )
+            .map(|change| change.percent),
+            Some(0.0)
+        );
+        assert_approx_eq!(
+            avg_weekly_change(
+                &BTreeMap::from([(
+                    from_num_days(0),
+                    BodyWeight {
+                        date: from_num_days(0),
+                        weight: 70.0
+                    }
+                )]),
+                Some(&BodyWeight {
+                    date: from_num_days(7),
+                    weight: 70.7
+                }),
+                14
+            )
+            .unwrap()
+            .percent,
+            1.0,
+            0.001
+        );
+        assert_approx_eq!(
+            avg_weekly_change(
+                &BTreeMap::from([
+                    (
+                        from_num_days(0),
+                        BodyWeight {
+                            date: from_num_days(0),
+                            weight: 69.0
+                        }
+                    ),
+                    (
+                        from_num_days(2),
+                        BodyWeight {
+                            date: from_num_days(2),
+                            weight: 71.0
+                        }
+                    )
+                ]),
+                Some(&BodyWeight {
+                    date: from_num_days(8),
+                    weight: 69.44
+                }),
+                14
+            )
+            .unwrap()
+            .percent,
+            -0.8,
+            0.001
+        );
+    }
+
+    fn body_weight_series(values: &[(i32, f32)]) -> BTreeMap<NaiveDate, BodyWeight> {
+        values
+            .iter()
+            .map(|(day, weight)| {
+                let date = from_num_days(*day);
+                (
+                    date,
+                    BodyWeight {
+                        date,
+                        weight: *weight,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_avg_weekly_change_single_value() {
+        let avg_body_weight = body_weight_series(&[(10, 80.0)]);
+
+        assert_eq!(
+            avg_weekly_change(
+                &avg_body_weight,
+                avg_body_weight.get(&from_num_days(10)),
+                14
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_avg_weekly_change_with_gap() {
+        let avg_body_weight = body_weight_series(&[
+            (0, 82.0),
+            (3, 81.0),
+            (6, 80.0),
+            (40, 79.0),
+            (42, 78.8),
+            (44, 78.6),
+        ]);
+
+        let change = avg_weekly_change(
+            &avg_body_weight,
+            avg_body_weight.get(&from_num_days(44)),
+            14,
+        )
+        .unwrap();
+
+        assert_eq!(change.days, 4);
+        assert_approx_eq!(change.weight, -0.7, 0.001);
+        assert_approx_eq!(change.percent, -0.886, 0.001);
+        assert_eq!(
+            avg_weekly_change(
+                &avg_body_weight,
+                avg_body_weight.get(&from_num_days(40)),
+                14
+            ),
+            None
+        );
+        assert_eq!(
+            avg_weekly_change(
+                &avg_body_weight,
+                avg_body_weight.get(&from_num_days(44)),
+                40
+            )
+            .map(|change| change.days),
+            Some(7)
+        );
+    }
+
+    fn series(values: &[(i32, f32)]) -> Vec<(NaiveDate, f32)> {
+        values
+            .iter()
+            .map(|(day, value)| (from_num_days(*day), *value))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::empty(&[], 28, None)]
+    #[case::single_value(&[(0, 80.0)], 28, None)]
+    #[case::short(&[(10, 80.0), (20, 81.0), (30, 82.0)], 28, None)]
+    #[case::flat(&[(0, 80.0), (14, 80.5), (28, 80.4)], 28, Some((TrendDirection::Flat, 0.5)))]
+    #[case::up(&[(0, 100.0), (14, 105.0), (28, 110.0)], 28, Some((TrendDirection::Up, 10.0)))]
+    #[case::down(&[(0, 80.0), (28, 76.0)], 28, Some((TrendDirection::Down, -5.0)))]
+    #[case::start_within_series(
+        &[(0, 50.0), (10, 100.0), (20, 90.0), (38, 120.0)],
+        28,
+        Some((TrendDirection::Up, 20.0))
+    )]
+    #[case::gap(&[(0, 100.0), (2, 120.0), (40, 90.0)], 28, Some((TrendDirection::Down, -25.0)))]
+    #[case::large_gap(&[(0, 100.0), (70, 90.0)], 28, None)]
+    #[case::zero(&[(0, 0.0), (28, 10.0)], 28, None)]
+    #[case::no_window(&[(0, 80.0), (28, 76.0)], 0, None)]
+    fn test_trend(
+        #[case] values: &[(i32, f32)],
+        #[case] window_days: i64,
+        #[case] expected: Option<(TrendDirection, f32)>,
+    ) {
+        let trend = trend(&series(values), window_days);
+
+        assert_eq!(trend.map(|t| t.direction), expected.map(|e| e.0));
+        if let (Some(trend), Some((_, percent))) = (trend, expected) {
+            assert_approx_eq!(trend.percent, percent, 0.001);
+        }
+    }
+
+    #[test]
+    fn test_avg_weekly_change_dense_data() {
+        #[allow(clippy::cast_precision_loss)]
+        let avg_body_weight = body_weight_series(
+            &(0..=20)
+                .map(|day| (day, 80.0 - day as f32 * 0.1))
+                .collect::<Vec<_>>(),
+        );
+
+        let change = avg_weekly_change(
+            &avg_body_weight,
+            avg_body_weight.get(&from_num_days(20)),
+            14,
+        )
+        .unwrap();
+
+        assert_eq!(change.days, 7);
+        assert_approx_eq!(change.weight, -0.7, 0.001);
+        assert_approx_eq!(change.percent, -0.7 / 78.7 * 100.0, 0.001);
+    }
+
+    #[rstest]
+    #[case::no_data(&[], 80.0, BodyWeightGoalDirection::Lose, None)]
+    #[case::reached_exactly(&[(0, 81.0), (7, 80.0)], 80.0, BodyWeightGoalDirection::Lose, Some(BodyWeightGoalProjection::Reached))]
+    #[case::reached_lose(&[(0, 81.0), (7, 79.5)], 80.0, BodyWeightGoalDirection::Lose, Some(BodyWeightGoalProjection::Reached))]
+    #[case::reached_gain(&[(0, 81.0), (7, 80.5)], 80.0, BodyWeightGoalDirection::Gain, Some(BodyWeightGoalProjection::Reached))]
+    #[case::not_enough_data(&[(7, 82.0)], 80.0, BodyWeightGoalDirection::Lose, None)]
+    #[case::expected_lose(
+        &[(0, 82.5), (7, 82.0)],
+        80.0,
+        BodyWeightGoalDirection::Lose,
+        Some(BodyWeightGoalProjection::Expected { weekly_change: -0.5, date: from_num_days(35) })
+    )]
+    #[case::expected_gain(
+        &[(0, 78.0), (4, 78.5), (10, 79.0)],
+        80.0,
+        BodyWeightGoalDirection::Gain,
+        Some(BodyWeightGoalProjection::Expected { weekly_change: 0.625, date: from_num_days(22) })
+    )]
+    #[case::flat(&[(0, 82.0), (7, 82.0)], 80.0, BodyWeightGoalDirection::Lose, None)]
+    #[case::away_from_goal(&[(0, 81.5), (7, 82.0)], 80.0, BodyWeightGoalDirection::Lose, None)]
+    #[case::distant(
+        &[(0, 82.015_625), (7, 82.0)],
+        80.0,
+        BodyWeightGoalDirection::Lose,
+        Some(BodyWeightGoalProjection::Distant { weekly_change: -0.015_625 })
+    )]
+    fn test_body_weight_goal_projection(
+        #[case] avg_body_weight: &[(i32, f32)],
+        #[case] goal_weight: f32,
+        #[case] direction: BodyWeightGoalDirection,
+        #[case] expected: Option<BodyWeightGoalProjection>,
+    ) {
+        assert_eq!(
+            body_weight_goal_projection(
+                &avg_body_weight
+                    .iter()
+                    .map(|(day, weight)| {
+                        let date = from_num_days(*day);
+                        (
+                            date,
+                            BodyWeight {
+                                date,
+                                weight: *weight,
+                            },
+                        )
+                    })
+                    .collect(),
+                &BodyWeightGoal {
+                    weight: goal_weight,
+                    direction
+                },
+                14
+            ),
+            expected
+        );
+    }
+
     #[test]
     fn test_body_fat_serde() {
         let obj = BodyFat {
@@ -1802,8 +7931,90 @@ mod tests {
         #[case] expected_jp3: Option<f32>,
         #[case] expected_jp7: Option<f32>,
     ) {
-        assert_eq!(body_fat.jp3(sex), expected_jp3);
-        assert_eq!(body_fat.jp7(sex), expected_jp7);
+        assert_eq!(body_fat.jp3(sex, DEFAULT_AGE), expected_jp3);
+        assert_eq!(body_fat.jp7(sex, DEFAULT_AGE), expected_jp7);
+    }
+
+    #[rstest]
+    #[case::female(0, 20, Some(16.685_242), Some(16.786_652))]
+    #[case::female_older(0, 50, Some(18.529_907), Some(18.486_847))]
+    #[case::male(1, 20, Some(9.500_153), Some(10.484_131))]
+    #[case::male_older(1, 50, Some(12.817_688), Some(14.218_689))]
+    fn test_body_fat_jp_age(
+        #[case] sex: u8,
+        #[case] age: u32,
+        #[case] expected_jp3: Option<f32>,
+        #[case] expected_jp7: Option<f32>,
+    ) {
+        let body_fat = BodyFat {
+            date: NaiveDate::from_ymd_opt(2020, 2, 2).unwrap(),
+            chest: Some(5),
+            abdominal: Some(15),
+            thigh: Some(15),
+            tricep: Some(15),
+            subscapular: Some(10),
+            suprailiac: Some(10),
+            midaxillary: Some(10),
+        };
+        assert_eq!(body_fat.jp3(sex, age), expected_jp3);
+        assert_eq!(body_fat.jp7(sex, age), expected_jp7);
+    }
+
+    #[test]
+    fn test_body_measurement_serde() {
+        let obj = BodyMeasurement {
+            date: NaiveDate::from_ymd_opt(2020, 2, 2).unwrap(),
+            waist: Some(80.5),
+            hips: Some(95.0),
+            neck: Some(38.0),
+            upper_arm: None,
+            thigh: Some(55.5),
+            chest: None,
+        };
+        let serialized = json!(obj);
+        let deserialized: BodyMeasurement = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, obj);
+    }
+
+    // The expected values of the female and male cases are taken from the equations in DoD
+    // Instruction 1308.3, which are based on inches, for measurements of 64/28/38/13 in and
+    // 70/34/15 in (height/waist/hips/neck). The metric equations are published separately and
+    // deviate from these by a few tenths of a percentage point.
+    #[rstest]
+    #[case::female(0, 162.56, Some(71.12), Some(33.02), Some(96.52), Some(26.59))]
+    #[case::female_without_hips(0, 165.0, Some(70.0), Some(34.0), None, None)]
+    #[case::male(1, 177.8, Some(86.36), Some(38.1), None, Some(17.51))]
+    #[case::male_with_hips(1, 177.8, Some(86.36), Some(38.1), Some(100.0), Some(17.51))]
+    #[case::missing_waist(1, 178.0, None, Some(38.0), None, None)]
+    #[case::missing_neck(1, 178.0, Some(90.0), None, None, None)]
+    #[case::neck_larger_than_waist(1, 178.0, Some(38.0), Some(40.0), None, None)]
+    #[case::zero_height(1, 0.0, Some(90.0), Some(38.0), None, None)]
+    #[case::invalid_sex(2, 178.0, Some(90.0), Some(38.0), Some(100.0), None)]
+    fn test_body_measurement_navy(
+        #[case] sex: u8,
+        #[case] height: f32,
+        #[case] waist: Option<f32>,
+        #[case] neck: Option<f32>,
+        #[case] hips: Option<f32>,
+        #[case] expected: Option<f32>,
+    ) {
+        let body_measurement = BodyMeasurement {
+            date: NaiveDate::from_ymd_opt(2020, 2, 2).unwrap(),
+            waist,
+            hips,
+            neck,
+            upper_arm: None,
+            thigh: None,
+            chest: None,
+        };
+        let result = body_measurement.navy(sex, height);
+        assert_eq!(result.is_some(), expected.is_some());
+        if let (Some(result), Some(expected)) = (result, expected) {
+            assert!(
+                (result - expected).abs() < 0.5,
+                "{result} is not close to {expected}"
+            );
+        }
     }
 
     #[test]
@@ -1817,95 +8028,167 @@ mod tests {
         assert_eq!(deserialized, obj);
     }
 
-    #[test]
-    fn test_cycles() {
-        assert_eq!(cycles(&BTreeMap::new()), vec![]);
-        assert_eq!(
-            cycles(&BTreeMap::from(
-                [
-                    Period {
-                        date: from_num_days(1),
-                        intensity: 3,
-                    },
-                    Period {
-                        date: from_num_days(5),
-                        intensity: 4,
-                    },
-                    Period {
-                        date: from_num_days(8),
-                        intensity: 2,
-                    },
-                    Period {
-                        date: from_num_days(33),
-                        intensity: 1,
-                    }
-                ]
-                .map(|p| (p.date, p))
-            )),
-            vec![
-                Cycle {
-                    begin: from_num_days(1),
-                    length: Duration::days(4),
-                },
-                Cycle {
-                    begin: from_num_days(5),
-                    length: Duration::days(28),
-                }
-            ]
-        );
+    fn period(days: &[i32]) -> BTreeMap<NaiveDate, Period> {
+        days.iter()
+            .map(|d| {
+                let date = from_num_days(*d);
+                (date, Period { date, intensity: 2 })
+            })
+            .collect()
+    }
+
+    fn recent_period(days_ago: &[i64]) -> BTreeMap<NaiveDate, Period> {
+        days_ago
+            .iter()
+            .map(|d| {
+                let date = *TODAY - Duration::days(*d);
+                (date, Period { date, intensity: 2 })
+            })
+            .collect()
     }
 
     #[rstest]
-    #[case::no_cycle(&[], None)]
-    #[case::no_recent_cycles(
-        &[
+    #[case::no_period(&[], vec![])]
+    #[case::single_period(&[1, 2, 3, 4], vec![])]
+    #[case::multiple_periods(
+        &[1, 5, 8, 33],
+        vec![
             Cycle {
-                begin: *TODAY - Duration::days(228),
-                length: Duration::days(26),
+                begin: from_num_days(1),
+                length: Duration::days(4),
             },
             Cycle {
-                begin: *TODAY - Duration::days(202),
+                begin: from_num_days(5),
                 length: Duration::days(28),
             }
-        ],
-        None
+        ]
     )]
-    #[case::one_cycle(
-        &[
+    #[case::trailing_single_day(
+        &[1, 2, 3, 29, 30, 56],
+        vec![
             Cycle {
-                begin: *TODAY - Duration::days(42),
+                begin: from_num_days(1),
                 length: Duration::days(28),
+            },
+            Cycle {
+                begin: from_num_days(29),
+                length: Duration::days(27),
             }
-        ],
+        ]
+    )]
+    #[case::three_days_apart(
+        &[1, 4, 7, 35],
+        vec![
+            Cycle {
+                begin: from_num_days(1),
+                length: Duration::days(34),
+            }
+        ]
+    )]
+    #[case::four_days_apart(
+        &[1, 5, 33],
+        vec![
+            Cycle {
+                begin: from_num_days(1),
+                length: Duration::days(4),
+            },
+            Cycle {
+                begin: from_num_days(5),
+                length: Duration::days(28),
+            }
+        ]
+    )]
+    fn test_cycles(#[case] days: &[i32], #[case] expected: Vec<Cycle>) {
+        assert_eq!(cycles(&period(days)), expected);
+    }
+
+    #[rstest]
+    #[case::no_period(&[], None)]
+    #[case::single_period(&[1, 2, 3, 4], Some(1))]
+    #[case::multiple_periods(&[1, 2, 29, 30, 31], Some(29))]
+    #[case::trailing_single_day(&[1, 2, 29, 30, 56], Some(56))]
+    #[case::three_days_apart(&[1, 2, 29, 32, 35], Some(29))]
+    #[case::four_days_apart(&[1, 2, 29, 33], Some(33))]
+    fn test_ongoing_cycle_begin(#[case] days: &[i32], #[case] expected: Option<i32>) {
+        assert_eq!(
+            ongoing_cycle_begin(&period(days)),
+            expected.map(from_num_days)
+        );
+    }
+
+    #[rstest]
+    #[case::no_period(&[], None)]
+    #[case::single_period(
+        &[14, 13, 12],
+        Some(
+            CurrentCycle {
+                begin: *TODAY - Duration::days(14),
+                time_left: None,
+                time_left_variation: Duration::days(0)
+            }
+        )
+    )]
+    #[case::no_recent_cycles(
+        &[228, 202, 174],
+        Some(
+            CurrentCycle {
+                begin: *TODAY - Duration::days(174),
+                time_left: None,
+                time_left_variation: Duration::days(0)
+            }
+        )
+    )]
+    #[case::one_cycle(
+        &[42, 14],
         Some(
             CurrentCycle {
                 begin: *TODAY - Duration::days(14),
-                time_left: Duration::days(13),
+                time_left: Some(Duration::days(13)),
                 time_left_variation: Duration::days(0)
             }
         )
     )]
     #[case::multiple_cycles(
-        &[
-            Cycle {
-                begin: *TODAY - Duration::days(68),
-                length: Duration::days(26),
-            },
-            Cycle {
-                begin: *TODAY - Duration::days(42),
-                length: Duration::days(28),
+        &[68, 42, 14],
+        Some(
+            CurrentCycle {
+                begin: *TODAY - Duration::days(14),
+                time_left: Some(Duration::days(12)),
+                time_left_variation: Duration::days(1)
             }
-        ],
+        )
+    )]
+    #[case::trailing_single_day(
+        &[68, 67, 42, 41, 40, 14],
         Some(
             CurrentCycle {
                 begin: *TODAY - Duration::days(14),
-                time_left: Duration::days(12),
+                time_left: Some(Duration::days(12)),
                 time_left_variation: Duration::days(1)
             }
         )
     )]
-    fn test_current_cycle(#[case] cycles: &[Cycle], #[case] expected: Option<CurrentCycle>) {
-        assert_eq!(current_cycle(cycles), expected);
+    fn test_current_cycle(#[case] days_ago: &[i64], #[case] expected: Option<CurrentCycle>) {
+        let period = recent_period(days_ago);
+        assert_eq!(
+            current_cycle(&period, &cycles(&period), &FixedClock(*TODAY)),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::tomorrow(&[29, -1], Duration::days(30))]
+    #[case::day_after_tomorrow(&[29, -2], Duration::days(31))]
+    fn test_current_cycle_begin_after_today(#[case] days_ago: &[i64], #[case] time_left: Duration) {
+        let period = recent_period(days_ago);
+        assert_eq!(
+            current_cycle(&period, &cycles(&period), &FixedClock(*TODAY)),
+            Some(CurrentCycle {
+                begin: *TODAY - Duration::days(days_ago[1]),
+                time_left: Some(time_left),
+                time_left_variation: Duration::days(0)
+            })
+        );
     }
 
     #[test]
@@ -2081,7 +8364,80 @@ mod tests {
         #[case] last: NaiveDate,
     ) {
         assert_eq!(
-            init_interval(dates, default_interval),
+            init_interval(dates, default_interval, None, &FixedClock(*TODAY)),
+            Interval { first, last }
+        );
+    }
+
+    #[rstest]
+    #[case::default_interval(DefaultInterval::_1M, *TODAY - Duration::days(DefaultInterval::_1M as i64))]
+    #[case::default_interval_all(DefaultInterval::All, *TODAY - Duration::days(2))]
+    fn test_init_interval_date_after_today(
+        #[case] default_interval: DefaultInterval,
+        #[case] first: NaiveDate,
+    ) {
+        assert_eq!(
+            init_interval(
+                &[*TODAY - Duration::days(2), *TODAY + Duration::days(1)],
+                default_interval,
+                None,
+                &FixedClock(*TODAY)
+            ),
+            Interval {
+                first,
+                last: *TODAY + Duration::days(1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_init_interval_today() {
+        let today = *TODAY;
+        assert_eq!(
+            init_interval(&[today], DefaultInterval::_1M, None, &FixedClock(today)),
+            Interval {
+                first: today - Duration::days(DefaultInterval::_1M as i64),
+                last: today
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::selected_interval(
+        &[*TODAY - Duration::days(21), *TODAY - Duration::days(42)],
+        DefaultInterval::_1M,
+        Some(DefaultInterval::_3M),
+        *TODAY - Duration::days(DefaultInterval::_3M as i64),
+        *TODAY
+    )]
+    #[case::selected_interval_all(
+        &[*TODAY - Duration::days(21), *TODAY - Duration::days(42)],
+        DefaultInterval::_1M,
+        Some(DefaultInterval::All),
+        *TODAY - Duration::days(42),
+        *TODAY
+    )]
+    #[case::no_data_in_selected_interval(
+        &[*TODAY - Duration::days(DefaultInterval::_1M as i64 + 42)],
+        DefaultInterval::_1Y,
+        Some(DefaultInterval::_1M),
+        *TODAY - Duration::days(DefaultInterval::_1M as i64 + 42),
+        *TODAY
+    )]
+    fn test_init_interval_selected_interval(
+        #[case] dates: &[NaiveDate],
+        #[case] default_interval: DefaultInterval,
+        #[case] selected_interval: Option<DefaultInterval>,
+        #[case] first: NaiveDate,
+        #[case] last: NaiveDate,
+    ) {
+        assert_eq!(
+            init_interval(
+                dates,
+                default_interval,
+                selected_interval,
+                &FixedClock(*TODAY)
+            ),
             Interval { first, last }
         );
     }
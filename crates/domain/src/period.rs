@@ -0,0 +1,338 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+use crate::{cycle_stats, Cycle, Interval, Period};
+
+/// Time span in which the next period is expected to begin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PredictedPeriod {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl PredictedPeriod {
+    #[must_use]
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        (self.first..=self.last).contains(&date)
+    }
+}
+
+/// Predict the begin of the next period based on the median and the variation of the lengths of
+/// the cycles started in the last half year.
+///
+/// No prediction is made if less than two cycles are available, as a single cycle does not allow
+/// to estimate the variation, or if the predicted time span has already passed. Days before today
+/// are excluded from the prediction.
+#[must_use]
+pub fn predicted_period(cycles: &[Cycle], today: NaiveDate) -> Option<PredictedPeriod> {
+    let cycles = cycles
+        .iter()
+        .filter(|c| c.begin >= today - Duration::days(182) && c.begin <= today)
+        .collect::<Vec<_>>();
+
+    if cycles.len() < 2 {
+        return None;
+    }
+
+    let last_cycle = cycles.last()?;
+    let stats = cycle_stats(&cycles);
+    let begin = last_cycle.begin + last_cycle.length + stats.length_median;
+    let first = begin - stats.length_variation;
+    let last = begin + stats.length_variation;
+
+    if last < today {
+        return None;
+    }
+
+    Some(PredictedPeriod {
+        first: first.max(today),
+        last,
+    })
+}
+
+/// Return the cycles that are completely recorded in the given interval.
+///
+/// The first recorded cycle is excluded, as the recording could have been started in the middle of
+/// a period.
+#[must_use]
+pub fn recorded_cycles<'a>(cycles: &'a [Cycle], interval: &Interval) -> Vec<&'a Cycle> {
+    cycles
+        .iter()
+        .skip(1)
+        .filter(|c| {
+            c.begin >= interval.first && c.begin + c.length - Duration::days(1) <= interval.last
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CycleIntensity {
+    pub bleeding_days: u32,
+    /// Sum of the intensities of all bleeding days.
+    pub intensity: u32,
+}
+
+#[must_use]
+pub fn cycle_intensity(cycle: &Cycle, period: &BTreeMap<NaiveDate, Period>) -> CycleIntensity {
+    period.range(cycle.begin..cycle.begin + cycle.length).fold(
+        CycleIntensity::default(),
+        |result, (_, p)| CycleIntensity {
+            bleeding_days: result.bleeding_days + 1,
+            intensity: result.intensity + u32::from(p.intensity),
+        },
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntensityStats {
+    pub bleeding_days: f32,
+    pub intensity: f32,
+}
+
+/// Calculate the average number of bleeding days and the average intensity per cycle.
+#[must_use]
+pub fn intensity_stats(
+    cycles: &[&Cycle],
+    period: &BTreeMap<NaiveDate, Period>,
+) -> Option<IntensityStats> {
+    if cycles.is_empty() {
+        return None;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let num_cycles = cycles.len() as f32;
+    let (bleeding_days, intensity) = cycles
+        .iter()
+        .map(|c| cycle_intensity(c, period))
+        .fold((0, 0), |(bleeding_days, intensity), c| {
+            (bleeding_days + c.bleeding_days, intensity + c.intensity)
+        });
+
+    #[allow(clippy::cast_precision_loss)]
+    Some(IntensityStats {
+        bleeding_days: bleeding_days as f32 / num_cycles,
+        intensity: intensity as f32 / num_cycles,
+    })
+}
+
+/// Return the weeks from Monday to Sunday that cover the month of the given date.
+///
+/// The first and the last week contain the adjacent days of the previous and the next month.
+#[must_use]
+pub fn month_weeks(date: NaiveDate) -> Vec<[NaiveDate; 7]> {
+    let first_day = date.with_day(1).unwrap_or(date);
+    let last_day = first_day
+        .checked_add_months(Months::new(1))
+        .and_then(|d| d.pred_opt())
+        .unwrap_or(first_day);
+    let mut weeks = vec![];
+    let mut days = first_day.week(Weekday::Mon).first_day().iter_days();
+
+    while let Some(day) = days.next().filter(|d| *d <= last_day) {
+        let mut week = [day; 7];
+        for d in &mut week[1..] {
+            *d = days.next().unwrap_or(day);
+        }
+        weeks.push(week);
+    }
+
+    weeks
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2020, month, day).unwrap()
+    }
+
+    fn cycle(begin: NaiveDate, length: i64) -> Cycle {
+        Cycle {
+            begin,
+            length: Duration::days(length),
+        }
+    }
+
+    #[rstest]
+    #[case::no_cycles(&[], date(3, 1), None)]
+    #[case::single_cycle(&[cycle(date(1, 1), 28)], date(2, 10), None)]
+    #[case::regular_cycles(
+        &[cycle(date(1, 1), 28), cycle(date(1, 29), 28)],
+        date(3, 1),
+        Some(PredictedPeriod { first: date(3, 25), last: date(3, 25) })
+    )]
+    #[case::irregular_cycles(
+        &[
+            cycle(date(1, 1), 26),
+            cycle(date(1, 27), 30),
+            cycle(date(2, 26), 28),
+            cycle(date(3, 25), 32),
+        ],
+        date(4, 30),
+        Some(PredictedPeriod { first: date(5, 23), last: date(5, 27) })
+    )]
+    #[case::partially_passed(
+        &[
+            cycle(date(1, 1), 26),
+            cycle(date(1, 27), 30),
+            cycle(date(2, 26), 28),
+            cycle(date(3, 25), 32),
+        ],
+        date(5, 26),
+        Some(PredictedPeriod { first: date(5, 26), last: date(5, 27) })
+    )]
+    #[case::passed(
+        &[cycle(date(1, 1), 28), cycle(date(1, 29), 28)],
+        date(3, 26),
+        None
+    )]
+    #[case::outdated_cycles(
+        &[cycle(date(1, 1), 28), cycle(date(1, 29), 28)],
+        date(9, 1),
+        None
+    )]
+    fn test_predicted_period(
+        #[case] cycles: &[Cycle],
+        #[case] today: NaiveDate,
+        #[case] expected: Option<PredictedPeriod>,
+    ) {
+        assert_eq!(predicted_period(cycles, today), expected);
+    }
+
+    #[test]
+    fn test_predicted_period_contains() {
+        let predicted_period = PredictedPeriod {
+            first: date(3, 24),
+            last: date(3, 26),
+        };
+        assert!(!predicted_period.contains(date(3, 23)));
+        assert!(predicted_period.contains(date(3, 24)));
+        assert!(predicted_period.contains(date(3, 26)));
+        assert!(!predicted_period.contains(date(3, 27)));
+    }
+
+    fn period(dates: &[(NaiveDate, u8)]) -> BTreeMap<NaiveDate, Period> {
+        dates
+            .iter()
+            .map(|(date, intensity)| {
+                (
+                    *date,
+                    Period {
+                        date: *date,
+                        intensity: *intensity,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case::no_cycles(&[], (date(1, 1), date(12, 31)), vec![])]
+    #[case::single_cycle(&[cycle(date(1, 1), 28)], (date(1, 1), date(12, 31)), vec![])]
+    #[case::multiple_cycles(
+        &[cycle(date(1, 1), 28), cycle(date(1, 29), 28), cycle(date(2, 26), 30)],
+        (date(1, 1), date(12, 31)),
+        vec![cycle(date(1, 29), 28), cycle(date(2, 26), 30)]
+    )]
+    #[case::partially_in_interval(
+        &[cycle(date(1, 1), 28), cycle(date(1, 29), 28), cycle(date(2, 26), 30)],
+        (date(2, 1), date(3, 26)),
+        vec![cycle(date(2, 26), 30)]
+    )]
+    fn test_recorded_cycles(
+        #[case] cycles: &[Cycle],
+        #[case] interval: (NaiveDate, NaiveDate),
+        #[case] expected: Vec<Cycle>,
+    ) {
+        assert_eq!(
+            recorded_cycles(cycles, &(interval.0..=interval.1).into()),
+            expected.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_cycle_intensity() {
+        let period = period(&[
+            (date(1, 1), 2),
+            (date(1, 2), 3),
+            (date(1, 3), 1),
+            (date(1, 29), 4),
+        ]);
+        assert_eq!(
+            cycle_intensity(&cycle(date(1, 1), 28), &period),
+            CycleIntensity {
+                bleeding_days: 3,
+                intensity: 6
+            }
+        );
+        assert_eq!(
+            cycle_intensity(&cycle(date(1, 29), 28), &period),
+            CycleIntensity {
+                bleeding_days: 1,
+                intensity: 4
+            }
+        );
+        assert_eq!(
+            cycle_intensity(&cycle(date(3, 1), 28), &period),
+            CycleIntensity::default()
+        );
+    }
+
+    #[test]
+    fn test_intensity_stats() {
+        let period = period(&[
+            (date(1, 1), 2),
+            (date(1, 2), 3),
+            (date(1, 29), 4),
+            (date(1, 30), 3),
+            (date(1, 31), 1),
+            (date(2, 26), 2),
+        ]);
+        assert_eq!(intensity_stats(&[], &period), None);
+        assert_eq!(
+            intensity_stats(&[&cycle(date(1, 1), 28), &cycle(date(1, 29), 28)], &period),
+            Some(IntensityStats {
+                bleeding_days: 2.5,
+                intensity: 6.5
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::adjacent_months(date(4, 15), 5, date(3, 30), date(5, 3))]
+    #[case::starting_on_monday(date(6, 1), 5, date(6, 1), date(7, 5))]
+    #[case::ending_on_sunday(date(5, 31), 5, date(4, 27), date(5, 31))]
+    #[case::four_weeks(
+        NaiveDate::from_ymd_opt(2021, 2, 1).unwrap(),
+        4,
+        NaiveDate::from_ymd_opt(2021, 2, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2021, 2, 28).unwrap()
+    )]
+    #[case::end_of_year(
+        date(12, 24),
+        5,
+        date(11, 30),
+        NaiveDate::from_ymd_opt(2021, 1, 3).unwrap()
+    )]
+    fn test_month_weeks(
+        #[case] day: NaiveDate,
+        #[case] num_weeks: usize,
+        #[case] first: NaiveDate,
+        #[case] last: NaiveDate,
+    ) {
+        let weeks = month_weeks(day);
+        assert_eq!(weeks.len(), num_weeks);
+        assert_eq!(weeks[0][0], first);
+        assert_eq!(weeks[num_weeks - 1][6], last);
+        assert!(weeks
+            .iter()
+            .flatten()
+            .zip(weeks.iter().flatten().skip(1))
+            .all(|(a, b)| *b - *a == Duration::days(1)));
+    }
+}
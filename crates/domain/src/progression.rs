@@ -0,0 +1,754 @@
+use chrono::NaiveDate;
+
+use crate::{equipment, Exercise, RpeRange, TrainingSession, TrainingSessionElement};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressionStrategy {
+    /// Increase the reps until all sets reach the rep ceiling, then increase the weight and drop
+    /// the reps to the rep floor.
+    DoubleProgression {
+        rep_floor: u32,
+        rep_ceiling: u32,
+        weight_increment: f32,
+    },
+}
+
+impl Default for ProgressionStrategy {
+    fn default() -> Self {
+        ProgressionStrategy::DoubleProgression {
+            rep_floor: 8,
+            rep_ceiling: 12,
+            weight_increment: 2.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Set {
+    pub reps: Option<u32>,
+    pub time: Option<u32>,
+    pub weight: Option<f32>,
+    pub rpe: Option<RpeRange>,
+}
+
+/// Suggest the targets for the next set of an exercise based on the sets of the exercise in the
+/// previous training session.
+///
+/// The reps are not increased if any set missed its target reps or was performed to failure
+/// (RPE 10). Sets without an RPE value are assumed not to be performed to failure. If the last set
+/// has a target rep range, its bounds are used instead of the rep floor and ceiling of the
+/// strategy, so that the weight is increased when all sets reach the upper bound of the range.
+#[must_use]
+pub fn suggest(history: &[TrainingSessionElement], strategy: ProgressionStrategy) -> Set {
+    let sets = history
+        .iter()
+        .filter_map(|e| match e {
+            TrainingSessionElement::Set {
+                reps: Some(reps),
+                time,
+                weight,
+                rpe,
+                target_reps,
+                target_rpe,
+                ..
+            } => Some((*reps, *time, *weight, *rpe, *target_reps, *target_rpe)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let Some((_, time, weight, _, target_reps, target_rpe)) = sets.last().copied() else {
+        return Set::default();
+    };
+    let min_reps = sets.iter().map(|s| s.0).min().unwrap_or_default();
+    let missed_reps = sets
+        .iter()
+        .any(|(reps, _, _, _, target_reps, _)| target_reps.is_some_and(|t| *reps < t.min));
+    let failed = sets
+        .iter()
+        .any(|(_, _, _, rpe, _, _)| rpe.is_some_and(|r| r >= 10.0));
+
+    match strategy {
+        ProgressionStrategy::DoubleProgression {
+            rep_floor,
+            rep_ceiling,
+            weight_increment,
+        } => {
+            let (rep_floor, rep_ceiling) = target_reps
+                .filter(|t| t.is_set() && !t.is_single())
+                .map_or((rep_floor, rep_ceiling), |t| (t.min, t.max));
+            let (reps, weight) = if missed_reps || failed {
+                (min_reps.max(rep_floor).min(rep_ceiling), weight)
+            } else if min_reps >= rep_ceiling {
+                match weight {
+                    Some(weight) => (rep_floor, Some(weight + weight_increment)),
+                    None => (min_reps + 1, None),
+                }
+            } else {
+                ((min_reps + 1).max(rep_floor), weight)
+            };
+            Set {
+                reps: Some(reps),
+                time,
+                weight,
+                rpe: target_rpe,
+            }
+        }
+    }
+}
+
+/// Round the weight of a suggested set to the weights achievable with the available equipment,
+/// which must be sorted in ascending order.
+///
+/// A weight above the weight of the last set in the history is rounded up, so that the suggested
+/// progression is not undone by the rounding. Other weights are rounded to the nearest achievable
+/// weight.
+#[must_use]
+pub fn round_suggestion(
+    suggestion: Set,
+    history: &[TrainingSessionElement],
+    weights: &[f32],
+) -> Set {
+    let previous_weight = history.iter().rev().find_map(|e| match e {
+        TrainingSessionElement::Set { weight, .. } => Some(*weight),
+        TrainingSessionElement::Rest { .. } => None,
+    });
+    let weight = suggestion.weight.map(|weight| {
+        let achievable = equipment::round(weight, weights);
+        if previous_weight
+            .flatten()
+            .is_some_and(|previous| weight > previous)
+        {
+            achievable.at_least()
+        } else {
+            achievable.nearest(weight)
+        }
+        .unwrap_or(weight)
+    });
+    Set {
+        weight,
+        ..suggestion
+    }
+}
+
+/// Estimate the one-repetition maximum of a set using the Epley formula.
+///
+/// The repetitions in reserve derived from the RPE are added to the performed repetitions. Sets
+/// without an RPE value are assumed to be performed to failure.
+#[must_use]
+pub fn one_rep_max(reps: u32, weight: f32, rpe: Option<f32>) -> Option<f32> {
+    if reps == 0 || weight <= 0.0 {
+        return None;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let reps = reps as f32 + (10.0 - rpe.unwrap_or(10.0)).max(0.0);
+    if reps <= 1.0 {
+        return Some(weight);
+    }
+    Some(weight * (30.0 + reps) / 30.0)
+}
+
+/// Return the highest estimated one-repetition maximum of an exercise for each training session.
+///
+/// The estimates are based on the total moved weight.
+#[must_use]
+pub fn one_rep_max_values<'a>(
+    training_sessions: impl Iterator<Item = &'a TrainingSession>,
+    exercise: &Exercise,
+) -> Vec<(NaiveDate, f32)> {
+    training_sessions
+        .filter_map(|t| {
+            t.elements
+                .iter()
+                .filter_map(|e| match e {
+                    TrainingSessionElement::Set {
+                        exercise_id: id,
+                        reps: Some(reps),
+                        weight: Some(weight),
+                        rpe,
+                        ..
+                    } if *id == exercise.id => {
+                        one_rep_max(*reps, exercise.total_weight(*weight), *rpe)
+                    }
+                    _ => None,
+                })
+                .reduce(f32::max)
+                .map(|value| (t.date, value))
+        })
+        .collect()
+}
+
+/// Return the highest estimated one-repetition maximum of the exercise for the last `n` training
+/// sessions that contain the exercise, ordered by date.
+#[must_use]
+pub fn recent_top_sets<'a>(
+    training_sessions: impl Iterator<Item = &'a TrainingSession>,
+    exercise: &Exercise,
+    n: usize,
+) -> Vec<(NaiveDate, f32)> {
+    let mut training_sessions = training_sessions.collect::<Vec<_>>();
+    training_sessions.sort_by_key(|t| (t.date, t.id));
+    let values = one_rep_max_values(training_sessions.into_iter(), exercise);
+    values[values.len().saturating_sub(n)..].to_vec()
+}
+
+/// Calculate the target weight for a percentage of the one-repetition maximum, rounded to the
+/// nearest multiple of the weight increment.
+///
+/// The one-repetition maximum is given as total moved weight. The target weight is returned in the
+/// weight convention of the exercise.
+#[must_use]
+pub fn target_weight(
+    percent_1rm: f32,
+    one_rep_max: f32,
+    exercise: &Exercise,
+    weight_increment: f32,
+) -> Option<f32> {
+    let weight = exercise.recorded_weight(one_rep_max * percent_1rm / 100.0);
+    let weight = if weight_increment > 0.0 {
+        (weight / weight_increment).round() * weight_increment
+    } else {
+        weight
+    };
+    if weight > 0.0 {
+        Some(weight)
+    } else {
+        None
+    }
+}
+
+/// Target and actual values of a set, for each value that has a target and was performed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SetDeviation {
+    pub reps: Option<(u32, u32)>,
+    pub time: Option<(u32, u32)>,
+    pub weight: Option<(f32, f32)>,
+    /// Target range and actual value.
+    pub rpe: Option<(RpeRange, f32)>,
+}
+
+impl SetDeviation {
+    /// Check whether the target was met, allowing to miss it by the given tolerance in percent.
+    ///
+    /// Reps, time and weight must not fall below their targets and the RPE must not exceed the
+    /// upper bound of its target range.
+    #[must_use]
+    pub fn target_met(&self, tolerance: u8) -> bool {
+        let tolerance = f32::from(tolerance) / 100.0;
+        #[allow(clippy::cast_precision_loss)]
+        let below =
+            |(target, actual): (u32, u32)| (actual as f32) < target as f32 * (1.0 - tolerance);
+        !(self.reps.is_some_and(below)
+            || self.time.is_some_and(below)
+            || self
+                .weight
+                .is_some_and(|(target, actual)| actual < target * (1.0 - tolerance))
+            || self
+                .rpe
+                .is_some_and(|(target, actual)| actual > target.max * (1.0 + tolerance)))
+    }
+}
+
+/// Compare the actual values of a set with its target values.
+///
+/// Values of zero are considered as not set. The performed RPE is expected to be a single value.
+/// No deviation is returned if no performed value has a target.
+#[must_use]
+pub fn deviation(target: Set, actual: Set) -> Option<SetDeviation> {
+    fn pair<T: Copy + Default + PartialOrd>(
+        target: Option<T>,
+        actual: Option<T>,
+    ) -> Option<(T, T)> {
+        let set = |value: Option<T>| value.filter(|v| *v > T::default());
+        Some((set(target)?, set(actual)?))
+    }
+    let deviation = SetDeviation {
+        reps: pair(target.reps, actual.reps),
+        time: pair(target.time, actual.time),
+        weight: pair(target.weight, actual.weight),
+        rpe: target
+            .rpe
+            .filter(RpeRange::is_set)
+            .zip(actual.rpe.filter(RpeRange::is_set).map(|rpe| rpe.max)),
+    };
+    if deviation == SetDeviation::default() {
+        None
+    } else {
+        Some(deviation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{Laterality, RepsRange, WeightConvention};
+
+    fn exercise(
+        id: u32,
+        weight_convention: Option<WeightConvention>,
+        base_weight: Option<f32>,
+    ) -> Exercise {
+        Exercise {
+            id,
+            name: String::from("A"),
+            muscles: vec![],
+            weight_convention,
+            base_weight,
+            laterality: Laterality::Bilateral,
+            archived: false,
+            image: None,
+        }
+    }
+
+    fn set(
+        reps: Option<u32>,
+        weight: Option<f32>,
+        rpe: Option<f32>,
+        target_reps: Option<RepsRange>,
+    ) -> TrainingSessionElement {
+        TrainingSessionElement::Set {
+            exercise_id: 1,
+            reps,
+            time: None,
+            weight,
+            rpe,
+            target_reps,
+            target_time: None,
+            target_weight: None,
+            target_rpe: None,
+            automatic: false,
+            partial_reps: None,
+            to_failure: false,
+            side: None,
+            comment: None,
+        }
+    }
+
+    #[rstest]
+    #[case::no_history(vec![], Set::default())]
+    #[case::no_performed_sets(
+        vec![
+            set(None, Some(50.0), None, Some(RepsRange::single(10))),
+            TrainingSessionElement::Rest { target_time: Some(60), automatic: true },
+        ],
+        Set::default()
+    )]
+    #[case::below_ceiling(
+        vec![
+            set(Some(10), Some(50.0), Some(8.0), None),
+            set(Some(9), Some(50.0), Some(9.0), None),
+        ],
+        Set { reps: Some(10), time: None, weight: Some(50.0), rpe: None }
+    )]
+    #[case::below_floor(
+        vec![set(Some(5), Some(50.0), Some(8.0), None)],
+        Set { reps: Some(8), time: None, weight: Some(50.0), rpe: None }
+    )]
+    #[case::hit_ceiling(
+        vec![
+            set(Some(12), Some(50.0), Some(8.0), Some(RepsRange::single(12))),
+            set(Some(12), Some(50.0), Some(9.0), Some(RepsRange::single(12))),
+        ],
+        Set { reps: Some(8), time: None, weight: Some(52.5), rpe: None }
+    )]
+    #[case::hit_ceiling_in_some_sets(
+        vec![
+            set(Some(12), Some(50.0), Some(8.0), None),
+            set(Some(11), Some(50.0), Some(9.0), None),
+        ],
+        Set { reps: Some(12), time: None, weight: Some(50.0), rpe: None }
+    )]
+    #[case::hit_ceiling_without_weight(
+        vec![set(Some(12), None, Some(8.0), None)],
+        Set { reps: Some(13), time: None, weight: None, rpe: None }
+    )]
+    #[case::missed_reps(
+        vec![
+            set(Some(10), Some(50.0), Some(8.0), Some(RepsRange::single(10))),
+            set(Some(9), Some(50.0), Some(9.0), Some(RepsRange::single(10))),
+        ],
+        Set { reps: Some(9), time: None, weight: Some(50.0), rpe: None }
+    )]
+    #[case::failure(
+        vec![set(Some(10), Some(50.0), Some(10.0), None)],
+        Set { reps: Some(10), time: None, weight: Some(50.0), rpe: None }
+    )]
+    #[case::missing_rpe(
+        vec![
+            set(Some(12), Some(50.0), None, None),
+            set(Some(12), Some(50.0), None, None),
+        ],
+        Set { reps: Some(8), time: None, weight: Some(52.5), rpe: None }
+    )]
+    #[case::target_range_below_ceiling(
+        vec![
+            set(Some(10), Some(50.0), Some(8.0), Some(RepsRange::new(6, 10))),
+            set(Some(9), Some(50.0), Some(9.0), Some(RepsRange::new(6, 10))),
+        ],
+        Set { reps: Some(10), time: None, weight: Some(50.0), rpe: None }
+    )]
+    #[case::target_range_hit_ceiling(
+        vec![
+            set(Some(10), Some(50.0), Some(8.0), Some(RepsRange::new(6, 10))),
+            set(Some(10), Some(50.0), Some(9.0), Some(RepsRange::new(6, 10))),
+        ],
+        Set { reps: Some(6), time: None, weight: Some(52.5), rpe: None }
+    )]
+    #[case::target_range_missed_floor(
+        vec![
+            set(Some(7), Some(50.0), Some(8.0), Some(RepsRange::new(6, 10))),
+            set(Some(5), Some(50.0), Some(9.0), Some(RepsRange::new(6, 10))),
+        ],
+        Set { reps: Some(6), time: None, weight: Some(50.0), rpe: None }
+    )]
+    fn test_suggest(#[case] history: Vec<TrainingSessionElement>, #[case] expected: Set) {
+        assert_eq!(suggest(&history, ProgressionStrategy::default()), expected);
+    }
+
+    #[rstest]
+    #[case::unchanged(Some(20.0), Some(20.0), Some(20.0))]
+    #[case::increased_exact(Some(20.0), Some(22.0), Some(22.0))]
+    #[case::increased_rounded_up(Some(20.0), Some(22.5), Some(24.0))]
+    #[case::increased_beyond_max(Some(30.0), Some(32.5), Some(30.0))]
+    #[case::rounded_to_nearest(Some(21.0), Some(21.0), Some(20.0))]
+    #[case::without_previous_weight(None, Some(23.5), Some(24.0))]
+    #[case::without_weight(Some(20.0), None, None)]
+    fn test_round_suggestion(
+        #[case] previous_weight: Option<f32>,
+        #[case] suggested_weight: Option<f32>,
+        #[case] expected: Option<f32>,
+    ) {
+        let dumbbells = equipment::WeightRange::new(2.0, 30.0, 2.0)
+            .unwrap()
+            .weights();
+        let suggestion = Set {
+            reps: Some(8),
+            time: None,
+            weight: suggested_weight,
+            rpe: None,
+        };
+        assert_eq!(
+            round_suggestion(
+                suggestion,
+                &[set(Some(12), previous_weight, None, None)],
+                &dumbbells
+            ),
+            Set {
+                weight: expected,
+                ..suggestion
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::no_reps(0, 100.0, None, None)]
+    #[case::no_weight(5, 0.0, None, None)]
+    #[case::single_rep(1, 100.0, None, Some(100.0))]
+    #[case::single_rep_at_rpe_10(1, 100.0, Some(10.0), Some(100.0))]
+    #[case::multiple_reps(6, 100.0, None, Some(120.0))]
+    #[case::reps_in_reserve(3, 100.0, Some(7.0), Some(120.0))]
+    fn test_one_rep_max(
+        #[case] reps: u32,
+        #[case] weight: f32,
+        #[case] rpe: Option<f32>,
+        #[case] expected: Option<f32>,
+    ) {
+        assert_eq!(one_rep_max(reps, weight, rpe), expected);
+    }
+
+    #[test]
+    fn test_one_rep_max_values() {
+        let training_sessions = [
+            TrainingSession {
+                id: 1,
+                routine_id: None,
+                date: NaiveDate::from_ymd_opt(2020, 2, 1).unwrap(),
+                notes: None,
+                start_time: None,
+                end_time: None,
+                elements: vec![
+                    set(Some(6), Some(100.0), None, None),
+                    set(Some(3), Some(100.0), Some(7.0), None),
+                    set(Some(1), Some(110.0), None, None),
+                ],
+            },
+            TrainingSession {
+                id: 2,
+                routine_id: None,
+                date: NaiveDate::from_ymd_opt(2020, 2, 3).unwrap(),
+                notes: None,
+                start_time: None,
+                end_time: None,
+                elements: vec![set(None, Some(100.0), None, None)],
+            },
+            TrainingSession {
+                id: 3,
+                routine_id: None,
+                date: NaiveDate::from_ymd_opt(2020, 2, 5).unwrap(),
+                notes: None,
+                start_time: None,
+                end_time: None,
+                elements: vec![set(Some(12), Some(50.0), Some(8.0), None)],
+            },
+        ];
+        assert_eq!(
+            one_rep_max_values(training_sessions.iter(), &exercise(1, None, None)),
+            vec![
+                (NaiveDate::from_ymd_opt(2020, 2, 1).unwrap(), 120.0),
+                (NaiveDate::from_ymd_opt(2020, 2, 5).unwrap(), 73.333_336),
+            ]
+        );
+        assert_eq!(
+            one_rep_max_values(training_sessions.iter(), &exercise(2, None, None)),
+            vec![]
+        );
+    }
+
+    #[rstest]
+    #[case::none(0, vec![])]
+    #[case::fewer_than_available(2, vec![(4, 80.0), (5, 100.0)])]
+    #[case::more_than_available(5, vec![(1, 40.0), (4, 80.0), (5, 100.0)])]
+    fn test_recent_top_sets(#[case] n: usize, #[case] expected: Vec<(u32, f32)>) {
+        let training_session = |id: u32, day: u32, elements| TrainingSession {
+            id,
+            routine_id: None,
+            date: NaiveDate::from_ymd_opt(2020, 2, day).unwrap(),
+            notes: None,
+            start_time: None,
+            end_time: None,
+            elements,
+        };
+        let training_sessions = [
+            training_session(1, 5, vec![set(Some(1), Some(100.0), None, None)]),
+            training_session(2, 1, vec![set(Some(1), Some(40.0), None, None)]),
+            training_session(3, 3, vec![set(None, Some(100.0), None, None)]),
+            training_session(4, 4, vec![set(Some(1), Some(80.0), None, None)]),
+        ];
+        assert_eq!(
+            recent_top_sets(training_sessions.iter(), &exercise(1, None, None), n),
+            expected
+                .into_iter()
+                .map(|(day, value)| (NaiveDate::from_ymd_opt(2020, 2, day).unwrap(), value))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            recent_top_sets(training_sessions.iter(), &exercise(2, None, None), n),
+            vec![]
+        );
+    }
+
+    #[rstest]
+    #[case::unspecified(None, Some(20.0), 30.0)]
+    #[case::total(Some(WeightConvention::Total), Some(20.0), 30.0)]
+    #[case::per_side(Some(WeightConvention::PerSide), Some(20.0), 80.0)]
+    #[case::per_side_without_base_weight(Some(WeightConvention::PerSide), None, 60.0)]
+    #[case::per_dumbbell(Some(WeightConvention::PerDumbbell), Some(2.5), 65.0)]
+    #[case::per_dumbbell_without_base_weight(Some(WeightConvention::PerDumbbell), None, 60.0)]
+    fn test_one_rep_max_values_weight_convention(
+        #[case] weight_convention: Option<WeightConvention>,
+        #[case] base_weight: Option<f32>,
+        #[case] expected: f32,
+    ) {
+        let training_sessions = [TrainingSession {
+            id: 1,
+            routine_id: None,
+            date: NaiveDate::from_ymd_opt(2020, 2, 1).unwrap(),
+            notes: None,
+            start_time: None,
+            end_time: None,
+            elements: vec![set(Some(1), Some(30.0), None, None)],
+        }];
+        assert_eq!(
+            one_rep_max_values(
+                training_sessions.iter(),
+                &exercise(1, weight_convention, base_weight)
+            ),
+            vec![(NaiveDate::from_ymd_opt(2020, 2, 1).unwrap(), expected)]
+        );
+    }
+
+    #[rstest]
+    #[case::exact(75.0, 100.0, None, 2.5, Some(75.0))]
+    #[case::rounded_up(80.0, 103.0, None, 2.5, Some(82.5))]
+    #[case::rounded_down(80.0, 101.0, None, 2.5, Some(80.0))]
+    #[case::no_increment(80.0, 103.0, None, 0.0, Some(82.4))]
+    #[case::too_small(1.0, 100.0, None, 2.5, None)]
+    #[case::total(75.0, 100.0, Some(WeightConvention::Total), 2.5, Some(75.0))]
+    #[case::per_side(75.0, 100.0, Some(WeightConvention::PerSide), 2.5, Some(27.5))]
+    #[case::per_dumbbell(75.0, 100.0, Some(WeightConvention::PerDumbbell), 2.5, Some(17.5))]
+    #[case::below_base_weight(10.0, 100.0, Some(WeightConvention::PerSide), 2.5, None)]
+    fn test_target_weight(
+        #[case] percent_1rm: f32,
+        #[case] one_rep_max: f32,
+        #[case] weight_convention: Option<WeightConvention>,
+        #[case] weight_increment: f32,
+        #[case] expected: Option<f32>,
+    ) {
+        assert_eq!(
+            target_weight(
+                percent_1rm,
+                one_rep_max,
+                &exercise(1, weight_convention, Some(20.0)),
+                weight_increment
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_suggest_keeps_time_and_target_rpe() {
+        assert_eq!(
+            suggest(
+                &[TrainingSessionElement::Set {
+                    exercise_id: 1,
+                    reps: Some(8),
+                    time: Some(4),
+                    weight: Some(20.0),
+                    rpe: Some(7.0),
+                    target_reps: Some(RepsRange::single(8)),
+                    target_time: Some(4),
+                    target_weight: Some(20.0),
+                    target_rpe: Some(RpeRange::single(8.0)),
+                    automatic: false,
+                    partial_reps: None,
+                    to_failure: false,
+                    side: None,
+                    comment: None,
+                }],
+                ProgressionStrategy::DoubleProgression {
+                    rep_floor: 6,
+                    rep_ceiling: 8,
+                    weight_increment: 5.0,
+                }
+            ),
+            Set {
+                reps: Some(6),
+                time: Some(4),
+                weight: Some(25.0),
+                rpe: Some(RpeRange::single(8.0)),
+            }
+        );
+    }
+
+    fn values(reps: Option<u32>, time: Option<u32>, weight: Option<f32>, rpe: Option<f32>) -> Set {
+        Set {
+            reps,
+            time,
+            weight,
+            rpe: rpe.map(RpeRange::single),
+        }
+    }
+
+    #[rstest]
+    #[case::no_target(
+        values(None, None, None, None),
+        values(Some(8), None, Some(50.0), Some(8.0)),
+        None
+    )]
+    #[case::not_performed(
+        values(Some(8), None, Some(50.0), None),
+        values(None, None, None, None),
+        None
+    )]
+    #[case::zero_values(
+        values(Some(0), None, Some(0.0), None),
+        values(Some(8), None, Some(50.0), None),
+        None
+    )]
+    #[case::reps_and_weight(
+        values(Some(8), None, Some(50.0), None),
+        values(Some(9), None, Some(47.5), Some(8.0)),
+        Some(SetDeviation {
+            reps: Some((8, 9)),
+            weight: Some((50.0, 47.5)),
+            ..SetDeviation::default()
+        })
+    )]
+    #[case::time(
+        values(None, Some(60), None, None),
+        values(Some(1), Some(45), None, None),
+        Some(SetDeviation { time: Some((60, 45)), ..SetDeviation::default() })
+    )]
+    #[case::rpe_only(
+        values(None, None, None, Some(8.0)),
+        values(Some(5), None, Some(100.0), Some(9.0)),
+        Some(SetDeviation { rpe: Some((RpeRange::single(8.0), 9.0)), ..SetDeviation::default() })
+    )]
+    #[case::rpe_range(
+        Set { rpe: Some(RpeRange::new(7.0, 8.0)), ..Set::default() },
+        values(Some(5), None, Some(100.0), Some(7.5)),
+        Some(SetDeviation { rpe: Some((RpeRange::new(7.0, 8.0), 7.5)), ..SetDeviation::default() })
+    )]
+    fn test_deviation(
+        #[case] target: Set,
+        #[case] actual: Set,
+        #[case] expected: Option<SetDeviation>,
+    ) {
+        assert_eq!(deviation(target, actual), expected);
+    }
+
+    #[rstest]
+    #[case::empty(SetDeviation::default(), 0, true)]
+    #[case::reps_exceeded(SetDeviation { reps: Some((8, 10)), ..SetDeviation::default() }, 0, true)]
+    #[case::reps_missed(SetDeviation { reps: Some((10, 9)), ..SetDeviation::default() }, 0, false)]
+    #[case::reps_within_tolerance(
+        SetDeviation { reps: Some((10, 9)), ..SetDeviation::default() },
+        10,
+        true
+    )]
+    #[case::reps_beyond_tolerance(
+        SetDeviation { reps: Some((10, 8)), ..SetDeviation::default() },
+        10,
+        false
+    )]
+    #[case::time_met(SetDeviation { time: Some((60, 60)), ..SetDeviation::default() }, 0, true)]
+    #[case::time_missed(
+        SetDeviation { time: Some((60, 45)), ..SetDeviation::default() },
+        10,
+        false
+    )]
+    #[case::weight_missed(
+        SetDeviation { weight: Some((50.0, 47.5)), ..SetDeviation::default() },
+        0,
+        false
+    )]
+    #[case::weight_within_tolerance(
+        SetDeviation { weight: Some((50.0, 47.5)), ..SetDeviation::default() },
+        5,
+        true
+    )]
+    #[case::rpe_lower(
+        SetDeviation { rpe: Some((RpeRange::single(8.0), 7.0)), ..SetDeviation::default() },
+        0,
+        true
+    )]
+    #[case::rpe_exceeded(
+        SetDeviation { rpe: Some((RpeRange::single(8.0), 9.0)), ..SetDeviation::default() },
+        10,
+        false
+    )]
+    #[case::rpe_within_tolerance(
+        SetDeviation { rpe: Some((RpeRange::single(8.0), 8.5)), ..SetDeviation::default() },
+        10,
+        true
+    )]
+    #[case::rpe_range_upper_bound(
+        SetDeviation { rpe: Some((RpeRange::new(7.0, 8.0), 8.0)), ..SetDeviation::default() },
+        0,
+        true
+    )]
+    #[case::rpe_range_exceeded(
+        SetDeviation { rpe: Some((RpeRange::new(7.0, 8.0), 8.5)), ..SetDeviation::default() },
+        0,
+        false
+    )]
+    fn test_set_deviation_target_met(
+        #[case] deviation: SetDeviation,
+        #[case] tolerance: u8,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(deviation.target_met(tolerance), expected);
+    }
+}
@@ -0,0 +1,299 @@
+//! All-time statistics of the complete data of a user.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+
+use crate::{
+    training_streak, BodyWeight, Clock, Exercise, TrainingSession, TrainingSessionElement,
+};
+
+/// Data of a user from which the summary is computed.
+pub struct UserData<'a> {
+    pub training_sessions: &'a BTreeMap<u32, TrainingSession>,
+    pub exercises: &'a BTreeMap<u32, Exercise>,
+    pub body_weight: &'a BTreeMap<NaiveDate, BodyWeight>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub first_training_session: Option<NaiveDate>,
+    pub last_training_session: Option<NaiveDate>,
+    pub training_sessions: u32,
+    /// Number of performed sets.
+    pub sets: u32,
+    /// Total volume load in kg.
+    pub volume_load: u64,
+    /// ID and number of performed sets of the exercise with the most performed sets.
+    pub most_trained_exercise: Option<(u32, u32)>,
+    /// ID and number of hard sets of the muscle with the most hard sets.
+    pub most_trained_muscle: Option<(u8, f32)>,
+    /// Longest number of consecutive weeks in which the weekly session target was met.
+    pub longest_streak: u32,
+    /// Number of different routines for which training sessions were performed.
+    pub routines: u32,
+    /// Lowest and highest body weight.
+    pub body_weight_range: Option<(f32, f32)>,
+}
+
+/// Compute the all-time statistics of all training sessions and body weight values.
+///
+/// Sets without any recorded reps or time are not considered as performed. Ties between exercises
+/// or muscles are resolved in favour of the lower ID. Training sessions after today are only
+/// ignored for the training streak.
+#[must_use]
+pub fn compute(user_data: &UserData, weekly_session_target: u32, clock: &impl Clock) -> Summary {
+    let training_sessions = user_data.training_sessions.values().collect::<Vec<_>>();
+
+    let mut sets_per_exercise: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut stimulus_per_muscle: BTreeMap<u8, u32> = BTreeMap::new();
+    for training_session in &training_sessions {
+        for element in &training_session.elements {
+            if let TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time,
+                ..
+            } = element
+            {
+                if reps.is_some() || time.is_some() {
+                    *sets_per_exercise.entry(*exercise_id).or_insert(0) += 1;
+                }
+            }
+        }
+        for (muscle_id, stimulus) in training_session.stimulus_per_muscle(user_data.exercises) {
+            *stimulus_per_muscle.entry(muscle_id).or_insert(0) += stimulus;
+        }
+    }
+
+    Summary {
+        first_training_session: training_sessions.iter().map(|t| t.date).min(),
+        last_training_session: training_sessions.iter().map(|t| t.date).max(),
+        training_sessions: u32::try_from(training_sessions.len()).unwrap_or(u32::MAX),
+        sets: sets_per_exercise.values().sum(),
+        volume_load: training_sessions
+            .iter()
+            .map(|t| u64::from(t.volume_load(user_data.exercises)))
+            .sum(),
+        most_trained_exercise: most(sets_per_exercise),
+        #[allow(clippy::cast_precision_loss)]
+        most_trained_muscle: most(stimulus_per_muscle)
+            .map(|(muscle_id, stimulus)| (muscle_id, stimulus as f32 / 100.0)),
+        longest_streak: training_streak(
+            &training_sessions.iter().map(|t| t.date).collect::<Vec<_>>(),
+            weekly_session_target,
+            clock.today(),
+        )
+        .longest,
+        routines: u32::try_from(
+            training_sessions
+                .iter()
+                .filter_map(|t| t.routine_id)
+                .collect::<BTreeSet<_>>()
+                .len(),
+        )
+        .unwrap_or(u32::MAX),
+        body_weight_range: user_data
+            .body_weight
+            .values()
+            .map(|bw| (bw.weight, bw.weight))
+            .reduce(|(min, max), (weight, _)| (min.min(weight), max.max(weight))),
+    }
+}
+
+/// Find the key with the highest non-zero value. The lowest key is returned for ties.
+fn most<K: Copy>(values: BTreeMap<K, u32>) -> Option<(K, u32)> {
+    values
+        .into_iter()
+        .filter(|(_, value)| *value > 0)
+        .fold(None, |result, (key, value)| match result {
+            Some((_, max)) if max >= value => result,
+            _ => Some((key, value)),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{ExerciseMuscle, FixedClock, Laterality, Muscle};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn exercises() -> BTreeMap<u32, Exercise> {
+        [
+            (1, "Squat", Muscle::Quads, 100),
+            (2, "Bench Press", Muscle::Pecs, 100),
+        ]
+        .into_iter()
+        .map(|(id, name, muscle, stimulus)| {
+            (
+                id,
+                Exercise {
+                    id,
+                    name: String::from(name),
+                    weight_convention: None,
+                    base_weight: None,
+                    laterality: Laterality::Bilateral,
+                    archived: false,
+                    image: None,
+                    muscles: vec![ExerciseMuscle {
+                        muscle_id: muscle.id(),
+                        stimulus,
+                    }],
+                },
+            )
+        })
+        .collect()
+    }
+
+    fn set(exercise_id: u32, reps: Option<u32>, weight: f32) -> TrainingSessionElement {
+        TrainingSessionElement::Set {
+            exercise_id,
+            reps,
+            time: None,
+            weight: Some(weight),
+            rpe: None,
+            target_reps: None,
+            target_time: None,
+            target_weight: None,
+            target_rpe: None,
+            automatic: false,
+            partial_reps: None,
+            to_failure: false,
+            side: None,
+            comment: None,
+        }
+    }
+
+    fn training_session(
+        id: u32,
+        routine_id: Option<u32>,
+        date: NaiveDate,
+        elements: Vec<TrainingSessionElement>,
+    ) -> (u32, TrainingSession) {
+        (
+            id,
+            TrainingSession {
+                id,
+                routine_id,
+                date,
+                notes: None,
+                start_time: None,
+                end_time: None,
+                elements,
+            },
+        )
+    }
+
+    fn body_weight(month: u32, day: u32, weight: f32) -> (NaiveDate, BodyWeight) {
+        (
+            date(month, day),
+            BodyWeight {
+                date: date(month, day),
+                weight,
+            },
+        )
+    }
+
+    #[test]
+    fn test_compute_empty() {
+        assert_eq!(
+            compute(
+                &UserData {
+                    training_sessions: &BTreeMap::new(),
+                    exercises: &BTreeMap::new(),
+                    body_weight: &BTreeMap::new(),
+                },
+                1,
+                &FixedClock(date(2, 1)),
+            ),
+            Summary::default()
+        );
+    }
+
+    #[test]
+    fn test_compute_single_training_session() {
+        let training_sessions = BTreeMap::from([training_session(
+            1,
+            None,
+            date(1, 10),
+            vec![set(1, Some(5), 100.0), set(1, None, 100.0)],
+        )]);
+
+        assert_eq!(
+            compute(
+                &UserData {
+                    training_sessions: &training_sessions,
+                    exercises: &exercises(),
+                    body_weight: &BTreeMap::from([body_weight(1, 10, 80.0)]),
+                },
+                1,
+                &FixedClock(date(2, 1)),
+            ),
+            Summary {
+                first_training_session: Some(date(1, 10)),
+                last_training_session: Some(date(1, 10)),
+                training_sessions: 1,
+                sets: 1,
+                volume_load: 500,
+                most_trained_exercise: Some((1, 1)),
+                most_trained_muscle: Some((Muscle::Quads.id(), 1.0)),
+                longest_streak: 1,
+                routines: 0,
+                body_weight_range: Some((80.0, 80.0)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_compute() {
+        let training_sessions = BTreeMap::from([
+            training_session(
+                1,
+                Some(1),
+                date(1, 1),
+                vec![set(1, Some(5), 100.0), set(2, Some(5), 80.0)],
+            ),
+            training_session(2, Some(2), date(1, 8), vec![set(2, Some(8), 60.0)]),
+            training_session(
+                3,
+                Some(1),
+                date(1, 15),
+                vec![set(1, Some(5), 100.0), set(2, Some(10), 50.0)],
+            ),
+            training_session(4, None, date(1, 29), vec![set(1, Some(3), 120.0)]),
+        ]);
+
+        assert_eq!(
+            compute(
+                &UserData {
+                    training_sessions: &training_sessions,
+                    exercises: &exercises(),
+                    body_weight: &BTreeMap::from([
+                        body_weight(1, 1, 81.5),
+                        body_weight(1, 15, 79.0),
+                        body_weight(1, 29, 80.2),
+                    ]),
+                },
+                1,
+                &FixedClock(date(2, 1)),
+            ),
+            Summary {
+                first_training_session: Some(date(1, 1)),
+                last_training_session: Some(date(1, 29)),
+                training_sessions: 4,
+                sets: 6,
+                volume_load: 2740,
+                most_trained_exercise: Some((1, 3)),
+                most_trained_muscle: Some((Muscle::Pecs.id(), 3.0)),
+                longest_streak: 3,
+                routines: 2,
+                body_weight_range: Some((79.0, 81.5)),
+            }
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use valens_domain::ExerciseImage;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{js_sys, IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+/// Cache for exercise images.
+///
+/// Images are too large to be kept in the local storage. They are stored in an `IndexedDB` object
+/// store instead, keyed by the exercise ID. If the storage quota is exceeded, the least recently
+/// cached images are evicted.
+pub struct ImageCache;
+
+const DATABASE_NAME: &str = "valens";
+const DATABASE_VERSION: u32 = 1;
+const STORE_EXERCISE_IMAGES: &str = "exercise images";
+
+#[derive(Serialize, Deserialize)]
+struct CachedImage {
+    digest: String,
+    image: ExerciseImage,
+    /// Time of caching in milliseconds since the Unix epoch.
+    #[serde(default)]
+    cached_at: f64,
+}
+
+const QUOTA_EXCEEDED_ERROR: &str = "QuotaExceededError";
+
+#[async_trait(?Send)]
+impl super::ImageCache for ImageCache {
+    async fn read_exercise_image(
+        &self,
+        exercise_id: u32,
+        digest: String,
+    ) -> Result<Option<ExerciseImage>, String> {
+        let store = object_store(IdbTransactionMode::Readonly).await?;
+        let value = result(&store.get(&exercise_id.into()).map_err(to_string)?).await?;
+        let Some(value) = value.as_string() else {
+            return Ok(None);
+        };
+        let cached_image =
+            serde_json::from_str::<CachedImage>(&value).map_err(|err| err.to_string())?;
+        // An outdated image is treated like a missing image
+        if cached_image.digest == digest {
+            Ok(Some(cached_image.image))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn write_exercise_image(
+        &self,
+        exercise_id: u32,
+        digest: String,
+        image: ExerciseImage,
+    ) -> Result<(), String> {
+        let value = serde_json::to_string(&CachedImage {
+            digest,
+            image,
+            cached_at: js_sys::Date::now(),
+        })
+        .map_err(|err| err.to_string())?;
+        match put(exercise_id, &value).await {
+            Err(err) if is_quota_exceeded(&err) => {
+                evict_oldest_images().await?;
+                // The write is retried only once to prevent repeated evictions
+                put(exercise_id, &value).await.map_err(|err| {
+                    if is_quota_exceeded(&err) {
+                        super::QUOTA_EXCEEDED.to_string()
+                    } else {
+                        to_string(err)
+                    }
+                })
+            }
+            result => result.map_err(to_string),
+        }
+    }
+
+    async fn delete_exercise_image(&self, exercise_id: u32) -> Result<(), String> {
+        let store = object_store(IdbTransactionMode::Readwrite).await?;
+        result(&store.delete(&exercise_id.into()).map_err(to_string)?).await?;
+        Ok(())
+    }
+
+    async fn count_exercise_images(&self) -> Result<u32, String> {
+        let store = object_store(IdbTransactionMode::Readonly).await?;
+        let count = result(&store.count().map_err(to_string)?).await?;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let count = count.as_f64().unwrap_or_default() as u32;
+        Ok(count)
+    }
+
+    async fn clear_exercise_images(&self) -> Result<(), String> {
+        let store = object_store(IdbTransactionMode::Readwrite).await?;
+        result(&store.clear().map_err(to_string)?).await?;
+        Ok(())
+    }
+}
+
+async fn put(exercise_id: u32, value: &str) -> Result<(), JsValue> {
+    let store = object_store(IdbTransactionMode::Readwrite)
+        .await
+        .map_err(JsValue::from)?;
+    request_result(&store.put_with_key(&value.into(), &exercise_id.into())?).await?;
+    Ok(())
+}
+
+/// Delete the older half of the cached images, but at least one image.
+async fn evict_oldest_images() -> Result<(), String> {
+    let store = object_store(IdbTransactionMode::Readonly).await?;
+    let keys = store.get_all_keys().map_err(to_string)?;
+    let values = store.get_all().map_err(to_string)?;
+    let keys = js_sys::Array::from(&result(&keys).await?);
+    let values = js_sys::Array::from(&result(&values).await?);
+
+    let mut cached_images = keys
+        .iter()
+        .zip(values.iter())
+        .map(|(key, value)| {
+            let cached_at = value
+                .as_string()
+                .and_then(|value| serde_json::from_str::<CachedImage>(&value).ok())
+                .map_or(0.0, |cached_image| cached_image.cached_at);
+            (cached_at, key)
+        })
+        .collect::<Vec<_>>();
+    cached_images.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let store = object_store(IdbTransactionMode::Readwrite).await?;
+    let requests = cached_images
+        .iter()
+        .take(cached_images.len().div_ceil(2))
+        .map(|(_, key)| store.delete(key).map_err(to_string))
+        .collect::<Result<Vec<_>, _>>()?;
+    for request in requests {
+        result(&request).await?;
+    }
+    Ok(())
+}
+
+async fn open() -> Result<IdbDatabase, String> {
+    let factory = web_sys::window()
+        .ok_or("no window")?
+        .indexed_db()
+        .map_err(to_string)?
+        .ok_or("IndexedDB not supported")?;
+    let request = factory
+        .open_with_u32(DATABASE_NAME, DATABASE_VERSION)
+        .map_err(to_string)?;
+    let on_upgrade_needed = Closure::once_into_js({
+        let request = request.clone();
+        move || {
+            if let Ok(database) = request.result() {
+                let database = database.unchecked_into::<IdbDatabase>();
+                if !database
+                    .object_store_names()
+                    .contains(STORE_EXERCISE_IMAGES)
+                {
+                    let _ = database.create_object_store(STORE_EXERCISE_IMAGES);
+                }
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade_needed.unchecked_ref()));
+    Ok(result(&request).await?.unchecked_into::<IdbDatabase>())
+}
+
+async fn object_store(mode: IdbTransactionMode) -> Result<IdbObjectStore, String> {
+    let database = open().await?;
+    let store = database
+        .transaction_with_str_and_mode(STORE_EXERCISE_IMAGES, mode)
+        .and_then(|transaction| transaction.object_store(STORE_EXERCISE_IMAGES))
+        .map_err(to_string);
+    // The connection is closed as soon as the pending transaction has completed
+    database.close();
+    store
+}
+
+async fn result(request: &IdbRequest) -> Result<JsValue, String> {
+    request_result(request).await.map_err(to_string)
+}
+
+async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let on_success = Closure::once_into_js({
+            let request = request.clone();
+            move || {
+                let _ = resolve.call1(&JsValue::UNDEFINED, &request.result().unwrap_or_default());
+            }
+        });
+        let on_error = Closure::once_into_js({
+            let request = request.clone();
+            move || {
+                let error = request
+                    .error()
+                    .ok()
+                    .flatten()
+                    .map_or(JsValue::UNDEFINED, JsValue::from);
+                let _ = reject.call1(&JsValue::UNDEFINED, &error);
+            }
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
+
+fn is_quota_exceeded(value: &JsValue) -> bool {
+    value
+        .dyn_ref::<web_sys::DomException>()
+        .is_some_and(|exception| exception.name() == QUOTA_EXCEEDED_ERROR)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn to_string(value: JsValue) -> String {
+    value
+        .dyn_ref::<web_sys::DomException>()
+        .map_or_else(|| format!("{value:?}"), web_sys::DomException::message)
+}
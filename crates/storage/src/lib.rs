@@ -1,20 +1,24 @@
 #![warn(clippy::pedantic)]
 
 use async_trait::async_trait;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, Weekday};
 use valens_domain::{
-    BodyFat, BodyWeight, Exercise, ExerciseMuscle, Period, Routine, RoutinePart, TrainingSession,
-    TrainingSessionElement, User,
+    BodyFat, BodyMeasurement, BodyWeight, Exercise, ExerciseImage, ExerciseMuscle, Period, Routine,
+    RoutinePart, Tag, TrainingSession, TrainingSessionElement, User,
 };
-use valens_web_app::{OngoingTrainingSession, Settings};
+use valens_web_app::{trash::Trash, OngoingTrainingSession, Settings, TrainingSessionDraft};
 
+pub mod indexed_db;
 #[allow(clippy::module_name_repetitions)]
 pub mod local_storage;
 pub mod rest;
 
+/// Error of a write which failed due to insufficient storage space on the device.
+pub const QUOTA_EXCEEDED: &str = "storage quota exceeded";
+
 #[async_trait(?Send)]
 pub trait Storage {
-    async fn request_session(&self, user_id: u32) -> Result<User, String>;
+    async fn request_session(&self, user_id: u32, pin: Option<String>) -> Result<User, String>;
     async fn initialize_session(&self) -> Result<User, String>;
     async fn delete_session(&self) -> Result<(), String>;
 
@@ -24,6 +28,7 @@ pub trait Storage {
     async fn create_user(&self, name: String, sex: u8) -> Result<User, String>;
     async fn replace_user(&self, user: User) -> Result<User, String>;
     async fn delete_user(&self, id: u32) -> Result<u32, String>;
+    async fn replace_user_pin(&self, id: u32, pin: Option<String>) -> Result<User, String>;
 
     async fn read_body_weight(&self) -> Result<Vec<BodyWeight>, String>;
     async fn create_body_weight(&self, body_weight: BodyWeight) -> Result<BodyWeight, String>;
@@ -35,6 +40,17 @@ pub trait Storage {
     async fn replace_body_fat(&self, body_fat: BodyFat) -> Result<BodyFat, String>;
     async fn delete_body_fat(&self, date: NaiveDate) -> Result<NaiveDate, String>;
 
+    async fn read_body_measurement(&self) -> Result<Vec<BodyMeasurement>, String>;
+    async fn create_body_measurement(
+        &self,
+        body_measurement: BodyMeasurement,
+    ) -> Result<BodyMeasurement, String>;
+    async fn replace_body_measurement(
+        &self,
+        body_measurement: BodyMeasurement,
+    ) -> Result<BodyMeasurement, String>;
+    async fn delete_body_measurement(&self, date: NaiveDate) -> Result<NaiveDate, String>;
+
     async fn read_period(&self) -> Result<Vec<Period>, String>;
     async fn create_period(&self, period: Period) -> Result<Period, String>;
     async fn replace_period(&self, period: Period) -> Result<Period, String>;
@@ -48,12 +64,20 @@ pub trait Storage {
     ) -> Result<Exercise, String>;
     async fn replace_exercise(&self, exercise: Exercise) -> Result<Exercise, String>;
     async fn delete_exercise(&self, id: u32) -> Result<u32, String>;
+    async fn read_exercise_image(&self, id: u32) -> Result<ExerciseImage, String>;
+    async fn replace_exercise_image(
+        &self,
+        id: u32,
+        image: ExerciseImage,
+    ) -> Result<Exercise, String>;
+    async fn delete_exercise_image(&self, id: u32) -> Result<u32, String>;
 
     async fn read_routines(&self) -> Result<Vec<Routine>, String>;
     async fn create_routine(
         &self,
         name: String,
         sections: Vec<RoutinePart>,
+        tags: Vec<Tag>,
     ) -> Result<Routine, String>;
     async fn modify_routine(
         &self,
@@ -61,10 +85,16 @@ pub trait Storage {
         name: Option<String>,
         archived: Option<bool>,
         sections: Option<Vec<RoutinePart>>,
+        schedule: Option<Vec<Weekday>>,
+        tags: Option<Vec<Tag>>,
     ) -> Result<Routine, String>;
     async fn delete_routine(&self, id: u32) -> Result<u32, String>;
 
-    async fn read_training_sessions(&self) -> Result<Vec<TrainingSession>, String>;
+    async fn read_training_sessions(
+        &self,
+        first: Option<NaiveDate>,
+        last: Option<NaiveDate>,
+    ) -> Result<Vec<TrainingSession>, String>;
     async fn create_training_session(
         &self,
         routine_id: Option<u32>,
@@ -77,6 +107,8 @@ pub trait Storage {
         id: u32,
         notes: Option<String>,
         elements: Option<Vec<TrainingSessionElement>>,
+        start_time: Option<NaiveDateTime>,
+        end_time: Option<NaiveDateTime>,
     ) -> Result<TrainingSession, String>;
     async fn delete_training_session(&self, id: u32) -> Result<u32, String>;
 }
@@ -92,4 +124,37 @@ pub trait UI {
         &self,
         ongoing_training_session: Option<OngoingTrainingSession>,
     ) -> Result<(), String>;
+
+    async fn read_training_session_draft(
+        &self,
+        id: u32,
+    ) -> Result<Option<TrainingSessionDraft>, String>;
+    async fn write_training_session_draft(
+        &self,
+        id: u32,
+        draft: Option<TrainingSessionDraft>,
+    ) -> Result<(), String>;
+    async fn read_trash(&self, user_id: u32) -> Result<Trash, String>;
+    async fn write_trash(&self, user_id: u32, trash: Trash) -> Result<(), String>;
+}
+
+/// Local copies of images to avoid fetching them again from the server.
+#[async_trait(?Send)]
+pub trait ImageCache {
+    /// Read the cached image of an exercise. No image is returned if the cached image has a
+    /// different digest, i.e., it has been replaced in the meantime.
+    async fn read_exercise_image(
+        &self,
+        exercise_id: u32,
+        digest: String,
+    ) -> Result<Option<ExerciseImage>, String>;
+    async fn write_exercise_image(
+        &self,
+        exercise_id: u32,
+        digest: String,
+        image: ExerciseImage,
+    ) -> Result<(), String>;
+    async fn delete_exercise_image(&self, exercise_id: u32) -> Result<(), String>;
+    async fn count_exercise_images(&self) -> Result<u32, String>;
+    async fn clear_exercise_images(&self) -> Result<(), String>;
 }
@@ -1,11 +1,13 @@
 use async_trait::async_trait;
 use gloo_storage::Storage as GlooStorage;
-use valens_web_app::{OngoingTrainingSession, Settings};
+use valens_web_app::{trash::Trash, OngoingTrainingSession, Settings, TrainingSessionDraft};
 
 pub struct UI;
 
 const KEY_SETTINGS: &str = "settings";
 const KEY_ONGOING_TRAINING_SESSION: &str = "ongoing training session";
+const KEY_TRAINING_SESSION_DRAFT: &str = "training session draft";
+const KEY_TRASH: &str = "trash";
 
 #[async_trait(?Send)]
 impl super::UI for UI {
@@ -34,4 +36,55 @@ impl super::UI for UI {
         )
         .map_err(|err| err.to_string())
     }
+
+    async fn read_training_session_draft(
+        &self,
+        id: u32,
+    ) -> Result<Option<TrainingSessionDraft>, String> {
+        match gloo_storage::LocalStorage::get(training_session_draft_key(id)) {
+            Ok(draft) => Ok(Some(draft)),
+            Err(gloo_storage::errors::StorageError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    async fn write_training_session_draft(
+        &self,
+        id: u32,
+        draft: Option<TrainingSessionDraft>,
+    ) -> Result<(), String> {
+        if let Some(draft) = draft {
+            gloo_storage::LocalStorage::set(training_session_draft_key(id), draft)
+                .map_err(|err| err.to_string())
+        } else {
+            gloo_storage::LocalStorage::delete(training_session_draft_key(id));
+            Ok(())
+        }
+    }
+
+    async fn read_trash(&self, user_id: u32) -> Result<Trash, String> {
+        match gloo_storage::LocalStorage::get(trash_key(user_id)) {
+            Ok(trash) => Ok(trash),
+            Err(gloo_storage::errors::StorageError::KeyNotFound(_)) => Ok(Trash::default()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    async fn write_trash(&self, user_id: u32, trash: Trash) -> Result<(), String> {
+        if trash.is_empty() {
+            gloo_storage::LocalStorage::delete(trash_key(user_id));
+            Ok(())
+        } else {
+            gloo_storage::LocalStorage::set(trash_key(user_id), trash)
+                .map_err(|err| err.to_string())
+        }
+    }
+}
+
+fn training_session_draft_key(id: u32) -> String {
+    format!("{KEY_TRAINING_SESSION_DRAFT} {id}")
+}
+
+fn trash_key(user_id: u32) -> String {
+    format!("{KEY_TRASH} {user_id}")
 }
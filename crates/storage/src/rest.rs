@@ -1,113 +1,211 @@
 use async_trait::async_trait;
-use chrono::NaiveDate;
-use gloo_net::http::Request;
+use chrono::{NaiveDate, NaiveDateTime, Weekday};
+use gloo_net::http::{Method, Request};
 use serde_json::{json, Map};
 
 use super::{
-    BodyFat, BodyWeight, Exercise, ExerciseMuscle, Period, Routine, RoutinePart, TrainingSession,
-    TrainingSessionElement, User,
+    BodyFat, BodyMeasurement, BodyWeight, Exercise, ExerciseImage, ExerciseMuscle, Period, Routine,
+    RoutinePart, Tag, TrainingSession, TrainingSessionElement, User,
 };
 
-pub struct Storage;
+pub mod scheduler;
+
+use scheduler::{Error, RetryPolicy, Scheduler, SendRequest};
+
+pub struct Storage {
+    scheduler: Scheduler<Fetch>,
+}
+
+impl Storage {
+    /// Create a storage which limits the number of concurrent requests to the server.
+    #[must_use]
+    pub fn new(max_concurrent_requests: usize) -> Self {
+        Self {
+            scheduler: Scheduler::new(Fetch, max_concurrent_requests, RetryPolicy::default()),
+        }
+    }
+
+    async fn fetch<T>(&self, request: impl Fn() -> Request) -> Result<T, String>
+    where
+        T: 'static + for<'de> serde::Deserialize<'de>,
+    {
+        let body = self
+            .scheduler
+            .send(request)
+            .await
+            .map_err(|error| error.to_string())?;
+        serde_json::from_str(&body)
+            .map_err(|error| Error::Deserialization(format!("{error:?}")).to_string())
+    }
+
+    async fn fetch_no_content<T>(
+        &self,
+        request: impl Fn() -> Request,
+        result: T,
+    ) -> Result<T, String> {
+        self.scheduler
+            .send(request)
+            .await
+            .map(|_| result)
+            .map_err(|error| error.to_string())
+    }
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self::new(scheduler::DEFAULT_MAX_CONCURRENT_REQUESTS)
+    }
+}
+
+/// Sending of requests using the Fetch API.
+struct Fetch;
+
+#[async_trait(?Send)]
+impl SendRequest for Fetch {
+    type Request = Request;
+    type Response = String;
+
+    async fn send(&self, request: Request) -> Result<String, Error> {
+        let response = request.send().await.map_err(|_| Error::NoConnection)?;
+        if !response.ok() {
+            return Err(Error::Status {
+                code: response.status(),
+                text: response.status_text(),
+            });
+        }
+        response.text().await.map_err(|_| Error::NoConnection)
+    }
+
+    async fn sleep(&self, millis: u32) {
+        gloo_timers::future::TimeoutFuture::new(millis).await;
+    }
+
+    fn random(&self) -> f64 {
+        js_sys::Math::random()
+    }
+
+    /// Requests for creating entries are not repeated to prevent duplicate entries.
+    fn retryable(&self, request: &Request) -> bool {
+        request.method() != Method::POST
+    }
+}
 
 #[async_trait(?Send)]
 impl super::Storage for Storage {
-    async fn request_session(&self, user_id: u32) -> Result<User, String> {
-        fetch(
+    async fn request_session(&self, user_id: u32, pin: Option<String>) -> Result<User, String> {
+        self.fetch(|| {
             Request::post("api/session")
-                .json(&json!({ "id": user_id }))
-                .expect("serialization failed"),
-        )
+                .json(&json!({ "id": user_id, "pin": pin }))
+                .expect("serialization failed")
+        })
         .await
     }
 
     async fn initialize_session(&self) -> Result<User, String> {
-        fetch(Request::get("api/session").build().unwrap()).await
+        self.fetch(|| Request::get("api/session").build().unwrap())
+            .await
     }
 
     async fn delete_session(&self) -> Result<(), String> {
-        fetch_no_content(Request::delete("api/session").build().unwrap(), ()).await
+        self.fetch_no_content(|| Request::delete("api/session").build().unwrap(), ())
+            .await
     }
 
     async fn read_version(&self) -> Result<String, String> {
-        fetch(Request::get("api/version").build().unwrap()).await
+        self.fetch(|| Request::get("api/version").build().unwrap())
+            .await
     }
 
     async fn read_users(&self) -> Result<Vec<User>, String> {
-        fetch(Request::get("api/users").build().unwrap()).await
+        self.fetch(|| Request::get("api/users").build().unwrap())
+            .await
     }
     async fn create_user(&self, name: String, sex: u8) -> Result<User, String> {
-        fetch(
+        self.fetch(|| {
             Request::post("api/users")
                 .json(&json!({
                     "name": name,
                     "sex": sex,
                 }))
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn replace_user(&self, user: User) -> Result<User, String> {
-        fetch(
+        self.fetch(|| {
             Request::put(&format!("api/users/{}", user.id))
                 .json(&json!({
                     "name": user.name,
                     "sex": user.sex,
+                    "height": user.height,
+                    "birthdate": user.birthdate,
                 }))
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn delete_user(&self, id: u32) -> Result<u32, String> {
-        fetch_no_content(
-            Request::delete(&format!("api/users/{id}")).build().unwrap(),
+        self.fetch_no_content(
+            || Request::delete(&format!("api/users/{id}")).build().unwrap(),
             id,
         )
         .await
     }
+    async fn replace_user_pin(&self, id: u32, pin: Option<String>) -> Result<User, String> {
+        self.fetch(|| {
+            Request::put(&format!("api/users/{id}/pin"))
+                .json(&json!({ "pin": pin }))
+                .expect("serialization failed")
+        })
+        .await
+    }
 
     async fn read_body_weight(&self) -> Result<Vec<BodyWeight>, String> {
-        fetch(Request::get("api/body_weight").build().unwrap()).await
+        self.fetch(|| Request::get("api/body_weight").build().unwrap())
+            .await
     }
     async fn create_body_weight(&self, body_weight: BodyWeight) -> Result<BodyWeight, String> {
-        fetch(
+        self.fetch(|| {
             Request::post("api/body_weight")
                 .json(&body_weight)
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn replace_body_weight(&self, body_weight: BodyWeight) -> Result<BodyWeight, String> {
-        fetch(
+        self.fetch(|| {
             Request::put(&format!("api/body_weight/{}", body_weight.date))
                 .json(&json!({ "weight": body_weight.weight }))
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn delete_body_weight(&self, date: NaiveDate) -> Result<NaiveDate, String> {
-        fetch_no_content(
-            Request::delete(&format!("api/body_weight/{date}"))
-                .build()
-                .unwrap(),
+        self.fetch_no_content(
+            || {
+                Request::delete(&format!("api/body_weight/{date}"))
+                    .build()
+                    .unwrap()
+            },
             date,
         )
         .await
     }
 
     async fn read_body_fat(&self) -> Result<Vec<BodyFat>, String> {
-        fetch(Request::get("api/body_fat").build().unwrap()).await
+        self.fetch(|| Request::get("api/body_fat").build().unwrap())
+            .await
     }
     async fn create_body_fat(&self, body_fat: BodyFat) -> Result<BodyFat, String> {
-        fetch(
+        self.fetch(|| {
             Request::post("api/body_fat")
                 .json(&body_fat)
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn replace_body_fat(&self, body_fat: BodyFat) -> Result<BodyFat, String> {
-        fetch(
+        self.fetch(|| {
             Request::put(&format!("api/body_fat/{}", body_fat.date))
                 .json(&json!({
                     "chest": body_fat.chest,
@@ -118,100 +216,187 @@ impl super::Storage for Storage {
                     "suprailiac": body_fat.suprailiac,
                     "midaxillary": body_fat.midaxillary,
                 }))
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn delete_body_fat(&self, date: NaiveDate) -> Result<NaiveDate, String> {
-        fetch_no_content(
-            Request::delete(&format!("api/body_fat/{date}"))
-                .build()
-                .unwrap(),
+        self.fetch_no_content(
+            || {
+                Request::delete(&format!("api/body_fat/{date}"))
+                    .build()
+                    .unwrap()
+            },
+            date,
+        )
+        .await
+    }
+
+    async fn read_body_measurement(&self) -> Result<Vec<BodyMeasurement>, String> {
+        self.fetch(|| Request::get("api/body_measurement").build().unwrap())
+            .await
+    }
+    async fn create_body_measurement(
+        &self,
+        body_measurement: BodyMeasurement,
+    ) -> Result<BodyMeasurement, String> {
+        self.fetch(|| {
+            Request::post("api/body_measurement")
+                .json(&body_measurement)
+                .expect("serialization failed")
+        })
+        .await
+    }
+    async fn replace_body_measurement(
+        &self,
+        body_measurement: BodyMeasurement,
+    ) -> Result<BodyMeasurement, String> {
+        self.fetch(|| {
+            Request::put(&format!("api/body_measurement/{}", body_measurement.date))
+                .json(&json!({
+                    "waist": body_measurement.waist,
+                    "hips": body_measurement.hips,
+                    "neck": body_measurement.neck,
+                    "upper_arm": body_measurement.upper_arm,
+                    "thigh": body_measurement.thigh,
+                    "chest": body_measurement.chest,
+                }))
+                .expect("serialization failed")
+        })
+        .await
+    }
+    async fn delete_body_measurement(&self, date: NaiveDate) -> Result<NaiveDate, String> {
+        self.fetch_no_content(
+            || {
+                Request::delete(&format!("api/body_measurement/{date}"))
+                    .build()
+                    .unwrap()
+            },
             date,
         )
         .await
     }
 
     async fn read_period(&self) -> Result<Vec<Period>, String> {
-        fetch(Request::get("api/period").build().unwrap()).await
+        self.fetch(|| Request::get("api/period").build().unwrap())
+            .await
     }
     async fn create_period(&self, period: Period) -> Result<Period, String> {
-        fetch(
+        self.fetch(|| {
             Request::post("api/period")
                 .json(&period)
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn replace_period(&self, period: Period) -> Result<Period, String> {
-        fetch(
+        self.fetch(|| {
             Request::put(&format!("api/period/{}", period.date))
                 .json(&json!({ "intensity": period.intensity }))
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn delete_period(&self, date: NaiveDate) -> Result<NaiveDate, String> {
-        fetch_no_content(
-            Request::delete(&format!("api/period/{date}"))
-                .build()
-                .unwrap(),
+        self.fetch_no_content(
+            || {
+                Request::delete(&format!("api/period/{date}"))
+                    .build()
+                    .unwrap()
+            },
             date,
         )
         .await
     }
 
     async fn read_exercises(&self) -> Result<Vec<Exercise>, String> {
-        fetch(Request::get("api/exercises").build().unwrap()).await
+        self.fetch(|| Request::get("api/exercises").build().unwrap())
+            .await
     }
     async fn create_exercise(
         &self,
         name: String,
         muscles: Vec<ExerciseMuscle>,
     ) -> Result<Exercise, String> {
-        fetch(
+        self.fetch(|| {
             Request::post("api/exercises")
                 .json(&json!({ "name": name, "muscles": muscles }))
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn replace_exercise(&self, exercise: Exercise) -> Result<Exercise, String> {
-        fetch(
+        self.fetch(|| {
             Request::put(&format!("api/exercises/{}", exercise.id))
                 .json(&exercise)
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn delete_exercise(&self, id: u32) -> Result<u32, String> {
-        fetch_no_content(
-            Request::delete(&format!("api/exercises/{id}"))
+        self.fetch_no_content(
+            || {
+                Request::delete(&format!("api/exercises/{id}"))
+                    .build()
+                    .unwrap()
+            },
+            id,
+        )
+        .await
+    }
+    async fn read_exercise_image(&self, id: u32) -> Result<ExerciseImage, String> {
+        self.fetch(|| {
+            Request::get(&format!("api/exercises/{id}/image"))
                 .build()
-                .unwrap(),
+                .unwrap()
+        })
+        .await
+    }
+    async fn replace_exercise_image(
+        &self,
+        id: u32,
+        image: ExerciseImage,
+    ) -> Result<Exercise, String> {
+        self.fetch(|| {
+            Request::put(&format!("api/exercises/{id}/image"))
+                .json(&image)
+                .expect("serialization failed")
+        })
+        .await
+    }
+    async fn delete_exercise_image(&self, id: u32) -> Result<u32, String> {
+        self.fetch_no_content(
+            || {
+                Request::delete(&format!("api/exercises/{id}/image"))
+                    .build()
+                    .unwrap()
+            },
             id,
         )
         .await
     }
 
     async fn read_routines(&self) -> Result<Vec<Routine>, String> {
-        fetch(Request::get("api/routines").build().unwrap()).await
+        self.fetch(|| Request::get("api/routines").build().unwrap())
+            .await
     }
     async fn create_routine(
         &self,
         name: String,
         sections: Vec<RoutinePart>,
+        tags: Vec<Tag>,
     ) -> Result<Routine, String> {
-        fetch(
+        self.fetch(|| {
             Request::post("api/routines")
                 .json(&json!({
                     "name": name,
                     "notes": "",
                     "archived": false,
-                    "sections": sections
+                    "sections": sections,
+                    "tags": tags
                 }))
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn modify_routine(
@@ -220,6 +405,8 @@ impl super::Storage for Storage {
         name: Option<String>,
         archived: Option<bool>,
         sections: Option<Vec<RoutinePart>>,
+        schedule: Option<Vec<Weekday>>,
+        tags: Option<Vec<Tag>>,
     ) -> Result<Routine, String> {
         let mut content = Map::new();
         if let Some(name) = name {
@@ -231,25 +418,47 @@ impl super::Storage for Storage {
         if let Some(sections) = sections {
             content.insert("sections".into(), json!(sections));
         }
-        fetch(
+        if let Some(schedule) = schedule {
+            content.insert("schedule".into(), json!(schedule));
+        }
+        if let Some(tags) = tags {
+            content.insert("tags".into(), json!(tags));
+        }
+        self.fetch(|| {
             Request::patch(&format!("api/routines/{id}"))
                 .json(&content)
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn delete_routine(&self, id: u32) -> Result<u32, String> {
-        fetch_no_content(
-            Request::delete(&format!("api/routines/{id}"))
-                .build()
-                .unwrap(),
+        self.fetch_no_content(
+            || {
+                Request::delete(&format!("api/routines/{id}"))
+                    .build()
+                    .unwrap()
+            },
             id,
         )
         .await
     }
 
-    async fn read_training_sessions(&self) -> Result<Vec<TrainingSession>, String> {
-        fetch(Request::get("api/workouts").build().unwrap()).await
+    async fn read_training_sessions(
+        &self,
+        first: Option<NaiveDate>,
+        last: Option<NaiveDate>,
+    ) -> Result<Vec<TrainingSession>, String> {
+        self.fetch(|| {
+            Request::get("api/workouts")
+                .query(
+                    [("first", first), ("last", last)]
+                        .into_iter()
+                        .filter_map(|(name, date)| date.map(|d| (name, d.to_string()))),
+                )
+                .build()
+                .unwrap()
+        })
+        .await
     }
     async fn create_training_session(
         &self,
@@ -258,7 +467,7 @@ impl super::Storage for Storage {
         notes: String,
         elements: Vec<TrainingSessionElement>,
     ) -> Result<TrainingSession, String> {
-        fetch(
+        self.fetch(|| {
             Request::post("api/workouts")
                 .json(&json!({
                     "routine_id": routine_id,
@@ -266,8 +475,8 @@ impl super::Storage for Storage {
                     "notes": notes,
                     "elements": elements
                 }))
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn modify_training_session(
@@ -275,6 +484,8 @@ impl super::Storage for Storage {
         id: u32,
         notes: Option<String>,
         elements: Option<Vec<TrainingSessionElement>>,
+        start_time: Option<NaiveDateTime>,
+        end_time: Option<NaiveDateTime>,
     ) -> Result<TrainingSession, String> {
         let mut content = Map::new();
         if let Some(notes) = notes {
@@ -283,52 +494,28 @@ impl super::Storage for Storage {
         if let Some(elements) = elements {
             content.insert("elements".into(), json!(elements));
         }
-        fetch(
+        if let Some(start_time) = start_time {
+            content.insert("start_time".into(), json!(start_time));
+        }
+        if let Some(end_time) = end_time {
+            content.insert("end_time".into(), json!(end_time));
+        }
+        self.fetch(|| {
             Request::patch(&format!("api/workouts/{id}"))
                 .json(&content)
-                .expect("serialization failed"),
-        )
+                .expect("serialization failed")
+        })
         .await
     }
     async fn delete_training_session(&self, id: u32) -> Result<u32, String> {
-        fetch_no_content(
-            Request::delete(&format!("api/workouts/{id}"))
-                .build()
-                .unwrap(),
+        self.fetch_no_content(
+            || {
+                Request::delete(&format!("api/workouts/{id}"))
+                    .build()
+                    .unwrap()
+            },
             id,
         )
         .await
     }
 }
-
-async fn fetch<'a, T>(request: Request) -> Result<T, String>
-where
-    T: 'static + for<'de> serde::Deserialize<'de>,
-{
-    match request.send().await {
-        Ok(response) => {
-            if response.ok() {
-                match response.json::<T>().await {
-                    Ok(data) => Ok(data),
-                    Err(error) => Err(format!("deserialization failed: {error:?}")),
-                }
-            } else {
-                Err(format!("{} {}", response.status(), response.status_text()))
-            }
-        }
-        Err(_) => Err("no connection".into()),
-    }
-}
-
-async fn fetch_no_content<'a, T>(request: Request, result: T) -> Result<T, String> {
-    match request.send().await {
-        Ok(response) => {
-            if response.ok() {
-                Ok(result)
-            } else {
-                Err(format!("{} {}", response.status(), response.status_text()))
-            }
-        }
-        Err(_) => Err("no connection".into()),
-    }
-}
@@ -0,0 +1,408 @@
+//! Scheduling of requests with a limited number of concurrent requests and retries of transient
+//! failures.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use async_trait::async_trait;
+
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The server could not be reached or the connection was interrupted.
+    NoConnection,
+    /// The server responded with an error status.
+    Status {
+        code: u16,
+        text: String,
+    },
+    Deserialization(String),
+}
+
+impl Error {
+    /// Check whether the failure may disappear when the request is repeated.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::NoConnection => true,
+            Error::Status { code, .. } => matches!(code, 502..=504),
+            Error::Deserialization(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoConnection => write!(f, "no connection"),
+            Error::Status { code, text } => write!(f, "{code} {text}"),
+            Error::Deserialization(message) => write!(f, "deserialization failed: {message}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds.
+    pub initial_delay: u32,
+    /// Upper bound of the delay in milliseconds.
+    pub max_delay: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: 500,
+            max_delay: 4000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Calculate the delay before the retry with the given zero-based number.
+    ///
+    /// The delay is doubled for each retry up to the maximum delay. The second half of the delay
+    /// is scaled by the jitter, which is expected to be in the range from 0 to 1, to prevent that
+    /// failed requests are repeated simultaneously.
+    #[must_use]
+    pub fn delay(&self, retry: u32, jitter: f64) -> u32 {
+        let delay = self
+            .initial_delay
+            .saturating_mul(2_u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let half = delay / 2;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let jitter = (f64::from(delay - half) * jitter.clamp(0., 1.)).round() as u32;
+        half + jitter
+    }
+}
+
+#[async_trait(?Send)]
+pub trait SendRequest {
+    type Request;
+    type Response;
+
+    async fn send(&self, request: Self::Request) -> Result<Self::Response, Error>;
+    async fn sleep(&self, millis: u32);
+    /// Return a random value in the range from 0 to 1.
+    fn random(&self) -> f64;
+    /// Check whether the request may be repeated without unintended side effects.
+    fn retryable(&self, request: &Self::Request) -> bool;
+}
+
+pub struct Scheduler<S> {
+    sender: S,
+    retry_policy: RetryPolicy,
+    semaphore: Semaphore,
+}
+
+impl<S: SendRequest> Scheduler<S> {
+    /// Create a scheduler. A maximum of zero concurrent requests is replaced by one.
+    pub fn new(sender: S, max_concurrent_requests: usize, retry_policy: RetryPolicy) -> Self {
+        Self {
+            sender,
+            retry_policy,
+            semaphore: Semaphore::new(max_concurrent_requests.max(1)),
+        }
+    }
+
+    /// Send the request as soon as the number of requests in flight is below the limit.
+    ///
+    /// Transient failures of retryable requests are retried according to the retry policy. The
+    /// request is created anew for each attempt. No slot is occupied while waiting for a retry.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt.
+    pub async fn send(&self, request: impl Fn() -> S::Request) -> Result<S::Response, Error> {
+        let mut retry = 0;
+        loop {
+            let request = request();
+            let retryable = self.sender.retryable(&request);
+            let result = {
+                let _permit = self.semaphore.acquire().await;
+                self.sender.send(request).await
+            };
+            match result {
+                Err(error)
+                    if retryable
+                        && error.is_transient()
+                        && retry < self.retry_policy.max_retries =>
+                {
+                    self.sender
+                        .sleep(self.retry_policy.delay(retry, self.sender.random()))
+                        .await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Semaphore for a single-threaded executor.
+struct Semaphore {
+    permits: Cell<usize>,
+    waiters: RefCell<VecDeque<Waker>>,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Self {
+        Self {
+            permits: Cell::new(permits),
+            waiters: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    fn acquire(&self) -> Acquire<'_> {
+        Acquire { semaphore: self }
+    }
+}
+
+struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = Permit<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let semaphore = self.semaphore;
+        let permits = semaphore.permits.get();
+        if permits > 0 {
+            semaphore.permits.set(permits - 1);
+            Poll::Ready(Permit { semaphore })
+        } else {
+            semaphore.waiters.borrow_mut().push_back(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+struct Permit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.permits.set(self.semaphore.permits.get() + 1);
+        // A waiter may have been registered several times or may not be interested anymore, so
+        // all waiters are woken and compete for the free permit.
+        for waker in self.semaphore.waiters.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, future::join_all};
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    const RETRY_POLICY: RetryPolicy = RetryPolicy {
+        max_retries: 3,
+        initial_delay: 100,
+        max_delay: 300,
+    };
+
+    #[derive(Default)]
+    struct MockSender {
+        /// Results of the consecutive requests. Requests are successful if no result is left.
+        results: RefCell<VecDeque<Result<u32, Error>>>,
+        not_retryable: bool,
+        sent: Cell<usize>,
+        in_flight: Cell<usize>,
+        max_in_flight: Cell<usize>,
+        delays: RefCell<Vec<u32>>,
+    }
+
+    impl MockSender {
+        fn with_results(results: impl IntoIterator<Item = Result<u32, Error>>) -> Self {
+            Self {
+                results: RefCell::new(results.into_iter().collect()),
+                ..Self::default()
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl SendRequest for MockSender {
+        type Request = u32;
+        type Response = u32;
+
+        async fn send(&self, request: u32) -> Result<u32, Error> {
+            self.sent.set(self.sent.get() + 1);
+            self.in_flight.set(self.in_flight.get() + 1);
+            self.max_in_flight
+                .set(self.max_in_flight.get().max(self.in_flight.get()));
+            YieldNow(false).await;
+            self.in_flight.set(self.in_flight.get() - 1);
+            self.results.borrow_mut().pop_front().unwrap_or(Ok(request))
+        }
+
+        async fn sleep(&self, millis: u32) {
+            self.delays.borrow_mut().push(millis);
+        }
+
+        fn random(&self) -> f64 {
+            0.5
+        }
+
+        fn retryable(&self, _: &u32) -> bool {
+            !self.not_retryable
+        }
+    }
+
+    /// Future which is pending when it is polled for the first time.
+    struct YieldNow(bool);
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    fn status(code: u16) -> Error {
+        Error::Status {
+            code,
+            text: String::new(),
+        }
+    }
+
+    #[rstest]
+    #[case::no_connection(Error::NoConnection, true)]
+    #[case::bad_gateway(status(502), true)]
+    #[case::service_unavailable(status(503), true)]
+    #[case::gateway_timeout(status(504), true)]
+    #[case::internal_server_error(status(500), false)]
+    #[case::not_found(status(404), false)]
+    #[case::deserialization(Error::Deserialization(String::new()), false)]
+    fn test_error_is_transient(#[case] error: Error, #[case] expected: bool) {
+        assert_eq!(error.is_transient(), expected);
+    }
+
+    #[rstest]
+    #[case::first_retry(0, 0.5, 75)]
+    #[case::second_retry(1, 0.5, 150)]
+    #[case::capped(2, 0.5, 225)]
+    #[case::capped_without_overflow(40, 0.5, 225)]
+    #[case::no_jitter(1, 0.0, 100)]
+    #[case::full_jitter(1, 1.0, 200)]
+    #[case::jitter_out_of_range(1, 2.0, 200)]
+    fn test_retry_policy_delay(#[case] retry: u32, #[case] jitter: f64, #[case] expected: u32) {
+        assert_eq!(RETRY_POLICY.delay(retry, jitter), expected);
+    }
+
+    #[rstest]
+    #[case::default(DEFAULT_MAX_CONCURRENT_REQUESTS, 4)]
+    #[case::single(1, 1)]
+    #[case::zero(0, 1)]
+    fn test_scheduler_concurrency(
+        #[case] max_concurrent_requests: usize,
+        #[case] expected_max_in_flight: usize,
+    ) {
+        let scheduler =
+            Scheduler::new(MockSender::default(), max_concurrent_requests, RETRY_POLICY);
+
+        let results = block_on(join_all((0..10).map(|i| scheduler.send(move || i))));
+
+        assert_eq!(results, (0..10).map(Ok).collect::<Vec<_>>());
+        assert_eq!(scheduler.sender.sent.get(), 10);
+        assert_eq!(scheduler.sender.max_in_flight.get(), expected_max_in_flight);
+        assert_eq!(scheduler.semaphore.permits.get(), expected_max_in_flight);
+    }
+
+    #[test]
+    fn test_scheduler_retry_transient_failures() {
+        let scheduler = Scheduler::new(
+            MockSender::with_results([Err(Error::NoConnection), Err(status(503))]),
+            DEFAULT_MAX_CONCURRENT_REQUESTS,
+            RETRY_POLICY,
+        );
+
+        assert_eq!(block_on(scheduler.send(|| 1)), Ok(1));
+        assert_eq!(scheduler.sender.sent.get(), 3);
+        assert_eq!(*scheduler.sender.delays.borrow(), vec![75, 150]);
+    }
+
+    #[test]
+    fn test_scheduler_retries_exhausted() {
+        let scheduler = Scheduler::new(
+            MockSender::with_results([
+                Err(status(502)),
+                Err(status(504)),
+                Err(Error::NoConnection),
+                Err(Error::NoConnection),
+            ]),
+            DEFAULT_MAX_CONCURRENT_REQUESTS,
+            RETRY_POLICY,
+        );
+
+        assert_eq!(block_on(scheduler.send(|| 1)), Err(Error::NoConnection));
+        assert_eq!(scheduler.sender.sent.get(), 4);
+        assert_eq!(*scheduler.sender.delays.borrow(), vec![75, 150, 225]);
+    }
+
+    #[test]
+    fn test_scheduler_no_retry_of_permanent_failure() {
+        let scheduler = Scheduler::new(
+            MockSender::with_results([Err(status(404))]),
+            DEFAULT_MAX_CONCURRENT_REQUESTS,
+            RETRY_POLICY,
+        );
+
+        assert_eq!(block_on(scheduler.send(|| 1)), Err(status(404)));
+        assert_eq!(scheduler.sender.sent.get(), 1);
+        assert!(scheduler.sender.delays.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_scheduler_no_retry_of_non_retryable_request() {
+        let scheduler = Scheduler::new(
+            MockSender {
+                not_retryable: true,
+                ..MockSender::with_results([Err(Error::NoConnection)])
+            },
+            DEFAULT_MAX_CONCURRENT_REQUESTS,
+            RETRY_POLICY,
+        );
+
+        assert_eq!(block_on(scheduler.send(|| 1)), Err(Error::NoConnection));
+        assert_eq!(scheduler.sender.sent.get(), 1);
+    }
+
+    #[test]
+    fn test_scheduler_concurrency_with_retries() {
+        let scheduler = Scheduler::new(
+            MockSender::with_results([Err(Error::NoConnection), Err(status(503))]),
+            2,
+            RETRY_POLICY,
+        );
+
+        let results = block_on(join_all((0..5).map(|i| scheduler.send(move || i))));
+
+        assert_eq!(results, (0..5).map(Ok).collect::<Vec<_>>());
+        assert_eq!(scheduler.sender.sent.get(), 7);
+        assert_eq!(scheduler.sender.max_in_flight.get(), 2);
+    }
+}
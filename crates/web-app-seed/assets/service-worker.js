@@ -8,14 +8,17 @@ self.addEventListener("install", function (event) {
 
 self.addEventListener("activate", (event) => {
     event.waitUntil(
-        deleteDeprecatedCaches()
+        deleteDeprecatedCaches().then(() => self.clients.claim())
     );
 });
 
 self.addEventListener("fetch", (event) => {
     event.respondWith(
         (async () => {
-            const cachedResponse = await caches.match(event.request);
+            // The app may be opened with a query string by a notification action
+            const cachedResponse = await caches.match(event.request, {
+                ignoreSearch: event.request.mode === "navigate",
+            });
             if (cachedResponse) {
                 return cachedResponse;
             }
@@ -30,12 +33,27 @@ self.addEventListener("message", (event) => {
         let task = event.data.task;
         let content = event.data.content;
         if (task === "UpdateCache") {
-            deleteCache();
-            deleteDeprecatedCaches();
-            addResourcesToCache();
+            event.waitUntil(updateCache(content));
+        }
+        if (task === "SkipWaiting") {
+            // The message is sent to the active service worker, so it must be forwarded to a
+            // service worker that is waiting for activation.
+            if (self.registration.waiting) {
+                self.registration.waiting.postMessage({ task: "Activate" });
+            }
+        }
+        if (task === "Activate") {
+            self.skipWaiting();
         }
         if (task === "ShowNotification") {
-            self.registration.showNotification(content.title, content.options);
+            let options = content.options instanceof Map ? Object.fromEntries(content.options) : content.options;
+            options.actions = content.actions;
+            options.data = {
+                url: options.data,
+                training_session_id: content.training_session_id,
+                element_count: content.element_count,
+            };
+            self.registration.showNotification(content.title, options);
         }
         if (task === "CloseNotifications") {
             self.registration.getNotifications().then((notifications) => {
@@ -52,10 +70,49 @@ self.addEventListener("notificationclick", function(event) {
     if (event.action === "timer-pause" || event.action === "timer-reset") {
         timer_channel.postMessage(event.action);
     }
+    if (event.action === "GoToNextSection" || event.action === "ExtendTimer") {
+        event.waitUntil(applyNotificationAction(event.action, event.notification.data));
+    }
 }, false);
 
-function addResourcesToCache() {
-    caches.open(CACHE_NAME).then((cache) => {
+async function applyNotificationAction(action, data) {
+    const clients = await self.clients.matchAll({ type: "window" });
+    if (clients.length > 0) {
+        clients.forEach(client => client.postMessage({
+            task: "NotificationAction",
+            content: {
+                action: action,
+                training_session_id: data.training_session_id,
+                element_count: data.element_count,
+            },
+        }));
+        return;
+    }
+    // Without an open window, the action is passed to the app by query parameters and applied
+    // after the ongoing training session has been restored.
+    const target = new URL(data.url || "app", self.registration.scope);
+    target.searchParams.set("notification_action", action);
+    target.searchParams.set("notification_training_session", data.training_session_id);
+    target.searchParams.set("notification_element_count", data.element_count);
+    await self.clients.openWindow(target.href);
+}
+
+async function updateCache(version) {
+    // The new assets are stored in a separate cache, which replaces the current cache only after
+    // all assets were fetched successfully. This prevents a mix of assets of different versions.
+    const cacheName = `${CACHE_NAME}-${version}`;
+    await addResourcesToCache(cacheName);
+    const keyList = await caches.keys();
+    await Promise.all(keyList.filter((key) => key !== cacheName).map((key) => caches.delete(key)));
+    const clients = await self.clients.matchAll({ type: "window" });
+    clients.forEach(client => client.postMessage({
+        task: "CacheUpdated",
+        content: version,
+    }));
+}
+
+function addResourcesToCache(cacheName = CACHE_NAME) {
+    return caches.open(cacheName).then((cache) => {
         return cache.addAll([
             "app",
             "valens-frontend_bg.wasm",
@@ -74,16 +131,12 @@ function addResourcesToCache() {
             "index.css",
             "manifest.json",
             "service-worker.js",
-        ]);
+        ].map((url) => new Request(url, { cache: "reload" })));
     })
 };
 
-function deleteCache() {
-    caches.delete(CACHE_NAME);
-};
-
 function deleteDeprecatedCaches() {
-    caches.keys().then((keyList) => {
+    return caches.keys().then((keyList) => {
         return Promise.all(keyList.map((key) => {
             if (key === CACHE_NAME) {
                 return;
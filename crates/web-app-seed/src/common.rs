@@ -1,11 +1,14 @@
 use std::collections::BTreeMap;
 
 use chrono::{prelude::*, Duration};
+use gloo_console::error;
 use plotters::style::{Color, Palette, Palette99, RGBAColor};
 use seed::{prelude::*, *};
 use valens_domain as domain;
+use valens_web_app as web_app;
 
 pub const ENTER_KEY: u32 = 13;
+pub const TAB_KEY: u32 = 9;
 
 #[derive(Clone)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
@@ -13,6 +16,8 @@ pub struct InputField<T> {
     pub input: String,
     pub parsed: Option<T>,
     pub orig: String,
+    /// Reason why the input is invalid.
+    pub error: Option<String>,
 }
 
 impl<T: Default> Default for InputField<T> {
@@ -21,6 +26,7 @@ impl<T: Default> Default for InputField<T> {
             input: String::new(),
             parsed: Some(T::default()),
             orig: String::new(),
+            error: None,
         }
     }
 }
@@ -35,6 +41,41 @@ impl<T> InputField<T> {
     }
 }
 
+/// Input field for a value of a set, which is valid if it is empty or within the allowed range.
+pub fn set_value_field<T: Default + std::str::FromStr>(
+    input: String,
+    orig: String,
+    validate: impl FnOnce(T) -> Result<T, domain::SetValueError>,
+    error: domain::SetValueError,
+) -> InputField<T> {
+    let result = if input.is_empty() {
+        Ok(T::default())
+    } else {
+        input.parse::<T>().map_err(|_| error).and_then(validate)
+    };
+    InputField {
+        input,
+        parsed: result.ok(),
+        orig,
+        error: result.err().map(|err| err.to_string()),
+    }
+}
+
+pub fn view_input_error<T, Ms>(field: &InputField<T>) -> Node<Ms> {
+    field
+        .error
+        .as_ref()
+        .map_or(Node::Empty, |error| p![C!["help"], C!["is-danger"], error])
+}
+
+/// Check whether non-essential motion like animations and smooth scrolling should be avoided.
+pub fn reduced_motion() -> bool {
+    web_app::media::prefers_reduced_motion().unwrap_or_else(|err| {
+        error!("failed to determine preferred motion:", err);
+        false
+    })
+}
+
 pub fn view_title<Ms>(title: &Node<Ms>, margin: u8) -> Node<Ms> {
     div![
         C!["container"],
@@ -163,6 +204,104 @@ pub fn view_delete_confirmation_dialog<Ms>(
     )
 }
 
+pub fn view_restore_confirmation_dialog<Ms>(
+    element: &str,
+    date: NaiveDate,
+    restore_event: &EventHandler<Ms>,
+    cancel_event: &EventHandler<Ms>,
+    loading: bool,
+) -> Node<Ms> {
+    view_dialog(
+        "warning",
+        &format!("Replace the {element}?"),
+        nodes![
+            div![
+                C!["block"],
+                format!(
+                    "A {element} for {date} has been added since the deletion. It will be replaced \
+                     by the deleted {element}."
+                ),
+            ],
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        cancel_event,
+                        "No"
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-warning"],
+                        C![IF![loading => "is-loading"]],
+                        restore_event,
+                        format!("Yes, replace {element}"),
+                    ]
+                ],
+            ],
+        ],
+        cancel_event,
+    )
+}
+
+/// Show the recently deleted entries, each with a short description and a restore button.
+pub fn view_trash<Ms: 'static>(
+    items: Vec<(&web_app::trash::Item, String)>,
+    restore_message: fn(web_app::trash::Entry) -> Ms,
+) -> Node<Ms> {
+    if items.is_empty() {
+        return empty![];
+    }
+    div![
+        C!["mt-6"],
+        C!["mx-2"],
+        p![
+            C!["has-text-centered"],
+            C!["has-text-weight-bold"],
+            C!["mb-2"],
+            "Recently deleted"
+        ],
+        div![
+            C!["table-container"],
+            table![
+                C!["table"],
+                C!["is-fullwidth"],
+                C!["is-hoverable"],
+                C!["has-text-centered"],
+                thead![tr![th!["Date"], th!["Entry"], th!["Kept until"], th![]]],
+                tbody![items.into_iter().map(|(item, description)| {
+                    let entry = item.entry.clone();
+                    tr![
+                        td![span![
+                            style! {St::WhiteSpace => "nowrap" },
+                            item.entry.date().to_string(),
+                        ]],
+                        td![description],
+                        td![span![
+                            style! {St::WhiteSpace => "nowrap" },
+                            item.expires().to_string(),
+                        ]],
+                        td![a![
+                            C!["icon"],
+                            attrs! {At::Title => "Restore"},
+                            ev(Ev::Click, move |_| restore_message(entry)),
+                            i![C!["fas fa-trash-arrow-up"]]
+                        ]]
+                    ]
+                })],
+            ]
+        ]
+    ]
+}
+
 pub fn view_search_box<Ms>(
     search_term: &str,
     search_term_changed: impl FnOnce(String) -> Ms + 'static + Clone,
@@ -206,12 +345,12 @@ where
 pub fn view_interval_buttons<Ms>(
     current: &domain::Interval,
     all: &domain::Interval,
-    message: fn(NaiveDate, NaiveDate) -> Ms,
+    message: fn(NaiveDate, NaiveDate, Option<domain::DefaultInterval>) -> Ms,
 ) -> Node<Ms>
 where
     Ms: 'static,
 {
-    let today = Local::now().date_naive();
+    let today = web_app::clock::today();
     let duration = current.last - current.first + Duration::days(1);
     let intervals = [
         (
@@ -219,6 +358,7 @@ where
             all.first,
             all.last,
             all.first == current.first && all.last == current.last,
+            Some(domain::DefaultInterval::All),
         ),
         (
             "1Y",
@@ -226,6 +366,7 @@ where
             today,
             current.last == today
                 && duration == Duration::days(domain::DefaultInterval::_1Y as i64 + 1),
+            Some(domain::DefaultInterval::_1Y),
         ),
         (
             "6M",
@@ -233,6 +374,7 @@ where
             today,
             current.last == today
                 && duration == Duration::days(domain::DefaultInterval::_6M as i64 + 1),
+            Some(domain::DefaultInterval::_6M),
         ),
         (
             "3M",
@@ -240,6 +382,7 @@ where
             today,
             current.last == today
                 && duration == Duration::days(domain::DefaultInterval::_3M as i64 + 1),
+            Some(domain::DefaultInterval::_3M),
         ),
         (
             "1M",
@@ -247,6 +390,7 @@ where
             today,
             current.last == today
                 && duration == Duration::days(domain::DefaultInterval::_1M as i64 + 1),
+            Some(domain::DefaultInterval::_1M),
         ),
         (
             "+",
@@ -261,6 +405,7 @@ where
                 current.first + Duration::days(6)
             },
             false,
+            None,
         ),
         (
             "−",
@@ -275,6 +420,7 @@ where
                 today
             },
             false,
+            None,
         ),
         (
             "<",
@@ -289,6 +435,7 @@ where
                 all.first + duration - Duration::days(1)
             },
             false,
+            None,
         ),
         (
             ">",
@@ -303,6 +450,7 @@ where
                 today
             },
             false,
+            None,
         ),
     ];
 
@@ -313,18 +461,19 @@ where
             C!["has-addons-centered"],
             intervals
                 .iter()
-                .map(|(name, first, last, is_active)| {
+                .map(|(name, first, last, is_active, selected_interval)| {
                     #[allow(clippy::clone_on_copy)]
                     let f = first.clone();
                     #[allow(clippy::clone_on_copy)]
                     let l = last.clone();
+                    let i = *selected_interval;
                     p![
                         C!["control"],
                         a![
                             C!["button"],
                             C!["is-small"],
                             C![IF![*is_active => "is-link"]],
-                            ev(Ev::Click, move |_| message(f, l)),
+                            ev(Ev::Click, move |_| message(f, l, i)),
                             name,
                         ]
                     ]
@@ -357,6 +506,57 @@ pub fn view_page_loading<Ms>() -> Node<Ms> {
     ]
 }
 
+/// Loading indicator or error message with retry button as replacement for the page content, if
+/// the data has not been loaded yet.
+pub fn view_resource_unavailable<T, Ms>(
+    resource: &web_app::resource::Resource<T>,
+    retry: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone,
+) -> Option<Node<Ms>>
+where
+    Ms: 'static,
+{
+    if resource.is_available() {
+        return None;
+    }
+    Some(match resource.error() {
+        Some(error) => view_read_error(error, retry),
+        None => view_page_loading(),
+    })
+}
+
+pub fn view_read_error<Ms>(
+    error: &web_app::resource::ReadError,
+    retry: impl FnOnce(web_sys::Event) -> Ms + 'static + Clone,
+) -> Node<Ms>
+where
+    Ms: 'static,
+{
+    div![
+        C!["message"],
+        C!["has-background-white"],
+        C!["is-danger"],
+        C!["mx-2"],
+        div![
+            C!["message-body"],
+            C!["has-text-dark"],
+            div![
+                C!["title"],
+                C!["has-text-danger"],
+                C!["is-size-4"],
+                "Failed to load data"
+            ],
+            p![C!["mb-3"], error.to_string()],
+            button![
+                C!["button"],
+                C!["is-danger"],
+                ev(Ev::Click, retry),
+                span![C!["icon"], i![C!["fas fa-rotate"]]],
+                span!["Retry"]
+            ]
+        ]
+    ]
+}
+
 pub fn view_error_not_found<Ms>(element: &str) -> Node<Ms> {
     div![
         C!["message"],
@@ -391,9 +591,17 @@ pub fn view_versions<Ms>(backend_version: &str) -> Vec<Node<Ms>> {
     ]
 }
 
-pub fn value_or_dash(option: Option<impl std::fmt::Display>) -> String {
+pub fn value_or_dash(option: Option<f32>, separator: web_app::format::DecimalSeparator) -> String {
+    if let Some(value) = option {
+        web_app::format::format_number(value, 1, separator)
+    } else {
+        "-".into()
+    }
+}
+
+pub fn integer_or_dash(option: Option<impl std::fmt::Display>) -> String {
     if let Some(value) = option {
-        format!("{value:.1}")
+        value.to_string()
     } else {
         "-".into()
     }
@@ -548,12 +756,135 @@ pub fn view_calendar<Ms>(
     ]
 }
 
-pub fn view_chart<Ms>(
+/// View the weeks of a month from Monday to Sunday.
+///
+/// The navigation to the previous or next month is disabled if no event handler is given.
+pub fn view_month_calendar<Ms: 'static>(
+    month: NaiveDate,
+    show_previous_month: Option<EventHandler<Ms>>,
+    show_next_month: Option<EventHandler<Ms>>,
+    view_day: impl Fn(&NaiveDate) -> Node<Ms>,
+) -> Node<Ms> {
+    div![
+        C!["is-month-calendar"],
+        C!["mx-2"],
+        C!["mb-4"],
+        div![
+            C!["is-flex"],
+            C!["is-justify-content-space-between"],
+            C!["is-align-items-center"],
+            a![
+                C!["icon"],
+                C![IF![show_previous_month.is_none() => "is-semitransparent"]],
+                show_previous_month,
+                i![C!["fas fa-chevron-left"]]
+            ],
+            span![
+                C!["has-text-weight-bold"],
+                month.format("%Y-%m").to_string()
+            ],
+            a![
+                C!["icon"],
+                C![IF![show_next_month.is_none() => "is-semitransparent"]],
+                show_next_month,
+                i![C!["fas fa-chevron-right"]]
+            ],
+        ],
+        table![
+            C!["table"],
+            C!["is-fullwidth"],
+            C!["is-size-7"],
+            C!["has-text-centered"],
+            thead![tr![["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+                .into_iter()
+                .map(|weekday| th![weekday])]],
+            tbody![domain::period::month_weeks(month)
+                .iter()
+                .map(|week| { tr![week.iter().map(&view_day)] })],
+        ],
+    ]
+}
+
+/// Toggle for showing the data of a chart additionally as table.
+pub struct DataTable<Ms> {
+    pub shown: bool,
+    pub toggle: fn() -> Ms,
+}
+
+// Implemented manually, as deriving would require `Ms: Copy`
+impl<Ms> Clone for DataTable<Ms> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Ms> Copy for DataTable<Ms> {}
+
+/// Zooming and panning of a chart inside the selected interval.
+pub struct ChartZoom<'a, Ms> {
+    pub viewport: &'a web_app::chart::Viewport,
+    pub input: fn(web_app::chart::ChartInput) -> Ms,
+}
+
+// Implemented manually, as deriving would require `Ms: Copy`
+impl<Ms> Clone for ChartZoom<'_, Ms> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Ms> Copy for ChartZoom<'_, Ms> {}
+
+pub fn view_chart<Ms: 'static>(
     labels: &[(&str, usize, f64)],
-    chart: Result<Option<String>, Box<dyn std::error::Error>>,
+    data: &[web_app::chart::PlotData],
+    interval: &domain::Interval,
+    style: &web_app::chart::Style,
+    data_table: DataTable<Ms>,
     no_data_label: bool,
 ) -> Node<Ms> {
-    match chart {
+    view_chart_with_zoom(
+        labels,
+        data,
+        interval,
+        style,
+        data_table,
+        no_data_label,
+        None,
+    )
+}
+
+/// View a chart which can be zoomed by the mouse wheel or a pinch gesture and panned by dragging.
+/// A double tap resets the zoom.
+pub fn view_zoomable_chart<Ms: 'static>(
+    labels: &[(&str, usize, f64)],
+    data: &[web_app::chart::PlotData],
+    style: &web_app::chart::Style,
+    data_table: DataTable<Ms>,
+    no_data_label: bool,
+    zoom: ChartZoom<Ms>,
+) -> Node<Ms> {
+    view_chart_with_zoom(
+        labels,
+        data,
+        &zoom.viewport.visible(),
+        style,
+        data_table,
+        no_data_label,
+        Some(zoom),
+    )
+}
+
+fn view_chart_with_zoom<Ms: 'static>(
+    labels: &[(&str, usize, f64)],
+    data: &[web_app::chart::PlotData],
+    interval: &domain::Interval,
+    style: &web_app::chart::Style,
+    data_table: DataTable<Ms>,
+    no_data_label: bool,
+    zoom: Option<ChartZoom<Ms>>,
+) -> Node<Ms> {
+    match web_app::chart::plot(data, interval, style) {
         Ok(result) => match result {
             None => if no_data_label {
                 div![
@@ -579,7 +910,7 @@ pub fn view_chart<Ms>(
                                     C!["icon"],
                                     style![
                                         St::Color => {
-                                            let RGBAColor(r, g, b, a) = Palette99::pick(*color_idx).mix(*opacity);
+                                            let RGBAColor(r, g, b, a) = web_app::chart::color(*color_idx, style).mix(*opacity);
                                             #[allow(clippy::cast_possible_truncation)]
                                             #[allow(clippy::cast_sign_loss)]
                                             let a = (a*255.0) as u8;
@@ -593,13 +924,225 @@ pub fn view_chart<Ms>(
                         })
                         .collect::<Vec<_>>(),
                 ],
-                raw![&value],
+                view_chart_svg(&value, data, zoom),
+                button![
+                    C!["button"],
+                    C!["is-small"],
+                    C!["is-ghost"],
+                    attrs! {
+                        At::from("aria-expanded") => data_table.shown.to_string(),
+                    },
+                    ev(Ev::Click, move |_| (data_table.toggle)()),
+                    span![C!["icon"], i![C!["fas fa-table"]]],
+                    span![if data_table.shown { "Hide data" } else { "Show data" }],
+                ],
+                IF![data_table.shown => {
+                    let labels = labels.iter().map(|(label, _, _)| *label).collect::<Vec<_>>();
+                    web_app::chart::table(&labels, data, interval).map_or_else(
+                        || div![C!["is-size-7"], C!["mb-4"], "No data in the selected interval."],
+                        |table| div![C!["table-container"], raw![&table]],
+                    )
+                }],
             ],
         },
         Err(err) => div![raw![&format!("failed to plot chart: {err}")]],
     }
 }
 
+fn view_chart_svg<Ms: 'static>(
+    svg: &str,
+    data: &[web_app::chart::PlotData],
+    zoom: Option<ChartZoom<Ms>>,
+) -> Vec<Node<Ms>> {
+    let Some(zoom) = zoom else {
+        return raw![svg];
+    };
+    let secondary = data.iter().any(|d| d.params.secondary);
+    let input = zoom.input;
+    vec![div![
+        C!["is-inline-block"],
+        style! {
+            St::TouchAction => "pan-y",
+            St::Cursor => if zoom.viewport.is_zoomed() { "grab" } else { "zoom-in" },
+        },
+        ev(Ev::Wheel, move |event| {
+            let event = event.unchecked_into::<web_sys::WheelEvent>();
+            event.prevent_default();
+            let delta = if event.delta_mode() == web_sys::WheelEvent::DOM_DELTA_LINE {
+                event.delta_y() * 16.0
+            } else {
+                event.delta_y()
+            };
+            input(web_app::chart::ChartInput::Wheel {
+                x: chart_position(&event, secondary),
+                delta,
+            })
+        }),
+        ev(Ev::PointerDown, move |event| {
+            let event = event.unchecked_into::<web_sys::PointerEvent>();
+            if let Some(element) = event
+                .current_target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            {
+                // Keep receiving the events of the pointer when it leaves the chart
+                let _ = element.set_pointer_capture(event.pointer_id());
+            }
+            input(web_app::chart::ChartInput::PointerDown {
+                id: event.pointer_id(),
+                x: chart_position(&event, secondary),
+                time: event.time_stamp(),
+            })
+        }),
+        ev(Ev::PointerMove, move |event| {
+            let event = event.unchecked_into::<web_sys::PointerEvent>();
+            input(web_app::chart::ChartInput::PointerMove {
+                id: event.pointer_id(),
+                x: chart_position(&event, secondary),
+            })
+        }),
+        ev(Ev::PointerUp, move |event| {
+            let event = event.unchecked_into::<web_sys::PointerEvent>();
+            input(web_app::chart::ChartInput::PointerUp {
+                id: event.pointer_id(),
+                time: event.time_stamp(),
+            })
+        }),
+        ev(Ev::PointerCancel, move |event| {
+            let event = event.unchecked_into::<web_sys::PointerEvent>();
+            input(web_app::chart::ChartInput::PointerUp {
+                id: event.pointer_id(),
+                time: event.time_stamp(),
+            })
+        }),
+        raw![svg],
+    ]]
+}
+
+/// Position of the event relative to the plot area of the chart.
+fn chart_position(event: &web_sys::MouseEvent, secondary: bool) -> f64 {
+    let Some(element) = event
+        .current_target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+    else {
+        return 0.5;
+    };
+    let rect = element.get_bounding_client_rect();
+    web_app::chart::PlotArea::new(rect.width(), secondary)
+        .fraction(f64::from(event.client_x()) - rect.left())
+}
+
+pub fn view_muscle_stimulus_editor<Ms: 'static>(
+    muscle_stimulus: &BTreeMap<u8, u8>,
+    set_muscle_stimulus: fn(u8, u8) -> Ms,
+) -> Node<Ms> {
+    div![
+        C!["mx-2"],
+        C!["mb-5"],
+        domain::Muscle::iter().map(|m| {
+            let m = *m;
+            let stimulus = muscle_stimulus.get(&m.id()).copied().unwrap_or_default();
+            div![
+                C!["columns"],
+                C!["is-mobile"],
+                div![
+                    C!["column"],
+                    p![m.name()],
+                    p![C!["is-size-7"], m.description()]
+                ],
+                div![
+                    C!["column"],
+                    C!["is-flex"],
+                    div![
+                        C!["field"],
+                        C!["has-addons"],
+                        C!["has-addons-centered"],
+                        C!["my-auto"],
+                        p![
+                            C!["control"],
+                            a![
+                                C!["button"],
+                                C!["is-small"],
+                                C![IF![stimulus == 100 => "is-link"]],
+                                &ev(Ev::Click, move |_| set_muscle_stimulus(m.id(), 100)),
+                                "primary",
+                            ]
+                        ],
+                        p![
+                            C!["control"],
+                            a![
+                                C!["button"],
+                                C!["is-small"],
+                                C![IF![stimulus > 0 && stimulus < 100 => "is-link"]],
+                                &ev(Ev::Click, move |_| set_muscle_stimulus(m.id(), 50)),
+                                "secondary",
+                            ]
+                        ],
+                        p![
+                            C!["control"],
+                            a![
+                                C!["button"],
+                                C!["is-small"],
+                                C![IF![stimulus == 0 => "is-link"]],
+                                &ev(Ev::Click, move |_| set_muscle_stimulus(m.id(), 0)),
+                                "none",
+                            ]
+                        ],
+                    ],
+                ]
+            ]
+        })
+    ]
+}
+
+/// Picker for selecting an RPE value by a single tap.
+///
+/// The picker is meant to be used in addition to a text input, which allows keyboard entry.
+/// Selecting "clear" removes the value.
+pub fn view_rpe_picker<Ms: 'static>(
+    rpe: &InputField<f32>,
+    rpe_changed: impl FnOnce(String) -> Ms + Clone + 'static,
+) -> Node<Ms> {
+    let selected = rpe.parsed.filter(|rpe| *rpe > 0.0);
+    div![
+        C!["field"],
+        C!["has-addons"],
+        C!["is-flex-wrap-wrap"],
+        web_app::RPE_PICKER_VALUES.iter().map(|&value| {
+            let rpe_changed = rpe_changed.clone();
+            let is_selected = selected.is_some_and(|rpe| (rpe - value).abs() < f32::EPSILON);
+            p![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-small"],
+                    C![if is_selected { "is-link" } else { "is-light" }],
+                    attrs! {
+                        At::Type => "button",
+                        At::from("tabindex") => -1,
+                    },
+                    ev(Ev::Click, move |_| rpe_changed(value.to_string())),
+                    value.to_string(),
+                ]
+            ]
+        }),
+        p![
+            C!["control"],
+            button![
+                C!["button"],
+                C!["is-small"],
+                C!["is-light"],
+                attrs! {
+                    At::Type => "button",
+                    At::from("tabindex") => -1,
+                    At::Disabled => selected.is_none().as_at_value(),
+                },
+                ev(Ev::Click, move |_| rpe_changed(String::new())),
+                "clear",
+            ]
+        ],
+    ]
+}
+
 pub fn view_sets_per_muscle<Ms>(stimulus_per_muscle: &[(domain::Muscle, u32)]) -> Vec<Node<Ms>>
 where
     Ms: 'static,
@@ -675,57 +1218,26 @@ pub fn view_element_with_description<Ms>(element: Node<Ms>, description: &str) -
     ]
 }
 
-pub fn format_set(
-    reps: Option<u32>,
-    time: Option<u32>,
-    show_tut: bool,
-    weight: Option<f32>,
-    rpe: Option<f32>,
-    show_rpe: bool,
-) -> String {
-    let mut parts = vec![];
-
-    if let Some(reps) = reps {
-        if reps > 0 {
-            parts.push(reps.to_string());
-        }
+pub fn tag_color_class(color: domain::TagColor) -> &'static str {
+    match color {
+        domain::TagColor::Gray => "is-dark",
+        domain::TagColor::Turquoise => "is-primary",
+        domain::TagColor::Blue => "is-link",
+        domain::TagColor::Cyan => "is-info",
+        domain::TagColor::Green => "is-success",
+        domain::TagColor::Yellow => "is-warning",
+        domain::TagColor::Red => "is-danger",
     }
-
-    if let Some(time) = time {
-        if show_tut && time > 0 {
-            parts.push(format!("{time} s"));
-        }
-    }
-
-    if let Some(weight) = weight {
-        if weight > 0.0 {
-            parts.push(format!("{weight} kg"));
-        }
-    }
-
-    let mut result = parts.join(" × ");
-
-    if let Some(rpe) = rpe {
-        if show_rpe && rpe > 0.0 {
-            result.push_str(&format!(" @ {rpe}"));
-        }
-    }
-
-    result
 }
 
 pub fn valid_reps(reps: u32) -> bool {
-    reps > 0 && reps < 1000
+    domain::validate_reps(reps).is_ok()
 }
 
 pub fn valid_time(duration: u32) -> bool {
-    duration > 0 && duration < 1000
+    domain::validate_time(duration).is_ok()
 }
 
 pub fn valid_weight(weight: f32) -> bool {
-    weight > 0.0 && weight < 1000.0 && (weight * 10.0 % 1.0).abs() < f32::EPSILON
-}
-
-pub fn valid_rpe(rpe: f32) -> bool {
-    (0.0..=10.0).contains(&rpe) && (rpe % 0.5).abs() < f32::EPSILON
+    domain::validate_weight(weight).is_ok()
 }
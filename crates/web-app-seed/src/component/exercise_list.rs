@@ -1,8 +1,9 @@
 use std::collections::BTreeSet;
 
-use chrono::{Duration, Local};
+use chrono::Duration;
 use seed::{prelude::*, *};
 use valens_domain as domain;
+use valens_web_app as web_app;
 
 use crate::{common, data};
 
@@ -15,6 +16,8 @@ const CURRENT_EXERCISE_CUTOFF_DAYS: i64 = 31;
 #[allow(clippy::struct_excessive_bools)]
 pub struct Model {
     pub search_term: String,
+    /// IDs of the selected exercises. Exercises can only be selected if a selection is set.
+    pub selection: Option<BTreeSet<u32>>,
     filter: domain::ExerciseFilter,
     view_filter_dialog: bool,
     view_create: bool,
@@ -33,6 +36,7 @@ impl Model {
     ) -> Self {
         Self {
             search_term: String::new(),
+            selection: None,
             filter: domain::ExerciseFilter::default(),
             view_filter_dialog: false,
             view_create,
@@ -52,6 +56,7 @@ impl Model {
     ) -> Self {
         Self {
             search_term: String::new(),
+            selection: None,
             filter,
             view_filter_dialog: false,
             view_create,
@@ -69,6 +74,7 @@ impl Model {
 pub enum Msg {
     SearchTermChanged(String),
     FilterChanged(domain::Muscle),
+    SelectionToggled(u32),
 
     Selected(u32),
     CreateClicked(String),
@@ -101,6 +107,14 @@ pub fn update(msg: Msg, model: &mut Model, _orders: &mut impl Orders<Msg>) -> Ou
             }
             OutMsg::None
         }
+        Msg::SelectionToggled(exercise_id) => {
+            if let Some(selection) = &mut model.selection {
+                if not(selection.remove(&exercise_id)) {
+                    selection.insert(exercise_id);
+                }
+            }
+            OutMsg::None
+        }
 
         Msg::Selected(exercise_id) => OutMsg::Selected(exercise_id),
         Msg::CreateClicked(exercise_id) => OutMsg::CreateClicked(exercise_id),
@@ -123,7 +137,7 @@ pub fn update(msg: Msg, model: &mut Model, _orders: &mut impl Orders<Msg>) -> Ou
 // ------ ------
 
 pub fn view(model: &Model, loading: bool, data_model: &data::Model) -> Vec<Node<Msg>> {
-    let cutoff = Local::now().date_naive() - Duration::days(CURRENT_EXERCISE_CUTOFF_DAYS);
+    let cutoff = web_app::clock::today() - Duration::days(CURRENT_EXERCISE_CUTOFF_DAYS);
     let muscle_filter = domain::Muscle::iter()
         .map(|m| (m, model.filter.muscles.contains(m)))
         .collect::<Vec<_>>();
@@ -267,11 +281,31 @@ fn view_exercises(model: &Model, exercises: &[&&domain::Exercise]) -> Vec<Node<M
                     C!["is-justify-content-space-between"],
                     C!["has-text-link"],
                     span![
-                        ev(Ev::Click, {
-                            let exercise_id = e.id;
-                            move |_| Msg::Selected(exercise_id)
-                        }),
-                        e.name.to_string(),
+                        if let Some(selection) = &model.selection {
+                            label![
+                                C!["checkbox"],
+                                C!["mr-2"],
+                                input![
+                                    attrs! {
+                                        At::Type => "checkbox",
+                                        At::Checked => selection.contains(&e.id).as_at_value(),
+                                    },
+                                    ev(Ev::Click, {
+                                        let exercise_id = e.id;
+                                        move |_| Msg::SelectionToggled(exercise_id)
+                                    }),
+                                ]
+                            ]
+                        } else {
+                            empty![]
+                        },
+                        span![
+                            ev(Ev::Click, {
+                                let exercise_id = e.id;
+                                move |_| Msg::Selected(exercise_id)
+                            }),
+                            e.name.to_string(),
+                        ],
                     ],
                     p![
                         C!["is-flex is-flex-wrap-nowrap"],
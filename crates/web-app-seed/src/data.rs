@@ -1,11 +1,14 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::Arc,
+};
 
 use chrono::{prelude::*, Duration};
 use gloo_console::{debug, error};
 use seed::{
     app::{subs, Orders},
     button, div, nodes, p,
-    prelude::{ev, El, Ev, Node},
+    prelude::{ev, streams, El, Ev, Node},
     virtual_dom::{ToClasses, UpdateEl},
     Url, C, IF,
 };
@@ -15,38 +18,87 @@ use valens_web_app as web_app;
 
 use crate::common;
 
+/// Number of days of training sessions that are loaded initially. Older training sessions are
+/// loaded on demand.
+const TRAINING_SESSION_HISTORY_DAYS: i64 = 182;
+
+/// Maximum factor by which automatic refreshes are delayed after failed refreshes.
+const MAX_REFRESH_BACKOFF: u32 = 16;
+
+/// Gaps between weigh-ins of more than this number of days are not bridged when determining the
+/// weekly change.
+pub const MAX_BODY_WEIGHT_GAP_DAYS: i64 = 14;
+
+/// Number of weeks on which the load recommendation is based.
+pub const RECOMMENDATION_WEEKS: usize = 4;
+
 // ------ ------
 //     Init
 // ------ ------
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
+    let pending_notification_action =
+        web_app::service_worker::SelectedNotificationAction::from_parameters(|name| {
+            url.search()
+                .get(name)
+                .and_then(|values| values.first())
+                .map(String::as_str)
+        });
+    if url
+        .search()
+        .contains_key(web_app::service_worker::NOTIFICATION_ACTION_PARAMETER)
+    {
+        let mut url = url.clone();
+        for name in [
+            web_app::service_worker::NOTIFICATION_ACTION_PARAMETER,
+            web_app::service_worker::NOTIFICATION_TRAINING_SESSION_PARAMETER,
+            web_app::service_worker::NOTIFICATION_ELEMENT_COUNT_PARAMETER,
+        ] {
+            url.search_mut().remove(name);
+        }
+        url.go_and_replace();
+    }
     orders
         .send_msg(Msg::ReadSettings)
-        .send_msg(Msg::ReadOngoingTrainingSession);
+        .send_msg(Msg::ReadOngoingTrainingSession)
+        .stream(streams::interval(60_000, || Msg::RefreshIfOutdated))
+        .stream(streams::window_event(Ev::Focus, |_| Msg::RefreshIfOutdated))
+        .stream(streams::document_event(
+            Ev::from("visibilitychange"),
+            |_| Msg::RefreshIfOutdated,
+        ));
     Model {
-        storage: Arc::new(storage::rest::Storage),
+        storage: Arc::new(storage::rest::Storage::default()),
         ui_storage: Arc::new(storage::local_storage::UI),
+        image_cache: Arc::new(storage::indexed_db::ImageCache),
+        image_cache_disabled: false,
         base_url: url.to_hash_base_url(),
         errors: Vec::new(),
-        app_update_available: false,
+        retry_queue: RetryQueue::default(),
+        version_compatibility: web_app::version::Compatibility::Compatible,
+        version_notice_dismissed: false,
+        app_update_pending: None,
+        pending_notification_action,
+        app_update_ready: None,
+        reload_after_app_update: false,
         session: None,
         version: String::new(),
-        users: BTreeMap::new(),
-        loading_users: false,
-        body_weight: BTreeMap::new(),
-        loading_body_weight: false,
-        body_fat: BTreeMap::new(),
-        loading_body_fat: false,
-        period: BTreeMap::new(),
-        loading_period: false,
-        exercises: BTreeMap::new(),
-        loading_exercises: false,
-        routines: BTreeMap::new(),
-        loading_routines: false,
-        training_sessions: BTreeMap::new(),
-        loading_training_sessions: false,
+        users: web_app::resource::Resource::default(),
+        body_weight: web_app::resource::Resource::default(),
+        body_fat: web_app::resource::Resource::default(),
+        body_measurement: web_app::resource::Resource::default(),
+        period: web_app::resource::Resource::default(),
+        exercises: web_app::resource::Resource::default(),
+        exercise_batch_progress: None,
+        exercise_images: BTreeMap::new(),
+        routines: web_app::resource::Resource::default(),
+        training_sessions: web_app::resource::Resource::default(),
+        training_sessions_since: initial_training_sessions_since(),
+        training_session_batch_progress: None,
+        modified_training_sessions: BTreeMap::new(),
         last_refresh: DateTime::default(),
+        refresh_failures: 0,
         avg_body_weight: BTreeMap::new(),
         cycles: Vec::new(),
         current_cycle: None,
@@ -54,8 +106,11 @@ pub fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
             short_term_load: Vec::new(),
             long_term_load: Vec::new(),
         },
+        changes: ChangeCounters::default(),
         settings: web_app::Settings::default(),
         ongoing_training_session: None,
+        trash: web_app::trash::Trash::default(),
+        storage_usage: None,
     }
 }
 
@@ -67,53 +122,244 @@ pub fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
 pub struct Model {
     storage: Arc<dyn storage::Storage>,
     ui_storage: Arc<dyn storage::UI>,
+    image_cache: Arc<dyn storage::ImageCache>,
+    /// Images are not cached anymore, as the storage space on the device is exhausted.
+    pub image_cache_disabled: bool,
     pub base_url: Url,
     errors: Vec<String>,
-    app_update_available: bool,
+    retry_queue: RetryQueue,
+    version_compatibility: web_app::version::Compatibility,
+    /// The user decided to continue in read-only mode despite incompatible versions.
+    version_notice_dismissed: bool,
+    app_update_pending: Option<String>,
+    /// Action of a notification which was selected while the app was closed. It is applied as soon
+    /// as the ongoing training session has been read.
+    pending_notification_action: Option<web_app::service_worker::SelectedNotificationAction>,
+    app_update_ready: Option<String>,
+    reload_after_app_update: bool,
 
     // ------ Data -----
     pub session: Option<domain::User>,
     pub version: String,
-    pub users: BTreeMap<u32, domain::User>,
-    pub loading_users: bool,
+    pub users: web_app::resource::Resource<BTreeMap<u32, domain::User>>,
 
     // ------ Session-dependent data ------
-    pub body_weight: BTreeMap<NaiveDate, domain::BodyWeight>,
-    pub loading_body_weight: bool,
-    pub body_fat: BTreeMap<NaiveDate, domain::BodyFat>,
-    pub loading_body_fat: bool,
-    pub period: BTreeMap<NaiveDate, domain::Period>,
-    pub loading_period: bool,
-    pub exercises: BTreeMap<u32, domain::Exercise>,
-    pub loading_exercises: bool,
-    pub routines: BTreeMap<u32, domain::Routine>,
-    pub loading_routines: bool,
-    pub training_sessions: BTreeMap<u32, domain::TrainingSession>,
-    pub loading_training_sessions: bool,
+    pub body_weight: web_app::resource::Resource<BTreeMap<NaiveDate, domain::BodyWeight>>,
+    pub body_fat: web_app::resource::Resource<BTreeMap<NaiveDate, domain::BodyFat>>,
+    pub body_measurement: web_app::resource::Resource<BTreeMap<NaiveDate, domain::BodyMeasurement>>,
+    pub period: web_app::resource::Resource<BTreeMap<NaiveDate, domain::Period>>,
+    pub exercises: web_app::resource::Resource<BTreeMap<u32, domain::Exercise>>,
+    /// Number of processed and total exercises while replacing multiple exercises.
+    pub exercise_batch_progress: Option<(usize, usize)>,
+    /// Loaded exercise images by image digest.
+    pub exercise_images: BTreeMap<String, domain::ExerciseImage>,
+    pub routines: web_app::resource::Resource<BTreeMap<u32, domain::Routine>>,
+    pub training_sessions: web_app::resource::Resource<BTreeMap<u32, domain::TrainingSession>>,
+    /// Training sessions before this date have not been loaded. All training sessions have been
+    /// loaded if no date is set.
+    training_sessions_since: Option<NaiveDate>,
+    /// Number of processed and total training sessions while deleting multiple training sessions.
+    pub training_session_batch_progress: Option<(usize, usize)>,
+    modified_training_sessions: BTreeMap<u32, ModifiedTrainingSession>,
     pub last_refresh: DateTime<Utc>,
+    refresh_failures: u32,
 
     // ------ Derived data ------
     pub avg_body_weight: BTreeMap<NaiveDate, domain::BodyWeight>,
     pub cycles: Vec<domain::Cycle>,
     pub current_cycle: Option<domain::CurrentCycle>,
     pub training_stats: domain::TrainingStats,
+    /// Counters of changes, which allow pages to cache data derived from specific entities.
+    pub changes: ChangeCounters,
 
     // ------ Client-side data ------
     pub settings: web_app::Settings,
     pub ongoing_training_session: Option<web_app::OngoingTrainingSession>,
+    /// Local copies of entries deleted by the current user.
+    pub trash: web_app::trash::Trash,
+    pub storage_usage: Option<StorageUsage>,
+}
+
+/// Usage of the storage on the device.
+#[derive(Clone)]
+pub struct StorageUsage {
+    pub estimate: Result<web_app::storage_usage::Estimate, String>,
+    pub local_storage: Result<web_app::storage_usage::LocalStorageUsage, String>,
+    pub cached_exercise_images: Result<u32, String>,
+}
+
+/// Training session that was modified locally, but whose modification was not yet confirmed by
+/// the server.
+struct ModifiedTrainingSession {
+    pending_requests: usize,
+    confirmed: domain::TrainingSession,
+}
+
+/// State of an import of a routine, which requires creating the missing exercises one after
+/// another before the routine can be created.
+#[derive(Clone)]
+pub struct RoutineImport {
+    name: String,
+    export: web_app::routine_exchange::RoutineExport,
+    /// IDs of the matched and already created exercises by exported exercise name.
+    exercise_ids: BTreeMap<String, u32>,
+    missing_exercises: Vec<web_app::routine_exchange::ExportedExercise>,
+}
+
+/// Create or update request which failed and can be sent again.
+#[derive(Clone)]
+pub struct FailedRequest {
+    message: String,
+    request: Box<Msg>,
+}
+
+impl FailedRequest {
+    fn new(request: Msg) -> impl FnOnce(String) -> Self {
+        move |message| Self {
+            message,
+            request: Box::new(request),
+        }
+    }
+}
+
+/// Failed requests which the user can retry or discard.
+///
+/// The queue is part of the data model, so that a retried request is completed even if the page
+/// which initiated it has been left in the meantime.
+#[derive(Default)]
+struct RetryQueue {
+    failed_requests: Vec<FailedRequest>,
+}
+
+impl RetryQueue {
+    fn push(&mut self, context: &str, failed_request: FailedRequest) {
+        self.failed_requests.push(FailedRequest {
+            message: format!("{context}: {}", failed_request.message),
+            request: failed_request.request,
+        });
+    }
+
+    fn last_message(&self) -> Option<&str> {
+        self.failed_requests.last().map(|f| f.message.as_str())
+    }
+
+    /// Remove the most recent failed request and return the request for sending it again.
+    fn retry(&mut self) -> Option<Msg> {
+        self.failed_requests.pop().map(|f| *f.request)
+    }
+
+    fn discard(&mut self) {
+        self.failed_requests.pop();
+    }
+
+    fn clear(&mut self) {
+        self.failed_requests.clear();
+    }
+}
+
+/// Number of changes per entity type.
+///
+/// A counter is incremented for each event which indicates a change of the corresponding entities.
+/// The absolute values are meaningless, only a difference between two values is relevant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ChangeCounters {
+    pub users: u32,
+    pub body_weight: u32,
+    pub body_fat: u32,
+    pub body_measurement: u32,
+    pub period: u32,
+    pub exercises: u32,
+    pub routines: u32,
+    pub training_sessions: u32,
+}
+
+impl ChangeCounters {
+    fn record(&mut self, event: &Event) {
+        match event {
+            Event::UsersChanged
+            | Event::UserCreatedOk
+            | Event::UserReplacedOk
+            | Event::UserDeletedOk
+            | Event::UserPinReplacedOk => increment(&mut self.users),
+            Event::BodyWeightChanged
+            | Event::BodyWeightCreatedOk
+            | Event::BodyWeightReplacedOk
+            | Event::BodyWeightDeletedOk => increment(&mut self.body_weight),
+            Event::BodyFatChanged
+            | Event::BodyFatCreatedOk
+            | Event::BodyFatReplacedOk
+            | Event::BodyFatDeletedOk => increment(&mut self.body_fat),
+            Event::BodyMeasurementChanged
+            | Event::BodyMeasurementCreatedOk
+            | Event::BodyMeasurementReplacedOk
+            | Event::BodyMeasurementDeletedOk => increment(&mut self.body_measurement),
+            Event::PeriodChanged
+            | Event::PeriodCreatedOk
+            | Event::PeriodReplacedOk
+            | Event::PeriodDeletedOk => increment(&mut self.period),
+            Event::ExercisesChanged(_)
+            | Event::ExerciseCreatedOk
+            | Event::ExerciseReplacedOk
+            | Event::ExercisesReplacedOk
+            | Event::ExercisesReplacedErr
+            | Event::ExerciseDeletedOk
+            | Event::ExerciseImageReplacedOk
+            | Event::ExerciseImageDeletedOk => increment(&mut self.exercises),
+            Event::ExercisesMergedOk | Event::ExercisesMergedErr => {
+                // A merge can be partially successful
+                increment(&mut self.exercises);
+                increment(&mut self.routines);
+                increment(&mut self.training_sessions);
+            }
+            Event::RoutineImportedOk(_) | Event::RoutineImportedErr => {
+                // Exercises may have been created even if the import failed
+                increment(&mut self.exercises);
+                increment(&mut self.routines);
+            }
+            Event::RoutinesChanged(_)
+            | Event::RoutineCreatedOk
+            | Event::RoutineModifiedOk
+            | Event::RoutineDeletedOk => increment(&mut self.routines),
+            Event::TagDeletedOk | Event::TagDeletedErr => {
+                // The tag may have been removed from some routines even if the deletion failed
+                increment(&mut self.routines);
+            }
+            Event::TrainingSessionsChanged(_)
+            | Event::TrainingSessionCreatedOk
+            | Event::TrainingSessionModifiedOk
+            | Event::TrainingSessionModifiedErr
+            | Event::TrainingSessionDeletedOk
+            | Event::TrainingSessionsDeletedOk
+            | Event::TrainingSessionsDeletedErr => increment(&mut self.training_sessions),
+            _ => {}
+        }
+    }
+
+    /// Record a change of all session-dependent data.
+    fn record_session_change(&mut self) {
+        for counter in [
+            &mut self.body_weight,
+            &mut self.body_fat,
+            &mut self.body_measurement,
+            &mut self.period,
+            &mut self.exercises,
+            &mut self.routines,
+            &mut self.training_sessions,
+        ] {
+            increment(counter);
+        }
+    }
+}
+
+fn increment(counter: &mut u32) {
+    *counter = counter.wrapping_add(1);
 }
 
 impl Model {
     pub fn exercises(&self, filter: &domain::ExerciseFilter) -> Vec<&domain::Exercise> {
         self.exercises
             .values()
-            .filter(|e| {
-                filter.muscles.is_empty()
-                    || filter
-                        .muscles
-                        .iter()
-                        .all(|m| e.muscle_stimulus().contains_key(&m.id()))
-            })
+            .filter(|e| filter.matches(e))
             .collect()
     }
 
@@ -124,40 +370,200 @@ impl Model {
         sort_routines_by_last_use(&self.routines, &self.training_sessions, filter)
     }
 
+    /// Return true if all training sessions have been loaded from the storage.
+    pub fn all_training_sessions_loaded(&self) -> bool {
+        self.training_sessions_since.is_none()
+    }
+
     pub fn training_sessions_date_range(&self) -> std::ops::RangeInclusive<NaiveDate> {
         let dates = self.training_sessions.values().map(|t| t.date);
         dates.clone().min().unwrap_or_default()..=dates.max().unwrap_or_default()
     }
 
+    /// Compute the statistics shown on the home and training pages.
+    pub fn dashboard(&self, interval: &domain::Interval) -> domain::dashboard::Dashboard {
+        domain::dashboard::compute(
+            &domain::dashboard::UserData {
+                training_sessions: &self.training_sessions,
+                exercises: &self.exercises,
+                training_stats: &self.training_stats,
+                avg_body_weight: &self.avg_body_weight,
+                period: &self.period,
+            },
+            interval,
+            &domain::dashboard::Options {
+                weekly_session_target: self.settings.weekly_session_target,
+                recommendation_weeks: RECOMMENDATION_WEEKS,
+                max_gap_days: MAX_BODY_WEIGHT_GAP_DAYS,
+                trend_window_days: i64::from(self.settings.trend_window),
+            },
+            &web_app::clock::BrowserClock,
+        )
+    }
+
+    /// Calculate the target weight for a percentage of the latest estimated one-repetition
+    /// maximum of an exercise. The target weight is rounded to the nearest weight achievable at
+    /// the active location. No target weight is returned if no estimate is available.
+    pub fn target_weight(&self, exercise_id: u32, percent_1rm: f32) -> Option<f32> {
+        let exercise = self.exercises.get(&exercise_id)?;
+        domain::progression::one_rep_max_values(self.training_sessions.values(), exercise)
+            .into_iter()
+            .max_by_key(|(date, _)| *date)
+            .and_then(|(_, one_rep_max)| {
+                domain::progression::target_weight(
+                    percent_1rm,
+                    one_rep_max,
+                    exercise,
+                    self.settings.weight_increment,
+                )
+            })
+            .map(|weight| {
+                self.settings
+                    .active_location()
+                    .and_then(|location| location.round(exercise, weight))
+                    .and_then(|achievable| achievable.nearest(weight))
+                    .unwrap_or(weight)
+            })
+    }
+
+    /// Weights of the exercise which are achievable with the equipment at the active location.
+    /// `None` is returned if no location is active or the location lacks the required equipment.
+    pub fn achievable_weights(&self, exercise_id: u32) -> Option<Vec<f32>> {
+        let exercise = self.exercises.get(&exercise_id)?;
+        self.settings.active_location()?.weights(exercise)
+    }
+
+    /// Current user as contained in the list of users, which also includes the body height.
+    pub fn session_user(&self) -> Option<&domain::User> {
+        self.session
+            .as_ref()
+            .and_then(|session| self.users.get(&session.id))
+    }
+
+    /// Body height of the current user in cm.
+    pub fn body_height(&self) -> Option<f32> {
+        self.session_user().and_then(|user| user.height)
+    }
+
+    /// Age of the current user at the given date, which is used for the body fat estimates.
+    pub fn age(&self, date: NaiveDate) -> u32 {
+        self.session_user()
+            .map_or(domain::DEFAULT_AGE, |user| user.age(date))
+    }
+
+    /// Check whether any previously loaded data is currently refreshed.
+    pub fn is_refreshing(&self) -> bool {
+        self.users.is_refreshing()
+            || self.body_weight.is_refreshing()
+            || self.body_fat.is_refreshing()
+            || self.body_measurement.is_refreshing()
+            || self.period.is_refreshing()
+            || self.exercises.is_refreshing()
+            || self.routines.is_refreshing()
+            || self.training_sessions.is_refreshing()
+    }
+
+    pub fn show_menstrual_cycle(&self) -> bool {
+        self.session
+            .as_ref()
+            .is_some_and(|user| self.settings.menstrual_cycle_shown(user))
+    }
+
+    pub fn show_data_tables(&self, page: web_app::chart::Page) -> bool {
+        self.settings.data_tables.contains(&page)
+    }
+
+    pub fn body_weight_goal(&self) -> Option<domain::BodyWeightGoal> {
+        self.session
+            .as_ref()
+            .and_then(|user| self.settings.body_weight_goal.get(&user.id))
+            .copied()
+    }
+
     pub fn theme(&self) -> &web_app::Theme {
         match self.settings.theme {
-            web_app::Theme::System => {
-                if let Some(window) = web_sys::window() {
-                    if let Ok(prefers_dark_scheme) =
-                        window.match_media("(prefers-color-scheme: dark)")
-                    {
-                        if let Some(media_query_list) = prefers_dark_scheme {
-                            if media_query_list.matches() {
-                                &web_app::Theme::Dark
-                            } else {
-                                &web_app::Theme::Light
-                            }
-                        } else {
-                            error!("failed to determine preferred color scheme");
-                            &web_app::Theme::Light
-                        }
-                    } else {
-                        error!("failed to match media to determine preferred color scheme");
-                        &web_app::Theme::Light
-                    }
-                } else {
-                    error!("failed to access window to determine preferred color scheme");
+            web_app::Theme::System => match web_app::media::prefers_dark_color_scheme() {
+                Ok(true) => &web_app::Theme::Dark,
+                Ok(false) => &web_app::Theme::Light,
+                Err(err) => {
+                    error!("failed to determine preferred color scheme:", err);
                     &web_app::Theme::Light
                 }
-            }
+            },
             web_app::Theme::Light | web_app::Theme::Dark => &self.settings.theme,
         }
     }
+
+    /// Check whether high contrast is effective, either by the settings or the system preference.
+    pub fn high_contrast(&self) -> bool {
+        self.settings
+            .contrast
+            .is_high(
+                web_app::media::prefers_high_contrast().unwrap_or_else(|err| {
+                    error!("failed to determine preferred contrast:", err);
+                    false
+                }),
+            )
+    }
+
+    pub fn chart_style(&self) -> web_app::chart::Style {
+        web_app::chart::Style {
+            theme: *self.theme(),
+            high_contrast: self.high_contrast(),
+        }
+    }
+
+    /// Whether the notice about incompatible versions of app and server has to be shown.
+    pub fn show_version_notice(&self) -> bool {
+        !self.version_compatibility.is_compatible() && !self.version_notice_dismissed
+    }
+}
+
+/// Check if the last refresh is older than the configured refresh interval.
+///
+/// The interval is doubled for each failed refresh to prevent repeated requests while the server
+/// is not reachable.
+fn refresh_due(model: &Model) -> bool {
+    if model.settings.refresh_interval == 0 {
+        return false;
+    }
+    let backoff = 2_u32
+        .saturating_pow(model.refresh_failures)
+        .min(MAX_REFRESH_BACKOFF);
+    Utc::now() - model.last_refresh
+        >= Duration::minutes(i64::from(
+            model.settings.refresh_interval.saturating_mul(backoff),
+        ))
+}
+
+/// Request the service worker to replace the cached app assets.
+///
+/// The service worker announces the completion of the update by a message, which leads to
+/// `Msg::AppUpdateReady`.
+fn update_app_cache(model: &mut Model, version: String) -> Result<(), String> {
+    web_app::service_worker::post(&web_app::service_worker::Message::UpdateCache(
+        version.clone(),
+    ))?;
+    model.app_update_pending = Some(version);
+    Ok(())
+}
+
+fn reload_app() {
+    if let Err(err) = web_app::service_worker::post(&web_app::service_worker::Message::SkipWaiting)
+    {
+        error!("failed to activate service worker:", err);
+    }
+    Url::reload();
+}
+
+fn document_hidden() -> bool {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .is_some_and(|document| document.hidden())
+}
+
+fn initial_training_sessions_since() -> Option<NaiveDate> {
+    Some(web_app::clock::today() - Duration::days(TRAINING_SESSION_HISTORY_DAYS))
 }
 
 fn sort_routines_by_last_use(
@@ -189,6 +595,104 @@ fn sort_routines_by_last_use(
         .collect()
 }
 
+/// Update the training statistics after the training sessions on the given dates were added,
+/// changed or removed.
+fn update_training_stats(model: &mut Model, changed_dates: &[NaiveDate]) {
+    model.training_stats.update(
+        &model.training_sessions.values().collect::<Vec<_>>(),
+        changed_dates,
+        &web_app::clock::BrowserClock,
+    );
+}
+
+/// Determine the event that signals the failure of a request that modifies data on the server.
+/// Returns `None` if the message does not cause such a request.
+fn rejected_modification(msg: &Msg) -> Option<Event> {
+    match msg {
+        Msg::CreateUser(..) => Some(Event::UserCreatedErr),
+        Msg::ReplaceUser(..) => Some(Event::UserReplacedErr),
+        Msg::DeleteUser(..) => Some(Event::UserDeletedErr),
+        Msg::ReplaceUserPin(..) => Some(Event::UserPinReplacedErr),
+        Msg::CreateBodyWeight(..) => Some(Event::BodyWeightCreatedErr),
+        Msg::ReplaceBodyWeight(..) => Some(Event::BodyWeightReplacedErr),
+        Msg::DeleteBodyWeight(..) => Some(Event::BodyWeightDeletedErr),
+        Msg::CreateBodyFat(..) => Some(Event::BodyFatCreatedErr),
+        Msg::ReplaceBodyFat(..) => Some(Event::BodyFatReplacedErr),
+        Msg::DeleteBodyFat(..) => Some(Event::BodyFatDeletedErr),
+        Msg::CreateBodyMeasurement(..) => Some(Event::BodyMeasurementCreatedErr),
+        Msg::ReplaceBodyMeasurement(..) => Some(Event::BodyMeasurementReplacedErr),
+        Msg::DeleteBodyMeasurement(..) => Some(Event::BodyMeasurementDeletedErr),
+        Msg::CreatePeriod(..) => Some(Event::PeriodCreatedErr),
+        Msg::ReplacePeriod(..) => Some(Event::PeriodReplacedErr),
+        Msg::DeletePeriod(..) => Some(Event::PeriodDeletedErr),
+        Msg::CreateExercise(..) => Some(Event::ExerciseCreatedErr),
+        Msg::ReplaceExercise(..) => Some(Event::ExerciseReplacedErr),
+        Msg::ReplaceExercises(..) => Some(Event::ExercisesReplacedErr),
+        Msg::DeleteExercise(..) => Some(Event::ExerciseDeletedErr),
+        Msg::ReplaceExerciseImage(..) => Some(Event::ExerciseImageReplacedErr),
+        Msg::DeleteExerciseImage(..) => Some(Event::ExerciseImageDeletedErr),
+        Msg::MergeExercises(..) => Some(Event::ExercisesMergedErr),
+        Msg::CreateRoutine(..) | Msg::CreateRoutineFromTrainingSession(..) => {
+            Some(Event::RoutineCreatedErr)
+        }
+        Msg::ModifyRoutine(..) => Some(Event::RoutineModifiedErr),
+        Msg::ImportRoutine(..) => Some(Event::RoutineImportedErr),
+        Msg::DeleteRoutine(..) => Some(Event::RoutineDeletedErr),
+        Msg::DeleteTag(..) => Some(Event::TagDeletedErr),
+        Msg::CreateTrainingSession(..) | Msg::RepeatTrainingSession(..) => {
+            Some(Event::TrainingSessionCreatedErr)
+        }
+        Msg::ModifyTrainingSession(..) => Some(Event::TrainingSessionModifiedErr),
+        Msg::DeleteTrainingSession(..) => Some(Event::TrainingSessionDeletedErr),
+        Msg::DeleteTrainingSessions(..) => Some(Event::TrainingSessionsDeletedErr),
+        Msg::RestoreFromTrash(entry) => Some(match entry {
+            web_app::trash::Entry::BodyWeight(_) => Event::BodyWeightCreatedErr,
+            web_app::trash::Entry::BodyFat(_) => Event::BodyFatCreatedErr,
+            web_app::trash::Entry::Period(_) => Event::PeriodCreatedErr,
+        }),
+        _ => None,
+    }
+}
+
+/// Store a copy of the image on the device, unless the cache has been disabled.
+fn cache_exercise_image(
+    model: &Model,
+    orders: &mut impl Orders<Msg>,
+    exercise_id: u32,
+    digest: String,
+    image: domain::ExerciseImage,
+) {
+    if model.image_cache_disabled {
+        return;
+    }
+    let image_cache = model.image_cache.clone();
+    orders.perform_cmd(async move {
+        Msg::ExerciseImageCached(
+            image_cache
+                .write_exercise_image(exercise_id, digest, image)
+                .await,
+        )
+    });
+}
+
+/// Record the change indicated by the event and notify all subscribers.
+fn notify(model: &mut Model, orders: &mut impl Orders<Msg>, event: Event) {
+    model.changes.record(&event);
+    orders.notify(event);
+}
+
+/// Determine the keys of all entries which were added, changed or removed.
+fn changed_keys<K: Ord + Copy, V: PartialEq>(old: &BTreeMap<K, V>, new: &BTreeMap<K, V>) -> Vec<K> {
+    let mut keys = old
+        .iter()
+        .filter(|(key, value)| new.get(key) != Some(value))
+        .map(|(key, _)| *key)
+        .chain(new.keys().filter(|key| !old.contains_key(key)).copied())
+        .collect::<Vec<_>>();
+    keys.sort_unstable();
+    keys
+}
+
 // ------ ------
 //    Update
 // ------ ------
@@ -196,14 +700,19 @@ fn sort_routines_by_last_use(
 #[derive(Clone)]
 pub enum Msg {
     RemoveError,
+    RetryFailedRequest,
+    DiscardFailedRequest,
 
     UpdateApp,
-    CancelAppUpdate,
+    DismissVersionNotice,
+    AppUpdateReady(String),
+    DismissAppUpdate,
 
     Refresh,
+    RefreshIfOutdated,
     ClearSessionDependentData,
 
-    RequestSession(u32),
+    RequestSession(u32, Option<String>),
     SessionReceived(Result<domain::User, String>),
     InitializeSession,
     SessionInitialized(Result<domain::User, String>),
@@ -222,84 +731,196 @@ pub enum Msg {
     UserReplaced(Result<domain::User, String>),
     DeleteUser(u32),
     UserDeleted(Result<u32, String>),
+    ReplaceUserPin(u32, Option<String>),
+    UserPinReplaced(Result<domain::User, String>),
 
     ReadBodyWeight,
     BodyWeightRead(Result<Vec<domain::BodyWeight>, String>),
     CreateBodyWeight(domain::BodyWeight),
-    BodyWeightCreated(Result<domain::BodyWeight, String>),
+    BodyWeightCreated(Result<domain::BodyWeight, FailedRequest>),
     ReplaceBodyWeight(domain::BodyWeight),
-    BodyWeightReplaced(Result<domain::BodyWeight, String>),
+    BodyWeightReplaced(Result<domain::BodyWeight, FailedRequest>),
     DeleteBodyWeight(NaiveDate),
     BodyWeightDeleted(Result<NaiveDate, String>),
 
     ReadBodyFat,
     BodyFatRead(Result<Vec<domain::BodyFat>, String>),
     CreateBodyFat(domain::BodyFat),
-    BodyFatCreated(Result<domain::BodyFat, String>),
+    BodyFatCreated(Result<domain::BodyFat, FailedRequest>),
     ReplaceBodyFat(domain::BodyFat),
-    BodyFatReplaced(Result<domain::BodyFat, String>),
+    BodyFatReplaced(Result<domain::BodyFat, FailedRequest>),
     DeleteBodyFat(NaiveDate),
     BodyFatDeleted(Result<NaiveDate, String>),
 
+    ReadBodyMeasurement,
+    BodyMeasurementRead(Result<Vec<domain::BodyMeasurement>, String>),
+    CreateBodyMeasurement(domain::BodyMeasurement),
+    BodyMeasurementCreated(Result<domain::BodyMeasurement, String>),
+    ReplaceBodyMeasurement(domain::BodyMeasurement),
+    BodyMeasurementReplaced(Result<domain::BodyMeasurement, String>),
+    DeleteBodyMeasurement(NaiveDate),
+    BodyMeasurementDeleted(Result<NaiveDate, String>),
+
     ReadPeriod,
     PeriodRead(Result<Vec<domain::Period>, String>),
     CreatePeriod(domain::Period),
-    PeriodCreated(Result<domain::Period, String>),
+    PeriodCreated(Result<domain::Period, FailedRequest>),
     ReplacePeriod(domain::Period),
-    PeriodReplaced(Result<domain::Period, String>),
+    PeriodReplaced(Result<domain::Period, FailedRequest>),
     DeletePeriod(NaiveDate),
     PeriodDeleted(Result<NaiveDate, String>),
 
     ReadExercises,
     ExercisesRead(Result<Vec<domain::Exercise>, String>),
     CreateExercise(String, Vec<domain::ExerciseMuscle>),
-    ExerciseCreated(Result<domain::Exercise, String>),
+    ExerciseCreated(Result<domain::Exercise, FailedRequest>),
     ReplaceExercise(domain::Exercise),
-    ExerciseReplaced(Result<domain::Exercise, String>),
+    ExerciseReplaced(Result<domain::Exercise, FailedRequest>),
+    ReplaceExercises(Vec<domain::Exercise>),
+    ReplaceNextExercise(Vec<domain::Exercise>, Vec<String>),
+    NextExerciseReplaced(
+        String,
+        Result<domain::Exercise, String>,
+        Vec<domain::Exercise>,
+        Vec<String>,
+    ),
     DeleteExercise(u32),
+    MergeExercises(u32, u32),
+    MergeNextReference(u32, domain::ExerciseMerge, Vec<String>),
+    RoutineOfMergeModified(
+        u32,
+        domain::ExerciseMerge,
+        Vec<String>,
+        String,
+        Result<domain::Routine, String>,
+    ),
+    TrainingSessionOfMergeModified(
+        u32,
+        domain::ExerciseMerge,
+        Vec<String>,
+        NaiveDate,
+        Result<domain::TrainingSession, String>,
+    ),
+    SourceExerciseOfMergeDeleted(Result<u32, String>),
     ExerciseDeleted(Result<u32, String>),
+    /// Load the image of the exercise from the cache or the server, if it is not loaded yet.
+    ReadExerciseImage(u32),
+    ExerciseImageFetched(u32, String, Result<domain::ExerciseImage, String>),
+    ExerciseImageRead(u32, String, Result<domain::ExerciseImage, String>),
+    ReplaceExerciseImage(u32, domain::ExerciseImage),
+    ExerciseImageReplaced(Result<(domain::Exercise, domain::ExerciseImage), String>),
+    DeleteExerciseImage(u32),
+    ExerciseImageDeleted(Result<u32, String>),
+    ExerciseImageCached(Result<(), String>),
+    ReadStorageUsage,
+    StorageUsageRead(StorageUsage),
+    ClearImageCache,
+    ImageCacheCleared(Result<(), String>),
 
     ReadRoutines,
     RoutinesRead(Result<Vec<domain::Routine>, String>),
     CreateRoutine(String, u32),
-    RoutineCreated(Result<domain::Routine, String>),
+    CreateRoutineFromTrainingSession(String, u32),
+    RoutineCreated(Result<domain::Routine, FailedRequest>),
     ModifyRoutine(
         u32,
         Option<String>,
         Option<bool>,
         Option<Vec<domain::RoutinePart>>,
+        Option<Vec<Weekday>>,
+        Option<Vec<domain::Tag>>,
     ),
-    RoutineModified(Result<domain::Routine, String>),
+    RoutineModified(Result<domain::Routine, FailedRequest>),
     DeleteRoutine(u32),
     RoutineDeleted(Result<u32, String>),
+    /// Create a routine from a snippet, optionally creating the exercises missing in the snippet.
+    ImportRoutine(String, web_app::routine_exchange::RoutineExport, bool),
+    ImportNextExercise(RoutineImport),
+    ExerciseOfImportCreated(RoutineImport, String, Result<domain::Exercise, String>),
+    RoutineImported(Result<domain::Routine, String>),
+    /// Remove a tag from all routines.
+    DeleteTag(String),
+    RemoveTagFromNextRoutine(Vec<domain::Routine>, Vec<String>),
+    RoutineOfTagDeletionModified(
+        Vec<domain::Routine>,
+        Vec<String>,
+        String,
+        Result<domain::Routine, String>,
+    ),
 
     ReadTrainingSessions,
-    TrainingSessionsRead(Result<Vec<domain::TrainingSession>, String>),
+    TrainingSessionsRead(
+        Option<NaiveDate>,
+        Result<Vec<domain::TrainingSession>, String>,
+    ),
+    ReadOlderTrainingSessions(Option<NaiveDate>),
+    OlderTrainingSessionsRead(
+        Option<NaiveDate>,
+        Result<Vec<domain::TrainingSession>, String>,
+    ),
     CreateTrainingSession(
         Option<u32>,
         NaiveDate,
         String,
         Vec<domain::TrainingSessionElement>,
     ),
-    TrainingSessionCreated(Result<domain::TrainingSession, String>),
+    /// Create a training session for today which repeats the training session.
+    RepeatTrainingSession(u32),
+    TrainingSessionCreated(Result<domain::TrainingSession, FailedRequest>),
     ModifyTrainingSession(
         u32,
         Option<String>,
         Option<Vec<domain::TrainingSessionElement>>,
+        Option<NaiveDateTime>,
+        Option<NaiveDateTime>,
     ),
-    TrainingSessionModified(Result<domain::TrainingSession, String>),
+    TrainingSessionModified(u32, Result<domain::TrainingSession, FailedRequest>),
     DeleteTrainingSession(u32),
     TrainingSessionDeleted(Result<u32, String>),
+    DeleteTrainingSessions(Vec<u32>),
+    DeleteNextTrainingSession(Vec<u32>, Vec<u32>, Vec<String>),
+    NextTrainingSessionDeleted(
+        Option<NaiveDate>,
+        Result<u32, String>,
+        Vec<u32>,
+        Vec<u32>,
+        Vec<String>,
+    ),
 
     SetBeepVolume(u8),
     SetTheme(web_app::Theme),
+    SetContrast(web_app::Contrast),
     SetAutomaticMetronome(bool),
     SetNotifications(bool),
     SetShowRPE(bool),
     SetShowTUT(bool),
+    SetInterval(domain::DefaultInterval),
+    SetDefaultInterval(Option<domain::DefaultInterval>),
+    SetRefreshInterval(u32),
+    SetShowMenstrualCycle(bool),
+    SetBodyWeightGoal(Option<domain::BodyWeightGoal>),
+    SetWeightIncrement(f32),
+    SetTargetTolerance(u8),
+    SetTrendWindow(u32),
+    ToggleDataTables(web_app::chart::Page),
+    SetDecimalSeparator(web_app::format::DecimalSeparator),
+    AddLocation(domain::equipment::Location),
+    ReplaceLocation(usize, domain::equipment::Location),
+    DeleteLocation(usize),
+    SetActiveLocation(Option<String>),
+    SetTimerPreset(usize, u32),
+    SetMetronomeInterval(u32),
+    SetMetronomeStressedBeat(u32),
+    SetVibration(web_app::vibration::Vibration),
+    SetAnnounceExercises(bool),
+    SetKeepScreenAwake(bool),
+    SetAdHocPreviousValues(bool),
+    SetSoundProfile(web_app::sound::SoundProfile),
+    SetTrainingView(web_app::TrainingView),
+    SetWeeklySessionTarget(u32),
 
-    StartTrainingSession(u32),
-    UpdateTrainingSession(usize, web_app::TimerState),
+    UpdateTrainingSession(web_app::OngoingTrainingSession),
+    ApplyNotificationAction(web_app::service_worker::SelectedNotificationAction),
     EndTrainingSession,
 
     ReadSettings,
@@ -311,16 +932,33 @@ pub enum Msg {
     OngoingTrainingSessionRead(Result<Option<web_app::OngoingTrainingSession>, String>),
     WriteOngoingTrainingSession,
     OngoingTrainingSessionWritten(Result<(), String>),
+
+    ReadTrainingSessionDraft(u32),
+    TrainingSessionDraftRead(Result<Option<web_app::TrainingSessionDraft>, String>),
+    WriteTrainingSessionDraft(u32, Option<web_app::TrainingSessionDraft>),
+    TrainingSessionDraftWritten(Result<(), String>),
+
+    ReadTrash,
+    TrashRead(Result<web_app::trash::Trash, String>),
+    WriteTrash,
+    TrashWritten(Result<(), String>),
+    /// Create the deleted entry again or replace the existing entry of the same date.
+    RestoreFromTrash(web_app::trash::Entry),
 }
 
 #[derive(Clone)]
 pub enum Event {
+    SessionRequestedErr,
+    PinRejected,
+    PinBlocked,
     UserCreatedOk,
     UserCreatedErr,
     UserReplacedOk,
     UserReplacedErr,
     UserDeletedOk,
     UserDeletedErr,
+    UserPinReplacedOk,
+    UserPinReplacedErr,
     BodyWeightCreatedOk,
     BodyWeightCreatedErr,
     BodyWeightReplacedOk,
@@ -333,6 +971,12 @@ pub enum Event {
     BodyFatReplacedErr,
     BodyFatDeletedOk,
     BodyFatDeletedErr,
+    BodyMeasurementCreatedOk,
+    BodyMeasurementCreatedErr,
+    BodyMeasurementReplacedOk,
+    BodyMeasurementReplacedErr,
+    BodyMeasurementDeletedOk,
+    BodyMeasurementDeletedErr,
     PeriodCreatedOk,
     PeriodCreatedErr,
     PeriodReplacedOk,
@@ -343,40 +987,103 @@ pub enum Event {
     ExerciseCreatedErr,
     ExerciseReplacedOk,
     ExerciseReplacedErr,
+    ExercisesReplacedOk,
+    ExercisesReplacedErr,
     ExerciseDeletedOk,
     ExerciseDeletedErr,
+    ExerciseImageReplacedOk,
+    ExerciseImageReplacedErr,
+    ExerciseImageDeletedOk,
+    ExerciseImageDeletedErr,
+    ExercisesMergedOk,
+    ExercisesMergedErr,
     RoutineCreatedOk,
     RoutineCreatedErr,
+    RoutineImportedOk(u32),
+    RoutineImportedErr,
     RoutineModifiedOk,
     RoutineModifiedErr,
     RoutineDeletedOk,
     RoutineDeletedErr,
+    TagDeletedOk,
+    TagDeletedErr,
     TrainingSessionCreatedOk,
     TrainingSessionCreatedErr,
     TrainingSessionModifiedOk,
     TrainingSessionModifiedErr,
     TrainingSessionDeletedOk,
     TrainingSessionDeletedErr,
-    DataChanged,
+    TrainingSessionsDeletedOk,
+    TrainingSessionsDeletedErr,
+    UsersChanged,
+    BodyWeightChanged,
+    BodyFatChanged,
+    BodyMeasurementChanged,
+    PeriodChanged,
+    /// Exercises which were added, changed or removed.
+    ExercisesChanged(Vec<u32>),
+    /// Routines which were added, changed or removed.
+    RoutinesChanged(Vec<u32>),
+    /// Training sessions which were added, changed or removed.
+    TrainingSessionsChanged(Vec<u32>),
     BeepVolumeChanged,
+    SoundProfileChanged,
+    VibrationChanged,
+    KeepScreenAwakeChanged,
+    /// Unsaved changes of a training session which were stored before the app was closed.
+    TrainingSessionDraftRead(web_app::TrainingSessionDraft),
 }
 
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    if !model.version_compatibility.is_compatible() {
+        if let Some(event) = rejected_modification(&msg) {
+            model.errors.push(
+                "Changes cannot be saved as the versions of app and server are incompatible".into(),
+            );
+            notify(model, orders, event);
+            return;
+        }
+    }
+
     match msg {
         Msg::RemoveError => {
             model.errors.pop();
         }
+        Msg::RetryFailedRequest => {
+            if let Some(request) = model.retry_queue.retry() {
+                orders.send_msg(request);
+            }
+        }
+        Msg::DiscardFailedRequest => {
+            model.retry_queue.discard();
+        }
 
         Msg::UpdateApp => {
-            match web_app::service_worker::post(&web_app::service_worker::Message::UpdateCache) {
-                Ok(()) => Url::reload(),
-                Err(err) => {
+            if model.app_update_ready.is_some() {
+                reload_app();
+            } else {
+                model.reload_after_app_update = true;
+                let version = model.version.clone();
+                if let Err(err) = update_app_cache(model, version) {
+                    model.reload_after_app_update = false;
                     model.errors.push(format!("Update failed: {err}"));
                 }
             }
         }
-        Msg::CancelAppUpdate => {
-            model.app_update_available = false;
+        Msg::DismissVersionNotice => {
+            model.version_notice_dismissed = true;
+        }
+        Msg::AppUpdateReady(version) => {
+            model.app_update_pending = None;
+            if model.reload_after_app_update {
+                reload_app();
+            } else {
+                model.app_update_ready = Some(version);
+            }
+        }
+        Msg::DismissAppUpdate => {
+            model.settings.dismissed_app_update = model.app_update_ready.clone();
+            orders.send_msg(Msg::WriteSettings);
         }
 
         Msg::Refresh => {
@@ -385,42 +1092,69 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .send_msg(Msg::ReadUsers)
                 .send_msg(Msg::ReadBodyWeight)
                 .send_msg(Msg::ReadBodyFat)
+                .send_msg(Msg::ReadBodyMeasurement)
                 .send_msg(Msg::ReadPeriod)
                 .send_msg(Msg::ReadExercises)
                 .send_msg(Msg::ReadRoutines)
                 .send_msg(Msg::ReadTrainingSessions);
             model.last_refresh = Utc::now();
         }
+        Msg::RefreshIfOutdated => {
+            if model.session.is_some()
+                && model.ongoing_training_session.is_none()
+                && !document_hidden()
+                && refresh_due(model)
+            {
+                orders.send_msg(Msg::Refresh);
+            }
+        }
         Msg::ClearSessionDependentData => {
-            model.body_weight.clear();
-            model.body_fat.clear();
-            model.period.clear();
-            model.exercises.clear();
-            model.routines.clear();
-            model.training_sessions.clear();
+            model.body_weight.reset();
+            model.body_fat.reset();
+            model.body_measurement.reset();
+            model.period.reset();
+            model.exercises.reset();
+            model.exercise_images.clear();
+            model.routines.reset();
+            model.training_sessions.reset();
+            model.training_sessions_since = initial_training_sessions_since();
+            model.modified_training_sessions.clear();
             model.avg_body_weight.clear();
             model.cycles.clear();
             model.current_cycle = None;
             model.training_stats.clear();
+            model.retry_queue.clear();
+            model.trash = web_app::trash::Trash::default();
+            model.changes.record_session_change();
         }
 
-        Msg::RequestSession(user_id) => {
+        Msg::RequestSession(user_id, pin) => {
             let storage = model.storage.clone();
             orders.skip().perform_cmd(async move {
-                Msg::SessionReceived(storage.request_session(user_id).await)
+                Msg::SessionReceived(storage.request_session(user_id, pin).await)
             });
         }
         Msg::SessionReceived(Ok(new_session)) => {
             model.session = Some(new_session);
-            orders.send_msg(Msg::Refresh).request_url(
-                crate::Urls::new(model.base_url.clone().set_hash_path([""; 0])).home(),
-            );
+            orders
+                .send_msg(Msg::Refresh)
+                .send_msg(Msg::ReadTrash)
+                .request_url(
+                    crate::Urls::new(model.base_url.clone().set_hash_path([""; 0])).home(),
+                );
         }
         Msg::SessionReceived(Err(message)) => {
             model.session = None;
-            model
-                .errors
-                .push("Failed to request session: ".to_owned() + &message);
+            if message.starts_with("401") {
+                notify(model, orders, Event::PinRejected);
+            } else if message.starts_with("429") {
+                notify(model, orders, Event::PinBlocked);
+            } else {
+                notify(model, orders, Event::SessionRequestedErr);
+                model
+                    .errors
+                    .push("Failed to request session: ".to_owned() + &message);
+            }
         }
         Msg::InitializeSession => {
             let storage = model.storage.clone();
@@ -432,7 +1166,8 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             model.session = Some(session);
             orders
                 .notify(subs::UrlChanged(Url::current()))
-                .send_msg(Msg::Refresh);
+                .send_msg(Msg::Refresh)
+                .send_msg(Msg::ReadTrash);
         }
         Msg::SessionInitialized(Err(_)) => {
             model.session = None;
@@ -460,40 +1195,48 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             orders.perform_cmd(async move { Msg::VersionRead(storage.read_version().await) });
         }
         Msg::VersionRead(Ok(version)) => {
+            model.refresh_failures = 0;
             model.version = version;
-            let frontend_version: Vec<&str> = env!("VALENS_VERSION").split('.').collect();
-            let backend_version: Vec<&str> = model.version.split('.').collect();
-            if frontend_version[0] != backend_version[0]
-                || frontend_version[1] != backend_version[1]
-                || frontend_version[2] != backend_version[2]
+            model.version_compatibility =
+                web_app::version::compatibility(env!("VALENS_VERSION"), &model.version);
+            if !web_app::version::same_release(env!("VALENS_VERSION"), &model.version)
+                && model.app_update_pending.is_none()
+                && model.app_update_ready.as_ref() != Some(&model.version)
             {
-                model.app_update_available = true;
+                let version = model.version.clone();
+                if let Err(err) = update_app_cache(model, version) {
+                    error!("failed to update app:", err);
+                }
             }
         }
         Msg::VersionRead(Err(message)) => {
+            model.refresh_failures = model.refresh_failures.saturating_add(1);
             model
                 .errors
                 .push("Failed to read version: ".to_owned() + &message);
         }
 
         Msg::ReadUsers => {
-            model.loading_users = true;
+            model.users.start_loading();
             let storage = model.storage.clone();
             orders.perform_cmd(async move { Msg::UsersRead(storage.read_users().await) });
         }
         Msg::UsersRead(Ok(users)) => {
             let users = users.into_iter().map(|e| (e.id, e)).collect();
-            if model.users != users {
-                model.users = users;
-                orders.notify(Event::DataChanged);
+            if *model.users != users {
+                *model.users = users;
+                notify(model, orders, Event::UsersChanged);
             }
-            model.loading_users = false;
+            model.users.finish_loading();
+            migrate_body_height(model, orders);
         }
         Msg::UsersRead(Err(message)) => {
-            model
-                .errors
-                .push("Failed to read users: ".to_owned() + &message);
-            model.loading_users = false;
+            if model.users.is_available() {
+                model
+                    .errors
+                    .push("Failed to read users: ".to_owned() + &message);
+            }
+            model.users.fail(message);
         }
         Msg::CreateUser(name, sex) => {
             let storage = model.storage.clone();
@@ -502,10 +1245,10 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::UserCreated(Ok(user)) => {
             model.users.insert(user.id, user);
-            orders.notify(Event::UserCreatedOk);
+            notify(model, orders, Event::UserCreatedOk);
         }
         Msg::UserCreated(Err(message)) => {
-            orders.notify(Event::UserCreatedErr);
+            notify(model, orders, Event::UserCreatedErr);
             model
                 .errors
                 .push("Failed to create user: ".to_owned() + &message);
@@ -516,10 +1259,11 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::UserReplaced(Ok(user)) => {
             model.users.insert(user.id, user);
-            orders.notify(Event::UserReplacedOk);
+            notify(model, orders, Event::UserReplacedOk);
+            migrate_body_height(model, orders);
         }
         Msg::UserReplaced(Err(message)) => {
-            orders.notify(Event::UserReplacedErr);
+            notify(model, orders, Event::UserReplacedErr);
             model
                 .errors
                 .push("Failed to replace user: ".to_owned() + &message);
@@ -530,17 +1274,36 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::UserDeleted(Ok(id)) => {
             model.users.remove(&id);
-            orders.notify(Event::UserDeletedOk);
+            notify(model, orders, Event::UserDeletedOk);
         }
         Msg::UserDeleted(Err(message)) => {
-            orders.notify(Event::UserDeletedErr);
+            notify(model, orders, Event::UserDeletedErr);
             model
                 .errors
                 .push("Failed to delete user: ".to_owned() + &message);
         }
+        Msg::ReplaceUserPin(id, pin) => {
+            let storage = model.storage.clone();
+            orders.perform_cmd(async move {
+                Msg::UserPinReplaced(storage.replace_user_pin(id, pin).await)
+            });
+        }
+        Msg::UserPinReplaced(Ok(user)) => {
+            if model.session.as_ref().is_some_and(|s| s.id == user.id) {
+                model.session = Some(user.clone());
+            }
+            model.users.insert(user.id, user);
+            notify(model, orders, Event::UserPinReplacedOk);
+        }
+        Msg::UserPinReplaced(Err(message)) => {
+            notify(model, orders, Event::UserPinReplacedErr);
+            model
+                .errors
+                .push("Failed to replace PIN: ".to_owned() + &message);
+        }
 
         Msg::ReadBodyWeight => {
-            model.loading_body_weight = true;
+            model.body_weight.start_loading();
             let storage = model.storage.clone();
             orders
                 .skip()
@@ -548,52 +1311,66 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::BodyWeightRead(Ok(body_weight)) => {
             let body_weight = body_weight.into_iter().map(|e| (e.date, e)).collect();
-            if model.body_weight != body_weight {
-                model.body_weight = body_weight;
+            if *model.body_weight != body_weight {
+                *model.body_weight = body_weight;
                 model.avg_body_weight = domain::avg_body_weight(&model.body_weight);
-                orders.notify(Event::DataChanged);
+                notify(model, orders, Event::BodyWeightChanged);
             }
-            model.loading_body_weight = false;
+            model.body_weight.finish_loading();
         }
         Msg::BodyWeightRead(Err(message)) => {
-            model
-                .errors
-                .push("Failed to read body weight: ".to_owned() + &message);
-            model.loading_body_weight = false;
+            if model.body_weight.is_available() {
+                model
+                    .errors
+                    .push("Failed to read body weight: ".to_owned() + &message);
+            }
+            model.body_weight.fail(message);
         }
         Msg::CreateBodyWeight(body_weight) => {
             let storage = model.storage.clone();
+            let request = Msg::CreateBodyWeight(body_weight.clone());
             orders.perform_cmd(async move {
-                Msg::BodyWeightCreated(storage.create_body_weight(body_weight).await)
+                Msg::BodyWeightCreated(
+                    storage
+                        .create_body_weight(body_weight)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
             });
         }
         Msg::BodyWeightCreated(Ok(body_weight)) => {
             model.body_weight.insert(body_weight.date, body_weight);
             model.avg_body_weight = domain::avg_body_weight(&model.body_weight);
-            orders.notify(Event::BodyWeightCreatedOk);
+            notify(model, orders, Event::BodyWeightCreatedOk);
         }
-        Msg::BodyWeightCreated(Err(message)) => {
-            orders.notify(Event::BodyWeightCreatedErr);
+        Msg::BodyWeightCreated(Err(failed_request)) => {
+            notify(model, orders, Event::BodyWeightCreatedErr);
             model
-                .errors
-                .push("Failed to create body weight: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to create body weight", failed_request);
         }
         Msg::ReplaceBodyWeight(body_weight) => {
             let storage = model.storage.clone();
+            let request = Msg::ReplaceBodyWeight(body_weight.clone());
             orders.perform_cmd(async move {
-                Msg::BodyWeightReplaced(storage.replace_body_weight(body_weight).await)
+                Msg::BodyWeightReplaced(
+                    storage
+                        .replace_body_weight(body_weight)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
             });
         }
         Msg::BodyWeightReplaced(Ok(body_weight)) => {
             model.body_weight.insert(body_weight.date, body_weight);
             model.avg_body_weight = domain::avg_body_weight(&model.body_weight);
-            orders.notify(Event::BodyWeightReplacedOk);
+            notify(model, orders, Event::BodyWeightReplacedOk);
         }
-        Msg::BodyWeightReplaced(Err(message)) => {
-            orders.notify(Event::BodyWeightReplacedErr);
+        Msg::BodyWeightReplaced(Err(failed_request)) => {
+            notify(model, orders, Event::BodyWeightReplacedErr);
             model
-                .errors
-                .push("Failed to replace body weight: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to replace body weight", failed_request);
         }
         Msg::DeleteBodyWeight(date) => {
             let storage = model.storage.clone();
@@ -602,19 +1379,25 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             });
         }
         Msg::BodyWeightDeleted(Ok(date)) => {
-            model.body_weight.remove(&date);
+            if let Some(body_weight) = model.body_weight.remove(&date) {
+                move_to_trash(
+                    model,
+                    orders,
+                    web_app::trash::Entry::BodyWeight(body_weight),
+                );
+            }
             model.avg_body_weight = domain::avg_body_weight(&model.body_weight);
-            orders.notify(Event::BodyWeightDeletedOk);
+            notify(model, orders, Event::BodyWeightDeletedOk);
         }
         Msg::BodyWeightDeleted(Err(message)) => {
-            orders.notify(Event::BodyWeightDeletedErr);
+            notify(model, orders, Event::BodyWeightDeletedErr);
             model
                 .errors
                 .push("Failed to delete body weight: ".to_owned() + &message);
         }
 
         Msg::ReadBodyFat => {
-            model.loading_body_fat = true;
+            model.body_fat.start_loading();
             let storage = model.storage.clone();
             orders
                 .skip()
@@ -622,49 +1405,63 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::BodyFatRead(Ok(body_fat)) => {
             let body_fat = body_fat.into_iter().map(|e| (e.date, e)).collect();
-            if model.body_fat != body_fat {
-                model.body_fat = body_fat;
-                orders.notify(Event::DataChanged);
+            if *model.body_fat != body_fat {
+                *model.body_fat = body_fat;
+                notify(model, orders, Event::BodyFatChanged);
             }
-            model.loading_body_fat = false;
+            model.body_fat.finish_loading();
         }
         Msg::BodyFatRead(Err(message)) => {
-            model
-                .errors
-                .push("Failed to read body fat: ".to_owned() + &message);
-            model.loading_body_fat = false;
+            if model.body_fat.is_available() {
+                model
+                    .errors
+                    .push("Failed to read body fat: ".to_owned() + &message);
+            }
+            model.body_fat.fail(message);
         }
         Msg::CreateBodyFat(body_fat) => {
             let storage = model.storage.clone();
+            let request = Msg::CreateBodyFat(body_fat.clone());
             orders.perform_cmd(async move {
-                Msg::BodyFatCreated(storage.create_body_fat(body_fat).await)
+                Msg::BodyFatCreated(
+                    storage
+                        .create_body_fat(body_fat)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
             });
         }
         Msg::BodyFatCreated(Ok(body_fat)) => {
             model.body_fat.insert(body_fat.date, body_fat);
-            orders.notify(Event::BodyFatCreatedOk);
+            notify(model, orders, Event::BodyFatCreatedOk);
         }
-        Msg::BodyFatCreated(Err(message)) => {
-            orders.notify(Event::BodyFatCreatedErr);
+        Msg::BodyFatCreated(Err(failed_request)) => {
+            notify(model, orders, Event::BodyFatCreatedErr);
             model
-                .errors
-                .push("Failed to create body fat: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to create body fat", failed_request);
         }
         Msg::ReplaceBodyFat(body_fat) => {
             let storage = model.storage.clone();
+            let request = Msg::ReplaceBodyFat(body_fat.clone());
             orders.perform_cmd(async move {
-                Msg::BodyFatReplaced(storage.replace_body_fat(body_fat).await)
+                Msg::BodyFatReplaced(
+                    storage
+                        .replace_body_fat(body_fat)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
             });
         }
         Msg::BodyFatReplaced(Ok(body_fat)) => {
             model.body_fat.insert(body_fat.date, body_fat);
-            orders.notify(Event::BodyFatReplacedOk);
+            notify(model, orders, Event::BodyFatReplacedOk);
         }
-        Msg::BodyFatReplaced(Err(message)) => {
-            orders.notify(Event::BodyFatReplacedErr);
+        Msg::BodyFatReplaced(Err(failed_request)) => {
+            notify(model, orders, Event::BodyFatReplacedErr);
             model
-                .errors
-                .push("Failed to replace body fat: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to replace body fat", failed_request);
         }
         Msg::DeleteBodyFat(date) => {
             let storage = model.storage.clone();
@@ -673,18 +1470,98 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             );
         }
         Msg::BodyFatDeleted(Ok(date)) => {
-            model.body_fat.remove(&date);
-            orders.notify(Event::BodyFatDeletedOk);
+            if let Some(body_fat) = model.body_fat.remove(&date) {
+                move_to_trash(model, orders, web_app::trash::Entry::BodyFat(body_fat));
+            }
+            notify(model, orders, Event::BodyFatDeletedOk);
         }
         Msg::BodyFatDeleted(Err(message)) => {
-            orders.notify(Event::BodyFatDeletedErr);
+            notify(model, orders, Event::BodyFatDeletedErr);
             model
                 .errors
                 .push("Failed to delete body fat: ".to_owned() + &message);
         }
 
+        Msg::ReadBodyMeasurement => {
+            model.body_measurement.start_loading();
+            let storage = model.storage.clone();
+            orders.skip().perform_cmd(async move {
+                Msg::BodyMeasurementRead(storage.read_body_measurement().await)
+            });
+        }
+        Msg::BodyMeasurementRead(Ok(body_measurement)) => {
+            let body_measurement = body_measurement.into_iter().map(|e| (e.date, e)).collect();
+            if *model.body_measurement != body_measurement {
+                *model.body_measurement = body_measurement;
+                notify(model, orders, Event::BodyMeasurementChanged);
+            }
+            model.body_measurement.finish_loading();
+        }
+        Msg::BodyMeasurementRead(Err(message)) => {
+            if model.body_measurement.is_available() {
+                model
+                    .errors
+                    .push("Failed to read body measurements: ".to_owned() + &message);
+            }
+            model.body_measurement.fail(message);
+        }
+        Msg::CreateBodyMeasurement(body_measurement) => {
+            let storage = model.storage.clone();
+            orders.perform_cmd(async move {
+                Msg::BodyMeasurementCreated(storage.create_body_measurement(body_measurement).await)
+            });
+        }
+        Msg::BodyMeasurementCreated(Ok(body_measurement)) => {
+            model
+                .body_measurement
+                .insert(body_measurement.date, body_measurement);
+            notify(model, orders, Event::BodyMeasurementCreatedOk);
+        }
+        Msg::BodyMeasurementCreated(Err(message)) => {
+            notify(model, orders, Event::BodyMeasurementCreatedErr);
+            model
+                .errors
+                .push("Failed to create body measurement: ".to_owned() + &message);
+        }
+        Msg::ReplaceBodyMeasurement(body_measurement) => {
+            let storage = model.storage.clone();
+            orders.perform_cmd(async move {
+                Msg::BodyMeasurementReplaced(
+                    storage.replace_body_measurement(body_measurement).await,
+                )
+            });
+        }
+        Msg::BodyMeasurementReplaced(Ok(body_measurement)) => {
+            model
+                .body_measurement
+                .insert(body_measurement.date, body_measurement);
+            notify(model, orders, Event::BodyMeasurementReplacedOk);
+        }
+        Msg::BodyMeasurementReplaced(Err(message)) => {
+            notify(model, orders, Event::BodyMeasurementReplacedErr);
+            model
+                .errors
+                .push("Failed to replace body measurement: ".to_owned() + &message);
+        }
+        Msg::DeleteBodyMeasurement(date) => {
+            let storage = model.storage.clone();
+            orders.perform_cmd(async move {
+                Msg::BodyMeasurementDeleted(storage.delete_body_measurement(date).await)
+            });
+        }
+        Msg::BodyMeasurementDeleted(Ok(date)) => {
+            model.body_measurement.remove(&date);
+            notify(model, orders, Event::BodyMeasurementDeletedOk);
+        }
+        Msg::BodyMeasurementDeleted(Err(message)) => {
+            notify(model, orders, Event::BodyMeasurementDeletedErr);
+            model
+                .errors
+                .push("Failed to delete body measurement: ".to_owned() + &message);
+        }
+
         Msg::ReadPeriod => {
-            model.loading_period = true;
+            model.period.start_loading();
             let storage = model.storage.clone();
             orders
                 .skip()
@@ -692,55 +1569,75 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::PeriodRead(Ok(period)) => {
             let period = period.into_iter().map(|e| (e.date, e)).collect();
-            if model.period != period {
-                model.period = period;
+            if *model.period != period {
+                *model.period = period;
                 model.cycles = domain::cycles(&model.period);
-                model.current_cycle = domain::current_cycle(&model.cycles);
-                orders.notify(Event::DataChanged);
+                model.current_cycle = domain::current_cycle(
+                    &model.period,
+                    &model.cycles,
+                    &web_app::clock::BrowserClock,
+                );
+                notify(model, orders, Event::PeriodChanged);
             }
-            model.loading_period = false;
+            model.period.finish_loading();
         }
         Msg::PeriodRead(Err(message)) => {
-            model
-                .errors
-                .push("Failed to read period: ".to_owned() + &message);
-            model.loading_period = false;
+            if model.period.is_available() {
+                model
+                    .errors
+                    .push("Failed to read period: ".to_owned() + &message);
+            }
+            model.period.fail(message);
         }
         Msg::CreatePeriod(period) => {
             let storage = model.storage.clone();
-            orders.perform_cmd(
-                async move { Msg::PeriodCreated(storage.create_period(period).await) },
-            );
+            let request = Msg::CreatePeriod(period.clone());
+            orders.perform_cmd(async move {
+                Msg::PeriodCreated(
+                    storage
+                        .create_period(period)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
+            });
         }
         Msg::PeriodCreated(Ok(period)) => {
             model.period.insert(period.date, period);
             model.cycles = domain::cycles(&model.period);
-            model.current_cycle = domain::current_cycle(&model.cycles);
-            orders.notify(Event::PeriodCreatedOk);
+            model.current_cycle =
+                domain::current_cycle(&model.period, &model.cycles, &web_app::clock::BrowserClock);
+            notify(model, orders, Event::PeriodCreatedOk);
         }
-        Msg::PeriodCreated(Err(message)) => {
-            orders.notify(Event::PeriodCreatedErr);
+        Msg::PeriodCreated(Err(failed_request)) => {
+            notify(model, orders, Event::PeriodCreatedErr);
             model
-                .errors
-                .push("Failed to create period: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to create period", failed_request);
         }
         Msg::ReplacePeriod(period) => {
             let storage = model.storage.clone();
-            orders.perform_cmd(
-                async move { Msg::PeriodReplaced(storage.replace_period(period).await) },
-            );
+            let request = Msg::ReplacePeriod(period.clone());
+            orders.perform_cmd(async move {
+                Msg::PeriodReplaced(
+                    storage
+                        .replace_period(period)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
+            });
         }
         Msg::PeriodReplaced(Ok(period)) => {
             model.period.insert(period.date, period);
             model.cycles = domain::cycles(&model.period);
-            model.current_cycle = domain::current_cycle(&model.cycles);
-            orders.notify(Event::PeriodReplacedOk);
+            model.current_cycle =
+                domain::current_cycle(&model.period, &model.cycles, &web_app::clock::BrowserClock);
+            notify(model, orders, Event::PeriodReplacedOk);
         }
-        Msg::PeriodReplaced(Err(message)) => {
-            orders.notify(Event::PeriodReplacedErr);
+        Msg::PeriodReplaced(Err(failed_request)) => {
+            notify(model, orders, Event::PeriodReplacedErr);
             model
-                .errors
-                .push("Failed to replace period: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to replace period", failed_request);
         }
         Msg::DeletePeriod(date) => {
             let storage = model.storage.clone();
@@ -748,20 +1645,23 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .perform_cmd(async move { Msg::PeriodDeleted(storage.delete_period(date).await) });
         }
         Msg::PeriodDeleted(Ok(date)) => {
-            model.period.remove(&date);
+            if let Some(period) = model.period.remove(&date) {
+                move_to_trash(model, orders, web_app::trash::Entry::Period(period));
+            }
             model.cycles = domain::cycles(&model.period);
-            model.current_cycle = domain::current_cycle(&model.cycles);
-            orders.notify(Event::PeriodDeletedOk);
+            model.current_cycle =
+                domain::current_cycle(&model.period, &model.cycles, &web_app::clock::BrowserClock);
+            notify(model, orders, Event::PeriodDeletedOk);
         }
         Msg::PeriodDeleted(Err(message)) => {
-            orders.notify(Event::PeriodDeletedErr);
+            notify(model, orders, Event::PeriodDeletedErr);
             model
                 .errors
                 .push("Failed to delete period: ".to_owned() + &message);
         }
 
         Msg::ReadExercises => {
-            model.loading_exercises = true;
+            model.exercises.start_loading();
             let storage = model.storage.clone();
             orders
                 .skip()
@@ -769,71 +1669,345 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::ExercisesRead(Ok(exercises)) => {
             let exercises = exercises.into_iter().map(|e| (e.id, e)).collect();
-            if model.exercises != exercises {
-                model.exercises = exercises;
-                orders.notify(Event::DataChanged);
+            let changed = changed_keys(&model.exercises, &exercises);
+            if !changed.is_empty() {
+                *model.exercises = exercises;
+                notify(model, orders, Event::ExercisesChanged(changed));
             }
-            model.loading_exercises = false;
+            model.exercises.finish_loading();
         }
         Msg::ExercisesRead(Err(message)) => {
-            model
-                .errors
-                .push("Failed to read exercises: ".to_owned() + &message);
-            model.loading_exercises = false;
+            if model.exercises.is_available() {
+                model
+                    .errors
+                    .push("Failed to read exercises: ".to_owned() + &message);
+            }
+            model.exercises.fail(message);
         }
         Msg::CreateExercise(name, muscles) => {
             let storage = model.storage.clone();
+            let request = Msg::CreateExercise(name.clone(), muscles.clone());
             orders.perform_cmd(async move {
-                Msg::ExerciseCreated(storage.create_exercise(name, muscles).await)
+                Msg::ExerciseCreated(
+                    storage
+                        .create_exercise(name, muscles)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
             });
         }
         Msg::ExerciseCreated(Ok(exercise)) => {
             model.exercises.insert(exercise.id, exercise);
-            orders.notify(Event::ExerciseCreatedOk);
+            notify(model, orders, Event::ExerciseCreatedOk);
         }
-        Msg::ExerciseCreated(Err(message)) => {
-            orders.notify(Event::ExerciseCreatedErr);
+        Msg::ExerciseCreated(Err(failed_request)) => {
+            notify(model, orders, Event::ExerciseCreatedErr);
             model
-                .errors
-                .push("Failed to create exercise: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to create exercise", failed_request);
         }
         Msg::ReplaceExercise(exercise) => {
             let storage = model.storage.clone();
+            let request = Msg::ReplaceExercise(exercise.clone());
             orders.perform_cmd(async move {
-                Msg::ExerciseReplaced(storage.replace_exercise(exercise).await)
+                Msg::ExerciseReplaced(
+                    storage
+                        .replace_exercise(exercise)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
             });
         }
         Msg::ExerciseReplaced(Ok(exercise)) => {
             model.exercises.insert(exercise.id, exercise);
-            model.training_stats =
-                domain::training_stats(&model.training_sessions.values().collect::<Vec<_>>());
-            orders.notify(Event::ExerciseReplacedOk);
+            notify(model, orders, Event::ExerciseReplacedOk);
         }
-        Msg::ExerciseReplaced(Err(message)) => {
-            orders.notify(Event::ExerciseReplacedErr);
+        Msg::ExerciseReplaced(Err(failed_request)) => {
+            notify(model, orders, Event::ExerciseReplacedErr);
             model
-                .errors
-                .push("Failed to replace exercise: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to replace exercise", failed_request);
+        }
+        Msg::ReplaceExercises(exercises) => {
+            model.exercise_batch_progress = Some((0, exercises.len()));
+            orders.send_msg(Msg::ReplaceNextExercise(exercises, vec![]));
+        }
+        Msg::ReplaceNextExercise(mut exercises, errors) => {
+            if exercises.is_empty() {
+                model.exercise_batch_progress = None;
+                if errors.is_empty() {
+                    notify(model, orders, Event::ExercisesReplacedOk);
+                } else {
+                    notify(model, orders, Event::ExercisesReplacedErr);
+                    model
+                        .errors
+                        .push("Failed to replace exercises: ".to_owned() + &errors.join(", "));
+                }
+            } else {
+                let exercise = exercises.remove(0);
+                let name = exercise.name.clone();
+                let storage = model.storage.clone();
+                orders.perform_cmd(async move {
+                    Msg::NextExerciseReplaced(
+                        name,
+                        storage.replace_exercise(exercise).await,
+                        exercises,
+                        errors,
+                    )
+                });
+            }
         }
-        Msg::DeleteExercise(id) => {
-            let storage = model.storage.clone();
-            orders.perform_cmd(
+        Msg::NextExerciseReplaced(name, result, exercises, mut errors) => {
+            match result {
+                Ok(exercise) => {
+                    model.exercises.insert(exercise.id, exercise);
+                }
+                Err(message) => {
+                    errors.push(format!("{name} ({message})"));
+                }
+            }
+            if let Some((done, _)) = &mut model.exercise_batch_progress {
+                *done += 1;
+            }
+            orders.send_msg(Msg::ReplaceNextExercise(exercises, errors));
+        }
+        Msg::DeleteExercise(id) => {
+            let storage = model.storage.clone();
+            orders.perform_cmd(
                 async move { Msg::ExerciseDeleted(storage.delete_exercise(id).await) },
             );
         }
         Msg::ExerciseDeleted(Ok(id)) => {
             model.exercises.remove(&id);
-            orders.notify(Event::ExerciseDeletedOk);
+            notify(model, orders, Event::ExerciseDeletedOk);
+            // The image of the exercise was deleted by the server
+            let image_cache = model.image_cache.clone();
+            orders.perform_cmd(async move {
+                Msg::ExerciseImageCached(image_cache.delete_exercise_image(id).await)
+            });
+            // The sets of the exercise in routines and training sessions are deleted as well
+            orders
+                .send_msg(Msg::ReadRoutines)
+                .send_msg(Msg::ReadTrainingSessions);
         }
         Msg::ExerciseDeleted(Err(message)) => {
-            orders.notify(Event::ExerciseDeletedErr);
+            notify(model, orders, Event::ExerciseDeletedErr);
             model
                 .errors
                 .push("Failed to delete exercise: ".to_owned() + &message);
         }
+        Msg::ReadExerciseImage(id) => {
+            let Some(digest) = model.exercises.get(&id).and_then(|e| e.image.clone()) else {
+                return;
+            };
+            if model.exercise_images.contains_key(&digest) {
+                return;
+            }
+            let storage = model.storage.clone();
+            let image_cache = (!model.image_cache_disabled).then(|| model.image_cache.clone());
+            orders.perform_cmd(async move {
+                if let Some(image_cache) = image_cache {
+                    if let Ok(Some(image)) =
+                        image_cache.read_exercise_image(id, digest.clone()).await
+                    {
+                        return Msg::ExerciseImageRead(id, digest, Ok(image));
+                    }
+                }
+                Msg::ExerciseImageFetched(id, digest, storage.read_exercise_image(id).await)
+            });
+        }
+        Msg::ExerciseImageFetched(id, digest, result) => {
+            if let Ok(image) = &result {
+                cache_exercise_image(model, orders, id, digest.clone(), image.clone());
+            }
+            orders.send_msg(Msg::ExerciseImageRead(id, digest, result));
+        }
+        Msg::ExerciseImageRead(id, digest, result) => match result {
+            Ok(image) => {
+                model.exercise_images.insert(digest, image);
+            }
+            Err(message) => {
+                // A missing image is not shown, but does not prevent the use of the exercise
+                error!(format!("Failed to read image of exercise {id}: {message}"));
+            }
+        },
+        Msg::ReplaceExerciseImage(id, image) => {
+            let storage = model.storage.clone();
+            orders.perform_cmd(async move {
+                let result = storage.replace_exercise_image(id, image.clone()).await;
+                Msg::ExerciseImageReplaced(result.map(|exercise| (exercise, image)))
+            });
+        }
+        Msg::ExerciseImageReplaced(Ok((exercise, image))) => {
+            if let Some(digest) = &exercise.image {
+                cache_exercise_image(model, orders, exercise.id, digest.clone(), image.clone());
+                model.exercise_images.insert(digest.clone(), image);
+            }
+            model.exercises.insert(exercise.id, exercise);
+            notify(model, orders, Event::ExerciseImageReplacedOk);
+        }
+        Msg::ExerciseImageReplaced(Err(message)) => {
+            notify(model, orders, Event::ExerciseImageReplacedErr);
+            model
+                .errors
+                .push("Failed to replace exercise image: ".to_owned() + &message);
+        }
+        Msg::DeleteExerciseImage(id) => {
+            let storage = model.storage.clone();
+            orders.perform_cmd(async move {
+                Msg::ExerciseImageDeleted(storage.delete_exercise_image(id).await)
+            });
+        }
+        Msg::ExerciseImageDeleted(Ok(id)) => {
+            if let Some(exercise) = model.exercises.get_mut(&id) {
+                exercise.image = None;
+            }
+            notify(model, orders, Event::ExerciseImageDeletedOk);
+            let image_cache = model.image_cache.clone();
+            orders.perform_cmd(async move {
+                Msg::ExerciseImageCached(image_cache.delete_exercise_image(id).await)
+            });
+        }
+        Msg::ExerciseImageDeleted(Err(message)) => {
+            notify(model, orders, Event::ExerciseImageDeletedErr);
+            model
+                .errors
+                .push("Failed to delete exercise image: ".to_owned() + &message);
+        }
+        Msg::ExerciseImageCached(result) => match result {
+            Ok(()) => {}
+            Err(message) if message == storage::QUOTA_EXCEEDED => {
+                // Images are still loaded from the server, so the app remains usable
+                if !model.image_cache_disabled {
+                    model.image_cache_disabled = true;
+                    model.errors.push(
+                        "Storage space on this device is exhausted: Images are no longer cached"
+                            .to_owned(),
+                    );
+                }
+            }
+            Err(message) => {
+                error!("Failed to update exercise image cache: ".to_owned() + &message);
+            }
+        },
+        Msg::ReadStorageUsage => {
+            let image_cache = model.image_cache.clone();
+            orders.perform_cmd(async move {
+                Msg::StorageUsageRead(StorageUsage {
+                    estimate: web_app::storage_usage::estimate().await,
+                    local_storage: web_app::storage_usage::local_storage_usage(),
+                    cached_exercise_images: image_cache.count_exercise_images().await,
+                })
+            });
+        }
+        Msg::StorageUsageRead(storage_usage) => {
+            model.storage_usage = Some(storage_usage);
+        }
+        Msg::ClearImageCache => {
+            let image_cache = model.image_cache.clone();
+            orders.perform_cmd(async move {
+                Msg::ImageCacheCleared(image_cache.clear_exercise_images().await)
+            });
+        }
+        Msg::ImageCacheCleared(result) => {
+            match result {
+                Ok(()) => model.image_cache_disabled = false,
+                Err(message) => model
+                    .errors
+                    .push("Failed to clear image cache: ".to_owned() + &message),
+            }
+            orders.send_msg(Msg::ReadStorageUsage);
+        }
+        Msg::MergeExercises(source_id, target_id) => {
+            if model.training_sessions_since.is_some() {
+                notify(model, orders, Event::ExercisesMergedErr);
+                model.errors.push(
+                    "Failed to merge exercises: Not all training sessions have been loaded"
+                        .to_owned(),
+                );
+                return;
+            }
+            let merge = domain::merge_exercises(
+                source_id,
+                target_id,
+                &model.routines.values().collect::<Vec<_>>(),
+                &model.training_sessions.values().collect::<Vec<_>>(),
+            );
+            orders.send_msg(Msg::MergeNextReference(source_id, merge, vec![]));
+        }
+        Msg::MergeNextReference(source_id, mut merge, failures) => {
+            let storage = model.storage.clone();
+            if let Some(routine) = merge.routines.pop() {
+                orders.perform_cmd(async move {
+                    let result = storage
+                        .modify_routine(routine.id, None, None, Some(routine.sections), None, None)
+                        .await;
+                    Msg::RoutineOfMergeModified(source_id, merge, failures, routine.name, result)
+                });
+            } else if let Some(training_session) = merge.training_sessions.pop() {
+                orders.perform_cmd(async move {
+                    let result = storage
+                        .modify_training_session(
+                            training_session.id,
+                            None,
+                            Some(training_session.elements),
+                            None,
+                            None,
+                        )
+                        .await;
+                    Msg::TrainingSessionOfMergeModified(
+                        source_id,
+                        merge,
+                        failures,
+                        training_session.date,
+                        result,
+                    )
+                });
+            } else if failures.is_empty() {
+                orders.perform_cmd(async move {
+                    Msg::SourceExerciseOfMergeDeleted(storage.delete_exercise(source_id).await)
+                });
+            } else {
+                notify(model, orders, Event::ExercisesMergedErr);
+                model.errors.push(format!(
+                    "Failed to merge exercises: Could not update {}",
+                    failures.join(", ")
+                ));
+            }
+        }
+        Msg::RoutineOfMergeModified(source_id, merge, mut failures, name, result) => {
+            match result {
+                Ok(routine) => {
+                    model.routines.insert(routine.id, routine);
+                }
+                Err(_) => failures.push(format!("routine \"{name}\"")),
+            }
+            orders.send_msg(Msg::MergeNextReference(source_id, merge, failures));
+        }
+        Msg::TrainingSessionOfMergeModified(source_id, merge, mut failures, date, result) => {
+            match result {
+                Ok(training_session) => {
+                    model
+                        .training_sessions
+                        .insert(training_session.id, training_session);
+                }
+                Err(_) => failures.push(format!("training session of {date}")),
+            }
+            orders.send_msg(Msg::MergeNextReference(source_id, merge, failures));
+        }
+        Msg::SourceExerciseOfMergeDeleted(Ok(id)) => {
+            model.exercises.remove(&id);
+            notify(model, orders, Event::ExercisesMergedOk);
+        }
+        Msg::SourceExerciseOfMergeDeleted(Err(message)) => {
+            notify(model, orders, Event::ExercisesMergedErr);
+            model
+                .errors
+                .push("Failed to delete merged exercise: ".to_owned() + &message);
+        }
 
         Msg::ReadRoutines => {
-            model.loading_routines = true;
+            model.routines.start_loading();
             let storage = model.storage.clone();
             orders
                 .skip()
@@ -841,54 +2015,181 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::RoutinesRead(Ok(routines)) => {
             let routines = routines.into_iter().map(|r| (r.id, r)).collect();
-            if model.routines != routines {
-                model.routines = routines;
-                orders.notify(Event::DataChanged);
+            let changed = changed_keys(&model.routines, &routines);
+            if !changed.is_empty() {
+                *model.routines = routines;
+                notify(model, orders, Event::RoutinesChanged(changed));
             }
-            model.loading_routines = false;
+            model.routines.finish_loading();
         }
         Msg::RoutinesRead(Err(message)) => {
-            model
-                .errors
-                .push("Failed to read routines: ".to_owned() + &message);
-            model.loading_routines = false;
+            if model.routines.is_available() {
+                model
+                    .errors
+                    .push("Failed to read routines: ".to_owned() + &message);
+            }
+            model.routines.fail(message);
         }
         Msg::CreateRoutine(name, template_routine_id) => {
-            let sections = if model.routines.contains_key(&template_routine_id) {
-                model.routines[&template_routine_id].sections.clone()
-            } else {
-                vec![]
+            let (sections, tags) = match model.routines.get(&template_routine_id) {
+                Some(template) => (template.sections.clone(), template.tags.clone()),
+                None => (vec![], vec![]),
             };
             let storage = model.storage.clone();
+            let request = Msg::CreateRoutine(name.clone(), template_routine_id);
             orders.perform_cmd(async move {
-                Msg::RoutineCreated(storage.create_routine(name, sections).await)
+                Msg::RoutineCreated(
+                    storage
+                        .create_routine(name, sections, tags)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
             });
         }
+        Msg::CreateRoutineFromTrainingSession(name, training_session_id) => {
+            if let Some(training_session) = model.training_sessions.get(&training_session_id) {
+                let sections = domain::routine_from_session(training_session, &name).sections;
+                let storage = model.storage.clone();
+                let request =
+                    Msg::CreateRoutineFromTrainingSession(name.clone(), training_session_id);
+                orders.perform_cmd(async move {
+                    Msg::RoutineCreated(
+                        storage
+                            .create_routine(name, sections, vec![])
+                            .await
+                            .map_err(FailedRequest::new(request)),
+                    )
+                });
+            }
+        }
         Msg::RoutineCreated(Ok(routine)) => {
             model.routines.insert(routine.id, routine);
-            orders.notify(Event::RoutineCreatedOk);
+            notify(model, orders, Event::RoutineCreatedOk);
         }
-        Msg::RoutineCreated(Err(message)) => {
-            orders.notify(Event::RoutineCreatedErr);
+        Msg::RoutineCreated(Err(failed_request)) => {
+            notify(model, orders, Event::RoutineCreatedErr);
             model
-                .errors
-                .push("Failed to create routine: ".to_owned() + &message);
+                .retry_queue
+                .push("Failed to create routine", failed_request);
         }
-        Msg::ModifyRoutine(id, name, archived, sections) => {
+        Msg::ModifyRoutine(id, name, archived, sections, schedule, tags) => {
             let storage = model.storage.clone();
+            let request = Msg::ModifyRoutine(
+                id,
+                name.clone(),
+                archived,
+                sections.clone(),
+                schedule.clone(),
+                tags.clone(),
+            );
             orders.perform_cmd(async move {
-                Msg::RoutineModified(storage.modify_routine(id, name, archived, sections).await)
+                Msg::RoutineModified(
+                    storage
+                        .modify_routine(id, name, archived, sections, schedule, tags)
+                        .await
+                        .map_err(FailedRequest::new(request)),
+                )
             });
         }
         Msg::RoutineModified(Ok(routine)) => {
             model.routines.insert(routine.id, routine);
-            orders.notify(Event::RoutineModifiedOk);
+            notify(model, orders, Event::RoutineModifiedOk);
+        }
+        Msg::RoutineModified(Err(failed_request)) => {
+            notify(model, orders, Event::RoutineModifiedErr);
+            model
+                .retry_queue
+                .push("Failed to modify routine", failed_request);
+        }
+        Msg::ImportRoutine(name, export, create_missing_exercises) => {
+            let plan = export.plan(&model.exercises);
+            orders.send_msg(Msg::ImportNextExercise(RoutineImport {
+                name,
+                export,
+                exercise_ids: plan.matched,
+                missing_exercises: if create_missing_exercises {
+                    plan.missing
+                } else {
+                    vec![]
+                },
+            }));
         }
-        Msg::RoutineModified(Err(message)) => {
-            orders.notify(Event::RoutineModifiedErr);
+        Msg::ImportNextExercise(mut import) => {
+            let storage = model.storage.clone();
+            if let Some(exercise) = import.missing_exercises.pop() {
+                orders.perform_cmd(async move {
+                    let result = storage
+                        .create_exercise(exercise.name.trim().to_string(), exercise.muscles)
+                        .await;
+                    Msg::ExerciseOfImportCreated(import, exercise.name, result)
+                });
+            } else {
+                let sections = import.export.sections(&import.exercise_ids);
+                orders.perform_cmd(async move {
+                    Msg::RoutineImported(
+                        storage
+                            .create_routine(import.name, sections, import.export.tags)
+                            .await,
+                    )
+                });
+            }
+        }
+        Msg::ExerciseOfImportCreated(mut import, exported_name, result) => match result {
+            Ok(exercise) => {
+                import.exercise_ids.insert(exported_name, exercise.id);
+                model.exercises.insert(exercise.id, exercise);
+                orders.send_msg(Msg::ImportNextExercise(import));
+            }
+            Err(message) => {
+                notify(model, orders, Event::RoutineImportedErr);
+                model.errors.push(format!(
+                    "Failed to import routine: Could not create exercise \"{}\": {message}",
+                    exported_name.trim()
+                ));
+            }
+        },
+        Msg::RoutineImported(Ok(routine)) => {
+            let id = routine.id;
+            model.routines.insert(id, routine);
+            notify(model, orders, Event::RoutineImportedOk(id));
+        }
+        Msg::RoutineImported(Err(message)) => {
+            notify(model, orders, Event::RoutineImportedErr);
             model
                 .errors
-                .push("Failed to modify routine: ".to_owned() + &message);
+                .push("Failed to import routine: ".to_owned() + &message);
+        }
+        Msg::DeleteTag(name) => {
+            let routines = domain::remove_tag(model.routines.values(), &name);
+            orders.send_msg(Msg::RemoveTagFromNextRoutine(routines, vec![]));
+        }
+        Msg::RemoveTagFromNextRoutine(mut routines, failures) => {
+            if let Some(routine) = routines.pop() {
+                let storage = model.storage.clone();
+                orders.perform_cmd(async move {
+                    let result = storage
+                        .modify_routine(routine.id, None, None, None, None, Some(routine.tags))
+                        .await;
+                    Msg::RoutineOfTagDeletionModified(routines, failures, routine.name, result)
+                });
+            } else if failures.is_empty() {
+                notify(model, orders, Event::TagDeletedOk);
+            } else {
+                notify(model, orders, Event::TagDeletedErr);
+                model.errors.push(format!(
+                    "Failed to delete tag: Could not update {}",
+                    failures.join(", ")
+                ));
+            }
+        }
+        Msg::RoutineOfTagDeletionModified(routines, mut failures, name, result) => {
+            match result {
+                Ok(routine) => {
+                    model.routines.insert(routine.id, routine);
+                }
+                Err(_) => failures.push(format!("routine \"{name}\"")),
+            }
+            orders.send_msg(Msg::RemoveTagFromNextRoutine(routines, failures));
         }
         Msg::DeleteRoutine(id) => {
             let storage = model.storage.clone();
@@ -897,83 +2198,246 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::RoutineDeleted(Ok(id)) => {
             model.routines.remove(&id);
-            orders.notify(Event::RoutineDeletedOk);
+            notify(model, orders, Event::RoutineDeletedOk);
         }
         Msg::RoutineDeleted(Err(message)) => {
-            orders.notify(Event::RoutineDeletedErr);
+            notify(model, orders, Event::RoutineDeletedErr);
             model
                 .errors
                 .push("Failed to delete routine: ".to_owned() + &message);
         }
 
         Msg::ReadTrainingSessions => {
-            model.loading_training_sessions = true;
+            model.training_sessions.start_loading();
+            model.training_sessions_since = match model.settings.initial_interval() {
+                Some(domain::DefaultInterval::All) => None,
+                Some(interval) => model.training_sessions_since.map(|since| {
+                    since.min(web_app::clock::today() - Duration::days(interval as i64))
+                }),
+                None => model.training_sessions_since,
+            };
+            let first = model.training_sessions_since;
             let storage = model.storage.clone();
             orders.skip().perform_cmd(async move {
-                Msg::TrainingSessionsRead(storage.read_training_sessions().await)
+                Msg::TrainingSessionsRead(first, storage.read_training_sessions(first, None).await)
             });
         }
-        Msg::TrainingSessionsRead(Ok(training_sessions)) => {
-            let training_sessions = training_sessions.into_iter().map(|t| (t.id, t)).collect();
-            if model.training_sessions != training_sessions {
-                model.training_sessions = training_sessions;
-                model.training_stats =
-                    domain::training_stats(&model.training_sessions.values().collect::<Vec<_>>());
-                orders.notify(Event::DataChanged);
+        Msg::TrainingSessionsRead(first, Ok(training_sessions)) => {
+            let mut training_sessions: BTreeMap<u32, domain::TrainingSession> =
+                training_sessions.into_iter().map(|t| (t.id, t)).collect();
+            if let Some(first) = first {
+                training_sessions.extend(
+                    model
+                        .training_sessions
+                        .values()
+                        .filter(|t| t.date < first)
+                        .map(|t| (t.id, t.clone())),
+                );
+            }
+            for id in model.modified_training_sessions.keys() {
+                if let (Some(local), Some(remote)) = (
+                    model.training_sessions.get(id),
+                    training_sessions.get_mut(id),
+                ) {
+                    *remote = local.clone();
+                }
+            }
+            let changed = changed_keys(&model.training_sessions, &training_sessions);
+            if !changed.is_empty() {
+                let changed_dates = changed
+                    .iter()
+                    .flat_map(|id| [model.training_sessions.get(id), training_sessions.get(id)])
+                    .flatten()
+                    .map(|t| t.date)
+                    .collect::<Vec<_>>();
+                *model.training_sessions = training_sessions;
+                update_training_stats(model, &changed_dates);
+                notify(model, orders, Event::TrainingSessionsChanged(changed));
             }
-            model.loading_training_sessions = false;
+            model.training_sessions.finish_loading();
         }
-        Msg::TrainingSessionsRead(Err(message)) => {
-            model
-                .errors
-                .push("Failed to read training sessions: ".to_owned() + &message);
-            model.loading_training_sessions = false;
+        Msg::TrainingSessionsRead(_, Err(message)) => {
+            if model.training_sessions.is_available() {
+                model
+                    .errors
+                    .push("Failed to read training sessions: ".to_owned() + &message);
+            }
+            model.training_sessions.fail(message);
+        }
+        Msg::ReadOlderTrainingSessions(first) => {
+            if let Some(since) = model.training_sessions_since {
+                if !first.is_some_and(|first| first >= since) {
+                    model.training_sessions.start_loading();
+                    let last = since.pred_opt();
+                    let storage = model.storage.clone();
+                    orders.skip().perform_cmd(async move {
+                        Msg::OlderTrainingSessionsRead(
+                            first,
+                            storage.read_training_sessions(first, last).await,
+                        )
+                    });
+                }
+            }
+        }
+        Msg::OlderTrainingSessionsRead(first, Ok(training_sessions)) => {
+            model.training_sessions_since =
+                first.and_then(|first| model.training_sessions_since.map(|since| since.min(first)));
+            let mut added = vec![];
+            let mut added_dates = vec![];
+            for training_session in training_sessions {
+                if let Entry::Vacant(entry) = model.training_sessions.entry(training_session.id) {
+                    added.push(training_session.id);
+                    added_dates.push(training_session.date);
+                    entry.insert(training_session);
+                }
+            }
+            if !added.is_empty() {
+                update_training_stats(model, &added_dates);
+                notify(model, orders, Event::TrainingSessionsChanged(added));
+            }
+            model.training_sessions.finish_loading();
+        }
+        Msg::OlderTrainingSessionsRead(_, Err(message)) => {
+            if model.training_sessions.is_available() {
+                model
+                    .errors
+                    .push("Failed to read training sessions: ".to_owned() + &message);
+            }
+            model.training_sessions.fail(message);
         }
         Msg::CreateTrainingSession(routine_id, date, notes, elements) => {
             let storage = model.storage.clone();
+            let request =
+                Msg::CreateTrainingSession(routine_id, date, notes.clone(), elements.clone());
             orders.perform_cmd(async move {
                 Msg::TrainingSessionCreated(
                     storage
                         .create_training_session(routine_id, date, notes, elements)
-                        .await,
+                        .await
+                        .map_err(FailedRequest::new(request)),
                 )
             });
         }
+        Msg::RepeatTrainingSession(id) => {
+            if let Some(training_session) = model.training_sessions.get(&id) {
+                let repetition =
+                    domain::repeat_training_session(training_session, web_app::clock::today());
+                let storage = model.storage.clone();
+                let request = Msg::RepeatTrainingSession(id);
+                orders.perform_cmd(async move {
+                    Msg::TrainingSessionCreated(
+                        storage
+                            .create_training_session(
+                                repetition.routine_id,
+                                repetition.date,
+                                String::new(),
+                                repetition.elements,
+                            )
+                            .await
+                            .map_err(FailedRequest::new(request)),
+                    )
+                });
+            }
+        }
         Msg::TrainingSessionCreated(Ok(training_session)) => {
+            let date = training_session.date;
             model
                 .training_sessions
                 .insert(training_session.id, training_session);
-            model.training_stats =
-                domain::training_stats(&model.training_sessions.values().collect::<Vec<_>>());
-            orders.notify(Event::TrainingSessionCreatedOk);
+            update_training_stats(model, &[date]);
+            notify(model, orders, Event::TrainingSessionCreatedOk);
         }
-        Msg::TrainingSessionCreated(Err(message)) => {
-            orders.notify(Event::TrainingSessionCreatedErr);
+        Msg::TrainingSessionCreated(Err(failed_request)) => {
+            notify(model, orders, Event::TrainingSessionCreatedErr);
             model
-                .errors
-                .push("Failed to create training session: ".to_owned() + &message);
-        }
-        Msg::ModifyTrainingSession(id, notes, elements) => {
+                .retry_queue
+                .push("Failed to create training session", failed_request);
+        }
+        Msg::ModifyTrainingSession(id, notes, elements, start_time, end_time) => {
+            if let Some(training_session) = model.training_sessions.get_mut(&id) {
+                model
+                    .modified_training_sessions
+                    .entry(id)
+                    .or_insert_with(|| ModifiedTrainingSession {
+                        pending_requests: 0,
+                        confirmed: training_session.clone(),
+                    })
+                    .pending_requests += 1;
+                if let Some(notes) = &notes {
+                    training_session.notes = Some(notes.clone());
+                }
+                if let Some(elements) = &elements {
+                    training_session.elements = elements.clone();
+                }
+                if start_time.is_some() {
+                    training_session.start_time = start_time;
+                }
+                if end_time.is_some() {
+                    training_session.end_time = end_time;
+                }
+                let date = training_session.date;
+                update_training_stats(model, &[date]);
+                notify(model, orders, Event::TrainingSessionsChanged(vec![id]));
+            }
             let storage = model.storage.clone();
+            let request = Msg::ModifyTrainingSession(
+                id,
+                notes.clone(),
+                elements.clone(),
+                start_time,
+                end_time,
+            );
             orders.perform_cmd(async move {
                 Msg::TrainingSessionModified(
-                    storage.modify_training_session(id, notes, elements).await,
+                    id,
+                    storage
+                        .modify_training_session(id, notes, elements, start_time, end_time)
+                        .await
+                        .map_err(FailedRequest::new(request)),
                 )
             });
         }
-        Msg::TrainingSessionModified(Ok(training_session)) => {
-            model
-                .training_sessions
-                .insert(training_session.id, training_session);
-            model.training_stats =
-                domain::training_stats(&model.training_sessions.values().collect::<Vec<_>>());
-            orders.notify(Event::TrainingSessionModifiedOk);
+        Msg::TrainingSessionModified(id, Ok(training_session)) => {
+            match model.modified_training_sessions.get_mut(&id) {
+                Some(modified) if modified.pending_requests > 1 => {
+                    modified.pending_requests -= 1;
+                    modified.confirmed = training_session;
+                }
+                _ => {
+                    model.modified_training_sessions.remove(&id);
+                    let mut changed_dates = vec![training_session.date];
+                    if let Some(previous) = model
+                        .training_sessions
+                        .insert(training_session.id, training_session)
+                    {
+                        changed_dates.push(previous.date);
+                    }
+                    update_training_stats(model, &changed_dates);
+                    notify(model, orders, Event::TrainingSessionModifiedOk);
+                }
+            }
         }
-        Msg::TrainingSessionModified(Err(message)) => {
-            orders.notify(Event::TrainingSessionModifiedErr);
-            model
-                .errors
-                .push("Failed to modify training session: ".to_owned() + &message);
+        Msg::TrainingSessionModified(id, Err(failed_request)) => {
+            match model.modified_training_sessions.get_mut(&id) {
+                Some(modified) if modified.pending_requests > 1 => {
+                    modified.pending_requests -= 1;
+                }
+                _ => {
+                    if let Some(modified) = model.modified_training_sessions.remove(&id) {
+                        let mut changed_dates = vec![modified.confirmed.date];
+                        if let Some(previous) =
+                            model.training_sessions.insert(id, modified.confirmed)
+                        {
+                            changed_dates.push(previous.date);
+                        }
+                        update_training_stats(model, &changed_dates);
+                    }
+                    notify(model, orders, Event::TrainingSessionModifiedErr);
+                    model
+                        .retry_queue
+                        .push("Failed to modify training session", failed_request);
+                }
+            }
         }
         Msg::DeleteTrainingSession(id) => {
             let storage = model.storage.clone();
@@ -982,17 +2446,78 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             });
         }
         Msg::TrainingSessionDeleted(Ok(id)) => {
-            model.training_sessions.remove(&id);
-            model.training_stats =
-                domain::training_stats(&model.training_sessions.values().collect::<Vec<_>>());
-            orders.notify(Event::TrainingSessionDeletedOk);
+            if let Some(training_session) = model.training_sessions.remove(&id) {
+                update_training_stats(model, &[training_session.date]);
+            }
+            notify(model, orders, Event::TrainingSessionDeletedOk);
         }
         Msg::TrainingSessionDeleted(Err(message)) => {
-            orders.notify(Event::TrainingSessionDeletedErr);
+            notify(model, orders, Event::TrainingSessionDeletedErr);
             model
                 .errors
                 .push("Failed to delete training session: ".to_owned() + &message);
         }
+        Msg::DeleteTrainingSessions(ids) => {
+            let ongoing_id = model
+                .ongoing_training_session
+                .as_ref()
+                .map(|ongoing_training_session| ongoing_training_session.training_session_id);
+            let ids = ids
+                .into_iter()
+                .filter(|id| Some(*id) != ongoing_id)
+                .collect::<Vec<_>>();
+            model.training_session_batch_progress = Some((0, ids.len()));
+            orders.send_msg(Msg::DeleteNextTrainingSession(ids, vec![], vec![]));
+        }
+        Msg::DeleteNextTrainingSession(mut ids, deleted, errors) => {
+            if ids.is_empty() {
+                model.training_session_batch_progress = None;
+                let dates = deleted
+                    .iter()
+                    .filter_map(|id| model.training_sessions.remove(id))
+                    .map(|training_session| training_session.date)
+                    .collect::<Vec<_>>();
+                if !dates.is_empty() {
+                    update_training_stats(model, &dates);
+                }
+                if errors.is_empty() {
+                    notify(model, orders, Event::TrainingSessionsDeletedOk);
+                } else {
+                    notify(model, orders, Event::TrainingSessionsDeletedErr);
+                    model.errors.push(
+                        "Failed to delete training sessions: ".to_owned() + &errors.join(", "),
+                    );
+                }
+            } else {
+                let id = ids.remove(0);
+                let date = model.training_sessions.get(&id).map(|t| t.date);
+                let storage = model.storage.clone();
+                orders.perform_cmd(async move {
+                    Msg::NextTrainingSessionDeleted(
+                        date,
+                        storage.delete_training_session(id).await,
+                        ids,
+                        deleted,
+                        errors,
+                    )
+                });
+            }
+        }
+        Msg::NextTrainingSessionDeleted(date, result, ids, mut deleted, mut errors) => {
+            match result {
+                Ok(id) => {
+                    deleted.push(id);
+                }
+                Err(message) => {
+                    let date = date.map_or_else(|| String::from("unknown date"), |d| d.to_string());
+                    errors.push(format!("{date} ({message})"));
+                }
+            }
+            if let Some((done, _)) = &mut model.training_session_batch_progress {
+                *done += 1;
+            }
+            orders.send_msg(Msg::DeleteNextTrainingSession(ids, deleted, errors));
+        }
 
         Msg::SetBeepVolume(value) => {
             model.settings.beep_volume = value;
@@ -1001,10 +2526,14 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .notify(Event::BeepVolumeChanged);
         }
         Msg::SetTheme(theme) => {
-            apply_theme(&theme);
+            apply_theme(theme);
             model.settings.theme = theme;
             orders.send_msg(Msg::WriteSettings);
         }
+        Msg::SetContrast(contrast) => {
+            model.settings.contrast = contrast;
+            orders.send_msg(Msg::WriteSettings);
+        }
         Msg::SetAutomaticMetronome(value) => {
             model.settings.automatic_metronome = value;
             orders.send_msg(Msg::WriteSettings);
@@ -1021,19 +2550,141 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             model.settings.show_tut = value;
             orders.send_msg(Msg::WriteSettings);
         }
+        Msg::SetInterval(value) => {
+            model.settings.interval = Some(value);
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetDefaultInterval(value) => {
+            model.settings.set_default_interval(value);
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetRefreshInterval(value) => {
+            model.settings.refresh_interval = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetShowMenstrualCycle(value) => {
+            if let Some(user) = &model.session {
+                model.settings.show_menstrual_cycle.insert(user.id, value);
+                orders.send_msg(Msg::WriteSettings);
+            }
+        }
+        Msg::SetBodyWeightGoal(value) => {
+            if let Some(user) = &model.session {
+                if let Some(goal) = value {
+                    model.settings.body_weight_goal.insert(user.id, goal);
+                } else {
+                    model.settings.body_weight_goal.remove(&user.id);
+                }
+                orders.send_msg(Msg::WriteSettings);
+            }
+        }
+        Msg::SetWeightIncrement(value) => {
+            model.settings.weight_increment = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetTargetTolerance(value) => {
+            model.settings.target_tolerance = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetTrendWindow(value) => {
+            model.settings.trend_window = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::ToggleDataTables(page) => {
+            if !model.settings.data_tables.remove(&page) {
+                model.settings.data_tables.insert(page);
+            }
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetDecimalSeparator(value) => {
+            model.settings.decimal_separator = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::AddLocation(location) => {
+            model.settings.locations.push(location);
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::ReplaceLocation(index, location) => {
+            if let Some(previous) = model.settings.locations.get_mut(index) {
+                if model.settings.active_location.as_ref() == Some(&previous.name) {
+                    model.settings.active_location = Some(location.name.clone());
+                }
+                *previous = location;
+                orders.send_msg(Msg::WriteSettings);
+            }
+        }
+        Msg::DeleteLocation(index) => {
+            if index < model.settings.locations.len() {
+                let location = model.settings.locations.remove(index);
+                if model.settings.active_location.as_ref() == Some(&location.name) {
+                    model.settings.active_location = None;
+                }
+                orders.send_msg(Msg::WriteSettings);
+            }
+        }
+        Msg::SetActiveLocation(name) => {
+            model.settings.active_location = name;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetTimerPreset(index, value) => {
+            if let Some(preset) = model.settings.timer_presets.get_mut(index) {
+                *preset = value;
+                orders.send_msg(Msg::WriteSettings);
+            }
+        }
+        Msg::SetMetronomeInterval(value) => {
+            model.settings.metronome_interval = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetMetronomeStressedBeat(value) => {
+            model.settings.metronome_stressed_beat = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetVibration(value) => {
+            model.settings.vibration = value;
+            orders
+                .send_msg(Msg::WriteSettings)
+                .notify(Event::VibrationChanged);
+        }
+        Msg::SetAnnounceExercises(value) => {
+            model.settings.announce_exercises = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetKeepScreenAwake(value) => {
+            model.settings.keep_screen_awake = value;
+            orders
+                .send_msg(Msg::WriteSettings)
+                .notify(Event::KeepScreenAwakeChanged);
+        }
+        Msg::SetAdHocPreviousValues(value) => {
+            model.settings.ad_hoc_previous_values = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetSoundProfile(value) => {
+            model.settings.sound_profile = value;
+            orders
+                .send_msg(Msg::WriteSettings)
+                .notify(Event::SoundProfileChanged);
+        }
+        Msg::SetTrainingView(value) => {
+            model.settings.training_view = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
+        Msg::SetWeeklySessionTarget(value) => {
+            model.settings.weekly_session_target = value;
+            orders.send_msg(Msg::WriteSettings);
+        }
 
-        Msg::StartTrainingSession(training_session_id) => {
-            model.ongoing_training_session =
-                Some(web_app::OngoingTrainingSession::new(training_session_id));
+        Msg::UpdateTrainingSession(ongoing_training_session) => {
+            model.ongoing_training_session = Some(ongoing_training_session);
             orders.send_msg(Msg::WriteOngoingTrainingSession);
         }
-        Msg::UpdateTrainingSession(section_idx, timer_state) => {
+        Msg::ApplyNotificationAction(selected) => {
             if let Some(ongoing_training_session) = &mut model.ongoing_training_session {
-                ongoing_training_session.element_idx = section_idx;
-                ongoing_training_session.element_start_time = Utc::now();
-                ongoing_training_session.timer_state = timer_state;
+                if ongoing_training_session.apply_notification_action(selected, Utc::now()) {
+                    orders.send_msg(Msg::WriteOngoingTrainingSession);
+                }
             }
-            orders.send_msg(Msg::WriteOngoingTrainingSession);
         }
         Msg::EndTrainingSession => {
             model.ongoing_training_session = None;
@@ -1047,8 +2698,9 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .perform_cmd(async move { Msg::SettingsRead(storage.read_settings().await) });
         }
         Msg::SettingsRead(Ok(settings)) => {
-            apply_theme(&settings.theme);
+            apply_theme(settings.theme);
             model.settings = settings;
+            migrate_body_height(model, orders);
         }
         Msg::SettingsRead(Err(message)) => {
             debug!("Failed to read settings: ".to_owned() + &message);
@@ -1074,8 +2726,12 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::OngoingTrainingSessionRead(Ok(ongoing_training_session)) => {
             model.ongoing_training_session = ongoing_training_session;
+            if let Some(action) = model.pending_notification_action.take() {
+                orders.send_msg(Msg::ApplyNotificationAction(action));
+            }
         }
         Msg::OngoingTrainingSessionRead(Err(message)) => {
+            model.pending_notification_action = None;
             debug!("Failed to read ongoing training session: ".to_owned() + &message);
         }
         Msg::WriteOngoingTrainingSession => {
@@ -1094,10 +2750,125 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 error!("Failed to write ongoing training session: ".to_owned() + &message);
             }
         }
+
+        Msg::ReadTrainingSessionDraft(id) => {
+            let storage = model.ui_storage.clone();
+            orders.skip().perform_cmd(async move {
+                Msg::TrainingSessionDraftRead(storage.read_training_session_draft(id).await)
+            });
+        }
+        Msg::TrainingSessionDraftRead(Ok(draft)) => {
+            if let Some(draft) = draft {
+                orders.notify(Event::TrainingSessionDraftRead(draft));
+            }
+        }
+        Msg::TrainingSessionDraftRead(Err(message)) => {
+            debug!("Failed to read training session draft: ".to_owned() + &message);
+        }
+        Msg::WriteTrainingSessionDraft(id, draft) => {
+            let storage = model.ui_storage.clone();
+            orders.skip().perform_cmd(async move {
+                Msg::TrainingSessionDraftWritten(
+                    storage.write_training_session_draft(id, draft).await,
+                )
+            });
+        }
+        Msg::TrainingSessionDraftWritten(result) => {
+            if let Err(message) = result {
+                error!("Failed to write training session draft: ".to_owned() + &message);
+            }
+        }
+
+        Msg::ReadTrash => {
+            if let Some(session) = &model.session {
+                let user_id = session.id;
+                let storage = model.ui_storage.clone();
+                orders
+                    .skip()
+                    .perform_cmd(async move { Msg::TrashRead(storage.read_trash(user_id).await) });
+            }
+        }
+        Msg::TrashRead(Ok(trash)) => {
+            model.trash = trash;
+            if model.trash.purge(web_app::clock::today()) {
+                orders.send_msg(Msg::WriteTrash);
+            }
+        }
+        Msg::TrashRead(Err(message)) => {
+            error!("Failed to read trash: ".to_owned() + &message);
+        }
+        Msg::WriteTrash => {
+            if let Some(session) = &model.session {
+                let user_id = session.id;
+                let storage = model.ui_storage.clone();
+                let trash = model.trash.clone();
+                orders.skip().perform_cmd(async move {
+                    Msg::TrashWritten(storage.write_trash(user_id, trash).await)
+                });
+            }
+        }
+        Msg::TrashWritten(result) => {
+            if let Err(message) = result {
+                error!("Failed to write trash: ".to_owned() + &message);
+            }
+        }
+        Msg::RestoreFromTrash(entry) => {
+            model.trash.remove(&entry);
+            orders.send_msg(Msg::WriteTrash);
+            // A failed request is kept in the retry queue, so the entry is not lost
+            orders.send_msg(match entry {
+                web_app::trash::Entry::BodyWeight(body_weight) => {
+                    if model.body_weight.contains_key(&body_weight.date) {
+                        Msg::ReplaceBodyWeight(body_weight)
+                    } else {
+                        Msg::CreateBodyWeight(body_weight)
+                    }
+                }
+                web_app::trash::Entry::BodyFat(body_fat) => {
+                    if model.body_fat.contains_key(&body_fat.date) {
+                        Msg::ReplaceBodyFat(body_fat)
+                    } else {
+                        Msg::CreateBodyFat(body_fat)
+                    }
+                }
+                web_app::trash::Entry::Period(period) => {
+                    if model.period.contains_key(&period.date) {
+                        Msg::ReplacePeriod(period)
+                    } else {
+                        Msg::CreatePeriod(period)
+                    }
+                }
+            });
+        }
     }
 }
 
-fn apply_theme(theme: &web_app::Theme) {
+fn move_to_trash(model: &mut Model, orders: &mut impl Orders<Msg>, entry: web_app::trash::Entry) {
+    model.trash.add(entry, web_app::clock::today());
+    orders.send_msg(Msg::WriteTrash);
+}
+
+/// Transfer the body height stored in the local settings to the current user, unless the user
+/// already has a body height. The local value is removed once the user has a body height.
+fn migrate_body_height(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    let Some(height) = model.settings.body_height else {
+        return;
+    };
+    let Some(user) = model.session_user() else {
+        return;
+    };
+    if user.height.is_none() {
+        orders.send_msg(Msg::ReplaceUser(domain::User {
+            height: Some(height),
+            ..user.clone()
+        }));
+    } else {
+        model.settings.body_height = None;
+        orders.send_msg(Msg::WriteSettings);
+    }
+}
+
+fn apply_theme(theme: web_app::Theme) {
     if let Some(window) = web_sys::window() {
         if let Some(document) = window.document() {
             if let Some(html_element) = document.document_element() {
@@ -1118,33 +2889,130 @@ fn apply_theme(theme: &web_app::Theme) {
 pub fn view(model: &Model) -> Vec<Node<Msg>> {
     nodes![
         common::view_error_dialog(&model.errors, &ev(Ev::Click, |_| Msg::RemoveError)),
-        view_app_update_dialog(model),
+        view_retry_notification(model),
+        view_read_only_notification(model),
+        view_refresh_indicator(model),
+        view_app_update_notification(model),
     ]
 }
 
-fn view_app_update_dialog(model: &Model) -> Option<Node<Msg>> {
-    IF![model.app_update_available => common::view_dialog(
-        "info",
-        "Update",
-        nodes![
+/// Full-page notice about incompatible versions of app and server.
+pub fn view_version_notice(model: &Model) -> Node<Msg> {
+    let app_outdated = model.version_compatibility == web_app::version::Compatibility::AppOutdated;
+    div![
+        C!["message"],
+        C!["is-warning"],
+        C!["mx-2"],
+        div![
+            C!["message-body"],
+            div![
+                C!["title"],
+                C!["is-size-4"],
+                C!["has-text-warning-dark"],
+                "Incompatible versions"
+            ],
             div![
                 C!["block"],
-                p!["An app update is available."],
-                p![C!["my-3"], common::view_versions(&model.version)],
-                p!["Update now to prevent unexpected errors due to incompatibilities with the server."]
+                if app_outdated {
+                    p!["The app is outdated and must be updated to work with the server."]
+                } else {
+                    p![
+                        "The server is outdated and must be updated to work with this app. ",
+                        "Please contact the administrator of the server."
+                    ]
+                },
+                div![C!["my-3"], common::view_versions(&model.version)],
+                p!["Until then, the data can be viewed, but changes cannot be saved."],
             ],
             div![
                 C!["field"],
                 C!["is-grouped"],
                 C!["is-grouped-centered"],
+                IF![app_outdated => div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-warning"],
+                        ev(Ev::Click, |_| Msg::UpdateApp),
+                        "Update"
+                    ]
+                ]],
                 div![
                     C!["control"],
-                    button![C!["button"], C!["is-info"], &ev(Ev::Click, |_| Msg::UpdateApp), "Update"]
+                    button![
+                        C!["button"],
+                        C!["is-warning"],
+                        C!["is-outlined"],
+                        ev(Ev::Click, |_| Msg::DismissVersionNotice),
+                        "Continue read-only"
+                    ]
                 ],
             ],
+        ]
+    ]
+}
+
+fn view_read_only_notification(model: &Model) -> Option<Node<Msg>> {
+    IF![model.version_notice_dismissed && !model.version_compatibility.is_compatible() => div![
+        C!["notification"],
+        C!["is-warning"],
+        C!["is-update-notification"],
+        p!["Read-only mode due to incompatible versions of app and server"],
+    ]]
+}
+
+fn view_retry_notification(model: &Model) -> Option<Node<Msg>> {
+    let message = model.retry_queue.last_message()?;
+    Some(div![
+        C!["notification"],
+        C!["is-danger"],
+        C!["is-retry-notification"],
+        button![C!["delete"], ev(Ev::Click, |_| Msg::DiscardFailedRequest)],
+        p![C!["mb-3"], message],
+        div![
+            C!["buttons"],
+            button![
+                C!["button"],
+                C!["is-small"],
+                ev(Ev::Click, |_| Msg::RetryFailedRequest),
+                "Retry"
+            ],
+            button![
+                C!["button"],
+                C!["is-small"],
+                C!["is-danger"],
+                C!["is-inverted"],
+                ev(Ev::Click, |_| Msg::DiscardFailedRequest),
+                "Discard"
+            ],
+        ],
+    ])
+}
+
+fn view_refresh_indicator(model: &Model) -> Option<Node<Msg>> {
+    IF![model.is_refreshing() => div![
+        C!["is-refresh-indicator"],
+        C!["has-text-grey"],
+        i![C!["fas fa-spinner fa-pulse"]]
+    ]]
+}
+
+fn view_app_update_notification(model: &Model) -> Option<Node<Msg>> {
+    let version = model.app_update_ready.as_deref()?;
+    let dismissed = model.settings.dismissed_app_update.as_deref() == Some(version);
+    IF![!dismissed && model.version_compatibility.is_compatible() => div![
+        C!["notification"],
+        C!["is-info"],
+        C!["is-update-notification"],
+        button![C!["delete"], ev(Ev::Click, |_| Msg::DismissAppUpdate)],
+        p![C!["mb-3"], "A new version is available."],
+        button![
+            C!["button"],
+            C!["is-small"],
+            ev(Ev::Click, |_| Msg::UpdateApp),
+            "Reload"
         ],
-        &ev(Ev::Click, |_| Msg::CancelAppUpdate),
-    )]
+    ]]
 }
 
 // ------ ------
@@ -1244,6 +3112,229 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_changed_keys() {
+        let old = BTreeMap::from([(1, "a"), (2, "b"), (3, "c"), (5, "e")]);
+        let new = BTreeMap::from([(1, "a"), (2, "x"), (4, "d"), (5, "e")]);
+        assert_eq!(changed_keys(&old, &new), vec![2, 3, 4]);
+        assert_eq!(changed_keys(&new, &old), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_changed_keys_unchanged() {
+        let map = BTreeMap::from([(1, "a"), (2, "b")]);
+        assert_eq!(changed_keys(&map, &map.clone()), Vec::<u32>::new());
+        assert_eq!(
+            changed_keys::<u32, &str>(&BTreeMap::new(), &BTreeMap::new()),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_change_counters_record() {
+        let mut changes = ChangeCounters::default();
+
+        changes.record(&Event::BodyWeightChanged);
+        changes.record(&Event::BodyWeightCreatedOk);
+        changes.record(&Event::BodyWeightCreatedErr);
+        changes.record(&Event::ExercisesChanged(vec![1, 2]));
+        changes.record(&Event::RoutinesChanged(vec![3]));
+        changes.record(&Event::TrainingSessionsChanged(vec![]));
+        changes.record(&Event::TrainingSessionModifiedErr);
+        changes.record(&Event::PinRejected);
+        changes.record(&Event::BeepVolumeChanged);
+
+        assert_eq!(
+            changes,
+            ChangeCounters {
+                body_weight: 2,
+                exercises: 1,
+                routines: 1,
+                training_sessions: 2,
+                ..ChangeCounters::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_counters_record_merge() {
+        let mut changes = ChangeCounters::default();
+
+        changes.record(&Event::ExercisesMergedOk);
+        changes.record(&Event::ExercisesMergedErr);
+
+        assert_eq!(
+            changes,
+            ChangeCounters {
+                exercises: 2,
+                routines: 2,
+                training_sessions: 2,
+                ..ChangeCounters::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_counters_record_tag_deletion() {
+        let mut changes = ChangeCounters::default();
+
+        changes.record(&Event::TagDeletedOk);
+        changes.record(&Event::TagDeletedErr);
+
+        assert_eq!(
+            changes,
+            ChangeCounters {
+                routines: 2,
+                ..ChangeCounters::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_counters_record_import() {
+        let mut changes = ChangeCounters::default();
+
+        changes.record(&Event::RoutineImportedOk(1));
+        changes.record(&Event::RoutineImportedErr);
+
+        assert_eq!(
+            changes,
+            ChangeCounters {
+                exercises: 2,
+                routines: 2,
+                ..ChangeCounters::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_counters_record_session_change() {
+        let mut changes = ChangeCounters {
+            users: 1,
+            period: u32::MAX,
+            ..ChangeCounters::default()
+        };
+
+        changes.record_session_change();
+
+        assert_eq!(
+            changes,
+            ChangeCounters {
+                users: 1,
+                body_weight: 1,
+                body_fat: 1,
+                body_measurement: 1,
+                period: 0,
+                exercises: 1,
+                routines: 1,
+                training_sessions: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_retry_queue_retry() {
+        let mut retry_queue = RetryQueue::default();
+        let body_weight = body_weight(80.0);
+
+        retry_queue.push(
+            "Failed to create body weight",
+            FailedRequest::new(Msg::CreateBodyWeight(body_weight.clone()))(String::from(
+                "No connection",
+            )),
+        );
+
+        assert_eq!(
+            retry_queue.last_message(),
+            Some("Failed to create body weight: No connection")
+        );
+        assert!(matches!(
+            retry_queue.retry(),
+            Some(Msg::CreateBodyWeight(request)) if request == body_weight
+        ));
+        assert_eq!(retry_queue.last_message(), None);
+        assert!(retry_queue.retry().is_none());
+    }
+
+    #[test]
+    fn test_retry_queue_retry_failed_again() {
+        let mut retry_queue = RetryQueue::default();
+        let elements = vec![domain::TrainingSessionElement::Rest {
+            target_time: Some(60),
+            automatic: true,
+        }];
+
+        retry_queue.push(
+            "Failed to modify training session",
+            FailedRequest::new(Msg::ModifyTrainingSession(
+                1,
+                Some(String::from("A")),
+                Some(elements.clone()),
+                None,
+                None,
+            ))(String::from("No connection")),
+        );
+        let request = retry_queue.retry().unwrap();
+        retry_queue.push(
+            "Failed to modify training session",
+            FailedRequest::new(request)(String::from("Timeout")),
+        );
+
+        assert_eq!(
+            retry_queue.last_message(),
+            Some("Failed to modify training session: Timeout")
+        );
+        assert!(matches!(
+            retry_queue.retry(),
+            Some(Msg::ModifyTrainingSession(1, Some(notes), Some(request_elements), None, None))
+                if notes == "A" && request_elements == elements
+        ));
+    }
+
+    #[test]
+    fn test_retry_queue_discard() {
+        let mut retry_queue = RetryQueue::default();
+
+        retry_queue.push(
+            "Failed to create body weight",
+            FailedRequest::new(Msg::CreateBodyWeight(body_weight(80.0)))(String::from("A")),
+        );
+        retry_queue.push(
+            "Failed to replace body weight",
+            FailedRequest::new(Msg::ReplaceBodyWeight(body_weight(81.0)))(String::from("B")),
+        );
+        retry_queue.discard();
+
+        assert_eq!(
+            retry_queue.last_message(),
+            Some("Failed to create body weight: A")
+        );
+
+        retry_queue.discard();
+
+        assert_eq!(retry_queue.last_message(), None);
+    }
+
+    #[test]
+    fn test_retry_queue_clear() {
+        let mut retry_queue = RetryQueue::default();
+
+        retry_queue.push(
+            "Failed to create body weight",
+            FailedRequest::new(Msg::CreateBodyWeight(body_weight(80.0)))(String::from("A")),
+        );
+        retry_queue.clear();
+
+        assert!(retry_queue.retry().is_none());
+    }
+
+    fn body_weight(weight: f32) -> domain::BodyWeight {
+        domain::BodyWeight {
+            date: NaiveDate::from_ymd_opt(2020, 2, 2).unwrap(),
+            weight,
+        }
+    }
+
     fn routine(id: u32) -> domain::Routine {
         domain::Routine {
             id,
@@ -1251,6 +3342,8 @@ mod tests {
             notes: None,
             archived: false,
             sections: vec![],
+            schedule: vec![],
+            tags: vec![],
         }
     }
 
@@ -1264,6 +3357,8 @@ mod tests {
             routine_id,
             date,
             notes: None,
+            start_time: None,
+            end_time: None,
             elements: vec![],
         }
     }
@@ -7,7 +7,9 @@
 )]
 
 use chrono::{prelude::*, Duration};
+use gloo_console::error;
 use seed::{prelude::*, *};
+use valens_domain as domain;
 use valens_web_app as web_app;
 
 mod common;
@@ -31,6 +33,20 @@ fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
 
     let data = data::init(url, &mut orders.proxy(Msg::Data));
 
+    let msg_sender = orders.msg_sender();
+    if let Err(err) = web_app::service_worker::on_notification_action(move |action| {
+        msg_sender(Some(Msg::NotificationAction(action)));
+    }) {
+        error!("failed to listen for notification actions:", err);
+    }
+
+    let msg_sender = orders.msg_sender();
+    if let Err(err) = web_app::service_worker::on_cache_updated(move |version| {
+        msg_sender(Some(Msg::Data(data::Msg::AppUpdateReady(version))));
+    }) {
+        error!("failed to listen for app updates:", err);
+    }
+
     Model {
         navbar: Navbar {
             title: String::from("Valens"),
@@ -39,6 +55,9 @@ fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
         },
         page: None,
         settings_dialog_visible: false,
+        pin: String::new(),
+        location_form: None,
+        audio_context: None,
         data,
     }
 }
@@ -51,12 +70,14 @@ const LOGIN: &str = "login";
 const ADMIN: &str = "admin";
 const BODY_WEIGHT: &str = "body_weight";
 const BODY_FAT: &str = "body_fat";
+const BODY_MEASUREMENT: &str = "body_measurement";
 const MENSTRUAL_CYCLE: &str = "menstrual_cycle";
 const EXERCISES: &str = "exercises";
 const EXERCISE: &str = "exercise";
 const MUSCLES: &str = "muscles";
 const ROUTINES: &str = "routines";
 const ROUTINE: &str = "routine";
+const STATISTICS: &str = "statistics";
 const TRAINING: &str = "training";
 const TRAINING_SESSION: &str = "training_session";
 
@@ -77,6 +98,9 @@ impl<'a> Urls<'a> {
     pub fn body_fat(self) -> Url {
         self.base_url().set_hash_path([BODY_FAT])
     }
+    pub fn body_measurement(self) -> Url {
+        self.base_url().set_hash_path([BODY_MEASUREMENT])
+    }
     pub fn menstrual_cycle(self) -> Url {
         self.base_url().set_hash_path([MENSTRUAL_CYCLE])
     }
@@ -95,6 +119,9 @@ impl<'a> Urls<'a> {
     pub fn routine(self) -> Url {
         self.base_url().set_hash_path([ROUTINE])
     }
+    pub fn statistics(self) -> Url {
+        self.base_url().set_hash_path([STATISTICS])
+    }
     pub fn training(self) -> Url {
         self.base_url().set_hash_path([TRAINING])
     }
@@ -111,9 +138,104 @@ struct Model {
     navbar: Navbar,
     page: Option<Page>,
     settings_dialog_visible: bool,
+    pin: String,
+    location_form: Option<LocationForm>,
+    /// Audio context for previewing the beep sound, created on first use.
+    audio_context: Option<web_sys::AudioContext>,
     data: data::Model,
 }
 
+/// Form for adding or editing a training location in the settings.
+struct LocationForm {
+    /// Index of the edited location, or `None` if a new location is added.
+    index: Option<usize>,
+    name: String,
+    /// Minimum, maximum and step of the dumbbell weights.
+    dumbbells: [String; 3],
+    plates: String,
+    /// Minimum, maximum and step of the machine stack weights.
+    machine_stack: [String; 3],
+    error: Option<String>,
+}
+
+impl LocationForm {
+    fn new(index: Option<usize>, location: Option<&domain::equipment::Location>) -> Self {
+        let range = |range: Option<domain::equipment::WeightRange>| {
+            range.map_or_else(Default::default, |range| {
+                [range.min, range.max, range.step].map(|value| value.to_string())
+            })
+        };
+        Self {
+            index,
+            name: location.map(|l| l.name.clone()).unwrap_or_default(),
+            dumbbells: range(location.and_then(|l| l.dumbbells)),
+            plates: location
+                .map(|l| {
+                    l.plates
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .unwrap_or_default(),
+            machine_stack: range(location.and_then(|l| l.machine_stack)),
+            error: None,
+        }
+    }
+
+    fn location(
+        &self,
+        locations: &[domain::equipment::Location],
+    ) -> Result<domain::equipment::Location, String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(String::from("Name must not be empty"));
+        }
+        if locations
+            .iter()
+            .enumerate()
+            .any(|(index, l)| Some(index) != self.index && l.name == name)
+        {
+            return Err(String::from("Name must be unique"));
+        }
+        Ok(domain::equipment::Location {
+            name: name.to_string(),
+            dumbbells: parse_weight_range(&self.dumbbells)?,
+            plates: domain::equipment::parse_plates(&self.plates).map_err(|err| err.to_string())?,
+            machine_stack: parse_weight_range(&self.machine_stack)?,
+        })
+    }
+}
+
+/// Parse the minimum, maximum and step of a weight range. No range is returned if all values are
+/// empty.
+fn parse_weight_range(
+    input: &[String; 3],
+) -> Result<Option<domain::equipment::WeightRange>, String> {
+    if input.iter().all(|value| value.trim().is_empty()) {
+        return Ok(None);
+    }
+    match input
+        .each_ref()
+        .map(|value| value.trim().replace(',', ".").parse::<f32>())
+    {
+        [Ok(min), Ok(max), Ok(step)] => domain::equipment::WeightRange::new(min, max, step)
+            .map(Some)
+            .map_err(|err| err.to_string()),
+        _ => Err(String::from(
+            "Minimum, maximum and step of the weights must be numbers",
+        )),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum LocationField {
+    Name,
+    Dumbbells(usize),
+    Plates,
+    MachineStack(usize),
+}
+
 pub struct Navbar {
     title: String,
     items: Vec<(EventHandler<Msg>, String)>,
@@ -126,12 +248,14 @@ enum Page {
     Admin(page::admin::Model),
     BodyWeight(page::body_weight::Model),
     BodyFat(page::body_fat::Model),
+    BodyMeasurement(page::body_measurement::Model),
     MenstrualCycle(page::menstrual_cycle::Model),
     Exercises(page::exercises::Model),
     Exercise(page::exercise::Model),
     Muscles(page::muscles::Model),
     Routines(page::routines::Model),
     Routine(page::routine::Model),
+    Statistics(page::statistics::Model),
     Training(page::training::Model),
     TrainingSession(page::training_session::Model),
     NotFound,
@@ -176,6 +300,12 @@ impl Page {
                     data_model,
                     navbar,
                 )),
+                Some(BODY_MEASUREMENT) => Self::BodyMeasurement(page::body_measurement::init(
+                    url,
+                    &mut orders.proxy(Msg::BodyMeasurement),
+                    data_model,
+                    navbar,
+                )),
                 Some(MENSTRUAL_CYCLE) => Self::MenstrualCycle(page::menstrual_cycle::init(
                     url,
                     &mut orders.proxy(Msg::MenstrualCycle),
@@ -205,6 +335,11 @@ impl Page {
                     data_model,
                     navbar,
                 )),
+                Some(STATISTICS) => Self::Statistics(page::statistics::init(
+                    url,
+                    &mut orders.proxy(Msg::Statistics),
+                    navbar,
+                )),
                 Some(TRAINING) => Self::Training(page::training::init(
                     url,
                     &mut orders.proxy(Msg::Training),
@@ -253,14 +388,44 @@ enum Msg {
     ShowSettingsDialog,
     CloseSettingsDialog,
     BeepVolumeChanged(String),
+    SetSoundProfile(web_app::sound::SoundProfile),
+    PreviewSoundProfile,
+    SetVibration(web_app::vibration::Vibration),
+    ToggleAnnounceExercises,
+    ToggleKeepScreenAwake,
     SetTheme(web_app::Theme),
+    SetContrast(web_app::Contrast),
     ToggleAutomaticMetronome,
     ToggleNotifications,
     ToggleShowRPE,
     ToggleShowTUT,
+    ToggleAdHocPreviousValues,
+    ToggleShowMenstrualCycle,
+    BodyHeightChanged(String),
+    BirthdateChanged(String),
+    SetDefaultInterval(Option<domain::DefaultInterval>),
+    SetRefreshInterval(u32),
+    SetWeightIncrement(f32),
+    SetTargetTolerance(u8),
+    SetTrendWindow(u32),
+    SetDecimalSeparator(web_app::format::DecimalSeparator),
+    SetWeeklySessionTarget(u32),
+    AddLocation,
+    EditLocation(usize),
+    LocationChanged(LocationField, String),
+    SaveLocation,
+    CancelLocation,
+    DeleteLocation(usize),
+    PinChanged(String),
+    SetPin,
+    RemovePin,
+    TimerPresetChanged(usize, String),
+    MetronomeIntervalChanged(String),
+    MetronomeStressedBeatChanged(String),
     UpdateApp,
     GoUp,
     LogOut,
+    NotificationAction(web_app::service_worker::SelectedNotificationAction),
 
     // ------ Pages ------
     Home(page::home::Msg),
@@ -268,12 +433,14 @@ enum Msg {
     Admin(page::admin::Msg),
     BodyWeight(page::body_weight::Msg),
     BodyFat(page::body_fat::Msg),
+    BodyMeasurement(page::body_measurement::Msg),
     MenstrualCycle(page::menstrual_cycle::Msg),
     Exercises(page::exercises::Msg),
     Exercise(page::exercise::Msg),
     Muscles(page::muscles::Msg),
     Routines(page::routines::Msg),
     Routine(page::routine::Msg),
+    Statistics(page::statistics::Msg),
     Training(page::training::Msg),
     TrainingSession(page::training_session::Msg),
 
@@ -322,6 +489,8 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::CloseSettingsDialog => {
             model.settings_dialog_visible = false;
+            model.pin = String::new();
+            model.location_form = None;
         }
         Msg::BeepVolumeChanged(input) => {
             if let Ok(value) = input.parse::<u8>() {
@@ -331,6 +500,9 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         Msg::SetTheme(theme) => {
             orders.send_msg(Msg::Data(data::Msg::SetTheme(theme)));
         }
+        Msg::SetContrast(contrast) => {
+            orders.send_msg(Msg::Data(data::Msg::SetContrast(contrast)));
+        }
         Msg::ToggleAutomaticMetronome => {
             orders.send_msg(Msg::Data(data::Msg::SetAutomaticMetronome(not(model
                 .data
@@ -371,6 +543,182 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .settings
                 .show_tut))));
         }
+        Msg::ToggleAdHocPreviousValues => {
+            orders.send_msg(Msg::Data(data::Msg::SetAdHocPreviousValues(not(model
+                .data
+                .settings
+                .ad_hoc_previous_values))));
+        }
+        Msg::ToggleShowMenstrualCycle => {
+            orders.send_msg(Msg::Data(data::Msg::SetShowMenstrualCycle(not(model
+                .data
+                .show_menstrual_cycle()))));
+        }
+        Msg::BodyHeightChanged(input) => {
+            let height = if input.is_empty() {
+                Some(None)
+            } else {
+                input.parse::<f32>().ok().filter(|h| *h > 0.0).map(Some)
+            };
+            if let (Some(height), Some(user)) = (height, model.data.session_user()) {
+                orders.send_msg(Msg::Data(data::Msg::ReplaceUser(domain::User {
+                    height,
+                    ..user.clone()
+                })));
+            }
+        }
+        Msg::BirthdateChanged(input) => {
+            let birthdate = if input.is_empty() {
+                Some(None)
+            } else {
+                NaiveDate::parse_from_str(&input, "%Y-%m-%d").ok().map(Some)
+            };
+            if let (Some(birthdate), Some(user)) = (birthdate, model.data.session_user()) {
+                orders.send_msg(Msg::Data(data::Msg::ReplaceUser(domain::User {
+                    birthdate,
+                    ..user.clone()
+                })));
+            }
+        }
+        Msg::SetDefaultInterval(interval) => {
+            orders.send_msg(Msg::Data(data::Msg::SetDefaultInterval(interval)));
+        }
+        Msg::SetRefreshInterval(interval) => {
+            orders.send_msg(Msg::Data(data::Msg::SetRefreshInterval(interval)));
+        }
+        Msg::SetVibration(vibration) => {
+            orders.send_msg(Msg::Data(data::Msg::SetVibration(vibration)));
+        }
+        Msg::ToggleAnnounceExercises => {
+            orders.send_msg(Msg::Data(data::Msg::SetAnnounceExercises(not(model
+                .data
+                .settings
+                .announce_exercises))));
+        }
+        Msg::ToggleKeepScreenAwake => {
+            orders.send_msg(Msg::Data(data::Msg::SetKeepScreenAwake(not(model
+                .data
+                .settings
+                .keep_screen_awake))));
+        }
+        Msg::SetSoundProfile(sound_profile) => {
+            orders.send_msg(Msg::Data(data::Msg::SetSoundProfile(sound_profile)));
+        }
+        Msg::PreviewSoundProfile => {
+            if model.audio_context.is_none() {
+                match web_sys::AudioContext::new() {
+                    Ok(audio_context) => model.audio_context = Some(audio_context),
+                    Err(err) => error!("failed to create audio context:", err),
+                }
+            }
+            if let Some(audio_context) = &model.audio_context {
+                if let Err(err) = web_app::sound::play_preview(
+                    audio_context,
+                    model.data.settings.sound_profile,
+                    model.data.settings.beep_volume,
+                ) {
+                    error!("failed to play beep:", err);
+                }
+            }
+        }
+        Msg::SetWeightIncrement(increment) => {
+            orders.send_msg(Msg::Data(data::Msg::SetWeightIncrement(increment)));
+        }
+        Msg::SetTargetTolerance(tolerance) => {
+            orders.send_msg(Msg::Data(data::Msg::SetTargetTolerance(tolerance)));
+        }
+        Msg::SetTrendWindow(days) => {
+            orders.send_msg(Msg::Data(data::Msg::SetTrendWindow(days)));
+        }
+        Msg::SetDecimalSeparator(separator) => {
+            orders.send_msg(Msg::Data(data::Msg::SetDecimalSeparator(separator)));
+        }
+        Msg::SetWeeklySessionTarget(target) => {
+            if web_app::WEEKLY_SESSION_TARGET_RANGE.contains(&target) {
+                orders.send_msg(Msg::Data(data::Msg::SetWeeklySessionTarget(target)));
+            }
+        }
+        Msg::AddLocation => {
+            model.location_form = Some(LocationForm::new(None, None));
+        }
+        Msg::EditLocation(index) => {
+            model.location_form = Some(LocationForm::new(
+                Some(index),
+                model.data.settings.locations.get(index),
+            ));
+        }
+        Msg::LocationChanged(field, input) => {
+            if let Some(form) = &mut model.location_form {
+                match field {
+                    LocationField::Name => form.name = input,
+                    LocationField::Dumbbells(i) => form.dumbbells[i] = input,
+                    LocationField::Plates => form.plates = input,
+                    LocationField::MachineStack(i) => form.machine_stack[i] = input,
+                }
+                form.error = None;
+            }
+        }
+        Msg::SaveLocation => {
+            if let Some(form) = &mut model.location_form {
+                match form.location(&model.data.settings.locations) {
+                    Ok(location) => {
+                        orders.send_msg(Msg::Data(match form.index {
+                            Some(index) => data::Msg::ReplaceLocation(index, location),
+                            None => data::Msg::AddLocation(location),
+                        }));
+                        model.location_form = None;
+                    }
+                    Err(message) => form.error = Some(message),
+                }
+            }
+        }
+        Msg::CancelLocation => {
+            model.location_form = None;
+        }
+        Msg::DeleteLocation(index) => {
+            model.location_form = None;
+            orders.send_msg(Msg::Data(data::Msg::DeleteLocation(index)));
+        }
+        Msg::PinChanged(pin) => {
+            model.pin = pin;
+        }
+        Msg::SetPin => {
+            if let Some(user) = &model.data.session {
+                if pin_valid(&model.pin) {
+                    orders.send_msg(Msg::Data(data::Msg::ReplaceUserPin(
+                        user.id,
+                        Some(model.pin.clone()),
+                    )));
+                    model.pin = String::new();
+                }
+            }
+        }
+        Msg::RemovePin => {
+            if let Some(user) = &model.data.session {
+                orders.send_msg(Msg::Data(data::Msg::ReplaceUserPin(user.id, None)));
+            }
+        }
+        Msg::TimerPresetChanged(index, input) => {
+            if let Ok(value) = input.parse::<u32>() {
+                if web_app::TIMER_PRESET_RANGE.contains(&value) {
+                    orders.send_msg(Msg::Data(data::Msg::SetTimerPreset(index, value)));
+                }
+            }
+        }
+        Msg::MetronomeIntervalChanged(input) => {
+            if let Ok(value) = input.parse::<u32>() {
+                if web_app::METRONOME_INTERVAL_RANGE.contains(&value) {
+                    orders.send_msg(Msg::Data(data::Msg::SetMetronomeInterval(value)));
+                }
+            }
+        }
+        Msg::MetronomeStressedBeatChanged(input) => {
+            if let Ok(value) = input.parse::<u32>() {
+                if web_app::METRONOME_STRESSED_BEAT_RANGE.contains(&value) {
+                    orders.send_msg(Msg::Data(data::Msg::SetMetronomeStressedBeat(value)));
+                }
+            }
+        }
         Msg::UpdateApp => {
             orders.skip().send_msg(Msg::Data(data::Msg::UpdateApp));
         }
@@ -382,7 +730,9 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             Some(
                 Page::BodyWeight(_)
                 | Page::BodyFat(_)
+                | Page::BodyMeasurement(_)
                 | Page::MenstrualCycle(_)
+                | Page::Statistics(_)
                 | Page::Training(_)
                 | Page::NotFound,
             )
@@ -407,16 +757,25 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         Msg::LogOut => {
             orders.skip().notify(data::Msg::DeleteSession);
         }
+        Msg::NotificationAction(action) => {
+            if let Some(Page::TrainingSession(_)) = &model.page {
+                orders.send_msg(Msg::TrainingSession(
+                    page::training_session::Msg::NotificationAction(action),
+                ));
+            } else {
+                orders.send_msg(Msg::Data(data::Msg::ApplyNotificationAction(action)));
+            }
+        }
 
         // ------ Pages ------
         Msg::Home(msg) => {
             if let Some(Page::Home(page_model)) = &mut model.page {
-                page::home::update(msg, page_model, &mut orders.proxy(Msg::Home));
+                page::home::update(msg, page_model, &model.data, &mut orders.proxy(Msg::Home));
             }
         }
         Msg::Login(msg) => {
             if let Some(Page::Login(page_model)) = &mut model.page {
-                page::login::update(msg, page_model, &mut orders.proxy(Msg::Login));
+                page::login::update(msg, page_model, &model.data, &mut orders.proxy(Msg::Login));
             }
         }
         Msg::Admin(msg) => {
@@ -444,6 +803,16 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 );
             }
         }
+        Msg::BodyMeasurement(msg) => {
+            if let Some(Page::BodyMeasurement(page_model)) = &mut model.page {
+                page::body_measurement::update(
+                    msg,
+                    page_model,
+                    &model.data,
+                    &mut orders.proxy(Msg::BodyMeasurement),
+                );
+            }
+        }
         Msg::MenstrualCycle(msg) => {
             if let Some(Page::MenstrualCycle(page_model)) = &mut model.page {
                 page::menstrual_cycle::update(
@@ -476,7 +845,12 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::Muscles(msg) => {
             if let Some(Page::Muscles(page_model)) = &mut model.page {
-                page::muscles::update(&msg, page_model);
+                page::muscles::update(
+                    msg,
+                    page_model,
+                    &model.data,
+                    &mut orders.proxy(Msg::Muscles),
+                );
             }
         }
         Msg::Routines(msg) => {
@@ -499,6 +873,16 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 );
             }
         }
+        Msg::Statistics(msg) => {
+            if let Some(Page::Statistics(page_model)) = &mut model.page {
+                page::statistics::update(
+                    msg,
+                    page_model,
+                    &model.data,
+                    &mut orders.proxy(Msg::Statistics),
+                );
+            }
+        }
         Msg::Training(msg) => {
             if let Some(Page::Training(page_model)) = &mut model.page {
                 page::training::update(
@@ -549,7 +933,7 @@ fn view(model: &Model) -> impl IntoNodes<Msg> {
         nodes![
             view_navbar(&model.navbar, &model.page, &model.data),
             Node::NoChange,
-            view_settings_dialog(&model.data),
+            view_settings_dialog(&model.pin, model.location_form.as_ref(), &model.data),
             data::view(&model.data).map_msg(Msg::Data),
         ]
     } else {
@@ -630,6 +1014,34 @@ fn view_navbar(navbar: &Navbar, page: &Option<Page>, data_model: &data::Model) -
                         C!["navbar-end"],
                         match &data_model.session {
                             Some(s) => nodes![
+                                data_model
+                                    .settings
+                                    .locations
+                                    .iter()
+                                    .map(|location| {
+                                        let active = data_model.settings.active_location.as_ref()
+                                            == Some(&location.name);
+                                        let name = location.name.clone();
+                                        a![
+                                            C!["navbar-item"],
+                                            ev(Ev::Click, move |_| {
+                                                Msg::Data(data::Msg::SetActiveLocation(
+                                                    IF![not(active) => name.clone()]
+                                                ))
+                                            }),
+                                            span![
+                                                C!["icon"],
+                                                C!["px-5"],
+                                                C![IF![not(active) => "has-text-grey-light"]],
+                                                i![C!["fas fa-location-dot"]]
+                                            ],
+                                            &location.name,
+                                        ]
+                                    })
+                                    .collect::<Vec<_>>(),
+                                IF![not(data_model.settings.locations.is_empty()) =>
+                                    hr![C!["navbar-divider"]]
+                                ],
                                 a![
                                     C!["navbar-item"],
                                     ev(Ev::Click, |_| Msg::ShowSettingsDialog),
@@ -674,6 +1086,15 @@ fn view_duration(duration: Duration) -> String {
 }
 
 fn view_page(page: &Option<Page>, data_model: &data::Model) -> Node<Msg> {
+    if data_model.show_version_notice() {
+        return div![
+            C!["container"],
+            C!["is-max-desktop"],
+            C!["py-4"],
+            data::view_version_notice(data_model).map_msg(Msg::Data)
+        ];
+    }
+
     div![
         C!["container"],
         C!["is-max-desktop"],
@@ -686,6 +1107,8 @@ fn view_page(page: &Option<Page>, data_model: &data::Model) -> Node<Msg> {
                 page::body_weight::view(model, data_model).map_msg(Msg::BodyWeight),
             Some(Page::BodyFat(model)) =>
                 page::body_fat::view(model, data_model).map_msg(Msg::BodyFat),
+            Some(Page::BodyMeasurement(model)) =>
+                page::body_measurement::view(model, data_model).map_msg(Msg::BodyMeasurement),
             Some(Page::MenstrualCycle(model)) =>
                 page::menstrual_cycle::view(model, data_model).map_msg(Msg::MenstrualCycle),
             Some(Page::Exercises(model)) =>
@@ -698,6 +1121,8 @@ fn view_page(page: &Option<Page>, data_model: &data::Model) -> Node<Msg> {
                 page::routines::view(model, data_model).map_msg(Msg::Routines),
             Some(Page::Routine(model)) =>
                 page::routine::view(model, data_model).map_msg(Msg::Routine),
+            Some(Page::Statistics(model)) =>
+                page::statistics::view(model, data_model).map_msg(Msg::Statistics),
             Some(Page::Training(model)) =>
                 page::training::view(model, data_model).map_msg(Msg::Training),
             Some(Page::TrainingSession(model)) =>
@@ -708,7 +1133,15 @@ fn view_page(page: &Option<Page>, data_model: &data::Model) -> Node<Msg> {
     ]
 }
 
-fn view_settings_dialog(data_model: &data::Model) -> Node<Msg> {
+fn pin_valid(pin: &str) -> bool {
+    (4..=8).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit())
+}
+
+fn view_settings_dialog(
+    pin: &str,
+    location_form: Option<&LocationForm>,
+    data_model: &data::Model,
+) -> Node<Msg> {
     common::view_dialog(
         "primary",
         "Settings",
@@ -729,6 +1162,140 @@ fn view_settings_dialog(data_model: &data::Model) -> Node<Msg> {
                     input_ev(Ev::Input, Msg::BeepVolumeChanged),
                 ]
             ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Sound"],
+                div![
+                    C!["field"],
+                    C!["is-grouped"],
+                    div![
+                        C!["control"],
+                        div![
+                            C!["field"],
+                            C!["has-addons"],
+                            [
+                                web_app::sound::SoundProfile::Classic,
+                                web_app::sound::SoundProfile::Soft,
+                                web_app::sound::SoundProfile::LoudClick,
+                            ]
+                            .into_iter()
+                            .map(|sound_profile| {
+                                let selected = data_model.settings.sound_profile == sound_profile;
+                                p![
+                                    C!["control"],
+                                    button![
+                                        C!["button"],
+                                        C![IF![selected => "is-link"]],
+                                        &ev(Ev::Click, move |_| {
+                                            Msg::SetSoundProfile(sound_profile)
+                                        }),
+                                        sound_profile.name(),
+                                    ]
+                                ]
+                            }),
+                        ],
+                    ],
+                    div![
+                        C!["control"],
+                        button![
+                            C!["button"],
+                            C!["is-light"],
+                            attrs! {
+                                At::Title => "Preview",
+                            },
+                            &ev(Ev::Click, |_| Msg::PreviewSoundProfile),
+                            span![C!["icon"], i![C!["fas fa-play"]]],
+                        ]
+                    ],
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Vibration"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [
+                        (web_app::vibration::Vibration::Off, "Off"),
+                        (web_app::vibration::Vibration::On, "On"),
+                        (web_app::vibration::Vibration::OnWhenMuted, "When muted"),
+                    ]
+                    .into_iter()
+                    .map(|(vibration, name)| {
+                        p![
+                            C!["control"],
+                            button![
+                                C!["button"],
+                                C![IF![data_model.settings.vibration == vibration => "is-link"]],
+                                &ev(Ev::Click, move |_| Msg::SetVibration(vibration)),
+                                name,
+                            ]
+                        ]
+                    }),
+                ],
+                p![
+                    C!["help"],
+                    "Vibrate in addition to or instead of beeping, if supported by the device"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Spoken announcements"],
+                div![
+                    C!["field"],
+                    C!["is-grouped"],
+                    div![
+                        C!["control"],
+                        button![
+                            C!["button"],
+                            if data_model.settings.announce_exercises {
+                                C!["is-primary"]
+                            } else {
+                                C![]
+                            },
+                            ev(Ev::Click, |_| Msg::ToggleAnnounceExercises),
+                            if data_model.settings.announce_exercises {
+                                "Enabled"
+                            } else {
+                                "Disabled"
+                            },
+                        ]
+                    ],
+                ],
+                p![
+                    C!["help"],
+                    "Announce the next exercise during guided workouts, if supported"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Keep screen awake during workouts"],
+                div![
+                    C!["field"],
+                    C!["is-grouped"],
+                    div![
+                        C!["control"],
+                        button![
+                            C!["button"],
+                            if data_model.settings.keep_screen_awake {
+                                C!["is-primary"]
+                            } else {
+                                C![]
+                            },
+                            ev(Ev::Click, |_| Msg::ToggleKeepScreenAwake),
+                            if data_model.settings.keep_screen_awake {
+                                "Enabled"
+                            } else {
+                                "Disabled"
+                            },
+                        ]
+                    ],
+                ],
+                p![
+                    C!["help"],
+                    "Prevent the screen from turning off during guided workouts, if supported"
+                ],
+            ],
             p![
                 C!["mb-5"],
                 h1![C!["subtitle"], "Theme"],
@@ -771,6 +1338,60 @@ fn view_settings_dialog(data_model: &data::Model) -> Node<Msg> {
                     ],
                 ],
             ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Contrast"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [
+                        (web_app::Contrast::Normal, "fa-circle-half-stroke", "Normal"),
+                        (web_app::Contrast::High, "fa-circle", "High"),
+                        (web_app::Contrast::System, "fa-desktop", "System"),
+                    ]
+                    .into_iter()
+                    .map(|(contrast, icon, label)| {
+                        p![
+                            C!["control"],
+                            button![
+                                C!["button"],
+                                C![IF![data_model.settings.contrast == contrast => "is-link"]],
+                                ev(Ev::Click, move |_| Msg::SetContrast(contrast)),
+                                span![C!["icon"], i![C!["fas", icon]]],
+                                span![label],
+                            ]
+                        ]
+                    }),
+                ],
+                p![
+                    C!["help"],
+                    if data_model.high_contrast() {
+                        "Charts are shown with high contrast colors"
+                    } else {
+                        "Charts are shown with normal colors"
+                    }
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Reduced motion"],
+                div![
+                    C!["field"],
+                    span![
+                        C!["tag"],
+                        C!["is-medium"],
+                        if common::reduced_motion() {
+                            "Enabled"
+                        } else {
+                            "Disabled"
+                        }
+                    ],
+                ],
+                p![
+                    C!["help"],
+                    "Avoid animations and smooth scrolling, as set in the operating system or browser"
+                ],
+            ],
             p![
                 C!["mb-5"],
                 h1![C!["subtitle"], "Metronome"],
@@ -839,6 +1460,440 @@ fn view_settings_dialog(data_model: &data::Model) -> Node<Msg> {
                     ],
                 ],
             ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Previous values in ad-hoc sessions"],
+                div![
+                    C!["field"],
+                    C!["is-grouped"],
+                    div![
+                        C!["control"],
+                        button![
+                            C!["button"],
+                            if data_model.settings.ad_hoc_previous_values {
+                                C!["is-primary"]
+                            } else {
+                                C![]
+                            },
+                            ev(Ev::Click, |_| Msg::ToggleAdHocPreviousValues),
+                            if data_model.settings.ad_hoc_previous_values {
+                                "Enabled"
+                            } else {
+                                "Disabled"
+                            },
+                        ]
+                    ],
+                ],
+                p![
+                    C!["help"],
+                    "Show the values of the last ad-hoc session containing the same exercise"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Menstrual cycle tracking"],
+                div![
+                    C!["field"],
+                    C!["is-grouped"],
+                    div![
+                        C!["control"],
+                        button![
+                            C!["button"],
+                            if data_model.show_menstrual_cycle() {
+                                C!["is-primary"]
+                            } else {
+                                C![]
+                            },
+                            ev(Ev::Click, |_| Msg::ToggleShowMenstrualCycle),
+                            if data_model.show_menstrual_cycle() {
+                                "Shown"
+                            } else {
+                                "Hidden"
+                            },
+                        ]
+                    ],
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Body height"],
+                div![
+                    C!["field"],
+                    div![
+                        C!["control"],
+                        C!["has-icons-right"],
+                        input_ev(Ev::Change, Msg::BodyHeightChanged),
+                        input![
+                            C!["input"],
+                            attrs! {
+                                At::from("inputmode") => "decimal",
+                                At::Value => data_model
+                                    .body_height()
+                                    .map_or(String::new(), |h| h.to_string()),
+                            }
+                        ],
+                        span![C!["icon"], C!["is-small"], C!["is-right"], "cm"],
+                    ],
+                    p![
+                        C!["help"],
+                        "Required for the BMI and the Navy body fat estimate"
+                    ],
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Birthdate"],
+                div![
+                    C!["field"],
+                    div![
+                        C!["control"],
+                        input_ev(Ev::Change, Msg::BirthdateChanged),
+                        input![
+                            C!["input"],
+                            attrs! {
+                                At::Type => "date",
+                                At::Value => data_model
+                                    .session_user()
+                                    .and_then(|user| user.birthdate)
+                                    .map_or(String::new(), |d| d.to_string()),
+                            }
+                        ],
+                    ],
+                    p![
+                        C!["help"],
+                        format!(
+                            "Used for body fat estimates, which otherwise assume an age of {}",
+                            domain::DEFAULT_AGE
+                        )
+                    ],
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Chart interval"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [
+                        (None, "Last"),
+                        (Some(domain::DefaultInterval::All), "ALL"),
+                        (Some(domain::DefaultInterval::_1Y), "1Y"),
+                        (Some(domain::DefaultInterval::_6M), "6M"),
+                        (Some(domain::DefaultInterval::_3M), "3M"),
+                        (Some(domain::DefaultInterval::_1M), "1M"),
+                    ]
+                    .into_iter()
+                    .map(|(interval, name)| {
+                        p![
+                            C!["control"],
+                            button![
+                                C!["button"],
+                                C![
+                                    IF![data_model.settings.default_interval == interval => "is-link"]
+                                ],
+                                &ev(Ev::Click, move |_| Msg::SetDefaultInterval(interval)),
+                                name,
+                            ]
+                        ]
+                    }),
+                ],
+                p![
+                    C!["help"],
+                    "Interval shown on chart pages until another one is selected (Last: last selected interval)"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Automatic data refresh"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [(0, "Off"), (5, "5 min"), (15, "15 min"), (60, "60 min")]
+                        .into_iter()
+                        .map(|(interval, name)| {
+                            p![
+                                C!["control"],
+                                button![
+                                    C!["button"],
+                                    C![
+                                        IF![data_model.settings.refresh_interval == interval => "is-link"]
+                                    ],
+                                    &ev(Ev::Click, move |_| Msg::SetRefreshInterval(interval)),
+                                    name,
+                                ]
+                            ]
+                        }),
+                ],
+                p![
+                    C!["help"],
+                    "Data older than the selected time is refreshed when the app is shown again, except during a guided training session"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Weight increment"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [0.5, 1.0, 1.25, 2.5, 5.0]
+                        .into_iter()
+                        .map(|increment| {
+                            p![
+                                C!["control"],
+                                button![
+                                    C!["button"],
+                                    C![
+                                        IF![(data_model.settings.weight_increment - increment).abs() < f32::EPSILON => "is-link"]
+                                    ],
+                                    &ev(Ev::Click, move |_| Msg::SetWeightIncrement(increment)),
+                                    format!("{increment} kg"),
+                                ]
+                            ]
+                        }),
+                ],
+                p![
+                    C!["help"],
+                    "Target weights derived from the one-repetition maximum are rounded to a multiple of the selected weight"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Locations"],
+                data_model
+                    .settings
+                    .locations
+                    .iter()
+                    .enumerate()
+                    .map(|(index, location)| {
+                        div![
+                            C!["field"],
+                            C!["is-grouped"],
+                            C!["is-align-items-center"],
+                            div![
+                                C!["control"],
+                                C!["is-expanded"],
+                                &location.name,
+                                IF![data_model.settings.active_location.as_ref() == Some(&location.name) =>
+                                    span![C!["tag"], C!["is-link"], C!["ml-2"], "Active"]
+                                ],
+                            ],
+                            div![
+                                C!["control"],
+                                button![
+                                    C!["button"],
+                                    C!["is-small"],
+                                    ev(Ev::Click, move |_| Msg::EditLocation(index)),
+                                    span![C!["icon"], i![C!["fas fa-edit"]]],
+                                ]
+                            ],
+                            div![
+                                C!["control"],
+                                button![
+                                    C!["button"],
+                                    C!["is-small"],
+                                    C!["is-danger"],
+                                    C!["is-outlined"],
+                                    ev(Ev::Click, move |_| Msg::DeleteLocation(index)),
+                                    span![C!["icon"], i![C!["fas fa-times"]]],
+                                ]
+                            ],
+                        ]
+                    }),
+                if let Some(form) = location_form {
+                    view_location_form(form)
+                } else {
+                    button![
+                        C!["button"],
+                        C!["is-small"],
+                        ev(Ev::Click, |_| Msg::AddLocation),
+                        span![C!["icon"], i![C!["fas fa-plus"]]],
+                        span!["Add location"],
+                    ]
+                },
+                p![
+                    C!["help"],
+                    "Target weights and progression suggestions are rounded to the weights achievable with the equipment of the active location, which can be selected in the menu"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Target tolerance"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [0, 5, 10, 20]
+                        .into_iter()
+                        .map(|tolerance| {
+                            p![
+                                C!["control"],
+                                button![
+                                    C!["button"],
+                                    C![
+                                        IF![data_model.settings.target_tolerance == tolerance => "is-link"]
+                                    ],
+                                    &ev(Ev::Click, move |_| Msg::SetTargetTolerance(tolerance)),
+                                    format!("{tolerance} %"),
+                                ]
+                            ]
+                        }),
+                ],
+                p![
+                    C!["help"],
+                    "Sets which miss their target values by more than the selected percentage are highlighted in the training session"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Trend window"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [7, 14, 28, 56]
+                        .into_iter()
+                        .map(|days| {
+                            p![
+                                C!["control"],
+                                button![
+                                    C!["button"],
+                                    C![
+                                        IF![data_model.settings.trend_window == days => "is-link"]
+                                    ],
+                                    &ev(Ev::Click, move |_| Msg::SetTrendWindow(days)),
+                                    format!("{days} days"),
+                                ]
+                            ]
+                        }),
+                ],
+                p![
+                    C!["help"],
+                    "Trends on the home page show the change over the selected number of days"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Decimal separator"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [
+                        web_app::format::DecimalSeparator::Point,
+                        web_app::format::DecimalSeparator::Comma
+                    ]
+                    .into_iter()
+                    .map(|separator| {
+                        p![
+                            C!["control"],
+                            button![
+                                C!["button"],
+                                C![
+                                    IF![data_model.settings.decimal_separator == separator => "is-link"]
+                                ],
+                                &ev(Ev::Click, move |_| Msg::SetDecimalSeparator(separator)),
+                                format!("1{}5", separator.symbol()),
+                            ]
+                        ]
+                    }),
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Weekly training target"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    web_app::WEEKLY_SESSION_TARGET_RANGE.map(|target| {
+                        p![
+                            C!["control"],
+                            button![
+                                C!["button"],
+                                C![IF![data_model.settings.weekly_session_target == target => "is-link"]],
+                                &ev(Ev::Click, move |_| Msg::SetWeeklySessionTarget(target)),
+                                target,
+                            ]
+                        ]
+                    }),
+                ],
+                p![
+                    C!["help"],
+                    "Number of training sessions per week required to continue the training streak"
+                ],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Timer presets"],
+                div![
+                    C!["field"],
+                    C!["is-grouped"],
+                    data_model
+                        .settings
+                        .timer_presets
+                        .iter()
+                        .enumerate()
+                        .map(|(index, preset)| {
+                            div![
+                                C!["control"],
+                                C!["has-icons-right"],
+                                input_ev(Ev::Change, move |input| Msg::TimerPresetChanged(index, input)),
+                                input![
+                                    C!["input"],
+                                    attrs! {
+                                        At::Type => "number",
+                                        At::Min => web_app::TIMER_PRESET_RANGE.start(),
+                                        At::Max => web_app::TIMER_PRESET_RANGE.end(),
+                                        At::Step => 1,
+                                        At::Size => 4,
+                                        At::Value => preset,
+                                    }
+                                ],
+                                span![C!["icon"], C!["is-small"], C!["is-right"], "s"],
+                            ]
+                        }),
+                ],
+                p![C!["help"], "Durations which can be selected with a single tap in the timer"],
+            ],
+            p![
+                C!["mb-5"],
+                h1![C!["subtitle"], "Metronome"],
+                div![
+                    C!["field"],
+                    C!["is-grouped"],
+                    div![
+                        C!["control"],
+                        label![C!["label"], "Interval"],
+                        input_ev(Ev::Change, Msg::MetronomeIntervalChanged),
+                        div![
+                            C!["select"],
+                            select![web_app::METRONOME_INTERVAL_RANGE.map(|i| {
+                                option![
+                                    &i,
+                                    attrs! {
+                                        At::Value => i,
+                                        At::Selected => (i == data_model.settings.metronome_interval).as_at_value()
+                                    }
+                                ]
+                            })]
+                        ]
+                    ],
+                    div![
+                        C!["control"],
+                        label![C!["label"], "Stress"],
+                        input_ev(Ev::Change, Msg::MetronomeStressedBeatChanged),
+                        div![
+                            C!["select"],
+                            select![web_app::METRONOME_STRESSED_BEAT_RANGE.map(|i| {
+                                option![
+                                    &i,
+                                    attrs! {
+                                        At::Value => i,
+                                        At::Selected => (i == data_model.settings.metronome_stressed_beat).as_at_value()
+                                    }
+                                ]
+                            })]
+                        ]
+                    ],
+                ],
+                p![C!["help"], "Initial values of the metronome"],
+            ],
             {
                 let permission = web_sys::Notification::permission();
                 let notifications_enabled = data_model.settings.notifications;
@@ -880,6 +1935,68 @@ fn view_settings_dialog(data_model: &data::Model) -> Node<Msg> {
                     }
                 ]
             },
+            if let Some(user) = &data_model.session {
+                let valid = pin_valid(pin);
+                p![
+                    C!["mb-5"],
+                    h1![C!["subtitle"], "PIN"],
+                    div![
+                        C!["field"],
+                        C!["has-addons"],
+                        div![
+                            C!["control"],
+                            input_ev(Ev::Input, Msg::PinChanged),
+                            keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+                                IF!(
+                                    valid && keyboard_event.key_code() == common::ENTER_KEY => {
+                                        Msg::SetPin
+                                    }
+                                )
+                            }),
+                            input![
+                                C!["input"],
+                                C![IF![!pin.is_empty() && !valid => "is-danger"]],
+                                attrs! {
+                                    At::Type => "password",
+                                    At::from("inputmode") => "numeric",
+                                    At::from("autocomplete") => "new-password",
+                                    At::Size => 8,
+                                    At::Value => pin,
+                                }
+                            ],
+                        ],
+                        div![
+                            C!["control"],
+                            button![
+                                C!["button"],
+                                C!["is-link"],
+                                attrs! {
+                                    At::Disabled => not(valid).as_at_value(),
+                                },
+                                ev(Ev::Click, |_| Msg::SetPin),
+                                if user.has_pin { "Change" } else { "Set" },
+                            ]
+                        ],
+                        IF![user.has_pin =>
+                            div![
+                                C!["control"],
+                                button![
+                                    C!["button"],
+                                    C!["is-danger"],
+                                    ev(Ev::Click, |_| Msg::RemovePin),
+                                    "Remove",
+                                ]
+                            ]
+                        ],
+                    ],
+                    p![
+                        C!["help"],
+                        "PIN of 4 to 8 digits required to log in as this user"
+                    ],
+                ]
+            } else {
+                empty![]
+            },
             p![
                 h1![C!["subtitle"], "Version"],
                 common::view_versions(&data_model.version),
@@ -898,6 +2015,118 @@ fn view_settings_dialog(data_model: &data::Model) -> Node<Msg> {
     )
 }
 
+fn view_location_form(form: &LocationForm) -> Node<Msg> {
+    let range_inputs = |values: &[String; 3], field: fn(usize) -> LocationField| {
+        div![
+            C!["field"],
+            C!["is-grouped"],
+            ["Min", "Max", "Step"]
+                .into_iter()
+                .enumerate()
+                .map(|(i, placeholder)| {
+                    div![
+                        C!["control"],
+                        C!["has-icons-right"],
+                        input_ev(Ev::Input, move |input| Msg::LocationChanged(
+                            field(i),
+                            input
+                        )),
+                        input![
+                            C!["input"],
+                            attrs! {
+                                At::from("inputmode") => "decimal",
+                                At::Size => 4,
+                                At::Placeholder => placeholder,
+                                At::Value => &values[i],
+                            }
+                        ],
+                        span![C!["icon"], C!["is-small"], C!["is-right"], "kg"],
+                    ]
+                }),
+        ]
+    };
+    div![
+        C!["box"],
+        div![
+            C!["field"],
+            label![C!["label"], "Name"],
+            div![
+                C!["control"],
+                input_ev(Ev::Input, |input| Msg::LocationChanged(
+                    LocationField::Name,
+                    input
+                )),
+                input![
+                    C!["input"],
+                    attrs! {
+                        At::Type => "text",
+                        At::Value => &form.name,
+                    }
+                ],
+            ],
+        ],
+        label![C!["label"], "Dumbbells"],
+        range_inputs(&form.dumbbells, LocationField::Dumbbells),
+        div![
+            C!["field"],
+            label![C!["label"], "Plates"],
+            div![
+                C!["control"],
+                input_ev(Ev::Input, |input| Msg::LocationChanged(
+                    LocationField::Plates,
+                    input
+                )),
+                input![
+                    C!["input"],
+                    attrs! {
+                        At::Type => "text",
+                        At::Placeholder => "20 20 10 5 2.5 1.25",
+                        At::Value => &form.plates,
+                    }
+                ],
+            ],
+            p![
+                C!["help"],
+                "Weight of each pair of plates in kg, separated by spaces"
+            ],
+        ],
+        label![C!["label"], "Machine stack"],
+        range_inputs(&form.machine_stack, LocationField::MachineStack),
+        p![
+            C!["help"],
+            "Leave the weights empty if the equipment is not available"
+        ],
+        form.error
+            .as_ref()
+            .map(|message| p![C!["help"], C!["is-danger"], message]),
+        div![
+            C!["field"],
+            C!["is-grouped"],
+            C!["is-grouped-right"],
+            C!["mt-4"],
+            div![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-light"],
+                    C!["is-soft"],
+                    ev(Ev::Click, |_| Msg::CancelLocation),
+                    "Cancel",
+                ]
+            ],
+            div![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-link"],
+                    ev(Ev::Click, |_| Msg::SaveLocation),
+                    "Save",
+                ]
+            ],
+        ],
+    ]
+}
+
 // ------ ------
 //     Start
 // ------ ------
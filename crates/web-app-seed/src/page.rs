@@ -1,5 +1,6 @@
 pub mod admin;
 pub mod body_fat;
+pub mod body_measurement;
 pub mod body_weight;
 pub mod exercise;
 pub mod exercises;
@@ -10,5 +11,6 @@ pub mod muscles;
 pub mod not_found;
 pub mod routine;
 pub mod routines;
+pub mod statistics;
 pub mod training;
 pub mod training_session;
@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use seed::{prelude::*, *};
 use valens_domain as domain;
 
@@ -11,13 +12,15 @@ pub fn init(_: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Navbar) -
     orders
         .subscribe(Msg::DataEvent)
         .notify(data::Msg::ReadVersion)
-        .notify(data::Msg::ReadUsers);
+        .notify(data::Msg::ReadUsers)
+        .notify(data::Msg::ReadStorageUsage);
 
     navbar.title = String::from("Administration");
 
     Model {
         dialog: Dialog::Hidden,
         loading: false,
+        bulk_deletion: BulkDeletion::default(),
     }
 }
 
@@ -28,6 +31,7 @@ pub fn init(_: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Navbar) -
 pub struct Model {
     dialog: Dialog,
     loading: bool,
+    bulk_deletion: BulkDeletion,
 }
 
 enum Dialog {
@@ -37,6 +41,31 @@ enum Dialog {
     DeleteUser(u32),
 }
 
+#[derive(Default)]
+struct BulkDeletion {
+    first: (String, Option<NaiveDate>),
+    last: (String, Option<NaiveDate>),
+    routine_id: Option<u32>,
+    confirmation: String,
+}
+
+impl BulkDeletion {
+    fn training_sessions<'a>(
+        &self,
+        data_model: &'a data::Model,
+    ) -> Vec<&'a domain::TrainingSession> {
+        match (self.first.1, self.last.1) {
+            (Some(first), Some(last)) => web_app::select_training_sessions(
+                data_model.training_sessions.values(),
+                &(first..=last),
+                self.routine_id,
+                data_model.ongoing_training_session.as_ref(),
+            ),
+            _ => vec![],
+        }
+    }
+}
+
 // ------ ------
 //    Update
 // ------ ------
@@ -54,11 +83,20 @@ pub enum Msg {
     DeleteUser(u32),
     DataEvent(data::Event),
 
+    FirstDateChanged(String),
+    LastDateChanged(String),
+    RoutineChanged(String),
+    DeletionConfirmationChanged(String),
+    DeleteTrainingSessions,
+
+    ClearImageCache,
+
     UpdateApp,
 }
 
 const ERROR_EMPTY_NAME: &str = "The name must not be empty";
 const ERROR_NAME_CONFLICT: &str = "A user with this name already exists";
+const DELETION_CONFIRMATION: &str = "DELETE";
 
 pub fn update(
     msg: Msg,
@@ -157,6 +195,42 @@ pub fn update(
             };
         }
 
+        Msg::FirstDateChanged(date) => {
+            let parsed_date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok();
+            if parsed_date.is_some() {
+                orders.notify(data::Msg::ReadOlderTrainingSessions(parsed_date));
+            }
+            model.bulk_deletion.first = (date, parsed_date);
+            model.bulk_deletion.confirmation = String::new();
+        }
+        Msg::LastDateChanged(date) => {
+            let parsed_date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok();
+            model.bulk_deletion.last = (date, parsed_date);
+            model.bulk_deletion.confirmation = String::new();
+        }
+        Msg::RoutineChanged(routine_id) => {
+            model.bulk_deletion.routine_id = routine_id.parse::<u32>().ok().filter(|id| *id > 0);
+            model.bulk_deletion.confirmation = String::new();
+        }
+        Msg::DeletionConfirmationChanged(confirmation) => {
+            model.bulk_deletion.confirmation = confirmation;
+        }
+        Msg::DeleteTrainingSessions => {
+            if model.bulk_deletion.confirmation == DELETION_CONFIRMATION {
+                let ids = model
+                    .bulk_deletion
+                    .training_sessions(data_model)
+                    .iter()
+                    .map(|t| t.id)
+                    .collect::<Vec<_>>();
+                model.bulk_deletion.confirmation = String::new();
+                orders.notify(data::Msg::DeleteTrainingSessions(ids));
+            }
+        }
+
+        Msg::ClearImageCache => {
+            orders.notify(data::Msg::ClearImageCache);
+        }
         Msg::UpdateApp => {
             orders.skip().notify(data::Msg::UpdateApp);
         }
@@ -185,6 +259,8 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
             Node::Empty
         },
         view_users(data_model),
+        IF![data_model.session.is_some() => view_bulk_deletion(&model.bulk_deletion, data_model)],
+        view_storage(data_model),
         view_versions(data_model)
     ]
 }
@@ -363,6 +439,237 @@ fn view_user_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
     )
 }
 
+fn view_bulk_deletion(bulk_deletion: &BulkDeletion, data_model: &data::Model) -> Node<Msg> {
+    let training_sessions = bulk_deletion.training_sessions(data_model);
+    let preview = match (training_sessions.first(), training_sessions.last()) {
+        (Some(first), Some(last)) => format!(
+            "{} training session{} from {} to {}",
+            training_sessions.len(),
+            if training_sessions.len() == 1 {
+                ""
+            } else {
+                "s"
+            },
+            first.date,
+            last.date
+        ),
+        _ => String::from("No training sessions"),
+    };
+    let mut routines = data_model.routines.values().collect::<Vec<_>>();
+    routines.sort_by(|a, b| a.name.cmp(&b.name));
+    let confirmed = bulk_deletion.confirmation == DELETION_CONFIRMATION;
+    let deleting = data_model.training_session_batch_progress.is_some();
+    let first_invalid = !bulk_deletion.first.0.is_empty() && bulk_deletion.first.1.is_none();
+    let last_invalid = !bulk_deletion.last.0.is_empty() && bulk_deletion.last.1.is_none();
+    div![
+        C!["container"],
+        C!["mt-6"],
+        C!["mx-3"],
+        common::view_title(&span!["Delete training sessions"], 3),
+        div![
+            C!["field"],
+            C!["is-grouped"],
+            div![
+                C!["control"],
+                C!["is-expanded"],
+                label![C!["label"], "From"],
+                input_ev(Ev::Input, Msg::FirstDateChanged),
+                input![
+                    C!["input"],
+                    C![IF![first_invalid => "is-danger"]],
+                    attrs! {
+                        At::Type => "date",
+                        At::Value => bulk_deletion.first.0,
+                    }
+                ],
+            ],
+            div![
+                C!["control"],
+                C!["is-expanded"],
+                label![C!["label"], "To"],
+                input_ev(Ev::Input, Msg::LastDateChanged),
+                input![
+                    C!["input"],
+                    C![IF![last_invalid => "is-danger"]],
+                    attrs! {
+                        At::Type => "date",
+                        At::Value => bulk_deletion.last.0,
+                    }
+                ],
+            ],
+        ],
+        div![
+            C!["field"],
+            label![C!["label"], "Routine"],
+            div![
+                C!["control"],
+                input_ev(Ev::Change, Msg::RoutineChanged),
+                div![
+                    C!["select"],
+                    select![
+                        option![
+                            "All routines",
+                            attrs![
+                                At::Value => 0,
+                                At::Selected => bulk_deletion.routine_id.is_none().as_at_value(),
+                            ]
+                        ],
+                        routines
+                            .iter()
+                            .map(|r| {
+                                option![
+                                    &r.name,
+                                    attrs![
+                                        At::Value => r.id,
+                                        At::Selected =>
+                                            (bulk_deletion.routine_id == Some(r.id)).as_at_value(),
+                                    ]
+                                ]
+                            })
+                            .collect::<Vec<_>>()
+                    ],
+                ],
+            ],
+        ],
+        p![C!["mb-3"], preview],
+        div![
+            C!["field"],
+            label![
+                C!["label"],
+                format!("Type {DELETION_CONFIRMATION} to confirm")
+            ],
+            div![
+                C!["control"],
+                input_ev(Ev::Input, Msg::DeletionConfirmationChanged),
+                input![
+                    C!["input"],
+                    attrs! {
+                        At::Type => "text",
+                        At::from("autocomplete") => "off",
+                        At::Value => bulk_deletion.confirmation,
+                    }
+                ],
+            ],
+        ],
+        if let Some((done, total)) = data_model.training_session_batch_progress {
+            progress![
+                C!["progress"],
+                C!["is-small"],
+                C!["is-danger"],
+                attrs! {
+                    At::Value => done,
+                    At::Max => total,
+                },
+                format!("{done} / {total}")
+            ]
+        } else {
+            empty![]
+        },
+        button![
+            C!["button"],
+            C!["is-danger"],
+            C![IF![deleting => "is-loading"]],
+            attrs![
+                At::Disabled =>
+                    (deleting || !confirmed || training_sessions.is_empty()).as_at_value(),
+            ],
+            ev(Ev::Click, |_| Msg::DeleteTrainingSessions),
+            "Delete",
+        ],
+    ]
+}
+
+fn view_storage(data_model: &data::Model) -> Node<Msg> {
+    let separator = data_model.settings.decimal_separator;
+    let format_bytes = |bytes| web_app::storage_usage::format_bytes(bytes, separator);
+    let row = |name: &str, value: String| tr![th![name], td![C!["has-text-right"], value]];
+    div![
+        C!["container"],
+        C!["mt-6"],
+        C!["mx-3"],
+        common::view_title(&span!["Storage"], 3),
+        IF![data_model.image_cache_disabled =>
+            div![
+                C!["message"],
+                C!["is-warning"],
+                div![
+                    C!["message-body"],
+                    "The storage space on this device is exhausted. Images are loaded from the server instead of the cache until space is freed."
+                ]
+            ]
+        ],
+        if let Some(storage_usage) = &data_model.storage_usage {
+            nodes![
+                match &storage_usage.estimate {
+                    Ok(estimate) => div![
+                        C!["mb-4"],
+                        progress![
+                            C!["progress"],
+                            C![IF![estimate.fraction() > 0.9 => "is-danger"]],
+                            C!["mb-1"],
+                            attrs! {
+                                At::Value => estimate.fraction(),
+                                At::Max => 1,
+                            },
+                        ],
+                        p![
+                            C!["has-text-centered"],
+                            format!(
+                                "{} of {} used",
+                                format_bytes(estimate.usage),
+                                format_bytes(estimate.quota)
+                            )
+                        ],
+                    ],
+                    Err(message) => p![
+                        C!["has-text-centered"],
+                        C!["has-text-grey"],
+                        C!["mb-4"],
+                        format!("Storage usage unknown ({message})")
+                    ],
+                },
+                table![
+                    C!["table"],
+                    C!["is-fullwidth"],
+                    tbody![
+                        row(
+                            "Settings and drafts",
+                            match &storage_usage.local_storage {
+                                Ok(usage) => format!(
+                                    "{} entries, {}",
+                                    usage.entries,
+                                    format_bytes(usage.bytes)
+                                ),
+                                Err(_) => String::from("-"),
+                            }
+                        ),
+                        row(
+                            "Cached images",
+                            storage_usage
+                                .cached_exercise_images
+                                .as_ref()
+                                .map_or_else(|_| String::from("-"), ToString::to_string)
+                        ),
+                    ]
+                ],
+            ]
+        } else {
+            nodes![common::view_page_loading()]
+        },
+        button![
+            C!["button"],
+            C!["is-danger"],
+            C!["is-outlined"],
+            ev(Ev::Click, |_| Msg::ClearImageCache),
+            "Clear image cache",
+        ],
+        p![
+            C!["help"],
+            "Cached images are loaded again from the server when needed"
+        ],
+    ]
+}
+
 fn view_versions(data_model: &data::Model) -> Node<Msg> {
     div![
         C!["container"],
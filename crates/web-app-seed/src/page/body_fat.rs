@@ -31,7 +31,12 @@ pub fn init(
                 .copied()
                 .collect::<Vec<NaiveDate>>(),
             domain::DefaultInterval::_3M,
+            data_model.settings.initial_interval(),
+            &web_app::clock::BrowserClock,
         ),
+        estimator: Estimator::JP3,
+        site: Site::Thigh,
+        inline_edit: None,
         dialog: Dialog::Hidden,
         loading: false,
     }
@@ -43,15 +48,187 @@ pub fn init(
 
 pub struct Model {
     interval: domain::Interval,
+    estimator: Estimator,
+    /// Skinfold site whose trend is shown.
+    site: Site,
+    /// Expanded entry in the table.
+    inline_edit: Option<InlineEdit>,
     dialog: Dialog,
     loading: bool,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum Estimator {
+    JP3,
+    JP7,
+    Navy,
+}
+
+impl Estimator {
+    fn name(self) -> &'static str {
+        match self {
+            Estimator::JP3 => "JP3",
+            Estimator::JP7 => "JP7",
+            Estimator::Navy => "Navy",
+        }
+    }
+
+    fn color(self) -> usize {
+        match self {
+            Estimator::JP3 => web_app::chart::COLOR_BODY_FAT_JP3,
+            Estimator::JP7 => web_app::chart::COLOR_BODY_FAT_JP7,
+            Estimator::Navy => web_app::chart::COLOR_BODY_FAT_NAVY,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Site {
+    Chest,
+    Abdominal,
+    Thigh,
+    Tricep,
+    Subscapular,
+    Suprailiac,
+    Midaxillary,
+}
+
+impl Site {
+    const ALL: [Site; 7] = [
+        Site::Chest,
+        Site::Abdominal,
+        Site::Thigh,
+        Site::Tricep,
+        Site::Subscapular,
+        Site::Suprailiac,
+        Site::Midaxillary,
+    ];
+
+    /// All sites, starting with the sites used for the JP3 estimate of the given sex.
+    fn ordered(sex: u8) -> [Site; 7] {
+        if sex == 0 {
+            [
+                Site::Tricep,
+                Site::Suprailiac,
+                Site::Thigh,
+                Site::Chest,
+                Site::Abdominal,
+                Site::Subscapular,
+                Site::Midaxillary,
+            ]
+        } else {
+            [
+                Site::Chest,
+                Site::Abdominal,
+                Site::Thigh,
+                Site::Tricep,
+                Site::Subscapular,
+                Site::Suprailiac,
+                Site::Midaxillary,
+            ]
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Site::Chest => "Chest",
+            Site::Abdominal => "Abdominal",
+            Site::Thigh => "Thigh",
+            Site::Tricep => "Tricep",
+            Site::Subscapular => "Subscapular",
+            Site::Suprailiac => "Suprailiac",
+            Site::Midaxillary => "Midaxillary",
+        }
+    }
+
+    fn value(self, body_fat: &domain::BodyFat) -> Option<u8> {
+        match self {
+            Site::Chest => body_fat.chest,
+            Site::Abdominal => body_fat.abdominal,
+            Site::Thigh => body_fat.thigh,
+            Site::Tricep => body_fat.tricep,
+            Site::Subscapular => body_fat.subscapular,
+            Site::Suprailiac => body_fat.suprailiac,
+            Site::Midaxillary => body_fat.midaxillary,
+        }
+    }
+}
+
+/// Skinfold values of an entry which are edited directly in the table.
+struct InlineEdit {
+    date: NaiveDate,
+    /// Input fields in the order of `Site::ALL`. An empty input is valid and means that the site
+    /// was not measured.
+    fields: [common::InputField<Option<u8>>; 7],
+}
+
+impl InlineEdit {
+    fn new(body_fat: &domain::BodyFat) -> Self {
+        Self {
+            date: body_fat.date,
+            fields: Site::ALL.map(|site| {
+                let value = site
+                    .value(body_fat)
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+                skinfold_field(value.clone(), value)
+            }),
+        }
+    }
+
+    fn field(&self, site: Site) -> &common::InputField<Option<u8>> {
+        &self.fields[site as usize]
+    }
+
+    fn field_mut(&mut self, site: Site) -> &mut common::InputField<Option<u8>> {
+        &mut self.fields[site as usize]
+    }
+
+    fn is_valid(&self) -> bool {
+        self.fields.iter().all(common::InputField::valid)
+            && self.fields.iter().any(|f| f.parsed.flatten().is_some())
+    }
+
+    fn changed(&self) -> bool {
+        self.fields.iter().any(common::InputField::changed)
+    }
+
+    /// Body fat entry with the current values of the input fields, invalid values are omitted.
+    fn body_fat(&self) -> domain::BodyFat {
+        let value = |site| self.field(site).parsed.flatten();
+        domain::BodyFat {
+            date: self.date,
+            chest: value(Site::Chest),
+            abdominal: value(Site::Abdominal),
+            thigh: value(Site::Thigh),
+            tricep: value(Site::Tricep),
+            subscapular: value(Site::Subscapular),
+            suprailiac: value(Site::Suprailiac),
+            midaxillary: value(Site::Midaxillary),
+        }
+    }
+}
+
+fn skinfold_field(input: String, orig: String) -> common::InputField<Option<u8>> {
+    let parsed = if input.is_empty() {
+        Some(None)
+    } else {
+        input.parse::<u8>().ok().filter(|v| *v > 0).map(Some)
+    };
+    common::InputField {
+        error: parsed.is_none().then(|| ERROR_INVALID_SKINFOLD.to_string()),
+        input,
+        parsed,
+        orig,
+    }
+}
+
 enum Dialog {
     Hidden,
     AddBodyFat(Form),
     EditBodyFat(Form),
     DeleteBodyFat(NaiveDate),
+    RestoreFromTrash(web_app::trash::Entry),
 }
 
 struct Form {
@@ -106,11 +283,23 @@ pub enum Msg {
 
     SaveBodyFat,
     DeleteBodyFat(NaiveDate),
+    RestoreFromTrash(web_app::trash::Entry),
+    ConfirmRestoreFromTrash,
+    Retry,
     DataEvent(data::Event),
 
-    ChangeInterval(NaiveDate, NaiveDate),
+    ChangeInterval(NaiveDate, NaiveDate, Option<domain::DefaultInterval>),
+    ToggleDataTables,
+    SelectEstimator(Estimator),
+    SelectSite(Site),
+
+    ToggleInlineEdit(NaiveDate),
+    InlineSiteChanged(Site, String),
+    SaveInlineEdit,
 }
 
+const ERROR_INVALID_SKINFOLD: &str = "The value must be a whole number between 1 and 255";
+
 pub fn update(
     msg: Msg,
     model: &mut Model,
@@ -119,7 +308,7 @@ pub fn update(
 ) {
     match msg {
         Msg::ShowAddBodyFatDialog => {
-            let local = Local::now().date_naive();
+            let local = web_app::clock::today();
             model.dialog = Dialog::AddBodyFat(Form {
                 date: (
                     local.to_string(),
@@ -228,7 +417,10 @@ pub fn update(
                     Err(_) => form.date = (date, None),
                 }
             }
-            Dialog::Hidden | Dialog::EditBodyFat(_) | Dialog::DeleteBodyFat(_) => {
+            Dialog::Hidden
+            | Dialog::EditBodyFat(_)
+            | Dialog::DeleteBodyFat(_)
+            | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -248,7 +440,7 @@ pub fn update(
                     Err(_) => form.chest = (chest, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeleteBodyFat(_) => {
+            Dialog::Hidden | Dialog::DeleteBodyFat(_) | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -268,7 +460,7 @@ pub fn update(
                     Err(_) => form.abdominal = (abdominal, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeleteBodyFat(_) => {
+            Dialog::Hidden | Dialog::DeleteBodyFat(_) | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -288,7 +480,7 @@ pub fn update(
                     Err(_) => form.thigh = (thigh, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeleteBodyFat(_) => {
+            Dialog::Hidden | Dialog::DeleteBodyFat(_) | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -308,7 +500,7 @@ pub fn update(
                     Err(_) => form.tricep = (tricep, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeleteBodyFat(_) => {
+            Dialog::Hidden | Dialog::DeleteBodyFat(_) | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -328,7 +520,7 @@ pub fn update(
                     Err(_) => form.subscapular = (subscapular, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeleteBodyFat(_) => {
+            Dialog::Hidden | Dialog::DeleteBodyFat(_) | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -348,7 +540,7 @@ pub fn update(
                     Err(_) => form.suprailiac = (suprailiac, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeleteBodyFat(_) => {
+            Dialog::Hidden | Dialog::DeleteBodyFat(_) | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -368,7 +560,7 @@ pub fn update(
                     Err(_) => form.midaxillary = (midaxillary, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeleteBodyFat(_) => {
+            Dialog::Hidden | Dialog::DeleteBodyFat(_) | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -400,7 +592,7 @@ pub fn update(
                         midaxillary: form.midaxillary.1,
                     }));
                 }
-                Dialog::Hidden | Dialog::DeleteBodyFat(_) => {
+                Dialog::Hidden | Dialog::DeleteBodyFat(_) | Dialog::RestoreFromTrash(_) => {
                     panic!();
                 }
             };
@@ -409,10 +601,26 @@ pub fn update(
             model.loading = true;
             orders.notify(data::Msg::DeleteBodyFat(date));
         }
+        Msg::RestoreFromTrash(entry) => {
+            if data_model.body_fat.contains_key(&entry.date()) {
+                model.dialog = Dialog::RestoreFromTrash(entry);
+            } else {
+                orders.notify(data::Msg::RestoreFromTrash(entry));
+            }
+        }
+        Msg::ConfirmRestoreFromTrash => {
+            if let Dialog::RestoreFromTrash(entry) = &model.dialog {
+                model.loading = true;
+                orders.notify(data::Msg::RestoreFromTrash(entry.clone()));
+            }
+        }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadBodyFat);
+        }
         Msg::DataEvent(event) => {
             model.loading = false;
             match event {
-                data::Event::DataChanged => {
+                data::Event::BodyFatChanged => {
                     model.interval = domain::init_interval(
                         &data_model
                             .body_fat
@@ -420,20 +628,61 @@ pub fn update(
                             .copied()
                             .collect::<Vec<NaiveDate>>(),
                         domain::DefaultInterval::_3M,
+                        data_model.settings.initial_interval(),
+                        &web_app::clock::BrowserClock,
                     );
                 }
                 data::Event::BodyFatCreatedOk
                 | data::Event::BodyFatReplacedOk
                 | data::Event::BodyFatDeletedOk => {
-                    orders.skip().send_msg(Msg::CloseBodyFatDialog);
+                    if let Some(date) = model.inline_edit.as_ref().map(|e| e.date) {
+                        model.inline_edit = data_model.body_fat.get(&date).map(InlineEdit::new);
+                    }
+                    if !matches!(model.dialog, Dialog::Hidden) {
+                        orders.skip().send_msg(Msg::CloseBodyFatDialog);
+                    }
                 }
                 _ => {}
             };
         }
 
-        Msg::ChangeInterval(first, last) => {
+        Msg::ChangeInterval(first, last, selected_interval) => {
             model.interval.first = first;
             model.interval.last = last;
+            if let Some(selected_interval) = selected_interval {
+                orders.notify(data::Msg::SetInterval(selected_interval));
+            }
+        }
+        Msg::ToggleDataTables => {
+            orders.notify(data::Msg::ToggleDataTables(web_app::chart::Page::BodyFat));
+        }
+        Msg::SelectEstimator(estimator) => {
+            model.estimator = estimator;
+        }
+        Msg::SelectSite(site) => {
+            model.site = site;
+        }
+
+        Msg::ToggleInlineEdit(date) => {
+            model.inline_edit = if model.inline_edit.as_ref().map(|e| e.date) == Some(date) {
+                None
+            } else {
+                data_model.body_fat.get(&date).map(InlineEdit::new)
+            };
+        }
+        Msg::InlineSiteChanged(site, input) => {
+            if let Some(inline_edit) = &mut model.inline_edit {
+                let field = inline_edit.field_mut(site);
+                *field = skinfold_field(input, field.orig.clone());
+            }
+        }
+        Msg::SaveInlineEdit => {
+            if let Some(inline_edit) = &model.inline_edit {
+                if inline_edit.is_valid() {
+                    model.loading = true;
+                    orders.notify(data::Msg::ReplaceBodyFat(inline_edit.body_fat()));
+                }
+            }
         }
     }
 }
@@ -443,8 +692,8 @@ pub fn update(
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.body_fat.is_empty() && data_model.loading_body_fat {
-        common::view_page_loading()
+    if let Some(node) = common::view_resource_unavailable(&data_model.body_fat, |_| Msg::Retry) {
+        node
     } else {
         let dates = data_model.body_fat.values().map(|bf| bf.date);
         let body_fat_interval = domain::Interval {
@@ -460,7 +709,9 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
             common::view_interval_buttons(&model.interval, &body_fat_interval, Msg::ChangeInterval),
             view_chart(model, data_model),
             view_calendar(data_model, &model.interval),
+            view_site_chart(model, data_model),
             view_table(model, data_model),
+            view_trash(data_model),
             common::view_fab("plus", |_| Msg::ShowAddBodyFatDialog),
         ]
     }
@@ -491,11 +742,20 @@ fn view_body_fat_dialog(dialog: &Dialog, loading: bool, sex: u8) -> Node<Msg> {
                 loading,
             );
         }
+        Dialog::RestoreFromTrash(entry) => {
+            return common::view_restore_confirmation_dialog(
+                "body fat entry",
+                entry.date(),
+                &ev(Ev::Click, |_| Msg::ConfirmRestoreFromTrash),
+                &ev(Ev::Click, |_| Msg::CloseBodyFatDialog),
+                loading,
+            );
+        }
         Dialog::Hidden => {
             return empty![];
         }
     }
-    let today = Local::now().date_naive();
+    let today = web_app::clock::today();
     let date_valid = form.date.1.map_or(false, |d| d <= today);
     let save_disabled = loading || !form.is_valid() || !date_valid;
     common::view_dialog(
@@ -696,6 +956,7 @@ fn view_body_fat_form_field(
     message: impl FnOnce(std::string::String) -> Msg + 'static + Clone,
     save_disabled: bool,
 ) -> Node<Msg> {
+    let invalid = field.1.is_none() && !field.0.is_empty();
     div![
         C!["field"],
         label![C!["label"], label],
@@ -712,7 +973,7 @@ fn view_body_fat_form_field(
             }),
             input![
                 C!["input"],
-                C![IF![field.1.is_none() && !field.0.is_empty() => "is-danger"]],
+                C![IF![invalid => "is-danger"]],
                 attrs! {
                     At::from("inputmode") => "numeric",
                     At::Value => field.0,
@@ -720,17 +981,22 @@ fn view_body_fat_form_field(
             ],
             span![C!["icon"], C!["is-small"], C!["is-right"], "mm"],
         ],
-        p![C!["help"], help]
+        if invalid {
+            p![C!["help"], C!["is-danger"], ERROR_INVALID_SKINFOLD]
+        } else {
+            p![C!["help"], help]
+        }
     ]
 }
 
-fn view_chart(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
-    let body_fat = data_model
-        .body_fat
-        .values()
-        .filter(|bf| bf.date >= model.interval.first && bf.date <= model.interval.last)
-        .collect::<Vec<_>>();
+fn data_table(data_model: &data::Model) -> common::DataTable<Msg> {
+    common::DataTable {
+        shown: data_model.show_data_tables(web_app::chart::Page::BodyFat),
+        toggle: || Msg::ToggleDataTables,
+    }
+}
 
+fn view_chart(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
     let avg_body_weight = data_model
         .avg_body_weight
         .values()
@@ -759,67 +1025,108 @@ fn view_chart(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
 
     let sex = data_model.session.as_ref().unwrap().sex;
 
-    let body_fat_jp7 = body_fat
-        .iter()
-        .filter_map(|bf| bf.jp7(sex).map(|jp7| (bf.date, jp7)))
-        .collect::<Vec<_>>();
+    let body_fat = match model.estimator {
+        Estimator::JP3 => data_model
+            .body_fat
+            .values()
+            .filter(|bf| bf.date >= model.interval.first && bf.date <= model.interval.last)
+            .filter_map(|bf| {
+                bf.jp3(sex, data_model.age(bf.date))
+                    .map(|jp3| (bf.date, jp3))
+            })
+            .collect::<Vec<_>>(),
+        Estimator::JP7 => data_model
+            .body_fat
+            .values()
+            .filter(|bf| bf.date >= model.interval.first && bf.date <= model.interval.last)
+            .filter_map(|bf| {
+                bf.jp7(sex, data_model.age(bf.date))
+                    .map(|jp7| (bf.date, jp7))
+            })
+            .collect::<Vec<_>>(),
+        Estimator::Navy => data_model
+            .body_measurement
+            .values()
+            .filter(|bm| bm.date >= model.interval.first && bm.date <= model.interval.last)
+            .filter_map(|bm| {
+                bm.navy(sex, data_model.body_height()?)
+                    .map(|navy| (bm.date, navy))
+            })
+            .collect::<Vec<_>>(),
+    };
 
-    let body_fat_jp3 = body_fat
-        .iter()
-        .filter_map(|bf| bf.jp3(sex).map(|jp3| (bf.date, jp3)))
-        .collect::<Vec<_>>();
+    let label = format!("{} (%)", model.estimator.name());
 
     nodes![
-        IF![
-            !body_fat_jp3.is_empty() =>
-            common::view_chart(
-                vec![
-                    ("JP3 (%)", web_app::chart::COLOR_BODY_FAT_JP3, web_app::chart::OPACITY_LINE),
-                    ("Weight (kg)", web_app::chart::COLOR_BODY_WEIGHT, web_app::chart::OPACITY_LINE),
-                ]
-                .as_slice(),
-                web_app::chart::plot(
-                    &[
-                        body_weight_plot_data.clone(),
-                        avg_body_weight_plot_data.clone(),
-                        web_app::chart::PlotData {
-                            values_high: body_fat_jp3,
-                            values_low: None,
-                            plots: web_app::chart::plot_line(web_app::chart::COLOR_BODY_FAT_JP3),
-                            params: web_app::chart::PlotParams::default(),
-                        },
-                    ],
-                    &model.interval,
-                    data_model.theme(),
-                ),
-                true,
-            )
+        div![
+            C!["field"],
+            C!["has-addons"],
+            C!["has-addons-centered"],
+            [Estimator::JP3, Estimator::JP7, Estimator::Navy]
+                .iter()
+                .map(|estimator| {
+                    let estimator = *estimator;
+                    p![
+                        C!["control"],
+                        a![
+                            C!["button"],
+                            C!["is-small"],
+                            C![IF![estimator == model.estimator => "is-link"]],
+                            ev(Ev::Click, move |_| Msg::SelectEstimator(estimator)),
+                            estimator.name(),
+                        ]
+                    ]
+                })
+                .collect::<Vec<_>>()
         ],
         IF![
-            !body_fat_jp7.is_empty() =>
-            common::view_chart(
+            model.estimator == Estimator::Navy && data_model.body_height().is_none() =>
+            div![
+                C!["is-size-7"],
+                C!["block"],
+                C!["has-text-centered"],
+                C!["mb-4"],
+                "Set your body height in the settings to enable the Navy estimate.",
+            ]
+        ],
+        common::view_chart(
+            vec![
+                (
+                    "Weight (kg)",
+                    web_app::chart::COLOR_BODY_WEIGHT,
+                    web_app::chart::OPACITY_AREA
+                ),
+                (
+                    "Avg. weight (kg)",
+                    web_app::chart::COLOR_AVG_BODY_WEIGHT,
+                    web_app::chart::OPACITY_LINE
+                ),
+                (
+                    label.as_str(),
+                    model.estimator.color(),
+                    web_app::chart::OPACITY_LINE
+                ),
+            ]
+            .as_slice(),
+            &if body_fat.is_empty() {
+                vec![]
+            } else {
                 vec![
-                    ("JP7 (%)", web_app::chart::COLOR_BODY_FAT_JP7, web_app::chart::OPACITY_LINE),
-                    ("Weight (kg)", web_app::chart::COLOR_BODY_WEIGHT, web_app::chart::OPACITY_LINE),
+                    body_weight_plot_data,
+                    avg_body_weight_plot_data,
+                    web_app::chart::PlotData {
+                        values_high: body_fat,
+                        values_low: None,
+                        plots: web_app::chart::plot_line(model.estimator.color()),
+                        params: web_app::chart::PlotParams::default(),
+                    },
                 ]
-                .as_slice(),
-                web_app::chart::plot(
-                    &[
-                        body_weight_plot_data,
-                        avg_body_weight_plot_data,
-                        web_app::chart::PlotData {
-                            values_high: body_fat_jp7,
-                            values_low: None,
-                            plots: web_app::chart::plot_line(web_app::chart::COLOR_BODY_FAT_JP7),
-                            params: web_app::chart::PlotParams::default(),
-                        },
-                    ],
-                    &model.interval,
-                    data_model.theme(),
-                ),
-                true,
-            )
-        ]
+            },
+            &model.interval,
+            &data_model.chart_style(),
+            data_table(data_model),
+            true,
+        )
     ]
 }
 
@@ -829,7 +1136,7 @@ fn view_calendar(data_model: &data::Model, interval: &domain::Interval) -> Node<
         .body_fat
         .values()
         .filter(|bf| (interval.first..=interval.last).contains(&bf.date))
-        .filter_map(|bf| bf.jp3(sex))
+        .filter_map(|bf| bf.jp3(sex, data_model.age(bf.date)))
         .collect::<Vec<_>>();
     let min = body_fat_values
         .iter()
@@ -848,7 +1155,7 @@ fn view_calendar(data_model: &data::Model, interval: &domain::Interval) -> Node<
             .values()
             .filter(|bf| (interval.first..=interval.last).contains(&bf.date))
             .filter_map(|bf| {
-                bf.jp3(sex).map(|jp3| {
+                bf.jp3(sex, data_model.age(bf.date)).map(|jp3| {
                     (
                         bf.date,
                         web_app::chart::COLOR_BODY_FAT_JP3,
@@ -865,8 +1172,91 @@ fn view_calendar(data_model: &data::Model, interval: &domain::Interval) -> Node<
     )
 }
 
+fn view_site_chart(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
+    let sex = data_model.session.as_ref().unwrap().sex;
+    let values = data_model
+        .body_fat
+        .values()
+        .filter(|bf| bf.date >= model.interval.first && bf.date <= model.interval.last)
+        .filter_map(|bf| model.site.value(bf).map(|v| (bf.date, f32::from(v))))
+        .collect::<Vec<_>>();
+    let average = domain::centered_moving_average(&values, &model.interval, 3);
+    let label = format!("{} (mm)", model.site.name());
+    let avg_label = format!("Avg. {} (mm)", model.site.name().to_lowercase());
+
+    nodes![
+        div![
+            C!["field"],
+            C!["has-addons"],
+            C!["has-addons-centered"],
+            C!["is-flex-wrap-wrap"],
+            C!["mt-5"],
+            Site::ordered(sex)
+                .iter()
+                .map(|site| {
+                    let site = *site;
+                    p![
+                        C!["control"],
+                        a![
+                            C!["button"],
+                            C!["is-small"],
+                            C![IF![site == model.site => "is-link"]],
+                            ev(Ev::Click, move |_| Msg::SelectSite(site)),
+                            site.name(),
+                        ]
+                    ]
+                })
+                .collect::<Vec<_>>()
+        ],
+        common::view_chart(
+            vec![
+                (
+                    label.as_str(),
+                    web_app::chart::COLOR_SKINFOLD,
+                    web_app::chart::OPACITY_AREA,
+                ),
+                (
+                    avg_label.as_str(),
+                    web_app::chart::COLOR_AVG_SKINFOLD,
+                    web_app::chart::OPACITY_LINE,
+                ),
+            ]
+            .as_slice(),
+            &if values.is_empty() {
+                vec![]
+            } else {
+                std::iter::once(web_app::chart::PlotData {
+                    values_high: values,
+                    values_low: None,
+                    plots: vec![web_app::chart::PlotType::Circle(
+                        web_app::chart::COLOR_SKINFOLD,
+                        web_app::chart::OPACITY_AREA,
+                        web_app::chart::WIDTH_LINE,
+                    )],
+                    params: web_app::chart::PlotParams::default(),
+                })
+                .chain(average.into_iter().map(|values| web_app::chart::PlotData {
+                    values_high: values,
+                    values_low: None,
+                    plots: web_app::chart::plot_line(web_app::chart::COLOR_AVG_SKINFOLD),
+                    params: web_app::chart::PlotParams::default(),
+                }))
+                .collect::<Vec<_>>()
+            },
+            &model.interval,
+            &data_model.chart_style(),
+            data_table(data_model),
+            true,
+        )
+    ]
+}
+
 fn view_table(model: &Model, data_model: &data::Model) -> Node<Msg> {
     let sex = data_model.session.as_ref().unwrap().sex;
+    let body_height = data_model.body_height();
+    let separator = data_model.settings.decimal_separator;
+    let sites = Site::ordered(sex);
+    let columns = sites.len() + if body_height.is_some() { 5 } else { 4 };
     div![
         C!["table-container"],
         C!["mt-4"],
@@ -876,30 +1266,15 @@ fn view_table(model: &Model, data_model: &data::Model) -> Node<Msg> {
             C!["is-hoverable"],
             C!["has-text-centered"],
             thead![tr![
+                th![],
                 th!["Date"],
                 th!["JP3 (%)"],
                 th!["JP7 (%)"],
-                if sex == 0 {
-                    nodes![
-                        th!["Tricep (mm)"],
-                        th!["Suprailiac (mm)"],
-                        th!["Thigh (mm)"],
-                        th!["Chest (mm)"],
-                        th!["Abdominal (mm)"],
-                        th!["Subscapular (mm)"],
-                        th!["Midaxillary (mm)"],
-                    ]
-                } else {
-                    nodes![
-                        th!["Chest (mm)"],
-                        th!["Abdominal (mm)"],
-                        th!["Thigh (mm)"],
-                        th!["Tricep (mm)"],
-                        th!["Subscapular (mm)"],
-                        th!["Suprailiac (mm)"],
-                        th!["Midaxillary (mm)"],
-                    ]
-                },
+                IF![body_height.is_some() => th!["Navy (%)"]],
+                sites
+                    .iter()
+                    .map(|site| th![format!("{} (mm)", site.name())])
+                    .collect::<Vec<_>>(),
                 th![]
             ]],
             tbody![&data_model
@@ -907,54 +1282,181 @@ fn view_table(model: &Model, data_model: &data::Model) -> Node<Msg> {
                 .values()
                 .rev()
                 .filter(|bf| bf.date >= model.interval.first && bf.date <= model.interval.last)
-                .map(|bf| {
+                .flat_map(|bf| {
                     let date = bf.date;
-                    tr![
-                        td![span![
-                            style! {St::WhiteSpace => "nowrap" },
-                            bf.date.to_string(),
-                        ]],
-                        td![common::value_or_dash(bf.jp3(sex))],
-                        td![common::value_or_dash(bf.jp7(sex))],
-                        if sex == 0 {
-                            nodes![
-                                td![common::value_or_dash(bf.tricep)],
-                                td![common::value_or_dash(bf.suprailiac)],
-                                td![common::value_or_dash(bf.thigh)],
-                                td![common::value_or_dash(bf.chest)],
-                                td![common::value_or_dash(bf.abdominal)],
-                                td![common::value_or_dash(bf.subscapular)],
-                                td![common::value_or_dash(bf.midaxillary)],
-                            ]
-                        } else {
-                            nodes![
-                                td![common::value_or_dash(bf.chest)],
-                                td![common::value_or_dash(bf.abdominal)],
-                                td![common::value_or_dash(bf.thigh)],
-                                td![common::value_or_dash(bf.tricep)],
-                                td![common::value_or_dash(bf.subscapular)],
-                                td![common::value_or_dash(bf.suprailiac)],
-                                td![common::value_or_dash(bf.midaxillary)],
-                            ]
-                        },
-                        td![p![
-                            C!["is-flex is-flex-wrap-nowrap"],
-                            a![
-                                C!["icon"],
-                                C!["mr-1"],
-                                ev(Ev::Click, move |_| Msg::ShowEditBodyFatDialog(date)),
-                                i![C!["fas fa-edit"]]
-                            ],
-                            a![
+                    let age = data_model.age(date);
+                    let inline_edit = model.inline_edit.as_ref().filter(|e| e.date == date);
+                    nodes![
+                        tr![
+                            td![a![
                                 C!["icon"],
-                                C!["ml-1"],
-                                ev(Ev::Click, move |_| Msg::ShowDeleteBodyFatDialog(date)),
-                                i![C!["fas fa-times"]]
-                            ]
-                        ]]
+                                ev(Ev::Click, move |_| Msg::ToggleInlineEdit(date)),
+                                i![C![if inline_edit.is_some() {
+                                    "fas fa-chevron-down"
+                                } else {
+                                    "fas fa-chevron-right"
+                                }]]
+                            ]],
+                            td![span![
+                                style! {St::WhiteSpace => "nowrap" },
+                                bf.date.to_string(),
+                            ]],
+                            td![common::value_or_dash(bf.jp3(sex, age), separator)],
+                            td![common::value_or_dash(bf.jp7(sex, age), separator)],
+                            IF![body_height.is_some() => td![common::value_or_dash(
+                                data_model
+                                    .body_measurement
+                                    .get(&date)
+                                    .and_then(|bm| bm.navy(sex, body_height?)),
+                                separator
+                            )]],
+                            sites
+                                .iter()
+                                .map(|site| td![common::integer_or_dash(site.value(bf))])
+                                .collect::<Vec<_>>(),
+                            td![p![
+                                C!["is-flex is-flex-wrap-nowrap"],
+                                a![
+                                    C!["icon"],
+                                    C!["mr-1"],
+                                    ev(Ev::Click, move |_| Msg::ShowEditBodyFatDialog(date)),
+                                    i![C!["fas fa-edit"]]
+                                ],
+                                a![
+                                    C!["icon"],
+                                    C!["ml-1"],
+                                    ev(Ev::Click, move |_| Msg::ShowDeleteBodyFatDialog(date)),
+                                    i![C!["fas fa-times"]]
+                                ]
+                            ]]
+                        ],
+                        inline_edit.map(|inline_edit| tr![td![
+                            attrs! {At::ColSpan => columns},
+                            view_inline_edit(
+                                inline_edit,
+                                &sites,
+                                sex,
+                                age,
+                                separator,
+                                model.loading
+                            )
+                        ]])
                     ]
                 })
                 .collect::<Vec<_>>()],
         ]
     ]
 }
+
+fn view_inline_edit(
+    inline_edit: &InlineEdit,
+    sites: &[Site],
+    sex: u8,
+    age: u32,
+    separator: web_app::format::DecimalSeparator,
+    loading: bool,
+) -> Node<Msg> {
+    let body_fat = inline_edit.body_fat();
+    let save_disabled = loading || !inline_edit.is_valid() || !inline_edit.changed();
+    div![
+        C!["has-text-left"],
+        div![
+            C!["columns"],
+            C!["is-multiline"],
+            C!["is-mobile"],
+            sites.iter().map(|site| {
+                let site = *site;
+                let field = inline_edit.field(site);
+                div![
+                    C!["column"],
+                    C!["is-one-quarter-tablet"],
+                    C!["is-half-mobile"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], C!["is-small"], site.name()],
+                        div![
+                            C!["control"],
+                            C!["has-icons-right"],
+                            input_ev(Ev::Input, move |input| Msg::InlineSiteChanged(site, input)),
+                            keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+                                IF!(
+                                    not(save_disabled)
+                                        && keyboard_event.key_code() == common::ENTER_KEY => {
+                                        Msg::SaveInlineEdit
+                                    }
+                                )
+                            }),
+                            input![
+                                C!["input"],
+                                C!["is-small"],
+                                C![IF![!field.valid() => "is-danger"]],
+                                attrs! {
+                                    At::from("inputmode") => "numeric",
+                                    At::Value => field.input,
+                                }
+                            ],
+                            span![C!["icon"], C!["is-small"], C!["is-right"], "mm"],
+                        ],
+                        common::view_input_error(field),
+                    ]
+                ]
+            })
+        ],
+        div![
+            C!["is-flex"],
+            C!["is-justify-content-space-between"],
+            C!["is-align-items-center"],
+            div![
+                span![
+                    C!["mr-4"],
+                    format!(
+                        "JP3: {} %",
+                        common::value_or_dash(body_fat.jp3(sex, age), separator)
+                    )
+                ],
+                span![format!(
+                    "JP7: {} %",
+                    common::value_or_dash(body_fat.jp7(sex, age), separator)
+                )],
+            ],
+            button![
+                C!["button"],
+                C!["is-small"],
+                C!["is-primary"],
+                C![IF![loading => "is-loading"]],
+                attrs![
+                    At::Disabled => save_disabled.as_at_value(),
+                ],
+                ev(Ev::Click, |_| Msg::SaveInlineEdit),
+                "Save",
+            ],
+        ]
+    ]
+}
+
+fn view_trash(data_model: &data::Model) -> Node<Msg> {
+    let sex = data_model.session.as_ref().map_or(0, |s| s.sex);
+    common::view_trash(
+        data_model
+            .trash
+            .body_fat()
+            .map(|(bf, item)| {
+                let age = data_model.age(bf.date);
+                (
+                    item,
+                    bf.jp7(sex, age).or_else(|| bf.jp3(sex, age)).map_or_else(
+                        || "-".into(),
+                        |value| {
+                            web_app::format::format_percentage(
+                                value,
+                                web_app::format::PRECISION_PERCENTAGE,
+                                data_model.settings.decimal_separator,
+                            )
+                        },
+                    ),
+                )
+            })
+            .collect(),
+        Msg::RestoreFromTrash,
+    )
+}
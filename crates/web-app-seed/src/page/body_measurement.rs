@@ -0,0 +1,702 @@
+use chrono::prelude::*;
+use seed::{prelude::*, *};
+use valens_domain as domain;
+use valens_web_app as web_app;
+
+use crate::{common, data};
+
+// ------ ------
+//     Init
+// ------ ------
+
+pub fn init(
+    mut url: Url,
+    orders: &mut impl Orders<Msg>,
+    data_model: &data::Model,
+    navbar: &mut crate::Navbar,
+) -> Model {
+    if url.next_hash_path_part() == Some("add") {
+        orders.send_msg(Msg::ShowAddBodyMeasurementDialog);
+    }
+
+    orders.subscribe(Msg::DataEvent);
+
+    navbar.title = String::from("Body measurements");
+
+    let interval = domain::init_interval(
+        &data_model
+            .body_measurement
+            .keys()
+            .copied()
+            .collect::<Vec<NaiveDate>>(),
+        domain::DefaultInterval::_3M,
+        data_model.settings.initial_interval(),
+        &web_app::clock::BrowserClock,
+    );
+
+    Model {
+        averages: Averages::new(&interval, data_model),
+        interval,
+        dialog: Dialog::Hidden,
+        loading: false,
+    }
+}
+
+// ------ ------
+//     Model
+// ------ ------
+
+pub struct Model {
+    interval: domain::Interval,
+    averages: Averages,
+    dialog: Dialog,
+    loading: bool,
+}
+
+enum Dialog {
+    Hidden,
+    AddBodyMeasurement(Form),
+    EditBodyMeasurement(Form),
+    DeleteBodyMeasurement(NaiveDate),
+}
+
+struct Form {
+    date: (String, Option<NaiveDate>),
+    waist: (String, Option<f32>),
+    hips: (String, Option<f32>),
+    neck: (String, Option<f32>),
+    upper_arm: (String, Option<f32>),
+    thigh: (String, Option<f32>),
+    chest: (String, Option<f32>),
+}
+
+impl Form {
+    fn fields(&self) -> [&(String, Option<f32>); 6] {
+        [
+            &self.waist,
+            &self.hips,
+            &self.neck,
+            &self.upper_arm,
+            &self.thigh,
+            &self.chest,
+        ]
+    }
+
+    fn field_mut(&mut self, measurement: Measurement) -> &mut (String, Option<f32>) {
+        match measurement {
+            Measurement::Waist => &mut self.waist,
+            Measurement::Hips => &mut self.hips,
+            Measurement::Neck => &mut self.neck,
+            Measurement::UpperArm => &mut self.upper_arm,
+            Measurement::Thigh => &mut self.thigh,
+            Measurement::Chest => &mut self.chest,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.date.1.is_some()
+            && self.fields().iter().any(|f| f.1.is_some())
+            && self
+                .fields()
+                .iter()
+                .all(|f| f.1.is_some() || f.0.is_empty())
+    }
+
+    fn body_measurement(&self) -> domain::BodyMeasurement {
+        domain::BodyMeasurement {
+            date: self.date.1.unwrap(),
+            waist: self.waist.1,
+            hips: self.hips.1,
+            neck: self.neck.1,
+            upper_arm: self.upper_arm.1,
+            thigh: self.thigh.1,
+            chest: self.chest.1,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Measurement {
+    Waist,
+    Hips,
+    Neck,
+    UpperArm,
+    Thigh,
+    Chest,
+}
+
+impl Measurement {
+    const ALL: [Measurement; 6] = [
+        Measurement::Waist,
+        Measurement::Hips,
+        Measurement::Neck,
+        Measurement::UpperArm,
+        Measurement::Thigh,
+        Measurement::Chest,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Measurement::Waist => "Waist",
+            Measurement::Hips => "Hips",
+            Measurement::Neck => "Neck",
+            Measurement::UpperArm => "Upper arm",
+            Measurement::Thigh => "Thigh",
+            Measurement::Chest => "Chest",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Measurement::Waist => "Horizontally at the level of the belly button",
+            Measurement::Hips => "Horizontally at the widest part of the buttocks",
+            Measurement::Neck => "Just below the larynx",
+            Measurement::UpperArm => "Midway between shoulder and elbow, arm relaxed",
+            Measurement::Thigh => "Midway between knee cap and top of thigh",
+            Measurement::Chest => "Horizontally at the level of the nipples",
+        }
+    }
+
+    fn value(self, body_measurement: &domain::BodyMeasurement) -> Option<f32> {
+        match self {
+            Measurement::Waist => body_measurement.waist,
+            Measurement::Hips => body_measurement.hips,
+            Measurement::Neck => body_measurement.neck,
+            Measurement::UpperArm => body_measurement.upper_arm,
+            Measurement::Thigh => body_measurement.thigh,
+            Measurement::Chest => body_measurement.chest,
+        }
+    }
+}
+
+/// Moving averages of all measurements in the displayed interval, which are cached to prevent their
+/// recalculation on changes of unrelated state.
+struct Averages {
+    /// Change counter of the body measurements and displayed interval.
+    key: (u32, NaiveDate, NaiveDate),
+    /// Moving averages in the order of `Measurement::ALL`.
+    values: Vec<Vec<Vec<(NaiveDate, f32)>>>,
+}
+
+impl Averages {
+    fn new(interval: &domain::Interval, data_model: &data::Model) -> Self {
+        Self {
+            key: Self::key(interval, data_model),
+            values: Measurement::ALL
+                .iter()
+                .map(|measurement| {
+                    domain::centered_moving_average(
+                        &measurement_values(*measurement, interval, data_model),
+                        interval,
+                        3,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn key(interval: &domain::Interval, data_model: &data::Model) -> (u32, NaiveDate, NaiveDate) {
+        (
+            data_model.changes.body_measurement,
+            interval.first,
+            interval.last,
+        )
+    }
+}
+
+fn measurement_values(
+    measurement: Measurement,
+    interval: &domain::Interval,
+    data_model: &data::Model,
+) -> Vec<(NaiveDate, f32)> {
+    data_model
+        .body_measurement
+        .values()
+        .filter(|bm| bm.date >= interval.first && bm.date <= interval.last)
+        .filter_map(|bm| measurement.value(bm).map(|v| (bm.date, v)))
+        .collect()
+}
+
+// ------ ------
+//    Update
+// ------ ------
+
+pub enum Msg {
+    ShowAddBodyMeasurementDialog,
+    ShowEditBodyMeasurementDialog(NaiveDate),
+    ShowDeleteBodyMeasurementDialog(NaiveDate),
+    CloseBodyMeasurementDialog,
+
+    DateChanged(String),
+    MeasurementChanged(Measurement, String),
+
+    SaveBodyMeasurement,
+    DeleteBodyMeasurement(NaiveDate),
+    Retry,
+    DataEvent(data::Event),
+
+    ChangeInterval(NaiveDate, NaiveDate, Option<domain::DefaultInterval>),
+    ToggleDataTables,
+}
+
+pub fn update(
+    msg: Msg,
+    model: &mut Model,
+    data_model: &data::Model,
+    orders: &mut impl Orders<Msg>,
+) {
+    match msg {
+        Msg::ShowAddBodyMeasurementDialog => {
+            let local = web_app::clock::today();
+            model.dialog = Dialog::AddBodyMeasurement(Form {
+                date: (
+                    local.to_string(),
+                    if data_model
+                        .body_measurement
+                        .keys()
+                        .all(|date| *date != local)
+                    {
+                        Some(local)
+                    } else {
+                        None
+                    },
+                ),
+                waist: (String::new(), None),
+                hips: (String::new(), None),
+                neck: (String::new(), None),
+                upper_arm: (String::new(), None),
+                thigh: (String::new(), None),
+                chest: (String::new(), None),
+            });
+        }
+        Msg::ShowEditBodyMeasurementDialog(date) => {
+            let body_measurement = &data_model.body_measurement[&date];
+            let field = |value: Option<f32>| {
+                (
+                    if let Some(value) = value {
+                        value.to_string()
+                    } else {
+                        String::new()
+                    },
+                    value,
+                )
+            };
+            model.dialog = Dialog::EditBodyMeasurement(Form {
+                date: (
+                    body_measurement.date.to_string(),
+                    Some(body_measurement.date),
+                ),
+                waist: field(body_measurement.waist),
+                hips: field(body_measurement.hips),
+                neck: field(body_measurement.neck),
+                upper_arm: field(body_measurement.upper_arm),
+                thigh: field(body_measurement.thigh),
+                chest: field(body_measurement.chest),
+            });
+        }
+        Msg::ShowDeleteBodyMeasurementDialog(date) => {
+            model.dialog = Dialog::DeleteBodyMeasurement(date);
+        }
+        Msg::CloseBodyMeasurementDialog => {
+            model.dialog = Dialog::Hidden;
+            Url::go_and_replace(&crate::Urls::new(&data_model.base_url).body_measurement());
+        }
+
+        Msg::DateChanged(date) => match model.dialog {
+            Dialog::AddBodyMeasurement(ref mut form) => {
+                match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(parsed_date) => {
+                        if data_model
+                            .body_measurement
+                            .keys()
+                            .all(|date| *date != parsed_date)
+                        {
+                            form.date = (date, Some(parsed_date));
+                        } else {
+                            form.date = (date, None);
+                        }
+                    }
+                    Err(_) => form.date = (date, None),
+                }
+            }
+            Dialog::Hidden | Dialog::EditBodyMeasurement(_) | Dialog::DeleteBodyMeasurement(_) => {
+                panic!();
+            }
+        },
+        Msg::MeasurementChanged(measurement, value) => match model.dialog {
+            Dialog::AddBodyMeasurement(ref mut form)
+            | Dialog::EditBodyMeasurement(ref mut form) => {
+                let field = form.field_mut(measurement);
+                match value.parse::<f32>() {
+                    Ok(parsed_value) => {
+                        *field = (
+                            value,
+                            if parsed_value > 0.0 {
+                                Some(parsed_value)
+                            } else {
+                                None
+                            },
+                        );
+                    }
+                    Err(_) => *field = (value, None),
+                }
+            }
+            Dialog::Hidden | Dialog::DeleteBodyMeasurement(_) => {
+                panic!();
+            }
+        },
+
+        Msg::SaveBodyMeasurement => {
+            model.loading = true;
+            match model.dialog {
+                Dialog::AddBodyMeasurement(ref mut form) => {
+                    orders.notify(data::Msg::CreateBodyMeasurement(form.body_measurement()));
+                }
+                Dialog::EditBodyMeasurement(ref mut form) => {
+                    orders.notify(data::Msg::ReplaceBodyMeasurement(form.body_measurement()));
+                }
+                Dialog::Hidden | Dialog::DeleteBodyMeasurement(_) => {
+                    panic!();
+                }
+            };
+        }
+        Msg::DeleteBodyMeasurement(date) => {
+            model.loading = true;
+            orders.notify(data::Msg::DeleteBodyMeasurement(date));
+        }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadBodyMeasurement);
+        }
+        Msg::DataEvent(event) => {
+            model.loading = false;
+            match event {
+                data::Event::BodyMeasurementChanged => {
+                    model.interval = domain::init_interval(
+                        &data_model
+                            .body_measurement
+                            .keys()
+                            .copied()
+                            .collect::<Vec<NaiveDate>>(),
+                        domain::DefaultInterval::_3M,
+                        data_model.settings.initial_interval(),
+                        &web_app::clock::BrowserClock,
+                    );
+                }
+                data::Event::BodyMeasurementCreatedOk
+                | data::Event::BodyMeasurementReplacedOk
+                | data::Event::BodyMeasurementDeletedOk => {
+                    orders.skip().send_msg(Msg::CloseBodyMeasurementDialog);
+                }
+                _ => {}
+            };
+            update_averages(model, data_model);
+        }
+
+        Msg::ChangeInterval(first, last, selected_interval) => {
+            model.interval.first = first;
+            model.interval.last = last;
+            update_averages(model, data_model);
+            if let Some(selected_interval) = selected_interval {
+                orders.notify(data::Msg::SetInterval(selected_interval));
+            }
+        }
+        Msg::ToggleDataTables => {
+            orders.notify(data::Msg::ToggleDataTables(
+                web_app::chart::Page::BodyMeasurement,
+            ));
+        }
+    }
+}
+
+fn update_averages(model: &mut Model, data_model: &data::Model) {
+    if model.averages.key != Averages::key(&model.interval, data_model) {
+        model.averages = Averages::new(&model.interval, data_model);
+    }
+}
+
+// ------ ------
+//     View
+// ------ ------
+
+pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    if let Some(node) =
+        common::view_resource_unavailable(&data_model.body_measurement, |_| Msg::Retry)
+    {
+        node
+    } else {
+        let dates = data_model.body_measurement.values().map(|bm| bm.date);
+        let body_measurement_interval = domain::Interval {
+            first: dates.clone().min().unwrap_or_default(),
+            last: dates.max().unwrap_or_default(),
+        };
+        div![
+            view_body_measurement_dialog(&model.dialog, model.loading),
+            common::view_interval_buttons(
+                &model.interval,
+                &body_measurement_interval,
+                Msg::ChangeInterval
+            ),
+            view_charts(model, data_model),
+            view_table(model, data_model),
+            common::view_fab("plus", |_| Msg::ShowAddBodyMeasurementDialog),
+        ]
+    }
+}
+
+fn view_body_measurement_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
+    let title;
+    let form;
+    let date_disabled;
+    match dialog {
+        Dialog::AddBodyMeasurement(ref f) => {
+            title = "Add body measurements";
+            form = f;
+            date_disabled = false;
+        }
+        Dialog::EditBodyMeasurement(ref f) => {
+            title = "Edit body measurements";
+            form = f;
+            date_disabled = true;
+        }
+        Dialog::DeleteBodyMeasurement(date) => {
+            #[allow(clippy::clone_on_copy)]
+            let date = date.clone();
+            return common::view_delete_confirmation_dialog(
+                "body measurement entry",
+                &ev(Ev::Click, move |_| Msg::DeleteBodyMeasurement(date)),
+                &ev(Ev::Click, |_| Msg::CloseBodyMeasurementDialog),
+                loading,
+            );
+        }
+        Dialog::Hidden => {
+            return empty![];
+        }
+    }
+    let today = web_app::clock::today();
+    let date_valid = form.date.1.map_or(false, |d| d <= today);
+    let save_disabled = loading || !form.is_valid() || !date_valid;
+    common::view_dialog(
+        "primary",
+        title,
+        nodes![
+            div![
+                C!["block"],
+                "Measure the circumferences using a tape measure."
+            ],
+            form![
+                attrs! {
+                    At::Action => "javascript:void(0);",
+                    At::OnKeyPress => "if (event.which == 13) return false;"
+                },
+                div![
+                    C!["field"],
+                    label![C!["label"], "Date"],
+                    div![
+                        C!["control"],
+                        input_ev(Ev::Input, Msg::DateChanged),
+                        input![
+                            C!["input"],
+                            C![IF![!date_valid => "is-danger"]],
+                            attrs! {
+                                At::Type => "date",
+                                At::Value => form.date.0,
+                                At::Disabled => date_disabled.as_at_value(),
+                                At::Max => today,
+                            }
+                        ],
+                    ]
+                ],
+                Measurement::ALL
+                    .iter()
+                    .zip(form.fields())
+                    .map(|(measurement, field)| {
+                        view_body_measurement_form_field(*measurement, field, save_disabled)
+                    })
+                    .collect::<Vec<_>>(),
+                div![
+                    C!["field"],
+                    C!["is-grouped"],
+                    C!["is-grouped-centered"],
+                    C!["mt-5"],
+                    div![
+                        C!["control"],
+                        button![
+                            C!["button"],
+                            C!["is-light"],
+                            C!["is-soft"],
+                            ev(Ev::Click, |_| Msg::CloseBodyMeasurementDialog),
+                            "Cancel",
+                        ]
+                    ],
+                    div![
+                        C!["control"],
+                        button![
+                            C!["button"],
+                            C!["is-primary"],
+                            C![IF![loading => "is-loading"]],
+                            attrs![
+                                At::Disabled => save_disabled.as_at_value(),
+                            ],
+                            ev(Ev::Click, |_| Msg::SaveBodyMeasurement),
+                            "Save",
+                        ]
+                    ],
+                ],
+            ]
+        ],
+        &ev(Ev::Click, |_| Msg::CloseBodyMeasurementDialog),
+    )
+}
+
+fn view_body_measurement_form_field(
+    measurement: Measurement,
+    field: &(String, Option<f32>),
+    save_disabled: bool,
+) -> Node<Msg> {
+    div![
+        C!["field"],
+        label![C!["label"], measurement.name()],
+        div![
+            C!["control"],
+            C!["has-icons-right"],
+            input_ev(Ev::Input, move |value| Msg::MeasurementChanged(
+                measurement,
+                value
+            )),
+            keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+                IF!(
+                    not(save_disabled) && keyboard_event.key_code() == common::ENTER_KEY => {
+                        Msg::SaveBodyMeasurement
+                    }
+                )
+            }),
+            input![
+                C!["input"],
+                C![IF![field.1.is_none() && !field.0.is_empty() => "is-danger"]],
+                attrs! {
+                    At::from("inputmode") => "decimal",
+                    At::Value => field.0,
+                }
+            ],
+            span![C!["icon"], C!["is-small"], C!["is-right"], "cm"],
+        ],
+        p![C!["help"], measurement.description()]
+    ]
+}
+
+fn data_table(data_model: &data::Model) -> common::DataTable<Msg> {
+    common::DataTable {
+        shown: data_model.show_data_tables(web_app::chart::Page::BodyMeasurement),
+        toggle: || Msg::ToggleDataTables,
+    }
+}
+
+fn view_charts(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
+    Measurement::ALL
+        .iter()
+        .zip(&model.averages.values)
+        .filter_map(|(measurement, average)| {
+            let values = measurement_values(*measurement, &model.interval, data_model);
+            if values.is_empty() {
+                return None;
+            }
+            let label = format!("{} (cm)", measurement.name());
+            let avg_label = format!("Avg. {} (cm)", measurement.name().to_lowercase());
+            Some(common::view_chart(
+                vec![
+                    (
+                        label.as_str(),
+                        web_app::chart::COLOR_BODY_MEASUREMENT,
+                        web_app::chart::OPACITY_AREA,
+                    ),
+                    (
+                        avg_label.as_str(),
+                        web_app::chart::COLOR_AVG_BODY_MEASUREMENT,
+                        web_app::chart::OPACITY_LINE,
+                    ),
+                ]
+                .as_slice(),
+                &std::iter::once(web_app::chart::PlotData {
+                    values_high: values,
+                    values_low: None,
+                    plots: vec![web_app::chart::PlotType::Circle(
+                        web_app::chart::COLOR_BODY_MEASUREMENT,
+                        web_app::chart::OPACITY_AREA,
+                        web_app::chart::WIDTH_LINE,
+                    )],
+                    params: web_app::chart::PlotParams::default(),
+                })
+                .chain(average.iter().map(|values| web_app::chart::PlotData {
+                    values_high: values.clone(),
+                    values_low: None,
+                    plots: web_app::chart::plot_line(web_app::chart::COLOR_AVG_BODY_MEASUREMENT),
+                    params: web_app::chart::PlotParams::default(),
+                }))
+                .collect::<Vec<_>>(),
+                &model.interval,
+                &data_model.chart_style(),
+                data_table(data_model),
+                true,
+            ))
+        })
+        .collect()
+}
+
+fn view_table(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    div![
+        C!["table-container"],
+        C!["mt-4"],
+        table![
+            C!["table"],
+            C!["is-fullwidth"],
+            C!["is-hoverable"],
+            C!["has-text-centered"],
+            thead![tr![
+                th!["Date"],
+                Measurement::ALL
+                    .iter()
+                    .map(|m| th![format!("{} (cm)", m.name())])
+                    .collect::<Vec<_>>(),
+                th![]
+            ]],
+            tbody![data_model
+                .body_measurement
+                .values()
+                .rev()
+                .filter(|bm| bm.date >= model.interval.first && bm.date <= model.interval.last)
+                .map(|bm| {
+                    let date = bm.date;
+                    tr![
+                        td![span![
+                            style! {St::WhiteSpace => "nowrap" },
+                            date.to_string(),
+                        ]],
+                        Measurement::ALL
+                            .iter()
+                            .map(|m| td![common::value_or_dash(
+                                m.value(bm),
+                                data_model.settings.decimal_separator
+                            )])
+                            .collect::<Vec<_>>(),
+                        td![p![
+                            C!["is-flex is-flex-wrap-nowrap"],
+                            a![
+                                C!["icon"],
+                                C!["mr-1"],
+                                ev(Ev::Click, move |_| Msg::ShowEditBodyMeasurementDialog(date)),
+                                i![C!["fas fa-edit"]]
+                            ],
+                            a![
+                                C!["icon"],
+                                C!["ml-1"],
+                                ev(Ev::Click, move |_| Msg::ShowDeleteBodyMeasurementDialog(
+                                    date
+                                )),
+                                i![C!["fas fa-times"]]
+                            ]
+                        ]]
+                    ]
+                })],
+        ]
+    ]
+}
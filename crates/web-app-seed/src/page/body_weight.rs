@@ -1,6 +1,4 @@
-use std::collections::BTreeMap;
-
-use chrono::{prelude::*, Duration};
+use chrono::prelude::*;
 use seed::{prelude::*, *};
 use valens_domain as domain;
 use valens_web_app as web_app;
@@ -25,15 +23,20 @@ pub fn init(
 
     navbar.title = String::from("Body weight");
 
+    let interval = domain::init_interval(
+        &data_model
+            .body_weight
+            .keys()
+            .copied()
+            .collect::<Vec<NaiveDate>>(),
+        domain::DefaultInterval::_3M,
+        data_model.settings.initial_interval(),
+        &web_app::clock::BrowserClock,
+    );
+
     Model {
-        interval: domain::init_interval(
-            &data_model
-                .body_weight
-                .keys()
-                .copied()
-                .collect::<Vec<NaiveDate>>(),
-            domain::DefaultInterval::_3M,
-        ),
+        viewport: web_app::chart::Viewport::new(&interval),
+        interval,
         dialog: Dialog::Hidden,
         loading: false,
     }
@@ -45,6 +48,7 @@ pub fn init(
 
 pub struct Model {
     interval: domain::Interval,
+    viewport: web_app::chart::Viewport,
     dialog: Dialog,
     loading: bool,
 }
@@ -54,6 +58,8 @@ enum Dialog {
     AddBodyWeight(Form),
     EditBodyWeight(Form),
     DeleteBodyWeight(NaiveDate),
+    RestoreFromTrash(web_app::trash::Entry),
+    SetGoal(GoalForm),
 }
 
 struct Form {
@@ -61,6 +67,11 @@ struct Form {
     weight: (String, Option<f32>),
 }
 
+struct GoalForm {
+    weight: (String, Option<f32>),
+    direction: domain::BodyWeightGoalDirection,
+}
+
 // ------ ------
 //    Update
 // ------ ------
@@ -76,9 +87,20 @@ pub enum Msg {
 
     SaveBodyWeight,
     DeleteBodyWeight(NaiveDate),
+    RestoreFromTrash(web_app::trash::Entry),
+    ConfirmRestoreFromTrash,
+    Retry,
     DataEvent(data::Event),
 
-    ChangeInterval(NaiveDate, NaiveDate),
+    ShowGoalDialog,
+    GoalWeightChanged(String),
+    GoalDirectionChanged(domain::BodyWeightGoalDirection),
+    SaveGoal,
+    RemoveGoal,
+
+    ChangeInterval(NaiveDate, NaiveDate, Option<domain::DefaultInterval>),
+    ToggleDataTables,
+    ChartInput(web_app::chart::ChartInput),
 }
 
 pub fn update(
@@ -89,7 +111,7 @@ pub fn update(
 ) {
     match msg {
         Msg::ShowAddBodyWeightDialog => {
-            let local = Local::now().date_naive();
+            let local = web_app::clock::today();
             model.dialog = Dialog::AddBodyWeight(Form {
                 date: (
                     local.to_string(),
@@ -135,7 +157,11 @@ pub fn update(
                     Err(_) => form.date = (date, None),
                 }
             }
-            Dialog::Hidden | Dialog::EditBodyWeight(_) | Dialog::DeleteBodyWeight(_) => {
+            Dialog::Hidden
+            | Dialog::EditBodyWeight(_)
+            | Dialog::DeleteBodyWeight(_)
+            | Dialog::RestoreFromTrash(_)
+            | Dialog::SetGoal(_) => {
                 panic!();
             }
         },
@@ -155,7 +181,10 @@ pub fn update(
                     Err(_) => form.weight = (weight, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeleteBodyWeight(_) => {
+            Dialog::Hidden
+            | Dialog::DeleteBodyWeight(_)
+            | Dialog::RestoreFromTrash(_)
+            | Dialog::SetGoal(_) => {
                 panic!();
             }
         },
@@ -175,7 +204,10 @@ pub fn update(
                         weight: form.weight.1.unwrap(),
                     }));
                 }
-                Dialog::Hidden | Dialog::DeleteBodyWeight(_) => {
+                Dialog::Hidden
+                | Dialog::DeleteBodyWeight(_)
+                | Dialog::RestoreFromTrash(_)
+                | Dialog::SetGoal(_) => {
                     panic!();
                 }
             };
@@ -184,10 +216,26 @@ pub fn update(
             model.loading = true;
             orders.notify(data::Msg::DeleteBodyWeight(date));
         }
+        Msg::RestoreFromTrash(entry) => {
+            if data_model.body_weight.contains_key(&entry.date()) {
+                model.dialog = Dialog::RestoreFromTrash(entry);
+            } else {
+                orders.notify(data::Msg::RestoreFromTrash(entry));
+            }
+        }
+        Msg::ConfirmRestoreFromTrash => {
+            if let Dialog::RestoreFromTrash(entry) = &model.dialog {
+                model.loading = true;
+                orders.notify(data::Msg::RestoreFromTrash(entry.clone()));
+            }
+        }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadBodyWeight);
+        }
         Msg::DataEvent(event) => {
             model.loading = false;
             match event {
-                data::Event::DataChanged => {
+                data::Event::BodyWeightChanged => {
                     model.interval = domain::init_interval(
                         &data_model
                             .body_weight
@@ -195,7 +243,10 @@ pub fn update(
                             .copied()
                             .collect::<Vec<NaiveDate>>(),
                         domain::DefaultInterval::_3M,
+                        data_model.settings.initial_interval(),
+                        &web_app::clock::BrowserClock,
                     );
+                    model.viewport.set_bounds(&model.interval);
                 }
                 data::Event::BodyWeightCreatedOk
                 | data::Event::BodyWeightReplacedOk
@@ -206,9 +257,62 @@ pub fn update(
             };
         }
 
-        Msg::ChangeInterval(first, last) => {
+        Msg::ShowGoalDialog => {
+            let goal = data_model.body_weight_goal();
+            model.dialog = Dialog::SetGoal(GoalForm {
+                weight: goal.map_or((String::new(), None), |goal| {
+                    (goal.weight.to_string(), Some(goal.weight))
+                }),
+                direction: goal
+                    .map_or(domain::BodyWeightGoalDirection::Lose, |goal| goal.direction),
+            });
+        }
+        Msg::GoalWeightChanged(weight) => {
+            if let Dialog::SetGoal(ref mut form) = model.dialog {
+                form.weight = match weight.parse::<f32>() {
+                    Ok(parsed_weight) if parsed_weight > 0.0 => (weight, Some(parsed_weight)),
+                    _ => (weight, None),
+                };
+            }
+        }
+        Msg::GoalDirectionChanged(direction) => {
+            if let Dialog::SetGoal(ref mut form) = model.dialog {
+                form.direction = direction;
+            }
+        }
+        Msg::SaveGoal => {
+            if let Dialog::SetGoal(ref form) = model.dialog {
+                if let Some(weight) = form.weight.1 {
+                    orders.notify(data::Msg::SetBodyWeightGoal(Some(domain::BodyWeightGoal {
+                        weight,
+                        direction: form.direction,
+                    })));
+                }
+            }
+            model.dialog = Dialog::Hidden;
+        }
+        Msg::RemoveGoal => {
+            orders.notify(data::Msg::SetBodyWeightGoal(None));
+            model.dialog = Dialog::Hidden;
+        }
+
+        Msg::ChangeInterval(first, last, selected_interval) => {
             model.interval.first = first;
             model.interval.last = last;
+            model.viewport.set_bounds(&model.interval);
+            if let Some(selected_interval) = selected_interval {
+                orders.notify(data::Msg::SetInterval(selected_interval));
+            }
+        }
+        Msg::ToggleDataTables => {
+            orders.notify(data::Msg::ToggleDataTables(
+                web_app::chart::Page::BodyWeight,
+            ));
+        }
+        Msg::ChartInput(input) => {
+            if not(model.viewport.handle(input)) {
+                orders.skip();
+            }
         }
     }
 }
@@ -218,8 +322,8 @@ pub fn update(
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.body_weight.is_empty() && data_model.loading_body_weight {
-        common::view_page_loading()
+    if let Some(node) = common::view_resource_unavailable(&data_model.body_weight, |_| Msg::Retry) {
+        node
     } else {
         let dates = data_model.body_weight.values().map(|bw| bw.date);
         let body_weight_interval = domain::Interval {
@@ -233,9 +337,12 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
                 &body_weight_interval,
                 Msg::ChangeInterval
             ),
+            view_weekly_change(data_model),
+            view_goal(data_model),
             view_chart(model, data_model),
             view_calendar(data_model, &model.interval),
             view_table(model, data_model),
+            view_trash(data_model),
             common::view_fab("plus", |_| Msg::ShowAddBodyWeightDialog),
         ]
     }
@@ -266,11 +373,23 @@ fn view_body_weight_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
                 loading,
             );
         }
+        Dialog::RestoreFromTrash(entry) => {
+            return common::view_restore_confirmation_dialog(
+                "body weight entry",
+                entry.date(),
+                &ev(Ev::Click, |_| Msg::ConfirmRestoreFromTrash),
+                &ev(Ev::Click, |_| Msg::CloseBodyWeightDialog),
+                loading,
+            );
+        }
+        Dialog::SetGoal(ref form) => {
+            return view_goal_dialog(form);
+        }
         Dialog::Hidden => {
             return empty![];
         }
     }
-    let today = Local::now().date_naive();
+    let today = web_app::clock::today();
     let date_valid = form.date.1.map_or(false, |d| d <= today);
     let save_disabled = loading || !date_valid || form.weight.1.is_none();
     common::view_dialog(
@@ -354,15 +473,230 @@ fn view_body_weight_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
     )
 }
 
-fn view_chart(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    let avg_body_weight = data_model
-        .avg_body_weight
-        .values()
-        .filter(|bw| bw.date >= model.interval.first && bw.date <= model.interval.last)
-        .map(|bw| (bw.date, bw.weight))
-        .collect::<Vec<_>>();
+fn view_goal_dialog(form: &GoalForm) -> Node<Msg> {
+    let save_disabled = form.weight.1.is_none();
+    common::view_dialog(
+        "primary",
+        "Body weight goal",
+        nodes![
+            div![
+                C!["field"],
+                label![C!["label"], "Direction"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    [
+                        (domain::BodyWeightGoalDirection::Lose, "Lose"),
+                        (domain::BodyWeightGoalDirection::Gain, "Gain"),
+                    ]
+                    .into_iter()
+                    .map(|(direction, name)| {
+                        p![
+                            C!["control"],
+                            button![
+                                C!["button"],
+                                C![IF![form.direction == direction => "is-link"]],
+                                ev(Ev::Click, move |_| Msg::GoalDirectionChanged(direction)),
+                                name,
+                            ]
+                        ]
+                    }),
+                ],
+            ],
+            div![
+                C!["field"],
+                label![C!["label"], "Weight"],
+                div![
+                    C!["control"],
+                    C!["has-icons-right"],
+                    input_ev(Ev::Input, Msg::GoalWeightChanged),
+                    keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+                        IF!(
+                            not(save_disabled) && keyboard_event.key_code() == common::ENTER_KEY => {
+                                Msg::SaveGoal
+                            }
+                        )
+                    }),
+                    input![
+                        C!["input"],
+                        C![IF![form.weight.1.is_none() => "is-danger"]],
+                        attrs! {
+                            At::from("inputmode") => "numeric",
+                            At::Value => form.weight.0,
+                        }
+                    ],
+                    span![C!["icon"], C!["is-small"], C!["is-right"], "kg"],
+                ],
+            ],
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                C!["mt-5"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-danger"],
+                        C!["is-soft"],
+                        ev(Ev::Click, |_| Msg::RemoveGoal),
+                        "Remove",
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        ev(Ev::Click, |_| Msg::CloseBodyWeightDialog),
+                        "Cancel",
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-primary"],
+                        attrs![
+                            At::Disabled => save_disabled.as_at_value(),
+                        ],
+                        ev(Ev::Click, |_| Msg::SaveGoal),
+                        "Save",
+                    ]
+                ],
+            ],
+        ],
+        &ev(Ev::Click, |_| Msg::CloseBodyWeightDialog),
+    )
+}
 
-    common::view_chart(
+fn view_weekly_change(data_model: &data::Model) -> Node<Msg> {
+    let Some(change) = domain::avg_weekly_change(
+        &data_model.avg_body_weight,
+        data_model.avg_body_weight.values().last(),
+        data::MAX_BODY_WEIGHT_GAP_DAYS,
+    ) else {
+        return empty![];
+    };
+    p![
+        C!["has-text-centered"],
+        C!["mx-2"],
+        C!["mb-2"],
+        span![
+            C!["has-text-weight-bold"],
+            format!(
+                "{} kg/week",
+                web_app::format::format_signed(
+                    change.weight,
+                    web_app::format::PRECISION_WEIGHT,
+                    data_model.settings.decimal_separator
+                )
+            )
+        ],
+        span![
+            C!["is-size-7"],
+            C!["ml-1"],
+            if change.days == 7 {
+                String::from("(based on last week)")
+            } else {
+                format!(
+                    "(based on last {} day{})",
+                    change.days,
+                    if change.days == 1 { "" } else { "s" }
+                )
+            }
+        ],
+    ]
+}
+
+fn view_goal(data_model: &data::Model) -> Node<Msg> {
+    let separator = data_model.settings.decimal_separator;
+    let text = if let Some(goal) = data_model.body_weight_goal() {
+        let weight = goal.weight;
+        match domain::body_weight_goal_projection(
+            &data_model.avg_body_weight,
+            &goal,
+            data::MAX_BODY_WEIGHT_GAP_DAYS,
+        ) {
+            Some(domain::BodyWeightGoalProjection::Reached) => {
+                format!("You have reached your goal of {weight} kg")
+            }
+            Some(domain::BodyWeightGoalProjection::Expected {
+                weekly_change,
+                date,
+            }) => format!(
+                "At the current rate ({} kg/week) you'll reach {weight} kg around {date}",
+                web_app::format::format_signed(
+                    weekly_change,
+                    web_app::format::PRECISION_WEIGHT,
+                    separator
+                )
+            ),
+            Some(domain::BodyWeightGoalProjection::Distant { weekly_change }) => format!(
+                "At the current rate ({} kg/week) you'll reach {weight} kg in more than {} years",
+                web_app::format::format_signed(weekly_change, 2, separator),
+                domain::BodyWeightGoalProjection::MAX_DAYS / 365
+            ),
+            None => format!("The current trend does not lead to your goal of {weight} kg"),
+        }
+    } else {
+        String::from("No goal set")
+    };
+    div![
+        C!["is-flex"],
+        C!["is-justify-content-center"],
+        C!["is-align-items-center"],
+        C!["mx-2"],
+        C!["mb-4"],
+        span![C!["is-size-7"], text],
+        a![
+            C!["icon"],
+            C!["ml-1"],
+            ev(Ev::Click, |_| Msg::ShowGoalDialog),
+            i![C!["fas fa-bullseye"]]
+        ],
+    ]
+}
+
+fn data_table(data_model: &data::Model) -> common::DataTable<Msg> {
+    common::DataTable {
+        shown: data_model.show_data_tables(web_app::chart::Page::BodyWeight),
+        toggle: || Msg::ToggleDataTables,
+    }
+}
+
+fn view_chart(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    let interval = model.viewport.visible();
+    let avg_body_weight = if model.viewport.is_zoomed() {
+        // The average is adapted to the zoom, so that short-term changes become visible
+        domain::value_based_centered_moving_average(
+            &data_model
+                .body_weight
+                .values()
+                .map(|bw| (bw.date, bw.weight))
+                .collect::<Vec<_>>(),
+            web_app::chart::moving_average_radius(domain::AVG_BODY_WEIGHT_RADIUS, &model.viewport),
+        )
+    } else {
+        data_model
+            .avg_body_weight
+            .values()
+            .map(|bw| (bw.date, bw.weight))
+            .collect::<Vec<_>>()
+    }
+    .into_iter()
+    .filter(|(date, _)| *date >= interval.first && *date <= interval.last)
+    .collect::<Vec<_>>();
+    let goal = data_model.body_weight_goal();
+    let bmi = data_model.body_height().map(|height| {
+        avg_body_weight
+            .iter()
+            .filter_map(|(date, weight)| Some((*date, domain::bmi(*weight, height)?)))
+            .collect::<Vec<_>>()
+    });
+
+    common::view_zoomable_chart(
         vec![
             (
                 "Weight (kg)",
@@ -375,33 +709,63 @@ fn view_chart(model: &Model, data_model: &data::Model) -> Node<Msg> {
                 web_app::chart::OPACITY_LINE,
             ),
         ]
+        .into_iter()
+        .chain(goal.map(|_| {
+            (
+                "Goal (kg)",
+                web_app::chart::COLOR_BODY_WEIGHT_GOAL,
+                web_app::chart::OPACITY_LINE,
+            )
+        }))
+        .chain(bmi.as_ref().map(|_| {
+            (
+                "BMI",
+                web_app::chart::COLOR_BMI,
+                web_app::chart::OPACITY_LINE,
+            )
+        }))
+        .collect::<Vec<_>>()
         .as_slice(),
-        web_app::chart::plot(
-            &[
-                web_app::chart::PlotData {
-                    values_high: data_model
-                        .body_weight
-                        .values()
-                        .filter(|bw| {
-                            bw.date >= model.interval.first && bw.date <= model.interval.last
-                        })
-                        .map(|bw| (bw.date, bw.weight))
-                        .collect::<Vec<_>>(),
-                    values_low: Some(avg_body_weight.clone()),
-                    plots: web_app::chart::plot_area(web_app::chart::COLOR_BODY_WEIGHT),
-                    params: web_app::chart::PlotParams::default(),
-                },
-                web_app::chart::PlotData {
-                    values_high: avg_body_weight,
-                    values_low: None,
-                    plots: web_app::chart::plot_line(web_app::chart::COLOR_AVG_BODY_WEIGHT),
-                    params: web_app::chart::PlotParams::default(),
-                },
-            ],
-            &model.interval,
-            data_model.theme(),
-        ),
+        &[
+            web_app::chart::PlotData {
+                values_high: data_model
+                    .body_weight
+                    .values()
+                    .filter(|bw| bw.date >= interval.first && bw.date <= interval.last)
+                    .map(|bw| (bw.date, bw.weight))
+                    .collect::<Vec<_>>(),
+                values_low: Some(avg_body_weight.clone()),
+                plots: web_app::chart::plot_area(web_app::chart::COLOR_BODY_WEIGHT),
+                params: web_app::chart::PlotParams::default(),
+            },
+            web_app::chart::PlotData {
+                values_high: avg_body_weight,
+                values_low: None,
+                plots: web_app::chart::plot_line(web_app::chart::COLOR_AVG_BODY_WEIGHT),
+                params: web_app::chart::PlotParams::default(),
+            },
+        ]
+        .into_iter()
+        .chain(goal.map(|goal| web_app::chart::PlotData {
+            values_high: vec![(interval.first, goal.weight), (interval.last, goal.weight)],
+            values_low: None,
+            plots: web_app::chart::plot_line(web_app::chart::COLOR_BODY_WEIGHT_GOAL),
+            params: web_app::chart::PlotParams::default(),
+        }))
+        .chain(bmi.map(|bmi| web_app::chart::PlotData {
+            values_high: bmi,
+            values_low: None,
+            plots: web_app::chart::plot_line(web_app::chart::COLOR_BMI),
+            params: web_app::chart::PlotParams::SECONDARY,
+        }))
+        .collect::<Vec<_>>(),
+        &data_model.chart_style(),
+        data_table(data_model),
         true,
+        common::ChartZoom {
+            viewport: &model.viewport,
+            input: Msg::ChartInput,
+        },
     )
 }
 
@@ -445,6 +809,7 @@ fn view_calendar(data_model: &data::Model, interval: &domain::Interval) -> Node<
 }
 
 fn view_table(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    let separator = data_model.settings.decimal_separator;
     div![
         C!["table-container"],
         C!["mt-4"],
@@ -473,12 +838,23 @@ fn view_table(model: &Model, data_model: &data::Model) -> Node<Msg> {
                             style! {St::WhiteSpace => "nowrap" },
                             date.to_string(),
                         ]],
-                        td![format!("{:.1}", bw.weight)],
-                        td![common::value_or_dash(avg_bw.map(|bw| bw.weight))],
-                        td![if let Some(value) =
-                            avg_weekly_change(&data_model.avg_body_weight, avg_bw)
-                        {
-                            format!("{value:+.1}")
+                        td![web_app::format::format_weight(
+                            bw.weight,
+                            "",
+                            web_app::format::PRECISION_WEIGHT,
+                            separator
+                        )],
+                        td![common::value_or_dash(avg_bw.map(|bw| bw.weight), separator)],
+                        td![if let Some(change) = domain::avg_weekly_change(
+                            &data_model.avg_body_weight,
+                            avg_bw,
+                            data::MAX_BODY_WEIGHT_GAP_DAYS
+                        ) {
+                            web_app::format::format_signed(
+                                change.percent,
+                                web_app::format::PRECISION_PERCENTAGE,
+                                separator,
+                            )
                         } else {
                             "-".into()
                         }],
@@ -503,135 +879,23 @@ fn view_table(model: &Model, data_model: &data::Model) -> Node<Msg> {
     ]
 }
 
-fn avg_weekly_change(
-    avg_body_weight: &BTreeMap<NaiveDate, domain::BodyWeight>,
-    current: Option<&domain::BodyWeight>,
-) -> Option<f32> {
-    let prev_date = current?.date - Duration::days(7);
-    let prev_avg_bw = if let Some(avg_bw) = avg_body_weight.get(&prev_date) {
-        avg_bw.clone()
-    } else {
-        let n = neighbors(avg_body_weight, prev_date);
-        interpolate_avg_body_weight(n?.0, n?.1, prev_date)
-    };
-    Some((current?.weight - prev_avg_bw.weight) / prev_avg_bw.weight * 100.)
-}
-
-fn neighbors(
-    body_weight: &BTreeMap<NaiveDate, domain::BodyWeight>,
-    date: NaiveDate,
-) -> Option<(&domain::BodyWeight, &domain::BodyWeight)> {
-    use std::ops::Bound::{Excluded, Unbounded};
-
-    let mut before = body_weight.range((Unbounded, Excluded(date)));
-    let mut after = body_weight.range((Excluded(date), Unbounded));
-
-    Some((
-        before.next_back().map(|(_, v)| v)?,
-        after.next().map(|(_, v)| v)?,
-    ))
-}
-
-fn interpolate_avg_body_weight(
-    a: &domain::BodyWeight,
-    b: &domain::BodyWeight,
-    date: NaiveDate,
-) -> domain::BodyWeight {
-    #[allow(clippy::cast_precision_loss)]
-    domain::BodyWeight {
-        date,
-        weight: a.weight
-            + (b.weight - a.weight)
-                * ((date - a.date).num_days() as f32 / (b.date - a.date).num_days() as f32),
-    }
-}
-
-// ------ ------
-//     Tests
-// ------ ------
-
-#[cfg(test)]
-mod tests {
-    use assert_approx_eq::assert_approx_eq;
-
-    use super::*;
-
-    fn from_num_days(days: i32) -> NaiveDate {
-        NaiveDate::from_num_days_from_ce_opt(days).unwrap()
-    }
-
-    #[test]
-    fn test_avg_weekly_change() {
-        assert_eq!(
-            avg_weekly_change(
-                &BTreeMap::new(),
-                Some(&domain::BodyWeight {
-                    date: from_num_days(1),
-                    weight: 70.0
-                })
-            ),
-            None
-        );
-        assert_eq!(
-            avg_weekly_change(
-                &BTreeMap::from([(
-                    from_num_days(0),
-                    domain::BodyWeight {
-                        date: from_num_days(0),
-                        weight: 70.0
-                    }
-                )]),
-                Some(&domain::BodyWeight {
-                    date: from_num_days(7),
-                    weight: 70.0
-                })
-            ),
-            Some(0.0)
-        );
-        assert_approx_eq!(
-            avg_weekly_change(
-                &BTreeMap::from([(
-                    from_num_days(0),
-                    domain::BodyWeight {
-                        date: from_num_days(0),
-                        weight: 70.0
-                    }
-                )]),
-                Some(&domain::BodyWeight {
-                    date: from_num_days(7),
-                    weight: 70.7
-                })
-            )
-            .unwrap(),
-            1.0,
-            0.001
-        );
-        assert_approx_eq!(
-            avg_weekly_change(
-                &BTreeMap::from([
-                    (
-                        from_num_days(0),
-                        domain::BodyWeight {
-                            date: from_num_days(0),
-                            weight: 69.0
-                        }
+fn view_trash(data_model: &data::Model) -> Node<Msg> {
+    common::view_trash(
+        data_model
+            .trash
+            .body_weight()
+            .map(|(bw, item)| {
+                (
+                    item,
+                    web_app::format::format_weight(
+                        bw.weight,
+                        "kg",
+                        web_app::format::PRECISION_WEIGHT,
+                        data_model.settings.decimal_separator,
                     ),
-                    (
-                        from_num_days(2),
-                        domain::BodyWeight {
-                            date: from_num_days(2),
-                            weight: 71.0
-                        }
-                    )
-                ]),
-                Some(&domain::BodyWeight {
-                    date: from_num_days(8),
-                    weight: 69.44
-                })
-            )
-            .unwrap(),
-            -0.8,
-            0.001
-        );
-    }
+                )
+            })
+            .collect(),
+        Msg::RestoreFromTrash,
+    )
 }
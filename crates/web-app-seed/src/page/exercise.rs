@@ -5,7 +5,10 @@ use seed::{prelude::*, *};
 use valens_domain as domain;
 use valens_web_app as web_app;
 
-use crate::{common, data, page::training};
+use crate::{
+    common, component, data,
+    page::{exercises, training},
+};
 
 // ------ ------
 //     Init
@@ -28,17 +31,33 @@ pub fn init(
 
     navbar.title = String::from("Exercise");
 
+    let interval = domain::init_interval(
+        &[],
+        domain::DefaultInterval::_3M,
+        data_model.settings.initial_interval(),
+        &web_app::clock::BrowserClock,
+    );
     let mut model = Model {
-        interval: domain::init_interval(&[], domain::DefaultInterval::_3M),
+        viewport: web_app::chart::Viewport::new(&interval),
+        interval,
         exercise_id,
         name: common::InputField::default(),
         muscle_stimulus: BTreeMap::new(),
+        weight_convention: None,
+        orig_weight_convention: None,
+        base_weight: common::InputField::default(),
+        laterality: domain::Laterality::default(),
+        orig_laterality: domain::Laterality::default(),
+        archived: false,
+        orig_archived: false,
+        image_error: None,
         dialog: Dialog::Hidden,
         editing,
         loading: false,
     };
 
     update_model(&mut model, data_model);
+    orders.notify(data::Msg::ReadExerciseImage(exercise_id));
 
     model
 }
@@ -49,9 +68,18 @@ pub fn init(
 
 pub struct Model {
     interval: domain::Interval,
+    viewport: web_app::chart::Viewport,
     exercise_id: u32,
     name: common::InputField<String>,
     muscle_stimulus: BTreeMap<u8, u8>,
+    weight_convention: Option<domain::WeightConvention>,
+    orig_weight_convention: Option<domain::WeightConvention>,
+    base_weight: common::InputField<f32>,
+    laterality: domain::Laterality,
+    orig_laterality: domain::Laterality,
+    archived: bool,
+    orig_archived: bool,
+    image_error: Option<String>,
     dialog: Dialog,
     editing: bool,
     loading: bool,
@@ -60,21 +88,32 @@ pub struct Model {
 impl Model {
     pub fn has_unsaved_changes(&self) -> bool {
         self.name.changed()
+            || self.weight_convention != self.orig_weight_convention
+            || self.base_weight.changed()
+            || self.laterality != self.orig_laterality
+            || self.archived != self.orig_archived
     }
 
     pub fn mark_as_unchanged(&mut self) {
         self.name.input = self.name.parsed.clone().unwrap();
         self.name.orig = self.name.parsed.clone().unwrap();
+        self.orig_weight_convention = self.weight_convention;
+        self.base_weight.orig.clone_from(&self.base_weight.input);
+        self.orig_laterality = self.laterality;
+        self.orig_archived = self.archived;
     }
 
     fn saving_disabled(&self) -> bool {
-        self.loading || not(self.name.valid())
+        self.loading || not(self.name.valid()) || not(self.base_weight.valid())
     }
 }
 
 enum Dialog {
     Hidden,
     DeleteTrainingSession(u32),
+    SelectMergeTarget(component::exercise_list::Model),
+    MergeExercise(u32),
+    DeleteExercise,
 }
 
 // ------ ------
@@ -86,15 +125,34 @@ pub enum Msg {
     SaveExercise,
 
     ShowDeleteTrainingSessionDialog(u32),
+    ShowMergeDialog,
+    ShowDeleteExerciseDialog,
     CloseDialog,
+    CloseMergeDialog,
+    CloseDeleteExerciseDialog,
+
+    ExerciseList(component::exercise_list::Msg),
+    MergeExercise(u32),
+    ToggleArchived,
+    ArchiveExercise(u32),
+    DeleteExercise(u32),
 
     NameChanged(String),
     SetMuscleStimulus(u8, u8),
+    WeightConventionChanged(String),
+    BaseWeightChanged(String),
+    LateralityChanged(String),
+    ImageSelected(Option<web_sys::File>),
+    ImageDownscaled(Result<domain::ExerciseImage, String>),
+    DeleteImage,
 
     DeleteTrainingSession(u32),
+    Retry,
     DataEvent(data::Event),
 
-    ChangeInterval(NaiveDate, NaiveDate),
+    ChangeInterval(NaiveDate, NaiveDate, Option<domain::DefaultInterval>),
+    ToggleDataTables,
+    ChartInput(web_app::chart::ChartInput),
 }
 
 pub fn update(
@@ -126,12 +184,30 @@ pub fn update(
                         stimulus: *stimulus,
                     })
                     .collect(),
+                weight_convention: model.weight_convention,
+                base_weight: model.base_weight.parsed.filter(|weight| *weight > 0.0),
+                laterality: model.laterality,
+                archived: model.archived,
+                image: data_model
+                    .exercises
+                    .get(&model.exercise_id)
+                    .and_then(|e| e.image.clone()),
             }));
         }
 
         Msg::ShowDeleteTrainingSessionDialog(position) => {
             model.dialog = Dialog::DeleteTrainingSession(position);
         }
+        Msg::ShowMergeDialog => {
+            model.dialog = Dialog::SelectMergeTarget(component::exercise_list::Model::new(
+                false, false, false, false,
+            ));
+            orders.notify(data::Msg::ReadOlderTrainingSessions(None));
+        }
+        Msg::ShowDeleteExerciseDialog => {
+            model.dialog = Dialog::DeleteExercise;
+            orders.notify(data::Msg::ReadOlderTrainingSessions(None));
+        }
         Msg::CloseDialog => {
             model.dialog = Dialog::Hidden;
             model.loading = false;
@@ -141,6 +217,48 @@ pub fn update(
                     .add_hash_path_part(model.exercise_id.to_string()),
             );
         }
+        Msg::CloseMergeDialog | Msg::CloseDeleteExerciseDialog => {
+            model.dialog = Dialog::Hidden;
+            model.loading = false;
+        }
+
+        Msg::ExerciseList(msg) => {
+            if let Dialog::SelectMergeTarget(exercise_list_model) = &mut model.dialog {
+                match component::exercise_list::update(
+                    msg,
+                    exercise_list_model,
+                    &mut orders.proxy(Msg::ExerciseList),
+                ) {
+                    component::exercise_list::OutMsg::None
+                    | component::exercise_list::OutMsg::CreateClicked(_)
+                    | component::exercise_list::OutMsg::EditClicked(_)
+                    | component::exercise_list::OutMsg::DeleteClicked(_) => {}
+                    component::exercise_list::OutMsg::Selected(exercise_id) => {
+                        if exercise_id != model.exercise_id {
+                            model.dialog = Dialog::MergeExercise(exercise_id);
+                        }
+                    }
+                }
+            }
+        }
+        Msg::MergeExercise(target_id) => {
+            model.loading = true;
+            orders.notify(data::Msg::MergeExercises(model.exercise_id, target_id));
+        }
+        Msg::ToggleArchived => {
+            model.archived = not(model.archived);
+        }
+        Msg::ArchiveExercise(_) => {
+            model.archived = true;
+            model.dialog = Dialog::Hidden;
+            if not(model.saving_disabled()) {
+                orders.send_msg(Msg::SaveExercise);
+            }
+        }
+        Msg::DeleteExercise(id) => {
+            model.loading = true;
+            orders.notify(data::Msg::DeleteExercise(id));
+        }
 
         Msg::NameChanged(name) => {
             let trimmed_name = name.trim();
@@ -155,12 +273,14 @@ pub fn update(
                     input: name.clone(),
                     parsed: Some(trimmed_name.to_string()),
                     orig: model.name.orig.clone(),
+                    error: None,
                 };
             } else {
                 model.name = common::InputField {
                     input: name,
                     parsed: None,
                     orig: model.name.orig.clone(),
+                    error: None,
                 }
             }
         }
@@ -173,16 +293,79 @@ pub fn update(
             }
             _ => {}
         },
+        Msg::WeightConventionChanged(input) => {
+            model.weight_convention = input
+                .parse::<usize>()
+                .ok()
+                .and_then(|idx| domain::WeightConvention::iter().nth(idx).copied());
+        }
+        Msg::BaseWeightChanged(input) => match input.parse::<f32>() {
+            Ok(parsed_weight) => {
+                let valid = common::valid_weight(parsed_weight);
+                model.base_weight = common::InputField {
+                    input,
+                    parsed: if valid { Some(parsed_weight) } else { None },
+                    orig: model.base_weight.orig.clone(),
+                    error: None,
+                }
+            }
+            Err(_) => {
+                model.base_weight = common::InputField {
+                    parsed: if input.is_empty() { Some(0.0) } else { None },
+                    input,
+                    orig: model.base_weight.orig.clone(),
+                    error: None,
+                }
+            }
+        },
+        Msg::LateralityChanged(input) => {
+            if let Some(laterality) = input
+                .parse::<usize>()
+                .ok()
+                .and_then(|idx| domain::Laterality::iter().nth(idx).copied())
+            {
+                model.laterality = laterality;
+            }
+        }
+
+        Msg::ImageSelected(file) => {
+            if let Some(file) = file {
+                model.loading = true;
+                model.image_error = None;
+                orders.perform_cmd(async move {
+                    Msg::ImageDownscaled(web_app::image::downscale(&file).await)
+                });
+            }
+        }
+        Msg::ImageDownscaled(Ok(image)) => {
+            orders.notify(data::Msg::ReplaceExerciseImage(model.exercise_id, image));
+        }
+        Msg::ImageDownscaled(Err(message)) => {
+            model.loading = false;
+            model.image_error = Some(message);
+        }
+        Msg::DeleteImage => {
+            model.loading = true;
+            model.image_error = None;
+            orders.notify(data::Msg::DeleteExerciseImage(model.exercise_id));
+        }
 
         Msg::DeleteTrainingSession(id) => {
             model.loading = true;
             orders.notify(data::Msg::DeleteTrainingSession(id));
         }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadExercises);
+        }
         Msg::DataEvent(event) => {
             model.loading = false;
             match event {
-                data::Event::DataChanged => {
-                    update_model(model, data_model);
+                data::Event::ExercisesChanged(ids) if ids.contains(&model.exercise_id) => {
+                    update_exercise(model, data_model);
+                    orders.notify(data::Msg::ReadExerciseImage(model.exercise_id));
+                }
+                data::Event::TrainingSessionsChanged(_) => {
+                    update_interval(model, data_model);
                 }
                 data::Event::ExerciseReplacedOk => {
                     model.editing = false;
@@ -196,18 +379,67 @@ pub fn update(
                 data::Event::TrainingSessionDeletedOk => {
                     orders.skip().send_msg(Msg::CloseDialog);
                 }
+                data::Event::ExercisesMergedOk => {
+                    if let Dialog::MergeExercise(target_id) = model.dialog {
+                        model.dialog = Dialog::Hidden;
+                        model.editing = false;
+                        model.name.orig.clone_from(&model.name.input);
+                        let url = crate::Urls::new(&data_model.base_url)
+                            .exercise()
+                            .add_hash_path_part(target_id.to_string());
+                        url.go_and_push();
+                        orders.notify(subs::UrlChanged(url));
+                    }
+                }
+                data::Event::ExercisesMergedErr => {
+                    model.dialog = Dialog::Hidden;
+                }
+                data::Event::ExerciseDeletedOk => {
+                    if let Dialog::DeleteExercise = model.dialog {
+                        model.dialog = Dialog::Hidden;
+                        model.editing = false;
+                        model.name.orig.clone_from(&model.name.input);
+                        let url = crate::Urls::new(&data_model.base_url).exercises();
+                        url.go_and_push();
+                        orders.notify(subs::UrlChanged(url));
+                    }
+                }
                 _ => {}
             };
         }
 
-        Msg::ChangeInterval(first, last) => {
+        Msg::ChangeInterval(first, last, selected_interval) => {
             model.interval.first = first;
             model.interval.last = last;
+            model.viewport.set_bounds(&model.interval);
+            if let Some(selected_interval) = selected_interval {
+                orders.notify(data::Msg::SetInterval(selected_interval));
+            }
+            orders.notify(data::Msg::ReadOlderTrainingSessions(
+                if selected_interval == Some(domain::DefaultInterval::All) {
+                    None
+                } else {
+                    Some(first)
+                },
+            ));
+        }
+        Msg::ToggleDataTables => {
+            orders.notify(data::Msg::ToggleDataTables(web_app::chart::Page::Exercise));
+        }
+        Msg::ChartInput(input) => {
+            if not(model.viewport.handle(input)) {
+                orders.skip();
+            }
         }
     }
 }
 
 fn update_model(model: &mut Model, data_model: &data::Model) {
+    update_interval(model, data_model);
+    update_exercise(model, data_model);
+}
+
+fn update_interval(model: &mut Model, data_model: &data::Model) {
     model.interval = domain::init_interval(
         &data_model
             .training_sessions
@@ -216,17 +448,37 @@ fn update_model(model: &mut Model, data_model: &data::Model) {
             .map(|t| t.date)
             .collect::<Vec<NaiveDate>>(),
         domain::DefaultInterval::_3M,
+        data_model.settings.initial_interval(),
+        &web_app::clock::BrowserClock,
     );
+    model.viewport.set_bounds(&model.interval);
+}
 
-    let exercise = &data_model.exercises.get(&model.exercise_id);
-
-    if let Some(exercise) = exercise {
+fn update_exercise(model: &mut Model, data_model: &data::Model) {
+    if let Some(exercise) = data_model.exercises.get(&model.exercise_id) {
         model.name = common::InputField {
             input: exercise.name.clone(),
             parsed: Some(exercise.name.clone()),
             orig: exercise.name.clone(),
+            error: None,
         };
         model.muscle_stimulus = exercise.muscle_stimulus();
+        model.weight_convention = exercise.weight_convention;
+        model.orig_weight_convention = exercise.weight_convention;
+        let base_weight = exercise
+            .base_weight
+            .map(|weight| weight.to_string())
+            .unwrap_or_default();
+        model.base_weight = common::InputField {
+            input: base_weight.clone(),
+            parsed: Some(exercise.base_weight.unwrap_or_default()),
+            orig: base_weight,
+            error: None,
+        };
+        model.laterality = exercise.laterality;
+        model.orig_laterality = exercise.laterality;
+        model.archived = exercise.archived;
+        model.orig_archived = exercise.archived;
     };
 }
 
@@ -235,8 +487,8 @@ fn update_model(model: &mut Model, data_model: &data::Model) {
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.exercises.is_empty() && data_model.loading_exercises {
-        common::view_page_loading()
+    if let Some(node) = common::view_resource_unavailable(&data_model.exercises, |_| Msg::Retry) {
+        node
     } else if model.exercise_id > 0 {
         let exercise_training_sessions = exercise_training_sessions(model, data_model);
         let dates = exercise_training_sessions.iter().map(|t| t.date);
@@ -250,42 +502,116 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
             .collect::<Vec<_>>();
         div![
             view_title(model),
+            view_image(model, data_model),
             view_muscles(model),
+            view_weight_convention(model, data_model),
+            view_laterality(model),
             if model.editing {
-                nodes![button![
-                    C!["button"],
-                    C!["is-fab"],
-                    C!["is-medium"],
-                    C!["is-link"],
-                    C![IF![model.loading => "is-loading"]],
-                    attrs![
-                        At::Disabled => model.saving_disabled().as_at_value(),
+                nodes![
+                    div![
+                        C!["buttons"],
+                        C!["is-centered"],
+                        C!["mb-5"],
+                        button![
+                            C!["button"],
+                            C!["is-small"],
+                            ev(Ev::Click, |_| Msg::ShowMergeDialog),
+                            span![C!["icon"], i![C!["fas fa-code-merge"]]],
+                            span!["Merge into other exercise"],
+                        ],
+                        button![
+                            C!["button"],
+                            C!["is-small"],
+                            C![IF![model.archived != model.orig_archived => "is-info"]],
+                            ev(Ev::Click, |_| Msg::ToggleArchived),
+                            span![
+                                C!["icon"],
+                                i![C![if model.archived {
+                                    "fas fa-box-open"
+                                } else {
+                                    "fas fa-box-archive"
+                                }]]
+                            ],
+                            span![if model.archived {
+                                "Restore exercise"
+                            } else {
+                                "Archive exercise"
+                            }],
+                        ],
+                        button![
+                            C!["button"],
+                            C!["is-small"],
+                            C!["is-danger"],
+                            C!["is-outlined"],
+                            ev(Ev::Click, |_| Msg::ShowDeleteExerciseDialog),
+                            span![C!["icon"], i![C!["fas fa-times"]]],
+                            span!["Delete exercise"],
+                        ],
                     ],
-                    ev(Ev::Click, |_| Msg::SaveExercise),
-                    span![C!["icon"], i![C!["fas fa-save"]]]
-                ]]
+                    view_dialog(&model.dialog, model.exercise_id, model.loading, data_model),
+                    button![
+                        C!["button"],
+                        C!["is-fab"],
+                        C!["is-medium"],
+                        C!["is-link"],
+                        C![IF![model.loading => "is-loading"]],
+                        attrs![
+                            At::Disabled => model.saving_disabled().as_at_value(),
+                        ],
+                        ev(Ev::Click, |_| Msg::SaveExercise),
+                        span![C!["icon"], i![C!["fas fa-save"]]]
+                    ]
+                ]
             } else {
                 nodes![
+                    data_model
+                        .exercises
+                        .get(&model.exercise_id)
+                        .map_or(empty![], |exercise| {
+                            view_usage_stats(
+                                &domain::exercise_usage_stats(
+                                    &data_model.training_sessions.values().collect::<Vec<_>>(),
+                                    exercise,
+                                    web_app::clock::today(),
+                                ),
+                                data_model.settings.decimal_separator,
+                            )
+                        }),
                     common::view_interval_buttons(
                         &model.interval,
                         &exercise_interval,
                         Msg::ChangeInterval
                     ),
+                    data_model
+                        .exercises
+                        .get(&model.exercise_id)
+                        .and_then(|exercise| domain::side_asymmetry(&training_sessions, exercise))
+                        .map_or(empty![], |asymmetry| view_side_asymmetry(
+                            &asymmetry,
+                            data_model.settings.decimal_separator
+                        )),
                     view_charts(
                         &training_sessions,
-                        &model.interval,
-                        data_model.theme(),
+                        &data_model.exercises,
+                        common::ChartZoom {
+                            viewport: &model.viewport,
+                            input: Msg::ChartInput,
+                        },
+                        &data_model.chart_style(),
                         data_model.settings.show_rpe,
                         data_model.settings.show_tut,
+                        data_table(data_model),
                     ),
-                    view_calendar(&training_sessions, &model.interval),
+                    view_calendar(&training_sessions, &data_model.exercises, &model.interval),
                     training::view_table(
                         &training_sessions,
                         &data_model.routines,
+                        &data_model.exercises,
                         &data_model.base_url,
                         Msg::ShowDeleteTrainingSessionDialog,
                         data_model.settings.show_rpe,
                         data_model.settings.show_tut,
+                        data_model.settings.decimal_separator,
                     ),
                     view_sets(
                         &training_sessions,
@@ -294,7 +620,7 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
                         data_model.settings.show_rpe,
                         data_model.settings.show_tut,
                     ),
-                    view_dialog(&model.dialog, model.loading),
+                    view_dialog(&model.dialog, model.exercise_id, model.loading, data_model),
                     common::view_fab("edit", |_| Msg::EditExercise),
                 ]
             },
@@ -334,11 +660,178 @@ fn view_title(model: &Model) -> Node<Msg> {
                 ],
             ]
         } else {
-            common::view_title(&span![&model.name.input], 0)
+            div![
+                common::view_title(&span![&model.name.input], 0),
+                IF![model.archived => p![
+                    C!["has-text-centered"],
+                    span![
+                        C!["tag"],
+                        span![C!["icon"], i![C!["fas fa-box-archive"]]],
+                        span!["Archived"]
+                    ]
+                ]],
+            ]
         }
     ]
 }
 
+fn view_image(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    let digest = data_model
+        .exercises
+        .get(&model.exercise_id)
+        .and_then(|e| e.image.as_ref());
+    // The rest of the page is shown while the image is loaded or if it could not be loaded
+    let image = digest.and_then(|digest| data_model.exercise_images.get(digest));
+    if not(model.editing) {
+        return image.map_or(empty![], |image| {
+            figure![
+                C!["image"],
+                C!["is-exercise-image"],
+                C!["mx-auto"],
+                C!["mb-5"],
+                img![attrs! {
+                    At::Src => image.data_url(),
+                    At::Alt => model.name.input,
+                }]
+            ]
+        });
+    }
+    div![
+        C!["mx-2"],
+        C!["mb-5"],
+        image.map(|image| {
+            figure![
+                C!["image"],
+                C!["is-exercise-image"],
+                C!["mx-auto"],
+                C!["mb-3"],
+                img![attrs! {
+                    At::Src => image.data_url(),
+                    At::Alt => model.name.input,
+                }]
+            ]
+        }),
+        div![
+            C!["buttons"],
+            C!["is-centered"],
+            label![
+                C!["button"],
+                C!["is-small"],
+                C![IF![model.loading => "is-loading"]],
+                input![
+                    C!["is-hidden"],
+                    attrs! {
+                        At::Type => "file",
+                        At::Accept => domain::ExerciseImage::MIME_TYPES.join(","),
+                        At::Disabled => model.loading.as_at_value(),
+                    },
+                    ev(Ev::Change, |event| {
+                        let file = event
+                            .target()
+                            .and_then(|target| target.dyn_into::<web_sys::HtmlInputElement>().ok())
+                            .and_then(|input| input.files())
+                            .and_then(|files| files.get(0));
+                        Msg::ImageSelected(file)
+                    }),
+                ],
+                span![C!["icon"], i![C!["fas fa-image"]]],
+                span![if digest.is_some() {
+                    "Replace image"
+                } else {
+                    "Add image"
+                }],
+            ],
+            IF![digest.is_some() => button![
+                C!["button"],
+                C!["is-small"],
+                C!["is-danger"],
+                C!["is-outlined"],
+                attrs! {
+                    At::Disabled => model.loading.as_at_value(),
+                },
+                ev(Ev::Click, |_| Msg::DeleteImage),
+                span![C!["icon"], i![C!["fas fa-times"]]],
+                span!["Remove image"],
+            ]],
+        ],
+        model.image_error.as_ref().map(|message| {
+            p![
+                C!["help"],
+                C!["is-danger"],
+                C!["has-text-centered"],
+                message
+            ]
+        }),
+    ]
+}
+
+fn view_usage_stats(
+    stats: &domain::ExerciseUsageStats,
+    separator: web_app::format::DecimalSeparator,
+) -> Node<Msg> {
+    let Some(last_performed) = stats.last_performed else {
+        return empty![];
+    };
+    div![
+        C!["grid"],
+        C!["is-col-min-8"],
+        C!["mx-3"],
+        C!["mb-5"],
+        C!["has-text-centered"],
+        [
+            ("Sets", stats.sets.to_string()),
+            ("Sets (28 days)", stats.recent_sets.to_string()),
+            ("Volume load", stats.volume_load.to_string()),
+            (
+                "Best set",
+                stats.best_set.map_or(String::from("-"), |best_set| {
+                    format!(
+                        "{} × {} kg (e1RM {}, {})",
+                        best_set.reps,
+                        best_set.weight,
+                        web_app::format::format_weight(
+                            best_set.one_rep_max,
+                            "kg",
+                            web_app::format::PRECISION_E1RM,
+                            separator
+                        ),
+                        best_set.date
+                    )
+                })
+            ),
+            ("Last performed", last_performed.to_string()),
+        ]
+        .into_iter()
+        .map(|(label, value)| {
+            div![
+                C!["cell"],
+                p![C!["is-size-7"], C!["has-text-grey"], label],
+                p![C!["has-text-weight-bold"], value],
+            ]
+        })
+    ]
+}
+
+fn view_side_asymmetry(
+    asymmetry: &domain::SideAsymmetry,
+    separator: web_app::format::DecimalSeparator,
+) -> Node<Msg> {
+    let label = match asymmetry.metric {
+        domain::AsymmetryMetric::OneRepMax => "Asymmetry (best e1RM)",
+        domain::AsymmetryMetric::Reps => "Asymmetry (best reps)",
+    };
+    div![
+        C!["mx-3"],
+        C!["mb-5"],
+        C!["has-text-centered"],
+        p![C!["is-size-7"], C!["has-text-grey"], label],
+        p![
+            C!["has-text-weight-bold"],
+            web_app::format_side_asymmetry(asymmetry, separator)
+        ],
+    ]
+}
+
 fn view_muscles(model: &Model) -> Node<Msg> {
     let muscles = domain::Muscle::iter()
         .map(|m| {
@@ -351,62 +844,7 @@ fn view_muscles(model: &Model) -> Node<Msg> {
         })
         .collect::<Vec<_>>();
     if model.editing {
-        div![
-            C!["mx-2"],
-            C!["mb-5"],
-            muscles.iter().map(|(m, stimulus)| {
-                let m = **m;
-                div![
-                    C!["columns"],
-                    C!["is-mobile"],
-                    div![
-                        C!["column"],
-                        p![m.name()],
-                        p![C!["is-size-7"], m.description()]
-                    ],
-                    div![
-                        C!["column"],
-                        C!["is-flex"],
-                        div![
-                            C!["field"],
-                            C!["has-addons"],
-                            C!["has-addons-centered"],
-                            C!["my-auto"],
-                            p![
-                                C!["control"],
-                                a![
-                                    C!["button"],
-                                    C!["is-small"],
-                                    C![IF![*stimulus == 100 => "is-link"]],
-                                    &ev(Ev::Click, move |_| Msg::SetMuscleStimulus(m.id(), 100)),
-                                    "primary",
-                                ]
-                            ],
-                            p![
-                                C!["control"],
-                                a![
-                                    C!["button"],
-                                    C!["is-small"],
-                                    C![IF![*stimulus > 0 && *stimulus < 100 => "is-link"]],
-                                    &ev(Ev::Click, move |_| Msg::SetMuscleStimulus(m.id(), 50)),
-                                    "secondary",
-                                ]
-                            ],
-                            p![
-                                C!["control"],
-                                a![
-                                    C!["button"],
-                                    C!["is-small"],
-                                    C![IF![*stimulus == 0 => "is-link"]],
-                                    &ev(Ev::Click, move |_| Msg::SetMuscleStimulus(m.id(), 0)),
-                                    "none",
-                                ]
-                            ],
-                        ],
-                    ]
-                ]
-            })
-        ]
+        common::view_muscle_stimulus_editor(&model.muscle_stimulus, Msg::SetMuscleStimulus)
     } else {
         let mut muscles = muscles
             .iter()
@@ -437,13 +875,146 @@ fn view_muscles(model: &Model) -> Node<Msg> {
     }
 }
 
-pub fn view_charts<Ms>(
+fn view_weight_convention(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    if model.editing {
+        let selected = model
+            .weight_convention
+            .and_then(|c| domain::WeightConvention::iter().position(|w| *w == c));
+        div![
+            C!["field"],
+            C!["is-grouped"],
+            C!["is-grouped-centered"],
+            C!["mx-2"],
+            C!["mb-5"],
+            div![
+                C!["control"],
+                label![C!["label"], C!["is-small"], "Weight convention"],
+                input_ev(Ev::Change, Msg::WeightConventionChanged),
+                div![
+                    C!["select"],
+                    C!["is-small"],
+                    select![
+                        option![
+                            "–",
+                            attrs![
+                                At::Value => "",
+                                At::Selected => selected.is_none().as_at_value(),
+                            ]
+                        ],
+                        domain::WeightConvention::iter()
+                            .enumerate()
+                            .map(|(idx, weight_convention)| {
+                                option![
+                                    weight_convention.name(),
+                                    attrs![
+                                        At::Value => idx,
+                                        At::Selected => (selected == Some(idx)).as_at_value(),
+                                    ]
+                                ]
+                            })
+                            .collect::<Vec<_>>(),
+                    ],
+                ],
+            ],
+            IF![matches!(
+                model.weight_convention,
+                Some(domain::WeightConvention::PerSide | domain::WeightConvention::PerDumbbell)
+            ) =>
+                div![
+                    C!["control"],
+                    label![C!["label"], C!["is-small"], "Base weight"],
+                    div![
+                        C!["control"],
+                        C!["has-icons-right"],
+                        C!["has-text-right"],
+                        input_ev(Ev::Input, Msg::BaseWeightChanged),
+                        input![
+                            C!["input"],
+                            C!["is-small"],
+                            C!["has-text-right"],
+                            C![IF![not(model.base_weight.valid()) => "is-danger"]],
+                            C![IF![model.base_weight.changed() => "is-info"]],
+                            attrs! {
+                                At::from("inputmode") => "numeric",
+                                At::Size => 3,
+                                At::Value => model.base_weight.input,
+                            }
+                        ],
+                        span![C!["icon"], C!["is-small"], C!["is-right"], "kg"],
+                    ],
+                ]
+            ],
+        ]
+    } else if let Some(hint) = data_model
+        .exercises
+        .get(&model.exercise_id)
+        .and_then(web_app::format_weight_convention)
+    {
+        p![
+            C!["has-text-centered"],
+            C!["is-size-7"],
+            C!["has-text-grey"],
+            C!["mx-2"],
+            C!["mb-5"],
+            hint
+        ]
+    } else {
+        empty![]
+    }
+}
+
+fn view_laterality(model: &Model) -> Node<Msg> {
+    if not(model.editing) {
+        return empty![];
+    }
+    div![
+        C!["field"],
+        C!["is-grouped"],
+        C!["is-grouped-centered"],
+        C!["mx-2"],
+        C!["mb-5"],
+        div![
+            C!["control"],
+            label![C!["label"], C!["is-small"], "Laterality"],
+            input_ev(Ev::Change, Msg::LateralityChanged),
+            div![
+                C!["select"],
+                C!["is-small"],
+                C![IF![model.laterality != model.orig_laterality => "is-info"]],
+                select![domain::Laterality::iter()
+                    .enumerate()
+                    .map(|(idx, laterality)| {
+                        option![
+                            laterality.name(),
+                            attrs![
+                                At::Value => idx,
+                                At::Selected => (*laterality == model.laterality).as_at_value(),
+                            ]
+                        ]
+                    })
+                    .collect::<Vec<_>>()],
+            ],
+        ],
+    ]
+}
+
+fn data_table(data_model: &data::Model) -> common::DataTable<Msg> {
+    common::DataTable {
+        shown: data_model.show_data_tables(web_app::chart::Page::Exercise),
+        toggle: || Msg::ToggleDataTables,
+    }
+}
+
+pub fn view_charts<Ms: 'static>(
     training_sessions: &[&domain::TrainingSession],
-    interval: &domain::Interval,
-    theme: &web_app::Theme,
+    exercises: &BTreeMap<u32, domain::Exercise>,
+    zoom: common::ChartZoom<Ms>,
+    style: &web_app::chart::Style,
     show_rpe: bool,
     show_tut: bool,
+    data_table: common::DataTable<Ms>,
 ) -> Vec<Node<Ms>> {
+    let interval = &zoom.viewport.visible();
     let mut set_volume: BTreeMap<NaiveDate, f32> = BTreeMap::new();
     let mut volume_load: BTreeMap<NaiveDate, f32> = BTreeMap::new();
     let mut tut: BTreeMap<NaiveDate, f32> = BTreeMap::new();
@@ -457,8 +1028,8 @@ pub fn view_charts<Ms>(
         #[allow(clippy::cast_precision_loss)]
         volume_load
             .entry(training_session.date)
-            .and_modify(|e| *e += training_session.volume_load() as f32)
-            .or_insert(training_session.volume_load() as f32);
+            .and_modify(|e| *e += training_session.volume_load(exercises) as f32)
+            .or_insert(training_session.volume_load(exercises) as f32);
         #[allow(clippy::cast_precision_loss)]
         tut.entry(training_session.date)
             .and_modify(|e| *e += training_session.tut().unwrap_or(0) as f32)
@@ -489,7 +1060,12 @@ pub fn view_charts<Ms>(
         })
         .collect::<Vec<_>>();
 
-    let mut data = vec![];
+    let mut data = vec![web_app::chart::PlotData {
+        values_high: reps_rpe_values.clone(),
+        values_low: None,
+        plots: web_app::chart::plot_line(web_app::chart::COLOR_REPS),
+        params: web_app::chart::PlotParams::primary_range(0., 10.),
+    }];
 
     if show_rpe {
         let rir_values = reps_rpe
@@ -514,81 +1090,71 @@ pub fn view_charts<Ms>(
             ));
             data.push(web_app::chart::PlotData {
                 values_high: rir_values,
-                values_low: Some(reps_rpe_values.clone()),
+                values_low: Some(reps_rpe_values),
                 plots: web_app::chart::plot_area(web_app::chart::COLOR_REPS_RIR),
                 params: web_app::chart::PlotParams::primary_range(0., 10.),
             });
         }
     }
 
-    data.push(web_app::chart::PlotData {
-        values_high: reps_rpe_values,
-        values_low: None,
-        plots: web_app::chart::plot_line(web_app::chart::COLOR_REPS),
-        params: web_app::chart::PlotParams::primary_range(0., 10.),
-    });
-
     nodes![
-        common::view_chart(
+        common::view_zoomable_chart(
             &[(
                 "Set volume",
                 web_app::chart::COLOR_SET_VOLUME,
                 web_app::chart::OPACITY_LINE
             )],
-            web_app::chart::plot(
-                &[web_app::chart::PlotData {
-                    values_high: set_volume.into_iter().collect::<Vec<_>>(),
-                    values_low: None,
-                    plots: web_app::chart::plot_area_with_border(
-                        web_app::chart::COLOR_SET_VOLUME,
-                        web_app::chart::COLOR_SET_VOLUME,
-                    ),
-                    params: web_app::chart::PlotParams::primary_range(0., 10.),
-                }],
-                interval,
-                theme,
-            ),
+            &[web_app::chart::PlotData {
+                values_high: set_volume.into_iter().collect::<Vec<_>>(),
+                values_low: None,
+                plots: web_app::chart::plot_area_with_border(
+                    web_app::chart::COLOR_SET_VOLUME,
+                    web_app::chart::COLOR_SET_VOLUME,
+                ),
+                params: web_app::chart::PlotParams::primary_range(0., 10.),
+            }],
+            style,
+            data_table,
             false,
+            zoom,
         ),
-        common::view_chart(
+        common::view_zoomable_chart(
             &[(
                 "Volume load",
                 web_app::chart::COLOR_VOLUME_LOAD,
                 web_app::chart::OPACITY_LINE
             )],
-            web_app::chart::plot(
-                &[web_app::chart::PlotData {
-                    values_high: volume_load.into_iter().collect::<Vec<_>>(),
-                    values_low: None,
-                    plots: web_app::chart::plot_area_with_border(
-                        web_app::chart::COLOR_VOLUME_LOAD,
-                        web_app::chart::COLOR_VOLUME_LOAD,
-                    ),
-                    params: web_app::chart::PlotParams::primary_range(0., 10.),
-                }],
-                interval,
-                theme,
-            ),
+            &[web_app::chart::PlotData {
+                values_high: volume_load.into_iter().collect::<Vec<_>>(),
+                values_low: None,
+                plots: web_app::chart::plot_area_with_border(
+                    web_app::chart::COLOR_VOLUME_LOAD,
+                    web_app::chart::COLOR_VOLUME_LOAD,
+                ),
+                params: web_app::chart::PlotParams::primary_range(0., 10.),
+            }],
+            style,
+            data_table,
             false,
+            zoom,
         ),
         IF![show_tut =>
-            common::view_chart(
+            common::view_zoomable_chart(
                 &[("Time under tension (s)", web_app::chart::COLOR_TUT, web_app::chart::OPACITY_LINE)],
-                web_app::chart::plot(
-                    &[web_app::chart::PlotData {
-                        values_high: tut.into_iter().collect::<Vec<_>>(),
-                        values_low: None,
-                        plots: web_app::chart::plot_area_with_border(web_app::chart::COLOR_TUT, web_app::chart::COLOR_TUT),
-                        params: web_app::chart::PlotParams::primary_range(0., 10.),
-                    }],
-                    interval,
-                    theme,
-                ),
+                &[web_app::chart::PlotData {
+                    values_high: tut.into_iter().collect::<Vec<_>>(),
+                    values_low: None,
+                    plots: web_app::chart::plot_area_with_border(web_app::chart::COLOR_TUT, web_app::chart::COLOR_TUT),
+                    params: web_app::chart::PlotParams::primary_range(0., 10.),
+                }],
+                style,
+                data_table,
                 false,
+                zoom,
             )
         ],
-        common::view_chart(&labels, web_app::chart::plot(&data, interval, theme), false,),
-        common::view_chart(
+        common::view_zoomable_chart(&labels, &data, style, data_table, false, zoom),
+        common::view_zoomable_chart(
             &[
                 (
                     "Weight (kg)",
@@ -601,7 +1167,7 @@ pub fn view_charts<Ms>(
                     web_app::chart::OPACITY_LINE
                 )
             ],
-            web_app::chart::plot_min_avg_max(
+            &web_app::chart::min_avg_max(
                 &training_sessions
                     .iter()
                     .flat_map(|s| s
@@ -617,17 +1183,19 @@ pub fn view_charts<Ms>(
                 interval,
                 web_app::chart::PlotParams::primary_range(0., 10.),
                 web_app::chart::COLOR_WEIGHT,
-                theme,
             ),
+            style,
+            data_table,
             false,
+            zoom,
         ),
         IF![show_tut =>
-            common::view_chart(
+            common::view_zoomable_chart(
                 &[
                     ("Time (s)", web_app::chart::COLOR_TIME, web_app::chart::OPACITY_AREA),
                     ("Avg. time (s)", web_app::chart::COLOR_TIME, web_app::chart::OPACITY_LINE)
                 ],
-                web_app::chart::plot_min_avg_max(
+                &web_app::chart::min_avg_max(
                     &training_sessions
                         .iter()
                         .flat_map(|s| s
@@ -644,9 +1212,11 @@ pub fn view_charts<Ms>(
                     interval,
                     web_app::chart::PlotParams::primary_range(0., 10.),
                     web_app::chart::COLOR_TIME,
-                    theme,
                 ),
+                style,
+                data_table,
                 false,
+                zoom,
             )
         ],
     ]
@@ -654,6 +1224,7 @@ pub fn view_charts<Ms>(
 
 fn view_calendar(
     training_sessions: &[&domain::TrainingSession],
+    exercises: &BTreeMap<u32, domain::Exercise>,
     interval: &domain::Interval,
 ) -> Node<Msg> {
     let mut volume_load: BTreeMap<NaiveDate, u32> = BTreeMap::new();
@@ -661,8 +1232,8 @@ fn view_calendar(
         if (interval.first..=interval.last).contains(&training_session.date) {
             volume_load
                 .entry(training_session.date)
-                .and_modify(|e| *e += training_session.volume_load())
-                .or_insert(training_session.volume_load());
+                .and_modify(|e| *e += training_session.volume_load(exercises))
+                .or_insert(training_session.volume_load(exercises));
         }
     }
     let min = volume_load
@@ -739,15 +1310,26 @@ fn view_sets(
                         t.elements.iter().map(|e| {
                             if let domain::TrainingSessionElement::Set {
                                 reps,
+                                partial_reps,
                                 time,
                                 weight,
                                 rpe,
+                                to_failure,
                                 ..
                             } = e {
                                 div![
                                     span![
                                         style! {St::WhiteSpace => "nowrap" },
-                                        common::format_set(*reps, *time, show_tut, *weight, *rpe, show_rpe)
+                                        web_app::format_set(
+                                            reps.map(domain::RepsRange::single),
+                                            *partial_reps,
+                                            *time,
+                                            show_tut,
+                                            *weight,
+                                            rpe.map(domain::RpeRange::single),
+                                            show_rpe,
+                                            *to_failure
+                                        )
                                     ]
                                 ]
                             } else {
@@ -760,7 +1342,12 @@ fn view_sets(
             .collect::<Vec<_>>()
 }
 
-fn view_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
+fn view_dialog(
+    dialog: &Dialog,
+    exercise_id: u32,
+    loading: bool,
+    data_model: &data::Model,
+) -> Node<Msg> {
     match dialog {
         Dialog::DeleteTrainingSession(id) => {
             #[allow(clippy::clone_on_copy)]
@@ -772,12 +1359,104 @@ fn view_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
                 loading,
             )
         }
+        Dialog::SelectMergeTarget(exercise_list_model) => common::view_dialog(
+            "primary",
+            "Merge into exercise",
+            component::exercise_list::view(exercise_list_model, loading, data_model)
+                .map_msg(Msg::ExerciseList),
+            &ev(Ev::Click, |_| Msg::CloseMergeDialog),
+        ),
+        Dialog::MergeExercise(target_id) => {
+            view_merge_dialog(exercise_id, *target_id, loading, data_model)
+        }
+        Dialog::DeleteExercise => exercises::view_delete_exercise_dialog(
+            exercise_id,
+            data_model,
+            Msg::DeleteExercise,
+            Msg::ArchiveExercise,
+            || Msg::CloseDeleteExerciseDialog,
+            loading,
+        ),
         Dialog::Hidden => {
             empty![]
         }
     }
 }
 
+fn view_merge_dialog(
+    source_id: u32,
+    target_id: u32,
+    loading: bool,
+    data_model: &data::Model,
+) -> Node<Msg> {
+    let name = |id| {
+        data_model
+            .exercises
+            .get(&id)
+            .map(|e| e.name.clone())
+            .unwrap_or_default()
+    };
+    let merge = domain::merge_exercises(
+        source_id,
+        target_id,
+        &data_model.routines.values().collect::<Vec<_>>(),
+        &data_model.training_sessions.values().collect::<Vec<_>>(),
+    );
+    common::view_dialog(
+        "danger",
+        "Merge exercises?",
+        nodes![
+            div![
+                C!["block"],
+                if data_model.training_sessions.is_loading() {
+                    p!["Loading training sessions …"]
+                } else {
+                    p![format!(
+                        "All references to \"{}\" in {} training sessions and {} routines will be replaced by \"{}\".",
+                        name(source_id),
+                        merge.training_sessions.len(),
+                        merge.routines.len(),
+                        name(target_id),
+                    )]
+                },
+                p![format!(
+                    "Afterwards \"{}\" will be permanently deleted. If any reference cannot be replaced, the exercise is kept.",
+                    name(source_id)
+                )],
+            ],
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        ev(Ev::Click, |_| Msg::CloseMergeDialog),
+                        "No"
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-danger"],
+                        C![IF![loading => "is-loading"]],
+                        attrs! {
+                            At::Disabled => data_model.training_sessions.is_loading().as_at_value(),
+                        },
+                        ev(Ev::Click, move |_| Msg::MergeExercise(target_id)),
+                        "Yes, merge exercises",
+                    ]
+                ],
+            ],
+        ],
+        &ev(Ev::Click, |_| Msg::CloseMergeDialog),
+    )
+}
+
 fn exercise_training_sessions(
     model: &Model,
     data_model: &data::Model,
@@ -791,6 +1470,8 @@ fn exercise_training_sessions(
             routine_id: t.routine_id,
             date: t.date,
             notes: t.notes.clone(),
+            start_time: t.start_time,
+            end_time: t.end_time,
             elements: t
                 .elements
                 .iter()
@@ -1,10 +1,13 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use seed::{prelude::*, *};
 use valens_domain as domain;
 
 use crate::{common, component, data};
 
+/// Hash path part for filtering the exercises by a muscle, followed by the muscle ID.
+pub const MUSCLE: &str = "muscle";
+
 // ------ ------
 //     Init
 // ------ ------
@@ -18,12 +21,36 @@ pub fn init(mut url: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Nav
 
     navbar.title = String::from("Exercises");
 
-    let mut exercise_list = component::exercise_list::Model::new(true, true, true, true);
-    exercise_list.search_term = url.hash_path().get(1).cloned().unwrap_or_default();
+    let hash_path = url.hash_path();
+    let muscle = if hash_path.get(1).map(String::as_str) == Some(MUSCLE) {
+        hash_path
+            .get(2)
+            .and_then(|id| id.parse::<u8>().ok())
+            .and_then(domain::Muscle::from_repr)
+    } else {
+        None
+    };
+    let exercise_list = if let Some(muscle) = muscle {
+        component::exercise_list::Model::new_with_filter(
+            true,
+            true,
+            true,
+            true,
+            domain::ExerciseFilter {
+                muscles: [muscle].into_iter().collect(),
+                archived: false,
+            },
+        )
+    } else {
+        let mut exercise_list = component::exercise_list::Model::new(true, true, true, true);
+        exercise_list.search_term = hash_path.get(1).cloned().unwrap_or_default();
+        exercise_list
+    };
 
     Model {
         exercise_list,
         dialog: Dialog::Hidden,
+        archive_visible: false,
         loading: false,
     }
 }
@@ -35,6 +62,7 @@ pub fn init(mut url: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Nav
 pub struct Model {
     exercise_list: component::exercise_list::Model,
     dialog: Dialog,
+    archive_visible: bool,
     loading: bool,
 }
 
@@ -43,6 +71,8 @@ enum Dialog {
     AddExercise(Form),
     EditExercise(Form),
     DeleteExercise(u32),
+    /// Muscle stimulus of the selected exercises before and after editing.
+    SetMuscles(BTreeMap<u8, u8>, BTreeMap<u8, u8>),
 }
 
 struct Form {
@@ -58,14 +88,22 @@ pub enum Msg {
     ShowAddExerciseDialog,
     ShowEditExerciseDialog(u32),
     ShowDeleteExerciseDialog(u32),
+    ShowSetMusclesDialog,
     CloseExerciseDialog,
 
+    ToggleSelectionMode,
+    SetMuscleStimulus(u8, u8),
+    SaveMuscles,
+
     ExerciseList(component::exercise_list::Msg),
     NameChanged(String),
+    ShowArchive,
 
     GoToExercise(u32),
     SaveExercise,
+    ChangeArchived(u32, bool),
     DeleteExercise(u32),
+    Retry,
     DataEvent(data::Event),
 }
 
@@ -91,17 +129,61 @@ pub fn update(
                     input: name.clone(),
                     parsed: Some(name.clone()),
                     orig: name,
+                    error: None,
                 },
             });
         }
         Msg::ShowDeleteExerciseDialog(id) => {
             model.dialog = Dialog::DeleteExercise(id);
+            orders.notify(data::Msg::ReadOlderTrainingSessions(None));
+        }
+        Msg::ShowSetMusclesDialog => {
+            let muscle_stimulus = domain::common_muscle_stimulus(&selected_exercises(
+                &model.exercise_list,
+                data_model,
+            ));
+            model.dialog = Dialog::SetMuscles(muscle_stimulus.clone(), muscle_stimulus);
         }
         Msg::CloseExerciseDialog => {
             model.dialog = Dialog::Hidden;
             Url::go_and_replace(&crate::Urls::new(&data_model.base_url).exercises());
         }
 
+        Msg::ToggleSelectionMode => {
+            model.exercise_list.selection = match model.exercise_list.selection {
+                Some(_) => None,
+                None => Some(BTreeSet::new()),
+            };
+        }
+        Msg::SetMuscleStimulus(muscle_id, stimulus) => {
+            if let Dialog::SetMuscles(_, ref mut muscle_stimulus) = model.dialog {
+                match stimulus {
+                    0 => {
+                        muscle_stimulus.remove(&muscle_id);
+                    }
+                    1..=100 => {
+                        muscle_stimulus.insert(muscle_id, stimulus);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Msg::SaveMuscles => {
+            if let Dialog::SetMuscles(ref before, ref after) = model.dialog {
+                if before == after {
+                    orders.send_msg(Msg::CloseExerciseDialog);
+                } else {
+                    model.loading = true;
+                    orders.notify(data::Msg::ReplaceExercises(
+                        selected_exercises(&model.exercise_list, data_model)
+                            .into_iter()
+                            .map(|e| e.with_changed_muscle_stimulus(before, after))
+                            .collect(),
+                    ));
+                }
+            }
+        }
+
         Msg::ExerciseList(msg) => {
             match component::exercise_list::update(
                 msg,
@@ -141,19 +223,24 @@ pub fn update(
                         input: name.clone(),
                         parsed: Some(trimmed_name.to_string()),
                         orig: form.name.orig.clone(),
+                        error: None,
                     };
                 } else {
                     form.name = common::InputField {
                         input: name.clone(),
                         parsed: None,
                         orig: form.name.orig.clone(),
+                        error: None,
                     };
                 }
             }
-            Dialog::Hidden | Dialog::DeleteExercise(_) => {
+            Dialog::Hidden | Dialog::DeleteExercise(_) | Dialog::SetMuscles(..) => {
                 panic!();
             }
         },
+        Msg::ShowArchive => {
+            model.archive_visible = true;
+        }
 
         Msg::GoToExercise(id) => {
             let url = crate::Urls::new(&data_model.base_url)
@@ -172,21 +259,39 @@ pub fn update(
                     ));
                 }
                 Dialog::EditExercise(ref mut form) => {
+                    let exercise = data_model.exercises.get(&form.id);
                     orders.notify(data::Msg::ReplaceExercise(domain::Exercise {
                         id: form.id,
                         name: form.name.parsed.clone().unwrap(),
                         muscles: vec![],
+                        weight_convention: exercise.and_then(|e| e.weight_convention),
+                        base_weight: exercise.and_then(|e| e.base_weight),
+                        laterality: exercise.map(|e| e.laterality).unwrap_or_default(),
+                        archived: exercise.is_some_and(|e| e.archived),
+                        image: exercise.and_then(|e| e.image.clone()),
                     }));
                 }
-                Dialog::Hidden | Dialog::DeleteExercise(_) => {
+                Dialog::Hidden | Dialog::DeleteExercise(_) | Dialog::SetMuscles(..) => {
                     panic!();
                 }
             };
         }
+        Msg::ChangeArchived(id, archived) => {
+            if let Some(exercise) = data_model.exercises.get(&id) {
+                model.loading = true;
+                orders.notify(data::Msg::ReplaceExercise(domain::Exercise {
+                    archived,
+                    ..exercise.clone()
+                }));
+            }
+        }
         Msg::DeleteExercise(id) => {
             model.loading = true;
             orders.notify(data::Msg::DeleteExercise(id));
         }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadExercises);
+        }
         Msg::DataEvent(event) => {
             model.loading = false;
             match event {
@@ -195,6 +300,13 @@ pub fn update(
                 | data::Event::ExerciseDeletedOk => {
                     orders.skip().send_msg(Msg::CloseExerciseDialog);
                 }
+                data::Event::ExercisesReplacedOk => {
+                    model.exercise_list.selection = None;
+                    orders.skip().send_msg(Msg::CloseExerciseDialog);
+                }
+                data::Event::ExercisesReplacedErr => {
+                    orders.skip().send_msg(Msg::CloseExerciseDialog);
+                }
                 _ => {}
             };
         }
@@ -206,23 +318,38 @@ pub fn update(
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.exercises.is_empty() && data_model.loading_exercises {
-        common::view_page_loading()
+    if let Some(node) = common::view_resource_unavailable(&data_model.exercises, |_| Msg::Retry) {
+        node
     } else {
         div![
-            view_exercise_dialog(&model.dialog, &data_model.exercises, model.loading),
+            if let Dialog::SetMuscles(_, muscle_stimulus) = &model.dialog {
+                view_set_muscles_dialog(
+                    muscle_stimulus,
+                    model
+                        .exercise_list
+                        .selection
+                        .as_ref()
+                        .map_or(0, BTreeSet::len),
+                    data_model.exercise_batch_progress,
+                    model.loading,
+                )
+            } else {
+                view_exercise_dialog(&model.dialog, data_model, model.loading)
+            },
+            view_selection_bar(model.exercise_list.selection.as_ref()),
             component::exercise_list::view(&model.exercise_list, model.loading, data_model)
                 .map_msg(Msg::ExerciseList),
+            view_archive(
+                &model.exercise_list.search_term,
+                model.archive_visible,
+                data_model
+            ),
             common::view_fab("plus", |_| Msg::ShowAddExerciseDialog),
         ]
     }
 }
 
-fn view_exercise_dialog(
-    dialog: &Dialog,
-    exercises: &BTreeMap<u32, domain::Exercise>,
-    loading: bool,
-) -> Node<Msg> {
+fn view_exercise_dialog(dialog: &Dialog, data_model: &data::Model, loading: bool) -> Node<Msg> {
     let title;
     let form;
     match dialog {
@@ -235,16 +362,16 @@ fn view_exercise_dialog(
             form = f;
         }
         Dialog::DeleteExercise(id) => {
-            let exercise = &exercises[id];
-            let id = exercise.id;
-            return common::view_delete_confirmation_dialog(
-                "exercise",
-                &ev(Ev::Click, move |_| Msg::DeleteExercise(id)),
-                &ev(Ev::Click, |_| Msg::CloseExerciseDialog),
+            return view_delete_exercise_dialog(
+                *id,
+                data_model,
+                Msg::DeleteExercise,
+                |id| Msg::ChangeArchived(id, true),
+                || Msg::CloseExerciseDialog,
                 loading,
             );
         }
-        Dialog::Hidden => {
+        Dialog::Hidden | Dialog::SetMuscles(..) => {
             return empty![];
         }
     }
@@ -310,3 +437,321 @@ fn view_exercise_dialog(
         &ev(Ev::Click, |_| Msg::CloseExerciseDialog),
     )
 }
+
+/// Confirmation dialog for deleting an exercise.
+///
+/// The routines and training sessions referring to the exercise are listed, as deleting the
+/// exercise also deletes its sets in these routines and training sessions. Archiving the exercise
+/// is offered as alternative, if the exercise is referenced.
+pub fn view_delete_exercise_dialog<Ms: 'static>(
+    exercise_id: u32,
+    data_model: &data::Model,
+    delete_message: fn(u32) -> Ms,
+    archive_message: fn(u32) -> Ms,
+    close_message: fn() -> Ms,
+    loading: bool,
+) -> Node<Ms> {
+    let Some(exercise) = data_model.exercises.get(&exercise_id) else {
+        return empty![];
+    };
+    let usage = domain::exercise_usage(
+        exercise_id,
+        &data_model.routines.values().collect::<Vec<_>>(),
+        &data_model.training_sessions.values().collect::<Vec<_>>(),
+    );
+    let usage_unknown = data_model.training_sessions.is_loading();
+    common::view_dialog(
+        "danger",
+        "Delete the exercise?",
+        nodes![
+            div![
+                C!["block"],
+                if usage_unknown {
+                    nodes![p!["Loading training sessions …"]]
+                } else if usage.is_empty() {
+                    nodes![p![format!(
+                        "\"{}\" is not used in any routine or training session and will be permanently deleted.",
+                        exercise.name
+                    )]]
+                } else {
+                    nodes![
+                        p![format!(
+                            "\"{}\" is used in {} training session{} and {} routine{}.",
+                            exercise.name,
+                            usage.training_sessions,
+                            if usage.training_sessions == 1 {
+                                ""
+                            } else {
+                                "s"
+                            },
+                            usage.routines.len(),
+                            if usage.routines.len() == 1 { "" } else { "s" },
+                        )],
+                        IF![not(usage.routines.is_empty()) => ul![
+                            C!["my-2"],
+                            usage.routines.iter().filter_map(|id| {
+                                data_model.routines.get(id).map(|routine| {
+                                    li![a![
+                                        attrs! {
+                                            At::Href => crate::Urls::new(&data_model.base_url)
+                                                .routine()
+                                                .add_hash_path_part(id.to_string()),
+                                        },
+                                        &routine.name,
+                                    ]]
+                                })
+                            })
+                        ]],
+                        p![
+                            "Deleting the exercise also permanently deletes all its sets in these \
+                             training sessions and routines. An archived exercise keeps its \
+                             history, but is not offered for selection anymore."
+                        ],
+                    ]
+                },
+            ],
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                C!["is-flex-wrap-wrap"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        ev(Ev::Click, move |_| close_message()),
+                        "No"
+                    ]
+                ],
+                IF![not(usage.is_empty()) && not(exercise.archived) => div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-link"],
+                        C![IF![loading => "is-loading"]],
+                        attrs! {
+                            At::Disabled => usage_unknown.as_at_value(),
+                        },
+                        ev(Ev::Click, move |_| archive_message(exercise_id)),
+                        "Archive instead",
+                    ]
+                ]],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-danger"],
+                        C![IF![loading => "is-loading"]],
+                        attrs! {
+                            At::Disabled => usage_unknown.as_at_value(),
+                        },
+                        ev(Ev::Click, move |_| delete_message(exercise_id)),
+                        "Yes, delete exercise",
+                    ]
+                ],
+            ],
+        ],
+        &ev(Ev::Click, move |_| close_message()),
+    )
+}
+
+fn view_archive(search_term: &str, archive_visible: bool, data_model: &data::Model) -> Node<Msg> {
+    let mut archived_exercises = data_model
+        .exercises(&domain::ExerciseFilter {
+            archived: true,
+            ..domain::ExerciseFilter::default()
+        })
+        .into_iter()
+        .filter(|e| {
+            e.name
+                .to_lowercase()
+                .contains(search_term.to_lowercase().trim())
+        })
+        .collect::<Vec<_>>();
+    archived_exercises.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if archived_exercises.is_empty() {
+        empty![]
+    } else if archive_visible {
+        div![
+            C!["mt-4"],
+            common::view_title(&span!["Archive"], 3),
+            div![
+                C!["table-container"],
+                table![
+                    C!["table"],
+                    C!["is-fullwidth"],
+                    C!["is-hoverable"],
+                    tbody![archived_exercises.iter().map(|e| {
+                        let id = e.id;
+                        tr![td![
+                            C!["is-flex"],
+                            C!["is-justify-content-space-between"],
+                            C!["has-text-link"],
+                            span![ev(Ev::Click, move |_| Msg::GoToExercise(id)), &e.name],
+                            p![
+                                C!["is-flex is-flex-wrap-nowrap"],
+                                a![
+                                    C!["icon"],
+                                    C!["mr-1"],
+                                    ev(Ev::Click, move |_| Msg::ChangeArchived(id, false)),
+                                    i![C!["fas fa-box-open"]]
+                                ],
+                                a![
+                                    C!["icon"],
+                                    C!["ml-1"],
+                                    ev(Ev::Click, move |_| Msg::ShowDeleteExerciseDialog(id)),
+                                    i![C!["fas fa-times"]]
+                                ]
+                            ]
+                        ]]
+                    })],
+                ]
+            ]
+        ]
+    } else {
+        div![
+            C!["has-text-centered"],
+            C!["mb-6"],
+            button![
+                C!["button"],
+                C!["is-small"],
+                ev(Ev::Click, |_| Msg::ShowArchive),
+                span![C!["icon"], C!["is-small"], i![C!["fas fa-box-archive"]]],
+                span!["Show archive"]
+            ]
+        ]
+    }
+}
+
+fn view_selection_bar(selection: Option<&BTreeSet<u32>>) -> Node<Msg> {
+    div![
+        C!["field"],
+        C!["is-grouped"],
+        C!["is-grouped-right"],
+        C!["is-align-items-center"],
+        C!["px-4"],
+        if let Some(selection) = selection {
+            nodes![
+                div![
+                    C!["control"],
+                    C!["is-size-7"],
+                    format!("{} selected", selection.len())
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-small"],
+                        C!["is-link"],
+                        attrs! {
+                            At::Disabled => selection.is_empty().as_at_value(),
+                        },
+                        ev(Ev::Click, |_| Msg::ShowSetMusclesDialog),
+                        "Set muscles…",
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-small"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        ev(Ev::Click, |_| Msg::ToggleSelectionMode),
+                        "Cancel",
+                    ]
+                ],
+            ]
+        } else {
+            nodes![div![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-small"],
+                    ev(Ev::Click, |_| Msg::ToggleSelectionMode),
+                    span![C!["icon"], i![C!["fas fa-check-square"]]],
+                    span!["Select"],
+                ]
+            ]]
+        }
+    ]
+}
+
+fn view_set_muscles_dialog(
+    muscle_stimulus: &BTreeMap<u8, u8>,
+    exercise_count: usize,
+    progress: Option<(usize, usize)>,
+    loading: bool,
+) -> Node<Msg> {
+    common::view_dialog(
+        "primary",
+        &format!("Set muscles of {exercise_count} exercises"),
+        nodes![
+            p![
+                C!["help"],
+                C!["mb-3"],
+                "Only the changed muscles are applied to the selected exercises"
+            ],
+            common::view_muscle_stimulus_editor(muscle_stimulus, Msg::SetMuscleStimulus),
+            if let Some((done, total)) = progress {
+                progress![
+                    C!["progress"],
+                    C!["is-small"],
+                    C!["is-primary"],
+                    attrs! {
+                        At::Value => done,
+                        At::Max => total,
+                    },
+                    format!("{done} / {total}")
+                ]
+            } else {
+                empty![]
+            },
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                C!["mt-5"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        attrs! {
+                            At::Disabled => loading.as_at_value(),
+                        },
+                        ev(Ev::Click, |_| Msg::CloseExerciseDialog),
+                        "Cancel",
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-primary"],
+                        C![IF![loading => "is-loading"]],
+                        ev(Ev::Click, |_| Msg::SaveMuscles),
+                        "Save",
+                    ]
+                ],
+            ],
+        ],
+        &ev(Ev::Click, |_| Msg::CloseExerciseDialog),
+    )
+}
+
+fn selected_exercises<'a>(
+    exercise_list: &component::exercise_list::Model,
+    data_model: &'a data::Model,
+) -> Vec<&'a domain::Exercise> {
+    exercise_list
+        .selection
+        .iter()
+        .flatten()
+        .filter_map(|id| data_model.exercises.get(id))
+        .collect()
+}
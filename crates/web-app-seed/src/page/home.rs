@@ -1,6 +1,7 @@
 use chrono::prelude::*;
 use seed::{prelude::*, *};
 use valens_domain as domain;
+use valens_web_app as web_app;
 
 use crate::{common, data};
 
@@ -11,22 +12,63 @@ use crate::{common, data};
 #[allow(clippy::needless_pass_by_value)]
 pub fn init(
     _url: Url,
-    _orders: &mut impl Orders<Msg>,
+    orders: &mut impl Orders<Msg>,
     data_model: &data::Model,
     navbar: &mut crate::Navbar,
 ) -> Model {
+    orders.subscribe(Msg::DataEvent);
+
     navbar
         .title
         .clone_from(&data_model.session.as_ref().unwrap().name);
 
-    Model {}
+    Model {
+        dashboard: Dashboard::new(data_model, web_app::clock::today()),
+    }
 }
 
 // ------ ------
 //     Model
 // ------ ------
 
-pub struct Model {}
+pub struct Model {
+    dashboard: Dashboard,
+}
+
+/// Statistics of the dashboard, which are cached as their calculation requires iterating over all
+/// training sessions.
+struct Dashboard {
+    key: DashboardKey,
+    stats: domain::dashboard::Dashboard,
+}
+
+/// Change counters of training sessions, exercises, body weight and period, weekly session target,
+/// trend window and current date.
+type DashboardKey = (u32, u32, u32, u32, u32, u32, NaiveDate);
+
+impl Dashboard {
+    fn new(data_model: &data::Model, today: NaiveDate) -> Self {
+        Self {
+            key: Self::key(data_model, today),
+            stats: data_model.dashboard(&domain::Interval {
+                first: today,
+                last: today,
+            }),
+        }
+    }
+
+    fn key(data_model: &data::Model, today: NaiveDate) -> DashboardKey {
+        (
+            data_model.changes.training_sessions,
+            data_model.changes.exercises,
+            data_model.changes.body_weight,
+            data_model.changes.period,
+            data_model.settings.weekly_session_target,
+            data_model.settings.trend_window,
+            today,
+        )
+    }
+}
 
 // ------ ------
 //    Update
@@ -34,13 +76,25 @@ pub struct Model {}
 
 pub enum Msg {
     ChangePage(Url),
+    DataEvent(data::Event),
 }
 
-pub fn update(msg: Msg, _model: &mut Model, orders: &mut impl Orders<Msg>) {
+pub fn update(
+    msg: Msg,
+    model: &mut Model,
+    data_model: &data::Model,
+    orders: &mut impl Orders<Msg>,
+) {
     match msg {
         Msg::ChangePage(url) => {
             orders.request_url(url);
         }
+        Msg::DataEvent(_) => {
+            let today = web_app::clock::today();
+            if model.dashboard.key != Dashboard::key(data_model, today) {
+                model.dashboard = Dashboard::new(data_model, today);
+            }
+        }
     }
 }
 
@@ -48,64 +102,101 @@ pub fn update(msg: Msg, _model: &mut Model, orders: &mut impl Orders<Msg>) {
 //     View
 // ------ ------
 
-pub fn view(_model: &Model, data_model: &data::Model) -> Node<Msg> {
+pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
     let sex = data_model.session.as_ref().unwrap().sex;
-    let today: NaiveDate = Local::now().date_naive();
+    let today: NaiveDate = web_app::clock::today();
+    let settings = &data_model.settings;
+    let separator = settings.decimal_separator;
+
+    // The cache is outdated if the settings or the date changed without a data event
+    let current_dashboard;
+    let dashboard = if model.dashboard.key == Dashboard::key(data_model, today) {
+        &model.dashboard.stats
+    } else {
+        current_dashboard = Dashboard::new(data_model, today).stats;
+        &current_dashboard
+    };
+
     let body_weight_subtitle;
     let body_weight_content;
     let body_fat_subtitle;
     let body_fat_content;
+    let body_measurement_subtitle;
+    let body_measurement_content;
 
     let training_subtitle =
-        if data_model.training_sessions.is_empty() && data_model.loading_training_sessions {
-            common::view_loading::<Msg>().to_string()
-        } else if let Some(load_ratio) = &data_model.training_stats.load_ratio() {
-            String::from(if *load_ratio > domain::TrainingStats::LOAD_RATIO_HIGH {
-                "high load"
+        if let Some(subtitle) = view_unavailable_subtitle(&data_model.training_sessions) {
+            subtitle
+        } else if let Some(load_ratio) = &dashboard.load_ratio {
+            let (text, color) = if *load_ratio > domain::TrainingStats::LOAD_RATIO_HIGH {
+                ("high load", "has-text-danger")
             } else if *load_ratio < domain::TrainingStats::LOAD_RATIO_LOW {
-                "low load"
+                ("low load", "has-text-warning")
             } else {
-                "optimal load"
-            })
+                ("optimal load", "has-text-success")
+            };
+            format!(
+                "{text}{}",
+                trend(dashboard.load_ratio_trend, color, settings)
+            )
         } else {
             String::new()
         };
-    let training_content = if let Some(date) = data_model
-        .training_sessions
-        .values()
-        .map(|ts| ts.date)
-        .max()
-    {
-        last("session", today - date)
+    let training_content = if let Some(date) = dashboard.last_training_session {
+        let mut content = last("session", today - date);
+        if dashboard.set_volume_trend.is_some() {
+            content.push_str(&format!(
+                "<br>Weekly set volume{}",
+                trend(dashboard.set_volume_trend, "has-text-grey", settings)
+            ));
+        }
+        content
     } else {
         String::new()
     };
 
-    if data_model.body_weight.is_empty() && data_model.loading_body_weight {
-        body_weight_subtitle = common::view_loading::<Msg>().to_string();
+    if let Some(subtitle) = view_unavailable_subtitle(&data_model.body_weight) {
+        body_weight_subtitle = subtitle;
         body_weight_content = String::new();
     } else if let Some(body_weight) = data_model
         .body_weight
         .values()
         .max_by(|a, b| a.date.cmp(&b.date))
     {
-        body_weight_subtitle = format!("{:.1} kg", body_weight.weight);
+        body_weight_subtitle = format!(
+            "{}{}",
+            web_app::format::format_weight(
+                body_weight.weight,
+                "kg",
+                web_app::format::PRECISION_WEIGHT,
+                separator
+            ),
+            trend(
+                dashboard.body_weight_window_trend,
+                "has-text-grey",
+                settings
+            )
+        );
         body_weight_content = last("entry", today - body_weight.date);
     } else {
         body_weight_subtitle = String::new();
         body_weight_content = String::new();
     }
 
-    if data_model.body_fat.is_empty() && data_model.loading_body_fat {
-        body_fat_subtitle = common::view_loading::<Msg>().to_string();
+    if let Some(subtitle) = view_unavailable_subtitle(&data_model.body_fat) {
+        body_fat_subtitle = subtitle;
         body_fat_content = String::new();
     } else if let Some(body_fat) = data_model
         .body_fat
         .values()
         .max_by(|a, b| a.date.cmp(&b.date))
     {
-        body_fat_subtitle = if let Some(jp3) = body_fat.jp3(sex) {
-            format!("{jp3:.1} %")
+        body_fat_subtitle = if let Some(jp3) = body_fat.jp3(sex, data_model.age(body_fat.date)) {
+            web_app::format::format_percentage(
+                jp3,
+                web_app::format::PRECISION_PERCENTAGE,
+                separator,
+            )
         } else {
             String::new()
         };
@@ -115,18 +206,46 @@ pub fn view(_model: &Model, data_model: &data::Model) -> Node<Msg> {
         body_fat_content = String::new();
     }
 
-    let menstrual_cycle_subtitle = if data_model.period.is_empty() && data_model.loading_period {
-        common::view_loading::<Msg>().to_string()
-    } else if let Some(current_cycle) = &data_model.current_cycle {
-        format!(
-            "{} (±{}) days left",
-            current_cycle.time_left.num_days(),
-            current_cycle.time_left_variation.num_days(),
-        )
+    if let Some(subtitle) = view_unavailable_subtitle(&data_model.body_measurement) {
+        body_measurement_subtitle = subtitle;
+        body_measurement_content = String::new();
+    } else if let Some(body_measurement) = data_model
+        .body_measurement
+        .values()
+        .max_by(|a, b| a.date.cmp(&b.date))
+    {
+        body_measurement_subtitle = if let Some(waist) = body_measurement.waist {
+            format!(
+                "{} cm waist",
+                web_app::format::format_number(waist, 1, separator)
+            )
+        } else {
+            String::new()
+        };
+        body_measurement_content = last("entry", today - body_measurement.date);
     } else {
-        String::new()
-    };
-    let menstrual_cycle_content = if let Some(current_cycle) = &data_model.current_cycle {
+        body_measurement_subtitle = String::new();
+        body_measurement_content = String::new();
+    }
+
+    let menstrual_cycle_subtitle =
+        if let Some(subtitle) = view_unavailable_subtitle(&data_model.period) {
+            subtitle
+        } else if let Some(domain::CurrentCycle {
+            time_left: Some(time_left),
+            time_left_variation,
+            ..
+        }) = &dashboard.current_cycle
+        {
+            format!(
+                "{} (±{}) days left",
+                time_left.num_days(),
+                time_left_variation.num_days(),
+            )
+        } else {
+            String::new()
+        };
+    let menstrual_cycle_content = if let Some(current_cycle) = &dashboard.current_cycle {
         last("cycle started", today - current_cycle.begin)
     } else {
         String::new()
@@ -139,6 +258,7 @@ pub fn view(_model: &Model, data_model: &data::Model) -> Node<Msg> {
             &training_content,
             crate::Urls::new(&data_model.base_url).training()
         ),
+        view_weekly_summary(dashboard, data_model),
         view_tile(
             "Body weight",
             &body_weight_subtitle,
@@ -151,8 +271,14 @@ pub fn view(_model: &Model, data_model: &data::Model) -> Node<Msg> {
             &body_fat_content,
             crate::Urls::new(&data_model.base_url).body_fat()
         ),
+        view_tile(
+            "Body measurements",
+            &body_measurement_subtitle,
+            &body_measurement_content,
+            crate::Urls::new(&data_model.base_url).body_measurement()
+        ),
         IF![
-            data_model.session.as_ref().unwrap().sex == 0 => {
+            data_model.show_menstrual_cycle() => {
                 view_tile(
                     "Menstrual cycle",
                     &menstrual_cycle_subtitle,
@@ -160,6 +286,144 @@ pub fn view(_model: &Model, data_model: &data::Model) -> Node<Msg> {
                     crate::Urls::new(&data_model.base_url).menstrual_cycle())
             }
         ],
+        div![
+            C!["has-text-centered"],
+            C!["m-5"],
+            a![
+                C!["button"],
+                C!["is-link"],
+                C!["is-outlined"],
+                attrs! {
+                    At::Href => crate::Urls::new(&data_model.base_url).statistics(),
+                },
+                span![C!["icon"], i![C!["fas fa-chart-simple"]]],
+                span!["All-time statistics"]
+            ]
+        ],
+    ]
+}
+
+/// Subtitle of a tile if the data has not been loaded yet.
+fn view_unavailable_subtitle<T>(resource: &web_app::resource::Resource<T>) -> Option<String> {
+    if resource.is_available() {
+        None
+    } else if resource.error().is_some() {
+        Some(String::from(
+            "<span class=\"has-text-danger\">failed to load data</span>",
+        ))
+    } else {
+        Some(common::view_loading::<Msg>().to_string())
+    }
+}
+
+fn view_weekly_summary(
+    dashboard: &domain::dashboard::Dashboard,
+    data_model: &data::Model,
+) -> Node<Msg> {
+    if !data_model.training_sessions.is_available() {
+        return empty![];
+    }
+
+    div![
+        C!["grid"],
+        C!["mx-3"],
+        div![
+            C!["cell"],
+            div![
+                C!["box"],
+                p![C!["title"], C!["is-size-5"], "This week"],
+                if let Some(summary) = &dashboard.weekly_summary {
+                    let previous_hard_sets = dashboard
+                        .previous_weekly_summary
+                        .as_ref()
+                        .map_or(0, |s| s.hard_sets);
+                    let (icon, color) = match summary.hard_sets.cmp(&previous_hard_sets) {
+                        std::cmp::Ordering::Greater => ("fa-arrow-up", "has-text-success"),
+                        std::cmp::Ordering::Less => ("fa-arrow-down", "has-text-danger"),
+                        std::cmp::Ordering::Equal => ("fa-equals", "has-text-grey"),
+                    };
+                    div![
+                        C!["content"],
+                        p![
+                            format!(
+                                "{} session{}, {} hard sets ",
+                                summary.sessions,
+                                if summary.sessions == 1 { "" } else { "s" },
+                                summary.hard_sets
+                            ),
+                            span![
+                                C!["icon"],
+                                C![color],
+                                attrs! {
+                                    At::Title => format!("{previous_hard_sets} hard sets in previous week"),
+                                },
+                                i![C!["fas"], C![icon]]
+                            ],
+                        ],
+                        p![
+                            format!("Volume load {}", summary.volume_load),
+                            IF![data_model.settings.show_tut => {
+                                summary.tut.map(|tut| format!(", TUT {tut} s")).unwrap_or_default()
+                            }],
+                        ],
+                        IF![!summary.hard_sets_per_muscle.is_empty() => {
+                            p![summary
+                                .hard_sets_per_muscle
+                                .iter()
+                                .filter_map(|(muscle_id, sets)| {
+                                    domain::Muscle::from_repr(*muscle_id)
+                                        .map(|m| format!(
+                                            "{} {}",
+                                            m.name(),
+                                            web_app::format::format_number(
+                                                *sets,
+                                                1,
+                                                data_model.settings.decimal_separator
+                                            )
+                                        ))
+                                })
+                                .collect::<Vec<_>>()
+                                .join(", ")]
+                        }],
+                    ]
+                } else {
+                    p![C!["is-italic"], "No training yet"]
+                },
+                view_training_streak(dashboard.training_streak, data_model),
+            ]
+        ]
+    ]
+}
+
+fn view_training_streak(streak: domain::TrainingStreak, data_model: &data::Model) -> Node<Msg> {
+    let weekly_target = data_model.settings.weekly_session_target;
+    if streak.longest == 0 {
+        return empty![];
+    }
+    p![
+        C!["mt-3"],
+        attrs! {
+            At::Title => format!(
+                "Weeks with at least {weekly_target} session{}, longest streak {} week{}",
+                if weekly_target == 1 { "" } else { "s" },
+                streak.longest,
+                if streak.longest == 1 { "" } else { "s" }
+            ),
+        },
+        span![
+            C!["icon"],
+            C![if streak.current > 0 {
+                "has-text-warning"
+            } else {
+                "has-text-grey"
+            }],
+            i![C!["fas fa-fire"]]
+        ],
+        format!(
+            "{} week{} streak",
+            streak.current,
+            if streak.current == 1 { "" } else { "s" }
+        ),
     ]
 }
 
@@ -204,6 +468,27 @@ fn view_tile(title: &str, subtitle: &str, content: &str, target: Url) -> Node<Ms
     ]
 }
 
+/// Arrow and percentage of the change over the trend window.
+fn trend(trend: Option<domain::Trend>, color: &str, settings: &web_app::Settings) -> String {
+    let Some(trend) = trend else {
+        return String::new();
+    };
+    let icon = match trend.direction {
+        domain::TrendDirection::Up => "fa-arrow-up",
+        domain::TrendDirection::Down => "fa-arrow-down",
+        domain::TrendDirection::Flat => "fa-arrow-right",
+    };
+    format!(
+        r#" <span class="is-size-6 {color}" title="Change over the last {} days"><span class="icon"><i class="fas {icon}"></i></span>{} %</span>"#,
+        settings.trend_window,
+        web_app::format::format_signed(
+            trend.percent,
+            web_app::format::PRECISION_PERCENTAGE,
+            settings.decimal_separator
+        )
+    )
+}
+
 fn last(text: &str, duration: chrono::Duration) -> String {
     if duration.num_days() == 0 {
         return format!("Last {text} <strong>today</strong>.");
@@ -7,7 +7,9 @@ use crate::{common, data};
 // ------ ------
 
 pub fn init(url: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Navbar) -> Model {
-    orders.notify(data::Msg::ReadUsers);
+    orders
+        .subscribe(Msg::DataEvent)
+        .notify(data::Msg::ReadUsers);
 
     navbar.title = String::from("Valens");
     navbar.items = vec![(
@@ -19,14 +21,25 @@ pub fn init(url: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Navbar)
         String::from("gears"),
     )];
 
-    Model {}
+    Model {
+        dialog: Dialog::Hidden,
+        loading: false,
+    }
 }
 
 // ------ ------
 //     Model
 // ------ ------
 
-pub struct Model {}
+pub struct Model {
+    dialog: Dialog,
+    loading: bool,
+}
+
+enum Dialog {
+    Hidden,
+    EnterPin(u32, String, String),
+}
 
 // ------ ------
 //    Update
@@ -34,13 +47,66 @@ pub struct Model {}
 
 pub enum Msg {
     LogIn(u32),
+    PinChanged(String),
+    SubmitPin,
+    ClosePinDialog,
+    Retry,
+    DataEvent(data::Event),
 }
 
-#[allow(clippy::needless_pass_by_value)]
-pub fn update(msg: Msg, _model: &mut Model, orders: &mut impl Orders<Msg>) {
+const ERROR_WRONG_PIN: &str = "Wrong PIN";
+const ERROR_PIN_BLOCKED: &str = "Too many failed attempts, try again in 30 seconds";
+
+pub fn update(
+    msg: Msg,
+    model: &mut Model,
+    data_model: &data::Model,
+    orders: &mut impl Orders<Msg>,
+) {
     match msg {
         Msg::LogIn(user_id) => {
-            orders.skip().notify(data::Msg::RequestSession(user_id));
+            if data_model.users.get(&user_id).is_some_and(|u| u.has_pin) {
+                model.dialog = Dialog::EnterPin(user_id, String::new(), String::new());
+            } else {
+                orders
+                    .skip()
+                    .notify(data::Msg::RequestSession(user_id, None));
+            }
+        }
+        Msg::PinChanged(pin) => {
+            if let Dialog::EnterPin(_, ref mut user_pin, ref mut error) = model.dialog {
+                *user_pin = pin;
+                *error = String::new();
+            }
+        }
+        Msg::SubmitPin => {
+            if let Dialog::EnterPin(user_id, ref pin, _) = model.dialog {
+                model.loading = true;
+                orders.notify(data::Msg::RequestSession(user_id, Some(pin.clone())));
+            }
+        }
+        Msg::ClosePinDialog => {
+            model.dialog = Dialog::Hidden;
+            model.loading = false;
+        }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadUsers);
+        }
+        Msg::DataEvent(event) => {
+            model.loading = false;
+            if let Dialog::EnterPin(_, ref mut pin, ref mut error) = model.dialog {
+                match event {
+                    data::Event::PinRejected => {
+                        *pin = String::new();
+                        *error = ERROR_WRONG_PIN.into();
+                    }
+                    data::Event::PinBlocked => {
+                        *pin = String::new();
+                        *error = ERROR_PIN_BLOCKED.into();
+                    }
+                    _ => {}
+                }
+            }
         }
     }
 }
@@ -49,29 +115,119 @@ pub fn update(msg: Msg, _model: &mut Model, orders: &mut impl Orders<Msg>) {
 //     View
 // ------ ------
 
-pub fn view(_model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.users.is_empty() && data_model.loading_users {
-        common::view_page_loading()
+pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    if let Some(node) = common::view_resource_unavailable(&data_model.users, |_| Msg::Retry) {
+        node
     } else {
         div![
-            C!["container"],
-            C!["has-text-centered"],
-            &data_model
-                .users
-                .values()
-                .map(|user| {
-                    let user_id = user.id;
-                    div![
-                        C!["column"],
-                        button![
-                            C!["button"],
-                            C!["is-link"],
-                            ev(Ev::Click, move |_| Msg::LogIn(user_id)),
-                            &user.name,
+            if let Dialog::EnterPin(user_id, ref pin, ref error) = model.dialog {
+                view_pin_dialog(
+                    data_model
+                        .users
+                        .get(&user_id)
+                        .map_or("", |u| u.name.as_str()),
+                    pin,
+                    error,
+                    model.loading,
+                )
+            } else {
+                Node::Empty
+            },
+            div![
+                C!["container"],
+                C!["has-text-centered"],
+                &data_model
+                    .users
+                    .values()
+                    .map(|user| {
+                        let user_id = user.id;
+                        div![
+                            C!["column"],
+                            button![
+                                C!["button"],
+                                C!["is-link"],
+                                ev(Ev::Click, move |_| Msg::LogIn(user_id)),
+                                IF![user.has_pin => span![C!["icon"], i![C!["fas fa-lock"]]]],
+                                span![&user.name],
+                            ]
                         ]
-                    ]
-                })
-                .collect::<Vec<_>>(),
+                    })
+                    .collect::<Vec<_>>(),
+            ]
         ]
     }
 }
+
+fn view_pin_dialog(name: &str, pin: &str, error: &str, loading: bool) -> Node<Msg> {
+    let submit_disabled = loading || pin.is_empty();
+    common::view_dialog(
+        "primary",
+        name,
+        nodes![
+            div![
+                C!["field"],
+                label![C!["label"], "PIN"],
+                div![
+                    C!["control"],
+                    input_ev(Ev::Input, Msg::PinChanged),
+                    keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+                        IF!(
+                            not(submit_disabled) && keyboard_event.key_code() == common::ENTER_KEY => {
+                                Msg::SubmitPin
+                            }
+                        )
+                    }),
+                    input![
+                        C!["input"],
+                        C![IF![!error.is_empty() => "is-danger"]],
+                        attrs! {
+                            At::Type => "password",
+                            At::from("inputmode") => "numeric",
+                            At::from("autocomplete") => "off",
+                            At::Value => pin,
+                        }
+                    ],
+                ],
+                p![
+                    C!["help"],
+                    C!["is-danger"],
+                    if error.is_empty() {
+                        raw!["&nbsp;"]
+                    } else {
+                        raw![error]
+                    }
+                ]
+            ],
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                C!["mt-5"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        ev(Ev::Click, |_| Msg::ClosePinDialog),
+                        "Cancel",
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-primary"],
+                        C![IF![loading => "is-loading"]],
+                        attrs![
+                            At::Disabled => submit_disabled.as_at_value(),
+                        ],
+                        ev(Ev::Click, |_| Msg::SubmitPin),
+                        "Log in",
+                    ]
+                ],
+            ],
+        ],
+        &ev(Ev::Click, |_| Msg::ClosePinDialog),
+    )
+}
@@ -1,4 +1,5 @@
-use chrono::prelude::*;
+use chrono::{prelude::*, Months};
+use plotters::style::{Color, Palette, Palette99};
 use seed::{prelude::*, *};
 use valens_domain as domain;
 use valens_web_app as web_app;
@@ -31,7 +32,10 @@ pub fn init(
                 .copied()
                 .collect::<Vec<NaiveDate>>(),
             domain::DefaultInterval::_3M,
+            data_model.settings.initial_interval(),
+            &web_app::clock::BrowserClock,
         ),
+        month: web_app::clock::today().with_day(1).unwrap_or_default(),
         dialog: Dialog::Hidden,
         loading: false,
     }
@@ -43,6 +47,7 @@ pub fn init(
 
 pub struct Model {
     interval: domain::Interval,
+    month: NaiveDate,
     dialog: Dialog,
     loading: bool,
 }
@@ -52,6 +57,7 @@ enum Dialog {
     AddPeriod(Form),
     EditPeriod(Form),
     DeletePeriod(NaiveDate),
+    RestoreFromTrash(web_app::trash::Entry),
 }
 
 struct Form {
@@ -74,9 +80,15 @@ pub enum Msg {
 
     SavePeriod,
     DeletePeriod(NaiveDate),
+    RestoreFromTrash(web_app::trash::Entry),
+    ConfirmRestoreFromTrash,
+    Retry,
     DataEvent(data::Event),
 
-    ChangeInterval(NaiveDate, NaiveDate),
+    ChangeInterval(NaiveDate, NaiveDate, Option<domain::DefaultInterval>),
+    ToggleDataTables,
+    ShowPreviousMonth,
+    ShowNextMonth,
 }
 
 pub fn update(
@@ -87,7 +99,7 @@ pub fn update(
 ) {
     match msg {
         Msg::ShowAddPeriodDialog => {
-            let local = Local::now().date_naive();
+            let local = web_app::clock::today();
             model.dialog = Dialog::AddPeriod(Form {
                 date: (
                     local.to_string(),
@@ -127,7 +139,10 @@ pub fn update(
                 }
                 Err(_) => form.date = (date, None),
             },
-            Dialog::Hidden | Dialog::EditPeriod(_) | Dialog::DeletePeriod(_) => {
+            Dialog::Hidden
+            | Dialog::EditPeriod(_)
+            | Dialog::DeletePeriod(_)
+            | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -147,7 +162,7 @@ pub fn update(
                     Err(_) => form.intensity = (intensity, None),
                 }
             }
-            Dialog::Hidden | Dialog::DeletePeriod(_) => {
+            Dialog::Hidden | Dialog::DeletePeriod(_) | Dialog::RestoreFromTrash(_) => {
                 panic!();
             }
         },
@@ -167,7 +182,7 @@ pub fn update(
                         intensity: form.intensity.1.unwrap(),
                     }));
                 }
-                Dialog::Hidden | Dialog::DeletePeriod(_) => {
+                Dialog::Hidden | Dialog::DeletePeriod(_) | Dialog::RestoreFromTrash(_) => {
                     panic!();
                 }
             };
@@ -176,10 +191,26 @@ pub fn update(
             model.loading = true;
             orders.notify(data::Msg::DeletePeriod(date));
         }
+        Msg::RestoreFromTrash(entry) => {
+            if data_model.period.contains_key(&entry.date()) {
+                model.dialog = Dialog::RestoreFromTrash(entry);
+            } else {
+                orders.notify(data::Msg::RestoreFromTrash(entry));
+            }
+        }
+        Msg::ConfirmRestoreFromTrash => {
+            if let Dialog::RestoreFromTrash(entry) = &model.dialog {
+                model.loading = true;
+                orders.notify(data::Msg::RestoreFromTrash(entry.clone()));
+            }
+        }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadPeriod);
+        }
         Msg::DataEvent(event) => {
             model.loading = false;
             match event {
-                data::Event::DataChanged => {
+                data::Event::PeriodChanged => {
                     model.interval = domain::init_interval(
                         &data_model
                             .period
@@ -187,6 +218,8 @@ pub fn update(
                             .copied()
                             .collect::<Vec<NaiveDate>>(),
                         domain::DefaultInterval::_3M,
+                        data_model.settings.initial_interval(),
+                        &web_app::clock::BrowserClock,
                     );
                 }
                 data::Event::PeriodCreatedOk
@@ -198,9 +231,23 @@ pub fn update(
             };
         }
 
-        Msg::ChangeInterval(first, last) => {
+        Msg::ChangeInterval(first, last, selected_interval) => {
             model.interval.first = first;
             model.interval.last = last;
+            if let Some(selected_interval) = selected_interval {
+                orders.notify(data::Msg::SetInterval(selected_interval));
+            }
+        }
+        Msg::ToggleDataTables => {
+            orders.notify(data::Msg::ToggleDataTables(
+                web_app::chart::Page::MenstrualCycle,
+            ));
+        }
+        Msg::ShowPreviousMonth => {
+            model.month = model.month - Months::new(1);
+        }
+        Msg::ShowNextMonth => {
+            model.month = model.month + Months::new(1);
         }
     }
 }
@@ -210,8 +257,11 @@ pub fn update(
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.period.is_empty() && data_model.loading_period {
-        common::view_page_loading()
+    if !data_model.show_menstrual_cycle() {
+        view_hidden()
+    } else if let Some(node) = common::view_resource_unavailable(&data_model.period, |_| Msg::Retry)
+    {
+        node
     } else {
         let dates = data_model.period.values().map(|p| p.date);
         let period_interval = domain::Interval {
@@ -221,30 +271,44 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
         div![
             view_period_dialog(&model.dialog, model.loading),
             view_current_cycle(data_model),
+            view_month_calendar(model, data_model),
             common::view_interval_buttons(&model.interval, &period_interval, Msg::ChangeInterval),
             view_calendar(data_model, &model.interval),
             view_chart(model, data_model),
             view_cycle_stats(model, data_model),
             view_period_table(model, data_model),
+            view_trash(data_model),
             common::view_fab("plus", |_| Msg::ShowAddPeriodDialog),
         ]
     }
 }
 
+fn view_hidden() -> Node<Msg> {
+    div![
+        C!["message"],
+        C!["is-info"],
+        C!["mx-2"],
+        div![
+            C!["message-body"],
+            "Menstrual cycle tracking is hidden for this user. It can be shown in the settings."
+        ]
+    ]
+}
+
 fn view_period_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
     let title;
     let form;
-    let date_disabled;
+    let editing;
     match dialog {
         Dialog::AddPeriod(ref f) => {
             title = "Add period";
             form = f;
-            date_disabled = false;
+            editing = false;
         }
         Dialog::EditPeriod(ref f) => {
             title = "Edit period";
             form = f;
-            date_disabled = true;
+            editing = true;
         }
         Dialog::DeletePeriod(date) => {
             #[allow(clippy::clone_on_copy)]
@@ -256,11 +320,20 @@ fn view_period_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
                 loading,
             );
         }
+        Dialog::RestoreFromTrash(entry) => {
+            return common::view_restore_confirmation_dialog(
+                "period entry",
+                entry.date(),
+                &ev(Ev::Click, |_| Msg::ConfirmRestoreFromTrash),
+                &ev(Ev::Click, |_| Msg::ClosePeriodDialog),
+                loading,
+            );
+        }
         Dialog::Hidden => {
             return empty![];
         }
     }
-    let today = Local::now().date_naive();
+    let today = web_app::clock::today();
     let date_valid = form.date.1.map_or(false, |d| d <= today);
     let save_disabled = loading || !date_valid || form.intensity.1.is_none();
     common::view_dialog(
@@ -279,11 +352,15 @@ fn view_period_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
                         attrs! {
                             At::Type => "date",
                             At::Value => form.date.0,
-                            At::Disabled => date_disabled.as_at_value(),
+                            At::Disabled => editing.as_at_value(),
                             At::Max => today,
                         }
                     ],
-                ]
+                ],
+                IF![editing => p![
+                    C!["help"],
+                    "To change the date, delete the entry and add a new one."
+                ]],
             ],
             div![
                 C!["field"],
@@ -319,6 +396,19 @@ fn view_period_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
                         "Cancel",
                     ]
                 ],
+                form.date.1.filter(|_| editing).map(|date| div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-danger"],
+                        C!["is-outlined"],
+                        attrs![
+                            At::Disabled => loading.as_at_value(),
+                        ],
+                        ev(Ev::Click, move |_| Msg::ShowDeletePeriodDialog(date)),
+                        "Delete",
+                    ]
+                ]),
                 div![
                     C!["control"],
                     button![
@@ -338,6 +428,13 @@ fn view_period_dialog(dialog: &Dialog, loading: bool) -> Node<Msg> {
     )
 }
 
+fn data_table(data_model: &data::Model) -> common::DataTable<Msg> {
+    common::DataTable {
+        shown: data_model.show_data_tables(web_app::chart::Page::MenstrualCycle),
+        toggle: || Msg::ToggleDataTables,
+    }
+}
+
 fn view_chart(model: &Model, data_model: &data::Model) -> Node<Msg> {
     let period = data_model
         .period
@@ -352,43 +449,100 @@ fn view_chart(model: &Model, data_model: &data::Model) -> Node<Msg> {
             web_app::chart::OPACITY_LINE,
         )]
         .as_slice(),
-        web_app::chart::plot(
-            &[web_app::chart::PlotData {
-                values_high: period
-                    .iter()
-                    .map(|p| (p.date, f32::from(p.intensity)))
-                    .collect::<Vec<_>>(),
-                values_low: None,
-                plots: vec![web_app::chart::PlotType::Histogram(
-                    web_app::chart::COLOR_PERIOD_INTENSITY,
-                    web_app::chart::OPACITY_LINE,
-                )],
-                params: web_app::chart::PlotParams::primary_range(0., 4.),
-            }],
-            &model.interval,
-            data_model.theme(),
-        ),
+        &[web_app::chart::PlotData {
+            values_high: period
+                .iter()
+                .map(|p| (p.date, f32::from(p.intensity)))
+                .collect::<Vec<_>>(),
+            values_low: None,
+            plots: vec![web_app::chart::PlotType::Histogram(
+                web_app::chart::COLOR_PERIOD_INTENSITY,
+                web_app::chart::OPACITY_LINE,
+            )],
+            params: web_app::chart::PlotParams::primary_range(0., 4.),
+        }],
+        &model.interval,
+        &data_model.chart_style(),
+        data_table(data_model),
         true,
     )
 }
 
 fn view_current_cycle(data_model: &data::Model) -> Node<Msg> {
-    let today = Local::now().date_naive();
+    let today = web_app::clock::today();
     if let Some(current_cycle) = &data_model.current_cycle {
         common::view_box(
             "Current cycle",
-            &format!(
-                "<strong>{}</strong> days, <strong>{} (&#177;{})</strong> days left",
-                (today - current_cycle.begin).num_days() + 1,
-                current_cycle.time_left.num_days(),
-                current_cycle.time_left_variation.num_days(),
-            ),
+            &if let Some(time_left) = current_cycle.time_left {
+                format!(
+                    "<strong>{}</strong> days, <strong>{} (&#177;{})</strong> days left",
+                    (today - current_cycle.begin).num_days() + 1,
+                    time_left.num_days(),
+                    current_cycle.time_left_variation.num_days(),
+                )
+            } else {
+                format!(
+                    "<strong>{}</strong> days",
+                    (today - current_cycle.begin).num_days() + 1
+                )
+            },
         )
     } else {
         empty![]
     }
 }
 
+fn view_month_calendar(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    let today = web_app::clock::today();
+    let predicted_period = domain::period::predicted_period(&data_model.cycles, today);
+    let (r, g, b) = Palette99::pick(web_app::chart::COLOR_PERIOD_INTENSITY).rgb();
+    div![
+        common::view_month_calendar(
+            model.month,
+            Some(ev(Ev::Click, |_| Msg::ShowPreviousMonth)),
+            Some(ev(Ev::Click, |_| Msg::ShowNextMonth)),
+            |date| {
+                let predicted = predicted_period.is_some_and(|p| p.contains(*date));
+                td![
+                    C![
+                        IF![date.month() != model.month.month() => "is-semitransparent"],
+                        IF![predicted => "is-predicted-period"],
+                        IF![*date == today => "is-today"],
+                        IF![data_model.period.contains_key(date) => "is-clickable"],
+                    ],
+                    if let Some(period) = data_model.period.get(date) {
+                        let opacity = f64::from(period.intensity) * 0.25;
+                        style! {
+                            St::BackgroundColor => format!("rgba({r}, {g}, {b}, {opacity})")
+                        }
+                    } else {
+                        style! {}
+                    },
+                    data_model.period.get(date).map(|period| {
+                        let date = period.date;
+                        ev(Ev::Click, move |_| Msg::ShowEditPeriodDialog(date))
+                    }),
+                    date.day()
+                ]
+            }
+        ),
+        if let Some(predicted_period) = predicted_period {
+            p![
+                C!["is-size-7"],
+                C!["mx-2"],
+                C!["mb-4"],
+                span![C!["is-predicted-period"], C!["px-1"], C!["mr-1"]],
+                format!(
+                    "Next period expected between {} and {}",
+                    predicted_period.first, predicted_period.last
+                )
+            ]
+        } else {
+            empty![]
+        }
+    ]
+}
+
 fn view_cycle_stats(model: &Model, data_model: &data::Model) -> Node<Msg> {
     let cycles = &data_model
         .cycles
@@ -396,18 +550,53 @@ fn view_cycle_stats(model: &Model, data_model: &data::Model) -> Node<Msg> {
         .filter(|c| c.begin >= model.interval.first && c.begin <= model.interval.last)
         .collect::<Vec<_>>();
     let stats = domain::cycle_stats(cycles);
-    common::view_box(
-        "Avg. cycle length",
-        &if not(cycles.is_empty()) {
-            format!(
-                "<strong>{} (&#177;{})</strong> days",
-                stats.length_median.num_days(),
-                stats.length_variation.num_days(),
-            )
-        } else {
-            String::from("–")
-        },
-    )
+    let intensity_stats = domain::period::intensity_stats(
+        &domain::period::recorded_cycles(&data_model.cycles, &model.interval),
+        &data_model.period,
+    );
+    div![
+        C!["columns"],
+        C!["is-gapless"],
+        C!["is-mobile"],
+        [
+            (
+                "Avg. cycle length",
+                if not(cycles.is_empty()) {
+                    format!(
+                        "<strong>{} (&#177;{})</strong> days",
+                        stats.length_median.num_days(),
+                        stats.length_variation.num_days(),
+                    )
+                } else {
+                    String::from("–")
+                }
+            ),
+            (
+                "Avg. bleeding days",
+                intensity_stats.map_or(String::from("–"), |s| format!(
+                    "<strong>{}</strong> days",
+                    web_app::format::format_number(
+                        s.bleeding_days,
+                        1,
+                        data_model.settings.decimal_separator
+                    )
+                ))
+            ),
+            (
+                "Avg. intensity",
+                intensity_stats.map_or(String::from("–"), |s| format!(
+                    "<strong>{}</strong> per cycle",
+                    web_app::format::format_number(
+                        s.intensity,
+                        1,
+                        data_model.settings.decimal_separator
+                    )
+                ))
+            ),
+        ]
+        .iter()
+        .map(|(title, content)| { div![C!["column"], common::view_box(title, content)] }),
+    ]
 }
 
 fn view_calendar(data_model: &data::Model, interval: &domain::Interval) -> Node<Msg> {
@@ -472,3 +661,14 @@ fn view_period_table(model: &Model, data_model: &data::Model) -> Node<Msg> {
         ]
     ]
 }
+
+fn view_trash(data_model: &data::Model) -> Node<Msg> {
+    common::view_trash(
+        data_model
+            .trash
+            .period()
+            .map(|(p, item)| (item, format!("Intensity {}", p.intensity)))
+            .collect(),
+        Msg::RestoreFromTrash,
+    )
+}
@@ -1,4 +1,4 @@
-use chrono::prelude::*;
+use chrono::{prelude::*, Duration};
 use seed::{prelude::*, *};
 use valens_domain as domain;
 use valens_web_app as web_app;
@@ -20,7 +20,10 @@ pub fn init(data_model: &data::Model, navbar: &mut crate::Navbar) -> Model {
                 .map(|t| t.date)
                 .collect::<Vec<NaiveDate>>(),
             domain::DefaultInterval::_1M,
+            data_model.settings.initial_interval(),
+            &web_app::clock::BrowserClock,
         ),
+        heatmap_days: HEATMAP_DAYS[0],
     }
 }
 
@@ -28,8 +31,15 @@ pub fn init(data_model: &data::Model, navbar: &mut crate::Navbar) -> Model {
 //     Model
 // ------ ------
 
+/// Selectable numbers of recent days which are included in the heatmap.
+const HEATMAP_DAYS: [i64; 2] = [7, 28];
+
+/// Number of hard sets per week at which the heatmap color is saturated.
+const HEATMAP_WEEKLY_CAP: f32 = 20.;
+
 pub struct Model {
     interval: domain::Interval,
+    heatmap_days: i64,
 }
 
 // ------ ------
@@ -37,14 +47,55 @@ pub struct Model {
 // ------ ------
 
 pub enum Msg {
-    ChangeInterval(NaiveDate, NaiveDate),
+    ChangeInterval(NaiveDate, NaiveDate, Option<domain::DefaultInterval>),
+    ToggleDataTables,
+    SetHeatmapDays(i64),
+    GoToExercises(u8),
+    Retry,
 }
 
-pub fn update(msg: &Msg, model: &mut Model) {
+pub fn update(
+    msg: Msg,
+    model: &mut Model,
+    data_model: &data::Model,
+    orders: &mut impl Orders<Msg>,
+) {
     match msg {
-        Msg::ChangeInterval(first, last) => {
-            model.interval.first = *first;
-            model.interval.last = *last;
+        Msg::ChangeInterval(first, last, selected_interval) => {
+            model.interval.first = first;
+            model.interval.last = last;
+            if let Some(selected_interval) = selected_interval {
+                orders.notify(data::Msg::SetInterval(selected_interval));
+            }
+            orders.notify(data::Msg::ReadOlderTrainingSessions(
+                if selected_interval == Some(domain::DefaultInterval::All) {
+                    None
+                } else {
+                    Some(first)
+                },
+            ));
+        }
+        Msg::ToggleDataTables => {
+            orders.notify(data::Msg::ToggleDataTables(web_app::chart::Page::Muscles));
+        }
+        Msg::SetHeatmapDays(days) => {
+            model.heatmap_days = days;
+        }
+        Msg::GoToExercises(muscle_id) => {
+            let url = crate::Urls::new(&data_model.base_url)
+                .exercises()
+                .add_hash_path_part(crate::page::exercises::MUSCLE)
+                .add_hash_path_part(muscle_id.to_string());
+            url.go_and_push();
+            orders.notify(subs::UrlChanged(url));
+        }
+        Msg::Retry => {
+            if data_model.exercises.error().is_some() {
+                orders.notify(data::Msg::ReadExercises);
+            }
+            if data_model.training_sessions.error().is_some() {
+                orders.notify(data::Msg::ReadTrainingSessions);
+            }
         }
     }
 }
@@ -54,19 +105,27 @@ pub fn update(msg: &Msg, model: &mut Model) {
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if (data_model.exercises.is_empty() && data_model.loading_exercises)
-        || (data_model.training_sessions.is_empty() && data_model.loading_training_sessions)
+    if let Some(node) = common::view_resource_unavailable(&data_model.exercises, |_| Msg::Retry)
+        .or_else(|| {
+            common::view_resource_unavailable(&data_model.training_sessions, |_| Msg::Retry)
+        })
     {
-        common::view_page_loading()
+        node
     } else {
         let training_sessions_interval: domain::Interval =
             data_model.training_sessions_date_range().into();
+        let weekly_stimulus_per_muscle = domain::weekly_stimulus_per_muscle(
+            &data_model.training_sessions.values().collect::<Vec<_>>(),
+            &data_model.exercises,
+            &model.interval,
+        );
         div![
             common::view_interval_buttons(
                 &model.interval,
                 &training_sessions_interval,
                 Msg::ChangeInterval
             ),
+            view_heatmap(model.heatmap_days, data_model),
             domain::Muscle::iter().map(|m| {
                 #[allow(clippy::cast_precision_loss)]
                 let total_7day_set_volume = domain::centered_moving_total(
@@ -82,6 +141,15 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
                     &model.interval,
                     3,
                 );
+                #[allow(clippy::cast_precision_loss)]
+                let weekly_set_volume = weekly_stimulus_per_muscle
+                    .iter()
+                    .filter(|((_, muscle_id), _)| *muscle_id == m.id())
+                    .filter_map(|((week, _), stimulus)| {
+                        NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon)
+                            .map(|date| (date.max(model.interval.first), *stimulus as f32 / 100.))
+                    })
+                    .collect::<Vec<_>>();
 
                 div![
                     common::view_title(&span![m.name()], 1),
@@ -97,23 +165,105 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
                             web_app::chart::COLOR_SET_VOLUME,
                             web_app::chart::OPACITY_LINE
                         )],
-                        web_app::chart::plot(
-                            &[web_app::chart::PlotData {
-                                values_high: total_7day_set_volume,
-                                values_low: None,
-                                plots: web_app::chart::plot_area_with_border(
-                                    web_app::chart::COLOR_SET_VOLUME,
-                                    web_app::chart::COLOR_SET_VOLUME,
-                                ),
-                                params: web_app::chart::PlotParams::primary_range(0., 10.),
-                            }],
-                            &model.interval,
-                            data_model.theme()
-                        ),
+                        &[web_app::chart::PlotData {
+                            values_high: total_7day_set_volume,
+                            values_low: None,
+                            plots: web_app::chart::plot_area_with_border(
+                                web_app::chart::COLOR_SET_VOLUME,
+                                web_app::chart::COLOR_SET_VOLUME,
+                            ),
+                            params: web_app::chart::PlotParams::primary_range(0., 10.),
+                        }],
+                        &model.interval,
+                        &data_model.chart_style(),
+                        data_table(data_model),
                         true,
+                    ),
+                    common::view_chart(
+                        &[(
+                            "Set volume (weekly total)",
+                            web_app::chart::COLOR_SET_VOLUME,
+                            web_app::chart::OPACITY_LINE
+                        )],
+                        &[web_app::chart::PlotData {
+                            values_high: weekly_set_volume,
+                            values_low: None,
+                            plots: web_app::chart::plot_line(web_app::chart::COLOR_SET_VOLUME),
+                            params: web_app::chart::PlotParams::primary_range(0., 10.),
+                        }],
+                        &model.interval,
+                        &data_model.chart_style(),
+                        data_table(data_model),
+                        false,
                     )
                 ]
             })
         ]
     }
 }
+
+fn data_table(data_model: &data::Model) -> common::DataTable<Msg> {
+    common::DataTable {
+        shown: data_model.show_data_tables(web_app::chart::Page::Muscles),
+        toggle: || Msg::ToggleDataTables,
+    }
+}
+
+fn view_heatmap(days: i64, data_model: &data::Model) -> Node<Msg> {
+    let today = web_app::clock::today();
+    #[allow(clippy::cast_precision_loss)]
+    let hard_sets = domain::total_stimulus_per_muscle(
+        &data_model.training_sessions.values().collect::<Vec<_>>(),
+        &data_model.exercises,
+        &domain::Interval {
+            first: today - Duration::days(days - 1),
+            last: today,
+        },
+    )
+    .into_iter()
+    .map(|(muscle_id, stimulus)| (muscle_id, stimulus as f32 / 100.))
+    .collect();
+    #[allow(clippy::cast_precision_loss)]
+    let cap = HEATMAP_WEEKLY_CAP * days as f32 / 7.;
+
+    div![
+        C!["block"],
+        div![
+            C!["field"],
+            C!["has-addons"],
+            C!["is-justify-content-center"],
+            HEATMAP_DAYS.iter().map(|d| {
+                let d = *d;
+                p![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-small"],
+                        C![IF![d == days => "is-link"]],
+                        ev(Ev::Click, move |_| Msg::SetHeatmapDays(d)),
+                        format!("{d} days"),
+                    ]
+                ]
+            })
+        ],
+        div![
+            C!["container"],
+            C!["has-text-centered"],
+            style![St::MaxWidth => "30rem"],
+            ev(Ev::Click, |event| {
+                event
+                    .target()
+                    .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                    .and_then(|element| element.get_attribute("data-muscle"))
+                    .and_then(|muscle_id| muscle_id.parse::<u8>().ok())
+                    .map(Msg::GoToExercises)
+            }),
+            raw![&web_app::muscle_map::svg(&hard_sets, cap)],
+        ],
+        div![
+            C!["is-size-7"],
+            C!["has-text-centered"],
+            format!("Hard sets in the last {days} days, tap a muscle to show its exercises"),
+        ],
+    ]
+}
@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use chrono::prelude::*;
+use gloo_console::error;
 use seed::{prelude::*, *};
 use valens_domain as domain;
 use valens_web_app as web_app;
@@ -29,14 +30,24 @@ pub fn init(
     navbar.title = String::from("Routine");
 
     let mut model = Model {
-        interval: domain::init_interval(&[], domain::DefaultInterval::All),
+        interval: domain::init_interval(
+            &[],
+            domain::DefaultInterval::All,
+            data_model.settings.initial_interval(),
+            &web_app::clock::BrowserClock,
+        ),
         routine_id,
         name: common::InputField::default(),
+        schedule: vec![],
+        orig_schedule: vec![],
         sections: vec![],
+        parts_moved: false,
         previous_exercises: BTreeSet::new(),
         dialog: Dialog::Hidden,
         editing,
+        preview: false,
         loading: false,
+        copied: false,
     };
 
     update_model(&mut model, data_model);
@@ -52,21 +63,34 @@ pub struct Model {
     interval: domain::Interval,
     routine_id: u32,
     name: common::InputField<String>,
+    schedule: Vec<Weekday>,
+    orig_schedule: Vec<Weekday>,
     sections: Vec<Form>,
+    /// Parts were moved since the routine was loaded or saved.
+    parts_moved: bool,
     previous_exercises: BTreeSet<u32>,
     dialog: Dialog,
     editing: bool,
+    /// Show the routine expanded into the sequence of a training session.
+    preview: bool,
     loading: bool,
+    /// The export of the routine was copied to the clipboard.
+    copied: bool,
 }
 
 impl Model {
     pub fn has_unsaved_changes(&self) -> bool {
-        self.name.changed() || self.sections.iter().any(Form::changed)
+        self.name.changed()
+            || self.schedule != self.orig_schedule
+            || self.parts_moved
+            || self.sections.iter().any(Form::changed)
     }
 
     pub fn mark_as_unchanged(&mut self) {
         self.name.input = self.name.parsed.clone().unwrap();
         self.name.orig = self.name.parsed.clone().unwrap();
+        self.orig_schedule = self.schedule.clone();
+        self.parts_moved = false;
         for s in &mut self.sections {
             s.mark_as_unchanged();
         }
@@ -92,9 +116,11 @@ enum Form {
     Activity {
         exercise_id: Option<u32>,
         reps: common::InputField<u32>,
+        reps_max: common::InputField<u32>,
         time: common::InputField<u32>,
         weight: common::InputField<f32>,
-        rpe: common::InputField<f32>,
+        rpe: common::InputField<domain::RpeRange>,
+        target_percent_1rm: common::InputField<f32>,
         automatic: bool,
     },
 }
@@ -105,11 +131,20 @@ impl Form {
             Form::Section { rounds, parts } => rounds.changed() || parts.iter().any(Form::changed),
             Form::Activity {
                 reps,
+                reps_max,
                 time,
                 weight,
                 rpe,
+                target_percent_1rm,
                 ..
-            } => reps.changed() || time.changed() || weight.changed() || rpe.changed(),
+            } => {
+                reps.changed()
+                    || reps_max.changed()
+                    || time.changed()
+                    || weight.changed()
+                    || rpe.changed()
+                    || target_percent_1rm.changed()
+            }
         }
     }
 
@@ -123,15 +158,19 @@ impl Form {
             }
             Form::Activity {
                 reps,
+                reps_max,
                 time,
                 weight,
                 rpe,
+                target_percent_1rm,
                 ..
             } => {
                 reps.orig = reps.input.clone();
+                reps_max.orig = reps_max.input.clone();
                 time.orig = time.input.clone();
                 weight.orig = weight.input.clone();
                 rpe.orig = rpe.input.clone();
+                target_percent_1rm.orig = target_percent_1rm.input.clone();
             }
         }
     }
@@ -141,11 +180,29 @@ impl Form {
             Form::Section { rounds, parts } => rounds.valid() && parts.iter().all(Form::valid),
             Form::Activity {
                 reps,
+                reps_max,
                 time,
                 weight,
                 rpe,
+                target_percent_1rm,
                 ..
-            } => reps.valid() && time.valid() && weight.valid() && rpe.valid(),
+            } => {
+                reps.valid()
+                    && reps_max.valid()
+                    && time.valid()
+                    && weight.valid()
+                    && rpe.valid()
+                    && target_percent_1rm.valid()
+            }
+        }
+    }
+}
+
+impl domain::PartTree for Form {
+    fn parts_mut(&mut self) -> Option<&mut [Self]> {
+        match self {
+            Form::Section { parts, .. } => Some(parts),
+            Form::Activity { .. } => None,
         }
     }
 }
@@ -164,6 +221,7 @@ impl From<&domain::RoutinePart> for Form {
                         input: rounds_str.clone(),
                         parsed: Some(*rounds),
                         orig: rounds_str,
+                        error: None,
                     },
                     parts: parts.iter().map(Into::into).collect(),
                 }
@@ -174,20 +232,34 @@ impl From<&domain::RoutinePart> for Form {
                 time,
                 weight,
                 rpe,
+                target_percent_1rm,
                 automatic,
-                ..
             } => Form::Activity {
                 exercise_id: *exercise_id,
                 reps: {
-                    let reps_str = if *reps == 0 {
-                        String::new()
+                    let reps_str = if reps.is_set() {
+                        reps.min.to_string()
                     } else {
-                        reps.to_string()
+                        String::new()
                     };
                     common::InputField {
                         input: reps_str.clone(),
-                        parsed: Some(*reps),
+                        parsed: Some(reps.min),
                         orig: reps_str,
+                        error: None,
+                    }
+                },
+                reps_max: {
+                    let reps_max_str = if reps.is_single() {
+                        String::new()
+                    } else {
+                        reps.max.to_string()
+                    };
+                    common::InputField {
+                        input: reps_max_str.clone(),
+                        parsed: Some(if reps.is_single() { 0 } else { reps.max }),
+                        orig: reps_max_str,
+                        error: None,
                     }
                 },
                 time: {
@@ -200,6 +272,7 @@ impl From<&domain::RoutinePart> for Form {
                         input: time_str.clone(),
                         parsed: Some(*time),
                         orig: time_str,
+                        error: None,
                     }
                 },
                 weight: {
@@ -212,18 +285,31 @@ impl From<&domain::RoutinePart> for Form {
                         input: weight_str.clone(),
                         parsed: Some(*weight),
                         orig: weight_str,
+                        error: None,
                     }
                 },
                 rpe: {
-                    let rpe_str = if *rpe == 0.0 {
-                        String::new()
-                    } else {
+                    let rpe_str = if rpe.is_set() {
                         rpe.to_string()
+                    } else {
+                        String::new()
                     };
                     common::InputField {
                         input: rpe_str.clone(),
                         parsed: Some(*rpe),
                         orig: rpe_str,
+                        error: None,
+                    }
+                },
+                target_percent_1rm: {
+                    let target_percent_1rm_str = target_percent_1rm
+                        .map(|v| v.to_string())
+                        .unwrap_or_default();
+                    common::InputField {
+                        input: target_percent_1rm_str.clone(),
+                        parsed: Some(target_percent_1rm.unwrap_or(0.0)),
+                        orig: target_percent_1rm_str,
+                        error: None,
                     }
                 },
                 automatic: *automatic,
@@ -243,17 +329,26 @@ fn to_routine_parts(parts: &[Form]) -> Vec<domain::RoutinePart> {
             Form::Activity {
                 exercise_id,
                 reps,
+                reps_max,
                 time,
                 weight,
                 rpe,
+                target_percent_1rm,
                 automatic,
             } => domain::RoutinePart::RoutineActivity {
                 exercise_id: *exercise_id,
-                reps: reps.parsed.unwrap_or(0),
+                reps: {
+                    let min = reps.parsed.unwrap_or(0);
+                    match reps_max.parsed {
+                        Some(max) if max > 0 => domain::RepsRange::new(min, max),
+                        _ => domain::RepsRange::single(min),
+                    }
+                },
                 time: time.parsed.unwrap_or(0),
                 weight: weight.parsed.unwrap_or(0.0),
-                rpe: rpe.parsed.unwrap_or(0.0),
+                rpe: rpe.parsed.unwrap_or_default(),
                 automatic: *automatic,
+                target_percent_1rm: target_percent_1rm.parsed.filter(|v| *v > 0.0),
             },
         })
         .collect()
@@ -266,12 +361,14 @@ fn to_routine_parts(parts: &[Form]) -> Vec<domain::RoutinePart> {
 pub enum Msg {
     EditRoutine,
     SaveRoutine,
+    SetPreview(bool),
 
     ShowSelectExerciseDialog(Vec<usize>),
     ShowDeleteTrainingSessionDialog(u32),
     CloseDialog,
 
     NameChanged(String),
+    ToggleScheduledWeekday(Weekday),
     AddSection(Vec<usize>),
     AddActivity(Vec<usize>, Option<u32>),
     RemovePart(Vec<usize>),
@@ -280,17 +377,24 @@ pub enum Msg {
     RoundsChanged(Vec<usize>, String),
     ExerciseChanged(Vec<usize>, u32),
     RepsChanged(Vec<usize>, String),
+    RepsMaxChanged(Vec<usize>, String),
     TimeChanged(Vec<usize>, String),
     WeightChanged(Vec<usize>, String),
     RPEChanged(Vec<usize>, String),
+    TargetPercent1RMChanged(Vec<usize>, String),
     AutomaticChanged(Vec<usize>),
 
     ExerciseList(component::exercise_list::Msg),
 
     DeleteTrainingSession(u32),
+    Retry,
     DataEvent(data::Event),
 
-    ChangeInterval(NaiveDate, NaiveDate),
+    ChangeInterval(NaiveDate, NaiveDate, Option<domain::DefaultInterval>),
+    ToggleDataTables,
+
+    CopyExport,
+    ExportCopied,
 }
 
 pub fn update(
@@ -309,6 +413,9 @@ pub fn update(
                     .add_hash_path_part("edit"),
             );
         }
+        Msg::SetPreview(preview) => {
+            model.preview = preview;
+        }
         Msg::SaveRoutine => {
             model.loading = true;
             orders.notify(data::Msg::ModifyRoutine(
@@ -316,6 +423,8 @@ pub fn update(
                 model.name.parsed.clone(),
                 None,
                 Some(to_routine_parts(&model.sections)),
+                Some(model.schedule.clone()),
+                None,
             ));
         }
 
@@ -348,21 +457,32 @@ pub fn update(
                     input: name.clone(),
                     parsed: Some(trimmed_name.to_string()),
                     orig: model.name.orig.clone(),
+                    error: None,
                 };
             } else {
                 model.name = common::InputField {
                     input: name,
                     parsed: None,
                     orig: model.name.orig.clone(),
+                    error: None,
                 }
             }
         }
+        Msg::ToggleScheduledWeekday(weekday) => {
+            if model.schedule.contains(&weekday) {
+                model.schedule.retain(|d| *d != weekday);
+            } else {
+                model.schedule.push(weekday);
+                model.schedule.sort_by_key(Weekday::num_days_from_monday);
+            }
+        }
         Msg::AddSection(id) => {
             let new_section = Form::Section {
                 rounds: common::InputField {
                     input: String::new(),
                     parsed: Some(1),
                     orig: String::new(),
+                    error: None,
                 },
                 parts: vec![],
             };
@@ -379,29 +499,46 @@ pub fn update(
                     input: String::new(),
                     parsed: Some(0),
                     orig: String::new(),
+                    error: None,
+                },
+                reps_max: common::InputField {
+                    input: String::new(),
+                    parsed: Some(0),
+                    orig: String::new(),
+                    error: None,
                 },
                 time: if exercise_id.is_none() {
                     common::InputField {
                         input: String::from("60"),
                         parsed: Some(60),
                         orig: String::from("60"),
+                        error: None,
                     }
                 } else {
                     common::InputField {
                         input: String::new(),
                         parsed: Some(0),
                         orig: String::new(),
+                        error: None,
                     }
                 },
                 weight: common::InputField {
                     input: String::new(),
                     parsed: Some(0.0),
                     orig: String::new(),
+                    error: None,
                 },
                 rpe: common::InputField {
+                    input: String::new(),
+                    parsed: Some(domain::RpeRange::default()),
+                    orig: String::new(),
+                    error: None,
+                },
+                target_percent_1rm: common::InputField {
                     input: String::new(),
                     parsed: Some(0.0),
                     orig: String::new(),
+                    error: None,
                 },
                 automatic: exercise_id.is_none(),
             };
@@ -419,38 +556,10 @@ pub fn update(
             }
         }
         Msg::MovePartDown(id) => {
-            if id.len() == 1 {
-                if id[0] == model.sections.len() - 1 {
-                    model.sections.rotate_right(1);
-                } else {
-                    model.sections.swap(id[0], id[0] + 1);
-                }
-            } else if let Some(Form::Section { parts, .. }) =
-                get_part(&mut model.sections, &id[1..])
-            {
-                if id[0] == parts.len() - 1 {
-                    parts.rotate_right(1);
-                } else {
-                    parts.swap(id[0], id[0] + 1);
-                }
-            }
+            move_part(model, &id, domain::Direction::Down);
         }
         Msg::MovePartUp(id) => {
-            if id.len() == 1 {
-                if id[0] == 0 {
-                    model.sections.rotate_left(1);
-                } else {
-                    model.sections.swap(id[0], id[0] - 1);
-                }
-            } else if let Some(Form::Section { parts, .. }) =
-                get_part(&mut model.sections, &id[1..])
-            {
-                if id[0] == 0 {
-                    parts.rotate_left(1);
-                } else {
-                    parts.swap(id[0], id[0] - 1);
-                }
-            }
+            move_part(model, &id, domain::Direction::Up);
         }
         Msg::RoundsChanged(id, input) => {
             if let Some(Form::Section { rounds, .. }) = get_part(&mut model.sections, &id) {
@@ -459,6 +568,7 @@ pub fn update(
                         input,
                         parsed: Some(1),
                         orig: rounds.orig.clone(),
+                        error: None,
                     };
                 } else {
                     match input.parse::<u32>() {
@@ -471,6 +581,7 @@ pub fn update(
                                     None
                                 },
                                 orig: rounds.orig.clone(),
+                                error: None,
                             }
                         }
                         Err(_) => {
@@ -478,6 +589,7 @@ pub fn update(
                                 input,
                                 parsed: None,
                                 orig: rounds.orig.clone(),
+                                error: None,
                             }
                         }
                     }
@@ -491,12 +603,14 @@ pub fn update(
             orders.send_msg(Msg::CloseDialog);
         }
         Msg::RepsChanged(id, input) => {
-            if let Some(Form::Activity { reps, .. }) = get_part(&mut model.sections, &id) {
+            if let Some(Form::Activity { reps, reps_max, .. }) = get_part(&mut model.sections, &id)
+            {
                 if input.is_empty() {
                     *reps = common::InputField {
                         input,
                         parsed: Some(0),
                         orig: reps.orig.clone(),
+                        error: None,
                     };
                 } else {
                     match input.parse::<u32>() {
@@ -506,6 +620,7 @@ pub fn update(
                                 input,
                                 parsed: if valid { Some(parsed_reps) } else { None },
                                 orig: reps.orig.clone(),
+                                error: None,
                             }
                         }
                         Err(_) => {
@@ -513,10 +628,23 @@ pub fn update(
                                 input,
                                 parsed: None,
                                 orig: reps.orig.clone(),
+                                error: None,
                             }
                         }
                     }
                 }
+                reps_max.parsed = parse_reps_max(&reps_max.input, reps.parsed);
+            }
+        }
+        Msg::RepsMaxChanged(id, input) => {
+            if let Some(Form::Activity { reps, reps_max, .. }) = get_part(&mut model.sections, &id)
+            {
+                *reps_max = common::InputField {
+                    parsed: parse_reps_max(&input, reps.parsed),
+                    input,
+                    orig: reps_max.orig.clone(),
+                    error: None,
+                };
             }
         }
         Msg::TimeChanged(id, input) => {
@@ -526,6 +654,7 @@ pub fn update(
                         input,
                         parsed: Some(0),
                         orig: time.orig.clone(),
+                        error: None,
                     };
                 } else {
                     match input.parse::<u32>() {
@@ -535,6 +664,7 @@ pub fn update(
                                 input,
                                 parsed: if valid { Some(parsed_time) } else { None },
                                 orig: time.orig.clone(),
+                                error: None,
                             }
                         }
                         Err(_) => {
@@ -542,6 +672,7 @@ pub fn update(
                                 input,
                                 parsed: None,
                                 orig: time.orig.clone(),
+                                error: None,
                             }
                         }
                     }
@@ -555,6 +686,7 @@ pub fn update(
                         input,
                         parsed: Some(0.0),
                         orig: weight.orig.clone(),
+                        error: None,
                     };
                 } else {
                     match input.parse::<f32>() {
@@ -564,6 +696,7 @@ pub fn update(
                                 input,
                                 parsed: if valid { Some(parsed_weight) } else { None },
                                 orig: weight.orig.clone(),
+                                error: None,
                             }
                         }
                         Err(_) => {
@@ -571,6 +704,7 @@ pub fn update(
                                 input,
                                 parsed: None,
                                 orig: weight.orig.clone(),
+                                error: None,
                             }
                         }
                     }
@@ -582,24 +716,49 @@ pub fn update(
                 if input.is_empty() {
                     *rpe = common::InputField {
                         input,
-                        parsed: Some(0.0),
+                        parsed: Some(domain::RpeRange::default()),
                         orig: rpe.orig.clone(),
+                        error: None,
+                    };
+                } else {
+                    *rpe = common::InputField {
+                        parsed: input.parse::<domain::RpeRange>().ok(),
+                        input,
+                        orig: rpe.orig.clone(),
+                        error: None,
+                    }
+                }
+            }
+        }
+        Msg::TargetPercent1RMChanged(id, input) => {
+            if let Some(Form::Activity {
+                target_percent_1rm, ..
+            }) = get_part(&mut model.sections, &id)
+            {
+                if input.is_empty() {
+                    *target_percent_1rm = common::InputField {
+                        input,
+                        parsed: Some(0.0),
+                        orig: target_percent_1rm.orig.clone(),
+                        error: None,
                     };
                 } else {
                     match input.parse::<f32>() {
-                        Ok(parsed_rpe) => {
-                            let valid = common::valid_rpe(parsed_rpe);
-                            *rpe = common::InputField {
+                        Ok(parsed_percent) => {
+                            let valid = parsed_percent > 0.0 && parsed_percent <= 100.0;
+                            *target_percent_1rm = common::InputField {
                                 input,
-                                parsed: if valid { Some(parsed_rpe) } else { None },
-                                orig: rpe.orig.clone(),
+                                parsed: if valid { Some(parsed_percent) } else { None },
+                                orig: target_percent_1rm.orig.clone(),
+                                error: None,
                             }
                         }
                         Err(_) => {
-                            *rpe = common::InputField {
+                            *target_percent_1rm = common::InputField {
                                 input,
                                 parsed: None,
-                                orig: rpe.orig.clone(),
+                                orig: target_percent_1rm.orig.clone(),
+                                error: None,
                             }
                         }
                     }
@@ -636,11 +795,19 @@ pub fn update(
             model.loading = true;
             orders.notify(data::Msg::DeleteTrainingSession(id));
         }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadRoutines);
+        }
         Msg::DataEvent(event) => {
             model.loading = false;
             match event {
-                data::Event::DataChanged => {
-                    update_model(model, data_model);
+                data::Event::RoutinesChanged(ids) if ids.contains(&model.routine_id) => {
+                    update_routine(model, data_model);
+                    update_previous_exercises(model, data_model);
+                }
+                data::Event::TrainingSessionsChanged(_) => {
+                    update_interval(model, data_model);
+                    update_previous_exercises(model, data_model);
                 }
                 data::Event::RoutineCreatedOk
                 | data::Event::RoutineModifiedOk
@@ -660,14 +827,59 @@ pub fn update(
             };
         }
 
-        Msg::ChangeInterval(first, last) => {
+        Msg::ChangeInterval(first, last, selected_interval) => {
             model.interval.first = first;
             model.interval.last = last;
+            if let Some(selected_interval) = selected_interval {
+                orders.notify(data::Msg::SetInterval(selected_interval));
+            }
+            orders.notify(data::Msg::ReadOlderTrainingSessions(
+                if selected_interval == Some(domain::DefaultInterval::All) {
+                    None
+                } else {
+                    Some(first)
+                },
+            ));
+        }
+        Msg::ToggleDataTables => {
+            orders.notify(data::Msg::ToggleDataTables(web_app::chart::Page::Routine));
+        }
+
+        Msg::CopyExport => {
+            let Some(routine) = data_model.routines.get(&model.routine_id) else {
+                return;
+            };
+            match web_app::routine_exchange::RoutineExport::new(routine, &data_model.exercises) {
+                Ok(export) => match web_app::share::copy_to_clipboard(&export.to_json()) {
+                    Ok(promise) => {
+                        orders.perform_cmd(async move {
+                            match wasm_bindgen_futures::JsFuture::from(promise).await {
+                                Ok(_) => Some(Msg::ExportCopied),
+                                Err(err) => {
+                                    error!("failed to copy routine:", err);
+                                    None
+                                }
+                            }
+                        });
+                    }
+                    Err(err) => error!("failed to copy routine:", err),
+                },
+                Err(err) => error!("failed to export routine:", err.to_string()),
+            }
+        }
+        Msg::ExportCopied => {
+            model.copied = true;
         }
     }
 }
 
 fn update_model(model: &mut Model, data_model: &data::Model) {
+    update_interval(model, data_model);
+    update_routine(model, data_model);
+    update_previous_exercises(model, data_model);
+}
+
+fn update_interval(model: &mut Model, data_model: &data::Model) {
     model.interval = domain::init_interval(
         &data_model
             .training_sessions
@@ -676,17 +888,30 @@ fn update_model(model: &mut Model, data_model: &data::Model) {
             .map(|t| t.date)
             .collect::<Vec<NaiveDate>>(),
         domain::DefaultInterval::All,
+        data_model.settings.initial_interval(),
+        &web_app::clock::BrowserClock,
     );
+}
 
-    let routine = &data_model.routines.get(&model.routine_id);
-
-    if let Some(routine) = routine {
+fn update_routine(model: &mut Model, data_model: &data::Model) {
+    if let Some(routine) = data_model.routines.get(&model.routine_id) {
         model.name = common::InputField {
             input: routine.name.clone(),
             parsed: Some(routine.name.clone()),
             orig: routine.name.clone(),
+            error: None,
         };
+        model.schedule = routine.schedule.clone();
+        model.orig_schedule = routine.schedule.clone();
         model.sections = routine.sections.iter().map(Into::into).collect();
+    } else {
+        model.sections = vec![];
+    };
+    model.parts_moved = false;
+}
+
+fn update_previous_exercises(model: &mut Model, data_model: &data::Model) {
+    if let Some(routine) = data_model.routines.get(&model.routine_id) {
         let training_sessions = &data_model
             .training_sessions
             .values()
@@ -698,7 +923,6 @@ fn update_model(model: &mut Model, data_model: &data::Model) {
             .collect::<BTreeSet<_>>();
         model.previous_exercises = all_exercises - &routine.exercises();
     } else {
-        model.sections = vec![];
         model.previous_exercises = BTreeSet::new();
     };
 }
@@ -708,18 +932,23 @@ fn update_model(model: &mut Model, data_model: &data::Model) {
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.routines.is_empty() && data_model.loading_routines {
-        common::view_page_loading()
+    if let Some(node) = common::view_resource_unavailable(&data_model.routines, |_| Msg::Retry) {
+        node
     } else if let Some(routine) = data_model.routines.get(&model.routine_id) {
         div![
             view_title(model),
             if not(model.editing) {
-                view_summary(routine)
+                view_summary(routine, data_model)
             } else {
-                empty![]
+                view_schedule_editor(&model.schedule)
             },
             view_dialog(&model.dialog, model.loading, data_model),
-            view_routine(data_model, &model.sections, model.editing),
+            IF![not(model.editing) => view_preview_buttons(model.preview)],
+            if model.preview && not(model.editing) {
+                view_preview(routine, data_model)
+            } else {
+                view_routine(data_model, &model.sections, model.editing)
+            },
             if model.editing {
                 nodes![button![
                     C!["button"],
@@ -735,9 +964,12 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
                 ]]
             } else {
                 nodes![
+                    view_notes(routine),
                     view_previous_exercises(model, data_model),
                     view_muscles(routine, data_model),
                     view_training_sessions(model, data_model),
+                    view_adherence(routine, model, data_model),
+                    view_export(routine, model.copied, data_model),
                     common::view_fab("edit", |_| Msg::EditRoutine),
                 ]
             },
@@ -806,7 +1038,14 @@ fn view_dialog(dialog: &Dialog, loading: bool, data_model: &data::Model) -> Node
     }
 }
 
-fn view_summary(routine: &domain::Routine) -> Node<Msg> {
+fn view_summary(routine: &domain::Routine, data_model: &data::Model) -> Node<Msg> {
+    let avg_duration = domain::avg_duration(
+        &data_model
+            .training_sessions
+            .values()
+            .filter(|t| t.routine_id == Some(routine.id))
+            .collect::<Vec<_>>(),
+    );
     div![
         C!["columns"],
         C!["is-gapless"],
@@ -824,6 +1063,64 @@ fn view_summary(routine: &domain::Routine) -> Node<Msg> {
         ]
         .iter()
         .map(|(title, subtitle)| { div![C!["column"], common::view_box(title, subtitle)] }),
+        avg_duration.map(|duration| {
+            div![
+                C!["column"],
+                common::view_box(
+                    "Avg. time",
+                    &format!("<strong>{}</strong> min", duration.num_minutes())
+                )
+            ]
+        }),
+        IF![not(routine.schedule.is_empty()) => {
+            div![
+                C!["column"],
+                common::view_box(
+                    "Schedule",
+                    &format!(
+                        "<strong>{}</strong>",
+                        routine
+                            .schedule
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    )
+                )
+            ]
+        }],
+    ]
+}
+
+fn view_schedule_editor(schedule: &[Weekday]) -> Node<Msg> {
+    div![
+        C!["field"],
+        C!["px-2"],
+        C!["mt-3"],
+        label![C!["label"], "Schedule"],
+        div![
+            C!["buttons"],
+            C!["has-addons"],
+            [
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun
+            ]
+            .into_iter()
+            .map(|weekday| {
+                button![
+                    C!["button"],
+                    C!["is-small"],
+                    C![IF![schedule.contains(&weekday) => "is-link"]],
+                    ev(Ev::Click, move |_| Msg::ToggleScheduledWeekday(weekday)),
+                    weekday.to_string(),
+                ]
+            }),
+        ],
     ]
 }
 
@@ -849,6 +1146,122 @@ fn view_routine(data_model: &data::Model, routine_sections: &[Form], editing: bo
     ]
 }
 
+fn view_preview_buttons(preview: bool) -> Node<Msg> {
+    div![
+        C!["field"],
+        C!["has-addons"],
+        C!["is-justify-content-center"],
+        C!["mt-5"],
+        [
+            (false, "fa-layer-group", "Sections"),
+            (true, "fa-list-ol", "Preview"),
+        ]
+        .into_iter()
+        .map(|(p, icon, name)| {
+            p![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-small"],
+                    C![IF![p == preview => "is-link"]],
+                    ev(Ev::Click, move |_| Msg::SetPreview(p)),
+                    span![C!["icon"], i![C!["fas"], C![icon]]],
+                    span![name],
+                ]
+            ]
+        })
+    ]
+}
+
+fn view_preview(routine: &domain::Routine, data_model: &data::Model) -> Node<Msg> {
+    let show_rpe = data_model.settings.show_rpe;
+    let show_tut = data_model.settings.show_tut;
+    let elements = domain::expand_routine_parts(&routine.sections, &|exercise_id, percent_1rm| {
+        data_model.target_weight(exercise_id, percent_1rm)
+    });
+    let has_rounds = elements
+        .iter()
+        .any(|e| e.rounds.iter().any(|r| r.total > 1));
+    let mut set_number = 0_u32;
+
+    div![
+        C!["table-container"],
+        C!["mt-4"],
+        table![
+            C!["table"],
+            C!["is-fullwidth"],
+            C!["is-hoverable"],
+            C!["has-text-centered"],
+            thead![tr![
+                th!["Set"],
+                IF![has_rounds => th!["Round"]],
+                th!["Exercise"],
+                th!["Target"],
+            ]],
+            tbody![elements
+                .iter()
+                .map(|e| {
+                    let round = e
+                        .rounds
+                        .iter()
+                        .filter(|r| r.total > 1)
+                        .map(|r| format!("{}/{}", r.number, r.total))
+                        .collect::<Vec<_>>()
+                        .join(" · ");
+                    match &e.element {
+                        domain::TrainingSessionElement::Set {
+                            exercise_id,
+                            target_reps,
+                            target_time,
+                            target_weight,
+                            target_rpe,
+                            ..
+                        } => {
+                            set_number += 1;
+                            let target = web_app::format_set(
+                                *target_reps,
+                                None,
+                                *target_time,
+                                show_tut,
+                                *target_weight,
+                                *target_rpe,
+                                show_rpe,
+                                false,
+                            );
+                            tr![
+                                td![set_number],
+                                IF![has_rounds => td![round]],
+                                td![data_model.exercises.get(exercise_id).map_or_else(
+                                    || format!("Exercise#{exercise_id}"),
+                                    |exercise| exercise.name.clone()
+                                )],
+                                td![if target.is_empty() {
+                                    String::from("-")
+                                } else {
+                                    target
+                                }],
+                            ]
+                        }
+                        domain::TrainingSessionElement::Rest { target_time, .. } => tr![
+                            C!["has-text-grey"],
+                            td![],
+                            IF![has_rounds => td![round]],
+                            td!["Rest"],
+                            td![target_time.map(|t| format!("{t} s"))],
+                        ],
+                    }
+                })
+                .collect::<Vec<_>>()],
+            tfoot![tr![
+                th![routine.num_sets()],
+                IF![has_rounds => th![]],
+                th!["Total"],
+                th![format!("~ {} min", routine.duration().num_minutes())],
+            ]],
+        ]
+    ]
+}
+
 fn view_routine_part(
     data_model: &data::Model,
     part: &Form,
@@ -938,11 +1351,22 @@ fn view_routine_part(
         Form::Activity {
             exercise_id,
             reps,
+            reps_max,
             time,
             weight,
             rpe,
+            target_percent_1rm,
             automatic,
         } => {
+            let one_rep_max_missing = exercise_id
+                .and_then(|exercise_id| data_model.exercises.get(&exercise_id))
+                .is_some_and(|exercise| {
+                    domain::progression::one_rep_max_values(
+                        data_model.training_sessions.values(),
+                        exercise,
+                    )
+                    .is_empty()
+                });
             div![
                 C!["message"],
                 IF![editing || id.first() != Some(&0) => C!["mt-3"]],
@@ -1049,6 +1473,40 @@ fn view_routine_part(
                                     ]
                                 ]
                             ],
+                            IF![
+                                exercise_id.is_some() =>
+                                div![
+                                    C!["field"],
+                                    C!["mb-0"],
+                                    C!["mr-2"],
+                                    div![
+                                        C!["control"],
+                                        C!["has-icons-left"],
+                                        C!["has-icons-right"],
+                                        input_ev(Ev::Input, {
+                                            let id = id.clone();
+                                            move |v| Msg::RepsMaxChanged(id, v)
+                                        }),
+                                        span![C!["icon"], C!["is-small"], C!["is-left"], "–"],
+                                        input![
+                                            C!["input"],
+                                            C!["has-text-right"],
+                                            C![IF![not(reps_max.valid()) => "is-danger"]],
+                                            C![IF![reps_max.changed() => "is-info"]],
+                                            attrs! {
+                                                At::Type => "number",
+                                                At::Min => 0,
+                                                At::Max => 999,
+                                                At::Step => 1,
+                                                At::Size => 2,
+                                                At::Placeholder => "max",
+                                                At::Value => reps_max.input,
+                                            }
+                                        ],
+                                        span![C!["icon"], C!["is-small"], C!["is-right"], "✕"],
+                                    ]
+                                ]
+                            ],
                             IF![
                                 show_tut || exercise_id.is_none() =>
                                 div![
@@ -1142,12 +1600,46 @@ fn view_routine_part(
                                             C!["has-text-right"],
                                             C![IF![not(rpe.valid()) => "is-danger"]],
                                             C![IF![rpe.changed() => "is-info"]],
+                                            attrs! {
+                                                At::Size => 5,
+                                                At::Value => rpe.input,
+                                            }
+                                        ],
+                                    ]
+                                ]
+                            ],
+                            IF![
+                                exercise_id.is_some() =>
+                                div![
+                                    C!["field"],
+                                    C!["mb-0"],
+                                    C!["mr-2"],
+                                    div![
+                                        C!["control"],
+                                        C!["has-icons-left"],
+                                        C!["has-icons-right"],
+                                        input_ev(Ev::Input, {
+                                            let id = id.clone();
+                                            move |v| Msg::TargetPercent1RMChanged(id, v)
+                                        }),
+                                        span![
+                                            C!["icon"],
+                                            C!["is-small"],
+                                            C!["is-left"],
+                                            i![C!["fas fa-percent"]]
+                                        ],
+                                        input![
+                                            C!["input"],
+                                            C!["has-text-right"],
+                                            C![IF![not(target_percent_1rm.valid()) => "is-danger"]],
+                                            C![IF![target_percent_1rm.changed() => "is-info"]],
                                             attrs! {
                                                 At::from("inputmode") => "numeric",
                                                 At::Size => 3,
-                                                At::Value => rpe.input,
+                                                At::Value => target_percent_1rm.input,
                                             }
                                         ],
+                                        span![C!["icon"], C!["is-small"], C!["is-right"], "1RM"],
                                     ]
                                 ]
                             ],
@@ -1173,7 +1665,13 @@ fn view_routine_part(
                                         C!["icon-text"],
                                         C!["mr-4"],
                                         span![C!["mr-2"], i![C!["fas fa-rotate-left"]]],
-                                        span![&reps.input]
+                                        span![
+                                            &reps.input,
+                                            IF![
+                                                reps_max.parsed.is_some_and(|max| max > 0) =>
+                                                plain![format!("–{}", reps_max.input)]
+                                            ]
+                                        ]
                                     ]
                                 }
                             ],
@@ -1198,7 +1696,7 @@ fn view_routine_part(
                                 }
                             ],
                             IF![
-                                if let Some(rpe) = rpe.parsed { rpe > 0.0 } else { false } && show_rpe => {
+                                rpe.parsed.is_some_and(|rpe| rpe.is_set()) && show_rpe => {
                                     span![
                                         C!["icon-text"],
                                         C!["mr-4"],
@@ -1207,6 +1705,25 @@ fn view_routine_part(
                                     ]
                                 }
                             ],
+                            IF![
+                                if let Some(percent) = target_percent_1rm.parsed { percent > 0.0 } else { false } => {
+                                    span![
+                                        C!["icon-text"],
+                                        C!["mr-4"],
+                                        span![C!["mr-2"], i![C!["fas fa-percent"]]],
+                                        span![&target_percent_1rm.input, " % 1RM"],
+                                        IF![
+                                            one_rep_max_missing =>
+                                            span![
+                                                C!["ml-2"],
+                                                C!["has-text-warning-dark"],
+                                                C!["is-italic"],
+                                                "(no 1RM data available)"
+                                            ]
+                                        ]
+                                    ]
+                                }
+                            ],
                             IF![
                                 *automatic => span![
                                     C!["icon"],
@@ -1223,6 +1740,57 @@ fn view_routine_part(
     }
 }
 
+fn view_notes(routine: &domain::Routine) -> Node<Msg> {
+    match &routine.notes {
+        Some(notes) if not(notes.is_empty()) => div![
+            C!["container"],
+            C!["m-3"],
+            C!["mt-6"],
+            common::view_title(&span!["Notes"], 3),
+            div![C!["content"], raw![&web_app::markdown::to_html(notes)]]
+        ],
+        _ => empty![],
+    }
+}
+
+/// Buttons for sharing the routine with users of other instances.
+fn view_export(routine: &domain::Routine, copied: bool, data_model: &data::Model) -> Node<Msg> {
+    let Ok(export) = web_app::routine_exchange::RoutineExport::new(routine, &data_model.exercises)
+    else {
+        return empty![];
+    };
+    div![
+        C!["buttons"],
+        C!["is-centered"],
+        C!["mt-6"],
+        button![
+            C!["button"],
+            C!["is-small"],
+            ev(Ev::Click, |_| Msg::CopyExport),
+            span![
+                C!["icon"],
+                C!["is-small"],
+                i![C![if copied {
+                    "fas fa-check"
+                } else {
+                    "fas fa-copy"
+                }]]
+            ],
+            span![if copied { "Copied" } else { "Copy export" }]
+        ],
+        a![
+            C!["button"],
+            C!["is-small"],
+            attrs! {
+                At::Href => web_app::routine_exchange::data_url(&export.to_json()),
+                At::from("download") => format!("{}.json", routine.name),
+            },
+            span![C!["icon"], C!["is-small"], i![C!["fas fa-file-export"]]],
+            span!["Export routine"]
+        ],
+    ]
+}
+
 fn view_previous_exercises(model: &Model, data_model: &data::Model) -> Node<Msg> {
     if model.previous_exercises.is_empty() {
         empty![]
@@ -1295,25 +1863,119 @@ fn view_training_sessions(model: &Model, data_model: &data::Model) -> Node<Msg>
         view_charts(
             &training_sessions,
             &model.interval,
-            data_model.theme(),
+            &data_model.chart_style(),
             data_model.settings.show_rpe,
+            data_table(data_model),
         ),
         training::view_calendar(&training_sessions, &model.interval),
         training::view_table(
             &training_sessions,
             &data_model.routines,
+            &data_model.exercises,
             &data_model.base_url,
             Msg::ShowDeleteTrainingSessionDialog,
             data_model.settings.show_rpe,
             data_model.settings.show_tut,
+            data_model.settings.decimal_separator,
         ),
     ]
 }
-pub fn view_charts<Ms>(
+
+fn view_adherence(routine: &domain::Routine, model: &Model, data_model: &data::Model) -> Node<Msg> {
+    let adherence = domain::routine_adherence(
+        routine,
+        &data_model.training_sessions.values().collect::<Vec<_>>(),
+        &model.interval,
+    );
+    if adherence.exercises.iter().all(|e| e.completed_sets == 0) {
+        return empty![];
+    }
+    let separator = data_model.settings.decimal_separator;
+    let format_deviation = |deviation: Option<f32>| {
+        deviation.map_or(String::from("-"), |deviation| {
+            web_app::format::format_signed(deviation, 1, separator)
+        })
+    };
+    div![
+        C!["container"],
+        C!["has-text-centered"],
+        C!["mt-6"],
+        common::view_title(&span!["Adherence"], 5),
+        IF![adherence.skipped_sessions > 0 => p![
+            C!["mb-3"],
+            format!(
+                "{} skipped training session{}",
+                adherence.skipped_sessions,
+                if adherence.skipped_sessions == 1 { "" } else { "s" }
+            )
+        ]],
+        div![
+            C!["table-container"],
+            table![
+                C!["table"],
+                C!["is-fullwidth"],
+                C!["is-hoverable"],
+                C!["has-text-centered"],
+                thead![tr![
+                    th!["Exercise"],
+                    th!["Sets"],
+                    th!["Completion"],
+                    th!["Reps deviation"],
+                    th!["Weight deviation (kg)"],
+                ]],
+                tbody![adherence.exercises.iter().map(|e| {
+                    let (completion, color) = match e.completion() {
+                        Some(completion) => (
+                            web_app::format::format_percentage(
+                                completion,
+                                web_app::format::PRECISION_COUNT,
+                                separator
+                            ),
+                            if completion >= 90.0 {
+                                "has-text-success"
+                            } else if completion >= 70.0 {
+                                "has-text-warning"
+                            } else {
+                                "has-text-danger"
+                            },
+                        ),
+                        None => (String::from("-"), "has-text-grey"),
+                    };
+                    tr![
+                        td![a![
+                            attrs! {
+                                At::Href => crate::Urls::new(&data_model.base_url).exercise().add_hash_path_part(e.exercise_id.to_string()),
+                            },
+                            data_model
+                                .exercises
+                                .get(&e.exercise_id)
+                                .map(|exercise| exercise.name.clone())
+                                .unwrap_or_default()
+                        ]],
+                        td![format!("{} / {}", e.completed_sets, e.planned_sets)],
+                        td![C![color], completion],
+                        td![format_deviation(e.reps_deviation)],
+                        td![format_deviation(e.weight_deviation)],
+                    ]
+                })],
+            ]
+        ],
+    ]
+}
+
+fn data_table(data_model: &data::Model) -> common::DataTable<Msg> {
+    common::DataTable {
+        shown: data_model.show_data_tables(web_app::chart::Page::Routine),
+        toggle: || Msg::ToggleDataTables,
+    }
+}
+
+pub fn view_charts<Ms: 'static>(
     training_sessions: &[&domain::TrainingSession],
     interval: &domain::Interval,
-    theme: &web_app::Theme,
+    style: &web_app::chart::Style,
     show_rpe: bool,
+    data_table: common::DataTable<Ms>,
 ) -> Vec<Node<Ms>> {
     let mut load: BTreeMap<NaiveDate, f32> = BTreeMap::new();
     let mut set_volume: BTreeMap<NaiveDate, f32> = BTreeMap::new();
@@ -1335,19 +1997,18 @@ pub fn view_charts<Ms>(
                 web_app::chart::COLOR_LOAD,
                 web_app::chart::OPACITY_LINE
             )],
-            web_app::chart::plot(
-                &[web_app::chart::PlotData {
-                    values_high: load.into_iter().collect::<Vec<_>>(),
-                    values_low: None,
-                    plots: web_app::chart::plot_area_with_border(
-                        web_app::chart::COLOR_LOAD,
-                        web_app::chart::COLOR_LOAD
-                    ),
-                    params: web_app::chart::PlotParams::primary_range(0., 10.),
-                }],
-                interval,
-                theme,
-            ),
+            &[web_app::chart::PlotData {
+                values_high: load.into_iter().collect::<Vec<_>>(),
+                values_low: None,
+                plots: web_app::chart::plot_area_with_border(
+                    web_app::chart::COLOR_LOAD,
+                    web_app::chart::COLOR_LOAD
+                ),
+                params: web_app::chart::PlotParams::primary_range(0., 10.),
+            }],
+            interval,
+            style,
+            data_table,
             false,
         ),
         common::view_chart(
@@ -1356,19 +2017,18 @@ pub fn view_charts<Ms>(
                 web_app::chart::COLOR_SET_VOLUME,
                 web_app::chart::OPACITY_LINE
             )],
-            web_app::chart::plot(
-                &[web_app::chart::PlotData {
-                    values_high: set_volume.into_iter().collect::<Vec<_>>(),
-                    values_low: None,
-                    plots: web_app::chart::plot_area_with_border(
-                        web_app::chart::COLOR_SET_VOLUME,
-                        web_app::chart::COLOR_SET_VOLUME,
-                    ),
-                    params: web_app::chart::PlotParams::primary_range(0., 10.),
-                }],
-                interval,
-                theme,
-            ),
+            &[web_app::chart::PlotData {
+                values_high: set_volume.into_iter().collect::<Vec<_>>(),
+                values_low: None,
+                plots: web_app::chart::plot_area_with_border(
+                    web_app::chart::COLOR_SET_VOLUME,
+                    web_app::chart::COLOR_SET_VOLUME,
+                ),
+                params: web_app::chart::PlotParams::primary_range(0., 10.),
+            }],
+            interval,
+            style,
+            data_table,
             false,
         ),
         IF![
@@ -1378,7 +2038,7 @@ pub fn view_charts<Ms>(
                     ("RPE", web_app::chart::COLOR_RPE, web_app::chart::OPACITY_AREA),
                     ("Avg. RPE", web_app::chart::COLOR_RPE, web_app::chart::OPACITY_LINE)
                 ],
-                web_app::chart::plot_min_avg_max(
+                &web_app::chart::min_avg_max(
                     &training_sessions
                         .iter()
                         .flat_map(|s| s
@@ -1394,8 +2054,10 @@ pub fn view_charts<Ms>(
                     interval,
                     web_app::chart::PlotParams::primary_range(5., 10.),
                     web_app::chart::COLOR_RPE,
-                    theme,
                 ),
+                interval,
+                style,
+                data_table,
                 false,
             )
         ],
@@ -1489,6 +2151,25 @@ fn view_add_section_button(id: Vec<usize>) -> Node<Msg> {
     ]
 }
 
+fn move_part(model: &mut Model, id: &[usize], direction: domain::Direction) {
+    let path = id.iter().rev().copied().collect::<Vec<_>>();
+    if domain::move_part(&mut model.sections, &path, direction) {
+        model.parts_moved = true;
+    }
+}
+
+/// Parse the upper bound of a reps range. An empty input means that no range is defined. The bound
+/// is invalid if it is lower than the given minimum reps or if the minimum reps are missing.
+fn parse_reps_max(input: &str, min: Option<u32>) -> Option<u32> {
+    if input.is_empty() {
+        return Some(0);
+    }
+    input.parse::<u32>().ok().filter(|max| match min {
+        Some(min) => domain::validate_reps_range(min, Some(*max)).is_ok(),
+        None => common::valid_reps(*max),
+    })
+}
+
 fn get_part<'a>(sections: &'a mut [Form], id: &[usize]) -> Option<&'a mut Form> {
     if let Some(i) = id.last() {
         if i < &sections.len() {
@@ -1516,9 +2197,11 @@ mod tests {
                 parts: vec![Form::Activity {
                     exercise_id: None,
                     reps: form_value(1),
+                    reps_max: form_value(0),
                     time: form_value(2),
                     weight: form_value(4.0),
-                    rpe: form_value(5.0),
+                    rpe: form_value(domain::RpeRange::single(5.0)),
+                    target_percent_1rm: form_value(0.0),
                     automatic: false,
                 }],
             },
@@ -1527,9 +2210,11 @@ mod tests {
                 parts: vec![Form::Activity {
                     exercise_id: None,
                     reps: form_value(2),
+                    reps_max: form_value(0),
                     time: form_value(3),
                     weight: form_value(5.0),
-                    rpe: form_value(6.0),
+                    rpe: form_value(domain::RpeRange::single(6.0)),
+                    target_percent_1rm: form_value(0.0),
                     automatic: false,
                 }],
             },
@@ -1541,9 +2226,11 @@ mod tests {
                 parts: vec![Form::Activity {
                     exercise_id: None,
                     reps: form_value(1),
+                    reps_max: form_value(0),
                     time: form_value(2),
                     weight: form_value(4.0),
-                    rpe: form_value(5.0),
+                    rpe: form_value(domain::RpeRange::single(5.0)),
+                    target_percent_1rm: form_value(0.0),
                     automatic: false,
                 }],
             }
@@ -1555,9 +2242,11 @@ mod tests {
                 parts: vec![Form::Activity {
                     exercise_id: None,
                     reps: form_value(2),
+                    reps_max: form_value(0),
                     time: form_value(3),
                     weight: form_value(5.0),
-                    rpe: form_value(6.0),
+                    rpe: form_value(domain::RpeRange::single(6.0)),
+                    target_percent_1rm: form_value(0.0),
                     automatic: false,
                 }],
             }
@@ -1568,9 +2257,11 @@ mod tests {
             Form::Activity {
                 exercise_id: None,
                 reps: form_value(1),
+                reps_max: form_value(0),
                 time: form_value(2),
                 weight: form_value(4.0),
-                rpe: form_value(5.0),
+                rpe: form_value(domain::RpeRange::single(5.0)),
+                target_percent_1rm: form_value(0.0),
                 automatic: false,
             },
         );
@@ -1580,9 +2271,11 @@ mod tests {
             Form::Activity {
                 exercise_id: None,
                 reps: form_value(2),
+                reps_max: form_value(0),
                 time: form_value(3),
                 weight: form_value(5.0),
-                rpe: form_value(6.0),
+                rpe: form_value(domain::RpeRange::single(6.0)),
+                target_percent_1rm: form_value(0.0),
                 automatic: false,
             },
         );
@@ -1597,9 +2290,11 @@ mod tests {
                 Form::Activity {
                     exercise_id: None,
                     reps: form_value(1),
+                    reps_max: form_value(0),
                     time: form_value(2),
                     weight: form_value(4.0),
-                    rpe: form_value(5.0),
+                    rpe: form_value(domain::RpeRange::single(5.0)),
+                    target_percent_1rm: form_value(0.0),
                     automatic: false,
                 },
                 Form::Section {
@@ -1607,9 +2302,11 @@ mod tests {
                     parts: vec![Form::Activity {
                         exercise_id: None,
                         reps: form_value(2),
+                        reps_max: form_value(0),
                         time: form_value(3),
                         weight: form_value(5.0),
-                        rpe: form_value(6.0),
+                        rpe: form_value(domain::RpeRange::single(6.0)),
+                        target_percent_1rm: form_value(0.0),
                         automatic: false,
                     }],
                 },
@@ -1623,9 +2320,11 @@ mod tests {
                     Form::Activity {
                         exercise_id: None,
                         reps: form_value(1),
+                        reps_max: form_value(0),
                         time: form_value(2),
                         weight: form_value(4.0),
-                        rpe: form_value(5.0),
+                        rpe: form_value(domain::RpeRange::single(5.0)),
+                        target_percent_1rm: form_value(0.0),
                         automatic: false,
                     },
                     Form::Section {
@@ -1633,9 +2332,11 @@ mod tests {
                         parts: vec![Form::Activity {
                             exercise_id: None,
                             reps: form_value(2),
+                            reps_max: form_value(0),
                             time: form_value(3),
                             weight: form_value(5.0),
-                            rpe: form_value(6.0),
+                            rpe: form_value(domain::RpeRange::single(6.0)),
+                            target_percent_1rm: form_value(0.0),
                             automatic: false,
                         }],
                     },
@@ -1648,9 +2349,11 @@ mod tests {
             Form::Activity {
                 exercise_id: None,
                 reps: form_value(1),
+                reps_max: form_value(0),
                 time: form_value(2),
                 weight: form_value(4.0),
-                rpe: form_value(5.0),
+                rpe: form_value(domain::RpeRange::single(5.0)),
+                target_percent_1rm: form_value(0.0),
                 automatic: false,
             },
         );
@@ -1661,9 +2364,11 @@ mod tests {
                 parts: vec![Form::Activity {
                     exercise_id: None,
                     reps: form_value(2),
+                    reps_max: form_value(0),
                     time: form_value(3),
                     weight: form_value(5.0),
-                    rpe: form_value(6.0),
+                    rpe: form_value(domain::RpeRange::single(6.0)),
+                    target_percent_1rm: form_value(0.0),
                     automatic: false,
                 }],
             },
@@ -1675,9 +2380,11 @@ mod tests {
             Form::Activity {
                 exercise_id: None,
                 reps: form_value(2),
+                reps_max: form_value(0),
                 time: form_value(3),
                 weight: form_value(5.0),
-                rpe: form_value(6.0),
+                rpe: form_value(domain::RpeRange::single(6.0)),
+                target_percent_1rm: form_value(0.0),
                 automatic: false,
             },
         );
@@ -1685,11 +2392,24 @@ mod tests {
         assert!(get_part(&mut sections, &[0, 0, 1, 0]).is_none());
     }
 
+    #[test]
+    fn parse_reps_max_validates_range() {
+        assert_eq!(parse_reps_max("", Some(8)), Some(0));
+        assert_eq!(parse_reps_max("12", Some(8)), Some(12));
+        assert_eq!(parse_reps_max("8", Some(8)), Some(8));
+        assert_eq!(parse_reps_max("6", Some(8)), None);
+        assert_eq!(parse_reps_max("12", Some(0)), None);
+        assert_eq!(parse_reps_max("12", None), Some(12));
+        assert_eq!(parse_reps_max("1000", None), None);
+        assert_eq!(parse_reps_max("x", Some(8)), None);
+    }
+
     fn form_value<T: std::fmt::Display + std::marker::Copy>(number: T) -> common::InputField<T> {
         common::InputField {
             input: number.to_string(),
             parsed: Some(number),
             orig: number.to_string(),
+            error: None,
         }
     }
 }
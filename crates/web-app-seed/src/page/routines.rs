@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
+
 use seed::{prelude::*, *};
 use valens_domain as domain;
+use valens_web_app as web_app;
 
 use crate::{common, data};
 
@@ -19,6 +22,7 @@ pub fn init(mut url: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Nav
     Model {
         search_term: url.hash_path().get(1).cloned().unwrap_or_default(),
         dialog: Dialog::Hidden,
+        tag_filter: None,
         archive_visible: false,
         loading: false,
     }
@@ -31,6 +35,7 @@ pub fn init(mut url: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Nav
 pub struct Model {
     search_term: String,
     dialog: Dialog,
+    tag_filter: Option<String>,
     archive_visible: bool,
     loading: bool,
 }
@@ -40,6 +45,9 @@ enum Dialog {
     AddRoutine(Form),
     EditRoutine(Form),
     DeleteRoutine(u32),
+    ImportRoutine(ImportForm),
+    EditTags(TagsForm),
+    DeleteTag(String),
 }
 
 struct Form {
@@ -48,6 +56,34 @@ struct Form {
     template_routine_id: u32,
 }
 
+struct ImportForm {
+    snippet: String,
+    export: Result<web_app::routine_exchange::RoutineExport, String>,
+    name: common::InputField<String>,
+    create_missing_exercises: bool,
+}
+
+struct TagsForm {
+    id: u32,
+    tags: Vec<domain::Tag>,
+    name: String,
+    color: domain::TagColor,
+}
+
+impl TagsForm {
+    fn new_tag(&self) -> Option<domain::Tag> {
+        let name = self.name.trim();
+        if name.is_empty() || self.tags.iter().any(|t| t.name == name) {
+            None
+        } else {
+            Some(domain::Tag {
+                name: name.to_string(),
+                color: self.color,
+            })
+        }
+    }
+}
+
 // ------ ------
 //    Update
 // ------ ------
@@ -56,17 +92,32 @@ pub enum Msg {
     ShowAddRoutineDialog,
     ShowEditRoutineDialog(u32),
     ShowDeleteRoutineDialog(u32),
+    ShowImportRoutineDialog,
+    ShowEditTagsDialog(u32),
+    ShowDeleteTagDialog(String),
     CloseRoutineDialog,
 
     SearchTermChanged(String),
     NameChanged(String),
     TemplateRoutineChanged(String),
+    SnippetChanged(String),
+    ToggleCreateMissingExercises,
+    TagNameChanged(String),
+    TagColorChanged(String),
+    AddTag,
+    AssignTag(domain::Tag),
+    UnassignTag(String),
 
+    ToggleTagFilter(String),
     ShowArchive,
 
     SaveRoutine,
+    ImportRoutine,
+    SaveTags,
     ChangeArchived(u32, bool),
     DeleteRoutine(u32),
+    DeleteTag(String),
+    Retry,
     DataEvent(data::Event),
 }
 
@@ -93,6 +144,7 @@ pub fn update(
                     input: name.clone(),
                     parsed: Some(name.clone()),
                     orig: name,
+                    error: None,
                 },
                 template_routine_id: 0,
             });
@@ -100,6 +152,25 @@ pub fn update(
         Msg::ShowDeleteRoutineDialog(id) => {
             model.dialog = Dialog::DeleteRoutine(id);
         }
+        Msg::ShowImportRoutineDialog => {
+            model.dialog = Dialog::ImportRoutine(ImportForm {
+                snippet: String::new(),
+                export: Err(String::new()),
+                name: common::InputField::default(),
+                create_missing_exercises: true,
+            });
+        }
+        Msg::ShowEditTagsDialog(id) => {
+            model.dialog = Dialog::EditTags(TagsForm {
+                id,
+                tags: data_model.routines[&id].tags.clone(),
+                name: String::new(),
+                color: domain::TagColor::default(),
+            });
+        }
+        Msg::ShowDeleteTagDialog(name) => {
+            model.dialog = Dialog::DeleteTag(name);
+        }
         Msg::CloseRoutineDialog => {
             model.dialog = Dialog::Hidden;
             Url::go_and_replace(&crate::Urls::new(&data_model.base_url).routines());
@@ -113,26 +184,42 @@ pub fn update(
                 .go_and_replace();
         }
         Msg::NameChanged(name) => match model.dialog {
-            Dialog::AddRoutine(ref mut form) | Dialog::EditRoutine(ref mut form) => {
+            Dialog::AddRoutine(Form {
+                name: ref mut name_field,
+                ..
+            })
+            | Dialog::EditRoutine(Form {
+                name: ref mut name_field,
+                ..
+            })
+            | Dialog::ImportRoutine(ImportForm {
+                name: ref mut name_field,
+                ..
+            }) => {
                 let trimmed_name = name.trim();
                 if not(trimmed_name.is_empty())
-                    && (trimmed_name == form.name.orig
+                    && (trimmed_name == name_field.orig
                         || data_model.routines.values().all(|e| e.name != trimmed_name))
                 {
-                    form.name = common::InputField {
+                    *name_field = common::InputField {
                         input: name.clone(),
                         parsed: Some(trimmed_name.to_string()),
-                        orig: form.name.orig.clone(),
+                        orig: name_field.orig.clone(),
+                        error: None,
                     };
                 } else {
-                    form.name = common::InputField {
+                    *name_field = common::InputField {
                         input: name.clone(),
                         parsed: None,
-                        orig: form.name.orig.clone(),
+                        orig: name_field.orig.clone(),
+                        error: None,
                     };
                 }
             }
-            Dialog::Hidden | Dialog::DeleteRoutine(_) => {
+            Dialog::Hidden
+            | Dialog::DeleteRoutine(_)
+            | Dialog::EditTags(_)
+            | Dialog::DeleteTag(_) => {
                 panic!();
             }
         },
@@ -143,11 +230,86 @@ pub fn update(
                 }
                 Err(_) => form.template_routine_id = 0,
             },
-            Dialog::Hidden | Dialog::EditRoutine(_) | Dialog::DeleteRoutine(_) => {
+            Dialog::Hidden
+            | Dialog::EditRoutine(_)
+            | Dialog::DeleteRoutine(_)
+            | Dialog::ImportRoutine(_)
+            | Dialog::EditTags(_)
+            | Dialog::DeleteTag(_) => {
                 panic!();
             }
         },
 
+        Msg::SnippetChanged(snippet) => {
+            if let Dialog::ImportRoutine(ref mut form) = model.dialog {
+                form.export = if snippet.trim().is_empty() {
+                    Err(String::new())
+                } else {
+                    web_app::routine_exchange::RoutineExport::from_json(&snippet)
+                        .map_err(|err| err.to_string())
+                };
+                form.snippet = snippet;
+                if let Ok(export) = &form.export {
+                    if form.name.input.is_empty() {
+                        orders.send_msg(Msg::NameChanged(export.name.trim().to_string()));
+                    }
+                }
+            }
+        }
+        Msg::ToggleCreateMissingExercises => {
+            if let Dialog::ImportRoutine(ref mut form) = model.dialog {
+                form.create_missing_exercises = not(form.create_missing_exercises);
+            }
+        }
+
+        Msg::TagNameChanged(name) => {
+            if let Dialog::EditTags(ref mut form) = model.dialog {
+                if let Some(tag) = domain::tags(data_model.routines.values())
+                    .into_iter()
+                    .find(|t| t.name == name.trim())
+                {
+                    form.color = tag.color;
+                }
+                form.name = name;
+            }
+        }
+        Msg::TagColorChanged(input) => {
+            if let Dialog::EditTags(ref mut form) = model.dialog {
+                if let Some(color) = input
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|idx| domain::TagColor::iter().nth(idx).copied())
+                {
+                    form.color = color;
+                }
+            }
+        }
+        Msg::AddTag => {
+            if let Dialog::EditTags(ref mut form) = model.dialog {
+                if let Some(tag) = form.new_tag() {
+                    domain::add_tag(&mut form.tags, tag);
+                    form.name = String::new();
+                }
+            }
+        }
+        Msg::AssignTag(tag) => {
+            if let Dialog::EditTags(ref mut form) = model.dialog {
+                domain::add_tag(&mut form.tags, tag);
+            }
+        }
+        Msg::UnassignTag(name) => {
+            if let Dialog::EditTags(ref mut form) = model.dialog {
+                form.tags.retain(|t| t.name != name);
+            }
+        }
+
+        Msg::ToggleTagFilter(name) => {
+            if model.tag_filter.as_ref() == Some(&name) {
+                model.tag_filter = None;
+            } else {
+                model.tag_filter = Some(name);
+            }
+        }
         Msg::ShowArchive => {
             model.archive_visible = true;
         }
@@ -167,27 +329,77 @@ pub fn update(
                         form.name.parsed.clone(),
                         None,
                         None,
+                        None,
+                        None,
                     ));
                 }
-                Dialog::Hidden | Dialog::DeleteRoutine(_) => {
+                Dialog::Hidden
+                | Dialog::DeleteRoutine(_)
+                | Dialog::ImportRoutine(_)
+                | Dialog::EditTags(_)
+                | Dialog::DeleteTag(_) => {
                     panic!();
                 }
             };
         }
+        Msg::ImportRoutine => {
+            if let Dialog::ImportRoutine(ref form) = model.dialog {
+                if let (Ok(export), Some(name)) = (&form.export, &form.name.parsed) {
+                    model.loading = true;
+                    orders.notify(data::Msg::ImportRoutine(
+                        name.clone(),
+                        export.clone(),
+                        form.create_missing_exercises,
+                    ));
+                }
+            }
+        }
+        Msg::SaveTags => {
+            if let Dialog::EditTags(ref form) = model.dialog {
+                model.loading = true;
+                orders.notify(data::Msg::ModifyRoutine(
+                    form.id,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(form.tags.clone()),
+                ));
+            }
+        }
         Msg::ChangeArchived(id, archived) => {
             model.loading = true;
-            orders.notify(data::Msg::ModifyRoutine(id, None, Some(archived), None));
+            orders.notify(data::Msg::ModifyRoutine(
+                id,
+                None,
+                Some(archived),
+                None,
+                None,
+                None,
+            ));
         }
         Msg::DeleteRoutine(id) => {
             model.loading = true;
             orders.notify(data::Msg::DeleteRoutine(id));
         }
+        Msg::DeleteTag(name) => {
+            model.loading = true;
+            orders.notify(data::Msg::DeleteTag(name));
+        }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadRoutines);
+        }
         Msg::DataEvent(event) => {
             model.loading = false;
             match event {
                 data::Event::RoutineCreatedOk
                 | data::Event::RoutineModifiedOk
-                | data::Event::RoutineDeletedOk => {
+                | data::Event::RoutineDeletedOk
+                | data::Event::RoutineImportedOk(_) => {
+                    orders.skip().send_msg(Msg::CloseRoutineDialog);
+                }
+                data::Event::TagDeletedOk => {
+                    model.tag_filter = None;
                     orders.skip().send_msg(Msg::CloseRoutineDialog);
                 }
                 _ => {}
@@ -201,20 +413,44 @@ pub fn update(
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.routines.is_empty() && data_model.loading_routines {
-        common::view_page_loading()
+    if let Some(node) = common::view_resource_unavailable(&data_model.routines, |_| Msg::Retry) {
+        node
     } else {
         div![
-            view_routine_dialog(
-                &model.dialog,
-                &data_model.routines_sorted_by_last_use(|r: &domain::Routine| !r.archived),
-                model.loading
-            ),
+            match &model.dialog {
+                Dialog::ImportRoutine(form) => {
+                    view_import_dialog(form, &data_model.exercises, model.loading)
+                }
+                Dialog::EditTags(form) => view_tags_dialog(form, data_model, model.loading),
+                Dialog::DeleteTag(name) => view_delete_tag_dialog(name, data_model, model.loading),
+                _ => view_routine_dialog(
+                    &model.dialog,
+                    &data_model.routines_sorted_by_last_use(|r: &domain::Routine| !r.archived),
+                    model.loading,
+                ),
+            },
             div![
                 C!["px-4"],
                 common::view_search_box(&model.search_term, Msg::SearchTermChanged)
             ],
-            view_table(&model.search_term, model.archive_visible, data_model),
+            view_tag_filter(model.tag_filter.as_ref(), data_model),
+            view_table(
+                &model.search_term,
+                model.tag_filter.as_ref(),
+                model.archive_visible,
+                data_model
+            ),
+            div![
+                C!["has-text-centered"],
+                C!["mt-4"],
+                button![
+                    C!["button"],
+                    C!["is-small"],
+                    ev(Ev::Click, |_| Msg::ShowImportRoutineDialog),
+                    span![C!["icon"], C!["is-small"], i![C!["fas fa-file-import"]]],
+                    span!["Import routine"]
+                ]
+            ],
             common::view_fab("plus", |_| Msg::ShowAddRoutineDialog),
         ]
     }
@@ -243,7 +479,7 @@ fn view_routine_dialog(dialog: &Dialog, routines: &[domain::Routine], loading: b
                 loading,
             );
         }
-        Dialog::Hidden => {
+        Dialog::Hidden | Dialog::ImportRoutine(_) | Dialog::EditTags(_) | Dialog::DeleteTag(_) => {
             return empty![];
         }
     }
@@ -337,13 +573,383 @@ fn view_routine_dialog(dialog: &Dialog, routines: &[domain::Routine], loading: b
     )
 }
 
-fn view_table(search_term: &str, archive_visible: bool, data_model: &data::Model) -> Node<Msg> {
-    let routines = data_model.routines_sorted_by_last_use(|r: &domain::Routine| {
-        !r.archived && r.name.to_lowercase().contains(&search_term.to_lowercase())
-    });
-    let archived_routines = data_model.routines_sorted_by_last_use(|r: &domain::Routine| {
-        r.archived && r.name.to_lowercase().contains(&search_term.to_lowercase())
-    });
+fn view_import_dialog(
+    form: &ImportForm,
+    exercises: &BTreeMap<u32, domain::Exercise>,
+    loading: bool,
+) -> Node<Msg> {
+    let import_disabled = loading || form.export.is_err() || not(form.name.valid());
+    let invalid_snippet = form.export.as_ref().is_err_and(|err| not(err.is_empty()));
+    common::view_dialog(
+        "primary",
+        "Import routine",
+        nodes![
+            div![
+                C!["field"],
+                label![C!["label"], "Routine"],
+                div![
+                    C!["control"],
+                    input_ev(Ev::Input, Msg::SnippetChanged),
+                    textarea![
+                        C!["textarea"],
+                        C!["is-family-monospace"],
+                        C![IF![invalid_snippet => "is-danger"]],
+                        attrs! {
+                            At::Placeholder => "Paste an exported routine",
+                            At::Rows => 4,
+                        },
+                        &form.snippet,
+                    ],
+                ],
+                match &form.export {
+                    Err(err) if not(err.is_empty()) => p![C!["help"], C!["is-danger"], err],
+                    _ => empty![],
+                },
+            ],
+            if let Ok(export) = &form.export {
+                let plan = export.plan(exercises);
+                nodes![
+                    div![
+                        C!["field"],
+                        label![C!["label"], "Name"],
+                        div![
+                            C!["control"],
+                            input_ev(Ev::Input, Msg::NameChanged),
+                            input![
+                                C!["input"],
+                                C![IF![not(form.name.valid()) => "is-danger"]],
+                                attrs! {
+                                    At::Type => "text",
+                                    At::Value => form.name.input,
+                                }
+                            ],
+                        ]
+                    ],
+                    IF![not(export.exercises.is_empty()) => div![
+                        C!["field"],
+                        label![C!["label"], "Exercises"],
+                        table![
+                            C!["table"],
+                            C!["is-fullwidth"],
+                            C!["is-narrow"],
+                            tbody![export.exercises.iter().map(|e| {
+                                let (status, color) = match plan.matched.get(&e.name) {
+                                    Some(id) => (
+                                        format!("Use {}", exercises[id].name),
+                                        "is-success",
+                                    ),
+                                    None if form.create_missing_exercises => {
+                                        (String::from("Create"), "is-info")
+                                    }
+                                    None => (String::from("Omit"), "is-warning"),
+                                };
+                                tr![
+                                    td![&e.name],
+                                    td![
+                                        C!["has-text-right"],
+                                        span![C!["tag"], C![color], status]
+                                    ],
+                                ]
+                            })],
+                        ],
+                        IF![not(plan.missing.is_empty()) => div![
+                            C!["control"],
+                            label![
+                                C!["checkbox"],
+                                input![
+                                    C!["mr-2"],
+                                    attrs! {
+                                        At::Type => "checkbox",
+                                        At::Checked => form.create_missing_exercises.as_at_value(),
+                                    },
+                                    ev(Ev::Click, |_| Msg::ToggleCreateMissingExercises),
+                                ],
+                                "Create missing exercises",
+                            ],
+                            IF![not(form.create_missing_exercises) => p![
+                                C!["help"],
+                                "Sets of missing exercises will be omitted."
+                            ]],
+                        ]],
+                    ]],
+                ]
+            } else {
+                nodes![]
+            },
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                C!["mt-5"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        ev(Ev::Click, |_| Msg::CloseRoutineDialog),
+                        "Cancel",
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-primary"],
+                        C![IF![loading => "is-loading"]],
+                        attrs! {
+                            At::Disabled => import_disabled.as_at_value(),
+                        },
+                        ev(Ev::Click, |_| Msg::ImportRoutine),
+                        "Import",
+                    ]
+                ],
+            ],
+        ],
+        &ev(Ev::Click, |_| Msg::CloseRoutineDialog),
+    )
+}
+
+fn view_tags_dialog(form: &TagsForm, data_model: &data::Model, loading: bool) -> Node<Msg> {
+    let new_tag = form.new_tag();
+    let invalid_name = new_tag.is_none() && not(form.name.trim().is_empty());
+    let unassigned_tags = domain::tags(data_model.routines.values())
+        .into_iter()
+        .filter(|t| form.tags.iter().all(|assigned| assigned.name != t.name))
+        .collect::<Vec<_>>();
+    common::view_dialog(
+        "primary",
+        "Edit tags",
+        nodes![
+            div![
+                C!["field"],
+                label![C!["label"], "Tags"],
+                if form.tags.is_empty() {
+                    p![C!["help"], "The routine has no tags."]
+                } else {
+                    div![
+                        C!["tags"],
+                        form.tags.iter().map(|t| {
+                            let name = t.name.clone();
+                            span![
+                                C!["tag"],
+                                C![common::tag_color_class(t.color)],
+                                &t.name,
+                                button![
+                                    C!["delete"],
+                                    C!["is-small"],
+                                    ev(Ev::Click, move |_| Msg::UnassignTag(name)),
+                                ]
+                            ]
+                        })
+                    ]
+                },
+            ],
+            IF![not(unassigned_tags.is_empty()) => div![
+                C!["field"],
+                label![C!["label"], "Existing tags"],
+                div![
+                    C!["tags"],
+                    unassigned_tags.into_iter().map(|t| {
+                        let color = common::tag_color_class(t.color);
+                        let name = t.name.clone();
+                        a![
+                            C!["tag"],
+                            C![color],
+                            C!["is-light"],
+                            ev(Ev::Click, move |_| Msg::AssignTag(t)),
+                            span![C!["icon"], C!["is-small"], i![C!["fas fa-plus"]]],
+                            span![name],
+                        ]
+                    })
+                ],
+            ]],
+            div![
+                C!["field"],
+                label![C!["label"], "New tag"],
+                div![
+                    C!["field"],
+                    C!["has-addons"],
+                    div![
+                        C!["control"],
+                        C!["is-expanded"],
+                        input_ev(Ev::Input, Msg::TagNameChanged),
+                        keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+                            IF!(keyboard_event.key_code() == common::ENTER_KEY => Msg::AddTag)
+                        }),
+                        input![
+                            C!["input"],
+                            C![IF![invalid_name => "is-danger"]],
+                            attrs! {
+                                At::Type => "text",
+                                At::Value => form.name,
+                            }
+                        ],
+                    ],
+                    div![
+                        C!["control"],
+                        input_ev(Ev::Change, Msg::TagColorChanged),
+                        div![
+                            C!["select"],
+                            select![domain::TagColor::iter().enumerate().map(|(idx, color)| {
+                                option![
+                                    color.name(),
+                                    attrs![
+                                        At::Value => idx,
+                                        At::Selected => (*color == form.color).as_at_value(),
+                                    ]
+                                ]
+                            })],
+                        ],
+                    ],
+                    div![
+                        C!["control"],
+                        button![
+                            C!["button"],
+                            C![common::tag_color_class(form.color)],
+                            attrs! {
+                                At::Disabled => new_tag.is_none().as_at_value(),
+                            },
+                            ev(Ev::Click, |_| Msg::AddTag),
+                            span![C!["icon"], i![C!["fas fa-plus"]]],
+                        ]
+                    ],
+                ],
+            ],
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                C!["mt-5"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        ev(Ev::Click, |_| Msg::CloseRoutineDialog),
+                        "Cancel",
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-primary"],
+                        C![IF![loading => "is-loading"]],
+                        attrs! {
+                            At::Disabled => loading.as_at_value(),
+                        },
+                        ev(Ev::Click, |_| Msg::SaveTags),
+                        "Save",
+                    ]
+                ],
+            ],
+        ],
+        &ev(Ev::Click, |_| Msg::CloseRoutineDialog),
+    )
+}
+
+fn view_delete_tag_dialog(name: &str, data_model: &data::Model, loading: bool) -> Node<Msg> {
+    let count = domain::remove_tag(data_model.routines.values(), name).len();
+    let tag_name = name.to_string();
+    common::view_dialog(
+        "danger",
+        "Delete the tag?",
+        nodes![
+            div![
+                C!["block"],
+                format!(
+                    "The tag \"{name}\" will be removed from {count} routine{}, \
+                     including archived routines.",
+                    if count == 1 { "" } else { "s" }
+                ),
+            ],
+            div![
+                C!["field"],
+                C!["is-grouped"],
+                C!["is-grouped-centered"],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-light"],
+                        C!["is-soft"],
+                        ev(Ev::Click, |_| Msg::CloseRoutineDialog),
+                        "No"
+                    ]
+                ],
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-danger"],
+                        C![IF![loading => "is-loading"]],
+                        ev(Ev::Click, move |_| Msg::DeleteTag(tag_name)),
+                        "Yes, delete tag",
+                    ]
+                ],
+            ],
+        ],
+        &ev(Ev::Click, |_| Msg::CloseRoutineDialog),
+    )
+}
+
+fn view_tag_filter(tag_filter: Option<&String>, data_model: &data::Model) -> Node<Msg> {
+    let tags = domain::tags(data_model.routines.values());
+    if tags.is_empty() {
+        return empty![];
+    }
+    div![
+        C!["field"],
+        C!["is-grouped"],
+        C!["is-grouped-multiline"],
+        C!["is-justify-content-center"],
+        C!["px-4"],
+        C!["mt-3"],
+        tags.into_iter().map(|t| {
+            let selected = tag_filter == Some(&t.name);
+            let name = t.name.clone();
+            div![
+                C!["control"],
+                div![
+                    C!["tags"],
+                    C!["has-addons"],
+                    a![
+                        C!["tag"],
+                        C![common::tag_color_class(t.color)],
+                        C![IF![not(selected) => "is-light"]],
+                        ev(Ev::Click, move |_| Msg::ToggleTagFilter(name)),
+                        &t.name,
+                    ],
+                    IF![selected => {
+                        let name = t.name.clone();
+                        a![
+                            C!["tag"],
+                            C!["is-delete"],
+                            ev(Ev::Click, move |_| Msg::ShowDeleteTagDialog(name)),
+                        ]
+                    }],
+                ]
+            ]
+        })
+    ]
+}
+
+fn view_table(
+    search_term: &str,
+    tag_filter: Option<&String>,
+    archive_visible: bool,
+    data_model: &data::Model,
+) -> Node<Msg> {
+    let matches = |r: &domain::Routine| {
+        r.name.to_lowercase().contains(&search_term.to_lowercase())
+            && tag_filter.into_iter().all(|name| r.has_tag(name))
+    };
+    let routines =
+        data_model.routines_sorted_by_last_use(|r: &domain::Routine| !r.archived && matches(r));
+    let archived_routines =
+        data_model.routines_sorted_by_last_use(|r: &domain::Routine| r.archived && matches(r));
     div![
         C!["table-container"],
         C!["mt-4"],
@@ -351,12 +957,9 @@ fn view_table(search_term: &str, archive_visible: bool, data_model: &data::Model
             C!["table"],
             C!["is-fullwidth"],
             C!["is-hoverable"],
-            tbody![routines.iter().map(|r| view_table_row(
-                r.id,
-                &r.name,
-                r.archived,
-                &data_model.base_url
-            ))],
+            tbody![routines
+                .iter()
+                .map(|r| view_table_row(r, &data_model.base_url))],
         ],
         IF![!archived_routines.is_empty() =>
             if archive_visible {
@@ -368,9 +971,7 @@ fn view_table(search_term: &str, archive_visible: bool, data_model: &data::Model
                         C!["is-hoverable"],
                         tbody![archived_routines
                             .iter()
-                            .map(|r|
-                                view_table_row(r.id, &r.name, r.archived, &data_model.base_url)
-                            )
+                            .map(|r| view_table_row(r, &data_model.base_url))
                         ],
                     ]
                 ]
@@ -396,23 +997,35 @@ fn view_table(search_term: &str, archive_visible: bool, data_model: &data::Model
     ]
 }
 
-fn view_table_row(id: u32, name: &str, archived: bool, base_url: &Url) -> Node<Msg> {
+fn view_table_row(routine: &domain::Routine, base_url: &Url) -> Node<Msg> {
+    let id = routine.id;
     tr![td![
         C!["is-flex"],
         C!["is-justify-content-space-between"],
-        a![
-            attrs! {
-                At::Href => {
-                    crate::Urls::new(base_url)
-                        .routine()
-                        .add_hash_path_part(id.to_string())
-                }
-            },
-            name,
+        div![
+            a![
+                C!["mr-2"],
+                attrs! {
+                    At::Href => {
+                        crate::Urls::new(base_url)
+                            .routine()
+                            .add_hash_path_part(id.to_string())
+                    }
+                },
+                &routine.name,
+            ],
+            routine.tags.iter().map(|t| {
+                span![
+                    C!["tag"],
+                    C!["mr-1"],
+                    C![common::tag_color_class(t.color)],
+                    &t.name
+                ]
+            })
         ],
         p![
             C!["is-flex is-flex-wrap-nowrap"],
-            if archived {
+            if routine.archived {
                 a![
                     C!["icon"],
                     C!["mr-1"],
@@ -427,6 +1040,12 @@ fn view_table_row(id: u32, name: &str, archived: bool, base_url: &Url) -> Node<M
                     i![C!["fas fa-box-archive"]]
                 ]
             },
+            a![
+                C!["icon"],
+                C!["mx-1"],
+                ev(Ev::Click, move |_| Msg::ShowEditTagsDialog(id)),
+                i![C!["fas fa-tags"]]
+            ],
             a![
                 C!["icon"],
                 C!["mx-1"],
@@ -0,0 +1,221 @@
+use seed::{prelude::*, *};
+use valens_domain as domain;
+use valens_web_app as web_app;
+
+use crate::{common, data};
+
+// ------ ------
+//     Init
+// ------ ------
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn init(_url: Url, orders: &mut impl Orders<Msg>, navbar: &mut crate::Navbar) -> Model {
+    navbar.title = String::from("Statistics");
+
+    // The statistics are based on the complete history
+    orders
+        .subscribe(Msg::DataEvent)
+        .notify(data::Msg::ReadOlderTrainingSessions(None))
+        .after_next_render(|_| Msg::Compute);
+
+    Model { summary: None }
+}
+
+// ------ ------
+//     Model
+// ------ ------
+
+pub struct Model {
+    /// Summary of all data, which is computed after the loading indicator has been rendered, as
+    /// the calculation requires iterating over the complete history.
+    summary: Option<Summary>,
+}
+
+struct Summary {
+    key: SummaryKey,
+    stats: domain::summary::Summary,
+}
+
+/// Change counters of training sessions, exercises and body weight and weekly session target.
+type SummaryKey = (u32, u32, u32, u32);
+
+fn key(data_model: &data::Model) -> SummaryKey {
+    (
+        data_model.changes.training_sessions,
+        data_model.changes.exercises,
+        data_model.changes.body_weight,
+        data_model.settings.weekly_session_target,
+    )
+}
+
+// ------ ------
+//    Update
+// ------ ------
+
+pub enum Msg {
+    Compute,
+    DataEvent(data::Event),
+    Retry,
+}
+
+pub fn update(
+    msg: Msg,
+    model: &mut Model,
+    data_model: &data::Model,
+    orders: &mut impl Orders<Msg>,
+) {
+    match msg {
+        Msg::Compute => {
+            model.summary = Some(Summary {
+                key: key(data_model),
+                stats: domain::summary::compute(
+                    &domain::summary::UserData {
+                        training_sessions: &data_model.training_sessions,
+                        exercises: &data_model.exercises,
+                        body_weight: &data_model.body_weight,
+                    },
+                    data_model.settings.weekly_session_target,
+                    &web_app::clock::BrowserClock,
+                ),
+            });
+        }
+        Msg::DataEvent(_) => {
+            if model
+                .summary
+                .as_ref()
+                .is_some_and(|summary| summary.key != key(data_model))
+            {
+                model.summary = None;
+                orders.after_next_render(|_| Msg::Compute);
+            }
+        }
+        Msg::Retry => {
+            if data_model.exercises.error().is_some() {
+                orders.notify(data::Msg::ReadExercises);
+            }
+            if data_model.training_sessions.error().is_some() {
+                orders.notify(data::Msg::ReadTrainingSessions);
+            }
+            if data_model.body_weight.error().is_some() {
+                orders.notify(data::Msg::ReadBodyWeight);
+            }
+        }
+    }
+}
+
+// ------ ------
+//     View
+// ------ ------
+
+pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    if let Some(node) = common::view_resource_unavailable(&data_model.exercises, |_| Msg::Retry)
+        .or_else(|| {
+            common::view_resource_unavailable(&data_model.training_sessions, |_| Msg::Retry)
+        })
+        .or_else(|| common::view_resource_unavailable(&data_model.body_weight, |_| Msg::Retry))
+    {
+        return node;
+    }
+    let Some(summary) = &model.summary else {
+        return common::view_page_loading();
+    };
+    if data_model.training_sessions.is_loading() {
+        return common::view_page_loading();
+    }
+
+    let stats = &summary.stats;
+    let separator = data_model.settings.decimal_separator;
+    #[allow(clippy::cast_precision_loss)]
+    let volume_load = stats.volume_load as f32 / 1000.0;
+    let date = |date: Option<chrono::NaiveDate>| date.map_or(String::from("-"), |d| d.to_string());
+
+    div![
+        C!["grid"],
+        C!["is-col-min-8"],
+        C!["mx-3"],
+        C!["mb-5"],
+        C!["has-text-centered"],
+        [
+            ("First training session", date(stats.first_training_session)),
+            ("Last training session", date(stats.last_training_session)),
+            ("Training sessions", stats.training_sessions.to_string()),
+            ("Sets", stats.sets.to_string()),
+            (
+                "Volume load",
+                web_app::format::format_weight(
+                    volume_load,
+                    "t",
+                    web_app::format::PRECISION_WEIGHT,
+                    separator
+                )
+            ),
+            (
+                "Most trained exercise",
+                stats
+                    .most_trained_exercise
+                    .map_or(String::from("-"), |(exercise_id, sets)| {
+                        format!(
+                            "{} ({sets} sets)",
+                            data_model
+                                .exercises
+                                .get(&exercise_id)
+                                .map_or("Unknown exercise", |e| e.name.as_str())
+                        )
+                    })
+            ),
+            (
+                "Most trained muscle",
+                stats
+                    .most_trained_muscle
+                    .and_then(|(muscle_id, sets)| {
+                        domain::Muscle::from_repr(muscle_id).map(|muscle| {
+                            format!(
+                                "{} ({} hard sets)",
+                                muscle.name(),
+                                web_app::format::format_number(sets, 1, separator)
+                            )
+                        })
+                    })
+                    .unwrap_or_else(|| String::from("-"))
+            ),
+            (
+                "Longest training streak",
+                format!(
+                    "{} week{}",
+                    stats.longest_streak,
+                    if stats.longest_streak == 1 { "" } else { "s" }
+                )
+            ),
+            ("Routines used", stats.routines.to_string()),
+            (
+                "Body weight range",
+                stats
+                    .body_weight_range
+                    .map_or(String::from("-"), |(min, max)| {
+                        format!(
+                            "{} – {}",
+                            web_app::format::format_number(
+                                min,
+                                web_app::format::PRECISION_WEIGHT,
+                                separator
+                            ),
+                            web_app::format::format_weight(
+                                max,
+                                "kg",
+                                web_app::format::PRECISION_WEIGHT,
+                                separator
+                            )
+                        )
+                    })
+            ),
+        ]
+        .into_iter()
+        .map(|(label, value)| {
+            div![
+                C!["cell"],
+                p![C!["is-size-7"], C!["has-text-grey"], label],
+                p![C!["has-text-weight-bold"], value],
+            ]
+        })
+    ]
+}
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
-use chrono::prelude::*;
+use chrono::{prelude::*, Duration};
+use plotters::style::{Palette, Palette99};
 use seed::{prelude::*, *};
 use valens_domain as domain;
 use valens_web_app as web_app;
@@ -23,19 +24,34 @@ pub fn init(
 
     orders.subscribe(Msg::DataEvent);
 
+    if data_model.settings.training_view == web_app::TrainingView::Calendar {
+        orders.notify(data::Msg::ReadOlderTrainingSessions(None));
+    }
+
     navbar.title = String::from("Training");
 
+    let interval = domain::init_interval(
+        &data_model
+            .training_sessions
+            .values()
+            .map(|t| t.date)
+            .collect::<Vec<NaiveDate>>(),
+        domain::DefaultInterval::_1M,
+        data_model.settings.initial_interval(),
+        &web_app::clock::BrowserClock,
+    );
+
     Model {
-        interval: domain::init_interval(
-            &data_model
-                .training_sessions
-                .values()
-                .map(|t| t.date)
-                .collect::<Vec<NaiveDate>>(),
-            domain::DefaultInterval::_1M,
-        ),
+        dashboard: Dashboard::new(&interval, data_model),
+        interval,
+        month: web_app::calendar::first_day_of_month(web_app::clock::today()),
         dialog: Dialog::Hidden,
         loading: false,
+        search_term: String::new(),
+        search_index: search_index(data_model),
+        search_index_key: search_index_key(data_model),
+        search_hits: vec![],
+        search_handle: None,
     }
 }
 
@@ -43,10 +59,24 @@ pub fn init(
 //     Model
 // ------ ------
 
+const SEARCH_DELAY: u32 = 300;
+/// Number of days before the displayed interval that are required to determine the training load.
+#[allow(clippy::cast_possible_wrap)]
+const LOAD_HISTORY_DAYS: i64 = (domain::TrainingStats::SHORT_TERM_LOAD_WINDOW
+    + domain::TrainingStats::LONG_TERM_LOAD_WINDOW) as i64;
+
 pub struct Model {
     interval: domain::Interval,
+    dashboard: Dashboard,
+    month: NaiveDate,
     dialog: Dialog,
     loading: bool,
+    search_term: String,
+    search_index: domain::SearchIndex,
+    /// Change counters of the data from which the search index was created.
+    search_index_key: (u32, u32, u32),
+    search_hits: Vec<domain::SearchHit>,
+    search_handle: Option<CmdHandle>,
 }
 
 enum Dialog {
@@ -60,6 +90,36 @@ struct Form {
     routine_id: (String, Option<u32>),
 }
 
+/// Statistics of the training sessions in the displayed interval, which are cached to prevent
+/// their recalculation on changes of unrelated state.
+struct Dashboard {
+    /// Change counters of the training sessions and exercises, displayed interval and current
+    /// date.
+    key: DashboardKey,
+    stats: domain::dashboard::Dashboard,
+}
+
+type DashboardKey = (u32, u32, NaiveDate, NaiveDate, NaiveDate);
+
+impl Dashboard {
+    fn new(interval: &domain::Interval, data_model: &data::Model) -> Self {
+        Self {
+            key: Self::key(interval, data_model),
+            stats: data_model.dashboard(interval),
+        }
+    }
+
+    fn key(interval: &domain::Interval, data_model: &data::Model) -> DashboardKey {
+        (
+            data_model.changes.training_sessions,
+            data_model.changes.exercises,
+            interval.first,
+            interval.last,
+            web_app::clock::today(),
+        )
+    }
+}
+
 // ------ ------
 //    Update
 // ------ ------
@@ -73,10 +133,19 @@ pub enum Msg {
     RoutineChanged(String),
 
     SaveTrainingSession,
+    StartRoutine(u32),
     DeleteTrainingSession(u32),
+    Retry,
     DataEvent(data::Event),
 
-    ChangeInterval(NaiveDate, NaiveDate),
+    ChangeInterval(NaiveDate, NaiveDate, Option<domain::DefaultInterval>),
+    ToggleDataTables,
+
+    SetTrainingView(web_app::TrainingView),
+    ShowMonth(NaiveDate),
+
+    SearchTermChanged(String),
+    Search,
 }
 
 pub fn update(
@@ -87,7 +156,7 @@ pub fn update(
 ) {
     match msg {
         Msg::ShowAddTrainingSessionDialog => {
-            let local = Local::now().date_naive();
+            let local = web_app::clock::today();
             model.dialog = Dialog::AddTrainingSession(Form {
                 date: (local.to_string(), Some(local)),
                 routine_id: (String::new(), None),
@@ -140,43 +209,38 @@ pub fn update(
                     let Some(date) = form.date.1 else {
                         return;
                     };
-                    if let Some(routine_id) = form.routine_id.1 {
-                        let Some(routine) = data_model.routines.get(&routine_id) else {
-                            return;
-                        };
-                        let sets = routine
-                            .sections
-                            .iter()
-                            .flat_map(to_training_session_elements)
-                            .collect::<Vec<domain::TrainingSessionElement>>();
-                        orders.notify(data::Msg::CreateTrainingSession(
-                            Some(routine_id),
-                            date,
-                            String::new(),
-                            sets,
-                        ));
-                    } else {
-                        orders.notify(data::Msg::CreateTrainingSession(
-                            None,
-                            date,
-                            String::new(),
-                            vec![],
-                        ));
-                    }
+                    create_training_session(form.routine_id.1, date, data_model, orders);
                 }
                 Dialog::Hidden | Dialog::DeleteTrainingSession(_) => {
                     panic!();
                 }
             };
         }
+        Msg::StartRoutine(routine_id) => {
+            model.loading = true;
+            create_training_session(
+                Some(routine_id),
+                web_app::clock::today(),
+                data_model,
+                orders,
+            );
+        }
         Msg::DeleteTrainingSession(id) => {
             model.loading = true;
             orders.notify(data::Msg::DeleteTrainingSession(id));
         }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadTrainingSessions);
+        }
         Msg::DataEvent(event) => {
             model.loading = false;
+            if model.search_index_key != search_index_key(data_model) {
+                model.search_index = search_index(data_model);
+                model.search_index_key = search_index_key(data_model);
+                model.search_hits = model.search_index.search(&model.search_term);
+            }
             match event {
-                data::Event::DataChanged => {
+                data::Event::TrainingSessionsChanged(_) => {
                     model.interval = domain::init_interval(
                         &data_model
                             .training_sessions
@@ -184,6 +248,8 @@ pub fn update(
                             .map(|t| t.date)
                             .collect::<Vec<NaiveDate>>(),
                         domain::DefaultInterval::_1M,
+                        data_model.settings.initial_interval(),
+                        &web_app::clock::BrowserClock,
                     );
                 }
                 data::Event::TrainingSessionCreatedOk => {
@@ -203,57 +269,118 @@ pub fn update(
                 }
                 _ => {}
             };
+            update_dashboard(model, data_model);
         }
 
-        Msg::ChangeInterval(first, last) => {
+        Msg::ChangeInterval(first, last, selected_interval) => {
             model.interval.first = first;
             model.interval.last = last;
+            update_dashboard(model, data_model);
+            if let Some(selected_interval) = selected_interval {
+                orders.notify(data::Msg::SetInterval(selected_interval));
+            }
+            orders.notify(data::Msg::ReadOlderTrainingSessions(
+                if selected_interval == Some(domain::DefaultInterval::All) {
+                    None
+                } else {
+                    Some(first - Duration::days(LOAD_HISTORY_DAYS))
+                },
+            ));
+        }
+        Msg::ToggleDataTables => {
+            orders.notify(data::Msg::ToggleDataTables(web_app::chart::Page::Training));
         }
+
+        Msg::SetTrainingView(training_view) => {
+            orders.notify(data::Msg::SetTrainingView(training_view));
+            if training_view == web_app::TrainingView::Calendar {
+                orders.notify(data::Msg::ReadOlderTrainingSessions(None));
+            }
+        }
+        Msg::ShowMonth(month) => {
+            model.month = month;
+        }
+
+        Msg::SearchTermChanged(search_term) => {
+            if model.search_term.trim().is_empty() && !search_term.trim().is_empty() {
+                orders.notify(data::Msg::ReadOlderTrainingSessions(None));
+            }
+            model.search_term = search_term;
+            model.search_handle =
+                Some(orders.perform_cmd_with_handle(cmds::timeout(SEARCH_DELAY, || Msg::Search)));
+        }
+        Msg::Search => {
+            model.search_handle = None;
+            model.search_hits = model.search_index.search(&model.search_term);
+        }
+    }
+}
+
+fn create_training_session(
+    routine_id: Option<u32>,
+    date: NaiveDate,
+    data_model: &data::Model,
+    orders: &mut impl Orders<Msg>,
+) {
+    if let Some(routine_id) = routine_id {
+        let Some(routine) = data_model.routines.get(&routine_id) else {
+            return;
+        };
+        let sets = domain::expand_routine_parts(&routine.sections, &|exercise_id, percent_1rm| {
+            data_model.target_weight(exercise_id, percent_1rm)
+        })
+        .into_iter()
+        .map(|e| e.element)
+        .collect::<Vec<domain::TrainingSessionElement>>();
+        let sets = domain::split_unilateral_sets(sets, &data_model.exercises);
+        orders.notify(data::Msg::CreateTrainingSession(
+            Some(routine_id),
+            date,
+            String::new(),
+            sets,
+        ));
+    } else {
+        orders.notify(data::Msg::CreateTrainingSession(
+            None,
+            date,
+            String::new(),
+            vec![],
+        ));
     }
 }
 
+fn search_index(data_model: &data::Model) -> domain::SearchIndex {
+    domain::SearchIndex::new(
+        data_model.training_sessions.values(),
+        data_model.routines.values(),
+        data_model.exercises.values(),
+    )
+}
+
+fn update_dashboard(model: &mut Model, data_model: &data::Model) {
+    if model.dashboard.key != Dashboard::key(&model.interval, data_model) {
+        model.dashboard = Dashboard::new(&model.interval, data_model);
+    }
+}
+
+fn search_index_key(data_model: &data::Model) -> (u32, u32, u32) {
+    (
+        data_model.changes.training_sessions,
+        data_model.changes.routines,
+        data_model.changes.exercises,
+    )
+}
+
 // ------ ------
 //     View
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.training_sessions.is_empty() && data_model.loading_training_sessions {
-        common::view_page_loading()
+    if let Some(node) =
+        common::view_resource_unavailable(&data_model.training_sessions, |_| Msg::Retry)
+    {
+        node
     } else {
-        let short_term_load = data_model
-            .training_stats
-            .short_term_load
-            .iter()
-            .filter(|(date, _)| *date >= model.interval.first && *date <= model.interval.last)
-            .copied()
-            .collect::<Vec<_>>();
-        let long_term_load = data_model
-            .training_stats
-            .long_term_load
-            .iter()
-            .filter(|(date, _)| *date >= model.interval.first && *date <= model.interval.last)
-            .copied()
-            .collect::<Vec<_>>();
-        #[allow(clippy::cast_precision_loss)]
-        let total_7day_set_volume = domain::centered_moving_total(
-            &data_model
-                .training_sessions
-                .values()
-                .map(|s| (s.date, s.set_volume() as f32))
-                .collect::<Vec<_>>(),
-            &model.interval,
-            3,
-        );
-
-        let average_7day_rpe = domain::centered_moving_average(
-            &data_model
-                .training_sessions
-                .values()
-                .filter_map(|s| s.avg_rpe().map(|v| (s.date, v)))
-                .collect::<Vec<_>>(),
-            &model.interval,
-            3,
-        );
         let mut training_sessions = data_model
             .training_sessions
             .values()
@@ -320,34 +447,345 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
                     ],
                 ]
             ],
+            view_load_recommendation(
+                model.dashboard.stats.load_recommendation,
+                data_model.settings.decimal_separator
+            ),
+            view_planned_routines(model, data_model),
+            view_search(model, data_model),
             common::view_interval_buttons(
                 &model.interval,
                 &training_sessions_interval,
                 Msg::ChangeInterval
             ),
             view_charts(
-                short_term_load,
-                &long_term_load,
-                total_7day_set_volume,
-                &average_7day_rpe,
+                model.dashboard.stats.short_term_load.clone(),
+                &model.dashboard.stats.long_term_load,
+                model.dashboard.stats.total_7day_set_volume.clone(),
+                &model.dashboard.stats.average_7day_rpe,
                 &model.interval,
-                data_model.theme(),
+                &data_model.chart_style(),
                 data_model.settings.show_rpe,
+                data_table(data_model),
             ),
-            view_calendar(&training_sessions, &model.interval),
-            view_table(
-                &training_sessions,
-                &data_model.routines,
-                &data_model.base_url,
-                Msg::ShowDeleteTrainingSessionDialog,
-                data_model.settings.show_rpe,
-                data_model.settings.show_tut,
-            ),
+            view_training_view_buttons(data_model.settings.training_view),
+            match data_model.settings.training_view {
+                web_app::TrainingView::List => nodes![
+                    view_calendar(&training_sessions, &model.interval),
+                    view_table(
+                        &training_sessions,
+                        &data_model.routines,
+                        &data_model.exercises,
+                        &data_model.base_url,
+                        Msg::ShowDeleteTrainingSessionDialog,
+                        data_model.settings.show_rpe,
+                        data_model.settings.show_tut,
+                        data_model.settings.decimal_separator,
+                    ),
+                ],
+                web_app::TrainingView::Calendar => nodes![view_month_calendar(model, data_model)],
+            },
             common::view_fab("plus", |_| Msg::ShowAddTrainingSessionDialog),
         ]
     }
 }
 
+fn view_planned_routines(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    let planned_routines = domain::planned_routines(
+        &data_model
+            .routines_sorted_by_last_use(|r: &domain::Routine| !r.archived)
+            .iter()
+            .collect::<Vec<_>>(),
+        &data_model.training_sessions.values().collect::<Vec<_>>(),
+        web_app::clock::today(),
+    );
+    if planned_routines.is_empty() {
+        return empty![];
+    }
+    div![
+        C!["mx-2"],
+        C!["mb-5"],
+        h1![C!["title"], C!["is-5"], "Next planned"],
+        planned_routines.iter().filter_map(|planned_routine| {
+            let routine = data_model.routines.get(&planned_routine.routine_id)?;
+            let routine_id = routine.id;
+            let (color, due) = match planned_routine.due {
+                domain::Due::Overdue => ("is-warning", "Overdue"),
+                domain::Due::Today => ("is-link", "Today"),
+                domain::Due::Tomorrow => ("is-light", "Tomorrow"),
+            };
+            Some(div![
+                C!["box"],
+                C!["is-flex"],
+                C!["is-align-items-center"],
+                C!["is-justify-content-space-between"],
+                C!["p-3"],
+                C!["mb-2"],
+                C![IF![planned_routine.due == domain::Due::Overdue => "has-background-warning-light"]],
+                div![
+                    a![
+                        C!["has-text-weight-bold"],
+                        attrs! {
+                            At::Href => crate::Urls::new(&data_model.base_url)
+                                .routine()
+                                .add_hash_path_part(routine_id.to_string()),
+                        },
+                        &routine.name,
+                    ],
+                    p![
+                        C!["is-size-7"],
+                        span![C!["tag"], C![color], C!["mr-2"], due],
+                        planned_routine.last_training_session.map_or_else(
+                            || String::from("Never trained"),
+                            |date| format!("Last trained on {date}")
+                        ),
+                    ]
+                ],
+                button![
+                    C!["button"],
+                    C!["is-link"],
+                    C!["is-small"],
+                    C![IF![model.loading => "is-loading"]],
+                    attrs![At::Disabled => model.loading.as_at_value()],
+                    ev(Ev::Click, move |_| Msg::StartRoutine(routine_id)),
+                    "Start",
+                ]
+            ])
+        }),
+    ]
+}
+
+fn view_load_recommendation(
+    load_recommendation: Option<domain::LoadRecommendation>,
+    separator: web_app::format::DecimalSeparator,
+) -> Node<Msg> {
+    let Some(load_recommendation) = load_recommendation else {
+        return empty![];
+    };
+    let (color, title, explanation) = match load_recommendation.recommendation {
+        domain::Recommendation::Deload => (
+            "is-warning",
+            "Deload recommended",
+            format!(
+                "The load ratio has been above {} for {} consecutive days (average {} in the past {} weeks).",
+                domain::TrainingStats::LOAD_RATIO_HIGH,
+                load_recommendation.days_above_high,
+                web_app::format::format_load(
+                    load_recommendation.average_load_ratio,
+                    web_app::format::PRECISION_LOAD,
+                    separator
+                ),
+                data::RECOMMENDATION_WEEKS,
+            ),
+        ),
+        domain::Recommendation::Increase => (
+            "is-info",
+            "Load can be increased",
+            format!(
+                "The average load ratio in the past {} weeks is {}, which is below {}.",
+                data::RECOMMENDATION_WEEKS,
+                web_app::format::format_load(
+                    load_recommendation.average_load_ratio,
+                    web_app::format::PRECISION_LOAD,
+                    separator
+                ),
+                domain::TrainingStats::LOAD_RATIO_LOW,
+            ),
+        ),
+        domain::Recommendation::Maintain => {
+            return empty![];
+        }
+    };
+    div![
+        C!["message"],
+        C![color],
+        C!["mx-2"],
+        C!["mb-5"],
+        div![
+            C!["message-body"],
+            p![C!["has-text-weight-bold"], title],
+            p![explanation]
+        ]
+    ]
+}
+
+fn view_search(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    let base_url = &data_model.base_url;
+    let loading_older_training_sessions = !data_model.all_training_sessions_loaded()
+        && data_model.training_sessions.error().is_none();
+    div![
+        C!["px-4"],
+        C!["mb-5"],
+        div![
+            C!["field"],
+            common::view_search_box(&model.search_term, Msg::SearchTermChanged),
+        ],
+        IF![!model.search_term.trim().is_empty() && model.search_handle.is_none() => {
+            if model.search_hits.is_empty() {
+                p![
+                    C!["has-text-centered"],
+                    C!["is-italic"],
+                    if loading_older_training_sessions {
+                        "Searching older training sessions …"
+                    } else {
+                        "No matches"
+                    }
+                ]
+            } else {
+                div![
+                    C!["table-container"],
+                    table![
+                        C!["table"],
+                        C!["is-fullwidth"],
+                        C!["is-hoverable"],
+                        tbody![model.search_hits.iter().map(|hit| {
+                            let (url, title, snippet) = match hit {
+                                domain::SearchHit::TrainingSession(id, date, snippet) => (
+                                    crate::Urls::new(base_url)
+                                        .training_session()
+                                        .add_hash_path_part(id.to_string()),
+                                    date.to_string(),
+                                    snippet,
+                                ),
+                                domain::SearchHit::Routine(id, snippet) => (
+                                    crate::Urls::new(base_url)
+                                        .routine()
+                                        .add_hash_path_part(id.to_string()),
+                                    data_model
+                                        .routines
+                                        .get(id)
+                                        .map(|r| r.name.clone())
+                                        .unwrap_or_default(),
+                                    snippet,
+                                ),
+                                domain::SearchHit::Exercise(id, snippet) => (
+                                    crate::Urls::new(base_url)
+                                        .exercise()
+                                        .add_hash_path_part(id.to_string()),
+                                    String::from("Exercise"),
+                                    snippet,
+                                ),
+                            };
+                            tr![
+                                td![a![
+                                    attrs! {
+                                        At::Href => url,
+                                    },
+                                    span![style! {St::WhiteSpace => "nowrap" }, title]
+                                ]],
+                                td![
+                                    snippet.prefix.as_str(),
+                                    mark![snippet.highlight.as_str()],
+                                    snippet.suffix.as_str(),
+                                ],
+                            ]
+                        })],
+                    ],
+                    IF![loading_older_training_sessions =>
+                        p![
+                            C!["has-text-centered"],
+                            C!["is-italic"],
+                            "Searching older training sessions …"
+                        ]
+                    ],
+                ]
+            }
+        }],
+    ]
+}
+
+fn view_training_view_buttons(training_view: web_app::TrainingView) -> Node<Msg> {
+    div![
+        C!["field"],
+        C!["has-addons"],
+        C!["is-justify-content-center"],
+        C!["mt-5"],
+        [
+            (web_app::TrainingView::List, "fa-list", "List"),
+            (
+                web_app::TrainingView::Calendar,
+                "fa-calendar-days",
+                "Calendar"
+            ),
+        ]
+        .into_iter()
+        .map(|(view, icon, name)| {
+            p![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-small"],
+                    C![IF![view == training_view => "is-link"]],
+                    ev(Ev::Click, move |_| Msg::SetTrainingView(view)),
+                    span![C!["icon"], i![C!["fas"], C![icon]]],
+                    span![name],
+                ]
+            ]
+        })
+    ]
+}
+
+fn view_month_calendar(model: &Model, data_model: &data::Model) -> Node<Msg> {
+    let today = web_app::clock::today();
+    let month_range = web_app::calendar::MonthRange::new(
+        data_model.training_sessions.values().map(|t| t.date).min(),
+        today,
+    );
+    let training_sessions = web_app::calendar::entries_per_day(
+        model.month,
+        data_model.training_sessions.values().map(|t| (t.date, t)),
+    );
+    common::view_month_calendar(
+        model.month,
+        month_range
+            .previous(model.month)
+            .map(|month| ev(Ev::Click, move |_| Msg::ShowMonth(month))),
+        month_range
+            .next(model.month)
+            .map(|month| ev(Ev::Click, move |_| Msg::ShowMonth(month))),
+        |date| {
+            td![
+                C![
+                    IF![date.month() != model.month.month() => "is-semitransparent"],
+                    IF![*date == today => "is-today"],
+                ],
+                div![date.day()],
+                div![
+                    C!["is-flex"],
+                    C!["is-flex-wrap-wrap"],
+                    C!["is-justify-content-center"],
+                    training_sessions
+                        .get(date)
+                        .into_iter()
+                        .flatten()
+                        .map(|training_session| {
+                            let routine = training_session
+                                .routine_id
+                                .and_then(|id| data_model.routines.get(&id));
+                            a![
+                                C!["is-calendar-dot"],
+                                attrs! {
+                                    At::Href => crate::Urls::new(&data_model.base_url)
+                                        .training_session()
+                                        .add_hash_path_part(training_session.id.to_string()),
+                                    At::Title => routine.map_or("-", |r| r.name.as_str()),
+                                },
+                                style! {
+                                    St::BackgroundColor => if let Some(routine) = routine {
+                                        let (r, g, b) = Palette99::pick(routine.id as usize).rgb();
+                                        format!("rgb({r}, {g}, {b})")
+                                    } else {
+                                        String::from("var(--bulma-grey)")
+                                    }
+                                },
+                            ]
+                        })
+                ]
+            ]
+        },
+    )
+}
+
 pub fn view_calendar<Ms>(
     training_sessions: &[&domain::TrainingSession],
     interval: &domain::Interval,
@@ -417,7 +855,7 @@ fn view_training_sessions_dialog(
             return empty![];
         }
     }
-    let today = Local::now().date_naive();
+    let today = web_app::clock::today();
     let date_valid = form.date.1.map_or(false, |d| d <= today);
     let save_disabled = loading || !date_valid;
     common::view_dialog(
@@ -506,14 +944,23 @@ fn view_training_sessions_dialog(
     )
 }
 
-pub fn view_charts<Ms>(
+fn data_table(data_model: &data::Model) -> common::DataTable<Msg> {
+    common::DataTable {
+        shown: data_model.show_data_tables(web_app::chart::Page::Training),
+        toggle: || Msg::ToggleDataTables,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn view_charts<Ms: 'static>(
     short_term_load: Vec<(NaiveDate, f32)>,
     long_term_load: &[(NaiveDate, f32)],
     total_7day_set_volume: Vec<(NaiveDate, f32)>,
     average_7day_rpe: &[Vec<(NaiveDate, f32)>],
     interval: &domain::Interval,
-    theme: &web_app::Theme,
+    style: &web_app::chart::Style,
     show_rpe: bool,
+    data_table: common::DataTable<Ms>,
 ) -> Vec<Node<Ms>> {
     let long_term_load_high = long_term_load
         .iter()
@@ -528,35 +975,34 @@ pub fn view_charts<Ms>(
     nodes![
         common::view_chart(
             &[
-                (
-                    "Short-term load",
-                    web_app::chart::COLOR_LOAD,
-                    web_app::chart::OPACITY_LINE
-                ),
                 (
                     "Long-term load",
                     web_app::chart::COLOR_LONG_TERM_LOAD,
                     web_app::chart::OPACITY_AREA
+                ),
+                (
+                    "Short-term load",
+                    web_app::chart::COLOR_LOAD,
+                    web_app::chart::OPACITY_LINE
                 )
             ],
-            web_app::chart::plot(
-                &[
-                    web_app::chart::PlotData {
-                        values_high: long_term_load_high,
-                        values_low: Some(long_term_load_low),
-                        plots: web_app::chart::plot_area(web_app::chart::COLOR_LONG_TERM_LOAD),
-                        params: web_app::chart::PlotParams::primary_range(0., 10.),
-                    },
-                    web_app::chart::PlotData {
-                        values_high: short_term_load,
-                        values_low: None,
-                        plots: web_app::chart::plot_line(web_app::chart::COLOR_LOAD),
-                        params: web_app::chart::PlotParams::primary_range(0., 10.),
-                    }
-                ],
-                interval,
-                theme,
-            ),
+            &[
+                web_app::chart::PlotData {
+                    values_high: long_term_load_high,
+                    values_low: Some(long_term_load_low),
+                    plots: web_app::chart::plot_area(web_app::chart::COLOR_LONG_TERM_LOAD),
+                    params: web_app::chart::PlotParams::primary_range(0., 10.),
+                },
+                web_app::chart::PlotData {
+                    values_high: short_term_load,
+                    values_low: None,
+                    plots: web_app::chart::plot_line(web_app::chart::COLOR_LOAD),
+                    params: web_app::chart::PlotParams::primary_range(0., 10.),
+                }
+            ],
+            interval,
+            style,
+            data_table,
             false,
         ),
         common::view_chart(
@@ -565,47 +1011,48 @@ pub fn view_charts<Ms>(
                 web_app::chart::COLOR_SET_VOLUME,
                 web_app::chart::OPACITY_LINE
             )],
-            web_app::chart::plot(
-                &[web_app::chart::PlotData {
-                    values_high: total_7day_set_volume,
-                    values_low: None,
-                    plots: web_app::chart::plot_area_with_border(
-                        web_app::chart::COLOR_SET_VOLUME,
-                        web_app::chart::COLOR_SET_VOLUME,
-                    ),
-                    params: web_app::chart::PlotParams::primary_range(0., 10.),
-                }],
-                interval,
-                theme,
-            ),
+            &[web_app::chart::PlotData {
+                values_high: total_7day_set_volume,
+                values_low: None,
+                plots: web_app::chart::plot_area_with_border(
+                    web_app::chart::COLOR_SET_VOLUME,
+                    web_app::chart::COLOR_SET_VOLUME,
+                ),
+                params: web_app::chart::PlotParams::primary_range(0., 10.),
+            }],
+            interval,
+            style,
+            data_table,
             false,
         ),
         IF![
             show_rpe =>
             common::view_chart(
                 &[("RPE (7 day average)", web_app::chart::COLOR_RPE, web_app::chart::OPACITY_LINE)],
-                web_app::chart::plot(
-                    &average_7day_rpe.iter().map(|values| web_app::chart::PlotData{values_high: values.clone(),
-                        values_low: None,
-                        plots: web_app::chart::plot_line(web_app::chart::COLOR_RPE),
-                        params: web_app::chart::PlotParams::primary_range(5., 10.)
-                    }).collect::<Vec<_>>(),
-                    interval,
-                    theme,
-                ),
+                &average_7day_rpe.iter().map(|values| web_app::chart::PlotData{values_high: values.clone(),
+                    values_low: None,
+                    plots: web_app::chart::plot_line(web_app::chart::COLOR_RPE),
+                    params: web_app::chart::PlotParams::primary_range(5., 10.)
+                }).collect::<Vec<_>>(),
+                interval,
+                style,
+                data_table,
                 false,
             )
         ],
     ]
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn view_table<Ms: 'static>(
     training_sessions: &[&domain::TrainingSession],
     routines: &BTreeMap<u32, domain::Routine>,
+    exercises: &BTreeMap<u32, domain::Exercise>,
     base_url: &Url,
     delete_training_session_message: fn(u32) -> Ms,
     show_rpe: bool,
     show_tut: bool,
+    separator: web_app::format::DecimalSeparator,
 ) -> Node<Ms> {
     let (has_avg_rpe_data, has_tut_data, has_avg_reps_data, has_avg_weight_data, has_avg_time_data) =
         training_sessions
@@ -672,18 +1119,18 @@ pub fn view_table<Ms: 'static>(
                         ],
                         td![&t.load()],
                         td![t.set_volume()],
-                        IF![show_rpe && has_avg_rpe_data => td![common::value_or_dash(t.avg_rpe())]],
-                        td![&t.volume_load()],
-                        IF![show_tut && has_tut_data => td![common::value_or_dash(t.tut())]],
-                        IF![has_avg_reps_data => td![common::value_or_dash(t.avg_reps())]],
+                        IF![show_rpe && has_avg_rpe_data => td![common::value_or_dash(t.avg_rpe(), separator)]],
+                        td![&t.volume_load(exercises)],
+                        IF![show_tut && has_tut_data => td![common::integer_or_dash(t.tut())]],
+                        IF![has_avg_reps_data => td![common::value_or_dash(t.avg_reps(), separator)]],
                         IF![show_rpe && has_avg_reps_data && has_avg_rpe_data =>
                             td![if let (Some(avg_reps), Some(avg_rpe)) = (t.avg_reps(), t.avg_rpe()) {
-                                format!("{:.1}", avg_reps + 10.0 - avg_rpe)
+                                web_app::format::format_number(avg_reps + 10.0 - avg_rpe, 1, separator)
                             } else {
                                 "-".into()
                             }]],
-                        IF![has_avg_weight_data => td![common::value_or_dash(t.avg_weight())]],
-                        IF![show_tut && has_avg_time_data => td![common::value_or_dash(t.avg_time())]],
+                        IF![has_avg_weight_data => td![common::value_or_dash(t.avg_weight(), separator)]],
+                        IF![show_tut && has_avg_time_data => td![common::value_or_dash(t.avg_time(), separator)]],
                         td![p![
                             C!["is-flex is-flex-wrap-nowrap"],
                             a![
@@ -699,47 +1146,3 @@ pub fn view_table<Ms: 'static>(
         ]
     ]
 }
-
-fn to_training_session_elements(part: &domain::RoutinePart) -> Vec<domain::TrainingSessionElement> {
-    let mut result = vec![];
-    match part {
-        domain::RoutinePart::RoutineSection { rounds, parts, .. } => {
-            for _ in 0..*rounds {
-                for p in parts {
-                    for s in to_training_session_elements(p) {
-                        result.push(s);
-                    }
-                }
-            }
-        }
-        domain::RoutinePart::RoutineActivity {
-            exercise_id,
-            reps,
-            time,
-            weight,
-            rpe,
-            automatic,
-        } => {
-            result.push(if let Some(exercise_id) = exercise_id {
-                domain::TrainingSessionElement::Set {
-                    exercise_id: *exercise_id,
-                    reps: None,
-                    time: None,
-                    weight: None,
-                    rpe: None,
-                    target_reps: if *reps > 0 { Some(*reps) } else { None },
-                    target_time: if *time > 0 { Some(*time) } else { None },
-                    target_weight: if *weight > 0.0 { Some(*weight) } else { None },
-                    target_rpe: if *rpe > 0.0 { Some(*rpe) } else { None },
-                    automatic: *automatic,
-                }
-            } else {
-                domain::TrainingSessionElement::Rest {
-                    target_time: if *time > 0 { Some(*time) } else { None },
-                    automatic: *automatic,
-                }
-            });
-        }
-    }
-    result
-}
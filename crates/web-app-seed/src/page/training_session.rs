@@ -4,7 +4,7 @@ use std::{
 };
 
 use chrono::{prelude::*, Duration};
-use gloo_console::error;
+use gloo_console::{debug, error};
 use seed::{prelude::*, *};
 use valens_domain as domain;
 use valens_web_app as web_app;
@@ -29,12 +29,20 @@ pub fn init(
     let action = url.next_hash_path_part();
     let editing = action == Some("edit");
     let guide = if action == Some("guide") {
-        Some(Guide::new(data_model.settings.beep_volume))
+        Some(Guide::new(
+            training_session_id,
+            data_model.settings.beep_volume,
+            data_model.settings.sound_profile,
+            data_model.settings.vibration,
+        ))
     } else {
         None
     };
 
     orders.subscribe(Msg::DataEvent);
+    let key_stream = orders.stream_with_handle(streams::window_event(Ev::KeyDown, |event| {
+        Msg::KeyDown(event.unchecked_into::<web_sys::KeyboardEvent>())
+    }));
 
     navbar.title = String::from("Training session");
     navbar.items = vec![(
@@ -53,6 +61,9 @@ pub fn init(
         }
     };
 
+    orders.notify(data::Msg::ReadTrainingSessionDraft(training_session_id));
+    read_exercise_images(training_session, orders);
+
     if let Some(ongoing_training_session) = &data_model.ongoing_training_session {
         if ongoing_training_session.training_session_id == training_session_id {
             orders.send_msg(Msg::ContinueGuidedTrainingSession(
@@ -64,44 +75,81 @@ pub fn init(
     Model {
         training_session_id,
         form: init_form(training_session, data_model),
+        duration: init_duration(training_session),
+        draft: None,
+        draft_restored: false,
         guide,
         dialog: Dialog::Hidden,
         smt: StopwatchMetronomTimer {
-            stopwatch: Stopwatch {
-                time: 0,
-                start_time: None,
-            },
+            stopwatch: web_app::stopwatch::Stopwatch::default(),
             metronome: Metronome {
-                interval: 1,
-                stressed_beat: 1,
-                beat_number: 0,
-                next_beat_time: 0.,
-                is_active: false,
+                state: web_app::metronome::Metronome::new(
+                    data_model.settings.metronome_interval,
+                    data_model.settings.metronome_stressed_beat,
+                ),
                 beep_volume: data_model.settings.beep_volume,
+                sound_profile: data_model.settings.sound_profile,
+                vibration: data_model.settings.vibration,
             },
             timer: Timer {
-                time: (String::from("60"), Some(60)),
-                reset_time: 60,
+                time: (
+                    data_model.settings.timer_presets[0].to_string(),
+                    Some(i64::from(data_model.settings.timer_presets[0])),
+                ),
+                reset_time: i64::from(data_model.settings.timer_presets[0]),
                 target_time: None,
                 beep_time: 0.,
                 beep_volume: data_model.settings.beep_volume,
+                sound_profile: data_model.settings.sound_profile,
+                vibration: data_model.settings.vibration,
             },
         },
+        set_timer: None,
         timer_stream: None,
+        _key_stream: key_stream,
         audio_context,
         editing,
         loading: false,
+        undo_stack: vec![],
+        undo_handle: None,
+        copied: false,
+        repeating: false,
+    }
+}
+
+fn read_exercise_images(
+    training_session: Option<&domain::TrainingSession>,
+    orders: &mut impl Orders<Msg>,
+) {
+    if let Some(training_session) = training_session {
+        for exercise_id in training_session.exercises() {
+            orders.notify(data::Msg::ReadExerciseImage(exercise_id));
+        }
+    }
+}
+
+fn init_duration(training_session: Option<&domain::TrainingSession>) -> common::InputField<u32> {
+    let minutes = training_session
+        .and_then(domain::TrainingSession::duration)
+        .and_then(|duration| u32::try_from(duration.num_minutes()).ok());
+    common::InputField {
+        input: minutes.map(|m| m.to_string()).unwrap_or_default(),
+        parsed: Some(minutes.unwrap_or_default()),
+        orig: minutes.map(|m| m.to_string()).unwrap_or_default(),
+        error: None,
     }
 }
 
 fn init_form(training_session: Option<&domain::TrainingSession>, data_model: &data::Model) -> Form {
-    let previous_sets = previous_sets(training_session, data_model);
+    let previous_training_sessions = previous_training_sessions(training_session, data_model);
+    let history = exercise_history(&previous_training_sessions);
     if let Some(training_session) = training_session {
         let mut elements = vec![];
         let mut exercises = vec![];
-        let mut prev_set_positions: HashMap<u32, usize> = HashMap::new();
+        let previous_sets =
+            domain::previous_sets(&training_session.elements, &previous_training_sessions);
 
-        for e in &training_session.elements {
+        for (e, previous_set) in training_session.elements.iter().zip(previous_sets) {
             match e {
                 domain::TrainingSessionElement::Set {
                     exercise_id,
@@ -114,6 +162,10 @@ fn init_form(training_session: Option<&domain::TrainingSession>, data_model: &da
                     target_weight,
                     target_rpe,
                     automatic,
+                    partial_reps,
+                    to_failure,
+                    side,
+                    comment,
                 } => {
                     if target_time.is_some() && target_reps.is_none() {
                         if not(exercises.is_empty()) {
@@ -121,24 +173,16 @@ fn init_form(training_session: Option<&domain::TrainingSession>, data_model: &da
                         }
                         exercises = vec![];
                     }
-                    let prev_set_position = prev_set_positions
-                        .entry(*exercise_id)
-                        .and_modify(|position| *position += 1)
-                        .or_insert(0);
                     let (prev_reps, prev_time, prev_weight, prev_rpe) =
-                        if let Some(prev_sets) = previous_sets.get(exercise_id) {
-                            if let Some(domain::TrainingSessionElement::Set {
-                                reps,
-                                time,
-                                weight,
-                                rpe,
-                                ..
-                            }) = prev_sets.get(*prev_set_position)
-                            {
-                                (*reps, *time, *weight, *rpe)
-                            } else {
-                                (None, None, None, None)
-                            }
+                        if let Some(domain::TrainingSessionElement::Set {
+                            reps,
+                            time,
+                            weight,
+                            rpe,
+                            ..
+                        }) = previous_set
+                        {
+                            (*reps, *time, *weight, *rpe)
                         } else {
                             (None, None, None, None)
                         };
@@ -151,7 +195,7 @@ fn init_form(training_session: Option<&domain::TrainingSession>, data_model: &da
                                 _ => None,
                             })
                             .flatten()
-                            .filter(|pe| pe.exercise_id == *exercise_id)
+                            .filter(|pe| pe.exercise_id == *exercise_id && pe.side == *side)
                             .last()
                         {
                             (
@@ -174,21 +218,31 @@ fn init_form(training_session: Option<&domain::TrainingSession>, data_model: &da
                             input: reps.map(|v| v.to_string()).unwrap_or_default(),
                             parsed: some_or_default(*reps),
                             orig: reps.map(|v| v.to_string()).unwrap_or_default(),
+                            error: None,
+                        },
+                        partial_reps: common::InputField {
+                            input: partial_reps.map(|v| v.to_string()).unwrap_or_default(),
+                            parsed: some_or_default(*partial_reps),
+                            orig: partial_reps.map(|v| v.to_string()).unwrap_or_default(),
+                            error: None,
                         },
                         time: common::InputField {
                             input: time.map(|v| v.to_string()).unwrap_or_default(),
                             parsed: some_or_default(*time),
                             orig: time.map(|v| v.to_string()).unwrap_or_default(),
+                            error: None,
                         },
                         weight: common::InputField {
                             input: weight.map(|v| v.to_string()).unwrap_or_default(),
                             parsed: some_or_default(*weight),
                             orig: weight.map(|v| v.to_string()).unwrap_or_default(),
+                            error: None,
                         },
                         rpe: common::InputField {
                             input: rpe.map(|v| v.to_string()).unwrap_or_default(),
                             parsed: some_or_default(*rpe),
                             orig: rpe.map(|v| v.to_string()).unwrap_or_default(),
+                            error: None,
                         },
                         target_reps: *target_reps,
                         target_time: *target_time,
@@ -202,7 +256,29 @@ fn init_form(training_session: Option<&domain::TrainingSession>, data_model: &da
                         prev_set_time,
                         prev_set_weight,
                         prev_set_rpe,
+                        suggestion: history
+                            .get(exercise_id)
+                            .map(|history| {
+                                let suggestion = domain::progression::suggest(
+                                    history,
+                                    domain::progression::ProgressionStrategy::default(),
+                                );
+                                match data_model.achievable_weights(*exercise_id) {
+                                    Some(weights) => domain::progression::round_suggestion(
+                                        suggestion, history, &weights,
+                                    ),
+                                    None => suggestion,
+                                }
+                            })
+                            .unwrap_or_default(),
                         automatic: *automatic,
+                        to_failure: *to_failure,
+                        side: *side,
+                        to_failure_changed: false,
+                        comment: comment.clone().unwrap_or_default(),
+                        comment_changed: false,
+                        comment_shown: comment.is_some(),
+                        partial_reps_shown: partial_reps.is_some(),
                     });
                     if target_time.is_some() && target_reps.is_none() {
                         if not(exercises.is_empty()) {
@@ -245,25 +321,264 @@ fn init_form(training_session: Option<&domain::TrainingSession>, data_model: &da
     }
 }
 
-fn previous_sets(
+/// Keep the values that were entered in the previous form, but not saved yet.
+///
+/// If saving failed, all values of the previous form are considered as unsaved. Values are only
+/// restored if the structure of both forms matches, i.e. if the values belong to the same
+/// exercises.
+fn restore_unsaved_changes(form: &mut Form, previous_form: &Form, saving_failed: bool) {
+    if (previous_form.notes_changed || saving_failed) && previous_form.notes != form.notes {
+        form.notes.clone_from(&previous_form.notes);
+        form.notes_changed = true;
+    }
+
+    if form.elements.len() != previous_form.elements.len() {
+        return;
+    }
+
+    for (element, previous_element) in form.elements.iter_mut().zip(&previous_form.elements) {
+        if let (
+            FormElement::Set { exercises },
+            FormElement::Set {
+                exercises: previous_exercises,
+            },
+        ) = (element, previous_element)
+        {
+            if exercises.len() != previous_exercises.len() {
+                continue;
+            }
+            for (exercise, previous_exercise) in exercises.iter_mut().zip(previous_exercises) {
+                if exercise.exercise_id != previous_exercise.exercise_id {
+                    continue;
+                }
+                restore_unsaved_input(&mut exercise.reps, &previous_exercise.reps, saving_failed);
+                restore_unsaved_input(
+                    &mut exercise.partial_reps,
+                    &previous_exercise.partial_reps,
+                    saving_failed,
+                );
+                exercise.partial_reps_shown |= previous_exercise.partial_reps_shown;
+                restore_unsaved_input(&mut exercise.time, &previous_exercise.time, saving_failed);
+                restore_unsaved_input(
+                    &mut exercise.weight,
+                    &previous_exercise.weight,
+                    saving_failed,
+                );
+                restore_unsaved_input(&mut exercise.rpe, &previous_exercise.rpe, saving_failed);
+                if (previous_exercise.comment_changed || saving_failed)
+                    && previous_exercise.comment != exercise.comment
+                {
+                    exercise.comment.clone_from(&previous_exercise.comment);
+                    exercise.comment_changed = true;
+                    exercise.comment_shown = true;
+                }
+                if (previous_exercise.to_failure_changed || saving_failed)
+                    && previous_exercise.to_failure != exercise.to_failure
+                {
+                    exercise.to_failure = previous_exercise.to_failure;
+                    exercise.to_failure_changed = true;
+                }
+            }
+        }
+    }
+}
+
+fn restore_unsaved_input<T: Copy + PartialEq>(
+    field: &mut common::InputField<T>,
+    previous_field: &common::InputField<T>,
+    saving_failed: bool,
+) {
+    if (previous_field.changed() || saving_failed) && previous_field.parsed != field.parsed {
+        field.input.clone_from(&previous_field.input);
+        field.parsed = previous_field.parsed;
+    }
+}
+
+fn duration_field(input: String, orig: String) -> common::InputField<u32> {
+    let parsed = if input.is_empty() {
+        Some(0)
+    } else {
+        input
+            .parse::<u32>()
+            .ok()
+            .filter(|minutes| (1..=MAX_DURATION).contains(minutes))
+    };
+    common::InputField {
+        error: parsed.is_none().then(|| {
+            format!("Duration must be a whole number of minutes between 1 and {MAX_DURATION}")
+        }),
+        parsed,
+        input,
+        orig,
+    }
+}
+
+/// Create a draft of the unsaved changes, if there are any.
+fn create_draft(
+    form: &Form,
+    duration: &common::InputField<u32>,
+    training_session: &domain::TrainingSession,
+) -> Option<web_app::TrainingSessionDraft> {
+    let sets = form
+        .elements
+        .iter()
+        .enumerate()
+        .filter_map(|(element_idx, element)| match element {
+            FormElement::Set { exercises } => Some((element_idx, exercises)),
+            FormElement::Rest { .. } => None,
+        })
+        .flat_map(|(element_idx, exercises)| {
+            exercises
+                .iter()
+                .enumerate()
+                .map(move |(exercise_idx, exercise)| web_app::SetDraft {
+                    element_idx,
+                    exercise_idx,
+                    exercise_id: exercise.exercise_id,
+                    reps: changed_input(&exercise.reps),
+                    time: changed_input(&exercise.time),
+                    weight: changed_input(&exercise.weight),
+                    rpe: changed_input(&exercise.rpe),
+                    partial_reps: changed_input(&exercise.partial_reps),
+                    to_failure: exercise.to_failure_changed.then_some(exercise.to_failure),
+                    comment: exercise.comment_changed.then(|| exercise.comment.clone()),
+                })
+        })
+        .filter(|set| {
+            set.reps.is_some()
+                || set.time.is_some()
+                || set.weight.is_some()
+                || set.rpe.is_some()
+                || set.partial_reps.is_some()
+                || set.to_failure.is_some()
+                || set.comment.is_some()
+        })
+        .collect::<Vec<_>>();
+    let notes = form.notes_changed.then(|| form.notes.clone());
+    let duration = changed_input(duration);
+
+    if sets.is_empty() && notes.is_none() && duration.is_none() {
+        return None;
+    }
+
+    Some(web_app::TrainingSessionDraft {
+        training_session: training_session.clone(),
+        notes,
+        duration,
+        sets,
+    })
+}
+
+fn changed_input<T>(field: &common::InputField<T>) -> Option<String> {
+    field.changed().then(|| field.input.clone())
+}
+
+/// Restore the unsaved changes of the draft.
+///
+/// Values of sets are only restored if the set still refers to the same exercise.
+fn apply_draft(
+    form: &mut Form,
+    duration: &mut common::InputField<u32>,
+    draft: &web_app::TrainingSessionDraft,
+) {
+    if let Some(notes) = &draft.notes {
+        form.notes.clone_from(notes);
+        form.notes_changed = true;
+    }
+
+    if let Some(input) = &draft.duration {
+        *duration = duration_field(input.clone(), duration.orig.clone());
+    }
+
+    for set in &draft.sets {
+        let Some(FormElement::Set { exercises }) = form.elements.get_mut(set.element_idx) else {
+            continue;
+        };
+        let Some(exercise) = exercises.get_mut(set.exercise_idx) else {
+            continue;
+        };
+        if exercise.exercise_id != set.exercise_id {
+            continue;
+        }
+        if let Some(input) = &set.reps {
+            exercise.reps = common::set_value_field(
+                input.clone(),
+                exercise.reps.orig.clone(),
+                domain::validate_reps,
+                domain::SetValueError::Reps,
+            );
+        }
+        if let Some(input) = &set.time {
+            exercise.time = common::set_value_field(
+                input.clone(),
+                exercise.time.orig.clone(),
+                domain::validate_time,
+                domain::SetValueError::Time,
+            );
+        }
+        if let Some(input) = &set.weight {
+            exercise.weight = common::set_value_field(
+                input.clone(),
+                exercise.weight.orig.clone(),
+                domain::validate_weight,
+                domain::SetValueError::Weight,
+            );
+        }
+        if let Some(input) = &set.rpe {
+            exercise.rpe = common::set_value_field(
+                input.clone(),
+                exercise.rpe.orig.clone(),
+                domain::validate_rpe,
+                domain::SetValueError::RPE,
+            );
+        }
+        if let Some(input) = &set.partial_reps {
+            exercise.partial_reps = common::set_value_field(
+                input.clone(),
+                exercise.partial_reps.orig.clone(),
+                domain::validate_reps,
+                domain::SetValueError::Reps,
+            );
+            exercise.partial_reps_shown = true;
+        }
+        if let Some(to_failure) = set.to_failure {
+            exercise.to_failure = to_failure;
+            exercise.to_failure_changed = true;
+        }
+        if let Some(comment) = &set.comment {
+            exercise.comment.clone_from(comment);
+            exercise.comment_changed = true;
+            exercise.comment_shown = true;
+        }
+    }
+}
+
+fn previous_training_sessions<'a>(
     training_session: Option<&domain::TrainingSession>,
-    data_model: &data::Model,
+    data_model: &'a data::Model,
+) -> BTreeMap<u32, &'a domain::TrainingSession> {
+    match training_session {
+        Some(training_session)
+            if training_session.routine_id.is_some()
+                || data_model.settings.ad_hoc_previous_values =>
+        {
+            domain::previous_training_sessions(
+                training_session,
+                data_model.training_sessions.values(),
+            )
+        }
+        _ => BTreeMap::new(),
+    }
+}
+
+fn exercise_history(
+    previous_training_sessions: &BTreeMap<u32, &domain::TrainingSession>,
 ) -> HashMap<u32, Vec<domain::TrainingSessionElement>> {
     let mut sets: HashMap<u32, Vec<domain::TrainingSessionElement>> = HashMap::new();
-    if let Some(training_session) = training_session {
-        if let Some(previous_training_session) = &data_model
-            .training_sessions
-            .values()
-            .filter(|t| {
-                t.id != training_session.id
-                    && t.date <= training_session.date
-                    && (not(training_session.routine_id.is_some())
-                        || t.routine_id == training_session.routine_id)
-            })
-            .last()
-        {
-            for e in &previous_training_session.elements {
-                if let domain::TrainingSessionElement::Set { exercise_id, .. } = e {
+    for (id, training_session) in previous_training_sessions {
+        for e in &training_session.elements {
+            if let domain::TrainingSessionElement::Set { exercise_id, .. } = e {
+                if exercise_id == id {
                     sets.entry(*exercise_id).or_default().push(e.clone());
                 }
             }
@@ -276,21 +591,47 @@ fn previous_sets(
 //     Model
 // ------ ------
 
+const UNDO_STACK_SIZE: usize = 10;
+/// Milliseconds for which the undo notification is shown after a destructive change.
+const UNDO_TIMEOUT: u32 = 10_000;
+/// Maximum duration in minutes which can be entered manually.
+const MAX_DURATION: u32 = 24 * 60;
+
 pub struct Model {
     training_session_id: u32,
     form: Form,
+    /// Manually entered duration in minutes.
+    duration: common::InputField<u32>,
+    /// Draft of unsaved changes which was read from the storage, but could not be checked yet, as
+    /// the training session is not loaded.
+    draft: Option<web_app::TrainingSessionDraft>,
+    /// Unsaved changes of a previous visit of the page were restored.
+    draft_restored: bool,
     guide: Option<Guide>,
     dialog: Dialog,
     smt: StopwatchMetronomTimer,
+    /// Timer for recording the time under tension of a single set.
+    set_timer: Option<SetTimer>,
     timer_stream: Option<StreamHandle>,
+    /// Handle of the subscription to key events, which is kept until leaving the page.
+    _key_stream: StreamHandle,
     audio_context: Option<web_sys::AudioContext>,
     editing: bool,
     loading: bool,
+    /// Snapshots of the form elements before destructive changes. The stack is bound to the
+    /// model and therefore discarded when leaving the page.
+    undo_stack: Vec<Vec<FormElement>>,
+    /// Handle of the timeout after which the undo notification is hidden.
+    undo_handle: Option<CmdHandle>,
+    /// The training session was copied to the clipboard.
+    copied: bool,
+    /// A repetition of the training session is being created.
+    repeating: bool,
 }
 
 impl Model {
     pub fn has_unsaved_changes(&self) -> bool {
-        self.form.changed()
+        self.form.changed() || self.duration.changed()
     }
 }
 
@@ -300,6 +641,7 @@ impl Drop for Model {
     }
 }
 
+#[cfg_attr(test, derive(Debug, PartialEq))]
 struct Form {
     notes: String,
     notes_changed: bool,
@@ -318,11 +660,17 @@ impl Form {
                 })
                 .flatten()
                 .any(|e| {
-                    e.reps.changed() || e.time.changed() || e.weight.changed() || e.rpe.changed()
+                    e.reps.changed()
+                        || e.partial_reps.changed()
+                        || e.time.changed()
+                        || e.weight.changed()
+                        || e.rpe.changed()
+                        || e.to_failure_changed
+                        || e.comment_changed
                 })
     }
 
-    fn valid(&self) -> bool {
+    fn invalid_fields(&self) -> usize {
         self.elements
             .iter()
             .filter_map(|e| match e {
@@ -330,10 +678,23 @@ impl Form {
                 _ => None,
             })
             .flatten()
-            .all(|s| s.reps.valid() && s.time.valid() && s.weight.valid() && s.rpe.valid())
+            .map(|s| {
+                [
+                    s.reps.valid(),
+                    s.partial_reps.valid(),
+                    s.time.valid(),
+                    s.weight.valid(),
+                    s.rpe.valid(),
+                ]
+                .into_iter()
+                .filter(|valid| not(*valid))
+                .count()
+            })
+            .sum()
     }
 }
 
+#[derive(Clone)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 enum FormElement {
     Set { exercises: Vec<ExerciseForm> },
@@ -346,13 +707,14 @@ struct ExerciseForm {
     exercise_id: u32,
     exercise_name: String,
     reps: common::InputField<u32>,
+    partial_reps: common::InputField<u32>,
     time: common::InputField<u32>,
     weight: common::InputField<f32>,
     rpe: common::InputField<f32>,
-    target_reps: Option<u32>,
+    target_reps: Option<domain::RepsRange>,
     target_time: Option<u32>,
     target_weight: Option<f32>,
-    target_rpe: Option<f32>,
+    target_rpe: Option<domain::RpeRange>,
     prev_reps: Option<u32>,
     prev_time: Option<u32>,
     prev_weight: Option<f32>,
@@ -361,43 +723,90 @@ struct ExerciseForm {
     prev_set_time: Option<u32>,
     prev_set_weight: Option<f32>,
     prev_set_rpe: Option<f32>,
+    suggestion: domain::progression::Set,
     automatic: bool,
+    to_failure: bool,
+    to_failure_changed: bool,
+    side: Option<domain::Side>,
+    comment: String,
+    comment_changed: bool,
+    comment_shown: bool,
+    partial_reps_shown: bool,
 }
 
 struct Guide {
-    element_idx: usize,
-    element_start_time: DateTime<Utc>,
+    session: web_app::OngoingTrainingSession,
     timer: Timer,
     stream: Option<StreamHandle>,
     element: ElRef<web_sys::Element>,
+    /// Screen wake lock, which is released when the guide is dropped.
+    wake_lock: Option<web_app::wake_lock::WakeLock>,
 }
 
 impl Guide {
-    fn new(beep_volume: u8) -> Guide {
+    fn new(
+        training_session_id: u32,
+        beep_volume: u8,
+        sound_profile: web_app::sound::SoundProfile,
+        vibration: web_app::vibration::Vibration,
+    ) -> Guide {
         Guide {
-            element_idx: 0,
-            element_start_time: Utc::now(),
-            timer: Timer::new(beep_volume),
+            session: web_app::OngoingTrainingSession::new(training_session_id),
+            timer: Timer::new(beep_volume, sound_profile, vibration),
             stream: None,
             element: ElRef::new(),
+            wake_lock: None,
         }
     }
 
     fn from_ongoing_training_session(
-        element_idx: usize,
-        element_start_time: DateTime<Utc>,
+        ongoing_training_session: web_app::OngoingTrainingSession,
         beep_volume: u8,
+        sound_profile: web_app::sound::SoundProfile,
+        vibration: web_app::vibration::Vibration,
     ) -> Guide {
+        let mut timer = Timer::new(beep_volume, sound_profile, vibration);
+        timer.restore(ongoing_training_session.timer_state);
         Guide {
-            element_idx,
-            element_start_time,
-            timer: Timer::new(beep_volume),
+            session: ongoing_training_session,
+            timer,
             stream: None,
             element: ElRef::new(),
+            wake_lock: None,
+        }
+    }
+
+    fn update_wake_lock(&mut self, keep_screen_awake: bool) {
+        if not(keep_screen_awake) {
+            self.wake_lock = None;
+        } else if self.wake_lock.is_none() {
+            self.wake_lock = acquire_wake_lock();
+        }
+    }
+}
+
+/// Acquire a screen wake lock. Failures are only logged, as the guided training session works
+/// without the lock as well.
+fn acquire_wake_lock() -> Option<web_app::wake_lock::WakeLock> {
+    match web_app::wake_lock::WakeLock::acquire(|err| error!("failed to acquire wake lock:", err)) {
+        Ok(wake_lock) => Some(wake_lock),
+        Err(err) => {
+            debug!("Screen wake lock unavailable: ".to_owned() + &err);
+            None
         }
     }
 }
 
+/// Input field of an exercise in the training session form.
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+enum SetInput {
+    Reps,
+    Time,
+    Weight,
+    RPE,
+}
+
 enum Dialog {
     Hidden,
     StopwatchMetronomTimer,
@@ -405,110 +814,72 @@ enum Dialog {
     ReplaceExercise(usize, usize, component::exercise_list::Model),
     AddExercise(usize, usize, component::exercise_list::Model),
     AppendExercise(component::exercise_list::Model),
+    SaveAsRoutine(common::InputField<String>),
+    /// Summary of the training session shown at the end of a guided training session.
+    Summary,
 }
 
 struct StopwatchMetronomTimer {
-    stopwatch: Stopwatch,
+    stopwatch: web_app::stopwatch::Stopwatch,
     metronome: Metronome,
     timer: Timer,
 }
 
-struct Stopwatch {
-    time: i64,
-    start_time: Option<DateTime<Utc>>,
-}
-
-impl Stopwatch {
-    fn is_active(&self) -> bool {
-        self.start_time.is_some()
-    }
-
-    fn toggle(&mut self) {
-        if not(self.is_active()) && self.time > 0 {
-            self.reset();
-        } else {
-            self.start_pause();
-        }
-    }
-
-    fn start_pause(&mut self) {
-        self.start_time = match self.start_time {
-            Some(_) => None,
-            None => Some(Utc::now() - Duration::milliseconds(self.time)),
-        };
-    }
-
-    fn reset(&mut self) {
-        self.time = 0;
-        if self.start_time.is_some() {
-            self.start_time = Some(Utc::now());
-        }
-    }
-
-    fn update(&mut self) {
-        if let Some(start_time) = self.start_time {
-            self.time = Utc::now()
-                .signed_duration_since(start_time)
-                .num_milliseconds();
-        }
-    }
+struct SetTimer {
+    element_idx: usize,
+    exercise_idx: usize,
+    exercise_id: u32,
+    stopwatch: web_app::stopwatch::Stopwatch,
 }
 
 struct Metronome {
-    interval: u32,
-    stressed_beat: u32,
-    beat_number: u32,
-    next_beat_time: f64,
-    is_active: bool,
+    state: web_app::metronome::Metronome,
     beep_volume: u8,
+    sound_profile: web_app::sound::SoundProfile,
+    vibration: web_app::vibration::Vibration,
 }
 
 impl Metronome {
     fn is_active(&self) -> bool {
-        self.is_active
+        self.state.is_active()
     }
 
     fn start(&mut self, audio_context: &Option<web_sys::AudioContext>) {
-        self.is_active = true;
-        if let Some(audio_context) = audio_context {
-            self.beat_number = 0;
-            self.next_beat_time = audio_context.current_time() + 0.5;
-        }
+        self.state.start(
+            audio_context
+                .as_ref()
+                .map_or(0., web_sys::AudioContext::current_time),
+        );
     }
 
     fn pause(&mut self) {
-        self.is_active = false;
+        self.state.pause();
     }
 
     fn start_pause(&mut self, audio_context: &Option<web_sys::AudioContext>) {
-        if self.is_active() {
-            self.pause();
-        } else {
-            self.start(audio_context);
-        }
+        self.state.start_pause(
+            audio_context
+                .as_ref()
+                .map_or(0., web_sys::AudioContext::current_time),
+        );
     }
 
     fn update(&mut self, audio_context: &Option<web_sys::AudioContext>) {
-        if self.is_active() {
-            if let Some(audio_context) = audio_context {
-                while self.next_beat_time < audio_context.current_time() + 0.5 {
-                    if let Err(err) = play_beep(
-                        audio_context,
-                        if self.beat_number % self.stressed_beat == 0 {
-                            1000.
-                        } else {
-                            500.
-                        },
-                        self.next_beat_time,
-                        0.05,
-                        self.beep_volume,
-                    ) {
-                        error!("failed to play beep:", err);
-                    }
-                    self.next_beat_time += f64::from(self.interval);
-                    self.beat_number += 1;
+        if let Some(audio_context) = audio_context {
+            for beat in self.state.schedule_beats(audio_context.current_time()) {
+                if let Err(err) = web_app::sound::play(
+                    audio_context,
+                    self.sound_profile,
+                    beat.beep(),
+                    beat.time,
+                    self.beep_volume,
+                ) {
+                    error!("failed to play beep:", err);
                 }
             }
+            if let Some(beat) = self.state.due_vibration(audio_context.current_time()) {
+                vibrate(self.vibration, self.beep_volume, beat.vibration_pattern());
+            }
         }
     }
 }
@@ -519,16 +890,24 @@ struct Timer {
     target_time: Option<DateTime<Utc>>,
     beep_time: f64,
     beep_volume: u8,
+    sound_profile: web_app::sound::SoundProfile,
+    vibration: web_app::vibration::Vibration,
 }
 
 impl Timer {
-    fn new(beep_volume: u8) -> Timer {
+    fn new(
+        beep_volume: u8,
+        sound_profile: web_app::sound::SoundProfile,
+        vibration: web_app::vibration::Vibration,
+    ) -> Timer {
         Timer {
             time: (String::new(), None),
             reset_time: i64::MAX,
             target_time: None,
             beep_time: 0.,
             beep_volume,
+            sound_profile,
+            vibration,
         }
     }
 
@@ -585,35 +964,40 @@ impl Timer {
                 .round() as i64;
             if let Some(audio_context) = audio_context {
                 if time == 10 && Some(time) != self.time.1 {
-                    if let Err(err) = play_beep(
+                    if let Err(err) = web_app::sound::play(
                         audio_context,
-                        2000.,
+                        self.sound_profile,
+                        web_app::sound::Beep::Warning,
                         {
                             self.beep_time = audio_context.current_time() + 0.01;
                             self.beep_time
                         },
-                        0.1,
                         self.beep_volume,
                     ) {
                         error!("failed to play beep:", err);
                     }
-                    if let Err(err) = play_beep(
+                    if let Err(err) = web_app::sound::play(
                         audio_context,
-                        2000.,
+                        self.sound_profile,
+                        web_app::sound::Beep::Warning,
                         {
                             self.beep_time = audio_context.current_time() + 0.18;
                             self.beep_time
                         },
-                        0.1,
                         self.beep_volume,
                     ) {
                         error!("failed to play beep:", err);
                     }
                 }
                 if (0..=2).contains(&time) && Some(time) != self.time.1 {
-                    if let Err(err) = play_beep(
+                    if let Err(err) = web_app::sound::play(
                         audio_context,
-                        2000.,
+                        self.sound_profile,
+                        if time == 0 {
+                            web_app::sound::Beep::Expiry
+                        } else {
+                            web_app::sound::Beep::Countdown
+                        },
                         if time == 2 {
                             self.beep_time = audio_context.current_time() + 0.01;
                             self.beep_time
@@ -621,13 +1005,23 @@ impl Timer {
                             self.beep_time += 1.;
                             self.beep_time
                         },
-                        if time == 0 { 0.5 } else { 0.15 },
                         self.beep_volume,
                     ) {
                         error!("failed to play beep:", err);
                     }
                 }
             }
+            if Some(time) != self.time.1 {
+                let pattern = match time {
+                    10 => Some(web_app::vibration::Pattern::Warning),
+                    1 | 2 => Some(web_app::vibration::Pattern::Countdown),
+                    0 => Some(web_app::vibration::Pattern::Expiry),
+                    _ => None,
+                };
+                if let Some(pattern) = pattern {
+                    vibrate(self.vibration, self.beep_volume, pattern);
+                }
+            }
             self.time = (time.to_string(), Some(time));
         }
     }
@@ -663,22 +1057,17 @@ impl Timer {
     }
 }
 
-fn play_beep(
-    audio_context: &web_sys::AudioContext,
-    frequency: f32,
-    start: f64,
-    length: f64,
-    volume: u8,
-) -> Result<(), JsValue> {
-    let oscillator = audio_context.create_oscillator()?;
-    let gain = audio_context.create_gain()?;
-    gain.gain().set_value(f32::from(volume) / 100.);
-    gain.connect_with_audio_node(&audio_context.destination())?;
-    oscillator.connect_with_audio_node(&gain)?;
-    oscillator.frequency().set_value(frequency);
-    oscillator.start_with_when(start)?;
-    oscillator.stop_with_when(start + length)?;
-    Ok(())
+fn vibrate(
+    vibration: web_app::vibration::Vibration,
+    beep_volume: u8,
+    pattern: web_app::vibration::Pattern,
+) {
+    web_app::vibration::vibrate(
+        &web_app::vibration::Navigator,
+        vibration,
+        beep_volume,
+        pattern,
+    );
 }
 
 // ------ ------
@@ -687,25 +1076,45 @@ fn play_beep(
 
 pub enum Msg {
     RepsChanged(usize, usize, String),
+    PartialRepsChanged(usize, usize, String),
+    TogglePartialReps(usize, usize),
     TimeChanged(usize, usize, String),
     WeightChanged(usize, usize, String),
     RPEChanged(usize, usize, String),
+    ToggleFailure(usize, usize),
+    CommentChanged(usize, usize, String),
+    ToggleComment(usize, usize),
     NotesChanged(String),
+    DurationChanged(String),
 
     EnterTargetValues(usize, usize),
     EnterPreviousValues(usize, usize),
     EnterPreviousSetValues(usize, usize),
+    EnterSuggestedValues(usize, usize),
+    FillFromPreviousSession,
+    FocusInput(usize, usize, SetInput),
+    StepWeight(usize, usize, bool),
+    KeyDown(web_sys::KeyboardEvent),
 
     StartGuidedTrainingSession,
     ContinueGuidedTrainingSession(web_app::OngoingTrainingSession),
     UpdateGuidedTrainingSession,
     StartPauseGuideTimer,
+    NotificationAction(web_app::service_worker::SelectedNotificationAction),
     GoToPreviousSection,
     GoToNextSection,
     ScrollToSection,
 
     EditTrainingSession,
     SaveTrainingSession,
+    CopyTrainingSession,
+    RepeatTrainingSession,
+    TrainingSessionCopied,
+    ShareTrainingSession,
+    ShowSaveAsRoutineDialog,
+    RoutineNameChanged(String),
+    SaveAsRoutine,
+    Retry,
     DataEvent(data::Event),
 
     ShowSMTDialog,
@@ -722,6 +1131,8 @@ pub enum Msg {
     RemoveSet(usize),
     RemoveExercise(usize, usize),
     AppendExercise(u32),
+    Undo,
+    HideUndo,
     CloseDialog,
 
     ExerciseList(component::exercise_list::Msg),
@@ -729,6 +1140,7 @@ pub enum Msg {
     UpdateStopwatchMetronomTimer,
 
     StartPauseStopwatch,
+    ToggleSetTimer(usize, usize),
     ResetStopwatch,
     ToggleStopwatch,
 
@@ -739,6 +1151,7 @@ pub enum Msg {
     MetronomeStressChanged(String),
 
     StartPauseTimer,
+    StartTimerPreset(u32),
     ResetTimer,
     TimerTimeChanged(String),
 }
@@ -749,103 +1162,158 @@ pub fn update(
     data_model: &data::Model,
     orders: &mut impl Orders<Msg>,
 ) {
+    let form_input = matches!(
+        msg,
+        Msg::RepsChanged(..)
+            | Msg::PartialRepsChanged(..)
+            | Msg::TimeChanged(..)
+            | Msg::WeightChanged(..)
+            | Msg::StepWeight(..)
+            | Msg::RPEChanged(..)
+            | Msg::ToggleFailure(..)
+            | Msg::CommentChanged(..)
+            | Msg::NotesChanged(_)
+            | Msg::DurationChanged(_)
+            | Msg::EnterTargetValues(..)
+            | Msg::EnterPreviousValues(..)
+            | Msg::EnterPreviousSetValues(..)
+            | Msg::EnterSuggestedValues(..)
+            | Msg::FillFromPreviousSession
+    );
+
     match msg {
         Msg::RepsChanged(element_idx, exercise_idx, input) => {
             if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
                 let ExerciseForm { reps, .. } = &mut exercises[exercise_idx];
-                match input.parse::<u32>() {
-                    Ok(parsed_reps) => {
-                        let valid = common::valid_reps(parsed_reps);
-                        let parsed = if valid { Some(parsed_reps) } else { None };
-                        *reps = common::InputField {
-                            input,
-                            parsed,
-                            orig: reps.orig.clone(),
-                        }
-                    }
-                    Err(_) => {
-                        *reps = common::InputField {
-                            input: input.clone(),
-                            parsed: if input.is_empty() { Some(0) } else { None },
-                            orig: reps.orig.clone(),
-                        }
-                    }
-                }
+                *reps = common::set_value_field(
+                    input,
+                    reps.orig.clone(),
+                    domain::validate_reps,
+                    domain::SetValueError::Reps,
+                );
+            }
+        }
+        Msg::PartialRepsChanged(element_idx, exercise_idx, input) => {
+            if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
+                let ExerciseForm { partial_reps, .. } = &mut exercises[exercise_idx];
+                *partial_reps = common::set_value_field(
+                    input,
+                    partial_reps.orig.clone(),
+                    domain::validate_reps,
+                    domain::SetValueError::Reps,
+                );
             }
         }
+        Msg::TogglePartialReps(element_idx, exercise_idx) => {
+            if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
+                let exercise = &mut exercises[exercise_idx];
+                exercise.partial_reps_shown = not(exercise.partial_reps_shown);
+            }
+            orders.send_msg(Msg::CloseDialog);
+        }
         Msg::TimeChanged(element_idx, exercise_idx, input) => {
             if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
                 let ExerciseForm { time, .. } = &mut exercises[exercise_idx];
-                match input.parse::<u32>() {
-                    Ok(parsed_time) => {
-                        let valid = common::valid_time(parsed_time);
-                        let parsed = if valid { Some(parsed_time) } else { None };
-                        *time = common::InputField {
-                            input,
-                            parsed,
-                            orig: time.orig.clone(),
-                        }
-                    }
-                    Err(_) => {
-                        *time = common::InputField {
-                            input: input.clone(),
-                            parsed: if input.is_empty() { Some(0) } else { None },
-                            orig: time.orig.clone(),
-                        }
-                    }
-                }
+                *time = common::set_value_field(
+                    input,
+                    time.orig.clone(),
+                    domain::validate_time,
+                    domain::SetValueError::Time,
+                );
             }
         }
         Msg::WeightChanged(element_idx, exercise_idx, input) => {
             if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
                 let ExerciseForm { weight, .. } = &mut exercises[exercise_idx];
-                match input.parse::<f32>() {
-                    Ok(parsed_weight) => {
-                        let valid = common::valid_weight(parsed_weight);
-                        let parsed = if valid { Some(parsed_weight) } else { None };
-                        *weight = common::InputField {
-                            input,
-                            parsed,
-                            orig: weight.orig.clone(),
+                *weight = common::set_value_field(
+                    input,
+                    weight.orig.clone(),
+                    domain::validate_weight,
+                    domain::SetValueError::Weight,
+                );
+            }
+        }
+        Msg::StepWeight(element_idx, exercise_idx, up) => {
+            if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
+                let ExerciseForm {
+                    exercise_id,
+                    weight,
+                    target_weight,
+                    ..
+                } = &mut exercises[exercise_idx];
+                let current = if weight.input.is_empty() {
+                    target_weight.unwrap_or_default()
+                } else {
+                    weight.parsed.unwrap_or_default()
+                };
+                let stepped = match data_model.achievable_weights(*exercise_id) {
+                    Some(weights) => {
+                        if up {
+                            domain::equipment::next_weight(current, &weights)
+                        } else {
+                            domain::equipment::previous_weight(current, &weights)
                         }
                     }
-                    Err(_) => {
-                        *weight = common::InputField {
-                            input: input.clone(),
-                            parsed: if input.is_empty() { Some(0.0) } else { None },
-                            orig: weight.orig.clone(),
-                        }
+                    None => {
+                        let increment = data_model.settings.weight_increment;
+                        let steps = current / increment;
+                        let steps = if up {
+                            (steps + 0.01).floor() + 1.
+                        } else {
+                            (steps - 0.01).ceil() - 1.
+                        };
+                        IF!(steps >= 0. => (steps * increment * 100.).round() / 100.)
                     }
+                };
+                if let Some(stepped) = stepped {
+                    *weight = common::set_value_field(
+                        stepped.to_string(),
+                        weight.orig.clone(),
+                        domain::validate_weight,
+                        domain::SetValueError::Weight,
+                    );
                 }
             }
         }
         Msg::RPEChanged(element_idx, exercise_idx, input) => {
             if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
                 let ExerciseForm { rpe, .. } = &mut exercises[exercise_idx];
-                match input.parse::<f32>() {
-                    Ok(parsed_rpe) => {
-                        let valid = common::valid_rpe(parsed_rpe);
-                        let parsed = if valid { Some(parsed_rpe) } else { None };
-                        *rpe = common::InputField {
-                            input,
-                            parsed,
-                            orig: rpe.orig.clone(),
-                        }
-                    }
-                    Err(_) => {
-                        *rpe = common::InputField {
-                            input: input.clone(),
-                            parsed: if input.is_empty() { Some(0.0) } else { None },
-                            orig: rpe.orig.clone(),
-                        }
-                    }
-                }
+                *rpe = common::set_value_field(
+                    input,
+                    rpe.orig.clone(),
+                    domain::validate_rpe,
+                    domain::SetValueError::RPE,
+                );
+            }
+        }
+        Msg::ToggleFailure(element_idx, exercise_idx) => {
+            if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
+                let exercise = &mut exercises[exercise_idx];
+                exercise.to_failure = not(exercise.to_failure);
+                exercise.to_failure_changed = true;
+            }
+            orders.send_msg(Msg::CloseDialog);
+        }
+        Msg::CommentChanged(element_idx, exercise_idx, comment) => {
+            if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
+                let exercise = &mut exercises[exercise_idx];
+                exercise.comment = comment;
+                exercise.comment_changed = true;
+            }
+        }
+        Msg::ToggleComment(element_idx, exercise_idx) => {
+            if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
+                let exercise = &mut exercises[exercise_idx];
+                exercise.comment_shown = not(exercise.comment_shown);
             }
         }
         Msg::NotesChanged(notes) => {
             model.form.notes = notes;
             model.form.notes_changed = true;
         }
+        Msg::DurationChanged(input) => {
+            model.duration = duration_field(input, model.duration.orig.clone());
+        }
 
         Msg::EnterTargetValues(element_idx, exercise_idx) => {
             if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
@@ -860,25 +1328,32 @@ pub fn update(
                     target_rpe,
                     ..
                 } = &mut exercises[exercise_idx];
+                let target_reps = target_reps.map(|r| r.min);
                 *reps = common::InputField {
                     input: target_reps.map(|v| v.to_string()).unwrap_or_default(),
-                    parsed: some_or_default(*target_reps),
+                    parsed: some_or_default(target_reps),
                     orig: reps.orig.clone(),
+                    error: None,
                 };
                 *time = common::InputField {
                     input: target_time.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*target_time),
                     orig: time.orig.clone(),
+                    error: None,
                 };
                 *weight = common::InputField {
                     input: target_weight.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*target_weight),
                     orig: weight.orig.clone(),
+                    error: None,
                 };
+                // A range does not determine a single value
+                let target_rpe = target_rpe.and_then(|v| v.value());
                 *rpe = common::InputField {
                     input: target_rpe.map(|v| v.to_string()).unwrap_or_default(),
-                    parsed: some_or_default(*target_rpe),
+                    parsed: some_or_default(target_rpe),
                     orig: rpe.orig.clone(),
+                    error: None,
                 };
             }
         }
@@ -899,21 +1374,25 @@ pub fn update(
                     input: prev_reps.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*prev_reps),
                     orig: reps.orig.clone(),
+                    error: None,
                 };
                 *time = common::InputField {
                     input: prev_time.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*prev_time),
                     orig: time.orig.clone(),
+                    error: None,
                 };
                 *weight = common::InputField {
                     input: prev_weight.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*prev_weight),
                     orig: weight.orig.clone(),
+                    error: None,
                 };
                 *rpe = common::InputField {
                     input: prev_rpe.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*prev_rpe),
                     orig: rpe.orig.clone(),
+                    error: None,
                 };
             }
         }
@@ -934,66 +1413,173 @@ pub fn update(
                     input: prev_set_reps.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*prev_set_reps),
                     orig: reps.orig.clone(),
+                    error: None,
                 };
                 *time = common::InputField {
                     input: prev_set_time.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*prev_set_time),
                     orig: time.orig.clone(),
+                    error: None,
                 };
                 *weight = common::InputField {
                     input: prev_set_weight.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*prev_set_weight),
                     orig: weight.orig.clone(),
+                    error: None,
                 };
                 *rpe = common::InputField {
                     input: prev_set_rpe.map(|v| v.to_string()).unwrap_or_default(),
                     parsed: some_or_default(*prev_set_rpe),
                     orig: rpe.orig.clone(),
+                    error: None,
                 };
             }
         }
-
-        Msg::StartGuidedTrainingSession => {
-            model.guide = Some(Guide::new(data_model.settings.beep_volume));
-            update_guide(model);
-            store_guide_state(model, orders);
-            update_streams(model, orders);
-            orders.notify(data::Msg::StartTrainingSession(model.training_session_id));
-            update_metronome(model, orders, data_model.settings.automatic_metronome);
-            show_element_notification(
-                model,
-                data_model.settings.notifications,
-                data_model.settings.show_rpe,
-                data_model.settings.show_tut,
-            );
-            Url::go_and_push(
-                &crate::Urls::new(&data_model.base_url)
-                    .training_session()
-                    .add_hash_path_part(model.training_session_id.to_string())
-                    .add_hash_path_part("guide"),
-            );
-        }
-        Msg::ContinueGuidedTrainingSession(ongoing_training_session) => {
-            model.guide = Some(Guide::from_ongoing_training_session(
-                ongoing_training_session.element_idx,
-                ongoing_training_session.element_start_time,
-                data_model.settings.beep_volume,
-            ));
-            model
-                .guide
-                .as_mut()
-                .unwrap()
-                .timer
-                .restore(ongoing_training_session.timer_state);
-            update_metronome(model, orders, data_model.settings.automatic_metronome);
-            update_streams(model, orders);
-            show_element_notification(
-                model,
-                data_model.settings.notifications,
-                data_model.settings.show_rpe,
-                data_model.settings.show_tut,
-            );
-            orders.force_render_now().send_msg(Msg::ScrollToSection);
+        Msg::EnterSuggestedValues(element_idx, exercise_idx) => {
+            if let FormElement::Set { exercises } = &mut model.form.elements[element_idx] {
+                let ExerciseForm {
+                    reps,
+                    time,
+                    weight,
+                    rpe,
+                    suggestion,
+                    ..
+                } = &mut exercises[exercise_idx];
+                *reps = common::InputField {
+                    input: suggestion.reps.map(|v| v.to_string()).unwrap_or_default(),
+                    parsed: some_or_default(suggestion.reps),
+                    orig: reps.orig.clone(),
+                    error: None,
+                };
+                *time = common::InputField {
+                    input: suggestion.time.map(|v| v.to_string()).unwrap_or_default(),
+                    parsed: some_or_default(suggestion.time),
+                    orig: time.orig.clone(),
+                    error: None,
+                };
+                *weight = common::InputField {
+                    input: suggestion.weight.map(|v| v.to_string()).unwrap_or_default(),
+                    parsed: some_or_default(suggestion.weight),
+                    orig: weight.orig.clone(),
+                    error: None,
+                };
+                let suggested_rpe = suggestion.rpe.and_then(|v| v.value());
+                *rpe = common::InputField {
+                    input: suggested_rpe.map(|v| v.to_string()).unwrap_or_default(),
+                    parsed: some_or_default(suggested_rpe),
+                    orig: rpe.orig.clone(),
+                    error: None,
+                };
+            }
+        }
+        Msg::FillFromPreviousSession => {
+            fill_from_previous_session(&mut model.form.elements);
+        }
+        Msg::FocusInput(element_idx, exercise_idx, input) => {
+            if let Some(element) = web_sys::window()
+                .and_then(|window| window.document())
+                .and_then(|document| {
+                    document.get_element_by_id(&input_id(element_idx, exercise_idx, input))
+                })
+                .and_then(|element| element.dyn_into::<web_sys::HtmlInputElement>().ok())
+            {
+                let _ = element.focus();
+                element.select();
+            }
+        }
+        Msg::KeyDown(event) => {
+            let Some(guide) = &model.guide else {
+                return;
+            };
+            let editing_text = event
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                .is_some_and(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA"));
+            if editing_text
+                || event.ctrl_key()
+                || event.alt_key()
+                || event.meta_key()
+                || not(matches!(model.dialog, Dialog::Hidden))
+            {
+                return;
+            }
+            match event.key().as_str() {
+                "n" | "N" => {
+                    orders.send_msg(Msg::GoToNextSection);
+                }
+                "p" | "P" if guide.session.element_idx > 0 => {
+                    orders.send_msg(Msg::GoToPreviousSection);
+                }
+                _ => {}
+            }
+        }
+
+        Msg::StartGuidedTrainingSession => {
+            if data_model
+                .training_sessions
+                .get(&model.training_session_id)
+                .is_some_and(|t| t.start_time.is_none())
+            {
+                orders.notify(data::Msg::ModifyTrainingSession(
+                    model.training_session_id,
+                    None,
+                    None,
+                    Some(now()),
+                    None,
+                ));
+            }
+            model.guide = Some(Guide::new(
+                model.training_session_id,
+                data_model.settings.beep_volume,
+                data_model.settings.sound_profile,
+                data_model.settings.vibration,
+            ));
+            if let Some(guide) = &mut model.guide {
+                guide.update_wake_lock(data_model.settings.keep_screen_awake);
+            }
+            update_guide(model);
+            store_guide_state(model, orders);
+            update_streams(model, orders);
+            update_metronome(model, orders, data_model.settings.automatic_metronome);
+            show_element_notification(
+                model,
+                data_model.settings.notifications,
+                data_model.settings.show_rpe,
+                data_model.settings.show_tut,
+            );
+            announce_element(model, &data_model.settings);
+            Url::go_and_push(
+                &crate::Urls::new(&data_model.base_url)
+                    .training_session()
+                    .add_hash_path_part(model.training_session_id.to_string())
+                    .add_hash_path_part("guide"),
+            );
+        }
+        Msg::ContinueGuidedTrainingSession(ongoing_training_session) => {
+            model.guide = Some(Guide::from_ongoing_training_session(
+                ongoing_training_session,
+                data_model.settings.beep_volume,
+                data_model.settings.sound_profile,
+                data_model.settings.vibration,
+            ));
+            if let Some(guide) = &mut model.guide {
+                guide.update_wake_lock(data_model.settings.keep_screen_awake);
+            }
+            if data_model
+                .training_sessions
+                .contains_key(&model.training_session_id)
+            {
+                check_guide(model, orders);
+            }
+            update_metronome(model, orders, data_model.settings.automatic_metronome);
+            update_streams(model, orders);
+            show_element_notification(
+                model,
+                data_model.settings.notifications,
+                data_model.settings.show_rpe,
+                data_model.settings.show_tut,
+            );
+            orders.force_render_now().send_msg(Msg::ScrollToSection);
             Url::go_and_push(
                 &crate::Urls::new(&data_model.base_url)
                     .training_session()
@@ -1003,7 +1589,7 @@ pub fn update(
         }
         Msg::UpdateGuidedTrainingSession => {
             if let Some(guide) = &mut model.guide {
-                match &model.form.elements.get(guide.element_idx) {
+                match guide_element(guide, &model.form.elements) {
                     Some(FormElement::Set { exercises }) => {
                         let exercise = &exercises[0];
                         if not(show_guide_timer(exercise)) {
@@ -1013,13 +1599,13 @@ pub fn update(
                                 if time <= 0 {
                                     if let Some(target_reps) = exercise.target_reps {
                                         orders.send_msg(Msg::RepsChanged(
-                                            guide.element_idx,
+                                            guide.session.element_idx,
                                             0,
-                                            target_reps.to_string(),
+                                            target_reps.max.to_string(),
                                         ));
                                     }
                                     orders.send_msg(Msg::TimeChanged(
-                                        guide.element_idx,
+                                        guide.session.element_idx,
                                         0,
                                         target_time.to_string(),
                                     ));
@@ -1045,16 +1631,41 @@ pub fn update(
         Msg::StartPauseGuideTimer => {
             if let Some(guide) = &mut model.guide {
                 guide.timer.start_pause();
-                orders.notify(data::Msg::UpdateTrainingSession(
-                    guide.element_idx,
-                    guide.timer.to_timer_state(),
-                ));
+                if guide.timer.is_active() {
+                    guide.session.resume(Utc::now());
+                } else {
+                    guide.session.pause(Utc::now());
+                }
             }
+            store_guide_state(model, orders);
             update_streams(model, orders);
         }
+        Msg::NotificationAction(selected) => match &mut model.guide {
+            Some(guide) => {
+                if guide.session.training_session_id == selected.training_session_id {
+                    match selected.action {
+                        web_app::service_worker::NotificationAction::GoToNextSection => {
+                            orders.send_msg(Msg::GoToNextSection);
+                        }
+                        web_app::service_worker::NotificationAction::ExtendTimer => {
+                            if let Some(time) = guide.timer.time.1 {
+                                guide.timer.set(time + 30);
+                                store_guide_state(model, orders);
+                            }
+                        }
+                    }
+                }
+            }
+            None => {
+                orders.notify(data::Msg::ApplyNotificationAction(selected));
+            }
+        },
         Msg::GoToPreviousSection => {
             if let Some(guide) = &mut model.guide {
-                let mut element_idx = guide.element_idx - 1;
+                let mut element_idx = clamp_element_idx(
+                    guide.session.element_idx.saturating_sub(1),
+                    &model.form.elements,
+                );
                 while if let Some(FormElement::Rest {
                     target_time,
                     automatic,
@@ -1066,28 +1677,23 @@ pub fn update(
                 } {
                     element_idx -= 1;
                 }
-                guide.element_idx = element_idx;
-                guide.element_start_time = Utc::now();
+                guide.session.go_to_element(element_idx, Utc::now());
             }
             update_guide(model);
             store_guide_state(model, orders);
             update_metronome(model, orders, data_model.settings.automatic_metronome);
             update_streams(model, orders);
             close_notifications();
+            announce_element(model, &data_model.settings);
             orders.force_render_now().send_msg(Msg::ScrollToSection);
         }
         Msg::GoToNextSection => {
             if let Some(guide) = &mut model.guide {
-                let element_idx = guide.element_idx + 1;
-                if element_idx == model.form.elements.len() {
-                    model.guide = None;
-                    close_notifications();
-                    orders
-                        .send_msg(Msg::PauseMetronome)
-                        .notify(data::Msg::EndTrainingSession);
+                let element_idx = guide.session.element_idx + 1;
+                if element_idx >= model.form.elements.len() {
+                    end_guide(model, orders);
                 } else {
-                    guide.element_idx = element_idx;
-                    guide.element_start_time = Utc::now();
+                    guide.session.go_to_element(element_idx, Utc::now());
                     update_metronome(model, orders, data_model.settings.automatic_metronome);
                     show_element_notification(
                         model,
@@ -1095,6 +1701,7 @@ pub fn update(
                         data_model.settings.show_rpe,
                         data_model.settings.show_tut,
                     );
+                    announce_element(model, &data_model.settings);
                 }
             }
             update_guide(model);
@@ -1111,7 +1718,11 @@ pub fn update(
         Msg::ScrollToSection => {
             if let Some(guide) = &mut model.guide {
                 let options = web_sys::ScrollIntoViewOptions::new();
-                options.set_behavior(web_sys::ScrollBehavior::Smooth);
+                options.set_behavior(if common::reduced_motion() {
+                    web_sys::ScrollBehavior::Instant
+                } else {
+                    web_sys::ScrollBehavior::Smooth
+                });
                 options.set_block(web_sys::ScrollLogicalPosition::Center);
                 if let Some(element) = guide.element.get() {
                     element.scroll_into_view_with_scroll_into_view_options(&options);
@@ -1119,6 +1730,76 @@ pub fn update(
             }
         }
 
+        Msg::RepeatTrainingSession => {
+            model.repeating = true;
+            orders.notify(data::Msg::RepeatTrainingSession(model.training_session_id));
+        }
+        Msg::CopyTrainingSession => {
+            if let Some(text) = share_text(model, data_model) {
+                match web_app::share::copy_to_clipboard(&text) {
+                    Ok(promise) => {
+                        orders.perform_cmd(async move {
+                            match wasm_bindgen_futures::JsFuture::from(promise).await {
+                                Ok(_) => Some(Msg::TrainingSessionCopied),
+                                Err(err) => {
+                                    error!("failed to copy training session:", err);
+                                    None
+                                }
+                            }
+                        });
+                    }
+                    Err(err) => error!("failed to copy training session:", err),
+                }
+            }
+        }
+        Msg::TrainingSessionCopied => {
+            model.copied = true;
+        }
+        Msg::ShareTrainingSession => {
+            if let Some(text) = share_text(model, data_model) {
+                match web_app::share::share("Training session", &text) {
+                    Ok(promise) => {
+                        orders.perform_cmd(async move {
+                            // The promise is also rejected if the user cancels sharing
+                            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+                        });
+                    }
+                    Err(err) => error!("failed to share training session:", err),
+                }
+            }
+        }
+        Msg::ShowSaveAsRoutineDialog => {
+            model.dialog = Dialog::SaveAsRoutine(common::InputField {
+                input: String::new(),
+                parsed: None,
+                orig: String::new(),
+                error: None,
+            });
+        }
+        Msg::RoutineNameChanged(name) => {
+            if let Dialog::SaveAsRoutine(ref mut field) = model.dialog {
+                let trimmed_name = name.trim();
+                field.parsed = if not(trimmed_name.is_empty())
+                    && data_model.routines.values().all(|r| r.name != trimmed_name)
+                {
+                    Some(trimmed_name.to_string())
+                } else {
+                    None
+                };
+                field.input = name;
+            }
+        }
+        Msg::SaveAsRoutine => {
+            if let Dialog::SaveAsRoutine(ref field) = model.dialog {
+                if let Some(name) = &field.parsed {
+                    model.loading = true;
+                    orders.notify(data::Msg::CreateRoutineFromTrainingSession(
+                        name.clone(),
+                        model.training_session_id,
+                    ));
+                }
+            }
+        }
         Msg::EditTrainingSession => {
             model.editing = true;
             Url::go_and_push(
@@ -1129,66 +1810,124 @@ pub fn update(
             );
         }
         Msg::SaveTrainingSession => {
-            model.loading = true;
+            let (start_time, end_time) = match (
+                model.duration.changed(),
+                model.duration.parsed,
+                data_model.training_sessions.get(&model.training_session_id),
+            ) {
+                (true, Some(minutes), Some(training_session)) if minutes > 0 => {
+                    let start_time = training_session
+                        .start_time
+                        .unwrap_or_else(|| training_session.date.and_time(NaiveTime::MIN));
+                    (
+                        Some(start_time),
+                        Some(start_time + Duration::minutes(i64::from(minutes))),
+                    )
+                }
+                _ => (None, None),
+            };
             orders.notify(data::Msg::ModifyTrainingSession(
                 model.training_session_id,
                 Some(model.form.notes.clone()),
-                Some(
-                    model
-                        .form
-                        .elements
-                        .iter()
-                        .flat_map(|e| match e {
-                            FormElement::Set { exercises } => exercises
-                                .iter()
-                                .map(|e| domain::TrainingSessionElement::Set {
-                                    exercise_id: e.exercise_id,
-                                    reps: e.reps.parsed.filter(|reps| *reps > 0),
-                                    time: e.time.parsed.filter(|time| *time > 0),
-                                    weight: e.weight.parsed.filter(|weight| *weight > 0.0),
-                                    rpe: e.rpe.parsed.filter(|rpe| *rpe > 0.0),
-                                    target_reps: e.target_reps,
-                                    target_time: e.target_time,
-                                    target_weight: e.target_weight,
-                                    target_rpe: e.target_rpe,
-                                    automatic: e.automatic,
-                                })
-                                .collect(),
-                            FormElement::Rest {
-                                target_time,
-                                automatic,
-                            } => vec![domain::TrainingSessionElement::Rest {
-                                target_time: if *target_time > 0 {
-                                    Some(*target_time)
-                                } else {
-                                    None
-                                },
-                                automatic: *automatic,
-                            }],
-                        })
-                        .collect::<Vec<_>>(),
-                ),
+                Some(training_session_elements(&model.form.elements)),
+                start_time,
+                end_time,
             ));
         }
+        Msg::Retry => {
+            orders.notify(data::Msg::ReadTrainingSessions);
+        }
         Msg::DataEvent(event) => {
             match event {
-                data::Event::DataChanged | data::Event::TrainingSessionModifiedOk => {
-                    model.form = init_form(
+                data::Event::TrainingSessionsChanged(_)
+                | data::Event::ExercisesChanged(_)
+                | data::Event::TrainingSessionModifiedOk
+                | data::Event::TrainingSessionModifiedErr => {
+                    read_exercise_images(
+                        data_model.training_sessions.get(&model.training_session_id),
+                        orders,
+                    );
+                    let mut form = init_form(
                         data_model.training_sessions.get(&model.training_session_id),
                         data_model,
                     );
-                    model.loading = false;
+                    restore_unsaved_changes(
+                        &mut form,
+                        &model.form,
+                        matches!(event, data::Event::TrainingSessionModifiedErr),
+                    );
+                    model.form = form;
+                    if not(model.duration.changed())
+                        || matches!(event, data::Event::TrainingSessionModifiedOk)
+                    {
+                        model.duration = init_duration(
+                            data_model.training_sessions.get(&model.training_session_id),
+                        );
+                    }
+                    if matches!(event, data::Event::TrainingSessionModifiedOk) {
+                        model.draft_restored = false;
+                    }
+                    if model.draft.is_some() {
+                        restore_draft(model, data_model, orders);
+                    } else if matches!(event, data::Event::TrainingSessionModifiedOk)
+                        || model.has_unsaved_changes()
+                    {
+                        store_draft(model, data_model, orders);
+                    }
+                    if data_model
+                        .training_sessions
+                        .contains_key(&model.training_session_id)
+                    {
+                        check_guide(model, orders);
+                    }
                     update_guide(model);
                     update_streams(model, orders);
                 }
-                data::Event::TrainingSessionModifiedErr => {
-                    model.loading = false;
-                    update_guide(model);
-                    update_streams(model, orders);
+                data::Event::TrainingSessionDraftRead(draft) => {
+                    if draft.training_session.id == model.training_session_id {
+                        model.draft = Some(draft);
+                        restore_draft(model, data_model, orders);
+                    }
                 }
                 data::Event::ExerciseCreatedOk | data::Event::ExerciseCreatedErr => {
                     model.loading = false;
                 }
+                data::Event::RoutineCreatedOk => {
+                    model.loading = false;
+                    if let Dialog::SaveAsRoutine(ref field) = model.dialog {
+                        if let Some(routine) = field
+                            .parsed
+                            .as_ref()
+                            .and_then(|name| data_model.routines.values().find(|r| &r.name == name))
+                        {
+                            orders.request_url(
+                                crate::Urls::new(&data_model.base_url)
+                                    .routine()
+                                    .add_hash_path_part(routine.id.to_string()),
+                            );
+                        }
+                        model.dialog = Dialog::Hidden;
+                    }
+                }
+                data::Event::RoutineCreatedErr => {
+                    model.loading = false;
+                }
+                data::Event::TrainingSessionCreatedOk if model.repeating => {
+                    model.repeating = false;
+                    if let Some((training_session_id, _)) =
+                        data_model.training_sessions.last_key_value()
+                    {
+                        orders.request_url(
+                            crate::Urls::new(&data_model.base_url)
+                                .training_session()
+                                .add_hash_path_part(training_session_id.to_string())
+                                .add_hash_path_part("edit"),
+                        );
+                    }
+                }
+                data::Event::TrainingSessionCreatedErr => {
+                    model.repeating = false;
+                }
                 data::Event::BeepVolumeChanged => {
                     model.smt.metronome.beep_volume = data_model.settings.beep_volume;
                     model.smt.timer.beep_volume = data_model.settings.beep_volume;
@@ -1196,6 +1935,25 @@ pub fn update(
                         guide.timer.beep_volume = data_model.settings.beep_volume;
                     }
                 }
+                data::Event::SoundProfileChanged => {
+                    model.smt.metronome.sound_profile = data_model.settings.sound_profile;
+                    model.smt.timer.sound_profile = data_model.settings.sound_profile;
+                    if let Some(guide) = &mut model.guide {
+                        guide.timer.sound_profile = data_model.settings.sound_profile;
+                    }
+                }
+                data::Event::VibrationChanged => {
+                    model.smt.metronome.vibration = data_model.settings.vibration;
+                    model.smt.timer.vibration = data_model.settings.vibration;
+                    if let Some(guide) = &mut model.guide {
+                        guide.timer.vibration = data_model.settings.vibration;
+                    }
+                }
+                data::Event::KeepScreenAwakeChanged => {
+                    if let Some(guide) = &mut model.guide {
+                        guide.update_wake_lock(data_model.settings.keep_screen_awake);
+                    }
+                }
                 _ => {}
             };
         }
@@ -1224,7 +1982,10 @@ pub fn update(
                     false,
                     false,
                     false,
-                    domain::ExerciseFilter { muscles },
+                    domain::ExerciseFilter {
+                        muscles,
+                        archived: false,
+                    },
                 ),
             );
         }
@@ -1241,6 +2002,7 @@ pub fn update(
             ));
         }
         Msg::ReplaceExercise(element_idx, exercise_idx, new_exercise_id) => {
+            store_undo_snapshot(model, orders);
             replace_exercise(
                 &mut model.form.elements,
                 element_idx,
@@ -1260,6 +2022,7 @@ pub fn update(
                 .send_msg(Msg::CloseDialog);
         }
         Msg::DeferExercise(element_idx) => {
+            store_undo_snapshot(model, orders);
             defer_exercise(&mut model.form.elements, element_idx);
             update_metronome(model, orders, data_model.settings.automatic_metronome);
             orders
@@ -1296,13 +2059,19 @@ pub fn update(
                 .send_msg(Msg::CloseDialog);
         }
         Msg::RemoveSet(element_idx) => {
+            store_undo_snapshot(model, orders);
             remove_set(&mut model.form.elements, element_idx);
+            check_guide(model, orders);
+            store_guide_state(model, orders);
             orders
                 .send_msg(Msg::SaveTrainingSession)
                 .send_msg(Msg::CloseDialog);
         }
         Msg::RemoveExercise(element_idx, exercise_idx) => {
+            store_undo_snapshot(model, orders);
             remove_exercise(&mut model.form.elements, element_idx, exercise_idx);
+            check_guide(model, orders);
+            store_guide_state(model, orders);
             orders
                 .send_msg(Msg::SaveTrainingSession)
                 .send_msg(Msg::CloseDialog);
@@ -1313,12 +2082,34 @@ pub fn update(
                 .send_msg(Msg::SaveTrainingSession)
                 .send_msg(Msg::CloseDialog);
         }
+        Msg::Undo => {
+            if let Some(elements) = model.undo_stack.pop() {
+                model.form.elements = elements;
+                check_guide(model, orders);
+                update_guide(model);
+                store_guide_state(model, orders);
+                update_metronome(model, orders, data_model.settings.automatic_metronome);
+                orders.send_msg(Msg::SaveTrainingSession);
+            }
+            model.undo_handle = if model.undo_stack.is_empty() {
+                None
+            } else {
+                Some(orders.perform_cmd_with_handle(cmds::timeout(UNDO_TIMEOUT, || Msg::HideUndo)))
+            };
+        }
+        Msg::HideUndo => {
+            model.undo_handle = None;
+        }
         Msg::CloseDialog => {
             model.dialog = Dialog::Hidden;
         }
 
         Msg::ExerciseList(msg) => match &mut model.dialog {
-            Dialog::Hidden | Dialog::StopwatchMetronomTimer | Dialog::Options(_, _) => {}
+            Dialog::Hidden
+            | Dialog::StopwatchMetronomTimer
+            | Dialog::Options(_, _)
+            | Dialog::SaveAsRoutine(_)
+            | Dialog::Summary => {}
             Dialog::ReplaceExercise(element_idx, exercise_idx, exercise_list_model) => {
                 match component::exercise_list::update(
                     msg,
@@ -1329,6 +2120,7 @@ pub fn update(
                     | component::exercise_list::OutMsg::EditClicked(_)
                     | component::exercise_list::OutMsg::DeleteClicked(_) => {}
                     component::exercise_list::OutMsg::CreateClicked(name) => {
+                        model.loading = true;
                         orders.notify(data::Msg::CreateExercise(name.trim().to_string(), vec![]));
                     }
                     component::exercise_list::OutMsg::Selected(exercise_id) => {
@@ -1350,6 +2142,7 @@ pub fn update(
                     | component::exercise_list::OutMsg::EditClicked(_)
                     | component::exercise_list::OutMsg::DeleteClicked(_) => {}
                     component::exercise_list::OutMsg::CreateClicked(name) => {
+                        model.loading = true;
                         orders.notify(data::Msg::CreateExercise(name.trim().to_string(), vec![]));
                     }
                     component::exercise_list::OutMsg::Selected(exercise_id) => {
@@ -1367,6 +2160,7 @@ pub fn update(
                     | component::exercise_list::OutMsg::EditClicked(_)
                     | component::exercise_list::OutMsg::DeleteClicked(_) => {}
                     component::exercise_list::OutMsg::CreateClicked(name) => {
+                        model.loading = true;
                         orders.notify(data::Msg::CreateExercise(name.trim().to_string(), vec![]));
                     }
                     component::exercise_list::OutMsg::Selected(exercise_id) => {
@@ -1377,26 +2171,47 @@ pub fn update(
         },
 
         Msg::UpdateStopwatchMetronomTimer => {
-            model.smt.stopwatch.update();
+            model.smt.stopwatch.update(Utc::now());
+            if let Some(set_timer) = &mut model.set_timer {
+                set_timer.stopwatch.update(Utc::now());
+            }
             model.smt.metronome.update(&model.audio_context);
             model.smt.timer.update(&model.audio_context);
         }
 
         Msg::StartPauseStopwatch => {
-            model.smt.stopwatch.start_pause();
+            model.smt.stopwatch.start_pause(Utc::now());
+            update_streams(model, orders);
+        }
+        Msg::ToggleSetTimer(element_idx, exercise_idx) => {
+            let previous = stop_set_timer(model, orders);
+            if previous != Some((element_idx, exercise_idx)) {
+                if let Some(exercise_id) =
+                    set_exercise_id(&model.form.elements, element_idx, exercise_idx)
+                {
+                    let mut stopwatch = web_app::stopwatch::Stopwatch::default();
+                    stopwatch.start(Utc::now());
+                    model.set_timer = Some(SetTimer {
+                        element_idx,
+                        exercise_idx,
+                        exercise_id,
+                        stopwatch,
+                    });
+                }
+            }
             update_streams(model, orders);
         }
         Msg::ResetStopwatch => {
-            model.smt.stopwatch.reset();
+            model.smt.stopwatch.reset(Utc::now());
         }
         Msg::ToggleStopwatch => {
-            model.smt.stopwatch.toggle();
+            model.smt.stopwatch.toggle(Utc::now());
             update_streams(model, orders);
         }
 
         Msg::StartMetronome(interval) => {
-            model.smt.metronome.interval = interval;
-            model.smt.metronome.stressed_beat = 1;
+            model.smt.metronome.state.set_interval(interval);
+            model.smt.metronome.state.set_stressed_beat(1);
             model.smt.metronome.start(&model.audio_context);
             update_streams(model, orders);
         }
@@ -1409,16 +2224,29 @@ pub fn update(
             update_streams(model, orders);
         }
         Msg::MetronomeIntervalChanged(interval) => {
-            model.smt.metronome.interval = interval.parse::<u32>().unwrap_or(1);
+            if let Ok(interval) = interval.parse::<u32>() {
+                if web_app::METRONOME_INTERVAL_RANGE.contains(&interval) {
+                    model.smt.metronome.state.set_interval(interval);
+                }
+            }
         }
         Msg::MetronomeStressChanged(stressed_beat) => {
-            model.smt.metronome.stressed_beat = stressed_beat.parse::<u32>().unwrap_or(1);
+            if let Ok(stressed_beat) = stressed_beat.parse::<u32>() {
+                if web_app::METRONOME_STRESSED_BEAT_RANGE.contains(&stressed_beat) {
+                    model.smt.metronome.state.set_stressed_beat(stressed_beat);
+                }
+            }
         }
 
         Msg::StartPauseTimer => {
             model.smt.timer.start_pause();
             update_streams(model, orders);
         }
+        Msg::StartTimerPreset(time) => {
+            model.smt.timer.set(i64::from(time));
+            model.smt.timer.start();
+            update_streams(model, orders);
+        }
         Msg::ResetTimer => {
             model.smt.timer.reset();
         }
@@ -1430,6 +2258,78 @@ pub fn update(
             Err(_) => model.smt.timer.time = (time, None),
         },
     }
+
+    if form_input {
+        store_draft(model, data_model, orders);
+    }
+}
+
+/// Apply the draft read from the storage as soon as the training session is loaded.
+///
+/// The draft is discarded if the training session has been changed since the draft was created.
+fn restore_draft(model: &mut Model, data_model: &data::Model, orders: &mut impl Orders<Msg>) {
+    let Some(training_session) = data_model.training_sessions.get(&model.training_session_id)
+    else {
+        return;
+    };
+    if let Some(draft) = model.draft.take() {
+        if draft.is_applicable(training_session) {
+            apply_draft(&mut model.form, &mut model.duration, &draft);
+            model.draft_restored = true;
+        } else {
+            orders.notify(data::Msg::WriteTrainingSessionDraft(
+                model.training_session_id,
+                None,
+            ));
+        }
+    }
+}
+
+/// Store the unsaved changes, so that they are not lost if the app is closed before saving.
+fn store_draft(model: &Model, data_model: &data::Model, orders: &mut impl Orders<Msg>) {
+    if model.draft.is_some() {
+        return;
+    }
+    if let Some(training_session) = data_model.training_sessions.get(&model.training_session_id) {
+        orders.notify(data::Msg::WriteTrainingSessionDraft(
+            model.training_session_id,
+            create_draft(&model.form, &model.duration, training_session),
+        ));
+    }
+}
+
+/// Stop the running set timer and enter the elapsed time into the corresponding set.
+///
+/// The time is not entered if the set has been removed or replaced in the meantime. The positions
+/// of the set are returned if a set timer was running.
+fn stop_set_timer(model: &mut Model, orders: &mut impl Orders<Msg>) -> Option<(usize, usize)> {
+    let mut set_timer = model.set_timer.take()?;
+    set_timer.stopwatch.pause(Utc::now());
+    if set_exercise_id(
+        &model.form.elements,
+        set_timer.element_idx,
+        set_timer.exercise_idx,
+    ) == Some(set_timer.exercise_id)
+    {
+        orders.send_msg(Msg::TimeChanged(
+            set_timer.element_idx,
+            set_timer.exercise_idx,
+            set_timer.stopwatch.seconds().to_string(),
+        ));
+    }
+    Some((set_timer.element_idx, set_timer.exercise_idx))
+}
+
+fn set_exercise_id(
+    elements: &[FormElement],
+    element_idx: usize,
+    exercise_idx: usize,
+) -> Option<u32> {
+    if let Some(FormElement::Set { exercises }) = elements.get(element_idx) {
+        exercises.get(exercise_idx).map(|e| e.exercise_id)
+    } else {
+        None
+    }
 }
 
 fn update_streams(model: &mut Model, orders: &mut impl Orders<Msg>) {
@@ -1444,6 +2344,7 @@ fn update_streams(model: &mut Model, orders: &mut impl Orders<Msg>) {
             }
     };
     model.timer_stream = if model.smt.stopwatch.is_active()
+        || model.set_timer.is_some()
         || model.smt.metronome.is_active()
         || model.smt.timer.is_active()
     {
@@ -1462,8 +2363,8 @@ fn update_guide(model: &mut Model) {
 
     if let Some(guide) = &mut model.guide {
         guide.timer.unset();
-        let elapsed_time = (Utc::now() - guide.element_start_time).num_seconds();
-        match &model.form.elements.get(guide.element_idx) {
+        let elapsed_time = guide.session.elapsed_time(Utc::now()).num_seconds();
+        match guide_element(guide, &model.form.elements) {
             Some(FormElement::Set { exercises }) => {
                 let exercise = &exercises[0];
                 if not(show_guide_timer(exercise)) {
@@ -1471,7 +2372,7 @@ fn update_guide(model: &mut Model) {
                 }
                 if let Some(target_time) = exercise.target_time {
                     let target_time = if let Some(target_reps) = exercise.target_reps {
-                        target_time * target_reps
+                        target_time * target_reps.max
                     } else {
                         target_time
                     };
@@ -1487,33 +2388,111 @@ fn update_guide(model: &mut Model) {
                     guide.timer.start();
                 }
             }
-            None => {
-                model.guide = None;
-            }
+            None => {}
         }
     }
 }
 
+fn store_undo_snapshot(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    push_undo_snapshot(&mut model.undo_stack, &model.form.elements);
+    model.undo_handle =
+        Some(orders.perform_cmd_with_handle(cmds::timeout(UNDO_TIMEOUT, || Msg::HideUndo)));
+}
+
 fn store_guide_state(model: &mut Model, orders: &mut impl Orders<Msg>) {
     if let Some(guide) = &mut model.guide {
-        orders.notify(data::Msg::UpdateTrainingSession(
-            guide.element_idx,
-            guide.timer.to_timer_state(),
-        ));
+        guide.session.timer_state = guide.timer.to_timer_state();
+        orders.notify(data::Msg::UpdateTrainingSession(guide.session.clone()));
+    }
+}
+
+/// Return the element the guide is pointing to.
+///
+/// The index of the guide can get out of range if elements are removed or the training session is
+/// changed on another device. All accesses of the current element must therefore be checked.
+fn guide_element<'a>(guide: &Guide, elements: &'a [FormElement]) -> Option<&'a FormElement> {
+    elements.get(guide.session.element_idx)
+}
+
+/// Estimate the remaining duration of the elements starting at the given index.
+///
+/// The duration of a set is based on its target reps and target time, like the timer of the
+/// guide. A default time is assumed for each rep of a set without target time, and a single rep is
+/// assumed for a set without target reps. The elapsed time of the element at the given index is
+/// subtracted from its estimated duration.
+fn estimated_remaining_duration(
+    elements: &[FormElement],
+    element_idx: usize,
+    elapsed_time: Duration,
+) -> Duration {
+    let mut durations = elements.iter().skip(element_idx).map(estimated_duration);
+    let Some(current) = durations.next() else {
+        return Duration::zero();
+    };
+    (current - elapsed_time).max(Duration::zero()) + durations.sum::<Duration>()
+}
+
+fn estimated_duration(element: &FormElement) -> Duration {
+    match element {
+        FormElement::Set { exercises } => exercises
+            .iter()
+            .map(|e| {
+                let reps = e.target_reps.map(|r| r.max).filter(|r| *r > 0).unwrap_or(1);
+                let time = e
+                    .target_time
+                    .filter(|t| *t > 0)
+                    .unwrap_or(domain::DEFAULT_REP_TIME);
+                Duration::seconds(i64::from(reps * time))
+            })
+            .sum(),
+        FormElement::Rest { target_time, .. } => Duration::seconds(i64::from(*target_time)),
     }
 }
 
+/// Clamp the index of the guide to the existing elements or end the guided training session if
+/// there are no elements left.
+fn check_guide(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    let Some(guide) = &mut model.guide else {
+        return;
+    };
+    match checked_element_idx(guide.session.element_idx, &model.form.elements) {
+        Some(element_idx) => guide.session.element_idx = element_idx,
+        None => end_guide(model, orders),
+    }
+}
+
+fn end_guide(model: &mut Model, orders: &mut impl Orders<Msg>) {
+    model.guide = None;
+    model.dialog = Dialog::Summary;
+    close_notifications();
+    web_app::speech::Synthesizer::cancel(&web_app::speech::Window);
+    orders
+        .send_msg(Msg::PauseMetronome)
+        .notify(data::Msg::ReadOlderTrainingSessions(None))
+        .notify(data::Msg::ModifyTrainingSession(
+            model.training_session_id,
+            None,
+            None,
+            None,
+            Some(now()),
+        ))
+        .notify(data::Msg::EndTrainingSession);
+}
+
+/// Current time in UTC without fractional seconds.
+fn now() -> NaiveDateTime {
+    let now = Utc::now().naive_utc();
+    now.with_nanosecond(0).unwrap_or(now)
+}
+
 fn update_metronome(model: &Model, orders: &mut impl Orders<Msg>, automatic_metronome: bool) {
     if model.form.elements.is_empty() || not(automatic_metronome) {
         return;
     }
 
     if let Some(guide) = &model.guide {
-        if guide.element_idx >= model.form.elements.len() {
-            return;
-        }
-        match &model.form.elements[guide.element_idx] {
-            FormElement::Set { exercises } => {
+        match guide_element(guide, &model.form.elements) {
+            Some(FormElement::Set { exercises }) => {
                 let exercise = &exercises[0];
                 if exercise.target_reps.is_some() {
                     if let Some(target_time) = exercise.target_time {
@@ -1521,23 +2500,37 @@ fn update_metronome(model: &Model, orders: &mut impl Orders<Msg>, automatic_metr
                     }
                 }
             }
-            FormElement::Rest { .. } => {
+            Some(FormElement::Rest { .. }) => {
                 orders.send_msg(Msg::PauseMetronome);
             }
+            None => {}
         }
     }
 }
 
-fn show_notification(title: &str, body: Option<String>) {
+fn show_notification(
+    title: &str,
+    body: Option<String>,
+    actions: Vec<web_app::service_worker::NotificationAction>,
+    training_session_id: u32,
+    element_count: usize,
+) {
     close_notifications();
     let mut options = HashMap::new();
     if let Some(body) = body {
         options.insert(String::from("body"), body);
     }
+    // The service worker opens this page if the app was closed when an action is selected
+    if let Ok(url) = window().location().href() {
+        options.insert(String::from("data"), url);
+    }
     if let Err(err) =
         web_app::service_worker::post(&web_app::service_worker::Message::ShowNotification {
             title: title.to_string(),
             options,
+            actions,
+            training_session_id,
+            element_count,
         })
     {
         error!("failed to show notification:", err);
@@ -1553,7 +2546,7 @@ fn close_notifications() {
 }
 
 fn show_element_notification(
-    model: &mut Model,
+    model: &Model,
     notifications_enabled: bool,
     show_rpe: bool,
     show_tut: bool,
@@ -1563,37 +2556,43 @@ fn show_element_notification(
         return;
     }
 
-    if let Some(guide) = &mut model.guide {
-        if guide.element_idx < model.form.elements.len() {
+    if let Some(guide) = &model.guide {
+        if let Some(element) = guide_element(guide, &model.form.elements) {
             let title;
             let body;
-            match &model.form.elements[guide.element_idx] {
+            let has_timer;
+            match element {
                 FormElement::Set { exercises } => {
                     let exercise = &exercises[0];
                     title = exercise.exercise_name.clone();
-                    let mut previously = common::format_set(
-                        exercise.prev_reps,
+                    let mut previously = web_app::format_set(
+                        exercise.prev_reps.map(domain::RepsRange::single),
+                        None,
                         exercise.prev_time,
                         show_tut,
                         exercise.prev_weight,
-                        exercise.prev_rpe,
+                        exercise.prev_rpe.map(domain::RpeRange::single),
                         show_rpe,
+                        false,
                     );
                     if not(previously.is_empty()) {
                         previously = format!("Previously:\n{previously}\n");
                     }
-                    let mut target = common::format_set(
+                    let mut target = web_app::format_set(
                         exercise.target_reps,
+                        None,
                         exercise.target_time,
                         show_tut,
                         exercise.target_weight,
                         exercise.target_rpe,
                         show_rpe,
+                        false,
                     );
                     if not(target.is_empty()) {
                         target = format!("Target:\n{target}\n");
                     }
                     body = Some(format!("{previously}{target}"));
+                    has_timer = exercise.target_time.is_some();
                 }
                 FormElement::Rest { target_time, .. } => {
                     title = String::from("Rest");
@@ -1602,14 +2601,60 @@ fn show_element_notification(
                     } else {
                         None
                     };
+                    has_timer = *target_time > 0;
                 }
             }
 
-            show_notification(&title, body);
+            let mut actions = vec![web_app::service_worker::NotificationAction::GoToNextSection];
+            if has_timer {
+                actions.push(web_app::service_worker::NotificationAction::ExtendTimer);
+            }
+
+            show_notification(
+                &title,
+                body,
+                actions,
+                guide.session.training_session_id,
+                model.form.elements.len(),
+            );
         }
     }
 }
 
+/// Announce the current section of the guide by speech output.
+fn announce_element(model: &Model, settings: &web_app::Settings) {
+    let Some(guide) = &model.guide else {
+        return;
+    };
+    let Some(element) = guide_element(guide, &model.form.elements) else {
+        return;
+    };
+    let text = match element {
+        FormElement::Set { exercises } => exercises
+            .iter()
+            .map(|e| {
+                web_app::speech::exercise_announcement(
+                    &e.exercise_name,
+                    e.target_reps,
+                    e.target_time,
+                    settings.show_tut,
+                    e.target_weight,
+                    e.target_rpe,
+                    settings.show_rpe,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(". "),
+        FormElement::Rest { target_time, .. } => web_app::speech::rest_announcement(*target_time),
+    };
+    web_app::speech::announce(
+        &web_app::speech::Window,
+        settings.announce_exercises,
+        settings.beep_volume,
+        &text,
+    );
+}
+
 fn replace_exercise(
     elements: &mut [FormElement],
     element_idx: usize,
@@ -1673,9 +2718,13 @@ fn prefer_exercise(elements: &mut Vec<FormElement>, element_idx: usize) {
             trailing_rest += 1;
         }
     }
+    let preferred_elements = preferred_section.1 + trailing_rest - preferred_section.0 + 1;
     elements[deferred_section.0..=preferred_section.1 + trailing_rest]
-        .rotate_right(preferred_section.1 + trailing_rest - preferred_section.0 + 1);
-}
+        .rotate_right(preferred_elements);
+    if trailing_rest > 0 {
+        remove_artificial_rest(elements, deferred_section.0 + preferred_elements - 1);
+    }
+}
 
 fn defer_exercise(elements: &mut Vec<FormElement>, element_idx: usize) {
     let mut deferred_ids = vec![];
@@ -1699,6 +2748,7 @@ fn defer_exercise(elements: &mut Vec<FormElement>, element_idx: usize) {
             preferred_elements = elements.len() - element_idx - deferred_elements;
         }
     }
+    let mut trailing_rest = false;
     if element_idx + deferred_elements + preferred_elements == elements.len() {
         if let Some(FormElement::Set { .. }) = elements.last() {
             elements.push(FormElement::Rest {
@@ -1706,10 +2756,27 @@ fn defer_exercise(elements: &mut Vec<FormElement>, element_idx: usize) {
                 automatic: true,
             });
             preferred_elements += 1;
+            trailing_rest = true;
         }
     }
     elements[element_idx..element_idx + deferred_elements + preferred_elements]
         .rotate_right(preferred_elements);
+    if trailing_rest {
+        remove_artificial_rest(elements, element_idx + preferred_elements - 1);
+    }
+}
+
+/// Remove the rest that was added temporarily to move the last section.
+///
+/// After the rotation, the artificial rest separates the moved sections and the session ends with
+/// the rest of the previously penultimate section. This rest is moved to the position of the
+/// artificial rest, so that the rest between both sections is kept and no rest is left at the end.
+fn remove_artificial_rest(elements: &mut Vec<FormElement>, rest_idx: usize) {
+    if rest_idx + 1 < elements.len() && matches!(elements.last(), Some(FormElement::Rest { .. })) {
+        elements.swap_remove(rest_idx);
+    } else {
+        elements.remove(rest_idx);
+    }
 }
 
 fn add_set(elements: &mut Vec<FormElement>, element_idx: usize) {
@@ -1746,6 +2813,7 @@ fn add_set(elements: &mut Vec<FormElement>, element_idx: usize) {
                         exercise_id: e.exercise_id,
                         exercise_name: e.exercise_name.to_string(),
                         reps: common::InputField::default(),
+                        partial_reps: common::InputField::default(),
                         time: common::InputField::default(),
                         weight: common::InputField::default(),
                         rpe: common::InputField::default(),
@@ -1761,7 +2829,15 @@ fn add_set(elements: &mut Vec<FormElement>, element_idx: usize) {
                         prev_set_time: None,
                         prev_set_weight: None,
                         prev_set_rpe: None,
+                        suggestion: domain::progression::Set::default(),
                         automatic: e.automatic,
+                        to_failure: false,
+                        side: e.side,
+                        to_failure_changed: false,
+                        comment: String::new(),
+                        comment_changed: false,
+                        comment_shown: false,
+                        partial_reps_shown: false,
                     })
                     .collect::<Vec<_>>(),
             },
@@ -1807,30 +2883,18 @@ fn add_exercise(
             } else if current_exercise_ids != ids {
                 break;
             }
-            exercises.insert(
-                exercise_idx + 1,
-                ExerciseForm {
-                    exercise_id: new_exercise_id,
-                    exercise_name: data_exercises[&new_exercise_id].name.clone(),
-                    reps: common::InputField::default(),
-                    time: common::InputField::default(),
-                    weight: common::InputField::default(),
-                    rpe: common::InputField::default(),
-                    target_reps: None,
-                    target_time: None,
-                    target_weight: None,
-                    target_rpe: None,
-                    prev_reps: None,
-                    prev_time: None,
-                    prev_weight: None,
-                    prev_rpe: None,
-                    prev_set_reps: None,
-                    prev_set_time: None,
-                    prev_set_weight: None,
-                    prev_set_rpe: None,
-                    automatic: false,
-                },
-            );
+            // The new exercise is added after both sides of a unilateral exercise
+            let insert_idx = if is_left_side_of_pair(exercises, exercise_idx) {
+                exercise_idx + 2
+            } else {
+                exercise_idx + 1
+            };
+            for (i, exercise) in new_exercise_forms(new_exercise_id, data_exercises)
+                .into_iter()
+                .enumerate()
+            {
+                exercises.insert(insert_idx + i, exercise);
+            }
         }
     }
 }
@@ -1885,10 +2949,26 @@ fn append_exercise(
         });
     }
     elements.push(FormElement::Set {
-        exercises: vec![ExerciseForm {
+        exercises: new_exercise_forms(exercise_id, data_exercises),
+    });
+}
+
+/// Create the forms for a new set of the exercise. Unilateral exercises get a form for each side.
+fn new_exercise_forms(
+    exercise_id: u32,
+    data_exercises: &BTreeMap<u32, domain::Exercise>,
+) -> Vec<ExerciseForm> {
+    let sides = match data_exercises[&exercise_id].laterality {
+        domain::Laterality::Bilateral => vec![None],
+        domain::Laterality::Unilateral => vec![Some(domain::Side::Left), Some(domain::Side::Right)],
+    };
+    sides
+        .into_iter()
+        .map(|side| ExerciseForm {
             exercise_id,
             exercise_name: data_exercises[&exercise_id].name.clone(),
             reps: common::InputField::default(),
+            partial_reps: common::InputField::default(),
             time: common::InputField::default(),
             weight: common::InputField::default(),
             rpe: common::InputField::default(),
@@ -1904,9 +2984,28 @@ fn append_exercise(
             prev_set_time: None,
             prev_set_weight: None,
             prev_set_rpe: None,
+            suggestion: domain::progression::Set::default(),
             automatic: false,
-        }],
-    });
+            to_failure: false,
+            side,
+            to_failure_changed: false,
+            comment: String::new(),
+            comment_changed: false,
+            comment_shown: false,
+            partial_reps_shown: false,
+        })
+        .collect()
+}
+
+fn is_left_side_of_pair(exercises: &[ExerciseForm], exercise_idx: usize) -> bool {
+    match (exercises.get(exercise_idx), exercises.get(exercise_idx + 1)) {
+        (Some(left), Some(right)) => {
+            left.exercise_id == right.exercise_id
+                && left.side == Some(domain::Side::Left)
+                && right.side == Some(domain::Side::Right)
+        }
+        _ => false,
+    }
 }
 
 fn is_set(elements: &mut [FormElement], element_idx: usize) -> bool {
@@ -1995,6 +3094,85 @@ fn determine_rest_between_sets(elements: &[FormElement], element_idx: usize) ->
     }
 }
 
+fn share_text(model: &Model, data_model: &data::Model) -> Option<String> {
+    data_model
+        .training_sessions
+        .get(&model.training_session_id)
+        .map(|training_session| {
+            web_app::share::format_training_session(
+                training_session,
+                &data_model.exercises,
+                data_model.settings.show_rpe,
+                data_model.settings.show_tut,
+            )
+        })
+}
+
+fn training_session_elements(elements: &[FormElement]) -> Vec<domain::TrainingSessionElement> {
+    elements
+        .iter()
+        .flat_map(|e| match e {
+            FormElement::Set { exercises } => exercises
+                .iter()
+                .map(|e| domain::TrainingSessionElement::Set {
+                    exercise_id: e.exercise_id,
+                    reps: e.reps.parsed.filter(|reps| *reps > 0),
+                    time: e.time.parsed.filter(|time| *time > 0),
+                    weight: e.weight.parsed.filter(|weight| *weight > 0.0),
+                    rpe: e.rpe.parsed.filter(|rpe| *rpe > 0.0),
+                    target_reps: e.target_reps,
+                    target_time: e.target_time,
+                    target_weight: e.target_weight,
+                    target_rpe: e.target_rpe,
+                    automatic: e.automatic,
+                    partial_reps: e.partial_reps.parsed.filter(|reps| *reps > 0),
+                    to_failure: e.to_failure,
+                    side: e.side,
+                    comment: Some(e.comment.trim())
+                        .filter(|comment| not(comment.is_empty()))
+                        .map(ToString::to_string),
+                })
+                .collect(),
+            FormElement::Rest {
+                target_time,
+                automatic,
+            } => vec![domain::TrainingSessionElement::Rest {
+                target_time: if *target_time > 0 {
+                    Some(*target_time)
+                } else {
+                    None
+                },
+                automatic: *automatic,
+            }],
+        })
+        .collect()
+}
+
+/// Determine the rounds of the routine sections for each form element. The rounds of a set are
+/// given by its first exercise.
+fn determine_rounds(
+    elements: &[FormElement],
+    routine: Option<&domain::Routine>,
+) -> Vec<Vec<domain::Round>> {
+    let Some(routine) = routine else {
+        return vec![vec![]; elements.len()];
+    };
+    let rounds =
+        domain::training_session_rounds(&training_session_elements(elements), &routine.sections);
+    let mut idx = 0;
+    elements
+        .iter()
+        .map(|element| {
+            let element_rounds = rounds.get(idx).cloned().unwrap_or_default();
+            idx += match element {
+                FormElement::Set { exercises } => exercises.len(),
+                FormElement::Rest { .. } => 1,
+            };
+            element_rounds
+        })
+        .collect()
+}
+
 fn determine_sections(elements: &[FormElement]) -> Vec<(usize, usize)> {
     let mut sections = vec![];
     let mut idx = 0;
@@ -2019,35 +3197,159 @@ fn determine_last_element_of_section(elements: &[FormElement], element_idx: usiz
     last
 }
 
+fn input_id(element_idx: usize, exercise_idx: usize, input: SetInput) -> String {
+    format!("set-input-{element_idx}-{exercise_idx}-{input:?}")
+}
+
+/// Determine the input field following the given input field.
+///
+/// The inputs are ordered by set, exercise and field. Hidden fields are skipped.
+fn next_input(
+    elements: &[FormElement],
+    element_idx: usize,
+    exercise_idx: usize,
+    input: SetInput,
+    show_tut: bool,
+    show_rpe: bool,
+) -> Option<(usize, usize, SetInput)> {
+    let inputs = [
+        SetInput::Reps,
+        SetInput::Time,
+        SetInput::Weight,
+        SetInput::RPE,
+    ]
+    .into_iter()
+    .filter(|i| match i {
+        SetInput::Reps | SetInput::Weight => true,
+        SetInput::Time => show_tut,
+        SetInput::RPE => show_rpe,
+    })
+    .collect::<Vec<_>>();
+    elements
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, e)| match e {
+            FormElement::Set { exercises } => Some((idx, exercises.len())),
+            FormElement::Rest { .. } => None,
+        })
+        .flat_map(|(idx, num_exercises)| {
+            (0..num_exercises).flat_map({
+                let inputs = &inputs;
+                move |position| inputs.iter().map(move |i| (idx, position, *i))
+            })
+        })
+        .skip_while(|current| *current != (element_idx, exercise_idx, input))
+        .nth(1)
+}
+
+fn push_undo_snapshot(undo_stack: &mut Vec<Vec<FormElement>>, elements: &[FormElement]) {
+    if undo_stack.len() >= UNDO_STACK_SIZE {
+        undo_stack.remove(0);
+    }
+    undo_stack.push(elements.to_vec());
+}
+
+fn clamp_element_idx(element_idx: usize, elements: &[FormElement]) -> usize {
+    element_idx.min(elements.len().saturating_sub(1))
+}
+
+fn checked_element_idx(element_idx: usize, elements: &[FormElement]) -> Option<usize> {
+    if elements.is_empty() {
+        None
+    } else {
+        Some(clamp_element_idx(element_idx, elements))
+    }
+}
+
+fn has_previous_values(elements: &[FormElement]) -> bool {
+    elements
+        .iter()
+        .filter_map(|e| match e {
+            FormElement::Set { exercises } => Some(exercises),
+            FormElement::Rest { .. } => None,
+        })
+        .flatten()
+        .any(|e| {
+            e.prev_reps.is_some()
+                || e.prev_time.is_some()
+                || e.prev_weight.is_some()
+                || e.prev_rpe.is_some()
+        })
+}
+
+/// Enter the values of the corresponding sets of the previous training session into all empty
+/// inputs.
+///
+/// Inputs which already contain a value are kept unchanged.
+fn fill_from_previous_session(elements: &mut [FormElement]) {
+    for exercise in elements
+        .iter_mut()
+        .filter_map(|e| match e {
+            FormElement::Set { exercises } => Some(exercises),
+            FormElement::Rest { .. } => None,
+        })
+        .flatten()
+    {
+        fill_empty_input(&mut exercise.reps, exercise.prev_reps);
+        fill_empty_input(&mut exercise.time, exercise.prev_time);
+        fill_empty_input(&mut exercise.weight, exercise.prev_weight);
+        fill_empty_input(&mut exercise.rpe, exercise.prev_rpe);
+    }
+}
+
+fn fill_empty_input<T: Copy + ToString>(field: &mut common::InputField<T>, value: Option<T>) {
+    if let Some(value) = value {
+        if field.input.is_empty() {
+            field.input = value.to_string();
+            field.parsed = Some(value);
+        }
+    }
+}
+
 // ------ ------
 //     View
 // ------ ------
 
 pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
-    if data_model.training_sessions.is_empty() && data_model.loading_training_sessions {
-        common::view_page_loading()
+    if let Some(node) =
+        common::view_resource_unavailable(&data_model.training_sessions, |_| Msg::Retry)
+    {
+        node
     } else if let Some(training_session) =
         data_model.training_sessions.get(&model.training_session_id)
     {
         if let Dialog::Hidden = model.dialog {
             div![
+                view_guide_estimate(model),
                 view_title(training_session, data_model),
                 div![if model.editing || model.guide.is_some() {
                     nodes![view_training_session_form(model, data_model)]
                 } else {
                     nodes![
+                        view_duration(training_session),
                         view_list(model, data_model),
                         view_notes(training_session),
                         view_muscles(training_session, data_model),
+                        view_share(model),
                         common::view_fab("edit", |_| Msg::EditTrainingSession)
                     ]
-                }]
+                }],
+                view_undo_notification(model),
             ]
         } else {
             div![
                 Node::NoChange,
                 Node::NoChange,
-                view_dialog(&model.dialog, &model.smt, model.loading, data_model),
+                Node::NoChange,
+                view_dialog(
+                    &model.dialog,
+                    &model.form,
+                    &model.smt,
+                    training_session,
+                    model.copied,
+                    model.loading,
+                    data_model
+                ),
             ]
         }
     } else {
@@ -2055,30 +3357,81 @@ pub fn view(model: &Model, data_model: &data::Model) -> Node<Msg> {
     }
 }
 
+fn view_guide_estimate(model: &Model) -> Node<Msg> {
+    let Some(guide) = &model.guide else {
+        return empty![];
+    };
+    let now = Utc::now();
+    let remaining = estimated_remaining_duration(
+        &model.form.elements,
+        guide.session.element_idx,
+        guide.session.elapsed_time(now),
+    );
+    let finish = web_app::clock::local_time(now + remaining);
+    p![
+        C!["has-text-centered"],
+        C!["is-size-7"],
+        C!["has-text-grey"],
+        C!["mb-2"],
+        span![
+            C!["icon-text"],
+            span![C!["icon"], i![C!["fas fa-flag-checkered"]]],
+            span![format!(
+                "~ {} min left, done at {}",
+                (remaining.num_seconds() + 59) / 60,
+                finish.format("%H:%M")
+            )],
+        ]
+    ]
+}
+
+fn view_undo_notification(model: &Model) -> Option<Node<Msg>> {
+    IF![model.undo_handle.is_some() && not(model.undo_stack.is_empty()) => div![
+        C!["notification"],
+        C!["is-undo-notification"],
+        C!["is-flex"],
+        C!["is-align-items-center"],
+        C!["is-justify-content-space-between"],
+        button![C!["delete"], ev(Ev::Click, |_| Msg::HideUndo)],
+        span!["Training session changed."],
+        button![
+            C!["button"],
+            C!["is-small"],
+            C!["is-link"],
+            C!["mr-5"],
+            ev(Ev::Click, |_| Msg::Undo),
+            span![C!["icon"], i![C!["fas fa-rotate-left"]]],
+            span!["Undo"]
+        ],
+    ]]
+}
+
 fn view_title(training_session: &domain::TrainingSession, data_model: &data::Model) -> Node<Msg> {
     div![
         common::view_title(&span![training_session.date.to_string()], 3),
-        if let Some(routine) = data_model
-            .routines
-            .get(&training_session.routine_id.unwrap_or(0))
-        {
-            common::view_title(
-                &a![
-                    attrs! {
-                        At::Href => crate::Urls::new(&data_model.base_url).routine().add_hash_path_part(routine.id.to_string()),
-                    },
-                    &routine.name
-                ],
-                3,
-            )
+        if let Some(routine_id) = training_session.routine_id {
+            if let Some(routine) = data_model.routines.get(&routine_id) {
+                common::view_title(
+                    &a![
+                        attrs! {
+                            At::Href => crate::Urls::new(&data_model.base_url).routine().add_hash_path_part(routine.id.to_string()),
+                        },
+                        &routine.name
+                    ],
+                    3,
+                )
+            } else {
+                empty![]
+            }
         } else {
-            empty![]
+            common::view_title(&span!["Ad-hoc session"], 3)
         }
     ]
 }
 
 fn view_list(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
     let sections = determine_sections(&model.form.elements);
+    let rounds = determine_rounds(&model.form.elements, routine(model, data_model));
     sections
         .iter()
         .flat_map(|(first, last)| {
@@ -2112,8 +3465,14 @@ fn view_list(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
                 ],
                 div![
                     C!["block"],
-                    model.form.elements[*first..=*last].iter().map(|element| {
+                    model.form.elements[*first..=*last].iter().enumerate().map(|(idx, element)| {
                         nodes![
+                            if let FormElement::Set { .. } = element {
+                                web_app::format_rounds(&rounds[first + idx])
+                                    .map_or(empty![], |round| p![C!["is-size-7"], C!["has-text-grey"], round])
+                            } else {
+                                empty![]
+                            },
                             if let FormElement::Set { exercises } = element {
                                 exercises
                                     .iter()
@@ -2122,14 +3481,20 @@ fn view_list(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
                                             C!["has-text-centered"],
                                             span![
                                                 style! {St::WhiteSpace => "nowrap" },
-                                                common::format_set(
-                                                    e.reps.parsed,
+                                                web_app::format_set(
+                                                    e.reps.parsed.map(domain::RepsRange::single),
+                                                    e.partial_reps.parsed,
                                                     e.time.parsed,
                                                     data_model.settings.show_tut,
                                                     e.weight.parsed,
-                                                    e.rpe.parsed,
+                                                    e.rpe.parsed.map(domain::RpeRange::single),
                                                     data_model.settings.show_rpe,
+                                                    e.to_failure,
                                                 )
+                                            ],
+                                            view_set_deviation(e, &data_model.settings),
+                                            IF![not(e.comment.is_empty()) =>
+                                                p![C!["is-size-7"], C!["is-italic"], &e.comment]
                                             ]
                                         ]
                                     })
@@ -2146,6 +3511,33 @@ fn view_list(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
         .collect::<Vec<_>>()
 }
 
+fn routine<'a>(model: &Model, data_model: &'a data::Model) -> Option<&'a domain::Routine> {
+    data_model
+        .training_sessions
+        .get(&model.training_session_id)
+        .and_then(|training_session| training_session.routine_id)
+        .and_then(|routine_id| data_model.routines.get(&routine_id))
+}
+
+fn view_duration(training_session: &domain::TrainingSession) -> Node<Msg> {
+    if let Some(duration) = training_session.duration() {
+        div![
+            C!["columns"],
+            C!["is-gapless"],
+            C!["is-mobile"],
+            div![
+                C!["column"],
+                common::view_box(
+                    "Duration",
+                    &format!("<strong>{}</strong> min", duration.num_minutes())
+                )
+            ]
+        ]
+    } else {
+        empty![]
+    }
+}
+
 fn view_notes(training_session: &domain::TrainingSession) -> Node<Msg> {
     if let Some(notes) = &training_session.notes {
         if notes.is_empty() {
@@ -2156,7 +3548,11 @@ fn view_notes(training_session: &domain::TrainingSession) -> Node<Msg> {
                 C!["m-3"],
                 C!["mt-6"],
                 common::view_title(&span!["Notes"], 3),
-                p![notes]
+                div![
+                    C!["content"],
+                    C!["has-text-left"],
+                    raw![&web_app::markdown::to_html(notes)]
+                ]
             ]
         }
     } else {
@@ -2185,17 +3581,92 @@ fn view_muscles(training_session: &domain::TrainingSession, data_model: &data::M
     }
 }
 
+fn view_share(model: &Model) -> Node<Msg> {
+    div![
+        C!["field"],
+        C!["is-grouped"],
+        C!["is-grouped-centered"],
+        C!["m-5"],
+        div![
+            C!["control"],
+            button![
+                C!["button"],
+                C!["is-link"],
+                C!["is-outlined"],
+                ev(Ev::Click, |_| Msg::CopyTrainingSession),
+                span![
+                    C!["icon"],
+                    i![
+                        C!["fas"],
+                        C![if model.copied { "fa-check" } else { "fa-copy" }]
+                    ]
+                ],
+                span![if model.copied { "Copied" } else { "Copy" }]
+            ]
+        ],
+        IF![web_app::share::share_supported() =>
+            div![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-link"],
+                    C!["is-outlined"],
+                    ev(Ev::Click, |_| Msg::ShareTrainingSession),
+                    span![C!["icon"], i![C!["fas fa-share-nodes"]]],
+                    span!["Share"]
+                ]
+            ]
+        ],
+        div![
+            C!["control"],
+            button![
+                C!["button"],
+                C!["is-link"],
+                C!["is-outlined"],
+                ev(Ev::Click, |_| Msg::ShowSaveAsRoutineDialog),
+                span![C!["icon"], i![C!["fas fa-clipboard-list"]]],
+                span!["Save as routine"]
+            ]
+        ],
+        div![
+            C!["control"],
+            button![
+                C!["button"],
+                C!["is-link"],
+                C!["is-outlined"],
+                C![IF![model.repeating => "is-loading"]],
+                attrs! {
+                    At::Title => "Create a training session for today with the same exercises and the performed values as targets",
+                },
+                ev(Ev::Click, |_| Msg::RepeatTrainingSession),
+                span![C!["icon"], i![C!["fas fa-repeat"]]],
+                span!["Repeat"]
+            ]
+        ],
+    ]
+}
+
 fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<Node<Msg>> {
     let sections = determine_sections(&model.form.elements);
-    let valid = model.form.valid();
-    let save_disabled = not(model.form.changed()) || not(valid);
+    let rounds = determine_rounds(&model.form.elements, routine(model, data_model));
+    let invalid_fields = model.form.invalid_fields() + usize::from(not(model.duration.valid()));
+    let valid = invalid_fields == 0;
+    let save_title = match invalid_fields {
+        0 => String::new(),
+        1 => String::from("1 invalid field"),
+        n => format!("{n} invalid fields"),
+    };
+    let save_disabled = not(model.has_unsaved_changes()) || not(valid);
+    let fill_disabled = not(has_previous_values(&model.form.elements));
+    let show_tut = data_model.settings.show_tut;
+    let show_rpe = data_model.settings.show_rpe;
 
     let form = sections.iter().map(|(first, last)| {
         let mut section_form: std::vec::Vec<seed::virtual_dom::Node<Msg>> = nodes![];
         for (idx, element) in model.form.elements[*first..=*last].iter().enumerate() {
             let element_idx = first + idx;
             if let Some(guide) = &model.guide {
-                if guide.element_idx == element_idx && element_idx != 0 {
+                if guide.session.element_idx == element_idx && element_idx != 0 {
                     section_form.push(div![
                         C!["has-text-centered"],
                         C!["m-5"],
@@ -2216,7 +3687,7 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                     section_form.push(
                         div![
                             if let Some(guide) = &model.guide {
-                                if guide.element_idx == element_idx {
+                                if guide.session.element_idx == element_idx {
                                     el_ref(&guide.element)
                                 } else {
                                     el_ref(&ElRef::new())
@@ -2226,15 +3697,18 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                             },
                             C!["message"],
                             C!["is-info"],
-                            IF![model.guide.as_ref().map_or(false, |guide| guide.element_idx != element_idx) => C!["is-semitransparent"]],
+                            IF![model.guide.as_ref().map_or(false, |guide| guide.session.element_idx != element_idx) => C!["is-semitransparent"]],
                             IF![idx > 0 => C!["mt-3"]],
                             C!["mb-0"],
                             div![
                                 C!["message-body"],
                                 C!["has-background-scheme-main"],
                                 C!["p-3"],
+                                web_app::format_rounds(&rounds[element_idx]).map_or(empty![], |round| {
+                                    p![C!["is-size-7"], C!["has-text-grey"], C!["has-text-right"], C!["mb-2"], round]
+                                }),
                                 exercise_forms.iter().enumerate().map(|(position, s)| {
-                                    let input_fields = div![
+                                    let set_inputs = div![
                                             C!["field"],
                                             C!["has-addons"],
                                             div![
@@ -2242,19 +3716,17 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                 C!["has-icons-right"],
                                                 C!["has-text-right"],
                                                 input_ev(Ev::Input, move |v| Msg::RepsChanged(element_idx, position, v)),
-                                                keyboard_ev(Ev::KeyDown, move |keyboard_event| {
-                                                    IF!(
-                                                        not(save_disabled) && keyboard_event.key_code() == common::ENTER_KEY => {
-                                                            Msg::SaveTrainingSession
-                                                        }
-                                                    )
-                                                }),
+                                                view_input_keydown_handler(
+                                                    next_input(&model.form.elements, element_idx, position, SetInput::Reps, show_tut, show_rpe),
+                                                    save_disabled,
+                                                ),
                                                 input![
                                                     C!["input"],
                                                     C!["has-text-right"],
                                                     C![IF![not(s.reps.valid()) => "is-danger"]],
                                                     C![IF![s.reps.changed() => "is-info"]],
                                                     attrs! {
+                                                        At::Id => input_id(element_idx, position, SetInput::Reps),
                                                         At::Type => "number",
                                                         At::Min => 0,
                                                         At::Max => 999,
@@ -2263,7 +3735,37 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                         At::Value => s.reps.input,
                                                     }
                                                 ],
-                                                span![C!["icon"], C!["is-small"], C!["is-right"], "✕"],
+                                                IF![not(s.partial_reps_shown) =>
+                                                    span![C!["icon"], C!["is-small"], C!["is-right"], "✕"]
+                                                ],
+                                            ],
+                                            IF![
+                                                s.partial_reps_shown => {
+                                                    div![
+                                                        C!["control"],
+                                                        C!["has-icons-left"],
+                                                        C!["has-icons-right"],
+                                                        C!["has-text-right"],
+                                                        input_ev(Ev::Input, move |v| Msg::PartialRepsChanged(element_idx, position, v)),
+                                                        input![
+                                                            C!["input"],
+                                                            C!["has-text-right"],
+                                                            C![IF![not(s.partial_reps.valid()) => "is-danger"]],
+                                                            C![IF![s.partial_reps.changed() => "is-info"]],
+                                                            attrs! {
+                                                                At::Type => "number",
+                                                                At::Title => "Partial reps",
+                                                                At::Min => 0,
+                                                                At::Max => 999,
+                                                                At::Step => 1,
+                                                                At::Size => 2,
+                                                                At::Value => s.partial_reps.input,
+                                                            }
+                                                        ],
+                                                        span![C!["icon"], C!["is-small"], C!["is-left"], "+"],
+                                                        span![C!["icon"], C!["is-small"], C!["is-right"], "✕"],
+                                                    ]
+                                                }
                                             ],
                                             IF![
                                                 data_model.settings.show_tut => {
@@ -2272,19 +3774,17 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                         C!["has-icons-right"],
                                                         C!["has-text-right"],
                                                         input_ev(Ev::Input, move |v| Msg::TimeChanged(element_idx, position, v)),
-                                                        keyboard_ev(Ev::KeyDown, move |keyboard_event| {
-                                                            IF!(
-                                                                not(save_disabled) && keyboard_event.key_code() == common::ENTER_KEY => {
-                                                                    Msg::SaveTrainingSession
-                                                                }
-                                                            )
-                                                        }),
+                                                        view_input_keydown_handler(
+                                                            next_input(&model.form.elements, element_idx, position, SetInput::Time, show_tut, show_rpe),
+                                                            save_disabled,
+                                                        ),
                                                         input![
                                                             C!["input"],
                                                             C!["has-text-right"],
                                                             C![IF![not(s.time.valid()) => "is-danger"]],
                                                             C![IF![s.time.changed() => "is-info"]],
                                                             attrs! {
+                                                                At::Id => input_id(element_idx, position, SetInput::Time),
                                                                 At::Type => "number",
                                                                 At::Min => 0,
                                                                 At::Max => 999,
@@ -2297,24 +3797,56 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                     ]
                                                 }
                                             ],
+                                            IF![
+                                                show_tut => {
+                                                    let set_timer = model.set_timer.as_ref().filter(|t| {
+                                                        t.element_idx == element_idx && t.exercise_idx == position
+                                                    });
+                                                    div![
+                                                        C!["control"],
+                                                        button![
+                                                            C!["button"],
+                                                            C![if set_timer.is_some() { "is-danger" } else { "is-light" }],
+                                                            attrs! {
+                                                                At::Type => "button",
+                                                                At::Title => "Record time",
+                                                                At::from("tabindex") => -1,
+                                                            },
+                                                            ev(Ev::Click, move |_| Msg::ToggleSetTimer(element_idx, position)),
+                                                            if let Some(set_timer) = set_timer {
+                                                                span![set_timer.stopwatch.time() / 1000]
+                                                            } else {
+                                                                span![C!["icon"], i![C!["fas fa-stopwatch"]]]
+                                                            }
+                                                        ]
+                                                    ]
+                                                }
+                                            ],
                                             div![
                                                 C!["control"],
                                                 C!["has-icons-right"],
                                                 C!["has-text-right"],
                                                 input_ev(Ev::Input, move |v| Msg::WeightChanged(element_idx, position, v)),
-                                                keyboard_ev(Ev::KeyDown, move |keyboard_event| {
-                                                    IF!(
-                                                        not(save_disabled) && keyboard_event.key_code() == common::ENTER_KEY => {
-                                                            Msg::SaveTrainingSession
-                                                        }
-                                                    )
-                                                }),
+                                                view_input_keydown_handler(
+                                                    next_input(&model.form.elements, element_idx, position, SetInput::Weight, show_tut, show_rpe),
+                                                    save_disabled,
+                                                ),
                                                 input![
                                                     C!["input"],
                                                     C!["has-text-right"],
+                                                    keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+                                                        let up = match keyboard_event.key().as_str() {
+                                                            "ArrowUp" => true,
+                                                            "ArrowDown" => false,
+                                                            _ => return None,
+                                                        };
+                                                        keyboard_event.prevent_default();
+                                                        Some(Msg::StepWeight(element_idx, position, up))
+                                                    }),
                                                     C![IF![not(s.weight.valid()) => "is-danger"]],
                                                     C![IF![s.weight.changed() => "is-info"]],
                                                     attrs! {
+                                                        At::Id => input_id(element_idx, position, SetInput::Weight),
                                                         At::from("inputmode") => "numeric",
                                                         At::Size => 3,
                                                         At::Value => s.weight.input,
@@ -2328,20 +3860,19 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                         C!["control"],
                                                         C!["has-icons-left"],
                                                         C!["has-text-right"],
+                                                        C!["is-hidden-touch"],
                                                         input_ev(Ev::Input, move |v| Msg::RPEChanged(element_idx, position, v)),
-                                                        keyboard_ev(Ev::KeyDown, move |keyboard_event| {
-                                                            IF!(
-                                                                not(save_disabled) && keyboard_event.key_code() == common::ENTER_KEY => {
-                                                                    Msg::SaveTrainingSession
-                                                                }
-                                                            )
-                                                        }),
+                                                        view_input_keydown_handler(
+                                                            next_input(&model.form.elements, element_idx, position, SetInput::RPE, show_tut, show_rpe),
+                                                            save_disabled,
+                                                        ),
                                                         input![
                                                             C!["input"],
                                                             C!["has-text-right"],
                                                             C![IF![not(s.rpe.valid()) => "is-danger"]],
                                                             C![IF![s.rpe.changed() => "is-info"]],
                                                             attrs! {
+                                                                At::Id => input_id(element_idx, position, SetInput::RPE),
                                                                 At::from("inputmode") => "numeric",
                                                                 At::Size => 2,
                                                                 At::Value => s.rpe.input,
@@ -2352,6 +3883,40 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                 }
                                             ],
                                         ];
+                                    let input_fields = div![
+                                        set_inputs,
+                                        common::view_input_error(&s.reps),
+                                        common::view_input_error(&s.partial_reps),
+                                        common::view_input_error(&s.time),
+                                        common::view_input_error(&s.weight),
+                                        common::view_input_error(&s.rpe),
+                                        data_model
+                                            .exercises
+                                            .get(&s.exercise_id)
+                                            .and_then(web_app::format_weight_convention)
+                                            .map_or(empty![], |hint| p![C!["help"], C!["has-text-grey"], hint]),
+                                        data_model
+                                            .settings
+                                            .active_location()
+                                            .zip(data_model.exercises.get(&s.exercise_id))
+                                            .and_then(|(location, exercise)| {
+                                                let weight = if s.weight.input.is_empty() {
+                                                    s.target_weight
+                                                } else {
+                                                    s.weight.parsed
+                                                };
+                                                web_app::format_equipment_hint(location, exercise, weight?)
+                                            })
+                                            .map_or(empty![], |hint| p![C!["help"], C!["has-text-grey"], hint]),
+                                        IF![
+                                            show_rpe => {
+                                                common::view_rpe_picker(
+                                                    &s.rpe,
+                                                    move |v| Msg::RPEChanged(element_idx, position, v),
+                                                )
+                                            }
+                                        ],
+                                    ];
                                     div![
                                         C!["field"],
                                         div![
@@ -2369,16 +3934,47 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                         },
                                                         At::from("tabindex") => -1
                                                     },
-                                                    &s.exercise_name
+                                                    view_exercise_thumbnail(s.exercise_id, data_model),
+                                                    &s.exercise_name,
+                                                    view_history_sparkline(s.exercise_id, model.training_session_id, data_model),
+                                                ],
+                                                div![
+                                                    s.side.map(|side| span![
+                                                        C!["tag"],
+                                                        C!["is-link"],
+                                                        C!["is-light"],
+                                                        C!["mr-2"],
+                                                        attrs! {At::Title => format!("{} side", side.name())},
+                                                        side.abbreviation()
+                                                    ]),
+                                                    IF![s.to_failure =>
+                                                        span![
+                                                            C!["tag"],
+                                                            C![if s.to_failure_changed { "is-info" } else { "is-danger" }],
+                                                            C!["is-light"],
+                                                            C!["mr-2"],
+                                                            attrs! {At::Title => "Performed to failure"},
+                                                            "F"
+                                                        ]
+                                                    ],
+                                                    a![
+                                                        C!["mr-2"],
+                                                        ev(Ev::Click, move |_| Msg::ToggleComment(element_idx, position)),
+                                                        span![
+                                                            C!["icon"],
+                                                            C![IF![s.comment.is_empty() => "has-text-grey-light"]],
+                                                            i![C!["fas fa-comment"]]
+                                                        ]
+                                                    ],
+                                                    a![
+                                                        ev(Ev::Click, move |_| Msg::ShowOptionsDialog(element_idx, position)),
+                                                        span![C!["icon"], i![C!["fas fa-ellipsis-vertical"]]]
+                                                    ]
                                                 ],
-                                                div![a![
-                                                    ev(Ev::Click, move |_| Msg::ShowOptionsDialog(element_idx, position)),
-                                                    span![C!["icon"], i![C!["fas fa-ellipsis-vertical"]]]
-                                                ]],
                                             ],
                                         ],
                                         if let Some(guide) = &model.guide {
-                                            if guide.timer.is_set() && guide.element_idx == element_idx {
+                                            if guide.timer.is_set() && guide.session.element_idx == element_idx {
                                                 view_guide_timer(guide)
                                             } else {
                                                 input_fields
@@ -2387,28 +3983,43 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                             input_fields
                                         },
                                         {
-                                            let target = common::format_set(
+                                            let target = web_app::format_set(
                                                 s.target_reps,
+                                                None,
                                                 s.target_time,
                                                 data_model.settings.show_tut,
                                                 s.target_weight,
                                                 s.target_rpe,
-                                                data_model.settings.show_rpe
+                                                data_model.settings.show_rpe,
+                                                false
                                             );
-                                            let previous = common::format_set(
-                                                s.prev_reps,
+                                            let previous = web_app::format_set(
+                                                s.prev_reps.map(domain::RepsRange::single),
+                                                None,
                                                 s.prev_time,
                                                 data_model.settings.show_tut,
                                                 s.prev_weight,
-                                                s.prev_rpe,
-                                                data_model.settings.show_rpe);
-                                            let previous_set = common::format_set(
-                                                s.prev_set_reps,
+                                                s.prev_rpe.map(domain::RpeRange::single),
+                                                data_model.settings.show_rpe,
+                                                false);
+                                            let previous_set = web_app::format_set(
+                                                s.prev_set_reps.map(domain::RepsRange::single),
+                                                None,
                                                 s.prev_set_time,
                                                 data_model.settings.show_tut,
                                                 s.prev_set_weight,
-                                                s.prev_set_rpe,
-                                                data_model.settings.show_rpe);
+                                                s.prev_set_rpe.map(domain::RpeRange::single),
+                                                data_model.settings.show_rpe,
+                                                false);
+                                            let suggestion = web_app::format_set(
+                                                s.suggestion.reps.map(domain::RepsRange::single),
+                                                None,
+                                                s.suggestion.time,
+                                                data_model.settings.show_tut,
+                                                s.suggestion.weight,
+                                                s.suggestion.rpe,
+                                                data_model.settings.show_rpe,
+                                                false);
                                             p![
                                                 IF![not(target.is_empty()) =>
                                                     span![
@@ -2418,7 +4029,12 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                         a![
                                                             ev(Ev::Click, move |_| Msg::EnterTargetValues(element_idx, position)),
                                                             target
-                                                        ]
+                                                        ],
+                                                        view_set_deviation(s, &data_model.settings).filter(|_| {
+                                                            model.guide.as_ref().map_or(true, |guide| {
+                                                                element_idx < guide.session.element_idx
+                                                            })
+                                                        }),
                                                     ]
                                                 ],
                                                 IF![not(previous.is_empty()) =>
@@ -2443,6 +4059,17 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                         ]
                                                     ]
                                                 ],
+                                                IF![not(suggestion.is_empty()) =>
+                                                    span![
+                                                        C!["icon-text"],
+                                                        C!["mr-4"],
+                                                        span![C!["icon"], i![C!["fas fa-arrow-trend-up"]]],
+                                                        a![
+                                                            ev(Ev::Click, move |_| Msg::EnterSuggestedValues(element_idx, position)),
+                                                            suggestion
+                                                        ]
+                                                    ]
+                                                ],
                                                 IF![
                                                     s.automatic =>
                                                     span![
@@ -2451,7 +4078,25 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                                                     ]
                                                 ]
                                             ]
-                                        }
+                                        },
+                                        IF![
+                                            s.comment_shown =>
+                                            div![
+                                                C!["control"],
+                                                C!["mt-2"],
+                                                input_ev(Ev::Input, move |v| Msg::CommentChanged(element_idx, position, v)),
+                                                input![
+                                                    C!["input"],
+                                                    C!["is-small"],
+                                                    C![IF![s.comment_changed => "is-info"]],
+                                                    attrs! {
+                                                        At::Type => "text",
+                                                        At::Placeholder => "Comment",
+                                                        At::Value => s.comment,
+                                                    }
+                                                ]
+                                            ]
+                                        ]
                                     ]
                                 })
                             ]
@@ -2464,7 +4109,7 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                 } => {
                     section_form.push(div![
                         if let Some(guide) = &model.guide {
-                            if guide.element_idx == element_idx {
+                            if guide.session.element_idx == element_idx {
                                 el_ref(&guide.element)
                             } else {
                                 el_ref(&ElRef::new())
@@ -2474,7 +4119,7 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                         },
                         C!["message"],
                         C!["is-success"],
-                        IF![model.guide.as_ref().map_or(false, |guide| guide.element_idx != element_idx) => C!["is-semitransparent"]],
+                        IF![model.guide.as_ref().map_or(false, |guide| guide.session.element_idx != element_idx) => C!["is-semitransparent"]],
                         IF![idx > 0 => C!["mt-3"]],
                         C!["mb-0"],
                         div![
@@ -2482,7 +4127,7 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
                             C!["has-background-scheme-main"],
                             C!["p-3"],
                             if let Some(guide) = &model.guide {
-                                if guide.timer.is_set() && guide.element_idx == element_idx {
+                                if guide.timer.is_set() && guide.session.element_idx == element_idx {
                                     view_guide_timer(guide)
                                 } else {
                                     common::view_rest(*target_time, *automatic)
@@ -2496,7 +4141,7 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
             }
 
             if let Some(guide) = &model.guide {
-                if guide.element_idx == element_idx {
+                if guide.session.element_idx == element_idx {
                     section_form.push(div![
                         C!["has-text-centered"],
                         C!["m-5"],
@@ -2524,18 +4169,34 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
     }).collect::<Vec<_>>();
 
     nodes![
-        IF![
-            model.guide.is_none() =>
-            div![
-                C!["has-text-centered"],
-                C!["m-5"],
+        div![
+            C!["has-text-centered"],
+            C!["m-5"],
+            IF![
+                model.guide.is_none() =>
                 button![
                     C!["button"],
                     C!["is-link"],
+                    C!["mr-2"],
                     ev(Ev::Click, |_| Msg::StartGuidedTrainingSession),
                     span![C!["icon"], i![C!["fas fa-play"]]]
                 ]
-            ]
+            ],
+            button![
+                C!["button"],
+                attrs![
+                    At::Disabled => fill_disabled.as_at_value(),
+                ],
+                ev(Ev::Click, |_| Msg::FillFromPreviousSession),
+                span![C!["icon"], i![C!["fas fa-clipboard-list"]]],
+                span!["Fill from previous session"]
+            ],
+            IF![model.draft_restored => p![
+                C!["help"],
+                C!["mt-3"],
+                span![C!["icon"], i![C!["fas fa-clock-rotate-left"]]],
+                "Restored unsaved changes"
+            ]],
         ],
         form![
             attrs! {
@@ -2561,6 +4222,26 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
             ],
             div![
                 C!["p-3"],
+                IF![model.guide.is_none() => div![
+                    C!["field"],
+                    label![C!["label"], "Duration"],
+                    div![
+                        C!["control"],
+                        C!["has-icons-right"],
+                        input_ev(Ev::Input, Msg::DurationChanged),
+                        input![
+                            C!["input"],
+                            C![IF![not(model.duration.valid()) => "is-danger"]],
+                            C![IF![model.duration.changed() => "is-info"]],
+                            attrs! {
+                                At::from("inputmode") => "numeric",
+                                At::Value => model.duration.input,
+                            }
+                        ],
+                        span![C!["icon"], C!["is-small"], C!["is-right"], "min"],
+                    ],
+                    common::view_input_error(&model.duration),
+                ]],
                 div![
                     C!["field"],
                     label![C!["label"], "Notes"],
@@ -2579,9 +4260,9 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
             C!["is-medium"],
             C!["is-link"],
             C![IF![not(valid) => "is-danger"]],
-            C![IF![model.loading => "is-loading"]],
             attrs![
                 At::Disabled => save_disabled.as_at_value(),
+                At::Title => save_title,
             ],
             ev(Ev::Click, |_| Msg::SaveTrainingSession),
             span![C!["icon"], i![C!["fas fa-save"]]]
@@ -2589,10 +4270,128 @@ fn view_training_session_form(model: &Model, data_model: &data::Model) -> Vec<No
     ]
 }
 
+/// Trend of the estimated one-repetition maximum of the exercise in the previous training sessions.
+fn view_exercise_thumbnail(exercise_id: u32, data_model: &data::Model) -> Option<Node<Msg>> {
+    let image = data_model
+        .exercises
+        .get(&exercise_id)
+        .and_then(|e| e.image.as_ref())
+        .and_then(|digest| data_model.exercise_images.get(digest))?;
+    Some(figure![
+        C!["image"],
+        C!["is-24x24"],
+        C!["is-exercise-thumbnail"],
+        C!["mr-2"],
+        img![attrs! {
+            At::Src => image.data_url(),
+            At::Alt => "",
+        }]
+    ])
+}
+
+fn view_history_sparkline(
+    exercise_id: u32,
+    training_session_id: u32,
+    data_model: &data::Model,
+) -> Option<Node<Msg>> {
+    let exercise = data_model.exercises.get(&exercise_id)?;
+    let date = data_model.training_sessions.get(&training_session_id)?.date;
+    let values = domain::progression::recent_top_sets(
+        data_model
+            .training_sessions
+            .values()
+            .filter(|t| t.id != training_session_id && t.date <= date),
+        exercise,
+        5,
+    );
+    let svg = web_app::sparkline::svg(&values.iter().map(|(_, v)| *v).collect::<Vec<_>>())?;
+    Some(span![
+        C!["ml-2"],
+        C!["has-text-weight-normal"],
+        attrs! {
+            At::Title => format!(
+                "Estimated 1RM of last sessions: {} kg",
+                values
+                    .iter()
+                    .map(|(_, v)| web_app::format::format_weight(
+                        *v,
+                        "",
+                        web_app::format::PRECISION_E1RM,
+                        data_model.settings.decimal_separator
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        },
+        raw![&svg]
+    ])
+}
+
+/// Colored differences between the entered and the target values of a set.
+fn view_set_deviation(exercise: &ExerciseForm, settings: &web_app::Settings) -> Option<Node<Msg>> {
+    let deviation = domain::progression::deviation(
+        domain::progression::Set {
+            reps: exercise.target_reps.map(|target| {
+                exercise
+                    .reps
+                    .parsed
+                    .map_or(target.min, |reps| target.clamp(reps))
+            }),
+            time: exercise.target_time,
+            weight: exercise.target_weight,
+            rpe: exercise.target_rpe,
+        },
+        domain::progression::Set {
+            reps: exercise.reps.parsed,
+            time: exercise.time.parsed,
+            weight: exercise.weight.parsed,
+            rpe: exercise.rpe.parsed.map(domain::RpeRange::single),
+        },
+    )?;
+    Some(span![
+        C!["is-size-7"],
+        C!["ml-2"],
+        C![if deviation.target_met(settings.target_tolerance) {
+            "has-text-success"
+        } else {
+            "has-text-danger"
+        }],
+        web_app::format_set_deviation(&deviation, settings.show_tut, settings.show_rpe)
+    ])
+}
+
+/// Move the focus to the next input on Enter or Tab. On the last input, Enter saves the training
+/// session. Ctrl+Enter saves the training session in any input.
+fn view_input_keydown_handler(
+    next_input: Option<(usize, usize, SetInput)>,
+    save_disabled: bool,
+) -> EventHandler<Msg> {
+    keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+        let enter = keyboard_event.key_code() == common::ENTER_KEY;
+        let tab = keyboard_event.key_code() == common::TAB_KEY && not(keyboard_event.shift_key());
+        if enter && (keyboard_event.ctrl_key() || next_input.is_none()) {
+            IF!(not(save_disabled) => Msg::SaveTrainingSession)
+        } else if enter || tab {
+            let (element_idx, exercise_idx, input) = next_input?;
+            keyboard_event.prevent_default();
+            Some(Msg::FocusInput(element_idx, exercise_idx, input))
+        } else {
+            None
+        }
+    })
+}
+
 fn view_guide_timer(guide: &Guide) -> Node<Msg> {
     div![
         C!["is-size-1"],
-        C![IF![not(guide.timer.is_active()) => "is-blinking"]],
+        // A paused timer is indicated by blinking, or by transparency if motion should be reduced
+        C![
+            IF![not(guide.timer.is_active()) => if common::reduced_motion() {
+                "is-semitransparent"
+            } else {
+                "is-blinking"
+            }]
+        ],
         C!["has-text-centered"],
         ev(Ev::Click, |_| Msg::StartPauseGuideTimer),
         &guide.timer.time.0,
@@ -2602,15 +4401,22 @@ fn view_guide_timer(guide: &Guide) -> Node<Msg> {
 
 fn view_dialog(
     dialog: &Dialog,
+    form: &Form,
     smt: &StopwatchMetronomTimer,
+    training_session: &domain::TrainingSession,
+    copied: bool,
     loading: bool,
     data_model: &data::Model,
 ) -> Node<Msg> {
     let content = match dialog {
         Dialog::Hidden => nodes![],
-        Dialog::StopwatchMetronomTimer => view_smt_dialog(smt),
+        Dialog::StopwatchMetronomTimer => view_smt_dialog(smt, data_model.settings.timer_presets),
         Dialog::Options(element_idx, exercise_idx) => {
-            view_options_dialog(*element_idx, *exercise_idx)
+            let exercise = match form.elements.get(*element_idx) {
+                Some(FormElement::Set { exercises }) => exercises.get(*exercise_idx),
+                _ => None,
+            };
+            view_options_dialog(*element_idx, *exercise_idx, exercise)
         }
         Dialog::ReplaceExercise(_, _, exercise_list_model)
         | Dialog::AddExercise(_, _, exercise_list_model)
@@ -2618,6 +4424,8 @@ fn view_dialog(
             component::exercise_list::view(exercise_list_model, loading, data_model)
                 .map_msg(Msg::ExerciseList)
         }
+        Dialog::SaveAsRoutine(name) => view_save_as_routine_dialog(name, loading),
+        Dialog::Summary => view_summary_dialog(training_session, copied, data_model),
     };
 
     div![
@@ -2640,7 +4448,7 @@ fn view_dialog(
     ]
 }
 
-fn view_smt_dialog(smt: &StopwatchMetronomTimer) -> Vec<Node<Msg>> {
+fn view_smt_dialog(smt: &StopwatchMetronomTimer, timer_presets: [u32; 3]) -> Vec<Node<Msg>> {
     nodes![
         div![
             C!["block"],
@@ -2653,7 +4461,7 @@ fn view_smt_dialog(smt: &StopwatchMetronomTimer) -> Vec<Node<Msg>> {
                 ev(Ev::Click, |_| Msg::ToggleStopwatch),
                 {
                     #[allow(clippy::cast_precision_loss)]
-                    let time = smt.stopwatch.time as f64 / 1000.;
+                    let time = smt.stopwatch.time() as f64 / 1000.;
                     format!("{time:.1}")
                 }],
                 button![
@@ -2698,12 +4506,12 @@ fn view_smt_dialog(smt: &StopwatchMetronomTimer) -> Vec<Node<Msg>> {
                             div![
                                 C!["select"],
                                 select![
-                                    (1..61).map(|i| {
+                                    web_app::METRONOME_INTERVAL_RANGE.map(|i| {
                                         option![
                                             &i,
                                             attrs! {
                                                 At::Value => i,
-                                                At::Selected => (i == smt.metronome.interval).as_at_value()
+                                                At::Selected => (i == smt.metronome.state.interval()).as_at_value()
                                             }
                                         ]
                                     }).collect::<Vec<_>>()
@@ -2721,12 +4529,12 @@ fn view_smt_dialog(smt: &StopwatchMetronomTimer) -> Vec<Node<Msg>> {
                             div![
                                 C!["select"],
                                 select![
-                                    (1..13).map(|i| {
+                                    web_app::METRONOME_STRESSED_BEAT_RANGE.map(|i| {
                                         option![
                                             &i,
                                             attrs! {
                                                 At::Value => i,
-                                                At::Selected => (i == smt.metronome.stressed_beat).as_at_value()
+                                                At::Selected => (i == smt.metronome.state.stressed_beat()).as_at_value()
                                             }
                                         ]
                                     }).collect::<Vec<_>>()
@@ -2788,6 +4596,21 @@ fn view_smt_dialog(smt: &StopwatchMetronomTimer) -> Vec<Node<Msg>> {
                         },
                     ]
                 ]],
+                div![
+                    C!["buttons"],
+                    C!["is-centered"],
+                    C!["mt-5"],
+                    timer_presets.into_iter().map(|time| {
+                        button![
+                            C!["button"],
+                            C!["is-small"],
+                            C!["is-rounded"],
+                            attrs! {At::Type => "button"},
+                            ev(Ev::Click, move |_| Msg::StartTimerPreset(time)),
+                            format!("{time} s")
+                        ]
+                    })
+                ],
                 button![
                     C!["button"],
                     C!["mt-5"],
@@ -2813,8 +4636,265 @@ fn view_smt_dialog(smt: &StopwatchMetronomTimer) -> Vec<Node<Msg>> {
     ]
 }
 
-fn view_options_dialog(element_idx: usize, exercise_idx: usize) -> Vec<Node<Msg>> {
+fn view_save_as_routine_dialog(name: &common::InputField<String>, loading: bool) -> Vec<Node<Msg>> {
+    let save_disabled = loading || not(name.valid());
+    nodes![
+        div![
+            C!["field"],
+            label![C!["label"], "Routine name"],
+            div![
+                C!["control"],
+                input_ev(Ev::Input, Msg::RoutineNameChanged),
+                keyboard_ev(Ev::KeyDown, move |keyboard_event| {
+                    IF!(
+                        not(save_disabled) && keyboard_event.key_code() == common::ENTER_KEY => {
+                            Msg::SaveAsRoutine
+                        }
+                    )
+                }),
+                input![
+                    C!["input"],
+                    C![IF![not(name.valid()) && name.changed() => "is-danger"]],
+                    attrs! {
+                        At::Type => "text",
+                        At::Value => name.input,
+                    }
+                ],
+            ]
+        ],
+        div![
+            C!["field"],
+            C!["is-grouped"],
+            C!["is-grouped-centered"],
+            C!["mt-5"],
+            div![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-light"],
+                    C!["is-soft"],
+                    ev(Ev::Click, |_| Msg::CloseDialog),
+                    "Cancel",
+                ]
+            ],
+            div![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-primary"],
+                    C![IF![loading => "is-loading"]],
+                    attrs! {
+                        At::Disabled => save_disabled.as_at_value(),
+                    },
+                    ev(Ev::Click, |_| Msg::SaveAsRoutine),
+                    "Save",
+                ]
+            ],
+        ],
+    ]
+}
+
+fn view_summary_dialog(
+    training_session: &domain::TrainingSession,
+    copied: bool,
+    data_model: &data::Model,
+) -> Vec<Node<Msg>> {
+    let separator = data_model.settings.decimal_separator;
+    let records = personal_records(
+        training_session,
+        &data_model.training_sessions,
+        &data_model.exercises,
+        data_model.all_training_sessions_loaded() && not(data_model.training_sessions.is_loading()),
+    );
+    let stimulus_per_muscle = training_session
+        .stimulus_per_muscle(&data_model.exercises)
+        .iter()
+        .filter_map(|(id, stimulus)| {
+            domain::Muscle::from_repr(*id).map(|muscle| (muscle, *stimulus))
+        })
+        .collect::<Vec<_>>();
+    nodes![
+        div![
+            C!["has-text-centered"],
+            C!["mb-5"],
+            common::view_title(&span!["Training session completed"], 3),
+        ],
+        div![
+            C!["columns"],
+            C!["is-gapless"],
+            C!["is-mobile"],
+            training_session.duration().map(|duration| {
+                div![
+                    C!["column"],
+                    common::view_box(
+                        "Duration",
+                        &format!("<strong>{}</strong> min", duration.num_minutes())
+                    )
+                ]
+            }),
+            div![
+                C!["column"],
+                common::view_box(
+                    "Volume load",
+                    &format!(
+                        "<strong>{}</strong>",
+                        training_session.volume_load(&data_model.exercises)
+                    )
+                )
+            ],
+            div![
+                C!["column"],
+                common::view_box(
+                    "Hard sets",
+                    &format!("<strong>{}</strong>", training_session.set_volume())
+                )
+            ],
+        ],
+        match &records {
+            None => IF![data_model.training_sessions.error().is_none() =>
+                div![
+                    C!["has-text-centered"],
+                    C!["mb-5"],
+                    p!["Loading training sessions …"]
+                ]
+            ],
+            Some(records) => IF![not(records.is_empty()) =>
+                div![
+                    C!["has-text-centered"],
+                    C!["mb-5"],
+                    common::view_title(&span!["Personal records"], 3),
+                    records.iter().map(|record| {
+                        p![
+                            span![C!["icon"], C!["has-text-warning"], i![C!["fas fa-trophy"]]],
+                            strong![
+                                data_model
+                                    .exercises
+                                    .get(&record.exercise_id)
+                                    .map_or("Unknown exercise", |e| e.name.as_str())
+                            ],
+                            ": ",
+                            web_app::format_personal_record(&record.kind, separator)
+                        ]
+                    })
+                ]
+            ],
+        },
+        IF![not(stimulus_per_muscle.is_empty()) =>
+            div![
+                C!["has-text-centered"],
+                C!["mb-5"],
+                common::view_title(&span!["Hard sets per muscle"], 3),
+                common::view_sets_per_muscle(&stimulus_per_muscle)
+            ]
+        ],
+        div![
+            C!["field"],
+            C!["is-grouped"],
+            C!["is-grouped-centered"],
+            C!["mt-5"],
+            div![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-link"],
+                    C!["is-outlined"],
+                    ev(Ev::Click, |_| Msg::CopyTrainingSession),
+                    span![
+                        C!["icon"],
+                        i![C!["fas"], C![if copied { "fa-check" } else { "fa-copy" }]]
+                    ],
+                    span![if copied { "Copied" } else { "Copy" }]
+                ]
+            ],
+            IF![web_app::share::share_supported() =>
+                div![
+                    C!["control"],
+                    button![
+                        C!["button"],
+                        C!["is-link"],
+                        C!["is-outlined"],
+                        ev(Ev::Click, |_| Msg::ShareTrainingSession),
+                        span![C!["icon"], i![C!["fas fa-share-nodes"]]],
+                        span!["Share"]
+                    ]
+                ]
+            ],
+            div![
+                C!["control"],
+                button![
+                    C!["button"],
+                    C!["is-primary"],
+                    ev(Ev::Click, |_| Msg::CloseDialog),
+                    "Close",
+                ]
+            ],
+        ],
+    ]
+}
+
+/// Determine the personal records achieved in the training session.
+///
+/// No records are returned until the complete training history has been loaded, as records based
+/// on a partial history would be misleading.
+fn personal_records(
+    training_session: &domain::TrainingSession,
+    training_sessions: &BTreeMap<u32, domain::TrainingSession>,
+    exercises: &BTreeMap<u32, domain::Exercise>,
+    complete_history: bool,
+) -> Option<Vec<domain::PersonalRecord>> {
+    complete_history.then(|| {
+        domain::personal_records(
+            training_session,
+            &training_sessions.values().collect::<Vec<_>>(),
+            exercises,
+        )
+    })
+}
+
+fn view_options_dialog(
+    element_idx: usize,
+    exercise_idx: usize,
+    exercise: Option<&ExerciseForm>,
+) -> Vec<Node<Msg>> {
+    let to_failure = exercise.is_some_and(|e| e.to_failure);
+    let partial_reps_shown = exercise.is_some_and(|e| e.partial_reps_shown);
     nodes![
+        p![a![
+            C!["has-text-weight-bold"],
+            ev(Ev::Click, move |_| Msg::ToggleFailure(
+                element_idx,
+                exercise_idx
+            )),
+            span![
+                C!["icon-text"],
+                span![C!["icon"], i![C!["fas fa-battery-empty"]]],
+                span![if to_failure {
+                    "Unmark failure"
+                } else {
+                    "Mark as failure"
+                }],
+            ]
+        ]],
+        p![
+            C!["mt-3"],
+            a![
+                C!["has-text-weight-bold"],
+                ev(Ev::Click, move |_| Msg::TogglePartialReps(
+                    element_idx,
+                    exercise_idx
+                )),
+                span![
+                    C!["icon-text"],
+                    span![C!["icon"], i![C!["fas fa-plus-minus"]]],
+                    span![if partial_reps_shown {
+                        "Hide partial reps"
+                    } else {
+                        "Show partial reps"
+                    }],
+                ]
+            ]
+        ],
+        p![C!["mt-5"]],
         p![a![
             C!["has-text-weight-bold"],
             ev(Ev::Click, move |_| Msg::ShowReplaceExerciseDialog(
@@ -2958,9 +5038,53 @@ fn show_guide_timer(exercise: &ExerciseForm) -> bool {
 mod tests {
     use common::InputField;
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
 
     use super::*;
 
+    #[rstest]
+    #[case::automatic_set(vec![set(vec![timed_exercise(None, Some(30), true)])], 0, 0, 30)]
+    #[case::automatic_set_with_reps(
+        vec![set(vec![timed_exercise(Some(8), Some(3), true)])], 0, 0, 24
+    )]
+    #[case::manual_set(vec![set(vec![timed_exercise(Some(8), None, false)])], 0, 0, 32)]
+    #[case::manual_set_with_time(
+        vec![set(vec![timed_exercise(Some(8), Some(3), false)])], 0, 0, 24
+    )]
+    #[case::untimed_set(vec![set(vec![timed_exercise(None, None, false)])], 0, 0, 4)]
+    #[case::superset(
+        vec![set(vec![timed_exercise(Some(5), None, false), timed_exercise(None, Some(20), true)])],
+        0,
+        0,
+        40
+    )]
+    #[case::rests(
+        vec![rest(60), set(vec![timed_exercise(Some(5), None, false)]), rest(90)],
+        0,
+        0,
+        170
+    )]
+    #[case::trailing_elements(
+        vec![rest(60), set(vec![timed_exercise(Some(5), None, false)]), rest(90)],
+        1,
+        0,
+        110
+    )]
+    #[case::elapsed_time(vec![rest(60), rest(90)], 0, 45, 105)]
+    #[case::elapsed_time_exceeded(vec![rest(60), rest(90)], 0, 75, 90)]
+    #[case::after_last_element(vec![rest(60)], 1, 0, 0)]
+    fn test_estimated_remaining_duration(
+        #[case] elements: Vec<FormElement>,
+        #[case] element_idx: usize,
+        #[case] elapsed_time: i64,
+        #[case] expected: i64,
+    ) {
+        assert_eq!(
+            estimated_remaining_duration(&elements, element_idx, Duration::seconds(elapsed_time)),
+            Duration::seconds(expected)
+        );
+    }
+
     #[test]
     fn test_replace_exercise_first_set() {
         let mut elements = vec![
@@ -2997,6 +5121,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_replace_exercise_with_comment() {
+        let mut elements = vec![
+            set(vec![commented_exercise(0, 0)]),
+            rest(0),
+            set(vec![exercise(1, 0)]),
+        ];
+        replace_exercise(&mut elements, 0, 0, 2, &exercises(2));
+        assert_eq!(
+            elements,
+            vec![
+                set(vec![commented_exercise(0, 2)]),
+                rest(0),
+                set(vec![exercise(1, 2)]),
+            ]
+        );
+    }
+
     #[test]
     fn test_replace_exercise_second_set() {
         let mut elements = vec![
@@ -3315,9 +5457,8 @@ mod tests {
                 set(vec![exercise(0, 0)]),
                 rest(0),
                 set(vec![exercise(2, 2)]),
-                rest(0),
-                set(vec![exercise(1, 1)]),
                 rest(1),
+                set(vec![exercise(1, 1)]),
             ]
         );
     }
@@ -3494,9 +5635,8 @@ mod tests {
                 set(vec![exercise(0, 0)]),
                 rest(0),
                 set(vec![exercise(2, 2)]),
-                rest(0),
-                set(vec![exercise(1, 1)]),
                 rest(1),
+                set(vec![exercise(1, 1)]),
             ]
         );
     }
@@ -3633,6 +5773,34 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::without_trailing_rest(vec![
+        set(vec![exercise(0, 0)]),
+        rest(0),
+        set(vec![exercise(1, 1)]),
+        rest(1),
+        set(vec![exercise(2, 2)]),
+    ])]
+    #[case::with_trailing_rest(vec![
+        set(vec![exercise(0, 0)]),
+        rest(0),
+        set(vec![exercise(1, 1)]),
+        rest(1),
+        set(vec![exercise(2, 2)]),
+        rest(2),
+    ])]
+    fn test_prefer_defer_exercise_round_trip(#[case] elements: Vec<FormElement>) {
+        let mut result = elements.clone();
+        prefer_exercise(&mut result, 4);
+        defer_exercise(&mut result, 2);
+        assert_eq!(result, elements);
+
+        let mut result = elements.clone();
+        defer_exercise(&mut result, 2);
+        prefer_exercise(&mut result, 4);
+        assert_eq!(result, elements);
+    }
+
     #[test]
     fn test_add_set_first_set() {
         let mut elements = vec![
@@ -3663,6 +5831,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_set_with_comment() {
+        let mut elements = vec![
+            set(vec![commented_exercise(0, 0)]),
+            rest(0),
+            set(vec![exercise(1, 1)]),
+        ];
+        add_set(&mut elements, 0);
+        assert_eq!(
+            elements,
+            vec![
+                set(vec![commented_exercise(0, 0)]),
+                rest(0),
+                set(vec![exercise(0, 0)]),
+                rest(0),
+                set(vec![exercise(1, 1)]),
+            ]
+        );
+    }
+
     #[test]
     fn test_add_set_second_set() {
         let mut elements = vec![
@@ -4995,6 +7183,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_append_exercise_unilateral() {
+        let mut elements = vec![set(vec![exercise(0, 1)])];
+        append_exercise(&mut elements, 2, &unilateral_exercises(2));
+        assert_eq!(
+            elements,
+            vec![
+                set(vec![exercise(0, 1)]),
+                rest(0),
+                set(vec![
+                    sided_exercise(0, 2, domain::Side::Left),
+                    sided_exercise(0, 2, domain::Side::Right)
+                ])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_exercise_unilateral() {
+        let mut elements = vec![
+            set(vec![
+                sided_exercise(0, 1, domain::Side::Left),
+                sided_exercise(0, 1, domain::Side::Right),
+            ]),
+            rest(0),
+        ];
+        add_exercise(&mut elements, 0, 0, 2, &unilateral_exercises(2));
+        assert_eq!(
+            elements,
+            vec![
+                set(vec![
+                    sided_exercise(0, 1, domain::Side::Left),
+                    sided_exercise(0, 1, domain::Side::Right),
+                    sided_exercise(0, 2, domain::Side::Left),
+                    sided_exercise(0, 2, domain::Side::Right),
+                ]),
+                rest(0),
+            ]
+        );
+    }
+
     #[test]
     fn test_determine_sections() {
         assert_eq!(
@@ -5027,26 +7256,673 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_determine_rounds() {
+        let activity = |exercise_id| domain::RoutinePart::RoutineActivity {
+            exercise_id,
+            reps: domain::RepsRange::default(),
+            time: 0,
+            weight: 0.0,
+            rpe: domain::RpeRange::default(),
+            automatic: false,
+            target_percent_1rm: None,
+        };
+        let routine = domain::Routine {
+            id: 1,
+            name: String::from("A"),
+            notes: None,
+            archived: false,
+            sections: vec![domain::RoutinePart::RoutineSection {
+                rounds: 2,
+                parts: vec![activity(Some(0)), activity(Some(2)), activity(None)],
+            }],
+            schedule: vec![],
+            tags: vec![],
+        };
+        let round = |number| vec![domain::Round { number, total: 2 }];
+        let elements = [
+            set(vec![exercise(0, 0), exercise(1, 2)]),
+            rest(0),
+            set(vec![exercise(2, 0), exercise(3, 2)]),
+            rest(1),
+            set(vec![exercise(4, 0)]),
+        ];
+
+        assert_eq!(
+            determine_rounds(&elements, Some(&routine)),
+            vec![round(1), round(1), round(2), round(2), vec![]]
+        );
+        assert_eq!(determine_rounds(&elements, None), vec![vec![]; 5]);
+    }
+
+    #[test]
+    fn test_restore_unsaved_changes() {
+        let saved = InputField {
+            input: String::from("10"),
+            parsed: Some(10),
+            orig: String::from("10"),
+            error: None,
+        };
+        let unsaved = InputField {
+            input: String::from("8"),
+            parsed: Some(8),
+            orig: String::new(),
+            error: None,
+        };
+        let mut form = Form {
+            notes: String::from("A"),
+            notes_changed: false,
+            elements: vec![
+                set(vec![ExerciseForm {
+                    reps: saved.clone(),
+                    ..exercise(0, 1)
+                }]),
+                rest(0),
+                set(vec![exercise(0, 1)]),
+            ],
+        };
+        let previous_form = Form {
+            notes: String::from("B"),
+            notes_changed: true,
+            elements: vec![
+                set(vec![ExerciseForm {
+                    reps: InputField {
+                        input: String::from("10"),
+                        parsed: Some(10),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    ..exercise(0, 1)
+                }]),
+                rest(0),
+                set(vec![ExerciseForm {
+                    reps: unsaved,
+                    ..exercise(0, 1)
+                }]),
+            ],
+        };
+        restore_unsaved_changes(&mut form, &previous_form, false);
+        assert_eq!(
+            form,
+            Form {
+                notes: String::from("B"),
+                notes_changed: true,
+                elements: vec![
+                    set(vec![ExerciseForm {
+                        reps: saved,
+                        ..exercise(0, 1)
+                    }]),
+                    rest(0),
+                    set(vec![ExerciseForm {
+                        reps: InputField {
+                            input: String::from("8"),
+                            parsed: Some(8),
+                            orig: String::new(),
+                            error: None,
+                        },
+                        ..exercise(0, 1)
+                    }]),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_restore_unsaved_changes_different_exercise() {
+        let mut form = Form {
+            notes: String::new(),
+            notes_changed: false,
+            elements: vec![set(vec![exercise(0, 1)])],
+        };
+        let previous_form = Form {
+            notes: String::new(),
+            notes_changed: false,
+            elements: vec![set(vec![ExerciseForm {
+                reps: InputField {
+                    input: String::from("8"),
+                    parsed: Some(8),
+                    orig: String::new(),
+                    error: None,
+                },
+                ..exercise(0, 2)
+            }])],
+        };
+        restore_unsaved_changes(&mut form, &previous_form, false);
+        assert_eq!(
+            form,
+            Form {
+                notes: String::new(),
+                notes_changed: false,
+                elements: vec![set(vec![exercise(0, 1)])],
+            }
+        );
+    }
+
+    #[test]
+    fn test_restore_unsaved_changes_saving_failed() {
+        let mut form = Form {
+            notes: String::new(),
+            notes_changed: false,
+            elements: vec![set(vec![exercise(0, 1)])],
+        };
+        let previous_form = Form {
+            notes: String::from("A"),
+            notes_changed: false,
+            elements: vec![set(vec![ExerciseForm {
+                reps: InputField {
+                    input: String::from("8"),
+                    parsed: Some(8),
+                    orig: String::from("8"),
+                    error: None,
+                },
+                ..exercise(0, 1)
+            }])],
+        };
+        restore_unsaved_changes(&mut form, &previous_form, true);
+        assert_eq!(
+            form,
+            Form {
+                notes: String::from("A"),
+                notes_changed: true,
+                elements: vec![set(vec![ExerciseForm {
+                    reps: InputField {
+                        input: String::from("8"),
+                        parsed: Some(8),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    ..exercise(0, 1)
+                }])],
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_draft_without_changes() {
+        let form = Form {
+            notes: String::from("A"),
+            notes_changed: false,
+            elements: vec![set(vec![exercise(0, 1)]), rest(0)],
+        };
+        assert_eq!(
+            create_draft(&form, &InputField::default(), &training_session()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_create_and_apply_draft() {
+        let unsaved_form = Form {
+            notes: String::from("B"),
+            notes_changed: true,
+            elements: vec![
+                set(vec![exercise(0, 1)]),
+                rest(0),
+                set(vec![
+                    exercise(0, 1),
+                    ExerciseForm {
+                        reps: InputField {
+                            input: String::from("8"),
+                            parsed: Some(8),
+                            orig: String::new(),
+                            error: None,
+                        },
+                        partial_reps: InputField {
+                            input: String::from("2"),
+                            parsed: Some(2),
+                            orig: String::new(),
+                            error: None,
+                        },
+                        rpe: InputField {
+                            input: String::from("11"),
+                            parsed: None,
+                            orig: String::new(),
+                            error: Some(domain::SetValueError::RPE.to_string()),
+                        },
+                        to_failure: true,
+                        to_failure_changed: true,
+                        comment: String::from("Felt pinch in left knee"),
+                        comment_changed: true,
+                        comment_shown: true,
+                        partial_reps_shown: true,
+                        ..exercise(0, 2)
+                    },
+                ]),
+            ],
+        };
+        let unsaved_duration = InputField {
+            input: String::from("45"),
+            parsed: Some(45),
+            orig: String::new(),
+            error: None,
+        };
+
+        let draft = create_draft(&unsaved_form, &unsaved_duration, &training_session()).unwrap();
+
+        assert_eq!(
+            draft,
+            web_app::TrainingSessionDraft {
+                training_session: training_session(),
+                notes: Some(String::from("B")),
+                duration: Some(String::from("45")),
+                sets: vec![web_app::SetDraft {
+                    element_idx: 2,
+                    exercise_idx: 1,
+                    exercise_id: 2,
+                    reps: Some(String::from("8")),
+                    time: None,
+                    weight: None,
+                    rpe: Some(String::from("11")),
+                    partial_reps: Some(String::from("2")),
+                    to_failure: Some(true),
+                    comment: Some(String::from("Felt pinch in left knee")),
+                }],
+            }
+        );
+
+        let mut form = Form {
+            notes: String::from("A"),
+            notes_changed: false,
+            elements: vec![
+                set(vec![exercise(0, 1)]),
+                rest(0),
+                set(vec![exercise(0, 1), exercise(0, 2)]),
+            ],
+        };
+        let mut duration = InputField::default();
+
+        apply_draft(&mut form, &mut duration, &draft);
+
+        assert_eq!(form, unsaved_form);
+        assert_eq!(duration, unsaved_duration);
+    }
+
+    #[test]
+    fn test_apply_draft_different_exercise() {
+        let mut form = Form {
+            notes: String::new(),
+            notes_changed: false,
+            elements: vec![set(vec![exercise(0, 1)])],
+        };
+        let mut duration = InputField::default();
+        apply_draft(
+            &mut form,
+            &mut duration,
+            &web_app::TrainingSessionDraft {
+                training_session: training_session(),
+                notes: None,
+                duration: None,
+                sets: vec![
+                    web_app::SetDraft {
+                        element_idx: 0,
+                        exercise_idx: 0,
+                        exercise_id: 2,
+                        reps: Some(String::from("8")),
+                        time: None,
+                        weight: None,
+                        rpe: None,
+                        partial_reps: None,
+                        to_failure: None,
+                        comment: None,
+                    },
+                    web_app::SetDraft {
+                        element_idx: 1,
+                        exercise_idx: 0,
+                        exercise_id: 1,
+                        reps: Some(String::from("8")),
+                        time: None,
+                        weight: None,
+                        rpe: None,
+                        partial_reps: None,
+                        to_failure: None,
+                        comment: None,
+                    },
+                ],
+            },
+        );
+        assert_eq!(
+            form,
+            Form {
+                notes: String::new(),
+                notes_changed: false,
+                elements: vec![set(vec![exercise(0, 1)])],
+            }
+        );
+        assert_eq!(duration, InputField::default());
+    }
+
+    #[rstest]
+    #[case::reps(0, 0, SetInput::Reps, true, true, Some((0, 0, SetInput::Time)))]
+    #[case::time_hidden(0, 0, SetInput::Reps, false, true, Some((0, 0, SetInput::Weight)))]
+    #[case::rpe(0, 0, SetInput::Weight, true, true, Some((0, 0, SetInput::RPE)))]
+    #[case::rpe_hidden(0, 0, SetInput::Weight, true, false, Some((0, 1, SetInput::Reps)))]
+    #[case::next_exercise(0, 0, SetInput::RPE, true, true, Some((0, 1, SetInput::Reps)))]
+    #[case::next_set(0, 1, SetInput::RPE, true, true, Some((2, 0, SetInput::Reps)))]
+    #[case::last_input(2, 0, SetInput::RPE, true, true, None)]
+    #[case::last_input_rpe_hidden(2, 0, SetInput::Weight, true, false, None)]
+    fn test_next_input(
+        #[case] element_idx: usize,
+        #[case] exercise_idx: usize,
+        #[case] input: SetInput,
+        #[case] show_tut: bool,
+        #[case] show_rpe: bool,
+        #[case] expected: Option<(usize, usize, SetInput)>,
+    ) {
+        let elements = [
+            set(vec![exercise(0, 1), exercise(0, 2)]),
+            rest(0),
+            set(vec![exercise(0, 1)]),
+        ];
+        assert_eq!(
+            next_input(
+                &elements,
+                element_idx,
+                exercise_idx,
+                input,
+                show_tut,
+                show_rpe
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_push_undo_snapshot() {
+        let mut undo_stack = vec![];
+        push_undo_snapshot(&mut undo_stack, &[set(vec![exercise(0, 1)])]);
+        assert_eq!(undo_stack, vec![vec![set(vec![exercise(0, 1)])]]);
+
+        let size = u32::try_from(UNDO_STACK_SIZE).unwrap();
+        for exercise_id in 2..=size + 1 {
+            push_undo_snapshot(&mut undo_stack, &[set(vec![exercise(0, exercise_id)])]);
+        }
+        assert_eq!(undo_stack.len(), UNDO_STACK_SIZE);
+        assert_eq!(undo_stack[0], vec![set(vec![exercise(0, 2)])]);
+        assert_eq!(
+            undo_stack[UNDO_STACK_SIZE - 1],
+            vec![set(vec![exercise(0, size + 1)])]
+        );
+    }
+
+    #[test]
+    fn test_personal_records() {
+        let session = |id, day, reps| domain::TrainingSession {
+            id,
+            date: NaiveDate::from_ymd_opt(2020, 2, day).unwrap(),
+            elements: vec![domain::TrainingSessionElement::Set {
+                exercise_id: 1,
+                reps: Some(reps),
+                time: None,
+                weight: Some(80.0),
+                rpe: None,
+                target_reps: None,
+                target_time: None,
+                target_weight: None,
+                target_rpe: None,
+                automatic: false,
+                partial_reps: None,
+                to_failure: false,
+                side: None,
+                comment: None,
+            }],
+            ..training_session()
+        };
+        let training_session = session(2, 2, 6);
+        let training_sessions =
+            BTreeMap::from([(1, session(1, 1, 5)), (2, training_session.clone())]);
+
+        assert_eq!(
+            personal_records(&training_session, &training_sessions, &exercises(1), true),
+            Some(vec![
+                domain::PersonalRecord {
+                    exercise_id: 1,
+                    kind: domain::RecordKind::OneRepMax(
+                        domain::progression::one_rep_max(6, 80.0, None).unwrap()
+                    )
+                },
+                domain::PersonalRecord {
+                    exercise_id: 1,
+                    kind: domain::RecordKind::Reps {
+                        reps: 6,
+                        weight: Some(80.0)
+                    }
+                },
+            ])
+        );
+        assert_eq!(
+            personal_records(&training_session, &training_sessions, &exercises(1), false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_clamp_element_idx() {
+        let elements = [
+            set(vec![exercise(0, 1)]),
+            rest(0),
+            set(vec![exercise(0, 1)]),
+        ];
+        assert_eq!(clamp_element_idx(1, &elements), 1);
+        assert_eq!(clamp_element_idx(2, &elements), 2);
+        assert_eq!(clamp_element_idx(5, &elements), 2);
+        assert_eq!(clamp_element_idx(5, &[]), 0);
+    }
+
+    #[test]
+    fn test_checked_element_idx() {
+        let elements = [
+            set(vec![exercise(0, 1)]),
+            rest(0),
+            set(vec![exercise(0, 1)]),
+        ];
+        assert_eq!(checked_element_idx(1, &elements), Some(1));
+        assert_eq!(checked_element_idx(5, &elements), Some(2));
+        assert_eq!(checked_element_idx(0, &[]), None);
+    }
+
+    #[test]
+    fn test_checked_element_idx_remove_current_set() {
+        let mut elements = vec![
+            set(vec![exercise(0, 0)]),
+            rest(0),
+            set(vec![exercise(1, 0)]),
+            rest(1),
+            set(vec![exercise(2, 1)]),
+            rest(2),
+        ];
+        let guide_idx = 2;
+        remove_set(&mut elements, guide_idx);
+        let element_idx = checked_element_idx(guide_idx, &elements).unwrap();
+        assert_eq!(element_idx, 2);
+        assert_eq!(elements[element_idx], set(vec![exercise(2, 1)]));
+    }
+
+    #[test]
+    fn test_checked_element_idx_remove_last_set() {
+        let mut elements = vec![
+            set(vec![exercise(0, 0)]),
+            rest(0),
+            set(vec![exercise(1, 0)]),
+            rest(1),
+            set(vec![exercise(2, 1)]),
+            rest(2),
+        ];
+        let guide_idx = 5;
+        remove_set(&mut elements, 4);
+        let element_idx = checked_element_idx(guide_idx, &elements).unwrap();
+        assert_eq!(element_idx, 3);
+        assert_eq!(elements[element_idx], rest(2));
+    }
+
+    #[test]
+    fn test_checked_element_idx_remove_last_exercise() {
+        let mut elements = vec![set(vec![exercise(0, 0)]), rest(0)];
+        let guide_idx = 1;
+        remove_exercise(&mut elements, 0, 0);
+        assert_eq!(elements, vec![]);
+        assert_eq!(checked_element_idx(guide_idx, &elements), None);
+    }
+
+    #[test]
+    fn test_checked_element_idx_replace_exercise_after_guide() {
+        let mut elements = vec![
+            set(vec![exercise(0, 0)]),
+            rest(0),
+            set(vec![exercise(1, 0)]),
+            rest(1),
+            set(vec![exercise(2, 1)]),
+            rest(2),
+            set(vec![exercise(3, 1)]),
+            rest(3),
+        ];
+        let guide_idx = 2;
+        replace_exercise(&mut elements, 4, 0, 3, &exercises(3));
+        let element_idx = checked_element_idx(guide_idx, &elements).unwrap();
+        assert_eq!(element_idx, 2);
+        assert_eq!(elements[element_idx], set(vec![exercise(1, 0)]));
+        assert_eq!(elements[4], set(vec![exercise(2, 3)]));
+        assert_eq!(elements[6], set(vec![exercise(3, 3)]));
+    }
+
+    #[test]
+    fn test_has_previous_values() {
+        assert!(!has_previous_values(&[]));
+        assert!(!has_previous_values(&[
+            set(vec![exercise(0, 1)]),
+            rest(0),
+            set(vec![exercise(0, 1)])
+        ]));
+        assert!(has_previous_values(&[
+            set(vec![exercise(0, 1)]),
+            rest(0),
+            set(vec![ExerciseForm {
+                prev_time: Some(30),
+                ..exercise(0, 1)
+            }])
+        ]));
+    }
+
+    #[test]
+    fn test_fill_from_previous_session() {
+        let previous = ExerciseForm {
+            prev_reps: Some(10),
+            prev_weight: Some(20.0),
+            prev_rpe: Some(8.0),
+            ..exercise(0, 1)
+        };
+        let mut elements = vec![
+            set(vec![previous.clone(), exercise(0, 2)]),
+            rest(0),
+            set(vec![
+                ExerciseForm {
+                    reps: InputField {
+                        input: String::from("8"),
+                        parsed: Some(8),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    ..previous.clone()
+                },
+                exercise(0, 2),
+            ]),
+        ];
+        let expected = vec![
+            set(vec![
+                ExerciseForm {
+                    reps: InputField {
+                        input: String::from("10"),
+                        parsed: Some(10),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    weight: InputField {
+                        input: String::from("20"),
+                        parsed: Some(20.0),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    rpe: InputField {
+                        input: String::from("8"),
+                        parsed: Some(8.0),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    ..previous.clone()
+                },
+                exercise(0, 2),
+            ]),
+            rest(0),
+            set(vec![
+                ExerciseForm {
+                    reps: InputField {
+                        input: String::from("8"),
+                        parsed: Some(8),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    weight: InputField {
+                        input: String::from("20"),
+                        parsed: Some(20.0),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    rpe: InputField {
+                        input: String::from("8"),
+                        parsed: Some(8.0),
+                        orig: String::new(),
+                        error: None,
+                    },
+                    ..previous
+                },
+                exercise(0, 2),
+            ]),
+        ];
+
+        fill_from_previous_session(&mut elements);
+        assert_eq!(elements, expected);
+
+        fill_from_previous_session(&mut elements);
+        assert_eq!(elements, expected);
+    }
+
     fn exercises(id: u32) -> BTreeMap<u32, domain::Exercise> {
         BTreeMap::from([(
             id,
             domain::Exercise {
                 id,
                 name: id.to_string(),
+                weight_convention: None,
+                base_weight: None,
+                laterality: domain::Laterality::Bilateral,
+                archived: false,
+                image: None,
                 muscles: Vec::new(),
             },
         )])
     }
 
+    fn unilateral_exercises(id: u32) -> BTreeMap<u32, domain::Exercise> {
+        let mut exercises = exercises(id);
+        for exercise in exercises.values_mut() {
+            exercise.laterality = domain::Laterality::Unilateral;
+        }
+        exercises
+    }
+
     fn exercise(entry_id: u32, exercise_id: u32) -> ExerciseForm {
         ExerciseForm {
             exercise_id,
             exercise_name: exercise_id.to_string(),
             reps: InputField::default(),
+            partial_reps: InputField::default(),
             time: InputField::default(),
             weight: InputField::default(),
             rpe: InputField::default(),
-            target_reps: if entry_id > 0 { Some(entry_id) } else { None },
+            target_reps: if entry_id > 0 {
+                Some(domain::RepsRange::single(entry_id))
+            } else {
+                None
+            },
             target_time: None,
             target_weight: None,
             target_rpe: None,
@@ -5058,7 +7934,55 @@ mod tests {
             prev_set_time: None,
             prev_set_weight: None,
             prev_set_rpe: None,
+            suggestion: domain::progression::Set::default(),
             automatic: false,
+            to_failure: false,
+            side: None,
+            to_failure_changed: false,
+            comment: String::new(),
+            comment_changed: false,
+            comment_shown: false,
+            partial_reps_shown: false,
+        }
+    }
+
+    fn sided_exercise(entry_id: u32, exercise_id: u32, side: domain::Side) -> ExerciseForm {
+        ExerciseForm {
+            side: Some(side),
+            ..exercise(entry_id, exercise_id)
+        }
+    }
+
+    fn commented_exercise(entry_id: u32, exercise_id: u32) -> ExerciseForm {
+        ExerciseForm {
+            comment: String::from("Felt pinch in left knee"),
+            comment_shown: true,
+            ..exercise(entry_id, exercise_id)
+        }
+    }
+
+    fn training_session() -> domain::TrainingSession {
+        domain::TrainingSession {
+            id: 1,
+            routine_id: None,
+            date: NaiveDate::from_ymd_opt(2020, 2, 2).unwrap(),
+            notes: Some(String::from("A")),
+            start_time: None,
+            end_time: None,
+            elements: vec![],
+        }
+    }
+
+    fn timed_exercise(
+        target_reps: Option<u32>,
+        target_time: Option<u32>,
+        automatic: bool,
+    ) -> ExerciseForm {
+        ExerciseForm {
+            target_reps: target_reps.map(domain::RepsRange::single),
+            target_time,
+            automatic,
+            ..exercise(0, 1)
         }
     }
 
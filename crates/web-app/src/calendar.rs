@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Months, NaiveDate};
+
+/// Return the first day of the month of the given date.
+#[must_use]
+pub fn first_day_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// Group the entries by day, considering only the days in the month of the given date.
+///
+/// The order of entries on the same day is preserved.
+#[must_use]
+pub fn entries_per_day<T>(
+    month: NaiveDate,
+    entries: impl IntoIterator<Item = (NaiveDate, T)>,
+) -> BTreeMap<NaiveDate, Vec<T>> {
+    let first_day = first_day_of_month(month);
+    let mut result: BTreeMap<NaiveDate, Vec<T>> = BTreeMap::new();
+    for (date, entry) in entries {
+        if first_day_of_month(date) == first_day {
+            result.entry(date).or_default().push(entry);
+        }
+    }
+    result
+}
+
+/// Months that can be navigated to, ranging from the month of the first entry to the current
+/// month.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonthRange {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl MonthRange {
+    #[must_use]
+    pub fn new(first_entry: Option<NaiveDate>, today: NaiveDate) -> Self {
+        let last = first_day_of_month(today);
+        Self {
+            first: first_entry.map_or(last, first_day_of_month).min(last),
+            last,
+        }
+    }
+
+    #[must_use]
+    pub fn previous(&self, month: NaiveDate) -> Option<NaiveDate> {
+        first_day_of_month(month)
+            .checked_sub_months(Months::new(1))
+            .filter(|m| *m >= self.first)
+    }
+
+    #[must_use]
+    pub fn next(&self, month: NaiveDate) -> Option<NaiveDate> {
+        first_day_of_month(month)
+            .checked_add_months(Months::new(1))
+            .filter(|m| *m <= self.last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_entries_per_day() {
+        assert_eq!(
+            entries_per_day(
+                date(2, 15),
+                [
+                    (date(1, 31), 1),
+                    (date(2, 1), 2),
+                    (date(2, 10), 3),
+                    (date(2, 10), 4),
+                    (date(2, 29), 5),
+                    (date(2, 10), 6),
+                    (date(3, 1), 7),
+                ]
+            ),
+            BTreeMap::from([
+                (date(2, 1), vec![2]),
+                (date(2, 10), vec![3, 4, 6]),
+                (date(2, 29), vec![5]),
+            ])
+        );
+    }
+
+    #[rstest]
+    #[case::first_month(date(1, 20), None, Some(date(2, 1)))]
+    #[case::middle_month(date(2, 20), Some(date(1, 1)), Some(date(3, 1)))]
+    #[case::current_month(date(3, 5), Some(date(2, 1)), None)]
+    fn test_month_range(
+        #[case] month: NaiveDate,
+        #[case] expected_previous: Option<NaiveDate>,
+        #[case] expected_next: Option<NaiveDate>,
+    ) {
+        let month_range = MonthRange::new(Some(date(1, 15)), date(3, 10));
+
+        assert_eq!(month_range.previous(month), expected_previous);
+        assert_eq!(month_range.next(month), expected_next);
+    }
+
+    #[test]
+    fn test_month_range_without_entries() {
+        let month_range = MonthRange::new(None, date(3, 10));
+
+        assert_eq!(
+            month_range,
+            MonthRange {
+                first: date(3, 1),
+                last: date(3, 1)
+            }
+        );
+        assert_eq!(month_range.previous(date(3, 10)), None);
+        assert_eq!(month_range.next(date(3, 10)), None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{borrow::BorrowMut, collections::BTreeMap};
+use std::{borrow::BorrowMut, collections::BTreeMap, fmt::Write};
 
 use chrono::prelude::*;
 use gloo_utils::window;
@@ -15,8 +15,15 @@ use crate::Theme;
 
 pub const COLOR_BODY_WEIGHT: usize = 1;
 pub const COLOR_AVG_BODY_WEIGHT: usize = 1;
+pub const COLOR_BODY_WEIGHT_GOAL: usize = 7;
+pub const COLOR_BMI: usize = 6;
 pub const COLOR_BODY_FAT_JP3: usize = 4;
 pub const COLOR_BODY_FAT_JP7: usize = 0;
+pub const COLOR_BODY_FAT_NAVY: usize = 5;
+pub const COLOR_SKINFOLD: usize = 4;
+pub const COLOR_AVG_SKINFOLD: usize = 4;
+pub const COLOR_BODY_MEASUREMENT: usize = 2;
+pub const COLOR_AVG_BODY_MEASUREMENT: usize = 2;
 pub const COLOR_PERIOD_INTENSITY: usize = 0;
 pub const COLOR_LOAD: usize = 1;
 pub const COLOR_LONG_TERM_LOAD: usize = 1;
@@ -36,6 +43,24 @@ pub const WIDTH_LINE: u32 = 2;
 
 pub const FONT: (&str, u32) = ("Roboto", 11);
 
+/// Colors used instead of the default palette when high contrast is preferred, for light and dark
+/// background.
+const HIGH_CONTRAST_PALETTE: [(RGBColor, RGBColor); 9] = [
+    (RGBColor(0, 84, 166), RGBColor(110, 180, 255)),
+    (RGBColor(190, 0, 0), RGBColor(255, 115, 115)),
+    (RGBColor(0, 115, 0), RGBColor(90, 230, 90)),
+    (RGBColor(125, 0, 160), RGBColor(215, 150, 255)),
+    (RGBColor(165, 80, 0), RGBColor(255, 180, 60)),
+    (RGBColor(0, 105, 115), RGBColor(60, 220, 230)),
+    (RGBColor(165, 0, 95), RGBColor(255, 125, 205)),
+    (RGBColor(60, 60, 60), RGBColor(220, 220, 220)),
+    (RGBColor(95, 70, 0), RGBColor(235, 205, 80)),
+];
+
+const MARGIN: u32 = 10;
+const X_LABEL_AREA_SIZE: u32 = 30;
+const Y_LABEL_AREA_SIZE: u32 = 40;
+
 #[derive(Clone)]
 pub enum PlotType {
     #[allow(dead_code)]
@@ -63,6 +88,43 @@ pub fn plot_area_with_border(line_color: usize, area_color: usize) -> Vec<PlotTy
     ]
 }
 
+/// Pages with charts, for which the data can be shown as table.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum Page {
+    BodyWeight,
+    BodyFat,
+    BodyMeasurement,
+    MenstrualCycle,
+    Training,
+    Exercise,
+    Routine,
+    Muscles,
+}
+
+/// Appearance of the charts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    pub theme: Theme,
+    pub high_contrast: bool,
+}
+
+/// Pick the color with the given index from the palette matching the style.
+#[must_use]
+pub fn color(index: usize, style: &Style) -> RGBColor {
+    if style.high_contrast {
+        let (light, dark) = HIGH_CONTRAST_PALETTE[index % HIGH_CONTRAST_PALETTE.len()];
+        match style.theme {
+            Theme::System | Theme::Light => light,
+            Theme::Dark => dark,
+        }
+    } else {
+        let (r, g, b) = Palette99::pick(index).rgb();
+        RGBColor(r, g, b)
+    }
+}
+
 #[derive(Default, Clone, Copy)]
 pub struct PlotParams {
     pub y_min_opt: Option<f32>,
@@ -169,7 +231,7 @@ impl Bounds {
 pub fn plot(
     data: &[PlotData],
     interval: &domain::Interval,
-    theme: &Theme,
+    style: &Style,
 ) -> Result<Option<String>, Box<dyn std::error::Error>> {
     if all_zeros(data) {
         return Ok(None);
@@ -182,22 +244,29 @@ pub fn plot(
     let mut result = String::new();
 
     {
-        let root = SVGBackend::with_string(&mut result, (chart_width(), 200)).into_drawing_area();
-        let (color, background_color) = colors(theme);
+        let width = chart_width();
+        let root = SVGBackend::with_string(&mut result, (width, 200)).into_drawing_area();
+        let (color, background_color) = colors(style.theme);
+        // Axes and grid lines are emphasized for high contrast
+        let (axis_opacity, grid_opacity) = if style.high_contrast {
+            (0.8, 0.2)
+        } else {
+            (0.3, 0.05)
+        };
 
         root.fill(&background_color)?;
 
-        let mut chart_builder = ChartBuilder::on(&root);
-        chart_builder
-            .margin(10f32)
-            .x_label_area_size(30f32)
-            .y_label_area_size(40f32);
+        let right_y_label_area_size = secondary_bounds.map_or(0, |_| Y_LABEL_AREA_SIZE);
+        let max_points = usize::try_from(
+            width.saturating_sub(2 * MARGIN + Y_LABEL_AREA_SIZE + right_y_label_area_size),
+        )
+        .unwrap_or(usize::MAX);
 
         let mut chart = ChartBuilder::on(&root)
-            .margin(10f32)
-            .x_label_area_size(30f32)
-            .y_label_area_size(40f32)
-            .right_y_label_area_size(secondary_bounds.map_or_else(|| 0f32, |_| 40f32))
+            .margin(MARGIN)
+            .x_label_area_size(X_LABEL_AREA_SIZE)
+            .y_label_area_size(Y_LABEL_AREA_SIZE)
+            .right_y_label_area_size(right_y_label_area_size)
             .build_cartesian_2d(
                 interval.first..interval.last,
                 primary_bounds.min_with_margin()..primary_bounds.max_with_margin(),
@@ -213,8 +282,8 @@ pub fn plot(
             .configure_mesh()
             .disable_x_mesh()
             .set_all_tick_mark_size(3u32)
-            .axis_style(color.mix(0.3))
-            .bold_line_style(color.mix(0.05))
+            .axis_style(color.mix(axis_opacity))
+            .bold_line_style(color.mix(grid_opacity))
             .light_line_style(color.mix(0.0))
             .label_style(TextStyle::from(FONT.into_font()).color(&color))
             .x_labels(2)
@@ -225,19 +294,29 @@ pub fn plot(
             chart
                 .configure_secondary_axes()
                 .set_all_tick_mark_size(3u32)
-                .axis_style(color.mix(0.3))
+                .axis_style(color.mix(axis_opacity))
                 .label_style(TextStyle::from(FONT.into_font()).color(&color))
                 .draw()?;
         }
 
         for plot_data in data {
-            let mut values_high = Some(plot_data.values_high.clone());
-            if let Some(values) = values_high.as_mut() {
+            // Histograms are not downsampled, as omitting values would change the displayed bars
+            let downsampling = !plot_data
+                .plots
+                .iter()
+                .any(|plot| matches!(plot, PlotType::Histogram(..)));
+            let prepare = |values: &Vec<(NaiveDate, f32)>| {
+                let mut values = values.clone();
                 values.sort_by_key(|e| e.0);
-            }
-            let mut values_low = plot_data.values_low.clone();
+                if downsampling {
+                    downsample(visible_values(&values, interval), max_points)
+                } else {
+                    values
+                }
+            };
+            let values_high = Some(prepare(&plot_data.values_high));
+            let mut values_low = plot_data.values_low.as_ref().map(prepare);
             if let Some(values) = values_low.as_mut() {
-                values.sort_by_key(|e| e.0);
                 values.reverse();
             }
 
@@ -253,7 +332,7 @@ pub fn plot(
                                         Circle::new(
                                             (*x, *y),
                                             size,
-                                            Palette99::pick(color).mix(opacity).filled(),
+                                            self::color(color, style).mix(opacity).filled(),
                                         )
                                     });
                                     if plot_data.params.secondary {
@@ -273,7 +352,7 @@ pub fn plot(
                                 |values| -> Result<(), DrawingAreaErrorKind<std::io::Error>> {
                                     let data = LineSeries::new(
                                         values.iter().map(|(x, y)| (*x, *y)),
-                                        Palette99::pick(color).mix(opacity).stroke_width(size),
+                                        self::color(color, style).mix(opacity).stroke_width(size),
                                     );
                                     if plot_data.params.secondary {
                                         chart.draw_secondary_series(data)?;
@@ -291,7 +370,7 @@ pub fn plot(
                             .try_for_each(
                                 |values| -> Result<(), DrawingAreaErrorKind<std::io::Error>> {
                                     let data = Histogram::vertical(&chart)
-                                        .style(Palette99::pick(color).mix(opacity).filled())
+                                        .style(self::color(color, style).mix(opacity).filled())
                                         .margin(0) // https://github.com/plotters-rs/plotters/issues/300
                                         .data(values.iter().map(|(x, y)| (*x, *y)));
 
@@ -314,7 +393,7 @@ pub fn plot(
                                     })
                                     .unwrap_or_default(),
                                 0.0,
-                                Palette99::pick(color).mix(opacity),
+                                self::color(color, style).mix(opacity),
                             );
                             if plot_data.params.secondary {
                                 chart.draw_secondary_series(data)?;
@@ -333,7 +412,7 @@ pub fn plot(
                                             .collect::<Vec<_>>()
                                     })
                                     .unwrap_or_default(),
-                                Palette99::pick(color).mix(opacity),
+                                self::color(color, style).mix(opacity),
                             );
                             if plot_data.params.secondary {
                                 chart.draw_secondary_series(std::iter::once(data))?;
@@ -352,14 +431,16 @@ pub fn plot(
     Ok(Some(result))
 }
 
-#[allow(clippy::missing_errors_doc)]
-pub fn plot_min_avg_max(
+/// Create the series of the minimum, average and maximum of all values of each day.
+///
+/// The range between minimum and maximum is shown as area and the average as line.
+#[must_use]
+pub fn min_avg_max(
     data: &Vec<(NaiveDate, f32)>,
     interval: &domain::Interval,
     params: PlotParams,
     color: usize,
-    theme: &Theme,
-) -> Result<Option<String>, Box<dyn std::error::Error>> {
+) -> Vec<PlotData> {
     let mut date_map: BTreeMap<&NaiveDate, Vec<f32>> = BTreeMap::new();
 
     for (date, value) in data {
@@ -393,24 +474,431 @@ pub fn plot_min_avg_max(
         values_max.push((date, max));
     }
 
-    plot(
-        &[
-            PlotData {
-                values_high: values_min,
-                values_low: Some(values_max),
-                plots: plot_area(color),
-                params,
-            },
-            PlotData {
-                values_high: values_avg,
-                values_low: None,
-                plots: plot_line(color),
-                params,
+    vec![
+        PlotData {
+            values_high: values_min,
+            values_low: Some(values_max),
+            plots: plot_area(color),
+            params,
+        },
+        PlotData {
+            values_high: values_avg,
+            values_low: None,
+            plots: plot_line(color),
+            params,
+        },
+    ]
+}
+
+/// Create an HTML table of the values of all series inside the interval.
+///
+/// The table contains a column for the date and a column for each label. The label at the same
+/// position as a series determines its column. Series without a label of their own belong to the
+/// column of the last label, so that series which are split into several segments are shown in a
+/// single column. For series with `values_low` the range between both values is shown. The rows
+/// are ordered from the latest to the earliest date. `None` is returned if there are no values
+/// inside the interval.
+#[must_use]
+pub fn table(labels: &[&str], data: &[PlotData], interval: &domain::Interval) -> Option<String> {
+    let mut columns: Vec<&str> = vec![];
+    let mut rows: BTreeMap<NaiveDate, BTreeMap<usize, String>> = BTreeMap::new();
+
+    for (i, plot_data) in data.iter().enumerate() {
+        let Some(label) = labels.get(i).or(labels.last()) else {
+            break;
+        };
+        let column = columns.iter().position(|c| c == label).unwrap_or_else(|| {
+            columns.push(label);
+            columns.len() - 1
+        });
+        let values_low = plot_data
+            .values_low
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<BTreeMap<_, _>>();
+        for (date, value) in &plot_data.values_high {
+            if *date >= interval.first && *date <= interval.last {
+                let mut text = format_value(*value);
+                if let Some(low) = values_low.get(date) {
+                    let (min, max) = (format_value(low.min(*value)), format_value(low.max(*value)));
+                    if min != max {
+                        text = format!("{min}–{max}");
+                    }
+                }
+                rows.entry(*date).or_default().insert(column, text);
+            }
+        }
+    }
+
+    if rows.is_empty() {
+        return None;
+    }
+
+    let mut result = String::from(r#"<table class="table is-narrow is-size-7 mx-auto">"#);
+    let _ = write!(
+        result,
+        "<caption>{} from {} to {}</caption><thead><tr><th scope=\"col\">Date</th>",
+        escape(&columns.join(", ")),
+        interval.first,
+        interval.last
+    );
+    for column in &columns {
+        let _ = write!(result, r#"<th scope="col">{}</th>"#, escape(column));
+    }
+    result.push_str("</tr></thead><tbody>");
+    for (date, values) in rows.iter().rev() {
+        let _ = write!(result, r#"<tr><th scope="row">{date}</th>"#);
+        for column in 0..columns.len() {
+            result.push_str("<td>");
+            if let Some(value) = values.get(&column) {
+                result.push_str(value);
+            }
+            result.push_str("</td>");
+        }
+        result.push_str("</tr>");
+    }
+    result.push_str("</tbody></table>");
+
+    Some(result)
+}
+
+/// Format a value with at most two decimal places and without trailing zeros.
+fn format_value(value: f32) -> String {
+    let result = format!("{value:.2}");
+    result
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Reduce the number of values to at most `max_points` by min/max bucketing.
+///
+/// The first and the last value are always kept. The remaining values are divided into buckets of
+/// equal size, of which only the minimum and the maximum value are kept. This preserves the
+/// extrema and the visual shape of the series, while the rendering time is bounded by the
+/// resolution of the chart. The values must be sorted by date. Values are returned unchanged if
+/// their number does not exceed `max_points`.
+#[must_use]
+pub fn downsample(values: &[(NaiveDate, f32)], max_points: usize) -> Vec<(NaiveDate, f32)> {
+    if values.len() <= max_points || max_points < 4 {
+        return values.to_vec();
+    }
+
+    let inner = &values[1..values.len() - 1];
+    let buckets = (max_points - 2) / 2;
+    let mut result = Vec::with_capacity(max_points);
+
+    result.push(values[0]);
+    for bucket in inner.chunks(inner.len().div_ceil(buckets)) {
+        let (min_idx, max_idx) = bucket.iter().enumerate().skip(1).fold(
+            (0, 0),
+            |(min_idx, max_idx), (idx, (_, value))| {
+                (
+                    if *value < bucket[min_idx].1 {
+                        idx
+                    } else {
+                        min_idx
+                    },
+                    if *value > bucket[max_idx].1 {
+                        idx
+                    } else {
+                        max_idx
+                    },
+                )
             },
-        ],
-        interval,
-        theme,
-    )
+        );
+        result.push(bucket[min_idx.min(max_idx)]);
+        if min_idx != max_idx {
+            result.push(bucket[min_idx.max(max_idx)]);
+        }
+    }
+    result.push(values[values.len() - 1]);
+
+    result
+}
+
+/// Select the values inside the interval including the adjacent values outside of the interval.
+///
+/// The adjacent values ensure that lines and areas are continued to the border of the chart. The
+/// values must be sorted by date.
+fn visible_values<'a>(
+    values: &'a [(NaiveDate, f32)],
+    interval: &domain::Interval,
+) -> &'a [(NaiveDate, f32)] {
+    let start = values
+        .partition_point(|(date, _)| *date < interval.first)
+        .saturating_sub(1);
+    let end = values
+        .partition_point(|(date, _)| *date <= interval.last)
+        .saturating_add(1)
+        .min(values.len());
+    &values[start..end.max(start)]
+}
+
+/// Horizontal extent of the plot area of a chart created by `plot` in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotArea {
+    pub left: f64,
+    pub width: f64,
+}
+
+impl PlotArea {
+    /// Determine the plot area of a chart of the given width. The plot area is narrower if the
+    /// chart has a secondary axis.
+    #[must_use]
+    pub fn new(chart_width: f64, secondary: bool) -> Self {
+        let left = f64::from(MARGIN + Y_LABEL_AREA_SIZE);
+        let right = f64::from(MARGIN + if secondary { Y_LABEL_AREA_SIZE } else { 0 });
+        Self {
+            left,
+            width: (chart_width - left - right).max(1.0),
+        }
+    }
+
+    /// Relative position of a pixel inside the plot area, between 0 (left) and 1 (right).
+    /// Positions outside of the plot area are clamped to its border.
+    #[must_use]
+    pub fn fraction(&self, x: f64) -> f64 {
+        ((x - self.left) / self.width).clamp(0.0, 1.0)
+    }
+
+    #[must_use]
+    pub fn x(&self, fraction: f64) -> f64 {
+        self.left + fraction * self.width
+    }
+}
+
+/// Interaction with a chart. Positions are given relative to the plot area (see
+/// `PlotArea::fraction`) and times in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartInput {
+    Wheel { x: f64, delta: f64 },
+    PointerDown { id: i32, x: f64, time: f64 },
+    PointerMove { id: i32, x: f64 },
+    PointerUp { id: i32, time: f64 },
+}
+
+/// Minimum number of days visible when zoomed in.
+const MIN_VISIBLE_DAYS: f64 = 7.0;
+/// Change of the zoom factor per pixel scrolled with the mouse wheel.
+const WHEEL_ZOOM_SPEED: f64 = 0.002;
+/// Maximum time between two taps to be recognized as double tap in milliseconds.
+const DOUBLE_TAP_TIME: f64 = 300.0;
+/// Distance up to which a pointer movement is still considered a tap.
+const TAP_DISTANCE: f64 = 0.01;
+
+/// Visible part of the x domain of a chart, which can be zoomed and panned by the user.
+///
+/// The visible part always lies within the bounds, which are given by the interval selected on
+/// the page. The visible part is kept in fractional days, so that zooming and panning is smooth
+/// even though only whole days are shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+    first: NaiveDate,
+    last: NaiveDate,
+    /// Start of the visible part in days after the first day of the bounds.
+    start: f64,
+    /// End of the visible part in days after the first day of the bounds.
+    end: f64,
+    pointers: Vec<Pointer>,
+    last_tap: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Pointer {
+    id: i32,
+    x: f64,
+    origin: f64,
+    /// The pointer was neither moved nor used together with other pointers.
+    tap: bool,
+}
+
+impl Viewport {
+    #[must_use]
+    pub fn new(bounds: &domain::Interval) -> Self {
+        let mut viewport = Self {
+            first: bounds.first,
+            last: bounds.last,
+            start: 0.0,
+            end: 0.0,
+            pointers: vec![],
+            last_tap: None,
+        };
+        viewport.reset();
+        viewport
+    }
+
+    /// Change the bounds. The zoom is reset if the bounds have changed.
+    pub fn set_bounds(&mut self, bounds: &domain::Interval) {
+        if bounds.first != self.first || bounds.last != self.last {
+            *self = Self::new(bounds);
+        }
+    }
+
+    #[must_use]
+    pub fn is_zoomed(&self) -> bool {
+        self.start > 0.0 || self.end < self.days()
+    }
+
+    /// The visible interval, extended to whole days.
+    #[must_use]
+    pub fn visible(&self) -> domain::Interval {
+        #[allow(clippy::cast_possible_truncation)]
+        let date = |days: f64| self.first + chrono::Duration::days(days as i64);
+        domain::Interval {
+            first: date(self.start.floor()),
+            last: date(self.end.ceil()),
+        }
+    }
+
+    /// Ratio between the visible part and the whole bounds, between 0 and 1.
+    #[must_use]
+    pub fn scale(&self) -> f64 {
+        if self.days() > 0.0 {
+            (self.end - self.start) / self.days()
+        } else {
+            1.0
+        }
+    }
+
+    /// Date at the relative position inside the plot area.
+    #[must_use]
+    pub fn date(&self, fraction: f64) -> NaiveDate {
+        #[allow(clippy::cast_possible_truncation)]
+        let days = (self.start + fraction * (self.end - self.start)).round() as i64;
+        self.first + chrono::Duration::days(days)
+    }
+
+    /// Relative position of the date inside the plot area. Dates outside of the visible part
+    /// result in values below 0 or above 1.
+    #[must_use]
+    pub fn fraction(&self, date: NaiveDate) -> f64 {
+        if self.end <= self.start {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let days = (date - self.first).num_days() as f64;
+        (days - self.start) / (self.end - self.start)
+    }
+
+    /// Show the whole bounds.
+    pub fn reset(&mut self) {
+        self.start = 0.0;
+        self.end = self.days();
+    }
+
+    /// Scale the visible part by the factor, keeping the date at the relative position `center`
+    /// in place. A factor below 1 zooms in.
+    pub fn zoom(&mut self, factor: f64, center: f64) {
+        let days = self.days();
+        let span = self.end - self.start;
+        let min_span = MIN_VISIBLE_DAYS.min(days);
+        let new_span = (span * factor).clamp(min_span, days);
+        let pivot = self.start + center * span;
+        self.move_to(pivot - center * new_span, new_span);
+    }
+
+    /// Move the visible part by the relative distance. A positive distance moves to earlier dates,
+    /// as when dragging the chart to the right.
+    pub fn pan(&mut self, distance: f64) {
+        let span = self.end - self.start;
+        self.move_to(self.start - distance * span, span);
+    }
+
+    /// Handle an interaction with the chart and return whether the visible part has changed.
+    pub fn handle(&mut self, input: ChartInput) -> bool {
+        let previous = (self.start, self.end);
+        match input {
+            ChartInput::Wheel { x, delta } => {
+                self.zoom((delta * WHEEL_ZOOM_SPEED).exp(), x);
+            }
+            ChartInput::PointerDown { id, x, .. } => {
+                self.pointers.retain(|p| p.id != id);
+                let tap = self.pointers.is_empty();
+                for pointer in &mut self.pointers {
+                    pointer.tap = false;
+                }
+                self.pointers.push(Pointer {
+                    id,
+                    x,
+                    origin: x,
+                    tap,
+                });
+            }
+            ChartInput::PointerMove { id, x } => {
+                let Some(idx) = self.pointers.iter().position(|p| p.id == id) else {
+                    return false;
+                };
+                let previous_x = self.pointers[idx].x;
+                match self.pointers.as_slice() {
+                    [_] => self.pan(x - previous_x),
+                    [a, b, ..] => {
+                        let other_x = if idx == 0 { b.x } else { a.x };
+                        let previous_distance = (previous_x - other_x).abs();
+                        let distance = (x - other_x).abs();
+                        if previous_distance > 0.0 && distance > 0.0 {
+                            self.zoom(previous_distance / distance, (x + other_x) / 2.0);
+                        }
+                    }
+                    [] => {}
+                }
+                let pointer = &mut self.pointers[idx];
+                pointer.x = x;
+                pointer.tap &= (x - pointer.origin).abs() <= TAP_DISTANCE;
+            }
+            ChartInput::PointerUp { id, time } => {
+                let Some(idx) = self.pointers.iter().position(|p| p.id == id) else {
+                    return false;
+                };
+                if !self.pointers.remove(idx).tap {
+                    self.last_tap = None;
+                } else if self
+                    .last_tap
+                    .is_some_and(|last_tap| time - last_tap <= DOUBLE_TAP_TIME)
+                {
+                    self.last_tap = None;
+                    self.reset();
+                } else {
+                    self.last_tap = Some(time);
+                }
+            }
+        }
+        previous != (self.start, self.end)
+    }
+
+    fn days(&self) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let days = (self.last - self.first).num_days().max(0) as f64;
+        days
+    }
+
+    fn move_to(&mut self, start: f64, span: f64) {
+        let span = span.min(self.days());
+        let start = start.clamp(0.0, self.days() - span);
+        self.start = start;
+        self.end = start + span;
+    }
+}
+
+/// Scale the radius of a moving average to the visible part of a chart, so that short-term
+/// changes become visible when zoomed in.
+#[must_use]
+pub fn moving_average_radius(radius: usize, viewport: &Viewport) -> usize {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    let scaled = (radius as f64 * viewport.scale()).round() as usize;
+    scaled.clamp(1, radius.max(1))
 }
 
 fn all_zeros(data: &[PlotData]) -> bool {
@@ -425,7 +913,7 @@ fn all_zeros(data: &[PlotData]) -> bool {
         .unwrap_or(true)
 }
 
-fn colors(theme: &Theme) -> (RGBColor, RGBColor) {
+fn colors(theme: Theme) -> (RGBColor, RGBColor) {
     let dark = RGBColor(20, 22, 26);
     match theme {
         Theme::System | Theme::Light => (dark, WHITE),
@@ -482,3 +970,533 @@ fn chart_width() -> u32 {
         960,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2020, 1, 1).unwrap() + chrono::Duration::days(day.into())
+    }
+
+    fn values(values: &[f32]) -> Vec<(NaiveDate, f32)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (date(u32::try_from(i).unwrap()), *v))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::empty(&[], 4)]
+    #[case::fewer_values(&[1.0, 2.0, 3.0], 4)]
+    #[case::equal_values(&[1.0, 2.0, 3.0, 4.0], 4)]
+    #[case::too_few_points(&[1.0, 2.0, 3.0, 4.0, 5.0], 3)]
+    fn test_downsample_unchanged(#[case] input: &[f32], #[case] max_points: usize) {
+        assert_eq!(downsample(&values(input), max_points), values(input));
+    }
+
+    #[test]
+    fn test_downsample() {
+        let input = values(&[5.0, 1.0, 3.0, 2.0, 4.0, 9.0, 0.0, 6.0, 7.0, 8.0]);
+        assert_eq!(
+            downsample(&input, 6),
+            vec![input[0], input[1], input[4], input[5], input[6], input[9]]
+        );
+    }
+
+    #[test]
+    fn test_downsample_endpoints_and_extrema() {
+        #[allow(clippy::cast_precision_loss)]
+        let input = (0..1000)
+            .map(|i| {
+                (
+                    date(i),
+                    ((i * 37) % 101) as f32 + if i == 500 { 500.0 } else { 0.0 },
+                )
+            })
+            .collect::<Vec<_>>();
+        let result = downsample(&input, 300);
+
+        assert!(result.len() <= 300);
+        assert_eq!(result.first(), input.first());
+        assert_eq!(result.last(), input.last());
+        assert!(result.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(result.contains(&input[500]));
+        assert!(result.iter().any(|(_, v)| *v == 0.0));
+    }
+
+    fn plot_data(values: Vec<(NaiveDate, f32)>) -> PlotData {
+        PlotData {
+            values_high: values,
+            values_low: None,
+            plots: plot_line(0),
+            params: PlotParams::default(),
+        }
+    }
+
+    #[rstest]
+    #[case::no_series(&["Weight"], vec![])]
+    #[case::empty_series(&["Weight"], vec![plot_data(vec![])])]
+    #[case::outside_interval(&["Weight"], vec![plot_data(vec![(date(9), 80.0)])])]
+    #[case::no_labels(&[], vec![plot_data(vec![(date(2), 80.0)])])]
+    fn test_table_empty(#[case] labels: &[&str], #[case] data: Vec<PlotData>) {
+        assert_eq!(
+            table(
+                labels,
+                &data,
+                &domain::Interval {
+                    first: date(1),
+                    last: date(5),
+                }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_table_multiple_series() {
+        let data = [
+            plot_data(vec![(date(0), 79.0), (date(1), 80.5), (date(3), 80.25)]),
+            plot_data(vec![(date(1), 80.333), (date(2), 80.4)]),
+        ];
+
+        assert_eq!(
+            table(
+                &["Weight (kg)", "Avg. weight (kg)"],
+                &data,
+                &domain::Interval {
+                    first: date(1),
+                    last: date(3),
+                }
+            )
+            .unwrap(),
+            [
+                r#"<table class="table is-narrow is-size-7 mx-auto">"#,
+                "<caption>Weight (kg), Avg. weight (kg) from 2020-01-02 to 2020-01-04</caption>",
+                r#"<thead><tr><th scope="col">Date</th>"#,
+                r#"<th scope="col">Weight (kg)</th><th scope="col">Avg. weight (kg)</th>"#,
+                "</tr></thead><tbody>",
+                r#"<tr><th scope="row">2020-01-04</th><td>80.25</td><td></td></tr>"#,
+                r#"<tr><th scope="row">2020-01-03</th><td></td><td>80.4</td></tr>"#,
+                r#"<tr><th scope="row">2020-01-02</th><td>80.5</td><td>80.33</td></tr>"#,
+                "</tbody></table>",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_table_range() {
+        let data = [PlotData {
+            values_high: vec![(date(1), 80.0), (date(2), 70.0), (date(3), 75.0)],
+            values_low: Some(vec![(date(1), 90.0), (date(3), 75.0)]),
+            plots: plot_area(0),
+            params: PlotParams::default(),
+        }];
+
+        assert_eq!(
+            table(
+                &["Weight (kg)"],
+                &data,
+                &domain::Interval {
+                    first: date(1),
+                    last: date(3),
+                }
+            )
+            .unwrap(),
+            [
+                r#"<table class="table is-narrow is-size-7 mx-auto">"#,
+                "<caption>Weight (kg) from 2020-01-02 to 2020-01-04</caption>",
+                r#"<thead><tr><th scope="col">Date</th><th scope="col">Weight (kg)</th>"#,
+                "</tr></thead><tbody>",
+                r#"<tr><th scope="row">2020-01-04</th><td>75</td></tr>"#,
+                r#"<tr><th scope="row">2020-01-03</th><td>70</td></tr>"#,
+                r#"<tr><th scope="row">2020-01-02</th><td>80–90</td></tr>"#,
+                "</tbody></table>",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_table_segments() {
+        let data = [
+            plot_data(vec![(date(1), 8.0), (date(2), 7.5)]),
+            plot_data(vec![(date(4), 9.0)]),
+        ];
+
+        assert_eq!(
+            table(
+                &["RPE <avg>"],
+                &data,
+                &domain::Interval {
+                    first: date(1),
+                    last: date(5),
+                }
+            )
+            .unwrap(),
+            [
+                r#"<table class="table is-narrow is-size-7 mx-auto">"#,
+                "<caption>RPE &lt;avg&gt; from 2020-01-02 to 2020-01-06</caption>",
+                r#"<thead><tr><th scope="col">Date</th><th scope="col">RPE &lt;avg&gt;</th>"#,
+                "</tr></thead><tbody>",
+                r#"<tr><th scope="row">2020-01-05</th><td>9</td></tr>"#,
+                r#"<tr><th scope="row">2020-01-03</th><td>7.5</td></tr>"#,
+                r#"<tr><th scope="row">2020-01-02</th><td>8</td></tr>"#,
+                "</tbody></table>",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_min_avg_max() {
+        let data = min_avg_max(
+            &vec![
+                (date(1), 80.0),
+                (date(1), 100.0),
+                (date(1), 90.0),
+                (date(2), 60.0),
+                (date(9), 70.0),
+            ],
+            &domain::Interval {
+                first: date(0),
+                last: date(5),
+            },
+            PlotParams::default(),
+            0,
+        );
+
+        assert_eq!(data[0].values_high, vec![(date(1), 80.0), (date(2), 60.0)]);
+        assert_eq!(
+            data[0].values_low,
+            Some(vec![(date(1), 100.0), (date(2), 60.0)])
+        );
+        assert_eq!(data[1].values_high, vec![(date(1), 90.0), (date(2), 60.0)]);
+    }
+
+    #[rstest]
+    #[case(0, Theme::Light)]
+    #[case(12, Theme::Dark)]
+    fn test_color(#[case] index: usize, #[case] theme: Theme) {
+        let (r, g, b) = Palette99::pick(index).rgb();
+
+        assert_eq!(
+            color(
+                index,
+                &Style {
+                    theme,
+                    high_contrast: false
+                }
+            ),
+            RGBColor(r, g, b)
+        );
+    }
+
+    /// The high contrast colors must achieve a contrast ratio of at least 4.5:1 to the
+    /// background, as required by WCAG for normal text.
+    #[rstest]
+    fn test_color_high_contrast(
+        #[values(0, 1, 2, 3, 4, 5, 6, 7, 8, 99)] index: usize,
+        #[values(Theme::System, Theme::Light, Theme::Dark)] theme: Theme,
+    ) {
+        fn luminance(RGBColor(r, g, b): RGBColor) -> f64 {
+            let channel = |c: u8| {
+                let c = f64::from(c) / 255.0;
+                if c <= 0.039_28 {
+                    c / 12.92
+                } else {
+                    ((c + 0.055) / 1.055).powf(2.4)
+                }
+            };
+            0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+        }
+
+        let (_, background) = colors(theme);
+        let (l1, l2) = (
+            luminance(color(
+                index,
+                &Style {
+                    theme,
+                    high_contrast: true,
+                },
+            )),
+            luminance(background),
+        );
+
+        assert!((l1.max(l2) + 0.05) / (l1.min(l2) + 0.05) >= 4.5);
+    }
+
+    #[rstest]
+    #[case::all(1, 5, vec![1, 2, 3, 4, 5])]
+    #[case::inner(3, 3, vec![2, 3, 4])]
+    #[case::before(0, 0, vec![1])]
+    #[case::after(7, 8, vec![5])]
+    fn test_visible_values(#[case] first: u32, #[case] last: u32, #[case] expected: Vec<u32>) {
+        let input = [1, 2, 3, 4, 5]
+            .into_iter()
+            .map(|d| (date(d), 1.0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            visible_values(
+                &input,
+                &domain::Interval {
+                    first: date(first),
+                    last: date(last)
+                }
+            ),
+            expected
+                .into_iter()
+                .map(|d| (date(d), 1.0))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    fn viewport(first: u32, last: u32) -> Viewport {
+        Viewport::new(&domain::Interval {
+            first: date(first),
+            last: date(last),
+        })
+    }
+
+    fn visible(viewport: &Viewport) -> (NaiveDate, NaiveDate) {
+        let interval = viewport.visible();
+        (interval.first, interval.last)
+    }
+
+    #[rstest]
+    #[case::primary(500.0, false, PlotArea { left: 50.0, width: 440.0 })]
+    #[case::secondary(500.0, true, PlotArea { left: 50.0, width: 400.0 })]
+    #[case::too_narrow(50.0, true, PlotArea { left: 50.0, width: 1.0 })]
+    fn test_plot_area(
+        #[case] chart_width: f64,
+        #[case] secondary: bool,
+        #[case] expected: PlotArea,
+    ) {
+        assert_eq!(PlotArea::new(chart_width, secondary), expected);
+    }
+
+    #[rstest]
+    #[case::left(50.0)]
+    #[case::center(270.0)]
+    #[case::right(490.0)]
+    fn test_plot_area_round_trip(#[case] x: f64) {
+        let area = PlotArea::new(500.0, false);
+        assert!((area.x(area.fraction(x)) - x).abs() < 1e-9);
+    }
+
+    #[rstest]
+    #[case::margin(0.0, 0.0)]
+    #[case::axis(600.0, 1.0)]
+    fn test_plot_area_fraction_clamped(#[case] x: f64, #[case] expected: f64) {
+        assert!((PlotArea::new(500.0, false).fraction(x) - expected).abs() < f64::EPSILON);
+    }
+
+    #[rstest]
+    #[case::first(0)]
+    #[case::inner(30)]
+    #[case::last(100)]
+    fn test_viewport_date_round_trip(#[case] day: u32) {
+        let mut viewport = viewport(0, 100);
+        assert_eq!(viewport.date(viewport.fraction(date(day))), date(day));
+        viewport.zoom(0.5, 0.2);
+        assert_eq!(viewport.date(viewport.fraction(date(day))), date(day));
+    }
+
+    #[test]
+    fn test_viewport_new() {
+        let viewport = viewport(0, 100);
+        assert!(!viewport.is_zoomed());
+        assert_eq!(visible(&viewport), (date(0), date(100)));
+        assert!((viewport.scale() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[rstest]
+    #[case::center(0.5, 0.5, (date(25), date(75)))]
+    #[case::left(0.5, 0.0, (date(0), date(50)))]
+    #[case::right(0.5, 1.0, (date(50), date(100)))]
+    #[case::min_span(0.01, 0.5, (date(46), date(54)))]
+    #[case::zoom_out(2.0, 0.5, (date(0), date(100)))]
+    fn test_viewport_zoom(
+        #[case] factor: f64,
+        #[case] center: f64,
+        #[case] expected: (NaiveDate, NaiveDate),
+    ) {
+        let mut viewport = viewport(0, 100);
+        viewport.zoom(factor, center);
+        assert_eq!(visible(&viewport), expected);
+    }
+
+    #[test]
+    fn test_viewport_zoom_keeps_date_under_cursor() {
+        let mut viewport = viewport(0, 100);
+        viewport.zoom(0.5, 0.3);
+        assert_eq!(viewport.date(0.3), date(30));
+        assert_eq!(visible(&viewport), (date(15), date(65)));
+    }
+
+    #[test]
+    fn test_viewport_zoom_short_bounds() {
+        let mut viewport = viewport(0, 3);
+        viewport.zoom(0.5, 0.5);
+        assert!(!viewport.is_zoomed());
+        assert_eq!(visible(&viewport), (date(0), date(3)));
+    }
+
+    #[rstest]
+    #[case::earlier(0.2, (date(15), date(65)))]
+    #[case::later(-0.2, (date(35), date(85)))]
+    #[case::clamped_at_first(2.0, (date(0), date(50)))]
+    #[case::clamped_at_last(-2.0, (date(50), date(100)))]
+    fn test_viewport_pan(#[case] distance: f64, #[case] expected: (NaiveDate, NaiveDate)) {
+        let mut viewport = viewport(0, 100);
+        viewport.zoom(0.5, 0.5);
+        viewport.pan(distance);
+        assert_eq!(visible(&viewport), expected);
+    }
+
+    #[test]
+    fn test_viewport_pan_not_zoomed() {
+        let mut viewport = viewport(0, 100);
+        viewport.pan(0.5);
+        assert_eq!(visible(&viewport), (date(0), date(100)));
+    }
+
+    #[test]
+    fn test_viewport_set_bounds() {
+        let mut viewport = viewport(0, 100);
+        viewport.zoom(0.5, 0.5);
+        viewport.set_bounds(&domain::Interval {
+            first: date(0),
+            last: date(100),
+        });
+        assert!(viewport.is_zoomed());
+        viewport.set_bounds(&domain::Interval {
+            first: date(0),
+            last: date(200),
+        });
+        assert!(!viewport.is_zoomed());
+        assert_eq!(visible(&viewport), (date(0), date(200)));
+    }
+
+    #[test]
+    fn test_viewport_wheel() {
+        let mut viewport = viewport(0, 100);
+        assert!(viewport.handle(ChartInput::Wheel {
+            x: 0.5,
+            delta: -100.0
+        }));
+        assert!(viewport.is_zoomed());
+        assert!(viewport.handle(ChartInput::Wheel {
+            x: 0.5,
+            delta: 1000.0
+        }));
+        assert!(!viewport.is_zoomed());
+        assert!(!viewport.handle(ChartInput::Wheel {
+            x: 0.5,
+            delta: 100.0
+        }));
+    }
+
+    #[test]
+    fn test_viewport_drag() {
+        let mut viewport = viewport(0, 100);
+        viewport.zoom(0.5, 0.5);
+        assert!(!viewport.handle(ChartInput::PointerDown {
+            id: 1,
+            x: 0.5,
+            time: 0.0
+        }));
+        assert!(viewport.handle(ChartInput::PointerMove { id: 1, x: 0.7 }));
+        assert_eq!(visible(&viewport), (date(15), date(65)));
+        assert!(!viewport.handle(ChartInput::PointerMove { id: 2, x: 0.9 }));
+        assert!(!viewport.handle(ChartInput::PointerUp { id: 1, time: 100.0 }));
+        assert!(!viewport.handle(ChartInput::PointerMove { id: 1, x: 0.9 }));
+        assert_eq!(visible(&viewport), (date(15), date(65)));
+    }
+
+    #[test]
+    fn test_viewport_pinch() {
+        let mut viewport = viewport(0, 100);
+        viewport.handle(ChartInput::PointerDown {
+            id: 1,
+            x: 0.4,
+            time: 0.0,
+        });
+        viewport.handle(ChartInput::PointerDown {
+            id: 2,
+            x: 0.6,
+            time: 0.0,
+        });
+        assert!(viewport.handle(ChartInput::PointerMove { id: 2, x: 0.8 }));
+        assert!((viewport.scale() - 0.5).abs() < 1e-9);
+        viewport.handle(ChartInput::PointerUp { id: 1, time: 50.0 });
+        viewport.handle(ChartInput::PointerUp { id: 2, time: 100.0 });
+        viewport.handle(ChartInput::PointerDown {
+            id: 3,
+            x: 0.5,
+            time: 200.0,
+        });
+        viewport.handle(ChartInput::PointerUp { id: 3, time: 250.0 });
+        assert!(viewport.is_zoomed());
+    }
+
+    #[rstest]
+    #[case::double_tap(200.0, false)]
+    #[case::slow_taps(600.0, true)]
+    fn test_viewport_double_tap(#[case] second_tap: f64, #[case] zoomed: bool) {
+        let mut viewport = viewport(0, 100);
+        viewport.zoom(0.5, 0.5);
+        for time in [0.0, second_tap] {
+            viewport.handle(ChartInput::PointerDown {
+                id: 1,
+                x: 0.5,
+                time,
+            });
+            viewport.handle(ChartInput::PointerUp {
+                id: 1,
+                time: time + 50.0,
+            });
+        }
+        assert_eq!(viewport.is_zoomed(), zoomed);
+    }
+
+    #[test]
+    fn test_viewport_double_tap_after_drag() {
+        let mut viewport = viewport(0, 100);
+        viewport.zoom(0.5, 0.5);
+        viewport.handle(ChartInput::PointerDown {
+            id: 1,
+            x: 0.5,
+            time: 0.0,
+        });
+        viewport.handle(ChartInput::PointerMove { id: 1, x: 0.6 });
+        viewport.handle(ChartInput::PointerUp { id: 1, time: 50.0 });
+        viewport.handle(ChartInput::PointerDown {
+            id: 1,
+            x: 0.5,
+            time: 100.0,
+        });
+        viewport.handle(ChartInput::PointerUp { id: 1, time: 150.0 });
+        assert!(viewport.is_zoomed());
+    }
+
+    #[rstest]
+    #[case::not_zoomed(4, 1.0, 4)]
+    #[case::zoomed(4, 0.5, 2)]
+    #[case::minimum(4, 0.01, 1)]
+    #[case::zero(0, 1.0, 1)]
+    fn test_moving_average_radius(
+        #[case] radius: usize,
+        #[case] factor: f64,
+        #[case] expected: usize,
+    ) {
+        let mut viewport = viewport(0, 1000);
+        viewport.zoom(factor, 0.5);
+        assert_eq!(moving_average_radius(radius, &viewport), expected);
+    }
+}
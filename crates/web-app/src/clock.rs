@@ -0,0 +1,36 @@
+//! Current date and local times in the time zone of the browser.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use valens_domain::Clock;
+use web_sys::js_sys;
+
+/// Clock based on the time zone of the browser.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrowserClock;
+
+impl Clock for BrowserClock {
+    fn today(&self) -> NaiveDate {
+        today()
+    }
+}
+
+/// Current date in the time zone of the browser.
+#[must_use]
+pub fn today() -> NaiveDate {
+    let date = js_sys::Date::new_0();
+    NaiveDate::from_ymd_opt(
+        i32::try_from(date.get_full_year()).unwrap_or_default(),
+        date.get_month() + 1,
+        date.get_date(),
+    )
+    .unwrap_or_default()
+}
+
+/// Time of day of the given point in time in the time zone of the browser.
+#[must_use]
+pub fn local_time(time: DateTime<Utc>) -> NaiveTime {
+    #[allow(clippy::cast_precision_loss)]
+    let date = js_sys::Date::new(&(time.timestamp_millis() as f64).into());
+    NaiveTime::from_hms_opt(date.get_hours(), date.get_minutes(), date.get_seconds())
+        .unwrap_or_default()
+}
@@ -0,0 +1,165 @@
+//! Formatting of computed values with a consistent precision.
+
+/// Character which separates the integer part from the fractional part of a number.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DecimalSeparator {
+    #[default]
+    Point,
+    Comma,
+}
+
+impl DecimalSeparator {
+    #[must_use]
+    pub fn symbol(self) -> char {
+        match self {
+            DecimalSeparator::Point => '.',
+            DecimalSeparator::Comma => ',',
+        }
+    }
+}
+
+/// Number of decimal places of body weights and lifted weights.
+pub const PRECISION_WEIGHT: usize = 1;
+/// Number of decimal places of estimated one-repetition maxima.
+pub const PRECISION_E1RM: usize = 1;
+/// Number of decimal places of percentages.
+pub const PRECISION_PERCENTAGE: usize = 1;
+/// Number of decimal places of load ratios.
+pub const PRECISION_LOAD: usize = 2;
+/// Number of decimal places of counts, such as the number of sets.
+pub const PRECISION_COUNT: usize = 0;
+
+#[must_use]
+pub fn format_number(value: f32, precision: usize, separator: DecimalSeparator) -> String {
+    localize(format!("{value:.precision$}"), separator)
+}
+
+/// Format a number with an explicit sign, as used for changes.
+#[must_use]
+pub fn format_signed(value: f32, precision: usize, separator: DecimalSeparator) -> String {
+    localize(format!("{value:+.precision$}"), separator)
+}
+
+/// Format a weight followed by its unit. The unit is omitted if it is empty.
+#[must_use]
+pub fn format_weight(
+    weight: f32,
+    unit: &str,
+    precision: usize,
+    separator: DecimalSeparator,
+) -> String {
+    with_unit(format_number(weight, precision, separator), unit)
+}
+
+#[must_use]
+pub fn format_percentage(value: f32, precision: usize, separator: DecimalSeparator) -> String {
+    with_unit(format_number(value, precision, separator), "%")
+}
+
+#[must_use]
+pub fn format_load(load: f32, precision: usize, separator: DecimalSeparator) -> String {
+    format_number(load, precision, separator)
+}
+
+fn localize(number: String, separator: DecimalSeparator) -> String {
+    // Negative zero would be shown as "-0.0" otherwise
+    let number =
+        if number.starts_with('-') && number.bytes().all(|b| !b.is_ascii_digit() || b == b'0') {
+            number[1..].to_string()
+        } else {
+            number
+        };
+    match separator {
+        DecimalSeparator::Point => number,
+        DecimalSeparator::Comma => number.replace('.', ","),
+    }
+}
+
+fn with_unit(number: String, unit: &str) -> String {
+    if unit.is_empty() {
+        number
+    } else {
+        format!("{number} {unit}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::integer(80.0, PRECISION_WEIGHT, "80.0", "80,0")]
+    #[case::float_tail(80.123, PRECISION_WEIGHT, "80.1", "80,1")]
+    #[case::rounding(79.96, PRECISION_WEIGHT, "80.0", "80,0")]
+    #[case::count(7.4, PRECISION_COUNT, "7", "7")]
+    #[case::load(1.234_5, PRECISION_LOAD, "1.23", "1,23")]
+    #[case::negative(-0.25, PRECISION_PERCENTAGE, "-0.2", "-0,2")]
+    #[case::negative_zero(-0.01, PRECISION_PERCENTAGE, "0.0", "0,0")]
+    fn test_format_number(
+        #[case] value: f32,
+        #[case] precision: usize,
+        #[case] point: &str,
+        #[case] comma: &str,
+    ) {
+        assert_eq!(
+            format_number(value, precision, DecimalSeparator::Point),
+            point
+        );
+        assert_eq!(
+            format_number(value, precision, DecimalSeparator::Comma),
+            comma
+        );
+    }
+
+    #[rstest]
+    #[case::positive(0.54, "+0.5", "+0,5")]
+    #[case::negative(-1.26, "-1.3", "-1,3")]
+    #[case::zero(0.0, "+0.0", "+0,0")]
+    fn test_format_signed(#[case] value: f32, #[case] point: &str, #[case] comma: &str) {
+        assert_eq!(format_signed(value, 1, DecimalSeparator::Point), point);
+        assert_eq!(format_signed(value, 1, DecimalSeparator::Comma), comma);
+    }
+
+    #[test]
+    fn test_format_weight() {
+        assert_eq!(
+            format_weight(82.46, "kg", PRECISION_WEIGHT, DecimalSeparator::Point),
+            "82.5 kg"
+        );
+        assert_eq!(
+            format_weight(82.46, "kg", PRECISION_WEIGHT, DecimalSeparator::Comma),
+            "82,5 kg"
+        );
+        assert_eq!(
+            format_weight(102.333, "", PRECISION_E1RM, DecimalSeparator::Point),
+            "102.3"
+        );
+    }
+
+    #[test]
+    fn test_format_percentage() {
+        assert_eq!(
+            format_percentage(15.06, PRECISION_PERCENTAGE, DecimalSeparator::Point),
+            "15.1 %"
+        );
+        assert_eq!(
+            format_percentage(15.06, PRECISION_PERCENTAGE, DecimalSeparator::Comma),
+            "15,1 %"
+        );
+    }
+
+    #[test]
+    fn test_format_load() {
+        assert_eq!(
+            format_load(0.756, PRECISION_LOAD, DecimalSeparator::Point),
+            "0.76"
+        );
+        assert_eq!(
+            format_load(0.756, PRECISION_LOAD, DecimalSeparator::Comma),
+            "0,76"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Preparation of images for upload.
+
+use valens_domain::ExerciseImage;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::js_sys;
+
+/// Maximum width and height of uploaded images in pixels.
+pub const MAX_DIMENSION: u32 = 1280;
+
+const JPEG_QUALITY: f64 = 0.85;
+
+/// Size of an image scaled down to fit into a square of the given dimension, keeping the aspect
+/// ratio. Images which already fit are not scaled up.
+#[must_use]
+pub fn scaled_size(width: u32, height: u32, max_dimension: u32) -> (u32, u32) {
+    if width <= max_dimension && height <= max_dimension {
+        return (width, height);
+    }
+    let scale = f64::from(max_dimension) / f64::from(width.max(height));
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let scale_dimension = |dimension: u32| ((f64::from(dimension) * scale).round() as u32).max(1);
+    (scale_dimension(width), scale_dimension(height))
+}
+
+/// Scale down the image in the file and convert it to JPEG.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be decoded as image or the resulting image is invalid.
+pub async fn downscale(file: &web_sys::File) -> Result<ExerciseImage, String> {
+    let url = web_sys::Url::create_object_url_with_blob(file)
+        .map_err(|err| format!("failed to read image: {err:?}"))?;
+    let image = load(&url).await;
+    let _ = web_sys::Url::revoke_object_url(&url);
+    let image = image?;
+
+    let (width, height) = scaled_size(image.natural_width(), image.natural_height(), MAX_DIMENSION);
+    let canvas = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?
+        .create_element("canvas")
+        .map_err(|err| format!("failed to create canvas: {err:?}"))?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .map_err(|_| "unexpected canvas element".to_string())?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|context| context.dyn_into::<web_sys::CanvasRenderingContext2d>().ok())
+        .ok_or("canvas not supported")?;
+    // JPEG does not support transparency
+    context.set_fill_style_str("white");
+    context.fill_rect(0., 0., f64::from(width), f64::from(height));
+    context
+        .draw_image_with_html_image_element_and_dw_and_dh(
+            &image,
+            0.,
+            0.,
+            f64::from(width),
+            f64::from(height),
+        )
+        .map_err(|err| format!("failed to draw image: {err:?}"))?;
+    let data_url = canvas
+        .to_data_url_with_type_and_encoder_options("image/jpeg", &JsValue::from(JPEG_QUALITY))
+        .map_err(|err| format!("failed to encode image: {err:?}"))?;
+
+    ExerciseImage::from_data_url(&data_url).map_err(|err| err.to_string())
+}
+
+async fn load(url: &str) -> Result<web_sys::HtmlImageElement, String> {
+    let image = web_sys::HtmlImageElement::new()
+        .map_err(|err| format!("failed to create image: {err:?}"))?;
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let on_load = Closure::once_into_js(move || {
+            let _ = resolve.call0(&JsValue::UNDEFINED);
+        });
+        let on_error = Closure::once_into_js(move || {
+            let _ = reject.call0(&JsValue::UNDEFINED);
+        });
+        image.set_onload(Some(on_load.unchecked_ref()));
+        image.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    image.set_src(url);
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map_err(|_| "File is not a supported image".to_string())?;
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::small(640, 480, (640, 480))]
+    #[case::max(1280, 1280, (1280, 1280))]
+    #[case::landscape(4000, 3000, (1280, 960))]
+    #[case::portrait(3000, 4000, (960, 1280))]
+    #[case::rounding(2000, 999, (1280, 639))]
+    #[case::narrow(100_000, 10, (1280, 1))]
+    fn test_scaled_size(#[case] width: u32, #[case] height: u32, #[case] expected: (u32, u32)) {
+        assert_eq!(scaled_size(width, height, MAX_DIMENSION), expected);
+    }
+}
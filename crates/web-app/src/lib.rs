@@ -1,10 +1,41 @@
 #![warn(clippy::pedantic)]
 #![allow(clippy::too_many_lines)]
 
-use chrono::{DateTime, Utc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+};
 
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+pub mod calendar;
+#[allow(clippy::module_name_repetitions)]
 pub mod chart;
+#[allow(clippy::module_name_repetitions)]
+pub mod clock;
+#[allow(clippy::module_name_repetitions)]
+pub mod format;
+pub mod image;
+pub mod markdown;
+pub mod media;
+pub mod metronome;
+pub mod muscle_map;
+pub mod resource;
+pub mod routine_exchange;
 pub mod service_worker;
+#[allow(clippy::module_name_repetitions)]
+pub mod share;
+#[allow(clippy::module_name_repetitions)]
+pub mod sound;
+pub mod sparkline;
+pub mod speech;
+pub mod stopwatch;
+#[allow(clippy::module_name_repetitions)]
+pub mod storage_usage;
+pub mod trash;
+pub mod version;
+pub mod vibration;
+pub mod wake_lock;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 #[allow(clippy::struct_excessive_bools)]
@@ -15,6 +46,79 @@ pub struct Settings {
     pub notifications: bool,
     pub show_rpe: bool,
     pub show_tut: bool,
+    /// Body height in cm, which was stored locally before it became a property of the user. It
+    /// is only kept until it has been transferred to the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_height: Option<f32>,
+    #[serde(default)]
+    pub interval: Option<valens_domain::DefaultInterval>,
+    #[serde(default)]
+    pub default_interval: Option<valens_domain::DefaultInterval>,
+    /// Minutes after which the data is refreshed automatically. Automatic refreshing is disabled
+    /// if set to zero.
+    #[serde(default = "default_refresh_interval")]
+    pub refresh_interval: u32,
+    /// Version of the last app update for which the update notification was dismissed.
+    #[serde(default)]
+    pub dismissed_app_update: Option<String>,
+    /// Explicit choice of each user whether the menstrual cycle tracking is shown.
+    #[serde(default)]
+    pub show_menstrual_cycle: BTreeMap<u32, bool>,
+    /// Smallest weight step in kg to which target weights derived from the one-repetition maximum
+    /// are rounded.
+    #[serde(default = "default_weight_increment")]
+    pub weight_increment: f32,
+    /// Durations in seconds that can be selected as quick presets for the timer.
+    #[serde(default = "default_timer_presets")]
+    pub timer_presets: [u32; 3],
+    /// Initial interval of the metronome in seconds.
+    #[serde(default = "default_metronome_interval")]
+    pub metronome_interval: u32,
+    /// Initial number of beats after which a stressed beat is played by the metronome.
+    #[serde(default = "default_metronome_stressed_beat")]
+    pub metronome_stressed_beat: u32,
+    #[serde(default)]
+    pub vibration: vibration::Vibration,
+    #[serde(default)]
+    pub sound_profile: sound::SoundProfile,
+    /// Announce the next exercise by speech output during guided training sessions.
+    #[serde(default)]
+    pub announce_exercises: bool,
+    /// Prevent the screen from turning off during guided training sessions.
+    #[serde(default = "default_keep_screen_awake")]
+    pub keep_screen_awake: bool,
+    #[serde(default)]
+    pub training_view: TrainingView,
+    /// Number of training sessions per week required to continue the training streak.
+    #[serde(default = "default_weekly_session_target")]
+    pub weekly_session_target: u32,
+    /// Body weight goal of each user.
+    #[serde(default)]
+    pub body_weight_goal: BTreeMap<u32, valens_domain::BodyWeightGoal>,
+    /// Percentage by which a set may miss its target values without being highlighted.
+    #[serde(default = "default_target_tolerance")]
+    pub target_tolerance: u8,
+    /// Number of days over which the trends on the home page are determined.
+    #[serde(default = "default_trend_window")]
+    pub trend_window: u32,
+    /// Pages on which the data of the charts is additionally shown as table.
+    #[serde(default)]
+    pub data_tables: BTreeSet<chart::Page>,
+    #[serde(default)]
+    pub decimal_separator: format::DecimalSeparator,
+    /// Training locations with their available equipment.
+    #[serde(default)]
+    pub locations: Vec<valens_domain::equipment::Location>,
+    /// Name of the location at which the user currently trains. Weights are rounded to the
+    /// weights achievable with the equipment of this location.
+    #[serde(default)]
+    pub active_location: Option<String>,
+    /// Show the values of previous ad-hoc training sessions containing the same exercise in ad-hoc
+    /// training sessions.
+    #[serde(default = "default_ad_hoc_previous_values")]
+    pub ad_hoc_previous_values: bool,
+    #[serde(default)]
+    pub contrast: Contrast,
 }
 
 impl Default for Settings {
@@ -26,23 +130,168 @@ impl Default for Settings {
             notifications: false,
             show_rpe: true,
             show_tut: true,
+            body_height: None,
+            interval: None,
+            default_interval: None,
+            refresh_interval: default_refresh_interval(),
+            dismissed_app_update: None,
+            show_menstrual_cycle: BTreeMap::new(),
+            weight_increment: default_weight_increment(),
+            timer_presets: default_timer_presets(),
+            metronome_interval: default_metronome_interval(),
+            metronome_stressed_beat: default_metronome_stressed_beat(),
+            vibration: vibration::Vibration::Off,
+            sound_profile: sound::SoundProfile::Classic,
+            announce_exercises: false,
+            keep_screen_awake: default_keep_screen_awake(),
+            training_view: TrainingView::List,
+            weekly_session_target: default_weekly_session_target(),
+            body_weight_goal: BTreeMap::new(),
+            target_tolerance: default_target_tolerance(),
+            trend_window: default_trend_window(),
+            data_tables: BTreeSet::new(),
+            decimal_separator: format::DecimalSeparator::Point,
+            locations: Vec::new(),
+            active_location: None,
+            ad_hoc_previous_values: default_ad_hoc_previous_values(),
+            contrast: Contrast::System,
         }
     }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+fn default_refresh_interval() -> u32 {
+    15
+}
+
+fn default_weight_increment() -> f32 {
+    2.5
+}
+
+fn default_timer_presets() -> [u32; 3] {
+    [60, 90, 180]
+}
+
+fn default_metronome_interval() -> u32 {
+    1
+}
+
+fn default_metronome_stressed_beat() -> u32 {
+    1
+}
+
+fn default_keep_screen_awake() -> bool {
+    true
+}
+
+fn default_ad_hoc_previous_values() -> bool {
+    true
+}
+
+fn default_weekly_session_target() -> u32 {
+    3
+}
+
+fn default_target_tolerance() -> u8 {
+    5
+}
+
+fn default_trend_window() -> u32 {
+    28
+}
+
+pub const TIMER_PRESET_RANGE: RangeInclusive<u32> = 1..=3600;
+pub const METRONOME_INTERVAL_RANGE: RangeInclusive<u32> = 1..=60;
+pub const METRONOME_STRESSED_BEAT_RANGE: RangeInclusive<u32> = 1..=12;
+pub const WEEKLY_SESSION_TARGET_RANGE: RangeInclusive<u32> = 1..=7;
+
+/// RPE values that can be selected directly in the training session form.
+pub const RPE_PICKER_VALUES: [f32; 9] = [6.0, 6.5, 7.0, 7.5, 8.0, 8.5, 9.0, 9.5, 10.0];
+
+impl Settings {
+    /// Interval used when opening a chart page.
+    ///
+    /// The last selected interval takes precedence over the configured default interval, so that
+    /// the default only seeds the interval until the user selects another one. If neither is set,
+    /// the page-specific default is used.
+    #[must_use]
+    pub fn initial_interval(&self) -> Option<valens_domain::DefaultInterval> {
+        self.interval.or(self.default_interval)
+    }
+
+    /// Configure the default interval.
+    ///
+    /// The last selected interval is cleared to make the new default effective immediately.
+    pub fn set_default_interval(&mut self, interval: Option<valens_domain::DefaultInterval>) {
+        self.default_interval = interval;
+        self.interval = None;
+    }
+
+    /// Check if the menstrual cycle tracking is shown for a user.
+    ///
+    /// By default, the tracking is shown only for female users.
+    #[must_use]
+    pub fn menstrual_cycle_shown(&self, user: &valens_domain::User) -> bool {
+        self.show_menstrual_cycle
+            .get(&user.id)
+            .copied()
+            .unwrap_or(user.sex == 0)
+    }
+
+    #[must_use]
+    pub fn active_location(&self) -> Option<&valens_domain::equipment::Location> {
+        let name = self.active_location.as_ref()?;
+        self.locations.iter().find(|l| &l.name == name)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum Theme {
     System,
     Light,
     Dark,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+/// Contrast of the charts.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub enum Contrast {
+    /// Use high contrast if requested by the operating system or browser.
+    #[default]
+    System,
+    Normal,
+    High,
+}
+
+impl Contrast {
+    /// Determine whether high contrast is effective.
+    #[must_use]
+    pub fn is_high(self, system_prefers_high_contrast: bool) -> bool {
+        match self {
+            Contrast::System => system_prefers_high_contrast,
+            Contrast::Normal => false,
+            Contrast::High => true,
+        }
+    }
+}
+
+/// Presentation of the training sessions on the training page.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub enum TrainingView {
+    #[default]
+    List,
+    Calendar,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct OngoingTrainingSession {
     pub training_session_id: u32,
     pub start_time: DateTime<Utc>,
     pub element_idx: usize,
     pub element_start_time: DateTime<Utc>,
+    /// Accumulated duration of completed pauses of the current element in milliseconds.
+    #[serde(default)]
+    pub paused_duration: i64,
+    #[serde(default)]
+    pub paused_since: Option<DateTime<Utc>>,
     pub timer_state: TimerState,
 }
 
@@ -54,14 +303,1243 @@ impl OngoingTrainingSession {
             start_time: Utc::now(),
             element_idx: 0,
             element_start_time: Utc::now(),
+            paused_duration: 0,
+            paused_since: None,
             timer_state: TimerState::Unset,
         }
     }
+
+    pub fn go_to_element(&mut self, element_idx: usize, now: DateTime<Utc>) {
+        self.element_idx = element_idx;
+        self.element_start_time = now;
+        self.paused_duration = 0;
+        self.paused_since = None;
+        self.timer_state = TimerState::Unset;
+    }
+
+    /// Go to the next of the given number of elements. The session stays at the last element if
+    /// there is no next element.
+    pub fn go_to_next_element(&mut self, element_count: usize, now: DateTime<Utc>) {
+        if self.element_idx + 1 < element_count {
+            self.go_to_element(self.element_idx + 1, now);
+        }
+    }
+
+    /// Apply an action selected in a notification. Actions of notifications belonging to another
+    /// training session are ignored.
+    ///
+    /// Returns true if the action was applied.
+    pub fn apply_notification_action(
+        &mut self,
+        selected: service_worker::SelectedNotificationAction,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if selected.training_session_id != self.training_session_id {
+            return false;
+        }
+        match selected.action {
+            service_worker::NotificationAction::GoToNextSection => {
+                self.go_to_next_element(selected.element_count, now);
+            }
+            service_worker::NotificationAction::ExtendTimer => {
+                self.extend_timer(Duration::seconds(30));
+            }
+        }
+        true
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused_since.is_some()
+    }
+
+    pub fn pause(&mut self, now: DateTime<Utc>) {
+        if self.paused_since.is_none() {
+            self.paused_since = Some(now);
+        }
+    }
+
+    pub fn resume(&mut self, now: DateTime<Utc>) {
+        if let Some(paused_since) = self.paused_since.take() {
+            self.paused_duration += (now - paused_since).num_milliseconds().max(0);
+        }
+    }
+
+    /// Time spent on the current element, excluding the time during which the session was
+    /// paused.
+    #[must_use]
+    pub fn elapsed_time(&self, now: DateTime<Utc>) -> Duration {
+        let end = self.paused_since.unwrap_or(now);
+        end - self.element_start_time - Duration::milliseconds(self.paused_duration)
+    }
+
+    pub fn extend_timer(&mut self, duration: Duration) {
+        self.timer_state = match self.timer_state {
+            TimerState::Unset => TimerState::Unset,
+            TimerState::Active { target_time } => TimerState::Active {
+                target_time: target_time + duration,
+            },
+            TimerState::Paused { time } => TimerState::Paused {
+                time: time + duration.num_seconds(),
+            },
+        };
+    }
+}
+
+/// Unsaved input of the training session form.
+///
+/// The draft is kept in the local storage, so that the input is not lost if the app is closed
+/// before the training session is saved.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct TrainingSessionDraft {
+    /// Training session as it was stored when the draft was created.
+    pub training_session: valens_domain::TrainingSession,
+    pub notes: Option<String>,
+    /// Manually entered duration in minutes.
+    pub duration: Option<String>,
+    pub sets: Vec<SetDraft>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Copy)]
+/// Unsaved input of a set. Values which were not changed are not set.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct SetDraft {
+    /// Position of the element in the training session form.
+    pub element_idx: usize,
+    /// Position of the exercise in the element.
+    pub exercise_idx: usize,
+    pub exercise_id: u32,
+    pub reps: Option<String>,
+    pub time: Option<String>,
+    pub weight: Option<String>,
+    pub rpe: Option<String>,
+    #[serde(default)]
+    pub partial_reps: Option<String>,
+    #[serde(default)]
+    pub to_failure: Option<bool>,
+    pub comment: Option<String>,
+}
+
+impl TrainingSessionDraft {
+    /// Check whether the draft can be applied to the stored training session.
+    ///
+    /// A draft is outdated if the training session has been changed since the draft was created,
+    /// e.g. because it was saved on another device. The positions of the sets in the draft are
+    /// only valid as long as the number and order of the exercises are unchanged.
+    #[must_use]
+    pub fn is_applicable(&self, training_session: &valens_domain::TrainingSession) -> bool {
+        self.training_session == *training_session
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum TimerState {
     Unset,
     Active { target_time: DateTime<Utc> },
     Paused { time: i64 },
 }
+
+/// Format the values of a set, omitting values which are not set or hidden.
+///
+/// Target reps can be given as range (e.g., "8–12"). Partial reps are appended to the reps (e.g.,
+/// "8+2") and a set performed to failure is marked by "(F)".
+#[must_use]
+#[allow(clippy::too_many_arguments)]
+pub fn format_set(
+    reps: Option<valens_domain::RepsRange>,
+    partial_reps: Option<u32>,
+    time: Option<u32>,
+    show_tut: bool,
+    weight: Option<f32>,
+    rpe: Option<valens_domain::RpeRange>,
+    show_rpe: bool,
+    to_failure: bool,
+) -> String {
+    let mut parts = vec![];
+
+    if let Some(reps) = reps {
+        if reps.is_set() {
+            parts.push(match partial_reps {
+                Some(partial_reps) if partial_reps > 0 => format!("{reps}+{partial_reps}"),
+                _ => reps.to_string(),
+            });
+        }
+    }
+
+    if let Some(time) = time {
+        if show_tut && time > 0 {
+            parts.push(format!("{time} s"));
+        }
+    }
+
+    if let Some(weight) = weight {
+        if weight > 0.0 {
+            parts.push(format!("{weight} kg"));
+        }
+    }
+
+    let mut result = parts.join(" × ");
+
+    if let Some(rpe) = rpe {
+        if show_rpe && rpe.is_set() {
+            result.push_str(" @ ");
+            result.push_str(&rpe.to_string());
+        }
+    }
+
+    if to_failure && !result.is_empty() {
+        result.push_str(" (F)");
+    }
+
+    result
+}
+
+/// Format the differences between the actual and the target values of a set, omitting values
+/// which match their target or are hidden. An RPE within the target range matches its target.
+#[must_use]
+pub fn format_set_deviation(
+    deviation: &valens_domain::progression::SetDeviation,
+    show_tut: bool,
+    show_rpe: bool,
+) -> String {
+    fn signed(difference: &str) -> String {
+        match difference.strip_prefix('-') {
+            Some(value) => format!("−{value}"),
+            None => format!("+{difference}"),
+        }
+    }
+
+    let mut parts = vec![];
+
+    if let Some((target, actual)) = deviation.reps {
+        if actual != target {
+            let difference = i64::from(actual) - i64::from(target);
+            let unit = if difference.abs() == 1 { "rep" } else { "reps" };
+            parts.push(format!("{} {unit}", signed(&difference.to_string())));
+        }
+    }
+
+    if let Some((target, actual)) = deviation.time {
+        if show_tut && actual != target {
+            let difference = i64::from(actual) - i64::from(target);
+            parts.push(format!("{} s", signed(&difference.to_string())));
+        }
+    }
+
+    if let Some((target, actual)) = deviation.weight {
+        let difference = ((actual - target) * 100.0).round() / 100.0;
+        if difference != 0.0 {
+            parts.push(format!("{} kg", signed(&difference.to_string())));
+        }
+    }
+
+    if let Some((target, actual)) = deviation.rpe {
+        if show_rpe && !target.contains(actual) {
+            parts.push(format!("@{actual} vs @{target}"));
+        }
+    }
+
+    if parts.is_empty() {
+        String::from("±0")
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Describe how the weight of a set of the exercise has to be entered.
+///
+/// `None` is returned if no weight convention is defined for the exercise.
+#[must_use]
+pub fn format_weight_convention(exercise: &valens_domain::Exercise) -> Option<String> {
+    let weight_convention = exercise.weight_convention?;
+    let description = weight_convention.description();
+    match (weight_convention, exercise.base_weight) {
+        (valens_domain::WeightConvention::Total, _) | (_, None) => Some(description.to_string()),
+        (_, Some(base_weight)) => Some(format!("{description} (base weight {base_weight} kg)")),
+    }
+}
+
+/// Describe the difference between the left and the right side of a unilateral exercise, e.g.
+/// "L 100.0 kg, R 90.0 kg (right 10.0 % weaker)".
+#[must_use]
+pub fn format_side_asymmetry(
+    asymmetry: &valens_domain::SideAsymmetry,
+    separator: format::DecimalSeparator,
+) -> String {
+    let format_value = |value| match asymmetry.metric {
+        valens_domain::AsymmetryMetric::OneRepMax => {
+            format::format_weight(value, "kg", format::PRECISION_E1RM, separator)
+        }
+        valens_domain::AsymmetryMetric::Reps => {
+            format::format_number(value, format::PRECISION_COUNT, separator)
+        }
+    };
+    let values = format!(
+        "L {}, R {}",
+        format_value(asymmetry.left),
+        format_value(asymmetry.right)
+    );
+    match asymmetry.weaker_side() {
+        Some(side) => format!(
+            "{values} ({} {} weaker)",
+            side.name().to_lowercase(),
+            format::format_percentage(
+                asymmetry.relative_difference().abs() * 100.0,
+                format::PRECISION_PERCENTAGE,
+                separator
+            )
+        ),
+        None => format!("{values} (balanced)"),
+    }
+}
+
+/// Describe a personal record, e.g. "Reps: 6 × 80.0 kg".
+#[must_use]
+pub fn format_personal_record(
+    kind: &valens_domain::RecordKind,
+    separator: format::DecimalSeparator,
+) -> String {
+    let format_weight = |weight: Option<f32>| {
+        weight.map_or(String::new(), |weight| {
+            format!(
+                " × {}",
+                format::format_weight(weight, "kg", format::PRECISION_WEIGHT, separator)
+            )
+        })
+    };
+    match kind {
+        valens_domain::RecordKind::OneRepMax(value) => format!(
+            "Estimated 1RM: {}",
+            format::format_weight(*value, "kg", format::PRECISION_E1RM, separator)
+        ),
+        valens_domain::RecordKind::Weight(value) => format!(
+            "Weight: {}",
+            format::format_weight(*value, "kg", format::PRECISION_WEIGHT, separator)
+        ),
+        valens_domain::RecordKind::Reps { reps, weight } => {
+            format!("Reps: {reps}{}", format_weight(*weight))
+        }
+        valens_domain::RecordKind::Time { time, weight } => {
+            format!("Time: {time} s{}", format_weight(*weight))
+        }
+    }
+}
+
+/// Describe how the weight of a set of the exercise can be set up with the equipment at the
+/// location, e.g. the plates per side of a bar or the nearest achievable weights.
+///
+/// `None` is returned if the location lacks the equipment for the exercise or if there is nothing
+/// to describe.
+#[must_use]
+pub fn format_equipment_hint(
+    location: &valens_domain::equipment::Location,
+    exercise: &valens_domain::Exercise,
+    weight: f32,
+) -> Option<String> {
+    match location.round(exercise, weight)? {
+        valens_domain::equipment::Achievable::Exact(weight) => {
+            let plates = location.plates_per_side(exercise, weight)?;
+            if plates.is_empty() {
+                return None;
+            }
+            Some(format!(
+                "Plates per side: {} kg",
+                plates
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" + ")
+            ))
+        }
+        valens_domain::equipment::Achievable::Unreachable { below, above } => {
+            let nearest = below
+                .into_iter()
+                .chain(above)
+                .map(|weight| format!("{weight} kg"))
+                .collect::<Vec<_>>();
+            Some(if nearest.is_empty() {
+                format!("Not achievable at {}", location.name)
+            } else {
+                format!(
+                    "Not achievable at {}, nearest: {}",
+                    location.name,
+                    nearest.join(" or ")
+                )
+            })
+        }
+    }
+}
+
+/// Describe the rounds of the enclosing sections, e.g. "Round 2/4". Sections with a single round
+/// are omitted.
+///
+/// `None` is returned if no enclosing section has multiple rounds.
+#[must_use]
+pub fn format_rounds(rounds: &[valens_domain::Round]) -> Option<String> {
+    let rounds = rounds
+        .iter()
+        .filter(|round| round.total > 1)
+        .map(|round| format!("{}/{}", round.number, round.total))
+        .collect::<Vec<_>>();
+    if rounds.is_empty() {
+        None
+    } else {
+        Some(format!("Round {}", rounds.join(" · ")))
+    }
+}
+
+/// Select the training sessions in the date range, ordered by date.
+///
+/// If a routine is given, only training sessions of this routine are selected. The training
+/// session of the ongoing training session is never selected.
+#[must_use]
+pub fn select_training_sessions<'a>(
+    training_sessions: impl IntoIterator<Item = &'a valens_domain::TrainingSession>,
+    dates: &RangeInclusive<NaiveDate>,
+    routine_id: Option<u32>,
+    ongoing_training_session: Option<&OngoingTrainingSession>,
+) -> Vec<&'a valens_domain::TrainingSession> {
+    let mut selected = training_sessions
+        .into_iter()
+        .filter(|t| dates.contains(&t.date))
+        .filter(|t| routine_id.is_none() || t.routine_id == routine_id)
+        .filter(|t| ongoing_training_session.map(|o| o.training_session_id) != Some(t.id))
+        .collect::<Vec<_>>();
+    selected.sort_by_key(|t| (t.date, t.id));
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use valens_domain::{progression::SetDeviation, RepsRange, RpeRange};
+
+    fn time(seconds: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2020, 2, 2, 10, 0, 0).unwrap() + Duration::seconds(seconds)
+    }
+
+    fn ongoing_training_session() -> OngoingTrainingSession {
+        OngoingTrainingSession {
+            training_session_id: 1,
+            start_time: time(0),
+            element_idx: 0,
+            element_start_time: time(0),
+            paused_duration: 0,
+            paused_since: None,
+            timer_state: TimerState::Unset,
+        }
+    }
+
+    #[test]
+    fn test_settings_initial_interval() {
+        let mut settings: Settings = serde_json::from_value(serde_json::json!({
+            "beep_volume": 80,
+            "theme": "Light",
+            "automatic_metronome": false,
+            "notifications": false,
+            "show_rpe": true,
+            "show_tut": true,
+        }))
+        .unwrap();
+
+        assert_eq!(settings.body_height, None);
+        assert_eq!(settings.initial_interval(), None);
+        assert_eq!(settings.refresh_interval, 15);
+        assert!((settings.weight_increment - 2.5).abs() < f32::EPSILON);
+        assert_eq!(settings.timer_presets, [60, 90, 180]);
+        assert_eq!(settings.metronome_interval, 1);
+        assert_eq!(settings.metronome_stressed_beat, 1);
+        assert_eq!(settings.vibration, vibration::Vibration::Off);
+        assert_eq!(settings.sound_profile, sound::SoundProfile::Classic);
+        assert!(!settings.announce_exercises);
+        assert!(settings.keep_screen_awake);
+        assert_eq!(settings.target_tolerance, 5);
+        assert_eq!(settings.trend_window, 28);
+        assert!(settings.data_tables.is_empty());
+        assert_eq!(settings.decimal_separator, format::DecimalSeparator::Point);
+        assert!(settings.locations.is_empty());
+        assert_eq!(settings.active_location, None);
+        assert!(settings.ad_hoc_previous_values);
+        assert_eq!(settings.contrast, Contrast::System);
+
+        settings.interval = Some(valens_domain::DefaultInterval::_1M);
+
+        assert_eq!(
+            settings.initial_interval(),
+            Some(valens_domain::DefaultInterval::_1M)
+        );
+
+        settings.set_default_interval(Some(valens_domain::DefaultInterval::_1Y));
+
+        assert_eq!(settings.interval, None);
+        assert_eq!(
+            settings.initial_interval(),
+            Some(valens_domain::DefaultInterval::_1Y)
+        );
+    }
+
+    #[test]
+    fn test_settings_initial_interval_after_reload() {
+        let mut settings = Settings::default();
+        settings.set_default_interval(Some(valens_domain::DefaultInterval::_3M));
+        settings.interval = Some(valens_domain::DefaultInterval::_1Y);
+
+        let reloaded: Settings =
+            serde_json::from_value(serde_json::to_value(&settings).unwrap()).unwrap();
+
+        assert_eq!(
+            reloaded.default_interval,
+            Some(valens_domain::DefaultInterval::_3M)
+        );
+        assert_eq!(
+            reloaded.initial_interval(),
+            Some(valens_domain::DefaultInterval::_1Y)
+        );
+    }
+
+    #[test]
+    fn test_settings_body_height() {
+        let mut settings: Settings = serde_json::from_value(serde_json::json!({
+            "beep_volume": 80,
+            "theme": "Light",
+            "automatic_metronome": false,
+            "notifications": false,
+            "show_rpe": true,
+            "show_tut": true,
+            "body_height": 180.0,
+        }))
+        .unwrap();
+
+        assert_eq!(settings.body_height, Some(180.0));
+
+        settings.body_height = None;
+
+        assert!(serde_json::to_value(&settings)
+            .unwrap()
+            .get("body_height")
+            .is_none());
+    }
+
+    #[test]
+    fn test_settings_active_location() {
+        let mut settings = Settings {
+            locations: vec![
+                valens_domain::equipment::Location {
+                    name: String::from("Home"),
+                    ..valens_domain::equipment::Location::default()
+                },
+                valens_domain::equipment::Location {
+                    name: String::from("Gym"),
+                    ..valens_domain::equipment::Location::default()
+                },
+            ],
+            ..Settings::default()
+        };
+
+        assert_eq!(settings.active_location(), None);
+
+        settings.active_location = Some(String::from("Gym"));
+
+        assert_eq!(
+            settings.active_location().map(|l| l.name.as_str()),
+            Some("Gym")
+        );
+
+        settings.active_location = Some(String::from("Park"));
+
+        assert_eq!(settings.active_location(), None);
+    }
+
+    #[rstest]
+    #[case::female(0, None, true)]
+    #[case::female_hidden(0, Some(false), false)]
+    #[case::male(1, None, false)]
+    #[case::male_shown(1, Some(true), true)]
+    fn test_settings_menstrual_cycle_shown(
+        #[case] sex: u8,
+        #[case] show_menstrual_cycle: Option<bool>,
+        #[case] expected: bool,
+    ) {
+        let user = valens_domain::User {
+            id: 1,
+            name: String::from("Alice"),
+            sex,
+            height: None,
+            birthdate: None,
+            has_pin: false,
+        };
+        let mut settings = Settings {
+            show_menstrual_cycle: BTreeMap::from([(2, !expected)]),
+            ..Settings::default()
+        };
+        if let Some(show_menstrual_cycle) = show_menstrual_cycle {
+            settings
+                .show_menstrual_cycle
+                .insert(1, show_menstrual_cycle);
+        }
+
+        assert_eq!(settings.menstrual_cycle_shown(&user), expected);
+    }
+
+    #[rstest]
+    #[case(Contrast::System, false, false)]
+    #[case(Contrast::System, true, true)]
+    #[case(Contrast::Normal, true, false)]
+    #[case(Contrast::High, false, true)]
+    fn test_contrast_is_high(
+        #[case] contrast: Contrast,
+        #[case] system_prefers_high_contrast: bool,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(contrast.is_high(system_prefers_high_contrast), expected);
+    }
+
+    #[test]
+    fn test_settings_serialization() {
+        let settings = Settings {
+            timer_presets: [30, 120, 300],
+            metronome_interval: 4,
+            metronome_stressed_beat: 3,
+            sound_profile: sound::SoundProfile::Soft,
+            ..Settings::default()
+        };
+
+        let deserialized: Settings =
+            serde_json::from_str(&serde_json::to_string(&settings).unwrap()).unwrap();
+
+        assert_eq!(deserialized.timer_presets, [30, 120, 300]);
+        assert_eq!(deserialized.metronome_interval, 4);
+        assert_eq!(deserialized.metronome_stressed_beat, 3);
+        assert_eq!(deserialized.sound_profile, sound::SoundProfile::Soft);
+    }
+
+    #[rstest]
+    #[case::on_target(
+        SetDeviation { reps: Some((8, 8)), weight: Some((50.0, 50.0)), ..SetDeviation::default() },
+        "±0"
+    )]
+    #[case::one_rep(SetDeviation { reps: Some((8, 9)), ..SetDeviation::default() }, "+1 rep")]
+    #[case::reps_and_weight(
+        SetDeviation { reps: Some((8, 6)), weight: Some((50.0, 47.5)), ..SetDeviation::default() },
+        "−2 reps, −2.5 kg"
+    )]
+    #[case::time(SetDeviation { time: Some((60, 65)), ..SetDeviation::default() }, "+5 s")]
+    #[case::weight_rounded(
+        SetDeviation { weight: Some((20.1, 20.3)), ..SetDeviation::default() },
+        "+0.2 kg"
+    )]
+    #[case::rpe(
+        SetDeviation { rpe: Some((RpeRange::single(9.0), 8.0)), ..SetDeviation::default() },
+        "@8 vs @9"
+    )]
+    #[case::rpe_in_range(
+        SetDeviation { rpe: Some((RpeRange::new(7.0, 8.0), 7.5)), ..SetDeviation::default() },
+        "±0"
+    )]
+    #[case::rpe_out_of_range(
+        SetDeviation { rpe: Some((RpeRange::new(7.0, 8.0), 9.0)), ..SetDeviation::default() },
+        "@9 vs @7–8"
+    )]
+    fn test_format_set_deviation(#[case] deviation: SetDeviation, #[case] expected: &str) {
+        assert_eq!(format_set_deviation(&deviation, true, true), expected);
+    }
+
+    #[test]
+    fn test_format_set_deviation_hidden_values() {
+        let deviation = SetDeviation {
+            reps: Some((8, 8)),
+            time: Some((60, 65)),
+            rpe: Some((RpeRange::single(9.0), 8.0)),
+            ..SetDeviation::default()
+        };
+
+        assert_eq!(format_set_deviation(&deviation, false, false), "±0");
+    }
+
+    #[rstest]
+    #[case::all(
+        Some(RepsRange::single(8)),
+        Some(4),
+        Some(50.0),
+        Some(RpeRange::single(8.0)),
+        "8 × 4 s × 50 kg @ 8"
+    )]
+    #[case::rpe_range(
+        Some(RepsRange::single(8)),
+        None,
+        Some(50.0),
+        Some(RpeRange::new(7.0, 8.0)),
+        "8 × 50 kg @ 7–8"
+    )]
+    #[case::reps_range(Some(RepsRange::new(8, 12)), None, Some(50.0), None, "8–12 × 50 kg")]
+    #[case::zero_values(
+        Some(RepsRange::default()),
+        Some(0),
+        Some(0.0),
+        Some(RpeRange::default()),
+        ""
+    )]
+    #[case::none(None, None, None, None, "")]
+    fn test_format_set(
+        #[case] reps: Option<RepsRange>,
+        #[case] time: Option<u32>,
+        #[case] weight: Option<f32>,
+        #[case] rpe: Option<RpeRange>,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            format_set(reps, None, time, true, weight, rpe, true, false),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::partial_reps(Some(2), None, false, "8+2 × 80 kg")]
+    #[case::zero_partial_reps(Some(0), None, false, "8 × 80 kg")]
+    #[case::to_failure(None, None, true, "8 × 80 kg (F)")]
+    #[case::partial_reps_to_failure(Some(2), None, true, "8+2 × 80 kg (F)")]
+    #[case::rpe_to_failure(Some(1), Some(RpeRange::single(10.0)), true, "8+1 × 80 kg @ 10 (F)")]
+    fn test_format_set_partial_reps_and_failure(
+        #[case] partial_reps: Option<u32>,
+        #[case] rpe: Option<RpeRange>,
+        #[case] to_failure: bool,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            format_set(
+                Some(RepsRange::single(8)),
+                partial_reps,
+                None,
+                true,
+                Some(80.0),
+                rpe,
+                true,
+                to_failure
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_format_set_to_failure_without_values() {
+        assert_eq!(
+            format_set(None, None, None, true, None, None, true, true),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_format_set_hidden_values() {
+        assert_eq!(
+            format_set(
+                Some(RepsRange::single(8)),
+                None,
+                Some(4),
+                false,
+                None,
+                Some(RpeRange::single(8.0)),
+                false,
+                false
+            ),
+            "8"
+        );
+    }
+
+    #[rstest]
+    #[case::unspecified(None, Some(20.0), None)]
+    #[case::total(
+        Some(valens_domain::WeightConvention::Total),
+        Some(20.0),
+        Some("Enter the total weight including the bar")
+    )]
+    #[case::per_side(
+        Some(valens_domain::WeightConvention::PerSide),
+        Some(20.0),
+        Some("Enter the weight on one side of the bar (base weight 20 kg)")
+    )]
+    #[case::per_dumbbell(
+        Some(valens_domain::WeightConvention::PerDumbbell),
+        None,
+        Some("Enter the weight of one dumbbell")
+    )]
+    fn test_format_weight_convention(
+        #[case] weight_convention: Option<valens_domain::WeightConvention>,
+        #[case] base_weight: Option<f32>,
+        #[case] expected: Option<&str>,
+    ) {
+        let exercise = valens_domain::Exercise {
+            id: 1,
+            name: String::from("A"),
+            muscles: vec![],
+            weight_convention,
+            base_weight,
+            laterality: valens_domain::Laterality::Bilateral,
+            archived: false,
+            image: None,
+        };
+
+        assert_eq!(format_weight_convention(&exercise).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case::one_rep_max(
+        valens_domain::AsymmetryMetric::OneRepMax,
+        100.0,
+        90.0,
+        format::DecimalSeparator::Point,
+        "L 100.0 kg, R 90.0 kg (right 10.0 % weaker)"
+    )]
+    #[case::reps(
+        valens_domain::AsymmetryMetric::Reps,
+        12.0,
+        15.0,
+        format::DecimalSeparator::Comma,
+        "L 12, R 15 (left 20,0 % weaker)"
+    )]
+    #[case::balanced(
+        valens_domain::AsymmetryMetric::Reps,
+        10.0,
+        10.0,
+        format::DecimalSeparator::Point,
+        "L 10, R 10 (balanced)"
+    )]
+    fn test_format_side_asymmetry(
+        #[case] metric: valens_domain::AsymmetryMetric,
+        #[case] left: f32,
+        #[case] right: f32,
+        #[case] separator: format::DecimalSeparator,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            format_side_asymmetry(
+                &valens_domain::SideAsymmetry {
+                    metric,
+                    left,
+                    right
+                },
+                separator
+            ),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::one_rep_max(
+        valens_domain::RecordKind::OneRepMax(93.33),
+        format::DecimalSeparator::Point,
+        "Estimated 1RM: 93.3 kg"
+    )]
+    #[case::weight(
+        valens_domain::RecordKind::Weight(82.5),
+        format::DecimalSeparator::Comma,
+        "Weight: 82,5 kg"
+    )]
+    #[case::reps_with_weight(
+        valens_domain::RecordKind::Reps { reps: 6, weight: Some(80.0) },
+        format::DecimalSeparator::Point,
+        "Reps: 6 × 80.0 kg"
+    )]
+    #[case::reps_without_weight(
+        valens_domain::RecordKind::Reps { reps: 12, weight: None },
+        format::DecimalSeparator::Point,
+        "Reps: 12"
+    )]
+    #[case::time(
+        valens_domain::RecordKind::Time { time: 45, weight: None },
+        format::DecimalSeparator::Point,
+        "Time: 45 s"
+    )]
+    fn test_format_personal_record(
+        #[case] kind: valens_domain::RecordKind,
+        #[case] separator: format::DecimalSeparator,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(format_personal_record(&kind, separator), expected);
+    }
+
+    #[rstest]
+    #[case::plates(
+        Some(valens_domain::WeightConvention::Total),
+        Some(20.0),
+        72.5,
+        Some("Plates per side: 20 + 5 + 1.25 kg")
+    )]
+    #[case::empty_bar(Some(valens_domain::WeightConvention::Total), Some(20.0), 20.0, None)]
+    #[case::unreachable_plates(
+        Some(valens_domain::WeightConvention::PerSide),
+        Some(20.0),
+        27.0,
+        Some("Not achievable at Gym, nearest: 26.25 kg")
+    )]
+    #[case::unreachable_dumbbells(
+        Some(valens_domain::WeightConvention::PerDumbbell),
+        None,
+        13.0,
+        Some("Not achievable at Gym, nearest: 12 kg or 14 kg")
+    )]
+    #[case::achievable_dumbbells(
+        Some(valens_domain::WeightConvention::PerDumbbell),
+        None,
+        14.0,
+        None
+    )]
+    #[case::missing_equipment(None, None, 47.5, None)]
+    fn test_format_equipment_hint(
+        #[case] weight_convention: Option<valens_domain::WeightConvention>,
+        #[case] base_weight: Option<f32>,
+        #[case] weight: f32,
+        #[case] expected: Option<&str>,
+    ) {
+        let location = valens_domain::equipment::Location {
+            name: String::from("Gym"),
+            dumbbells: Some(valens_domain::equipment::WeightRange::new(2.0, 30.0, 2.0).unwrap()),
+            plates: vec![20.0, 5.0, 1.25],
+            machine_stack: None,
+        };
+        let exercise = valens_domain::Exercise {
+            id: 1,
+            name: String::from("A"),
+            muscles: vec![],
+            weight_convention,
+            base_weight,
+            laterality: valens_domain::Laterality::Bilateral,
+            archived: false,
+            image: None,
+        };
+
+        assert_eq!(
+            format_equipment_hint(&location, &exercise, weight).as_deref(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::no_rounds(&[], None)]
+    #[case::single_round(&[(1, 1)], None)]
+    #[case::rounds(&[(2, 4)], Some("Round 2/4"))]
+    #[case::nested_rounds(&[(1, 2), (1, 1), (3, 3)], Some("Round 1/2 · 3/3"))]
+    fn test_format_rounds(#[case] rounds: &[(u32, u32)], #[case] expected: Option<&str>) {
+        assert_eq!(
+            format_rounds(
+                &rounds
+                    .iter()
+                    .map(|(number, total)| valens_domain::Round {
+                        number: *number,
+                        total: *total,
+                    })
+                    .collect::<Vec<_>>()
+            )
+            .as_deref(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::all(None, None, &[4, 1, 2, 3])]
+    #[case::routine(Some(2), None, &[2, 3])]
+    #[case::ongoing(None, Some(2), &[4, 1, 3])]
+    #[case::routine_and_ongoing(Some(2), Some(3), &[2])]
+    fn test_select_training_sessions(
+        #[case] routine_id: Option<u32>,
+        #[case] ongoing_training_session_id: Option<u32>,
+        #[case] expected: &[u32],
+    ) {
+        let training_session =
+            |id: u32, routine_id: Option<u32>, day: u32| valens_domain::TrainingSession {
+                id,
+                routine_id,
+                date: NaiveDate::from_ymd_opt(2020, 2, day).unwrap(),
+                notes: None,
+                start_time: None,
+                end_time: None,
+                elements: vec![],
+            };
+        let training_sessions = [
+            training_session(1, Some(1), 3),
+            training_session(2, Some(2), 4),
+            training_session(3, Some(2), 5),
+            training_session(4, None, 2),
+            training_session(5, Some(2), 1),
+            training_session(6, Some(2), 6),
+        ];
+        let ongoing_training_session = ongoing_training_session_id.map(OngoingTrainingSession::new);
+
+        assert_eq!(
+            select_training_sessions(
+                &training_sessions,
+                &(NaiveDate::from_ymd_opt(2020, 2, 2).unwrap()
+                    ..=NaiveDate::from_ymd_opt(2020, 2, 5).unwrap()),
+                routine_id,
+                ongoing_training_session.as_ref(),
+            )
+            .iter()
+            .map(|t| t.id)
+            .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    fn training_session_draft() -> TrainingSessionDraft {
+        TrainingSessionDraft {
+            training_session: valens_domain::TrainingSession {
+                id: 1,
+                routine_id: None,
+                date: NaiveDate::from_ymd_opt(2020, 2, 2).unwrap(),
+                notes: None,
+                start_time: None,
+                end_time: None,
+                elements: vec![
+                    valens_domain::TrainingSessionElement::Set {
+                        exercise_id: 2,
+                        reps: None,
+                        time: None,
+                        weight: Some(52.5),
+                        rpe: None,
+                        target_reps: Some(RepsRange::single(8)),
+                        target_time: None,
+                        target_weight: None,
+                        target_rpe: Some(RpeRange::new(8.0, 9.0)),
+                        automatic: false,
+                        partial_reps: None,
+                        to_failure: false,
+                        side: None,
+                        comment: None,
+                    },
+                    valens_domain::TrainingSessionElement::Rest {
+                        target_time: Some(60),
+                        automatic: true,
+                    },
+                ],
+            },
+            notes: Some(String::from("Felt strong")),
+            duration: None,
+            sets: vec![SetDraft {
+                element_idx: 0,
+                exercise_idx: 0,
+                exercise_id: 2,
+                reps: Some(String::from("8")),
+                time: None,
+                weight: None,
+                rpe: Some(String::from("9")),
+                partial_reps: None,
+                to_failure: None,
+                comment: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_training_session_draft_is_applicable() {
+        let draft = training_session_draft();
+        let mut training_session = draft.training_session.clone();
+
+        assert!(draft.is_applicable(&training_session));
+
+        training_session.notes = Some(String::from("Saved on other device"));
+
+        assert!(!draft.is_applicable(&training_session));
+    }
+
+    #[rstest]
+    #[case::removed_element(|elements: &mut Vec<valens_domain::TrainingSessionElement>| {
+        elements.pop();
+    })]
+    #[case::replaced_exercise(|elements: &mut Vec<valens_domain::TrainingSessionElement>| {
+        if let valens_domain::TrainingSessionElement::Set { exercise_id, .. } = &mut elements[0] {
+            *exercise_id = 3;
+        }
+    })]
+    fn test_training_session_draft_is_applicable_changed_structure(
+        #[case] change: fn(&mut Vec<valens_domain::TrainingSessionElement>),
+    ) {
+        let draft = training_session_draft();
+        let mut training_session = draft.training_session.clone();
+
+        change(&mut training_session.elements);
+
+        assert!(!draft.is_applicable(&training_session));
+    }
+
+    #[test]
+    fn test_training_session_draft_serde() {
+        let draft = training_session_draft();
+
+        let deserialized: TrainingSessionDraft =
+            serde_json::from_str(&serde_json::to_string(&draft).unwrap()).unwrap();
+
+        assert_eq!(deserialized, draft);
+        assert!(deserialized.is_applicable(&draft.training_session));
+    }
+
+    #[test]
+    fn test_ongoing_training_session_pause_resume() {
+        let mut session = ongoing_training_session();
+
+        assert_eq!(session.elapsed_time(time(10)), Duration::seconds(10));
+        assert!(!session.is_paused());
+
+        session.pause(time(10));
+
+        assert!(session.is_paused());
+        assert_eq!(session.elapsed_time(time(40)), Duration::seconds(10));
+
+        session.resume(time(40));
+
+        assert!(!session.is_paused());
+        assert_eq!(session.paused_duration, 30_000);
+        assert_eq!(session.elapsed_time(time(50)), Duration::seconds(20));
+
+        session.go_to_next_element(3, time(60));
+
+        assert_eq!(
+            session,
+            OngoingTrainingSession {
+                element_idx: 1,
+                element_start_time: time(60),
+                ..ongoing_training_session()
+            }
+        );
+        assert_eq!(session.elapsed_time(time(65)), Duration::seconds(5));
+    }
+
+    #[test]
+    fn test_ongoing_training_session_multiple_pauses() {
+        let mut session = ongoing_training_session();
+
+        session.pause(time(10));
+        session.pause(time(20));
+        session.resume(time(30));
+        session.resume(time(40));
+        session.pause(time(50));
+        session.resume(time(55));
+
+        assert_eq!(session.paused_duration, 25_000);
+        assert_eq!(session.elapsed_time(time(60)), Duration::seconds(35));
+    }
+
+    #[test]
+    fn test_ongoing_training_session_go_to_next_element_while_paused() {
+        let mut session = ongoing_training_session();
+
+        session.pause(time(10));
+        session.go_to_next_element(3, time(20));
+
+        assert!(!session.is_paused());
+        assert_eq!(session.element_idx, 1);
+        assert_eq!(session.elapsed_time(time(30)), Duration::seconds(10));
+    }
+
+    #[test]
+    fn test_ongoing_training_session_go_to_next_element_at_last_element() {
+        let mut session = OngoingTrainingSession {
+            element_idx: 2,
+            ..ongoing_training_session()
+        };
+
+        session.go_to_next_element(3, time(20));
+
+        assert_eq!(
+            session,
+            OngoingTrainingSession {
+                element_idx: 2,
+                ..ongoing_training_session()
+            }
+        );
+    }
+
+    #[test]
+    fn test_ongoing_training_session_apply_notification_action() {
+        let mut session = ongoing_training_session();
+
+        assert!(session.apply_notification_action(
+            service_worker::SelectedNotificationAction {
+                action: service_worker::NotificationAction::GoToNextSection,
+                training_session_id: 1,
+                element_count: 2,
+            },
+            time(10)
+        ));
+        assert_eq!(session.element_idx, 1);
+
+        assert!(session.apply_notification_action(
+            service_worker::SelectedNotificationAction {
+                action: service_worker::NotificationAction::GoToNextSection,
+                training_session_id: 1,
+                element_count: 2,
+            },
+            time(20)
+        ));
+        assert_eq!(session.element_idx, 1);
+        assert_eq!(session.element_start_time, time(10));
+    }
+
+    #[test]
+    fn test_ongoing_training_session_apply_notification_action_other_session() {
+        let mut session = ongoing_training_session();
+
+        assert!(!session.apply_notification_action(
+            service_worker::SelectedNotificationAction {
+                action: service_worker::NotificationAction::GoToNextSection,
+                training_session_id: 2,
+                element_count: 3,
+            },
+            time(10)
+        ));
+        assert_eq!(session, ongoing_training_session());
+    }
+
+    #[test]
+    fn test_ongoing_training_session_extend_timer() {
+        let mut session = ongoing_training_session();
+
+        session.extend_timer(Duration::seconds(30));
+
+        assert_eq!(session.timer_state, TimerState::Unset);
+
+        session.timer_state = TimerState::Active {
+            target_time: time(60),
+        };
+        session.extend_timer(Duration::seconds(30));
+
+        assert_eq!(
+            session.timer_state,
+            TimerState::Active {
+                target_time: time(90)
+            }
+        );
+
+        session.timer_state = TimerState::Paused { time: 20 };
+        session.extend_timer(Duration::seconds(30));
+
+        assert_eq!(session.timer_state, TimerState::Paused { time: 50 });
+    }
+
+    #[test]
+    fn test_ongoing_training_session_deserialize_without_pause() {
+        let session: OngoingTrainingSession = serde_json::from_value(serde_json::json!({
+            "training_session_id": 1,
+            "start_time": "2020-02-02T10:00:00Z",
+            "element_idx": 0,
+            "element_start_time": "2020-02-02T10:00:00Z",
+            "timer_state": "Unset"
+        }))
+        .unwrap();
+
+        assert_eq!(session, ongoing_training_session());
+        assert_eq!(session.elapsed_time(time(10)), Duration::seconds(10));
+    }
+
+    #[test]
+    fn test_ongoing_training_session_serde() {
+        let mut session = ongoing_training_session();
+        session.pause(time(10));
+
+        let deserialized: OngoingTrainingSession =
+            serde_json::from_value(serde_json::json!(session)).unwrap();
+
+        assert_eq!(deserialized, session);
+    }
+}
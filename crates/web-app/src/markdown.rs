@@ -0,0 +1,174 @@
+//! Rendering of a minimal subset of Markdown for notes.
+//!
+//! Supported are bold (`**text**`), italic (`*text*` or `_text_`), bullet lists (lines starting
+//! with `- ` or `* `) and line breaks. Paragraphs are separated by empty lines. Everything else is
+//! escaped, so that the result can be safely inserted into the page.
+
+use std::fmt::Write;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Block {
+    None,
+    Paragraph,
+    List,
+}
+
+/// Convert the text into HTML.
+#[must_use]
+pub fn to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut block = Block::None;
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        if let Some(item) = list_item(line) {
+            if block != Block::List {
+                close(&mut html, block);
+                html.push_str("<ul>");
+            }
+            let _ = write!(html, "<li>{}</li>", inline(item));
+            block = Block::List;
+        } else if line.trim_start().is_empty() {
+            close(&mut html, block);
+            block = Block::None;
+        } else {
+            match block {
+                Block::Paragraph => html.push_str("<br>"),
+                Block::List => {
+                    close(&mut html, block);
+                    html.push_str("<p>");
+                }
+                Block::None => html.push_str("<p>"),
+            }
+            html.push_str(&inline(line.trim_start()));
+            block = Block::Paragraph;
+        }
+    }
+    close(&mut html, block);
+
+    html
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .map(str::trim_start)
+}
+
+fn close(html: &mut String, block: Block) {
+    match block {
+        Block::None => {}
+        Block::Paragraph => html.push_str("</p>"),
+        Block::List => html.push_str("</ul>"),
+    }
+}
+
+fn inline(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut html = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if let Some((tag, delimiter_len, end)) = emphasis(&chars, i) {
+            let content = chars[i + delimiter_len..end].iter().collect::<String>();
+            let _ = write!(html, "<{tag}>{}</{tag}>", inline(&content));
+            i = end + delimiter_len;
+        } else {
+            escape(&mut html, chars[i]);
+            i += 1;
+        }
+    }
+
+    html
+}
+
+/// Find an emphasis starting at the given position.
+///
+/// The result contains the HTML tag, the length of the delimiter and the position of the closing
+/// delimiter. Like in Markdown, the content must not start or end with whitespace, and underscores
+/// inside of words do not mark an emphasis.
+fn emphasis(chars: &[char], start: usize) -> Option<(&'static str, usize, usize)> {
+    let delimiter = chars[start];
+    if delimiter != '*' && delimiter != '_' {
+        return None;
+    }
+    if delimiter == '_' && start > 0 && chars[start - 1].is_alphanumeric() {
+        return None;
+    }
+    let (tag, delimiter_len) = if chars.get(start + 1) == Some(&delimiter) {
+        ("strong", 2)
+    } else {
+        ("em", 1)
+    };
+    let content_start = start + delimiter_len;
+    chars.get(content_start).filter(|c| !c.is_whitespace())?;
+    let end = (content_start + 1..=chars.len().saturating_sub(delimiter_len)).find(|&j| {
+        chars[j..j + delimiter_len].iter().all(|&c| c == delimiter)
+            && !chars[j - 1].is_whitespace()
+            && (delimiter != '_'
+                || !chars
+                    .get(j + delimiter_len)
+                    .is_some_and(|c| c.is_alphanumeric()))
+    })?;
+    Some((tag, delimiter_len, end))
+}
+
+fn escape(html: &mut String, c: char) {
+    match c {
+        '&' => html.push_str("&amp;"),
+        '<' => html.push_str("&lt;"),
+        '>' => html.push_str("&gt;"),
+        '"' => html.push_str("&quot;"),
+        '\'' => html.push_str("&#39;"),
+        _ => html.push(c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty("", "")]
+    #[case::plain("Focus on depth", "<p>Focus on depth</p>")]
+    #[case::bold("Keep **back straight**", "<p>Keep <strong>back straight</strong></p>")]
+    #[case::italic_asterisk("*Slow* descent", "<p><em>Slow</em> descent</p>")]
+    #[case::italic_underscore("_Slow_ descent", "<p><em>Slow</em> descent</p>")]
+    #[case::nested("**Brace _hard_**", "<p><strong>Brace <em>hard</em></strong></p>")]
+    #[case::underscore_in_word("snake_case_name", "<p>snake_case_name</p>")]
+    #[case::unclosed("**bold and *italic", "<p>**bold and *italic</p>")]
+    #[case::whitespace_after_delimiter("2 * 3 * 4", "<p>2 * 3 * 4</p>")]
+    #[case::line_break("Squat\nBench", "<p>Squat<br>Bench</p>")]
+    #[case::paragraphs("Squat\n\nBench", "<p>Squat</p><p>Bench</p>")]
+    #[case::list(
+        "Warm-up:\n- Rows\n* **Band** pull-aparts\n\nThen start",
+        "<p>Warm-up:</p><ul><li>Rows</li><li><strong>Band</strong> pull-aparts</li></ul><p>Then start</p>"
+    )]
+    #[case::text_after_list("- Rows\nDone", "<ul><li>Rows</li></ul><p>Done</p>")]
+    #[case::windows_line_endings("A\r\nB", "<p>A<br>B</p>")]
+    fn test_to_html(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(to_html(text), expected);
+    }
+
+    #[rstest]
+    #[case::script(
+        "<script>alert('x')</script>",
+        "<p>&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;</p>"
+    )]
+    #[case::script_in_bold(
+        "**<script>alert(1)</script>**",
+        "<p><strong>&lt;script&gt;alert(1)&lt;/script&gt;</strong></p>"
+    )]
+    #[case::script_in_list(
+        "- <img src=x onerror=\"alert(1)\">",
+        "<ul><li>&lt;img src=x onerror=&quot;alert(1)&quot;&gt;</li></ul>"
+    )]
+    #[case::entity("&lt;b&gt;", "<p>&amp;lt;b&amp;gt;</p>")]
+    fn test_to_html_escaping(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(to_html(text), expected);
+    }
+}
@@ -0,0 +1,37 @@
+//! Detection of user preferences expressed by media features.
+
+/// Check whether the user prefers a dark color scheme.
+///
+/// # Errors
+///
+/// Returns an error if the media query could not be evaluated.
+pub fn prefers_dark_color_scheme() -> Result<bool, String> {
+    matches("(prefers-color-scheme: dark)")
+}
+
+/// Check whether the user prefers to minimize the amount of non-essential motion.
+///
+/// # Errors
+///
+/// Returns an error if the media query could not be evaluated.
+pub fn prefers_reduced_motion() -> Result<bool, String> {
+    matches("(prefers-reduced-motion: reduce)")
+}
+
+/// Check whether the user prefers an increased contrast.
+///
+/// # Errors
+///
+/// Returns an error if the media query could not be evaluated.
+pub fn prefers_high_contrast() -> Result<bool, String> {
+    Ok(matches("(prefers-contrast: more)")? || matches("(forced-colors: active)")?)
+}
+
+fn matches(query: &str) -> Result<bool, String> {
+    Ok(web_sys::window()
+        .ok_or("no window")?
+        .match_media(query)
+        .map_err(|err| format!("failed to match media {query}: {err:?}"))?
+        .ok_or_else(|| format!("failed to parse media query {query}"))?
+        .matches())
+}
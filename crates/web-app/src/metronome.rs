@@ -0,0 +1,273 @@
+//! Scheduling of metronome beats.
+//!
+//! All times are given in seconds on the clock of the audio context.
+
+use std::num::NonZeroU32;
+
+use crate::{sound::Beep, vibration::Pattern};
+
+/// Time by which beats are scheduled ahead of the current time.
+const SCHEDULE_AHEAD_TIME: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beat {
+    pub time: f64,
+    /// First beat of a group of beats.
+    pub stressed: bool,
+}
+
+impl Beat {
+    #[must_use]
+    pub fn beep(self) -> Beep {
+        if self.stressed {
+            Beep::StressedBeat
+        } else {
+            Beep::Beat
+        }
+    }
+
+    #[must_use]
+    pub fn vibration_pattern(self) -> Pattern {
+        if self.stressed {
+            Pattern::StressedBeat
+        } else {
+            Pattern::Beat
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Metronome {
+    /// Seconds between two beats.
+    interval: NonZeroU32,
+    /// Every nth beat is stressed.
+    stressed_beat: NonZeroU32,
+    beat_number: u32,
+    next_beat_time: f64,
+    vibration_beat_number: u32,
+    next_vibration_time: f64,
+    is_active: bool,
+}
+
+impl Metronome {
+    /// Create an inactive metronome. Values of zero are replaced by one.
+    #[must_use]
+    pub fn new(interval: u32, stressed_beat: u32) -> Self {
+        Self {
+            interval: non_zero(interval),
+            stressed_beat: non_zero(stressed_beat),
+            beat_number: 0,
+            next_beat_time: 0.,
+            vibration_beat_number: 0,
+            next_vibration_time: 0.,
+            is_active: false,
+        }
+    }
+
+    #[must_use]
+    pub fn interval(&self) -> u32 {
+        self.interval.get()
+    }
+
+    /// Set the seconds between two beats. A value of zero is replaced by one.
+    pub fn set_interval(&mut self, interval: u32) {
+        self.interval = non_zero(interval);
+    }
+
+    #[must_use]
+    pub fn stressed_beat(&self) -> u32 {
+        self.stressed_beat.get()
+    }
+
+    /// Set which beat is stressed. A value of zero is replaced by one.
+    pub fn set_stressed_beat(&mut self, stressed_beat: u32) {
+        self.stressed_beat = non_zero(stressed_beat);
+    }
+
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Start the metronome with a stressed beat shortly after the current time.
+    pub fn start(&mut self, current_time: f64) {
+        self.is_active = true;
+        self.beat_number = 0;
+        self.next_beat_time = current_time + SCHEDULE_AHEAD_TIME;
+        self.vibration_beat_number = 0;
+        self.next_vibration_time = self.next_beat_time;
+    }
+
+    pub fn pause(&mut self) {
+        self.is_active = false;
+    }
+
+    pub fn start_pause(&mut self, current_time: f64) {
+        if self.is_active {
+            self.pause();
+        } else {
+            self.start(current_time);
+        }
+    }
+
+    /// Return the beats which have to be scheduled up to shortly after the current time.
+    ///
+    /// Each beat is returned only once.
+    pub fn schedule_beats(&mut self, current_time: f64) -> Vec<Beat> {
+        let mut beats = vec![];
+        if !self.is_active {
+            return beats;
+        }
+        while self.next_beat_time < current_time + SCHEDULE_AHEAD_TIME {
+            beats.push(Beat {
+                time: self.next_beat_time,
+                stressed: self.beat_number % self.stressed_beat == 0,
+            });
+            self.next_beat_time += f64::from(self.interval.get());
+            self.beat_number += 1;
+        }
+        beats
+    }
+
+    /// Return the beat for which a vibration has to be triggered at the current time.
+    ///
+    /// Vibrations cannot be scheduled in advance like beeps, so they are triggered as soon as the
+    /// time of the corresponding beat has been reached. Beats which have been missed are skipped.
+    pub fn due_vibration(&mut self, current_time: f64) -> Option<Beat> {
+        if !self.is_active || self.next_vibration_time > current_time {
+            return None;
+        }
+        let beat = Beat {
+            time: self.next_vibration_time,
+            stressed: self.vibration_beat_number % self.stressed_beat == 0,
+        };
+        while self.next_vibration_time <= current_time {
+            self.next_vibration_time += f64::from(self.interval.get());
+            self.vibration_beat_number += 1;
+        }
+        Some(beat)
+    }
+}
+
+fn non_zero(value: u32) -> NonZeroU32 {
+    NonZeroU32::new(value).unwrap_or(NonZeroU32::MIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn beat(time: f64, stressed: bool) -> Beat {
+        Beat { time, stressed }
+    }
+
+    #[rstest]
+    #[case::zero(0, 0, 1, 1)]
+    #[case::non_zero(2, 4, 2, 4)]
+    fn test_metronome_new(
+        #[case] interval: u32,
+        #[case] stressed_beat: u32,
+        #[case] expected_interval: u32,
+        #[case] expected_stressed_beat: u32,
+    ) {
+        let metronome = Metronome::new(interval, stressed_beat);
+
+        assert_eq!(metronome.interval(), expected_interval);
+        assert_eq!(metronome.stressed_beat(), expected_stressed_beat);
+        assert!(!metronome.is_active());
+    }
+
+    #[test]
+    fn test_metronome_set_zero() {
+        let mut metronome = Metronome::new(2, 3);
+
+        metronome.set_interval(0);
+        metronome.set_stressed_beat(0);
+
+        assert_eq!(metronome.interval(), 1);
+        assert_eq!(metronome.stressed_beat(), 1);
+    }
+
+    #[test]
+    fn test_metronome_schedule_beats_inactive() {
+        let mut metronome = Metronome::new(1, 1);
+
+        assert_eq!(metronome.schedule_beats(10.0), vec![]);
+        assert_eq!(metronome.due_vibration(10.0), None);
+    }
+
+    #[test]
+    fn test_metronome_schedule_beats() {
+        let mut metronome = Metronome::new(1, 3);
+        metronome.start(10.0);
+
+        assert_eq!(metronome.schedule_beats(10.0), vec![]);
+        assert_eq!(metronome.schedule_beats(10.1), vec![beat(10.5, true)]);
+        assert_eq!(metronome.schedule_beats(10.2), vec![]);
+        assert_eq!(
+            metronome.schedule_beats(12.6),
+            vec![beat(11.5, false), beat(12.5, false)]
+        );
+        assert_eq!(
+            metronome.schedule_beats(14.1),
+            vec![beat(13.5, true), beat(14.5, false)]
+        );
+
+        metronome.pause();
+
+        assert_eq!(metronome.schedule_beats(20.0), vec![]);
+
+        metronome.start(20.0);
+
+        assert_eq!(metronome.schedule_beats(20.5), vec![beat(20.5, true)]);
+    }
+
+    #[test]
+    fn test_metronome_schedule_beats_interval_changed() {
+        let mut metronome = Metronome::new(1, 2);
+        metronome.start(0.0);
+
+        assert_eq!(
+            metronome.schedule_beats(1.1),
+            vec![beat(0.5, true), beat(1.5, false)]
+        );
+
+        metronome.set_interval(0);
+
+        assert_eq!(
+            metronome.schedule_beats(3.1),
+            vec![beat(2.5, true), beat(3.5, false)]
+        );
+    }
+
+    #[test]
+    fn test_metronome_due_vibration() {
+        let mut metronome = Metronome::new(2, 2);
+        metronome.start(0.0);
+
+        assert_eq!(metronome.due_vibration(0.4), None);
+        assert_eq!(metronome.due_vibration(0.5), Some(beat(0.5, true)));
+        assert_eq!(metronome.due_vibration(1.0), None);
+        assert_eq!(metronome.due_vibration(2.6), Some(beat(2.5, false)));
+        assert_eq!(metronome.due_vibration(8.6), Some(beat(4.5, true)));
+        assert_eq!(metronome.due_vibration(9.0), None);
+        assert_eq!(metronome.due_vibration(10.5), Some(beat(10.5, false)));
+    }
+
+    #[rstest]
+    #[case::unstressed(false, Beep::Beat, Pattern::Beat)]
+    #[case::stressed(true, Beep::StressedBeat, Pattern::StressedBeat)]
+    fn test_beat(
+        #[case] stressed: bool,
+        #[case] expected_beep: Beep,
+        #[case] expected_pattern: Pattern,
+    ) {
+        let beat = beat(0.0, stressed);
+
+        assert_eq!(beat.beep(), expected_beep);
+        assert_eq!(beat.vibration_pattern(), expected_pattern);
+    }
+}
@@ -0,0 +1,351 @@
+//! Body diagram with muscles colored by training stimulus.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use valens_domain as domain;
+
+/// Color of muscles without any stimulus.
+pub const COLOR_NO_STIMULUS: (u8, u8, u8) = (181, 181, 181);
+/// Color of muscles with the lowest stimulus greater than zero.
+pub const COLOR_LOW_STIMULUS: (u8, u8, u8) = (255, 224, 138);
+/// Color of muscles with a stimulus at or above the cap.
+pub const COLOR_HIGH_STIMULUS: (u8, u8, u8) = (204, 15, 53);
+
+const WIDTH: f32 = 200.;
+const HEIGHT: f32 = 220.;
+const CENTER_FRONT: f32 = 50.;
+const CENTER_BACK: f32 = 150.;
+
+/// Map the number of hard sets of a muscle to a color.
+///
+/// Muscles without hard sets are shown in a neutral color. All values at or above the cap are
+/// mapped to the color of the highest stimulus.
+#[must_use]
+pub fn stimulus_color(hard_sets: f32, cap: f32) -> (u8, u8, u8) {
+    if hard_sets.is_nan() || hard_sets <= 0. || cap <= 0. {
+        return COLOR_NO_STIMULUS;
+    }
+
+    let ratio = (hard_sets / cap).min(1.);
+    let mix = |low: u8, high: u8| {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let value = (f32::from(low) + (f32::from(high) - f32::from(low)) * ratio).round() as u8;
+        value
+    };
+
+    (
+        mix(COLOR_LOW_STIMULUS.0, COLOR_HIGH_STIMULUS.0),
+        mix(COLOR_LOW_STIMULUS.1, COLOR_HIGH_STIMULUS.1),
+        mix(COLOR_LOW_STIMULUS.2, COLOR_HIGH_STIMULUS.2),
+    )
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[derive(Clone, Copy)]
+enum View {
+    Front,
+    Back,
+}
+
+enum Shape {
+    Rect(f32, f32, f32, f32),
+    Ellipse(f32, f32, f32, f32),
+    Polygon(&'static [(f32, f32)]),
+}
+
+/// Area of a muscle in the diagram.
+///
+/// The x coordinates are relative to the center of the body. Symmetric areas are defined for the
+/// left side of the diagram and mirrored to the right side.
+struct Region {
+    muscle: domain::Muscle,
+    views: &'static [View],
+    shape: Shape,
+    symmetric: bool,
+}
+
+const REGIONS: &[Region] = &[
+    Region {
+        muscle: domain::Muscle::Neck,
+        views: &[View::Front],
+        shape: Shape::Rect(-4., 27., 8., 6.),
+        symmetric: false,
+    },
+    Region {
+        muscle: domain::Muscle::Pecs,
+        views: &[View::Front],
+        shape: Shape::Rect(-19., 37., 18., 16.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Traps,
+        views: &[View::Back],
+        shape: Shape::Polygon(&[(-14., 34.), (14., 34.), (0., 60.)]),
+        symmetric: false,
+    },
+    Region {
+        muscle: domain::Muscle::Lats,
+        views: &[View::Back],
+        shape: Shape::Polygon(&[(-20., 44.), (-8., 60.), (-10., 82.), (-20., 68.)]),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::FrontDelts,
+        views: &[View::Front],
+        shape: Shape::Ellipse(-24., 38., 6., 5.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::SideDelts,
+        views: &[View::Front, View::Back],
+        shape: Shape::Ellipse(-29., 41., 3., 5.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::RearDelts,
+        views: &[View::Back],
+        shape: Shape::Ellipse(-24., 38., 6., 5.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Biceps,
+        views: &[View::Front],
+        shape: Shape::Rect(-30., 47., 8., 20.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Triceps,
+        views: &[View::Back],
+        shape: Shape::Rect(-30., 47., 8., 20.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Forearms,
+        views: &[View::Front, View::Back],
+        shape: Shape::Rect(-31., 71., 8., 27.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Abs,
+        views: &[View::Front],
+        shape: Shape::Rect(-8., 56., 16., 34.),
+        symmetric: false,
+    },
+    Region {
+        muscle: domain::Muscle::ErectorSpinae,
+        views: &[View::Back],
+        shape: Shape::Rect(-5., 62., 10., 30.),
+        symmetric: false,
+    },
+    Region {
+        muscle: domain::Muscle::Glutes,
+        views: &[View::Back],
+        shape: Shape::Rect(-18., 94., 17., 16.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Abductors,
+        views: &[View::Back],
+        shape: Shape::Rect(-22., 92., 3.5, 14.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Quads,
+        views: &[View::Front],
+        shape: Shape::Rect(-20., 104., 13., 44.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Hamstrings,
+        views: &[View::Back],
+        shape: Shape::Rect(-20., 112., 18., 38.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Adductors,
+        views: &[View::Front],
+        shape: Shape::Rect(-6., 104., 5., 26.),
+        symmetric: true,
+    },
+    Region {
+        muscle: domain::Muscle::Calves,
+        views: &[View::Back],
+        shape: Shape::Rect(-18., 156., 13., 30.),
+        symmetric: true,
+    },
+];
+
+/// Outline of the body, which is drawn behind the muscles.
+const OUTLINE: &[(Shape, bool)] = &[
+    (Shape::Ellipse(0., 17., 9., 10.), false),
+    (Shape::Rect(-4., 26., 8., 8.), false),
+    (Shape::Rect(-22., 33., 44., 69.), false),
+    (Shape::Rect(-31., 35., 9., 35.), true),
+    (Shape::Rect(-32., 70., 9., 32.), true),
+    (Shape::Rect(-21., 100., 20., 54.), true),
+    (Shape::Rect(-19., 152., 16., 46.), true),
+];
+
+/// Create a SVG of the front and the back of the body with the muscles colored by the number of
+/// hard sets.
+///
+/// Each muscle area has a `data-muscle` attribute containing the muscle ID, which allows to
+/// determine the muscle when an area is clicked.
+#[must_use]
+pub fn svg(hard_sets: &BTreeMap<u8, f32>, cap: f32) -> String {
+    let mut result = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {WIDTH} {HEIGHT}" class="muscle-map">"#
+    );
+
+    let _ = write!(
+        result,
+        r#"<defs><linearGradient id="muscle-map-legend"><stop offset="0" stop-color="{}"/><stop offset="1" stop-color="{}"/></linearGradient></defs>"#,
+        hex(COLOR_LOW_STIMULUS),
+        hex(COLOR_HIGH_STIMULUS),
+    );
+
+    for center in [CENTER_FRONT, CENTER_BACK] {
+        for (shape, symmetric) in OUTLINE {
+            for mirrored in mirrors(*symmetric) {
+                result.push_str(&shape_element(
+                    shape,
+                    center,
+                    *mirrored,
+                    r#"fill="currentColor" fill-opacity="0.1""#,
+                    "",
+                ));
+            }
+        }
+    }
+
+    for region in REGIONS {
+        let value = hard_sets.get(&region.muscle.id()).copied().unwrap_or(0.);
+        let attributes = format!(
+            r#"fill="{}" stroke="currentColor" stroke-opacity="0.3" stroke-width="0.5" data-muscle="{}" style="cursor: pointer""#,
+            hex(stimulus_color(value, cap)),
+            region.muscle.id(),
+        );
+        for view in region.views {
+            let center = match view {
+                View::Front => CENTER_FRONT,
+                View::Back => CENTER_BACK,
+            };
+            for mirrored in mirrors(region.symmetric) {
+                result.push_str(&shape_element(
+                    &region.shape,
+                    center,
+                    *mirrored,
+                    &attributes,
+                    &format!("<title>{}: {value:.1}</title>", region.muscle.name()),
+                ));
+            }
+        }
+    }
+
+    let _ = write!(
+        result,
+        r#"<text x="{CENTER_FRONT}" y="208" font-size="6" text-anchor="middle" fill="currentColor">Front</text><text x="{CENTER_BACK}" y="208" font-size="6" text-anchor="middle" fill="currentColor">Back</text>"#,
+    );
+    let _ = write!(
+        result,
+        r#"<text x="52" y="217" font-size="6" text-anchor="end" fill="currentColor">0</text><rect x="55" y="212" width="90" height="6" rx="1" fill="url(#muscle-map-legend)"/><text x="148" y="217" font-size="6" fill="currentColor">≥ {cap}</text>"#,
+    );
+
+    result.push_str("</svg>");
+    result
+}
+
+fn mirrors(symmetric: bool) -> &'static [bool] {
+    if symmetric {
+        &[false, true]
+    } else {
+        &[false]
+    }
+}
+
+fn shape_element(
+    shape: &Shape,
+    center: f32,
+    mirrored: bool,
+    attributes: &str,
+    content: &str,
+) -> String {
+    let x = |x: f32| if mirrored { center - x } else { center + x };
+    let (tag, geometry) = match *shape {
+        Shape::Rect(left, top, width, height) => {
+            let left = if mirrored { x(left) - width } else { x(left) };
+            (
+                "rect",
+                format!(r#"x="{left}" y="{top}" width="{width}" height="{height}" rx="2""#),
+            )
+        }
+        Shape::Ellipse(cx, cy, rx, ry) => (
+            "ellipse",
+            format!(r#"cx="{}" cy="{cy}" rx="{rx}" ry="{ry}""#, x(cx)),
+        ),
+        Shape::Polygon(points) => (
+            "polygon",
+            format!(
+                r#"points="{}""#,
+                points
+                    .iter()
+                    .map(|(px, py)| format!("{},{py}", x(*px)))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        ),
+    };
+    if content.is_empty() {
+        format!("<{tag} {geometry} {attributes}/>")
+    } else {
+        format!("<{tag} {geometry} {attributes}>{content}</{tag}>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::zero(0., 20., COLOR_NO_STIMULUS)]
+    #[case::negative(-1., 20., COLOR_NO_STIMULUS)]
+    #[case::nan(f32::NAN, 20., COLOR_NO_STIMULUS)]
+    #[case::zero_cap(5., 0., COLOR_NO_STIMULUS)]
+    #[case::low(0.0001, 20., COLOR_LOW_STIMULUS)]
+    #[case::half(10., 20., (230, 120, 96))]
+    #[case::cap(20., 20., COLOR_HIGH_STIMULUS)]
+    #[case::above_cap(35., 20., COLOR_HIGH_STIMULUS)]
+    fn test_stimulus_color(
+        #[case] hard_sets: f32,
+        #[case] cap: f32,
+        #[case] expected: (u8, u8, u8),
+    ) {
+        assert_eq!(stimulus_color(hard_sets, cap), expected);
+    }
+
+    #[test]
+    fn test_svg() {
+        let result = svg(&BTreeMap::from([(11, 20.), (22, 5.)]), 20.);
+
+        assert!(result.starts_with("<svg"));
+        assert!(result.ends_with("</svg>"));
+        for muscle in domain::Muscle::iter() {
+            assert!(result.contains(&format!(r#"data-muscle="{}""#, muscle.id())));
+        }
+        assert!(result.contains(&format!(
+            r#"fill="{}" stroke="currentColor" stroke-opacity="0.3" stroke-width="0.5" data-muscle="11""#,
+            hex(COLOR_HIGH_STIMULUS)
+        )));
+        assert!(result.contains(&format!(
+            r#"fill="{}" stroke="currentColor" stroke-opacity="0.3" stroke-width="0.5" data-muscle="1""#,
+            hex(COLOR_NO_STIMULUS)
+        )));
+        assert!(result.contains("<title>Lats: 5.0</title></polygon>"));
+    }
+}
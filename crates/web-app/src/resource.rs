@@ -0,0 +1,277 @@
+//! Loading state of data collections.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+/// Error that occurred while reading a data collection from the storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadError(pub String);
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<String> for ReadError {
+    fn from(message: String) -> Self {
+        Self(message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State {
+    NotAsked,
+    Loading,
+    Loaded,
+    Failed(ReadError),
+}
+
+/// Data collection together with its loading state.
+///
+/// The data of the last successful read is kept while the collection is refreshed and after a
+/// refresh failed, so that stale data can be shown instead of a blank page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resource<T> {
+    data: T,
+    state: State,
+    /// The data has been loaded at least once since the last reset.
+    available: bool,
+}
+
+impl<T: Default> Default for Resource<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Resource<T> {
+    #[must_use]
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            state: State::NotAsked,
+            available: false,
+        }
+    }
+
+    #[must_use]
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    #[must_use]
+    pub fn is_loading(&self) -> bool {
+        self.state == State::Loading
+    }
+
+    #[must_use]
+    pub fn error(&self) -> Option<&ReadError> {
+        match &self.state {
+            State::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Check whether the data has been loaded. The data may be stale while it is refreshed or if
+    /// the last refresh failed.
+    #[must_use]
+    pub fn is_available(&self) -> bool {
+        self.available
+    }
+
+    /// Check whether the data is available and currently refreshed.
+    #[must_use]
+    pub fn is_refreshing(&self) -> bool {
+        self.available && self.is_loading()
+    }
+
+    pub fn start_loading(&mut self) {
+        self.state = State::Loading;
+    }
+
+    /// Mark the data as loaded after it has been updated in place.
+    pub fn finish_loading(&mut self) {
+        self.state = State::Loaded;
+        self.available = true;
+    }
+
+    /// Replace the data by newly loaded data.
+    pub fn set(&mut self, data: T) {
+        self.data = data;
+        self.finish_loading();
+    }
+
+    /// Mark the loading as failed. Previously loaded data is kept.
+    pub fn fail(&mut self, error: impl Into<ReadError>) {
+        self.state = State::Failed(error.into());
+    }
+
+    /// Discard the data, e.g., when the user has changed.
+    pub fn reset(&mut self)
+    where
+        T: Default,
+    {
+        *self = Self::default();
+    }
+}
+
+impl<T> Deref for Resource<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for Resource<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_resource_default() {
+        let resource = Resource::<Vec<u32>>::default();
+
+        assert_eq!(resource.state(), &State::NotAsked);
+        assert!(!resource.is_loading());
+        assert!(!resource.is_available());
+        assert!(!resource.is_refreshing());
+        assert_eq!(resource.error(), None);
+        assert!(resource.is_empty());
+    }
+
+    #[test]
+    fn test_resource_initial_load() {
+        let mut resource = Resource::<Vec<u32>>::default();
+
+        resource.start_loading();
+
+        assert_eq!(resource.state(), &State::Loading);
+        assert!(resource.is_loading());
+        assert!(!resource.is_available());
+        assert!(!resource.is_refreshing());
+
+        resource.set(vec![1, 2]);
+
+        assert_eq!(resource.state(), &State::Loaded);
+        assert!(!resource.is_loading());
+        assert!(resource.is_available());
+        assert_eq!(*resource, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_resource_initial_load_failed() {
+        let mut resource = Resource::<Vec<u32>>::default();
+
+        resource.start_loading();
+        resource.fail(String::from("no connection"));
+
+        assert_eq!(
+            resource.state(),
+            &State::Failed(ReadError(String::from("no connection")))
+        );
+        assert_eq!(
+            resource.error(),
+            Some(&ReadError(String::from("no connection")))
+        );
+        assert!(!resource.is_loading());
+        assert!(!resource.is_available());
+    }
+
+    #[test]
+    fn test_resource_retry_after_failure() {
+        let mut resource = Resource::<Vec<u32>>::default();
+
+        resource.start_loading();
+        resource.fail(String::from("no connection"));
+        resource.start_loading();
+
+        assert_eq!(resource.state(), &State::Loading);
+        assert_eq!(resource.error(), None);
+        assert!(!resource.is_available());
+
+        resource.set(vec![1]);
+
+        assert_eq!(resource.state(), &State::Loaded);
+        assert!(resource.is_available());
+    }
+
+    #[test]
+    fn test_resource_refresh_keeps_stale_data() {
+        let mut resource = Resource::<Vec<u32>>::default();
+
+        resource.start_loading();
+        resource.set(vec![1, 2]);
+        resource.start_loading();
+
+        assert_eq!(resource.state(), &State::Loading);
+        assert!(resource.is_available());
+        assert!(resource.is_refreshing());
+        assert_eq!(*resource, vec![1, 2]);
+
+        resource.set(vec![1, 2, 3]);
+
+        assert_eq!(resource.state(), &State::Loaded);
+        assert!(!resource.is_refreshing());
+        assert_eq!(*resource, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_resource_failed_refresh_keeps_stale_data() {
+        let mut resource = Resource::<Vec<u32>>::default();
+
+        resource.start_loading();
+        resource.set(vec![1, 2]);
+        resource.start_loading();
+        resource.fail(String::from("timeout"));
+
+        assert_eq!(resource.error(), Some(&ReadError(String::from("timeout"))));
+        assert!(resource.is_available());
+        assert!(!resource.is_refreshing());
+        assert_eq!(*resource, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_resource_finish_loading_after_update_in_place() {
+        let mut resource = Resource::<Vec<u32>>::default();
+
+        resource.start_loading();
+        resource.push(1);
+        resource.finish_loading();
+
+        assert_eq!(resource.state(), &State::Loaded);
+        assert!(resource.is_available());
+        assert_eq!(*resource, vec![1]);
+    }
+
+    #[test]
+    fn test_resource_reset() {
+        let mut resource = Resource::<Vec<u32>>::default();
+
+        resource.start_loading();
+        resource.set(vec![1, 2]);
+        resource.reset();
+
+        assert_eq!(resource, Resource::default());
+        assert!(!resource.is_available());
+        assert!(resource.is_empty());
+    }
+
+    #[test]
+    fn test_read_error_display() {
+        assert_eq!(
+            ReadError::from(String::from("timeout")).to_string(),
+            "timeout"
+        );
+    }
+}
@@ -0,0 +1,509 @@
+//! Exchange of routines between independent instances as self-contained JSON snippets.
+//!
+//! Exercises are referenced by name instead of ID, as the IDs differ between instances. The
+//! muscles trained by each exercise are included, so that missing exercises can be created when
+//! importing the routine. Tags are included with their colors.
+
+use std::{collections::BTreeMap, fmt};
+
+use valens_domain::{Exercise, ExerciseMuscle, RepsRange, Routine, RoutinePart, RpeRange, Tag};
+use web_sys::js_sys;
+
+/// Version of the snippet format. Snippets of other versions are rejected.
+pub const VERSION: u32 = 1;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct RoutineExport {
+    pub version: u32,
+    pub name: String,
+    pub sections: Vec<Part>,
+    pub exercises: Vec<ExportedExercise>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ExportedExercise {
+    pub name: String,
+    #[serde(default)]
+    pub muscles: Vec<ExerciseMuscle>,
+}
+
+/// Routine part with the exercise referenced by name.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Part {
+    Section {
+        rounds: u32,
+        parts: Vec<Part>,
+    },
+    Activity {
+        exercise: Option<String>,
+        reps: RepsRange,
+        time: u32,
+        weight: f32,
+        rpe: RpeRange,
+        automatic: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        target_percent_1rm: Option<f32>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    UnknownExercise(u32),
+    InvalidFormat(String),
+    UnsupportedVersion(u32),
+    EmptyName,
+    UndefinedExercise(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownExercise(id) => write!(f, "Exercise #{id} does not exist"),
+            Error::InvalidFormat(message) => write!(f, "Invalid routine: {message}"),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "Unsupported version {version} (expected {VERSION})")
+            }
+            Error::EmptyName => write!(f, "Names must not be empty"),
+            Error::UndefinedExercise(name) => write!(f, "Exercise \"{name}\" is not defined"),
+        }
+    }
+}
+
+/// Assignment of the exercises of an imported routine to the existing exercises.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportPlan {
+    /// Existing exercise IDs by exported exercise name.
+    pub matched: BTreeMap<String, u32>,
+    /// Exercises without an existing counterpart.
+    pub missing: Vec<ExportedExercise>,
+}
+
+impl RoutineExport {
+    /// Create a snippet of the routine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the routine references an unknown exercise.
+    pub fn new(routine: &Routine, exercises: &BTreeMap<u32, Exercise>) -> Result<Self, Error> {
+        let mut exercise_ids = vec![];
+        let sections = to_parts(&routine.sections, exercises, &mut exercise_ids)?;
+        Ok(RoutineExport {
+            version: VERSION,
+            name: routine.name.clone(),
+            sections,
+            exercises: exercise_ids
+                .iter()
+                .map(|id| {
+                    let exercise = &exercises[id];
+                    ExportedExercise {
+                        name: exercise.name.clone(),
+                        muscles: exercise.muscles.clone(),
+                    }
+                })
+                .collect(),
+            tags: routine.tags.clone(),
+        })
+    }
+
+    /// Parse and validate a snippet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snippet is malformed, has an unsupported version, contains empty
+    /// names or references exercises which are not defined in the snippet.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let export = serde_json::from_str::<RoutineExport>(json)
+            .map_err(|err| Error::InvalidFormat(err.to_string()))?;
+        if export.version != VERSION {
+            return Err(Error::UnsupportedVersion(export.version));
+        }
+        if export.name.trim().is_empty()
+            || export.exercises.iter().any(|e| e.name.trim().is_empty())
+            || export.tags.iter().any(|t| t.name.trim().is_empty())
+        {
+            return Err(Error::EmptyName);
+        }
+        let mut referenced = vec![];
+        referenced_exercises(&export.sections, &mut referenced);
+        if let Some(name) = referenced
+            .into_iter()
+            .find(|name| export.exercises.iter().all(|e| e.name != **name))
+        {
+            return Err(Error::UndefinedExercise(name.clone()));
+        }
+        Ok(export)
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Match the exported exercises against the existing exercises by name.
+    ///
+    /// Leading and trailing whitespace and the case of the names are ignored. An exact match is
+    /// preferred if several exercises differ only in case.
+    #[must_use]
+    pub fn plan(&self, exercises: &BTreeMap<u32, Exercise>) -> ImportPlan {
+        let mut plan = ImportPlan::default();
+        for exported in &self.exercises {
+            let name = exported.name.trim();
+            let existing = exercises
+                .values()
+                .find(|e| e.name.trim() == name)
+                .or_else(|| {
+                    exercises
+                        .values()
+                        .find(|e| e.name.trim().to_lowercase() == name.to_lowercase())
+                });
+            match existing {
+                Some(exercise) => {
+                    plan.matched.insert(exported.name.clone(), exercise.id);
+                }
+                None => plan.missing.push(exported.clone()),
+            }
+        }
+        plan
+    }
+
+    /// Create the sections of the routine using the given exercise IDs.
+    ///
+    /// Activities of exercises without an ID are omitted, as are sections which become empty.
+    #[must_use]
+    pub fn sections(&self, exercise_ids: &BTreeMap<String, u32>) -> Vec<RoutinePart> {
+        to_routine_parts(&self.sections, exercise_ids)
+    }
+}
+
+/// Create a data URL of the snippet, which can be used for downloading the snippet as file.
+#[must_use]
+pub fn data_url(json: &str) -> String {
+    format!(
+        "data:application/json;charset=utf-8,{}",
+        js_sys::encode_uri_component(json)
+    )
+}
+
+fn to_parts(
+    parts: &[RoutinePart],
+    exercises: &BTreeMap<u32, Exercise>,
+    exercise_ids: &mut Vec<u32>,
+) -> Result<Vec<Part>, Error> {
+    parts
+        .iter()
+        .map(|part| match part {
+            RoutinePart::RoutineSection { rounds, parts } => Ok(Part::Section {
+                rounds: *rounds,
+                parts: to_parts(parts, exercises, exercise_ids)?,
+            }),
+            RoutinePart::RoutineActivity {
+                exercise_id,
+                reps,
+                time,
+                weight,
+                rpe,
+                automatic,
+                target_percent_1rm,
+            } => {
+                let exercise = match exercise_id {
+                    Some(id) => {
+                        let exercise = exercises.get(id).ok_or(Error::UnknownExercise(*id))?;
+                        if !exercise_ids.contains(id) {
+                            exercise_ids.push(*id);
+                        }
+                        Some(exercise.name.clone())
+                    }
+                    None => None,
+                };
+                Ok(Part::Activity {
+                    exercise,
+                    reps: *reps,
+                    time: *time,
+                    weight: *weight,
+                    rpe: *rpe,
+                    automatic: *automatic,
+                    target_percent_1rm: *target_percent_1rm,
+                })
+            }
+        })
+        .collect()
+}
+
+fn referenced_exercises<'a>(parts: &'a [Part], result: &mut Vec<&'a String>) {
+    for part in parts {
+        match part {
+            Part::Section { parts, .. } => referenced_exercises(parts, result),
+            Part::Activity {
+                exercise: Some(name),
+                ..
+            } => result.push(name),
+            Part::Activity { exercise: None, .. } => {}
+        }
+    }
+}
+
+fn to_routine_parts(parts: &[Part], exercise_ids: &BTreeMap<String, u32>) -> Vec<RoutinePart> {
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Section { rounds, parts } => {
+                let parts = to_routine_parts(parts, exercise_ids);
+                if parts.is_empty() {
+                    None
+                } else {
+                    Some(RoutinePart::RoutineSection {
+                        rounds: *rounds,
+                        parts,
+                    })
+                }
+            }
+            Part::Activity {
+                exercise,
+                reps,
+                time,
+                weight,
+                rpe,
+                automatic,
+                target_percent_1rm,
+            } => {
+                let exercise_id = match exercise {
+                    Some(name) => Some(*exercise_ids.get(name)?),
+                    None => None,
+                };
+                Some(RoutinePart::RoutineActivity {
+                    exercise_id,
+                    reps: *reps,
+                    time: *time,
+                    weight: *weight,
+                    rpe: *rpe,
+                    automatic: *automatic,
+                    target_percent_1rm: *target_percent_1rm,
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use valens_domain::TagColor;
+
+    use super::*;
+
+    fn exercise(id: u32, name: &str) -> Exercise {
+        Exercise {
+            id,
+            name: name.to_string(),
+            muscles: vec![ExerciseMuscle {
+                muscle_id: 11,
+                stimulus: 100,
+            }],
+            weight_convention: None,
+            base_weight: None,
+            laterality: valens_domain::Laterality::Bilateral,
+            archived: false,
+            image: None,
+        }
+    }
+
+    fn activity(exercise_id: Option<u32>, reps: u32, time: u32) -> RoutinePart {
+        RoutinePart::RoutineActivity {
+            exercise_id,
+            reps: if exercise_id.is_some() && reps > 0 {
+                RepsRange::new(reps, reps + 2)
+            } else {
+                RepsRange::single(reps)
+            },
+            time,
+            weight: if exercise_id.is_some() { 20.0 } else { 0.0 },
+            rpe: if exercise_id.is_some() {
+                RpeRange::new(7.0, 8.0)
+            } else {
+                RpeRange::default()
+            },
+            automatic: exercise_id.is_none(),
+            target_percent_1rm: None,
+        }
+    }
+
+    fn sections(squat: u32, bench_press: u32, plank: u32) -> Vec<RoutinePart> {
+        vec![
+            RoutinePart::RoutineSection {
+                rounds: 3,
+                parts: vec![
+                    activity(Some(squat), 10, 0),
+                    activity(None, 0, 60),
+                    RoutinePart::RoutineSection {
+                        rounds: 2,
+                        parts: vec![
+                            activity(Some(bench_press), 8, 0),
+                            activity(Some(squat), 5, 0),
+                        ],
+                    },
+                ],
+            },
+            activity(None, 0, 120),
+            RoutinePart::RoutineSection {
+                rounds: 1,
+                parts: vec![activity(Some(plank), 0, 30), activity(None, 0, 30)],
+            },
+        ]
+    }
+
+    fn routine() -> Routine {
+        Routine {
+            id: 1,
+            name: String::from("Full Body"),
+            notes: None,
+            archived: false,
+            sections: sections(2, 3, 4),
+            schedule: vec![],
+            tags: vec![Tag {
+                name: String::from("Full body"),
+                color: TagColor::Blue,
+            }],
+        }
+    }
+
+    fn exercises() -> BTreeMap<u32, Exercise> {
+        BTreeMap::from([
+            (2, exercise(2, "Squat")),
+            (3, exercise(3, "Bench Press")),
+            (4, exercise(4, "Plank")),
+        ])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let export = RoutineExport::new(&routine(), &exercises()).unwrap();
+        let imported = RoutineExport::from_json(&export.to_json()).unwrap();
+
+        assert_eq!(imported, export);
+        assert_eq!(imported.tags, routine().tags);
+        assert_eq!(
+            imported
+                .exercises
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Squat", "Bench Press", "Plank"]
+        );
+
+        let other_exercises = BTreeMap::from([
+            (12, exercise(12, "Squat")),
+            (13, exercise(13, "bench press")),
+            (14, exercise(14, "Plank")),
+        ]);
+        let plan = imported.plan(&other_exercises);
+
+        assert!(plan.missing.is_empty());
+        assert_eq!(imported.sections(&plan.matched), sections(12, 13, 14));
+    }
+
+    #[test]
+    fn test_new_unknown_exercise() {
+        let mut exercises = exercises();
+        exercises.remove(&3);
+
+        assert_eq!(
+            RoutineExport::new(&routine(), &exercises),
+            Err(Error::UnknownExercise(3))
+        );
+    }
+
+    #[test]
+    fn test_plan() {
+        let export = RoutineExport::new(&routine(), &exercises()).unwrap();
+        let existing = BTreeMap::from([
+            (5, exercise(5, "squat")),
+            (6, exercise(6, " Squat ")),
+            (7, exercise(7, "Deadlift")),
+        ]);
+
+        let plan = export.plan(&existing);
+
+        assert_eq!(plan.matched, BTreeMap::from([(String::from("Squat"), 6)]));
+        assert_eq!(
+            plan.missing
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Bench Press", "Plank"]
+        );
+    }
+
+    #[test]
+    fn test_sections_omit_unmatched_exercises() {
+        let export = RoutineExport::new(&routine(), &exercises()).unwrap();
+
+        assert_eq!(
+            export.sections(&BTreeMap::from([(String::from("Squat"), 2)])),
+            vec![
+                RoutinePart::RoutineSection {
+                    rounds: 3,
+                    parts: vec![
+                        activity(Some(2), 10, 0),
+                        activity(None, 0, 60),
+                        RoutinePart::RoutineSection {
+                            rounds: 2,
+                            parts: vec![activity(Some(2), 5, 0)],
+                        },
+                    ],
+                },
+                activity(None, 0, 120),
+                RoutinePart::RoutineSection {
+                    rounds: 1,
+                    parts: vec![activity(None, 0, 30)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json_without_tags() {
+        let export = RoutineExport::from_json(
+            r#"{"version": 1, "name": "A", "sections": [], "exercises": []}"#,
+        )
+        .unwrap();
+
+        assert!(export.tags.is_empty());
+        assert!(!export.to_json().contains("tags"));
+    }
+
+    #[rstest]
+    #[case::invalid_json("{", "Invalid routine")]
+    #[case::unsupported_version(
+        r#"{"version": 2, "name": "A", "sections": [], "exercises": []}"#,
+        "Unsupported version 2 (expected 1)"
+    )]
+    #[case::empty_routine_name(
+        r#"{"version": 1, "name": " ", "sections": [], "exercises": []}"#,
+        "Names must not be empty"
+    )]
+    #[case::empty_exercise_name(
+        r#"{"version": 1, "name": "A", "sections": [], "exercises": [{"name": ""}]}"#,
+        "Names must not be empty"
+    )]
+    #[case::empty_tag_name(
+        r#"{"version": 1, "name": "A", "sections": [], "exercises": [],
+            "tags": [{"name": "", "color": "Red"}]}"#,
+        "Names must not be empty"
+    )]
+    #[case::undefined_exercise(
+        r#"{"version": 1, "name": "A", "exercises": [], "sections": [
+            {"exercise": "Squat", "reps": 5, "time": 0, "weight": 0.0, "rpe": 8.0, "automatic": false}
+        ]}"#,
+        "Exercise \"Squat\" is not defined"
+    )]
+    fn test_from_json_invalid(#[case] json: &str, #[case] expected: &str) {
+        assert!(RoutineExport::from_json(json)
+            .unwrap_err()
+            .to_string()
+            .starts_with(expected));
+    }
+}
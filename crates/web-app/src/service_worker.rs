@@ -1,18 +1,112 @@
 use std::collections::HashMap;
 
+use wasm_bindgen::{closure::Closure, JsCast};
 use web_sys;
 
 #[derive(serde::Serialize)]
 #[serde(tag = "task", content = "content")]
 pub enum Message {
-    UpdateCache,
+    /// Replace the cached app assets by the assets of the given version.
+    UpdateCache(String),
+    /// Activate a waiting service worker.
+    SkipWaiting,
     ShowNotification {
         title: String,
         options: HashMap<String, String>,
+        #[serde(serialize_with = "serialize_notification_actions")]
+        actions: Vec<NotificationAction>,
+        /// Training session to which the notification belongs.
+        training_session_id: u32,
+        /// Number of elements of the training session.
+        element_count: usize,
     },
     CloseNotifications,
 }
 
+/// Message sent from the service worker to the app.
+#[derive(serde::Deserialize)]
+#[serde(tag = "task", content = "content")]
+enum ClientMessage {
+    NotificationAction(SelectedNotificationAction),
+    CacheUpdated(String),
+}
+
+/// URL query parameters by which the service worker passes a notification action to the app, if
+/// the app has to be opened to handle the action.
+pub const NOTIFICATION_ACTION_PARAMETER: &str = "notification_action";
+pub const NOTIFICATION_TRAINING_SESSION_PARAMETER: &str = "notification_training_session";
+pub const NOTIFICATION_ELEMENT_COUNT_PARAMETER: &str = "notification_element_count";
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    GoToNextSection,
+    ExtendTimer,
+}
+
+impl NotificationAction {
+    /// Parse the action from its serialized name.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "GoToNextSection" => Some(NotificationAction::GoToNextSection),
+            "ExtendTimer" => Some(NotificationAction::ExtendTimer),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn title(self) -> &'static str {
+        match self {
+            NotificationAction::GoToNextSection => "Next section",
+            NotificationAction::ExtendTimer => "+30 s",
+        }
+    }
+}
+
+/// Action selected in a notification together with the state of the training session at the
+/// time the notification was shown.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedNotificationAction {
+    pub action: NotificationAction,
+    pub training_session_id: u32,
+    pub element_count: usize,
+}
+
+impl SelectedNotificationAction {
+    /// Parse the action from the URL query parameters set by the service worker.
+    #[must_use]
+    pub fn from_parameters<'a>(parameter: impl Fn(&str) -> Option<&'a str>) -> Option<Self> {
+        Some(Self {
+            action: NotificationAction::from_name(parameter(NOTIFICATION_ACTION_PARAMETER)?)?,
+            training_session_id: parameter(NOTIFICATION_TRAINING_SESSION_PARAMETER)?
+                .parse()
+                .ok()?,
+            element_count: parameter(NOTIFICATION_ELEMENT_COUNT_PARAMETER)?
+                .parse()
+                .ok()?,
+        })
+    }
+}
+
+fn serialize_notification_actions<S>(
+    actions: &[NotificationAction],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    #[derive(serde::Serialize)]
+    struct Action {
+        action: NotificationAction,
+        title: &'static str,
+    }
+
+    serializer.collect_seq(actions.iter().map(|a| Action {
+        action: *a,
+        title: a.title(),
+    }))
+}
+
 #[allow(clippy::missing_errors_doc)]
 pub fn post(message: &Message) -> Result<(), String> {
     let Some(window) = web_sys::window() else {
@@ -33,3 +127,128 @@ pub fn post(message: &Message) -> Result<(), String> {
         )),
     }
 }
+
+/// Register a handler for notification actions forwarded by the service worker.
+#[allow(clippy::missing_errors_doc)]
+pub fn on_notification_action(
+    handler: impl Fn(SelectedNotificationAction) + 'static,
+) -> Result<(), String> {
+    add_message_listener(move |message| {
+        if let ClientMessage::NotificationAction(action) = message {
+            handler(action);
+        }
+    })
+}
+
+/// Register a handler that is called with the version of the app assets after the service
+/// worker has completely replaced the cached assets.
+#[allow(clippy::missing_errors_doc)]
+pub fn on_cache_updated(handler: impl Fn(String) + 'static) -> Result<(), String> {
+    add_message_listener(move |message| {
+        if let ClientMessage::CacheUpdated(version) = message {
+            handler(version);
+        }
+    })
+}
+
+fn add_message_listener(handler: impl Fn(ClientMessage) + 'static) -> Result<(), String> {
+    let Some(window) = web_sys::window() else {
+        return Err("failed to get window".to_string());
+    };
+    let callback =
+        Closure::<dyn Fn(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+            if let Ok(message) = serde_wasm_bindgen::from_value(event.data()) {
+                handler(message);
+            }
+        });
+    let result = window
+        .navigator()
+        .service_worker()
+        .add_event_listener_with_callback("message", callback.as_ref().unchecked_ref());
+    callback.forget();
+    result.map_err(|err| format!("failed to add service worker message listener: {err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_message_update_cache() {
+        assert_eq!(
+            serde_json::to_value(Message::UpdateCache(String::from("1.2.3"))).unwrap(),
+            json!({"task": "UpdateCache", "content": "1.2.3"})
+        );
+    }
+
+    #[test]
+    fn test_notification_action_from_name() {
+        for action in [
+            NotificationAction::GoToNextSection,
+            NotificationAction::ExtendTimer,
+        ] {
+            assert_eq!(
+                NotificationAction::from_name(
+                    serde_json::to_value(action).unwrap().as_str().unwrap()
+                ),
+                Some(action)
+            );
+        }
+        assert_eq!(NotificationAction::from_name("Unknown"), None);
+    }
+
+    #[test]
+    fn test_selected_notification_action_from_parameters() {
+        let parameters = HashMap::from([
+            (NOTIFICATION_ACTION_PARAMETER, "ExtendTimer"),
+            (NOTIFICATION_TRAINING_SESSION_PARAMETER, "1"),
+            (NOTIFICATION_ELEMENT_COUNT_PARAMETER, "3"),
+        ]);
+        assert_eq!(
+            SelectedNotificationAction::from_parameters(|name| parameters.get(name).copied()),
+            Some(SelectedNotificationAction {
+                action: NotificationAction::ExtendTimer,
+                training_session_id: 1,
+                element_count: 3
+            })
+        );
+        assert_eq!(
+            SelectedNotificationAction::from_parameters(|name| {
+                (name == NOTIFICATION_ACTION_PARAMETER).then_some("ExtendTimer")
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_client_message_notification_action() {
+        assert!(matches!(
+            serde_json::from_value(json!({
+                "task": "NotificationAction",
+                "content": {
+                    "action": "GoToNextSection",
+                    "training_session_id": 1,
+                    "element_count": 3
+                }
+            })),
+            Ok(ClientMessage::NotificationAction(
+                SelectedNotificationAction {
+                    action: NotificationAction::GoToNextSection,
+                    training_session_id: 1,
+                    element_count: 3
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_client_message_cache_updated() {
+        assert!(matches!(
+            serde_json::from_value(json!({"task": "CacheUpdated", "content": "1.2.3"})),
+            Ok(ClientMessage::CacheUpdated(version)) if version == "1.2.3"
+        ));
+    }
+}
@@ -0,0 +1,415 @@
+//! Sharing of training sessions as plain text.
+
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use valens_domain::{Exercise, Muscle, TrainingSession, TrainingSessionElement};
+use wasm_bindgen::JsValue;
+use web_sys::js_sys;
+
+/// Format the training session as compact plain text.
+///
+/// Consecutive sets without a rest in between are considered as superset and are put on the same
+/// line. Consecutive sets or supersets of the same exercises are combined.
+#[must_use]
+pub fn format_training_session(
+    training_session: &TrainingSession,
+    exercises: &BTreeMap<u32, Exercise>,
+    show_rpe: bool,
+    show_tut: bool,
+) -> String {
+    let mut lines = vec![training_session.date.to_string()];
+
+    let blocks = blocks(training_session, show_rpe, show_tut);
+    if !blocks.is_empty() {
+        lines.push(String::new());
+        for (exercise_ids, sets) in blocks {
+            let names = exercise_ids
+                .iter()
+                .map(|id| {
+                    exercises
+                        .get(id)
+                        .map_or("Unknown exercise", |e| e.name.as_str())
+                })
+                .collect::<Vec<_>>()
+                .join(" + ");
+            lines.push(format!("{names}: {}", sets.join(", ")));
+        }
+    }
+
+    let mut stimulus_per_muscle = training_session
+        .stimulus_per_muscle(exercises)
+        .into_iter()
+        .filter(|(_, stimulus)| *stimulus > 0)
+        .filter_map(|(id, stimulus)| Muscle::from_repr(id).map(|muscle| (muscle, stimulus)))
+        .collect::<Vec<_>>();
+    if !stimulus_per_muscle.is_empty() {
+        stimulus_per_muscle.sort_by_key(|(_, stimulus)| Reverse(*stimulus));
+        lines.push(String::new());
+        lines.push(format!(
+            "Hard sets per muscle: {}",
+            stimulus_per_muscle
+                .iter()
+                .map(|(muscle, stimulus)| {
+                    let sets = f64::from(*stimulus) / 100.0;
+                    format!(
+                        "{} {:.2$}",
+                        muscle.name(),
+                        sets,
+                        usize::from(sets.fract() != 0.0)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    if let Some(notes) = &training_session.notes {
+        if !notes.trim().is_empty() {
+            lines.push(String::new());
+            lines.push(format!("Notes: {}", notes.trim()));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Group the sets into blocks of the same exercises.
+fn blocks(
+    training_session: &TrainingSession,
+    show_rpe: bool,
+    show_tut: bool,
+) -> Vec<(Vec<u32>, Vec<String>)> {
+    let mut groups: Vec<Vec<(u32, String)>> = vec![];
+    let mut new_group = true;
+
+    for element in &training_session.elements {
+        match element {
+            TrainingSessionElement::Set {
+                exercise_id,
+                reps,
+                time,
+                weight,
+                rpe,
+                partial_reps,
+                to_failure,
+                ..
+            } => {
+                let set = crate::format_set(
+                    reps.map(valens_domain::RepsRange::single),
+                    *partial_reps,
+                    *time,
+                    show_tut,
+                    *weight,
+                    rpe.map(valens_domain::RpeRange::single),
+                    show_rpe,
+                    *to_failure,
+                );
+                let set = if set.is_empty() {
+                    String::from("–")
+                } else {
+                    set
+                };
+                if new_group {
+                    groups.push(vec![]);
+                    new_group = false;
+                }
+                if let Some(group) = groups.last_mut() {
+                    group.push((*exercise_id, set));
+                }
+            }
+            TrainingSessionElement::Rest { .. } => {
+                new_group = true;
+            }
+        }
+    }
+
+    let mut blocks: Vec<(Vec<u32>, Vec<String>)> = vec![];
+
+    for group in groups {
+        let exercise_ids = group.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        let sets = group
+            .into_iter()
+            .map(|(_, set)| set)
+            .collect::<Vec<_>>()
+            .join(" + ");
+        match blocks.last_mut() {
+            Some((ids, block_sets)) if *ids == exercise_ids => block_sets.push(sets),
+            _ => blocks.push((exercise_ids, vec![sets])),
+        }
+    }
+
+    blocks
+}
+
+/// Copy the text to the clipboard using the Clipboard API.
+///
+/// # Errors
+///
+/// Returns an error if the window is not available.
+pub fn copy_to_clipboard(text: &str) -> Result<js_sys::Promise, String> {
+    let window = web_sys::window().ok_or("no window")?;
+    Ok(window.navigator().clipboard().write_text(text))
+}
+
+/// Check whether sharing via the Web Share API is supported by the browser.
+#[must_use]
+pub fn share_supported() -> bool {
+    web_sys::window().is_some_and(|window| {
+        js_sys::Reflect::has(&window.navigator(), &JsValue::from("share")).unwrap_or(false)
+    })
+}
+
+/// Share the text using the Web Share API.
+///
+/// # Errors
+///
+/// Returns an error if the window is not available or sharing is not supported.
+pub fn share(title: &str, text: &str) -> Result<js_sys::Promise, String> {
+    if !share_supported() {
+        return Err("sharing not supported".into());
+    }
+    let window = web_sys::window().ok_or("no window")?;
+    let data = web_sys::ShareData::new();
+    data.set_title(title);
+    data.set_text(text);
+    Ok(window.navigator().share_with_data(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use valens_domain::ExerciseMuscle;
+
+    use super::*;
+
+    fn exercises() -> BTreeMap<u32, Exercise> {
+        BTreeMap::from([
+            (
+                1,
+                Exercise {
+                    id: 1,
+                    name: String::from("Squat"),
+                    weight_convention: None,
+                    base_weight: None,
+                    laterality: valens_domain::Laterality::Bilateral,
+                    archived: false,
+                    image: None,
+                    muscles: vec![
+                        ExerciseMuscle {
+                            muscle_id: Muscle::Quads.id(),
+                            stimulus: 100,
+                        },
+                        ExerciseMuscle {
+                            muscle_id: Muscle::Glutes.id(),
+                            stimulus: 50,
+                        },
+                    ],
+                },
+            ),
+            (
+                2,
+                Exercise {
+                    id: 2,
+                    name: String::from("Bench Press"),
+                    weight_convention: None,
+                    base_weight: None,
+                    laterality: valens_domain::Laterality::Bilateral,
+                    archived: false,
+                    image: None,
+                    muscles: vec![ExerciseMuscle {
+                        muscle_id: Muscle::Pecs.id(),
+                        stimulus: 100,
+                    }],
+                },
+            ),
+            (
+                3,
+                Exercise {
+                    id: 3,
+                    name: String::from("Rows"),
+                    weight_convention: None,
+                    base_weight: None,
+                    laterality: valens_domain::Laterality::Bilateral,
+                    archived: false,
+                    image: None,
+                    muscles: vec![],
+                },
+            ),
+        ])
+    }
+
+    fn set(
+        exercise_id: u32,
+        reps: Option<u32>,
+        time: Option<u32>,
+        weight: Option<f32>,
+        rpe: Option<f32>,
+    ) -> TrainingSessionElement {
+        TrainingSessionElement::Set {
+            exercise_id,
+            reps,
+            time,
+            weight,
+            rpe,
+            target_reps: None,
+            target_time: None,
+            target_weight: None,
+            target_rpe: None,
+            automatic: false,
+            partial_reps: None,
+            to_failure: false,
+            side: None,
+            comment: None,
+        }
+    }
+
+    fn rest() -> TrainingSessionElement {
+        TrainingSessionElement::Rest {
+            target_time: Some(60),
+            automatic: true,
+        }
+    }
+
+    fn training_session(
+        notes: Option<&str>,
+        elements: Vec<TrainingSessionElement>,
+    ) -> TrainingSession {
+        TrainingSession {
+            id: 1,
+            routine_id: None,
+            date: NaiveDate::from_ymd_opt(2024, 2, 3).unwrap(),
+            notes: notes.map(String::from),
+            start_time: None,
+            end_time: None,
+            elements,
+        }
+    }
+
+    #[test]
+    fn test_format_training_session_empty() {
+        assert_eq!(
+            format_training_session(&training_session(None, vec![]), &exercises(), true, true),
+            "2024-02-03"
+        );
+    }
+
+    #[rstest]
+    #[case::all_values(true, true, "Squat: 5 × 3 s × 100 kg @ 8, 5 × 3 s × 100 kg @ 9")]
+    #[case::hidden_values(false, false, "Squat: 5 × 100 kg, 5 × 100 kg")]
+    fn test_format_training_session_sets(
+        #[case] show_rpe: bool,
+        #[case] show_tut: bool,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            format_training_session(
+                &training_session(
+                    None,
+                    vec![
+                        set(1, Some(5), Some(3), Some(100.0), Some(8.0)),
+                        rest(),
+                        set(1, Some(5), Some(3), Some(100.0), Some(9.0)),
+                        rest(),
+                    ]
+                ),
+                &exercises(),
+                show_rpe,
+                show_tut
+            ),
+            format!("2024-02-03\n\n{expected}\n\nHard sets per muscle: Quads 2, Glutes 1")
+        );
+    }
+
+    #[test]
+    fn test_format_training_session_supersets() {
+        assert_eq!(
+            format_training_session(
+                &training_session(
+                    None,
+                    vec![
+                        set(2, Some(10), None, Some(60.0), None),
+                        set(3, Some(12), None, Some(40.0), None),
+                        rest(),
+                        set(2, Some(8), None, Some(60.0), None),
+                        set(3, Some(12), None, Some(40.0), None),
+                        rest(),
+                        set(2, Some(6), None, Some(60.0), None),
+                        rest(),
+                        set(1, None, None, None, None),
+                        set(4, Some(20), None, None, None),
+                    ]
+                ),
+                &exercises(),
+                true,
+                true
+            ),
+            "2024-02-03\n\
+             \n\
+             Bench Press + Rows: 10 × 60 kg + 12 × 40 kg, 8 × 60 kg + 12 × 40 kg\n\
+             Bench Press: 6 × 60 kg\n\
+             Squat + Unknown exercise: – + 20\n\
+             \n\
+             Hard sets per muscle: Pecs 3"
+        );
+    }
+
+    #[test]
+    fn test_format_training_session_rest_elements() {
+        assert_eq!(
+            format_training_session(
+                &training_session(
+                    None,
+                    vec![
+                        rest(),
+                        set(1, Some(5), None, Some(100.0), None),
+                        rest(),
+                        rest(),
+                        set(1, Some(3), None, Some(100.0), None),
+                        rest(),
+                        set(2, Some(10), None, Some(60.0), Some(6.0)),
+                        rest(),
+                    ]
+                ),
+                &exercises(),
+                true,
+                true
+            ),
+            "2024-02-03\n\
+             \n\
+             Squat: 5 × 100 kg, 3 × 100 kg\n\
+             Bench Press: 10 × 60 kg @ 6\n\
+             \n\
+             Hard sets per muscle: Quads 2, Glutes 1"
+        );
+    }
+
+    #[test]
+    fn test_format_training_session_notes() {
+        assert_eq!(
+            format_training_session(
+                &training_session(
+                    Some(" Felt strong. \n"),
+                    vec![
+                        set(1, Some(5), None, Some(100.0), None),
+                        rest(),
+                        set(1, Some(5), None, Some(102.5), None),
+                        rest(),
+                        set(1, Some(5), None, Some(105.0), None),
+                    ]
+                ),
+                &exercises(),
+                true,
+                true
+            ),
+            "2024-02-03\n\
+             \n\
+             Squat: 5 × 100 kg, 5 × 102.5 kg, 5 × 105 kg\n\
+             \n\
+             Hard sets per muscle: Quads 3, Glutes 1.5\n\
+             \n\
+             Notes: Felt strong."
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Synthesis of beeps using the Web Audio API.
+
+use wasm_bindgen::JsValue;
+
+/// Setting for the sound of beeps.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SoundProfile {
+    #[default]
+    Classic,
+    Soft,
+    LoudClick,
+}
+
+impl SoundProfile {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            SoundProfile::Classic => "Classic",
+            SoundProfile::Soft => "Soft",
+            SoundProfile::LoudClick => "Loud click",
+        }
+    }
+
+    /// Tone which is played for the beep.
+    #[must_use]
+    pub fn tone(self, beep: Beep) -> Tone {
+        match self {
+            SoundProfile::Classic => Tone {
+                waveform: Waveform::Sine,
+                frequency: match beep {
+                    Beep::Beat => 500.,
+                    Beep::StressedBeat => 1000.,
+                    Beep::Warning | Beep::Countdown | Beep::Expiry => 2000.,
+                },
+                duration: match beep {
+                    Beep::Beat | Beep::StressedBeat => 0.05,
+                    Beep::Warning => 0.1,
+                    Beep::Countdown => 0.15,
+                    Beep::Expiry => 0.5,
+                },
+                attack: 0.,
+                release: 0.,
+                gain: 1.,
+            },
+            SoundProfile::Soft => Tone {
+                waveform: Waveform::Triangle,
+                frequency: match beep {
+                    Beep::Beat => 440.,
+                    Beep::StressedBeat => 660.,
+                    Beep::Warning | Beep::Countdown | Beep::Expiry => 880.,
+                },
+                duration: match beep {
+                    Beep::Beat | Beep::StressedBeat => 0.08,
+                    Beep::Warning => 0.12,
+                    Beep::Countdown => 0.2,
+                    Beep::Expiry => 0.6,
+                },
+                attack: 0.02,
+                release: 0.05,
+                gain: 0.6,
+            },
+            SoundProfile::LoudClick => Tone {
+                waveform: Waveform::Square,
+                frequency: match beep {
+                    Beep::Beat => 1500.,
+                    Beep::StressedBeat => 2500.,
+                    Beep::Warning | Beep::Countdown | Beep::Expiry => 3000.,
+                },
+                duration: match beep {
+                    Beep::Beat | Beep::StressedBeat => 0.02,
+                    Beep::Warning => 0.05,
+                    Beep::Countdown => 0.08,
+                    Beep::Expiry => 0.3,
+                },
+                attack: 0.,
+                release: 0.01,
+                gain: 1.,
+            },
+        }
+    }
+}
+
+/// Occasion on which a beep is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Beep {
+    Beat,
+    StressedBeat,
+    /// Timer will expire soon.
+    Warning,
+    /// Final seconds before the timer expires.
+    Countdown,
+    Expiry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Sine,
+    Triangle,
+    Square,
+}
+
+/// Parameters of a tone. All times are given in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    pub waveform: Waveform,
+    /// Frequency in Hz.
+    pub frequency: f32,
+    pub duration: f64,
+    /// Time in which the volume rises at the beginning of the tone.
+    pub attack: f64,
+    /// Time in which the volume falls at the end of the tone.
+    pub release: f64,
+    /// Factor applied to the beep volume.
+    pub gain: f32,
+}
+
+impl Tone {
+    /// Peak gain of the tone, taking the beep volume in percent into account.
+    #[must_use]
+    pub fn peak_gain(&self, volume: u8) -> f32 {
+        self.gain * f32::from(volume.min(100)) / 100.
+    }
+}
+
+/// Play the beep at the given time on the clock of the audio context.
+///
+/// # Errors
+///
+/// Returns an error if the audio nodes cannot be created or scheduled.
+pub fn play(
+    audio_context: &web_sys::AudioContext,
+    profile: SoundProfile,
+    beep: Beep,
+    start: f64,
+    volume: u8,
+) -> Result<(), JsValue> {
+    let tone = profile.tone(beep);
+    let peak_gain = tone.peak_gain(volume);
+    let end = start + tone.duration;
+    let oscillator = audio_context.create_oscillator()?;
+    let gain = audio_context.create_gain()?;
+    gain.gain().set_value_at_time(0., start)?;
+    gain.gain()
+        .linear_ramp_to_value_at_time(peak_gain, start + tone.attack)?;
+    gain.gain()
+        .set_value_at_time(peak_gain, end - tone.release)?;
+    gain.gain().linear_ramp_to_value_at_time(0., end)?;
+    gain.connect_with_audio_node(&audio_context.destination())?;
+    oscillator.connect_with_audio_node(&gain)?;
+    oscillator.set_type(match tone.waveform {
+        Waveform::Sine => web_sys::OscillatorType::Sine,
+        Waveform::Triangle => web_sys::OscillatorType::Triangle,
+        Waveform::Square => web_sys::OscillatorType::Square,
+    });
+    oscillator.frequency().set_value(tone.frequency);
+    oscillator.start_with_when(start)?;
+    oscillator.stop_with_when(end)?;
+    Ok(())
+}
+
+/// Play a short sequence of beeps to demonstrate the sound profile.
+///
+/// # Errors
+///
+/// Returns an error if the beeps cannot be played.
+pub fn play_preview(
+    audio_context: &web_sys::AudioContext,
+    profile: SoundProfile,
+    volume: u8,
+) -> Result<(), JsValue> {
+    let start = audio_context.current_time() + 0.05;
+    for (offset, beep) in [
+        (0.0, Beep::StressedBeat),
+        (0.5, Beep::Beat),
+        (1.0, Beep::Countdown),
+        (2.0, Beep::Expiry),
+    ] {
+        play(audio_context, profile, beep, start + offset, volume)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    const PROFILES: [SoundProfile; 3] = [
+        SoundProfile::Classic,
+        SoundProfile::Soft,
+        SoundProfile::LoudClick,
+    ];
+    const BEEPS: [Beep; 5] = [
+        Beep::Beat,
+        Beep::StressedBeat,
+        Beep::Warning,
+        Beep::Countdown,
+        Beep::Expiry,
+    ];
+
+    #[test]
+    fn test_sound_profile_tone_envelope() {
+        for profile in PROFILES {
+            for beep in BEEPS {
+                let tone = profile.tone(beep);
+                assert!(tone.frequency > 0.);
+                assert!(tone.attack >= 0. && tone.release >= 0.);
+                assert!(tone.attack + tone.release <= tone.duration);
+                assert!(tone.gain > 0. && tone.gain <= 1.);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sound_profile_tone_stressed_beat() {
+        for profile in PROFILES {
+            assert!(
+                profile.tone(Beep::StressedBeat).frequency > profile.tone(Beep::Beat).frequency
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::muted(SoundProfile::Classic, 0, 0.)]
+    #[case::full(SoundProfile::Classic, 100, 1.)]
+    #[case::above_maximum(SoundProfile::Classic, 200, 1.)]
+    #[case::soft(SoundProfile::Soft, 50, 0.3)]
+    fn test_tone_peak_gain(
+        #[case] profile: SoundProfile,
+        #[case] volume: u8,
+        #[case] expected: f32,
+    ) {
+        assert!((profile.tone(Beep::Beat).peak_gain(volume) - expected).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_sound_profile_serde() {
+        assert_eq!(
+            serde_json::to_string(&SoundProfile::LoudClick).unwrap(),
+            "\"LoudClick\""
+        );
+        assert_eq!(
+            serde_json::from_str::<SoundProfile>("\"Soft\"").unwrap(),
+            SoundProfile::Soft
+        );
+    }
+}
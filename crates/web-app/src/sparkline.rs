@@ -0,0 +1,75 @@
+//! Minimal line chart without axes for showing a trend inline with text.
+
+use std::fmt::Write;
+
+const WIDTH: f32 = 40.;
+const HEIGHT: f32 = 12.;
+/// Space above and below the line, so that the stroke is not cut off.
+const PADDING: f32 = 1.;
+
+/// Create an SVG image of a line connecting the values from left to right.
+///
+/// `None` is returned if there are fewer than two values, as a single point does not show a
+/// trend.
+#[must_use]
+pub fn svg(values: &[f32]) -> Option<String> {
+    let points = points(values)?;
+    Some(format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {WIDTH} {HEIGHT}" width="{WIDTH}" height="{HEIGHT}" class="sparkline"><polyline points="{points}" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linejoin="round" stroke-linecap="round"/></svg>"#
+    ))
+}
+
+fn points(values: &[f32]) -> Option<String> {
+    if values.len() < 2 {
+        return None;
+    }
+
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    #[allow(clippy::cast_precision_loss)]
+    let step = WIDTH / (values.len() - 1) as f32;
+
+    let mut result = String::new();
+    for (i, value) in values.iter().enumerate() {
+        let y = if max > min {
+            HEIGHT - PADDING - (value - min) / (max - min) * (HEIGHT - 2. * PADDING)
+        } else {
+            HEIGHT / 2.
+        };
+        if i > 0 {
+            result.push(' ');
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let _ = write!(result, "{:.1},{y:.1}", i as f32 * step);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty(&[], None)]
+    #[case::single_value(&[100.0], None)]
+    #[case::two_values(&[100.0, 110.0], Some("0.0,11.0 40.0,1.0"))]
+    #[case::constant(&[100.0, 100.0, 100.0], Some("0.0,6.0 20.0,6.0 40.0,6.0"))]
+    #[case::trend(
+        &[100.0, 90.0, 95.0, 110.0, 105.0],
+        Some("0.0,6.0 10.0,11.0 20.0,8.5 30.0,1.0 40.0,3.5")
+    )]
+    fn test_points(#[case] values: &[f32], #[case] expected: Option<&str>) {
+        assert_eq!(points(values).as_deref(), expected);
+    }
+
+    #[test]
+    fn test_svg() {
+        assert_eq!(svg(&[100.0]), None);
+        assert!(svg(&[100.0, 110.0])
+            .unwrap()
+            .contains(r#"<polyline points="0.0,11.0 40.0,1.0""#));
+    }
+}
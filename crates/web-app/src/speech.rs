@@ -0,0 +1,259 @@
+//! Spoken announcements using the Web Speech API.
+
+use std::fmt::Write;
+
+use valens_domain::{RepsRange, RpeRange};
+use wasm_bindgen::JsValue;
+use web_sys::js_sys;
+
+/// Text announcing an exercise and its target values, e.g. "Bench press, 5 reps at 80 kilos".
+#[must_use]
+pub fn exercise_announcement(
+    exercise_name: &str,
+    reps: Option<RepsRange>,
+    time: Option<u32>,
+    show_tut: bool,
+    weight: Option<f32>,
+    rpe: Option<RpeRange>,
+    show_rpe: bool,
+) -> String {
+    let mut quantities = vec![];
+
+    if let Some(reps) = reps {
+        if reps.is_set() {
+            if reps.is_single() {
+                let unit = if reps.min == 1 { "rep" } else { "reps" };
+                quantities.push(format!("{} {unit}", reps.min));
+            } else {
+                quantities.push(format!("{} to {} reps", reps.min, reps.max));
+            }
+        }
+    }
+
+    if let Some(time) = time {
+        if show_tut && time > 0 {
+            quantities.push(seconds(time));
+        }
+    }
+
+    let mut result = exercise_name.to_string();
+
+    if !quantities.is_empty() {
+        result.push_str(", ");
+        result.push_str(&quantities.join(", "));
+    }
+
+    if let Some(weight) = weight {
+        if weight > 0.0 {
+            let unit = if (weight - 1.0).abs() < f32::EPSILON {
+                "kilo"
+            } else {
+                "kilos"
+            };
+            let _ = write!(result, " at {weight} {unit}");
+        }
+    }
+
+    if let Some(rpe) = rpe {
+        if show_rpe && rpe.is_set() {
+            if rpe.is_single() {
+                let _ = write!(result, ", RPE {}", rpe.min);
+            } else {
+                let _ = write!(result, ", RPE {} to {}", rpe.min, rpe.max);
+            }
+        }
+    }
+
+    result
+}
+
+/// Text announcing a rest, e.g. "Rest, 90 seconds".
+#[must_use]
+pub fn rest_announcement(target_time: u32) -> String {
+    if target_time > 0 {
+        format!("Rest, {}", seconds(target_time))
+    } else {
+        String::from("Rest")
+    }
+}
+
+fn seconds(time: u32) -> String {
+    format!("{time} {}", if time == 1 { "second" } else { "seconds" })
+}
+
+pub trait Synthesizer {
+    /// Speak the text with the given volume between 0 and 1, cancelling any previous utterances.
+    /// Return false if speech synthesis is not supported.
+    fn speak(&self, text: &str, volume: f32) -> bool;
+    /// Cancel all pending utterances.
+    fn cancel(&self);
+}
+
+/// Synthesizer using the Speech Synthesis API of the browser.
+pub struct Window;
+
+impl Window {
+    fn speech_synthesis() -> Option<web_sys::SpeechSynthesis> {
+        let window = web_sys::window()?;
+        if !js_sys::Reflect::has(&window, &JsValue::from("speechSynthesis")).unwrap_or(false) {
+            return None;
+        }
+        window.speech_synthesis().ok()
+    }
+}
+
+impl Synthesizer for Window {
+    fn speak(&self, text: &str, volume: f32) -> bool {
+        let Some(speech_synthesis) = Self::speech_synthesis() else {
+            return false;
+        };
+        let Ok(utterance) = web_sys::SpeechSynthesisUtterance::new_with_text(text) else {
+            return false;
+        };
+        utterance.set_volume(volume);
+        // Announcements of sections which were skipped quickly are outdated
+        speech_synthesis.cancel();
+        speech_synthesis.speak(&utterance);
+        true
+    }
+
+    fn cancel(&self) {
+        if let Some(speech_synthesis) = Self::speech_synthesis() {
+            speech_synthesis.cancel();
+        }
+    }
+}
+
+/// Speak the text if announcements are enabled, using the beep volume as speech volume.
+pub fn announce(synthesizer: &impl Synthesizer, enabled: bool, beep_volume: u8, text: &str) {
+    if enabled && beep_volume > 0 {
+        synthesizer.speak(text, f32::from(beep_volume.min(100)) / 100.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MockSynthesizer {
+        utterances: RefCell<Vec<(String, f32)>>,
+    }
+
+    impl Synthesizer for MockSynthesizer {
+        fn speak(&self, text: &str, volume: f32) -> bool {
+            self.utterances
+                .borrow_mut()
+                .push((text.to_string(), volume));
+            true
+        }
+
+        fn cancel(&self) {
+            self.utterances.borrow_mut().clear();
+        }
+    }
+
+    #[rstest]
+    #[case::name_only(None, None, None, None, "Bench press")]
+    #[case::reps_and_weight(Some(5), None, Some(80.0), None, "Bench press, 5 reps at 80 kilos")]
+    #[case::single_rep(Some(1), None, Some(1.0), None, "Bench press, 1 rep at 1 kilo")]
+    #[case::fractional_weight(Some(8), None, Some(82.5), None, "Bench press, 8 reps at 82.5 kilos")]
+    #[case::time(None, Some(60), None, None, "Bench press, 60 seconds")]
+    #[case::weight_only(None, None, Some(20.0), None, "Bench press at 20 kilos")]
+    #[case::all(
+        Some(5),
+        Some(4),
+        Some(80.0),
+        Some(RpeRange::single(8.0)),
+        "Bench press, 5 reps, 4 seconds at 80 kilos, RPE 8"
+    )]
+    #[case::rpe_range(
+        Some(5),
+        None,
+        None,
+        Some(RpeRange::new(7.0, 8.5)),
+        "Bench press, 5 reps, RPE 7 to 8.5"
+    )]
+    #[case::zero_values(Some(0), Some(0), Some(0.0), None, "Bench press")]
+    fn test_exercise_announcement(
+        #[case] reps: Option<u32>,
+        #[case] time: Option<u32>,
+        #[case] weight: Option<f32>,
+        #[case] rpe: Option<RpeRange>,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            exercise_announcement(
+                "Bench press",
+                reps.map(RepsRange::single),
+                time,
+                true,
+                weight,
+                rpe,
+                true
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_exercise_announcement_reps_range() {
+        assert_eq!(
+            exercise_announcement(
+                "Bench press",
+                Some(RepsRange::new(8, 12)),
+                None,
+                true,
+                Some(60.0),
+                None,
+                true
+            ),
+            "Bench press, 8 to 12 reps at 60 kilos"
+        );
+    }
+
+    #[test]
+    fn test_exercise_announcement_hidden_values() {
+        assert_eq!(
+            exercise_announcement(
+                "Plank",
+                None,
+                Some(60),
+                false,
+                None,
+                Some(RpeRange::single(8.0)),
+                false
+            ),
+            "Plank"
+        );
+    }
+
+    #[rstest]
+    #[case::without_time(0, "Rest")]
+    #[case::one_second(1, "Rest, 1 second")]
+    #[case::seconds(90, "Rest, 90 seconds")]
+    fn test_rest_announcement(#[case] target_time: u32, #[case] expected: &str) {
+        assert_eq!(rest_announcement(target_time), expected);
+    }
+
+    #[rstest]
+    #[case::disabled(false, 80, vec![])]
+    #[case::enabled(true, 80, vec![(String::from("Squat"), 0.8)])]
+    #[case::muted(true, 0, vec![])]
+    fn test_announce(
+        #[case] enabled: bool,
+        #[case] beep_volume: u8,
+        #[case] expected: Vec<(String, f32)>,
+    ) {
+        let synthesizer = MockSynthesizer::default();
+
+        announce(&synthesizer, enabled, beep_volume, "Squat");
+
+        assert_eq!(synthesizer.utterances.into_inner(), expected);
+    }
+}
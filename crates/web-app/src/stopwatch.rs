@@ -0,0 +1,164 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// Count-up timer with millisecond resolution.
+///
+/// The elapsed time is only updated when calling `update`, which allows showing a consistent time
+/// between updates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stopwatch {
+    /// Elapsed time in milliseconds.
+    time: i64,
+    start_time: Option<DateTime<Utc>>,
+}
+
+impl Stopwatch {
+    /// Elapsed time in milliseconds.
+    #[must_use]
+    pub fn time(&self) -> i64 {
+        self.time
+    }
+
+    /// Elapsed time rounded to whole seconds.
+    #[must_use]
+    pub fn seconds(&self) -> u32 {
+        u32::try_from((self.time + 500) / 1000).unwrap_or(0)
+    }
+
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.start_time.is_some()
+    }
+
+    /// Reset a paused stopwatch with an elapsed time or otherwise start or pause it.
+    pub fn toggle(&mut self, now: DateTime<Utc>) {
+        if !self.is_active() && self.time > 0 {
+            self.reset(now);
+        } else {
+            self.start_pause(now);
+        }
+    }
+
+    pub fn start_pause(&mut self, now: DateTime<Utc>) {
+        if self.is_active() {
+            self.pause(now);
+        } else {
+            self.start(now);
+        }
+    }
+
+    /// Start the stopwatch, continuing from the elapsed time.
+    pub fn start(&mut self, now: DateTime<Utc>) {
+        if !self.is_active() {
+            self.start_time = Some(now - Duration::milliseconds(self.time));
+        }
+    }
+
+    /// Pause the stopwatch, keeping the elapsed time up to now.
+    pub fn pause(&mut self, now: DateTime<Utc>) {
+        self.update(now);
+        self.start_time = None;
+    }
+
+    pub fn reset(&mut self, now: DateTime<Utc>) {
+        self.time = 0;
+        if self.start_time.is_some() {
+            self.start_time = Some(now);
+        }
+    }
+
+    pub fn update(&mut self, now: DateTime<Utc>) {
+        if let Some(start_time) = self.start_time {
+            self.time = now.signed_duration_since(start_time).num_milliseconds();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn time(milliseconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(1_700_000_000_000 + milliseconds).unwrap()
+    }
+
+    #[test]
+    fn test_stopwatch_start_pause() {
+        let mut stopwatch = Stopwatch::default();
+
+        assert!(!stopwatch.is_active());
+
+        stopwatch.start_pause(time(0));
+        stopwatch.update(time(1200));
+
+        assert!(stopwatch.is_active());
+        assert_eq!(stopwatch.time(), 1200);
+
+        stopwatch.start_pause(time(1500));
+        stopwatch.update(time(5000));
+
+        assert!(!stopwatch.is_active());
+        assert_eq!(stopwatch.time(), 1500);
+
+        stopwatch.start_pause(time(6000));
+        stopwatch.update(time(7000));
+
+        assert!(stopwatch.is_active());
+        assert_eq!(stopwatch.time(), 2500);
+    }
+
+    #[test]
+    fn test_stopwatch_start_active() {
+        let mut stopwatch = Stopwatch::default();
+
+        stopwatch.start(time(0));
+        stopwatch.start(time(1000));
+        stopwatch.update(time(2000));
+
+        assert_eq!(stopwatch.time(), 2000);
+    }
+
+    #[test]
+    fn test_stopwatch_toggle() {
+        let mut stopwatch = Stopwatch::default();
+
+        stopwatch.toggle(time(0));
+        stopwatch.toggle(time(1000));
+
+        assert!(!stopwatch.is_active());
+        assert_eq!(stopwatch.time(), 1000);
+
+        stopwatch.toggle(time(2000));
+
+        assert!(!stopwatch.is_active());
+        assert_eq!(stopwatch.time(), 0);
+    }
+
+    #[test]
+    fn test_stopwatch_reset() {
+        let mut stopwatch = Stopwatch::default();
+
+        stopwatch.start(time(0));
+        stopwatch.reset(time(1000));
+        stopwatch.update(time(1500));
+
+        assert!(stopwatch.is_active());
+        assert_eq!(stopwatch.time(), 500);
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(499, 0)]
+    #[case(500, 1)]
+    #[case(12_345, 12)]
+    fn test_stopwatch_seconds(#[case] milliseconds: i64, #[case] expected: u32) {
+        let mut stopwatch = Stopwatch::default();
+
+        stopwatch.start(time(0));
+        stopwatch.pause(time(milliseconds));
+
+        assert_eq!(stopwatch.seconds(), expected);
+    }
+}
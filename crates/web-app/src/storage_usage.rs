@@ -0,0 +1,144 @@
+//! Estimation of the storage used by the app on the device.
+
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::js_sys;
+
+use crate::format::{format_number, DecimalSeparator};
+
+/// Usage and quota of the storage available to the app in bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub usage: f64,
+    pub quota: f64,
+}
+
+impl Estimate {
+    /// Fraction of the quota which is used, between 0 and 1.
+    #[must_use]
+    pub fn fraction(&self) -> f64 {
+        if self.quota > 0.0 {
+            (self.usage / self.quota).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Number of entries and size of the local storage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalStorageUsage {
+    pub entries: u32,
+    /// Size of all keys and values in bytes, based on the UTF-16 encoding used by browsers.
+    pub bytes: f64,
+}
+
+/// Check whether the Storage API is supported by the browser.
+#[must_use]
+pub fn supported() -> bool {
+    web_sys::window().is_some_and(|window| {
+        js_sys::Reflect::has(&window.navigator(), &JsValue::from("storage")).unwrap_or(false)
+    })
+}
+
+/// Estimate the storage usage of the app using the Storage API.
+///
+/// # Errors
+///
+/// Returns an error if the Storage API is not supported by the browser or the estimation failed.
+pub async fn estimate() -> Result<Estimate, String> {
+    if !supported() {
+        return Err("storage estimation not supported".into());
+    }
+    let promise = web_sys::window()
+        .ok_or("no window")?
+        .navigator()
+        .storage()
+        .estimate()
+        .map_err(|err| format!("failed to estimate storage usage: {err:?}"))?;
+    let estimate = wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map_err(|err| format!("failed to estimate storage usage: {err:?}"))?
+        .unchecked_into::<web_sys::StorageEstimate>();
+    Ok(Estimate {
+        usage: estimate.get_usage().unwrap_or_default(),
+        quota: estimate.get_quota().unwrap_or_default(),
+    })
+}
+
+/// Determine the number of entries and the size of the local storage.
+///
+/// # Errors
+///
+/// Returns an error if the local storage is not accessible.
+pub fn local_storage_usage() -> Result<LocalStorageUsage, String> {
+    let storage = web_sys::window()
+        .ok_or("no window")?
+        .local_storage()
+        .map_err(|err| format!("failed to access local storage: {err:?}"))?
+        .ok_or("no local storage")?;
+    let entries = storage
+        .length()
+        .map_err(|err| format!("failed to access local storage: {err:?}"))?;
+    let bytes = (0..entries)
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .map(|key| {
+            let value = storage.get_item(&key).ok().flatten().unwrap_or_default();
+            2 * (key.encode_utf16().count() + value.encode_utf16().count())
+        })
+        .sum::<usize>();
+    #[allow(clippy::cast_precision_loss)]
+    let bytes = bytes as f64;
+    Ok(LocalStorageUsage { entries, bytes })
+}
+
+/// Format a size in bytes using decimal units.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn format_bytes(bytes: f64, separator: DecimalSeparator) -> String {
+    const UNITS: [&str; 4] = ["kB", "MB", "GB", "TB"];
+    if bytes < 1000.0 {
+        return format!("{} B", format_number(bytes.max(0.0) as f32, 0, separator));
+    }
+    let mut value = bytes / 1000.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if value < 1000.0 {
+            break;
+        }
+        value /= 1000.0;
+        unit = next_unit;
+    }
+    format!("{} {unit}", format_number(value as f32, 1, separator))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0.0, 0.0, 0.0)]
+    #[case(250.0, 1000.0, 0.25)]
+    #[case(2000.0, 1000.0, 1.0)]
+    fn test_estimate_fraction(#[case] usage: f64, #[case] quota: f64, #[case] expected: f64) {
+        assert!((Estimate { usage, quota }.fraction() - expected).abs() < f64::EPSILON);
+    }
+
+    #[rstest]
+    #[case(0.0, DecimalSeparator::Point, "0 B")]
+    #[case(999.0, DecimalSeparator::Point, "999 B")]
+    #[case(1000.0, DecimalSeparator::Point, "1.0 kB")]
+    #[case(1_536.0, DecimalSeparator::Comma, "1,5 kB")]
+    #[case(12_345_678.0, DecimalSeparator::Point, "12.3 MB")]
+    #[case(2_500_000_000.0, DecimalSeparator::Point, "2.5 GB")]
+    #[case(3e15, DecimalSeparator::Point, "3000.0 TB")]
+    fn test_format_bytes(
+        #[case] bytes: f64,
+        #[case] separator: DecimalSeparator,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(format_bytes(bytes, separator), expected);
+    }
+}
@@ -0,0 +1,252 @@
+//! Local copies of deleted entries, which allow restoring accidentally deleted entries.
+//!
+//! The deletion is committed to the backend immediately. The trash only keeps the deleted data in
+//! the local storage, so that the entry can be created again later.
+
+use chrono::NaiveDate;
+use valens_domain::{BodyFat, BodyWeight, Period};
+
+/// Number of days a deleted entry is kept.
+pub const RETENTION_DAYS: i64 = 30;
+/// Maximum number of kept entries. The entries deleted first are discarded if exceeded.
+pub const CAPACITY: usize = 100;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub enum Entry {
+    BodyWeight(BodyWeight),
+    BodyFat(BodyFat),
+    Period(Period),
+}
+
+impl Entry {
+    #[must_use]
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Entry::BodyWeight(body_weight) => body_weight.date,
+            Entry::BodyFat(body_fat) => body_fat.date,
+            Entry::Period(period) => period.date,
+        }
+    }
+
+    fn same_kind(&self, other: &Entry) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct Item {
+    pub entry: Entry,
+    pub deleted: NaiveDate,
+}
+
+impl Item {
+    /// Date after which the item will be discarded.
+    #[must_use]
+    pub fn expires(&self) -> NaiveDate {
+        self.deleted + chrono::Duration::days(RETENTION_DAYS)
+    }
+}
+
+/// Deleted entries of a single user, ordered by the time of deletion.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Trash {
+    items: Vec<Item>,
+}
+
+impl Trash {
+    /// Add a deleted entry.
+    ///
+    /// A previously deleted entry of the same kind and date is replaced, as only the most recent
+    /// state can be restored.
+    pub fn add(&mut self, entry: Entry, deleted: NaiveDate) {
+        self.items
+            .retain(|item| !(item.entry.same_kind(&entry) && item.entry.date() == entry.date()));
+        self.items.push(Item { entry, deleted });
+        if self.items.len() > CAPACITY {
+            self.items.drain(..self.items.len() - CAPACITY);
+        }
+    }
+
+    /// Remove an entry, e.g. because it was restored. Return whether the entry was found.
+    pub fn remove(&mut self, entry: &Entry) -> bool {
+        let len = self.items.len();
+        self.items.retain(|item| item.entry != *entry);
+        self.items.len() != len
+    }
+
+    /// Discard all items whose retention period has passed. Return whether items were discarded.
+    pub fn purge(&mut self, today: NaiveDate) -> bool {
+        let len = self.items.len();
+        self.items.retain(|item| item.expires() >= today);
+        self.items.len() != len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Deleted body weight entries, most recently deleted first.
+    pub fn body_weight(&self) -> impl Iterator<Item = (&BodyWeight, &Item)> {
+        self.items
+            .iter()
+            .rev()
+            .filter_map(|item| match &item.entry {
+                Entry::BodyWeight(body_weight) => Some((body_weight, item)),
+                _ => None,
+            })
+    }
+
+    /// Deleted body fat entries, most recently deleted first.
+    pub fn body_fat(&self) -> impl Iterator<Item = (&BodyFat, &Item)> {
+        self.items
+            .iter()
+            .rev()
+            .filter_map(|item| match &item.entry {
+                Entry::BodyFat(body_fat) => Some((body_fat, item)),
+                _ => None,
+            })
+    }
+
+    /// Deleted period entries, most recently deleted first.
+    pub fn period(&self) -> impl Iterator<Item = (&Period, &Item)> {
+        self.items
+            .iter()
+            .rev()
+            .filter_map(|item| match &item.entry {
+                Entry::Period(period) => Some((period, item)),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2020, 3, day).unwrap()
+    }
+
+    fn body_weight(day: u32, weight: f32) -> Entry {
+        Entry::BodyWeight(BodyWeight {
+            date: date(day),
+            weight,
+        })
+    }
+
+    fn period(day: u32, intensity: u8) -> Entry {
+        Entry::Period(Period {
+            date: date(day),
+            intensity,
+        })
+    }
+
+    fn entries(trash: &Trash) -> Vec<Entry> {
+        trash.items.iter().map(|item| item.entry.clone()).collect()
+    }
+
+    #[test]
+    fn test_trash_add() {
+        let mut trash = Trash::default();
+
+        trash.add(body_weight(1, 80.0), date(5));
+        trash.add(period(1, 2), date(5));
+        trash.add(body_weight(2, 81.0), date(6));
+
+        assert_eq!(
+            entries(&trash),
+            vec![body_weight(1, 80.0), period(1, 2), body_weight(2, 81.0)]
+        );
+    }
+
+    #[test]
+    fn test_trash_add_same_date() {
+        let mut trash = Trash::default();
+
+        trash.add(body_weight(1, 80.0), date(5));
+        trash.add(period(1, 2), date(5));
+        trash.add(body_weight(1, 79.5), date(6));
+
+        assert_eq!(entries(&trash), vec![period(1, 2), body_weight(1, 79.5)]);
+    }
+
+    #[test]
+    fn test_trash_add_capacity() {
+        let mut trash = Trash::default();
+
+        for i in 0..=CAPACITY {
+            #[allow(clippy::cast_precision_loss)]
+            let weight = 60.0 + i as f32;
+            trash.add(
+                Entry::BodyWeight(BodyWeight {
+                    date: date(1) + chrono::Duration::days(i64::try_from(i).unwrap()),
+                    weight,
+                }),
+                date(1),
+            );
+        }
+
+        assert_eq!(trash.items.len(), CAPACITY);
+        assert_eq!(trash.items[0].entry.date(), date(2));
+    }
+
+    #[test]
+    fn test_trash_remove() {
+        let mut trash = Trash::default();
+        trash.add(body_weight(1, 80.0), date(5));
+        trash.add(period(1, 2), date(5));
+
+        assert!(!trash.remove(&body_weight(1, 79.0)));
+        assert!(trash.remove(&body_weight(1, 80.0)));
+        assert_eq!(entries(&trash), vec![period(1, 2)]);
+    }
+
+    #[test]
+    fn test_trash_purge() {
+        let mut trash = Trash::default();
+        trash.add(body_weight(1, 80.0), date(1));
+        trash.add(body_weight(2, 81.0), date(2));
+
+        assert!(!trash.purge(date(31)));
+        assert_eq!(trash.items.len(), 2);
+
+        assert!(trash.purge(NaiveDate::from_ymd_opt(2020, 4, 1).unwrap()));
+        assert_eq!(entries(&trash), vec![body_weight(2, 81.0)]);
+    }
+
+    #[test]
+    fn test_trash_kinds() {
+        let mut trash = Trash::default();
+        trash.add(body_weight(1, 80.0), date(5));
+        trash.add(period(1, 2), date(5));
+        trash.add(body_weight(2, 81.0), date(6));
+
+        assert_eq!(
+            trash
+                .body_weight()
+                .map(|(bw, _)| bw.date)
+                .collect::<Vec<_>>(),
+            vec![date(2), date(1)]
+        );
+        assert_eq!(
+            trash.period().map(|(p, _)| p.intensity).collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(trash.body_fat().count(), 0);
+    }
+
+    #[test]
+    fn test_trash_serde() {
+        let mut trash = Trash::default();
+        trash.add(body_weight(1, 80.0), date(5));
+        trash.add(period(1, 2), date(5));
+
+        let deserialized: Trash =
+            serde_json::from_str(&serde_json::to_string(&trash).unwrap()).unwrap();
+
+        assert_eq!(deserialized, trash);
+    }
+}
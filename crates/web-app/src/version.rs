@@ -0,0 +1,92 @@
+//! Compatibility of the versions of app and server.
+
+use std::cmp::Ordering;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    #[default]
+    Compatible,
+    /// The app is older than the server.
+    AppOutdated,
+    /// The server is older than the app.
+    ServerOutdated,
+}
+
+impl Compatibility {
+    #[must_use]
+    pub fn is_compatible(self) -> bool {
+        self == Compatibility::Compatible
+    }
+}
+
+/// Check whether the server version is in the range supported by the app.
+///
+/// The app supports all server versions with the same major and minor version. Versions that
+/// cannot be parsed are considered compatible, as a mismatch cannot be determined.
+#[must_use]
+pub fn compatibility(app_version: &str, server_version: &str) -> Compatibility {
+    match (major_minor(app_version), major_minor(server_version)) {
+        (Some(app), Some(server)) => match app.cmp(&server) {
+            Ordering::Less => Compatibility::AppOutdated,
+            Ordering::Greater => Compatibility::ServerOutdated,
+            Ordering::Equal => Compatibility::Compatible,
+        },
+        _ => Compatibility::Compatible,
+    }
+}
+
+/// Check whether both versions match in the major, minor and patch version.
+#[must_use]
+pub fn same_release(app_version: &str, server_version: &str) -> bool {
+    app_version
+        .split('.')
+        .take(3)
+        .eq(server_version.split('.').take(3))
+}
+
+fn major_minor(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::equal("0.5.0", "0.5.0", Compatibility::Compatible)]
+    #[case::different_patch("0.5.0", "0.5.3", Compatibility::Compatible)]
+    #[case::development_version("0.5.1.dev4+g1a2b3c4", "0.5.0", Compatibility::Compatible)]
+    #[case::newer_server_minor("0.5.2", "0.6.0", Compatibility::AppOutdated)]
+    #[case::newer_server_major("0.5.0", "1.0.0", Compatibility::AppOutdated)]
+    #[case::older_server_minor("0.6.0", "0.5.2", Compatibility::ServerOutdated)]
+    #[case::older_server_major("1.0.0", "0.9.0", Compatibility::ServerOutdated)]
+    #[case::numeric_order("0.10.0", "0.9.0", Compatibility::ServerOutdated)]
+    #[case::empty_server_version("0.5.0", "", Compatibility::Compatible)]
+    #[case::invalid_server_version("0.5.0", "unknown", Compatibility::Compatible)]
+    #[case::incomplete_server_version("0.5.0", "1", Compatibility::Compatible)]
+    fn test_compatibility(
+        #[case] app_version: &str,
+        #[case] server_version: &str,
+        #[case] expected: Compatibility,
+    ) {
+        assert_eq!(compatibility(app_version, server_version), expected);
+    }
+
+    #[rstest]
+    #[case::equal("0.5.0", "0.5.0", true)]
+    #[case::development_version("0.5.0.dev4", "0.5.0", true)]
+    #[case::different_patch("0.5.0", "0.5.1", false)]
+    #[case::incomplete("0.5.0", "0.5", false)]
+    #[case::empty("0.5.0", "", false)]
+    fn test_same_release(
+        #[case] app_version: &str,
+        #[case] server_version: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(same_release(app_version, server_version), expected);
+    }
+}
@@ -0,0 +1,139 @@
+//! Haptic feedback using the Vibration API.
+
+use web_sys::js_sys;
+
+/// Setting for vibrating in addition to playing beeps.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Vibration {
+    #[default]
+    Off,
+    On,
+    /// Vibrate only if the beep volume is set to zero.
+    OnWhenMuted,
+}
+
+impl Vibration {
+    #[must_use]
+    pub fn enabled(self, beep_volume: u8) -> bool {
+        match self {
+            Vibration::Off => false,
+            Vibration::On => true,
+            Vibration::OnWhenMuted => beep_volume == 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    Beat,
+    StressedBeat,
+    /// Timer will expire soon.
+    Warning,
+    /// Final seconds before the timer expires.
+    Countdown,
+    Expiry,
+}
+
+impl Pattern {
+    /// Alternating durations of vibrations and pauses in milliseconds.
+    #[must_use]
+    pub fn durations(self) -> &'static [u32] {
+        match self {
+            Pattern::Beat => &[30],
+            Pattern::StressedBeat => &[80],
+            Pattern::Warning => &[100, 80, 100],
+            Pattern::Countdown => &[150],
+            Pattern::Expiry => &[600],
+        }
+    }
+}
+
+pub trait Vibrator {
+    /// Vibrate using the given pattern. Return false if vibrating is not supported.
+    fn vibrate(&self, durations: &[u32]) -> bool;
+}
+
+/// Vibrator using the Vibration API of the browser.
+pub struct Navigator;
+
+impl Vibrator for Navigator {
+    fn vibrate(&self, durations: &[u32]) -> bool {
+        let Some(window) = web_sys::window() else {
+            return false;
+        };
+        let navigator = window.navigator();
+        if !js_sys::Reflect::has(&navigator, &"vibrate".into()).unwrap_or(false) {
+            return false;
+        }
+        navigator.vibrate_with_pattern(
+            &durations
+                .iter()
+                .map(|d| wasm_bindgen::JsValue::from(*d))
+                .collect::<js_sys::Array>(),
+        )
+    }
+}
+
+/// Vibrate if enabled by the vibration setting.
+pub fn vibrate(vibrator: &impl Vibrator, vibration: Vibration, beep_volume: u8, pattern: Pattern) {
+    if vibration.enabled(beep_volume) {
+        vibrator.vibrate(pattern.durations());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MockVibrator {
+        durations: RefCell<Vec<Vec<u32>>>,
+    }
+
+    impl Vibrator for MockVibrator {
+        fn vibrate(&self, durations: &[u32]) -> bool {
+            self.durations.borrow_mut().push(durations.to_vec());
+            true
+        }
+    }
+
+    #[rstest]
+    #[case::off(Vibration::Off, 80, false)]
+    #[case::off_muted(Vibration::Off, 0, false)]
+    #[case::on(Vibration::On, 80, true)]
+    #[case::on_muted(Vibration::On, 0, true)]
+    #[case::on_when_muted(Vibration::OnWhenMuted, 80, false)]
+    #[case::on_when_muted_muted(Vibration::OnWhenMuted, 0, true)]
+    fn test_vibrate(#[case] vibration: Vibration, #[case] beep_volume: u8, #[case] expected: bool) {
+        let vibrator = MockVibrator::default();
+
+        vibrate(&vibrator, vibration, beep_volume, Pattern::Expiry);
+
+        assert_eq!(
+            vibrator.durations.into_inner(),
+            if expected { vec![vec![600]] } else { vec![] }
+        );
+    }
+
+    #[test]
+    fn test_pattern_durations() {
+        let patterns = [
+            Pattern::Beat,
+            Pattern::StressedBeat,
+            Pattern::Warning,
+            Pattern::Countdown,
+            Pattern::Expiry,
+        ];
+        for (i, a) in patterns.iter().enumerate() {
+            assert!(!a.durations().is_empty());
+            for b in &patterns[i + 1..] {
+                assert_ne!(a.durations(), b.durations());
+            }
+        }
+    }
+}
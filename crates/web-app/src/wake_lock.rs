@@ -0,0 +1,137 @@
+//! Prevention of screen dimming and locking using the Screen Wake Lock API.
+
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::js_sys;
+
+/// Check whether the Screen Wake Lock API is supported by the browser.
+#[must_use]
+pub fn supported() -> bool {
+    web_sys::window().is_some_and(|window| {
+        js_sys::Reflect::has(&window.navigator(), &JsValue::from("wakeLock")).unwrap_or(false)
+    })
+}
+
+/// Screen wake lock which is held until it is dropped.
+///
+/// The browser releases the lock when the page gets hidden. Therefore, the lock is requested again
+/// when the page becomes visible.
+pub struct WakeLock {
+    state: Rc<RefCell<State>>,
+    document: web_sys::Document,
+    visibility_listener: Closure<dyn Fn()>,
+}
+
+#[derive(Default)]
+struct State {
+    released: bool,
+    sentinel: Option<JsValue>,
+}
+
+impl WakeLock {
+    /// Request a screen wake lock. Errors that occur asynchronously while requesting the lock are
+    /// passed to the handler.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Screen Wake Lock API is not supported by the browser.
+    pub fn acquire(on_error: impl Fn(String) + 'static) -> Result<Self, String> {
+        if !supported() {
+            return Err("wake lock not supported".into());
+        }
+        let window = web_sys::window().ok_or("no window")?;
+        let document = window.document().ok_or("no document")?;
+        let state = Rc::new(RefCell::new(State::default()));
+        let on_error: Rc<dyn Fn(String)> = Rc::new(on_error);
+
+        request(&state, &on_error);
+
+        let visibility_listener = {
+            let state = state.clone();
+            let document = document.clone();
+            Closure::<dyn Fn()>::new(move || {
+                if document.visibility_state() == web_sys::VisibilityState::Visible {
+                    request(&state, &on_error);
+                }
+            })
+        };
+        document
+            .add_event_listener_with_callback(
+                "visibilitychange",
+                visibility_listener.as_ref().unchecked_ref(),
+            )
+            .map_err(|err| format!("failed to add visibility change listener: {err:?}"))?;
+
+        Ok(Self {
+            state,
+            document,
+            visibility_listener,
+        })
+    }
+}
+
+impl Drop for WakeLock {
+    fn drop(&mut self) {
+        let _ = self.document.remove_event_listener_with_callback(
+            "visibilitychange",
+            self.visibility_listener.as_ref().unchecked_ref(),
+        );
+        let mut state = self.state.borrow_mut();
+        state.released = true;
+        if let Some(sentinel) = state.sentinel.take() {
+            release(&sentinel);
+        }
+    }
+}
+
+fn request(state: &Rc<RefCell<State>>, on_error: &Rc<dyn Fn(String)>) {
+    let promise = match request_promise() {
+        Ok(promise) => promise,
+        Err(err) => {
+            on_error(err);
+            return;
+        }
+    };
+    let state = state.clone();
+    let on_error = on_error.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        match wasm_bindgen_futures::JsFuture::from(promise).await {
+            Ok(sentinel) => {
+                let mut state = state.borrow_mut();
+                // The wake lock could have been dropped while the request was pending
+                if state.released {
+                    release(&sentinel);
+                } else if let Some(previous_sentinel) = state.sentinel.replace(sentinel) {
+                    release(&previous_sentinel);
+                }
+            }
+            Err(err) => on_error(format!("{err:?}")),
+        }
+    });
+}
+
+fn request_promise() -> Result<js_sys::Promise, String> {
+    let window = web_sys::window().ok_or("no window")?;
+    let wake_lock = js_sys::Reflect::get(&window.navigator(), &JsValue::from("wakeLock"))
+        .map_err(|err| format!("failed to get wake lock: {err:?}"))?;
+    let request = js_sys::Reflect::get(&wake_lock, &JsValue::from("request"))
+        .ok()
+        .and_then(|request| request.dyn_into::<js_sys::Function>().ok())
+        .ok_or("wake lock request not supported")?;
+    request
+        .call1(&wake_lock, &JsValue::from("screen"))
+        .map_err(|err| format!("failed to request wake lock: {err:?}"))?
+        .dyn_into::<js_sys::Promise>()
+        .map_err(|_| "unexpected result of wake lock request".to_string())
+}
+
+fn release(sentinel: &JsValue) {
+    if let Some(release) = js_sys::Reflect::get(sentinel, &JsValue::from("release"))
+        .ok()
+        .and_then(|release| release.dyn_into::<js_sys::Function>().ok())
+    {
+        // The returned promise is ignored, as the lock is of no use anymore anyway
+        let _ = release.call0(sentinel);
+    }
+}
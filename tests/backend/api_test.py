@@ -1,15 +1,19 @@
 from __future__ import annotations
 
+import base64
 from collections.abc import Generator
+from datetime import date, timedelta
 from http import HTTPStatus
 from pathlib import Path
 
 import pytest
+from sqlalchemy import select
 from werkzeug.test import Client, TestResponse as Response
 
 import tests.data
 import tests.utils
-from valens import app
+from valens import app, database as db
+from valens.models import ExerciseImage
 
 
 @pytest.fixture(name="client")
@@ -34,18 +38,25 @@ def delete_session(client: Client) -> Response:
     ("method", "route"),
     [
         ("get", "/api/users/1"),
+        ("put", "/api/users/1/pin"),
         ("get", "/api/body_weight"),
         ("post", "/api/body_weight"),
         ("put", "/api/body_weight/2002-02-22"),
         ("get", "/api/body_fat"),
         ("post", "/api/body_fat"),
         ("put", "/api/body_fat/2002-02-22"),
+        ("get", "/api/body_measurement"),
+        ("post", "/api/body_measurement"),
+        ("put", "/api/body_measurement/2002-02-22"),
         ("get", "/api/period"),
         ("post", "/api/period"),
         ("put", "/api/period/2002-02-22"),
         ("get", "/api/exercises"),
         ("post", "/api/exercises"),
         ("put", "/api/exercises/1"),
+        ("get", "/api/exercises/1/image"),
+        ("put", "/api/exercises/1/image"),
+        ("delete", "/api/exercises/1/image"),
         ("get", "/api/routines"),
         ("post", "/api/routines"),
         ("put", "/api/routines/1"),
@@ -66,14 +77,18 @@ def test_session_required(client: Client, method: str, route: str) -> None:
         ("post", "/api/session"),
         ("post", "/api/users"),
         ("put", "/api/users/2"),
+        ("put", "/api/users/1/pin"),
         ("post", "/api/body_weight"),
         ("put", "/api/body_weight/2002-02-22"),
         ("post", "/api/body_fat"),
         ("put", "/api/body_fat/2002-02-22"),
+        ("post", "/api/body_measurement"),
+        ("put", "/api/body_measurement/2002-02-22"),
         ("post", "/api/period"),
         ("put", "/api/period/2002-02-22"),
         ("post", "/api/exercises"),
         ("put", "/api/exercises/1"),
+        ("put", "/api/exercises/1/image"),
         ("post", "/api/routines"),
         ("put", "/api/routines/1"),
         ("post", "/api/workouts"),
@@ -96,21 +111,43 @@ def test_json_required(client: Client, method: str, route: str) -> None:
         ("post", "/api/session", {"invalid": "data"}),
         ("post", "/api/users", {"invalid": "data"}),
         ("put", "/api/users/2", {"invalid": "data"}),
+        ("put", "/api/users/1/pin", {"invalid": "data"}),
+        ("put", "/api/users/1/pin", {"pin": "123"}),
+        ("put", "/api/users/1/pin", {"pin": "12a4"}),
         ("post", "/api/body_weight", {"invalid": "data"}),
         ("put", "/api/body_weight/2002-02-22", {"invalid": "data"}),
         ("post", "/api/body_fat", {"invalid": "data"}),
         ("put", "/api/body_fat/2002-02-20", {"invalid": "data"}),
+        ("post", "/api/body_measurement", {"invalid": "data"}),
+        ("put", "/api/body_measurement/2002-02-20", {"invalid": "data"}),
         ("post", "/api/period", {"invalid": "data"}),
         ("put", "/api/period/2002-02-22", {"invalid": "data"}),
         ("post", "/api/exercises", {"invalid": "data"}),
         ("post", "/api/exercises", {"name": "data", "muscles": [{"invalid": "data"}]}),
         ("put", "/api/exercises/1", {"invalid": "data"}),
+        (
+            "put",
+            "/api/exercises/1",
+            {"name": "data", "muscles": [], "weight_convention": "PerBar"},
+        ),
+        ("put", "/api/exercises/1", {"name": "data", "muscles": [], "laterality": "Both"}),
+        ("put", "/api/exercises/1/image", {"invalid": "data"}),
+        ("put", "/api/exercises/1/image", {"mime_type": "image/bmp", "data": "Qk0="}),
+        ("put", "/api/exercises/1/image", {"mime_type": "image/jpeg", "data": "invalid!"}),
+        ("put", "/api/exercises/1/image", {"mime_type": "image/jpeg", "data": ""}),
+        ("put", "/api/exercises/1/image", {"mime_type": "image/jpeg", "data": 42}),
         ("post", "/api/routines", {"invalid": "data"}),
         ("put", "/api/routines/1", {"invalid": "data"}),
         ("patch", "/api/routines/1", {"sections": [{"invalid": "data"}]}),
+        ("patch", "/api/routines/1", {"schedule": ["Monday"]}),
+        ("patch", "/api/routines/1", {"schedule": "Mon"}),
+        ("patch", "/api/routines/1", {"tags": "Push"}),
+        ("patch", "/api/routines/1", {"tags": [{"name": "Push", "color": "Purple"}]}),
+        ("patch", "/api/routines/1", {"tags": [{"name": " ", "color": "Red"}]}),
         ("post", "/api/workouts", {"invalid": "data"}),
         ("put", "/api/workouts/1", {"invalid": "data"}),
         ("patch", "/api/workouts/1", {"elements": [{"invalid": "data"}]}),
+        ("patch", "/api/workouts/1", {"start_time": "invalid"}),
     ],
 )
 def test_invalid_data(client: Client, method: str, route: str, data: object) -> None:
@@ -136,11 +173,18 @@ def test_session(client: Client) -> None:
 
     resp = create_session(client)
     assert resp.status_code == HTTPStatus.OK
-    assert resp.json == {"id": 1, "name": "Alice", "sex": 0}
+    assert resp.json == {
+        "id": 1,
+        "name": "Alice",
+        "sex": 0,
+        "height": None,
+        "birthdate": None,
+        "has_pin": False,
+    }
 
     resp = client.get("/api/session")
     assert resp.status_code == HTTPStatus.OK
-    assert resp.json == {"id": 1, "name": "Alice", "sex": 0}
+    assert resp.json == {"id": 1, "name": "Alice", "sex": 0, "has_pin": False}
 
     resp = delete_session(client)
     assert resp.status_code == HTTPStatus.NO_CONTENT
@@ -172,8 +216,8 @@ def test_read_users(client: Client) -> None:
 
     assert resp.status_code == HTTPStatus.OK
     assert resp.json == [
-        {"id": 1, "name": "Alice", "sex": 0},
-        {"id": 2, "name": "Bob", "sex": 1},
+        {"id": 1, "name": "Alice", "sex": 0, "height": None, "birthdate": None, "has_pin": False},
+        {"id": 2, "name": "Bob", "sex": 1, "height": None, "birthdate": None, "has_pin": False},
     ]
 
 
@@ -191,7 +235,14 @@ def test_read_user(client: Client) -> None:
     resp = client.get("/api/users/1")
 
     assert resp.status_code == HTTPStatus.OK
-    assert resp.json == {"id": 1, "name": "Alice", "sex": 0}
+    assert resp.json == {
+        "id": 1,
+        "name": "Alice",
+        "sex": 0,
+        "height": None,
+        "birthdate": None,
+        "has_pin": False,
+    }
 
     resp = delete_session(client)
     assert resp.status_code == HTTPStatus.NO_CONTENT
@@ -204,15 +255,22 @@ def test_create_user(client: Client) -> None:
     resp = client.post("/api/users", json={"name": "Carol", "sex": 0})
 
     assert resp.status_code == HTTPStatus.CREATED
-    assert resp.json == {"id": 3, "name": "Carol", "sex": 0}
+    assert resp.json == {
+        "id": 3,
+        "name": "Carol",
+        "sex": 0,
+        "height": None,
+        "birthdate": None,
+        "has_pin": False,
+    }
 
     resp = client.get("/api/users")
 
     assert resp.status_code == HTTPStatus.OK
     assert resp.json == [
-        {"id": 1, "name": "Alice", "sex": 0},
-        {"id": 2, "name": "Bob", "sex": 1},
-        {"id": 3, "name": "Carol", "sex": 0},
+        {"id": 1, "name": "Alice", "sex": 0, "height": None, "birthdate": None, "has_pin": False},
+        {"id": 2, "name": "Bob", "sex": 1, "height": None, "birthdate": None, "has_pin": False},
+        {"id": 3, "name": "Carol", "sex": 0, "height": None, "birthdate": None, "has_pin": False},
     ]
 
 
@@ -231,14 +289,21 @@ def test_replace_user(client: Client) -> None:
     resp = client.put("/api/users/2", json={"name": "Carol", "sex": 0})
 
     assert resp.status_code == HTTPStatus.OK
-    assert resp.json == {"id": 2, "name": "Carol", "sex": 0}
+    assert resp.json == {
+        "id": 2,
+        "name": "Carol",
+        "sex": 0,
+        "height": None,
+        "birthdate": None,
+        "has_pin": False,
+    }
 
     resp = client.get("/api/users")
 
     assert resp.status_code == HTTPStatus.OK
     assert resp.json == [
-        {"id": 1, "name": "Alice", "sex": 0},
-        {"id": 2, "name": "Carol", "sex": 0},
+        {"id": 1, "name": "Alice", "sex": 0, "height": None, "birthdate": None, "has_pin": False},
+        {"id": 2, "name": "Carol", "sex": 0, "height": None, "birthdate": None, "has_pin": False},
     ]
 
 
@@ -260,6 +325,122 @@ def test_replace_user_conflict(client: Client) -> None:
     assert resp.json
 
 
+def test_create_user_with_height(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    resp = client.post("/api/users", json={"name": "Carol", "sex": 0, "height": 168.5})
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json == {
+        "id": 3,
+        "name": "Carol",
+        "sex": 0,
+        "height": 168.5,
+        "birthdate": None,
+        "has_pin": False,
+    }
+
+
+def test_replace_user_height(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    resp = client.put("/api/users/2", json={"name": "Bob", "sex": 1, "height": 180})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {
+        "id": 2,
+        "name": "Bob",
+        "sex": 1,
+        "height": 180.0,
+        "birthdate": None,
+        "has_pin": False,
+    }
+
+    resp = client.put("/api/users/2", json={"name": "Bob", "sex": 1})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {
+        "id": 2,
+        "name": "Bob",
+        "sex": 1,
+        "height": 180.0,
+        "birthdate": None,
+        "has_pin": False,
+    }
+
+    resp = client.put("/api/users/2", json={"name": "Bob", "sex": 1, "height": None})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {
+        "id": 2,
+        "name": "Bob",
+        "sex": 1,
+        "height": None,
+        "birthdate": None,
+        "has_pin": False,
+    }
+
+
+def test_replace_user_birthdate(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    resp = client.put("/api/users/2", json={"name": "Bob", "sex": 1, "birthdate": "1990-03-15"})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {
+        "id": 2,
+        "name": "Bob",
+        "sex": 1,
+        "height": None,
+        "birthdate": "1990-03-15",
+        "has_pin": False,
+    }
+
+    resp = client.put("/api/users/2", json={"name": "Bob", "sex": 1, "birthdate": None})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {
+        "id": 2,
+        "name": "Bob",
+        "sex": 1,
+        "height": None,
+        "birthdate": None,
+        "has_pin": False,
+    }
+
+
+@pytest.mark.parametrize("birthdate", ["1990-13-01", "15.03.1990", 1990])
+def test_replace_user_invalid_birthdate(client: Client, birthdate: object) -> None:
+    tests.utils.init_db_data()
+
+    resp = client.put("/api/users/2", json={"name": "Bob", "sex": 1, "birthdate": birthdate})
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+    assert resp.json
+
+
+def test_replace_user_future_birthdate(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    resp = client.put(
+        "/api/users/2",
+        json={"name": "Bob", "sex": 1, "birthdate": (date.today() + timedelta(days=1)).isoformat()},
+    )
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+    assert resp.json
+
+
+@pytest.mark.parametrize("height", [0, -180, "180", True])
+def test_replace_user_invalid_height(client: Client, height: object) -> None:
+    tests.utils.init_db_data()
+
+    resp = client.put("/api/users/2", json={"name": "Bob", "sex": 1, "height": height})
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+    assert resp.json
+
+
 def test_delete_user(client: Client) -> None:
     tests.utils.init_db_data()
 
@@ -272,7 +453,7 @@ def test_delete_user(client: Client) -> None:
 
     assert resp.status_code == HTTPStatus.OK
     assert resp.json == [
-        {"id": 1, "name": "Alice", "sex": 0},
+        {"id": 1, "name": "Alice", "sex": 0, "height": None, "birthdate": None, "has_pin": False},
     ]
 
     resp = client.delete("/api/users/2")
@@ -281,6 +462,95 @@ def test_delete_user(client: Client) -> None:
     assert not resp.data
 
 
+def test_replace_user_pin(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.put("/api/users/1/pin", json={"pin": "1234"})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {
+        "id": 1,
+        "name": "Alice",
+        "sex": 0,
+        "height": None,
+        "birthdate": None,
+        "has_pin": True,
+    }
+
+    assert delete_session(client).status_code == HTTPStatus.NO_CONTENT
+
+    resp = client.post("/api/session", json={"id": 1})
+
+    assert resp.status_code == HTTPStatus.UNAUTHORIZED
+    assert not resp.data
+
+    resp = client.post("/api/session", json={"id": 1, "pin": "4321"})
+
+    assert resp.status_code == HTTPStatus.UNAUTHORIZED
+    assert not resp.data
+
+    resp = client.post("/api/session", json={"id": 1, "pin": "1234"})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {
+        "id": 1,
+        "name": "Alice",
+        "sex": 0,
+        "height": None,
+        "birthdate": None,
+        "has_pin": True,
+    }
+
+    resp = client.put("/api/users/1/pin", json={"pin": None})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {
+        "id": 1,
+        "name": "Alice",
+        "sex": 0,
+        "height": None,
+        "birthdate": None,
+        "has_pin": False,
+    }
+
+    assert delete_session(client).status_code == HTTPStatus.NO_CONTENT
+    assert create_session(client).status_code == HTTPStatus.OK
+
+
+def test_replace_user_pin_forbidden(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.put("/api/users/2/pin", json={"pin": "1234"})
+
+    assert resp.status_code == HTTPStatus.FORBIDDEN
+    assert not resp.data
+
+
+def test_create_session_pin_failures(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+    assert client.put("/api/users/1/pin", json={"pin": "1234"}).status_code == HTTPStatus.OK
+    assert delete_session(client).status_code == HTTPStatus.NO_CONTENT
+
+    for _ in range(5):
+        resp = client.post("/api/session", json={"id": 1, "pin": "4321"})
+        assert resp.status_code == HTTPStatus.UNAUTHORIZED
+
+    resp = client.post("/api/session", json={"id": 1, "pin": "1234"})
+
+    assert resp.status_code == HTTPStatus.TOO_MANY_REQUESTS
+    assert not resp.data
+
+    resp = create_session(client, 2)
+
+    assert resp.status_code == HTTPStatus.OK
+
+
 @pytest.mark.parametrize(
     ("user_id", "route", "data"),
     [
@@ -319,6 +589,30 @@ def test_delete_user(client: Client) -> None:
                 },
             ],
         ),
+        (
+            1,
+            "/api/body_measurement",
+            [
+                {
+                    "date": "2002-02-20",
+                    "waist": 71.5,
+                    "hips": 96.0,
+                    "neck": 32.5,
+                    "upper_arm": 27.0,
+                    "thigh": 55.5,
+                    "chest": 88.0,
+                },
+                {
+                    "date": "2002-02-21",
+                    "waist": 71.0,
+                    "hips": None,
+                    "neck": None,
+                    "upper_arm": None,
+                    "thigh": None,
+                    "chest": None,
+                },
+            ],
+        ),
         (
             1,
             "/api/period",
@@ -332,9 +626,36 @@ def test_delete_user(client: Client) -> None:
             1,
             "/api/exercises",
             [
-                {"id": 1, "name": "Exercise 1", "muscles": [{"muscle_id": 11, "stimulus": 100}]},
-                {"id": 3, "name": "Exercise 3", "muscles": []},
-                {"id": 5, "name": "Unused Exercise", "muscles": []},
+                {
+                    "id": 1,
+                    "name": "Exercise 1",
+                    "muscles": [{"muscle_id": 11, "stimulus": 100}],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
+                {
+                    "id": 3,
+                    "name": "Exercise 3",
+                    "muscles": [],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
+                {
+                    "id": 5,
+                    "name": "Unused Exercise",
+                    "muscles": [],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
             ],
         ),
         (
@@ -346,6 +667,8 @@ def test_delete_user(client: Client) -> None:
                     "name": "R1",
                     "notes": "First Routine",
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 1,
@@ -357,6 +680,7 @@ def test_delete_user(client: Client) -> None:
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -365,6 +689,7 @@ def test_delete_user(client: Client) -> None:
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -378,6 +703,7 @@ def test_delete_user(client: Client) -> None:
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -386,6 +712,7 @@ def test_delete_user(client: Client) -> None:
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "rounds": 2,
@@ -397,6 +724,7 @@ def test_delete_user(client: Client) -> None:
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                         {
                                             "exercise_id": None,
@@ -405,6 +733,7 @@ def test_delete_user(client: Client) -> None:
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -420,6 +749,7 @@ def test_delete_user(client: Client) -> None:
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -428,6 +758,7 @@ def test_delete_user(client: Client) -> None:
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -438,6 +769,8 @@ def test_delete_user(client: Client) -> None:
                     "name": "R2",
                     "notes": None,
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 5,
@@ -449,6 +782,7 @@ def test_delete_user(client: Client) -> None:
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -457,6 +791,7 @@ def test_delete_user(client: Client) -> None:
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         }
@@ -472,6 +807,8 @@ def test_delete_user(client: Client) -> None:
                     "id": 1,
                     "date": "2002-01-11",
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": "First Workout",
                     "elements": [
                         {
@@ -485,6 +822,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -497,6 +838,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -509,6 +854,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -516,6 +865,8 @@ def test_delete_user(client: Client) -> None:
                     "id": 3,
                     "date": "2002-02-22",
                     "routine_id": None,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": None,
                     "elements": [
                         {
@@ -529,6 +880,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -541,6 +896,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -553,6 +912,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -565,6 +928,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -577,6 +944,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -585,6 +956,8 @@ def test_delete_user(client: Client) -> None:
                     "date": "2002-02-24",
                     "notes": None,
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "elements": [
                         {
                             "exercise_id": 3,
@@ -597,6 +970,10 @@ def test_delete_user(client: Client) -> None:
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -683,6 +1060,47 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                 },
             ],
         ),
+        (
+            "/api/body_measurement",
+            {
+                "date": "2002-02-24",
+                "waist": 70.5,
+                "hips": 95.5,
+                "neck": None,
+                "upper_arm": None,
+                "thigh": 55.0,
+                "chest": None,
+            },
+            [
+                {
+                    "date": "2002-02-20",
+                    "waist": 71.5,
+                    "hips": 96.0,
+                    "neck": 32.5,
+                    "upper_arm": 27.0,
+                    "thigh": 55.5,
+                    "chest": 88.0,
+                },
+                {
+                    "date": "2002-02-21",
+                    "waist": 71.0,
+                    "hips": None,
+                    "neck": None,
+                    "upper_arm": None,
+                    "thigh": None,
+                    "chest": None,
+                },
+                {
+                    "date": "2002-02-24",
+                    "waist": 70.5,
+                    "hips": 95.5,
+                    "neck": None,
+                    "upper_arm": None,
+                    "thigh": 55.0,
+                    "chest": None,
+                },
+            ],
+        ),
         (
             "/api/period",
             {"date": "2002-02-24", "intensity": 1},
@@ -699,10 +1117,33 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                 "id": 6,
                 "name": "New Exercise",
                 "muscles": [{"muscle_id": 11, "stimulus": 100}, {"muscle_id": 12, "stimulus": 50}],
+                "weight_convention": None,
+                "base_weight": None,
+                "laterality": "Bilateral",
+                "archived": False,
+                "image": None,
             },
             [
-                {"id": 1, "name": "Exercise 1", "muscles": [{"muscle_id": 11, "stimulus": 100}]},
-                {"id": 3, "name": "Exercise 3", "muscles": []},
+                {
+                    "id": 1,
+                    "name": "Exercise 1",
+                    "muscles": [{"muscle_id": 11, "stimulus": 100}],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
+                {
+                    "id": 3,
+                    "name": "Exercise 3",
+                    "muscles": [],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
                 {
                     "id": 6,
                     "name": "New Exercise",
@@ -710,8 +1151,22 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                         {"muscle_id": 11, "stimulus": 100},
                         {"muscle_id": 12, "stimulus": 50},
                     ],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
+                {
+                    "id": 5,
+                    "name": "Unused Exercise",
+                    "muscles": [],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
                 },
-                {"id": 5, "name": "Unused Exercise", "muscles": []},
             ],
         ),
         (
@@ -721,6 +1176,8 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                 "name": "New Routine",
                 "notes": "Something New",
                 "archived": True,
+                "schedule": [],
+                "tags": [],
                 "sections": [
                     {
                         "rounds": 2,
@@ -732,6 +1189,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": 75.0,
                             },
                             {
                                 "rounds": 2,
@@ -743,6 +1201,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": True,
+                                        "target_percent_1rm": None,
                                     },
                                     {
                                         "exercise_id": 1,
@@ -751,6 +1210,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": True,
+                                        "target_percent_1rm": None,
                                     },
                                 ],
                             },
@@ -766,6 +1226,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -774,6 +1235,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -785,6 +1247,8 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                     "name": "New Routine",
                     "notes": "Something New",
                     "archived": True,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 2,
@@ -796,6 +1260,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": 75.0,
                                 },
                                 {
                                     "rounds": 2,
@@ -807,6 +1272,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": True,
+                                            "target_percent_1rm": None,
                                         },
                                         {
                                             "exercise_id": 1,
@@ -815,6 +1281,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": True,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -830,6 +1297,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -838,6 +1306,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -848,6 +1317,8 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                     "name": "R1",
                     "notes": "First Routine",
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 1,
@@ -859,6 +1330,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -867,6 +1339,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -880,6 +1353,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -888,6 +1362,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "rounds": 2,
@@ -899,6 +1374,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                         {
                                             "exercise_id": None,
@@ -907,6 +1383,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -922,6 +1399,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -930,6 +1408,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -940,6 +1419,8 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                     "name": "R2",
                     "notes": None,
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 5,
@@ -951,6 +1432,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -959,6 +1441,7 @@ def test_read_all(client: Client, user_id: int, route: str, data: list[dict[str,
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         }
@@ -991,6 +1474,39 @@ def test_create(
     assert resp.json
 
 
+@pytest.mark.parametrize(
+    ("query", "ids"),
+    [
+        ("", [1, 3, 4]),
+        ("?first=2002-02-22", [3, 4]),
+        ("?last=2002-02-22", [1, 3]),
+        ("?first=2002-02-01&last=2002-02-23", [3]),
+        ("?first=2002-03-01", []),
+    ],
+)
+def test_read_workouts_interval(client: Client, query: str, ids: list[int]) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.get(f"/api/workouts{query}")
+
+    assert resp.status_code == HTTPStatus.OK
+    assert isinstance(resp.json, list)
+    assert [w["id"] for w in resp.json] == ids
+
+
+@pytest.mark.parametrize("query", ["?first=invalid", "?last=2002-13-01"])
+def test_read_workouts_invalid_interval(client: Client, query: str) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.get(f"/api/workouts{query}")
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+
+
 @pytest.mark.parametrize(
     ("data", "created_id"),
     [
@@ -998,6 +1514,8 @@ def test_create(
             {
                 "date": "2002-02-24",
                 "routine_id": 1,
+                "start_time": None,
+                "end_time": None,
                 "notes": "",
                 "elements": [
                     {
@@ -1011,6 +1529,10 @@ def test_create(
                         "target_weight": None,
                         "target_rpe": 8,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": "Felt pinch in left knee",
                     },
                     {
                         "target_time": 60,
@@ -1027,6 +1549,10 @@ def test_create(
                         "target_weight": 10,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "target_time": 120,
@@ -1043,6 +1569,10 @@ def test_create(
                         "target_weight": None,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                 ],
             },
@@ -1052,6 +1582,8 @@ def test_create(
             {
                 "date": "2002-02-24",
                 "routine_id": None,
+                "start_time": None,
+                "end_time": None,
                 "notes": "",
                 "elements": [],
             },
@@ -1075,6 +1607,8 @@ def test_create_workout(
             "id": 1,
             "notes": "First Workout",
             "routine_id": 1,
+            "start_time": None,
+            "end_time": None,
             "elements": [
                 {
                     "exercise_id": 3,
@@ -1087,6 +1621,10 @@ def test_create_workout(
                     "target_weight": None,
                     "target_rpe": None,
                     "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
                 },
                 {
                     "exercise_id": 1,
@@ -1099,6 +1637,10 @@ def test_create_workout(
                     "target_weight": None,
                     "target_rpe": None,
                     "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
                 },
                 {
                     "exercise_id": 1,
@@ -1111,6 +1653,10 @@ def test_create_workout(
                     "target_weight": None,
                     "target_rpe": None,
                     "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
                 },
             ],
         },
@@ -1119,6 +1665,8 @@ def test_create_workout(
             "id": 3,
             "notes": None,
             "routine_id": None,
+            "start_time": None,
+            "end_time": None,
             "elements": [
                 {
                     "exercise_id": 3,
@@ -1131,6 +1679,10 @@ def test_create_workout(
                     "target_weight": None,
                     "target_rpe": None,
                     "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
                 },
                 {
                     "exercise_id": 3,
@@ -1143,6 +1695,10 @@ def test_create_workout(
                     "target_weight": None,
                     "target_rpe": None,
                     "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
                 },
                 {
                     "exercise_id": 3,
@@ -1155,6 +1711,10 @@ def test_create_workout(
                     "target_weight": None,
                     "target_rpe": None,
                     "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
                 },
                 {
                     "exercise_id": 4,
@@ -1167,6 +1727,10 @@ def test_create_workout(
                     "target_weight": None,
                     "target_rpe": None,
                     "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
                 },
                 {
                     "exercise_id": 4,
@@ -1179,45 +1743,674 @@ def test_create_workout(
                     "target_weight": None,
                     "target_rpe": None,
                     "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
                 },
             ],
         },
-        {
-            "id": 4,
+        {
+            "id": 4,
+            "date": "2002-02-24",
+            "notes": None,
+            "routine_id": 1,
+            "start_time": None,
+            "end_time": None,
+            "elements": [
+                {
+                    "exercise_id": 3,
+                    "reps": 11,
+                    "time": 4,
+                    "weight": None,
+                    "rpe": 8.5,
+                    "target_reps": None,
+                    "target_time": None,
+                    "target_weight": None,
+                    "target_rpe": None,
+                    "automatic": False,
+                    "partial_reps": None,
+                    "to_failure": False,
+                    "side": None,
+                    "comment": None,
+                },
+            ],
+        },
+        created,
+    ]
+
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(route, json=data)
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json == created
+
+    resp = client.get(route)
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == result
+
+
+def test_create_workout_without_comment(client: Client) -> None:
+    element = {
+        "exercise_id": 3,
+        "reps": 10,
+        "time": None,
+        "weight": None,
+        "rpe": None,
+        "target_reps": None,
+        "target_time": None,
+        "target_weight": None,
+        "target_rpe": None,
+        "automatic": False,
+    }
+
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/workouts",
+        json={"date": "2002-02-24", "routine_id": None, "notes": None, "elements": [element]},
+    )
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json is not None
+    assert resp.json["elements"] == [
+        {**element, "partial_reps": None, "to_failure": False, "side": None, "comment": None}
+    ]
+
+
+def test_create_workout_with_partial_reps_and_failure(client: Client) -> None:
+    element = {
+        "exercise_id": 3,
+        "reps": 8,
+        "time": None,
+        "weight": 80.0,
+        "rpe": None,
+        "target_reps": None,
+        "target_time": None,
+        "target_weight": None,
+        "target_rpe": None,
+        "automatic": False,
+        "partial_reps": 2,
+        "to_failure": True,
+        "side": None,
+        "comment": None,
+    }
+
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/workouts",
+        json={"date": "2002-02-24", "routine_id": None, "notes": None, "elements": [element]},
+    )
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json is not None
+    assert resp.json["elements"] == [element]
+
+
+def test_create_workout_with_sides(client: Client) -> None:
+    set_ = {
+        "exercise_id": 3,
+        "reps": 8,
+        "time": None,
+        "weight": 20.0,
+        "rpe": None,
+        "target_reps": None,
+        "target_time": None,
+        "target_weight": None,
+        "target_rpe": None,
+        "automatic": False,
+        "partial_reps": None,
+        "to_failure": False,
+        "comment": None,
+    }
+    elements = [{**set_, "side": "Left"}, {**set_, "side": "Right"}]
+
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/workouts",
+        json={"date": "2002-02-24", "routine_id": None, "notes": None, "elements": elements},
+    )
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json is not None
+    assert resp.json["elements"] == elements
+
+    resp = client.post(
+        "/api/workouts",
+        json={
+            "date": "2002-02-24",
+            "routine_id": None,
+            "notes": None,
+            "elements": [{**set_, "side": "Both"}],
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+
+
+def test_create_routine_without_target_percent_1rm(client: Client) -> None:
+    part = {
+        "exercise_id": 3,
+        "reps": 5,
+        "time": 0,
+        "weight": 100.0,
+        "rpe": 0.0,
+        "automatic": False,
+    }
+
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/routines",
+        json={
+            "name": "New Routine",
+            "notes": None,
+            "archived": False,
+            "schedule": [],
+            "tags": [],
+            "sections": [{"rounds": 1, "parts": [part]}],
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json is not None
+    assert resp.json["sections"] == [{"rounds": 1, "parts": [{**part, "target_percent_1rm": None}]}]
+
+
+def test_create_routine_with_rpe_range(client: Client) -> None:
+    part = {
+        "exercise_id": 3,
+        "reps": 5,
+        "time": 0,
+        "weight": 100.0,
+        "rpe": [7.0, 8.0],
+        "automatic": False,
+        "target_percent_1rm": None,
+    }
+
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/routines",
+        json={
+            "name": "New Routine",
+            "notes": None,
+            "archived": False,
+            "schedule": [],
+            "tags": [],
+            "sections": [{"rounds": 1, "parts": [part]}],
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json is not None
+    assert resp.json["sections"] == [{"rounds": 1, "parts": [part]}]
+
+    resp = client.post(
+        "/api/routines",
+        json={
+            "name": "Invalid Routine",
+            "notes": None,
+            "archived": False,
+            "schedule": [],
+            "tags": [],
+            "sections": [{"rounds": 1, "parts": [{**part, "rpe": [7.0, 8.0, 9.0]}]}],
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+
+
+def test_create_workout_with_target_rpe_range(client: Client) -> None:
+    element = {
+        "exercise_id": 3,
+        "reps": 10,
+        "time": None,
+        "weight": None,
+        "rpe": 7.5,
+        "target_reps": None,
+        "target_time": None,
+        "target_weight": None,
+        "target_rpe": [7.0, 8.0],
+        "automatic": False,
+        "partial_reps": None,
+        "to_failure": False,
+        "side": None,
+        "comment": None,
+    }
+
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/workouts",
+        json={"date": "2002-02-24", "routine_id": None, "notes": None, "elements": [element]},
+    )
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json is not None
+    assert resp.json["elements"] == [element]
+
+    resp = client.patch(
+        f"/api/workouts/{resp.json['id']}",
+        json={"elements": [{**element, "target_rpe": 8.0}]},
+    )
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["elements"] == [{**element, "target_rpe": 8.0}]
+
+
+@pytest.mark.parametrize("reps", [[12, 8], [10, 10], [0, 8], [8, 1000], ["a", 3], [1.5, 3], 8.0])
+def test_create_routine_with_invalid_reps_range(client: Client, reps: object) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/routines",
+        json={
+            "name": "Invalid Routine",
+            "notes": None,
+            "archived": False,
+            "schedule": [],
+            "tags": [],
+            "sections": [
+                {
+                    "rounds": 1,
+                    "parts": [
+                        {
+                            "exercise_id": 3,
+                            "reps": reps,
+                            "time": 0,
+                            "weight": 100.0,
+                            "rpe": 0.0,
+                            "automatic": False,
+                            "target_percent_1rm": None,
+                        }
+                    ],
+                }
+            ],
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+
+
+@pytest.mark.parametrize("target_reps", [[12, 8], [10, 10], ["a", 3], [1.5, 3]])
+def test_create_workout_with_invalid_target_reps_range(client: Client, target_reps: object) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/workouts",
+        json={
+            "date": "2002-02-24",
+            "routine_id": None,
+            "notes": None,
+            "elements": [
+                {
+                    "exercise_id": 3,
+                    "reps": 10,
+                    "time": None,
+                    "weight": None,
+                    "rpe": None,
+                    "target_reps": target_reps,
+                    "target_time": None,
+                    "target_weight": None,
+                    "target_rpe": None,
+                    "automatic": False,
+                }
+            ],
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+
+
+@pytest.mark.parametrize(
+    "rpe",
+    [
+        [8.0, 7.0],
+        [8.0, 8.0],
+        [7.0, 10.5],
+        [-1.0, 7.0],
+        ["a", 8.0],
+        [True, 8.0],
+        "8",
+    ],
+)
+def test_create_routine_with_invalid_rpe_range(client: Client, rpe: object) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/routines",
+        json={
+            "name": "Invalid Routine",
+            "notes": None,
+            "archived": False,
+            "schedule": [],
+            "tags": [],
+            "sections": [
+                {
+                    "rounds": 1,
+                    "parts": [
+                        {
+                            "exercise_id": 3,
+                            "reps": 5,
+                            "time": 0,
+                            "weight": 100.0,
+                            "rpe": rpe,
+                            "automatic": False,
+                            "target_percent_1rm": None,
+                        }
+                    ],
+                }
+            ],
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+
+
+@pytest.mark.parametrize("target_rpe", [[8.0, 7.0], [8.0, 8.0], [7.0, 11.0], [7.0, "8"]])
+def test_create_workout_with_invalid_target_rpe_range(client: Client, target_rpe: object) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/workouts",
+        json={
             "date": "2002-02-24",
+            "routine_id": None,
             "notes": None,
-            "routine_id": 1,
             "elements": [
                 {
                     "exercise_id": 3,
-                    "reps": 11,
-                    "time": 4,
+                    "reps": 10,
+                    "time": None,
                     "weight": None,
-                    "rpe": 8.5,
+                    "rpe": None,
                     "target_reps": None,
                     "target_time": None,
                     "target_weight": None,
-                    "target_rpe": None,
+                    "target_rpe": target_rpe,
                     "automatic": False,
-                },
+                }
             ],
         },
-        created,
-    ]
+    )
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+
+
+def test_create_routine_with_reps_range(client: Client) -> None:
+    part = {
+        "exercise_id": 3,
+        "reps": [8, 12],
+        "time": 0,
+        "weight": 100.0,
+        "rpe": 0.0,
+        "automatic": False,
+        "target_percent_1rm": None,
+    }
 
     tests.utils.init_db_data()
 
     assert create_session(client).status_code == HTTPStatus.OK
 
-    resp = client.post(route, json=data)
+    resp = client.post(
+        "/api/routines",
+        json={
+            "name": "New Routine",
+            "notes": None,
+            "archived": False,
+            "schedule": [],
+            "tags": [],
+            "sections": [{"rounds": 1, "parts": [part]}],
+        },
+    )
 
     assert resp.status_code == HTTPStatus.CREATED
-    assert resp.json == created
+    assert resp.json is not None
+    assert resp.json["sections"] == [{"rounds": 1, "parts": [part]}]
 
-    resp = client.get(route)
+    resp = client.post(
+        "/api/routines",
+        json={
+            "name": "Invalid Routine",
+            "notes": None,
+            "archived": False,
+            "schedule": [],
+            "tags": [],
+            "sections": [{"rounds": 1, "parts": [{**part, "reps": [8]}]}],
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.BAD_REQUEST
+
+
+def test_create_workout_with_target_reps_range(client: Client) -> None:
+    element = {
+        "exercise_id": 3,
+        "reps": 10,
+        "time": None,
+        "weight": None,
+        "rpe": None,
+        "target_reps": [8, 12],
+        "target_time": None,
+        "target_weight": None,
+        "target_rpe": None,
+        "automatic": False,
+        "partial_reps": None,
+        "to_failure": False,
+        "side": None,
+        "comment": None,
+    }
+
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.post(
+        "/api/workouts",
+        json={"date": "2002-02-24", "routine_id": None, "notes": None, "elements": [element]},
+    )
+
+    assert resp.status_code == HTTPStatus.CREATED
+    assert resp.json is not None
+    assert resp.json["elements"] == [element]
+
+    resp = client.patch(
+        f"/api/workouts/{resp.json['id']}",
+        json={"elements": [{**element, "target_reps": 10}]},
+    )
 
     assert resp.status_code == HTTPStatus.OK
-    assert resp.json == result
+    assert resp.json is not None
+    assert resp.json["elements"] == [{**element, "target_reps": 10}]
+
+
+def test_modify_workout_start_and_end_time(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.patch(
+        "/api/workouts/1",
+        json={"start_time": "2002-02-20T18:00:00", "end_time": "2002-02-20T19:05:00"},
+    )
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["start_time"] == "2002-02-20T18:00:00"
+    assert resp.json["end_time"] == "2002-02-20T19:05:00"
+
+    resp = client.patch("/api/workouts/1", json={"end_time": None})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["start_time"] == "2002-02-20T18:00:00"
+    assert resp.json["end_time"] is None
+
+
+def test_modify_routine_schedule(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.patch("/api/routines/1", json={"schedule": ["Thu", "Mon", "Thu"]})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["schedule"] == ["Mon", "Thu"]
+
+    resp = client.get("/api/routines")
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert [r["schedule"] for r in resp.json if r["id"] == 1] == [["Mon", "Thu"]]
+
+
+def test_modify_routine_tags(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.patch(
+        "/api/routines/1",
+        json={"tags": [{"name": "Push", "color": "Red"}, {"name": " Legs ", "color": "Blue"}]},
+    )
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["tags"] == [
+        {"name": "Legs", "color": "Blue"},
+        {"name": "Push", "color": "Red"},
+    ]
+
+    resp = client.patch(
+        "/api/routines/1",
+        json={"tags": [{"name": "Push", "color": "Green"}, {"name": "Upper", "color": "Gray"}]},
+    )
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["tags"] == [
+        {"name": "Push", "color": "Green"},
+        {"name": "Upper", "color": "Gray"},
+    ]
+
+    resp = client.patch("/api/routines/1", json={"archived": True})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert len(resp.json["tags"]) == 2
+
+    resp = client.get("/api/routines")
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert [r["tags"] for r in resp.json if r["id"] == 1] == [
+        [{"name": "Push", "color": "Green"}, {"name": "Upper", "color": "Gray"}]
+    ]
+
+
+def test_replace_exercise_weight_convention(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.put(
+        "/api/exercises/1",
+        json={
+            "name": "Exercise 1",
+            "muscles": [],
+            "weight_convention": "PerSide",
+            "base_weight": 20.0,
+        },
+    )
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["weight_convention"] == "PerSide"
+    assert resp.json["base_weight"] == 20.0
+
+    resp = client.put("/api/exercises/1", json={"name": "Exercise 1", "muscles": []})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["weight_convention"] is None
+    assert resp.json["base_weight"] is None
+
+
+def test_replace_exercise_laterality(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.put(
+        "/api/exercises/1",
+        json={"name": "Exercise 1", "muscles": [], "laterality": "Unilateral"},
+    )
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["laterality"] == "Unilateral"
+
+    resp = client.put("/api/exercises/1", json={"name": "Exercise 1", "muscles": []})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["laterality"] == "Bilateral"
+
+
+def test_replace_exercise_archived(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.put(
+        "/api/exercises/1",
+        json={"name": "Exercise 1", "muscles": [], "archived": True},
+    )
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["archived"] is True
+
+    resp = client.get("/api/exercises")
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert [e["archived"] for e in resp.json if e["id"] == 1] == [True]
+
+    resp = client.put("/api/exercises/1", json={"name": "Exercise 1", "muscles": []})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json is not None
+    assert resp.json["archived"] is False
 
 
 @pytest.mark.parametrize(
@@ -1287,6 +2480,54 @@ def test_create_workout(
                 "midaxillary": 0,
             },
         ),
+        (
+            "/api/body_measurement/2002-02-20",
+            {
+                "waist": 70.0,
+                "hips": 95.0,
+                "neck": 32.0,
+                "upper_arm": None,
+                "thigh": 55.0,
+                "chest": 87.5,
+            },
+            {
+                "date": "2002-02-20",
+                "waist": 70.0,
+                "hips": 95.0,
+                "neck": 32.0,
+                "upper_arm": None,
+                "thigh": 55.0,
+                "chest": 87.5,
+            },
+            [
+                {
+                    "date": "2002-02-20",
+                    "waist": 70.0,
+                    "hips": 95.0,
+                    "neck": 32.0,
+                    "upper_arm": None,
+                    "thigh": 55.0,
+                    "chest": 87.5,
+                },
+                {
+                    "date": "2002-02-21",
+                    "waist": 71.0,
+                    "hips": None,
+                    "neck": None,
+                    "upper_arm": None,
+                    "thigh": None,
+                    "chest": None,
+                },
+            ],
+            {
+                "waist": 0.0,
+                "hips": None,
+                "neck": None,
+                "upper_arm": None,
+                "thigh": None,
+                "chest": None,
+            },
+        ),
         (
             "/api/period/2002-02-20",
             {"intensity": 3},
@@ -1303,11 +2544,21 @@ def test_create_workout(
             {
                 "name": "Changed Exercise",
                 "muscles": [{"muscle_id": 11, "stimulus": 50}, {"muscle_id": 12, "stimulus": 100}],
+                "weight_convention": None,
+                "base_weight": None,
+                "laterality": "Bilateral",
+                "archived": False,
+                "image": None,
             },
             {
                 "id": 1,
                 "name": "Changed Exercise",
                 "muscles": [{"muscle_id": 11, "stimulus": 50}, {"muscle_id": 12, "stimulus": 100}],
+                "weight_convention": None,
+                "base_weight": None,
+                "laterality": "Bilateral",
+                "archived": False,
+                "image": None,
             },
             [
                 {
@@ -1317,9 +2568,32 @@ def test_create_workout(
                         {"muscle_id": 11, "stimulus": 50},
                         {"muscle_id": 12, "stimulus": 100},
                     ],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
+                {
+                    "id": 3,
+                    "name": "Exercise 3",
+                    "muscles": [],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
+                {
+                    "id": 5,
+                    "name": "Unused Exercise",
+                    "muscles": [],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
                 },
-                {"id": 3, "name": "Exercise 3", "muscles": []},
-                {"id": 5, "name": "Unused Exercise", "muscles": []},
             ],
             {"name": "Exercise 3", "muscles": []},
         ),
@@ -1329,6 +2603,8 @@ def test_create_workout(
                 "name": "Changed Routine",
                 "notes": "First Changed Routine",
                 "archived": True,
+                "schedule": [],
+                "tags": [],
                 "sections": [
                     {
                         "rounds": 3,
@@ -1340,6 +2616,7 @@ def test_create_workout(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "rounds": 2,
@@ -1351,6 +2628,7 @@ def test_create_workout(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                 ],
                             },
@@ -1366,6 +2644,7 @@ def test_create_workout(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -1374,6 +2653,7 @@ def test_create_workout(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -1384,6 +2664,8 @@ def test_create_workout(
                 "name": "Changed Routine",
                 "notes": "First Changed Routine",
                 "archived": True,
+                "schedule": [],
+                "tags": [],
                 "sections": [
                     {
                         "rounds": 3,
@@ -1395,6 +2677,7 @@ def test_create_workout(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "rounds": 2,
@@ -1406,6 +2689,7 @@ def test_create_workout(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                 ],
                             },
@@ -1421,6 +2705,7 @@ def test_create_workout(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -1429,6 +2714,7 @@ def test_create_workout(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -1440,6 +2726,8 @@ def test_create_workout(
                     "name": "Changed Routine",
                     "notes": "First Changed Routine",
                     "archived": True,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 3,
@@ -1451,6 +2739,7 @@ def test_create_workout(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "rounds": 2,
@@ -1462,6 +2751,7 @@ def test_create_workout(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -1477,6 +2767,7 @@ def test_create_workout(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -1485,6 +2776,7 @@ def test_create_workout(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -1495,6 +2787,8 @@ def test_create_workout(
                     "name": "R2",
                     "notes": None,
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 5,
@@ -1506,6 +2800,7 @@ def test_create_workout(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -1514,6 +2809,7 @@ def test_create_workout(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         }
@@ -1525,6 +2821,8 @@ def test_create_workout(
                 "name": "R2",
                 "notes": "",
                 "archived": False,
+                "schedule": [],
+                "tags": [],
                 "sections": [],
             },
         ),
@@ -1545,6 +2843,10 @@ def test_create_workout(
                         "target_weight": None,
                         "target_rpe": 8,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "exercise_id": 1,
@@ -1557,12 +2859,18 @@ def test_create_workout(
                         "target_weight": 10,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                 ],
             },
             {
                 "id": 1,
                 "routine_id": 1,
+                "start_time": None,
+                "end_time": None,
                 "date": "2002-02-23",
                 "notes": "",
                 "elements": [
@@ -1577,6 +2885,10 @@ def test_create_workout(
                         "target_weight": None,
                         "target_rpe": 8,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "exercise_id": 1,
@@ -1589,6 +2901,10 @@ def test_create_workout(
                         "target_weight": 10,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                 ],
             },
@@ -1596,6 +2912,8 @@ def test_create_workout(
                 {
                     "id": 1,
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "date": "2002-02-23",
                     "notes": "",
                     "elements": [
@@ -1610,6 +2928,10 @@ def test_create_workout(
                             "target_weight": None,
                             "target_rpe": 8,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -1622,6 +2944,10 @@ def test_create_workout(
                             "target_weight": 10,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -1630,6 +2956,8 @@ def test_create_workout(
                     "date": "2002-02-22",
                     "notes": None,
                     "routine_id": None,
+                    "start_time": None,
+                    "end_time": None,
                     "elements": [
                         {
                             "exercise_id": 3,
@@ -1642,6 +2970,10 @@ def test_create_workout(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -1654,6 +2986,10 @@ def test_create_workout(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -1666,6 +3002,10 @@ def test_create_workout(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -1678,6 +3018,10 @@ def test_create_workout(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -1690,6 +3034,10 @@ def test_create_workout(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -1698,6 +3046,8 @@ def test_create_workout(
                     "date": "2002-02-24",
                     "notes": None,
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "elements": [
                         {
                             "exercise_id": 3,
@@ -1710,6 +3060,10 @@ def test_create_workout(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -1765,6 +3119,8 @@ def test_replace(
                 "name": "Changed Routine",
                 "notes": "First Routine",
                 "archived": False,
+                "schedule": [],
+                "tags": [],
                 "sections": [
                     {
                         "rounds": 1,
@@ -1776,6 +3132,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -1784,6 +3141,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -1797,6 +3155,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -1805,6 +3164,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "rounds": 2,
@@ -1816,6 +3176,7 @@ def test_replace(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                     {
                                         "exercise_id": None,
@@ -1824,6 +3185,7 @@ def test_replace(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                 ],
                             },
@@ -1839,6 +3201,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -1847,6 +3210,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -1858,6 +3222,8 @@ def test_replace(
                     "name": "Changed Routine",
                     "notes": "First Routine",
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 1,
@@ -1869,6 +3235,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -1877,6 +3244,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -1890,6 +3258,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -1898,6 +3267,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "rounds": 2,
@@ -1909,6 +3279,7 @@ def test_replace(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                         {
                                             "exercise_id": None,
@@ -1917,6 +3288,7 @@ def test_replace(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -1932,6 +3304,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -1940,6 +3313,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -1950,6 +3324,8 @@ def test_replace(
                     "name": "R2",
                     "notes": None,
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 5,
@@ -1961,6 +3337,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -1969,6 +3346,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         }
@@ -1989,6 +3367,8 @@ def test_replace(
                 "name": "R1",
                 "notes": "Changed Notes",
                 "archived": False,
+                "schedule": [],
+                "tags": [],
                 "sections": [
                     {
                         "rounds": 1,
@@ -2000,6 +3380,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -2008,6 +3389,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -2021,6 +3403,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -2029,6 +3412,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "rounds": 2,
@@ -2040,6 +3424,7 @@ def test_replace(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                     {
                                         "exercise_id": None,
@@ -2048,6 +3433,7 @@ def test_replace(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                 ],
                             },
@@ -2063,6 +3449,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -2071,6 +3458,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -2082,6 +3470,8 @@ def test_replace(
                     "name": "R1",
                     "notes": "Changed Notes",
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 1,
@@ -2093,6 +3483,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2101,6 +3492,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -2114,6 +3506,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2122,6 +3515,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "rounds": 2,
@@ -2133,6 +3527,7 @@ def test_replace(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                         {
                                             "exercise_id": None,
@@ -2141,6 +3536,7 @@ def test_replace(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -2156,6 +3552,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2164,6 +3561,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -2174,6 +3572,8 @@ def test_replace(
                     "name": "R2",
                     "notes": None,
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 5,
@@ -2185,6 +3585,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2193,6 +3594,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         }
@@ -2208,12 +3610,16 @@ def test_replace(
             "/api/routines/1",
             {
                 "archived": True,
+                "schedule": [],
+                "tags": [],
             },
             {
                 "id": 1,
                 "name": "R1",
                 "notes": "First Routine",
                 "archived": True,
+                "schedule": [],
+                "tags": [],
                 "sections": [
                     {
                         "rounds": 1,
@@ -2225,6 +3631,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -2233,6 +3640,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -2246,6 +3654,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -2254,6 +3663,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "rounds": 2,
@@ -2265,6 +3675,7 @@ def test_replace(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                     {
                                         "exercise_id": None,
@@ -2273,6 +3684,7 @@ def test_replace(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                 ],
                             },
@@ -2288,6 +3700,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -2296,6 +3709,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -2307,6 +3721,8 @@ def test_replace(
                     "name": "R1",
                     "notes": "First Routine",
                     "archived": True,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 1,
@@ -2318,6 +3734,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2326,6 +3743,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -2339,6 +3757,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2347,6 +3766,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "rounds": 2,
@@ -2358,6 +3778,7 @@ def test_replace(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                         {
                                             "exercise_id": None,
@@ -2366,6 +3787,7 @@ def test_replace(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -2381,6 +3803,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2389,6 +3812,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -2399,6 +3823,8 @@ def test_replace(
                     "name": "R2",
                     "notes": None,
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 5,
@@ -2410,6 +3836,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2418,6 +3845,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         }
@@ -2442,6 +3870,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "rounds": 2,
@@ -2453,6 +3882,7 @@ def test_replace(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                 ],
                             },
@@ -2468,6 +3898,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -2476,6 +3907,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -2486,6 +3918,8 @@ def test_replace(
                 "name": "R1",
                 "notes": "First Routine",
                 "archived": False,
+                "schedule": [],
+                "tags": [],
                 "sections": [
                     {
                         "rounds": 3,
@@ -2497,6 +3931,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": False,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "rounds": 2,
@@ -2508,6 +3943,7 @@ def test_replace(
                                         "weight": 0.0,
                                         "rpe": 0.0,
                                         "automatic": False,
+                                        "target_percent_1rm": None,
                                     },
                                 ],
                             },
@@ -2523,6 +3959,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                             {
                                 "exercise_id": None,
@@ -2531,6 +3968,7 @@ def test_replace(
                                 "weight": 0.0,
                                 "rpe": 0.0,
                                 "automatic": True,
+                                "target_percent_1rm": None,
                             },
                         ],
                     },
@@ -2542,6 +3980,8 @@ def test_replace(
                     "name": "R1",
                     "notes": "First Routine",
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 3,
@@ -2553,6 +3993,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "rounds": 2,
@@ -2564,6 +4005,7 @@ def test_replace(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -2579,6 +4021,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2587,6 +4030,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -2597,6 +4041,8 @@ def test_replace(
                     "name": "R2",
                     "notes": None,
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 5,
@@ -2608,6 +4054,7 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -2616,13 +4063,14 @@ def test_replace(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         }
                     ],
                 },
             ],
-            {"name": "R2", "notes": "", "archived": False, "sections": []},
+            {"name": "R2", "notes": "", "archived": False, "sections": [], "schedule": []},
         ),
         (
             "/api/workouts/1",
@@ -2633,6 +4081,8 @@ def test_replace(
                 "id": 1,
                 "date": "2002-02-23",
                 "routine_id": 1,
+                "start_time": None,
+                "end_time": None,
                 "notes": "First Workout",
                 "elements": [
                     {
@@ -2646,6 +4096,10 @@ def test_replace(
                         "target_weight": None,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "exercise_id": 1,
@@ -2658,6 +4112,10 @@ def test_replace(
                         "target_weight": None,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "exercise_id": 1,
@@ -2670,6 +4128,10 @@ def test_replace(
                         "target_weight": None,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                 ],
             },
@@ -2678,6 +4140,8 @@ def test_replace(
                     "id": 1,
                     "date": "2002-02-23",
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": "First Workout",
                     "elements": [
                         {
@@ -2691,6 +4155,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -2703,6 +4171,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -2715,6 +4187,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -2722,6 +4198,8 @@ def test_replace(
                     "id": 3,
                     "date": "2002-02-22",
                     "routine_id": None,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": None,
                     "elements": [
                         {
@@ -2735,6 +4213,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -2747,6 +4229,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -2759,6 +4245,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -2771,6 +4261,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -2783,6 +4277,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -2791,6 +4289,8 @@ def test_replace(
                     "date": "2002-02-24",
                     "notes": None,
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "elements": [
                         {
                             "exercise_id": 3,
@@ -2803,6 +4303,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -2818,6 +4322,8 @@ def test_replace(
                 "id": 1,
                 "date": "2002-01-11",
                 "routine_id": 1,
+                "start_time": None,
+                "end_time": None,
                 "notes": "",
                 "elements": [
                     {
@@ -2831,6 +4337,10 @@ def test_replace(
                         "target_weight": None,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "exercise_id": 1,
@@ -2843,6 +4353,10 @@ def test_replace(
                         "target_weight": None,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "exercise_id": 1,
@@ -2855,6 +4369,10 @@ def test_replace(
                         "target_weight": None,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                 ],
             },
@@ -2863,6 +4381,8 @@ def test_replace(
                     "id": 1,
                     "date": "2002-01-11",
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": "",
                     "elements": [
                         {
@@ -2876,6 +4396,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -2888,6 +4412,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -2900,6 +4428,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -2907,6 +4439,8 @@ def test_replace(
                     "id": 3,
                     "date": "2002-02-22",
                     "routine_id": None,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": None,
                     "elements": [
                         {
@@ -2920,6 +4454,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -2932,6 +4470,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -2944,6 +4486,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -2956,6 +4502,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -2968,6 +4518,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -2976,6 +4530,8 @@ def test_replace(
                     "date": "2002-02-24",
                     "notes": None,
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "elements": [
                         {
                             "exercise_id": 3,
@@ -2988,6 +4544,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -3009,6 +4569,10 @@ def test_replace(
                         "target_weight": None,
                         "target_rpe": 8,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "target_time": 120,
@@ -3025,12 +4589,18 @@ def test_replace(
                         "target_weight": 10,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                 ],
             },
             {
                 "id": 1,
                 "routine_id": 1,
+                "start_time": None,
+                "end_time": None,
                 "date": "2002-01-11",
                 "notes": "First Workout",
                 "elements": [
@@ -3045,6 +4615,10 @@ def test_replace(
                         "target_weight": None,
                         "target_rpe": 8,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                     {
                         "target_time": 120,
@@ -3061,6 +4635,10 @@ def test_replace(
                         "target_weight": 10,
                         "target_rpe": None,
                         "automatic": False,
+                        "partial_reps": None,
+                        "to_failure": False,
+                        "side": None,
+                        "comment": None,
                     },
                 ],
             },
@@ -3069,6 +4647,8 @@ def test_replace(
                     "id": 1,
                     "date": "2002-01-11",
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": "First Workout",
                     "elements": [
                         {
@@ -3082,6 +4662,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": 8,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "target_time": 120,
@@ -3098,6 +4682,10 @@ def test_replace(
                             "target_weight": 10,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -3105,6 +4693,8 @@ def test_replace(
                     "id": 3,
                     "date": "2002-02-22",
                     "routine_id": None,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": None,
                     "elements": [
                         {
@@ -3118,6 +4708,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -3130,6 +4724,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 3,
@@ -3142,6 +4740,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -3154,6 +4756,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 4,
@@ -3166,6 +4772,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -3174,6 +4784,8 @@ def test_replace(
                     "date": "2002-02-24",
                     "notes": None,
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "elements": [
                         {
                             "exercise_id": 3,
@@ -3186,6 +4798,10 @@ def test_replace(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -3253,6 +4869,20 @@ def test_modify(
                 },
             ],
         ),
+        (
+            "/api/body_measurement/2002-02-21",
+            [
+                {
+                    "date": "2002-02-20",
+                    "waist": 71.5,
+                    "hips": 96.0,
+                    "neck": 32.5,
+                    "upper_arm": 27.0,
+                    "thigh": 55.5,
+                    "chest": 88.0,
+                },
+            ],
+        ),
         (
             "/api/period/2002-02-21",
             [
@@ -3263,8 +4893,26 @@ def test_modify(
         (
             "/api/exercises/3",
             [
-                {"id": 1, "name": "Exercise 1", "muscles": [{"muscle_id": 11, "stimulus": 100}]},
-                {"id": 5, "name": "Unused Exercise", "muscles": []},
+                {
+                    "id": 1,
+                    "name": "Exercise 1",
+                    "muscles": [{"muscle_id": 11, "stimulus": 100}],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
+                {
+                    "id": 5,
+                    "name": "Unused Exercise",
+                    "muscles": [],
+                    "weight_convention": None,
+                    "base_weight": None,
+                    "laterality": "Bilateral",
+                    "archived": False,
+                    "image": None,
+                },
             ],
         ),
         (
@@ -3275,6 +4923,8 @@ def test_modify(
                     "name": "R1",
                     "notes": "First Routine",
                     "archived": False,
+                    "schedule": [],
+                    "tags": [],
                     "sections": [
                         {
                             "rounds": 1,
@@ -3286,6 +4936,7 @@ def test_modify(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -3294,6 +4945,7 @@ def test_modify(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -3307,6 +4959,7 @@ def test_modify(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -3315,6 +4968,7 @@ def test_modify(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": False,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "rounds": 2,
@@ -3326,6 +4980,7 @@ def test_modify(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                         {
                                             "exercise_id": None,
@@ -3334,6 +4989,7 @@ def test_modify(
                                             "weight": 0.0,
                                             "rpe": 0.0,
                                             "automatic": False,
+                                            "target_percent_1rm": None,
                                         },
                                     ],
                                 },
@@ -3349,6 +5005,7 @@ def test_modify(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                                 {
                                     "exercise_id": None,
@@ -3357,6 +5014,7 @@ def test_modify(
                                     "weight": 0.0,
                                     "rpe": 0.0,
                                     "automatic": True,
+                                    "target_percent_1rm": None,
                                 },
                             ],
                         },
@@ -3371,6 +5029,8 @@ def test_modify(
                     "id": 1,
                     "date": "2002-01-11",
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "notes": "First Workout",
                     "elements": [
                         {
@@ -3384,6 +5044,10 @@ def test_modify(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -3396,6 +5060,10 @@ def test_modify(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                         {
                             "exercise_id": 1,
@@ -3408,6 +5076,10 @@ def test_modify(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -3416,6 +5088,8 @@ def test_modify(
                     "date": "2002-02-24",
                     "notes": None,
                     "routine_id": 1,
+                    "start_time": None,
+                    "end_time": None,
                     "elements": [
                         {
                             "exercise_id": 3,
@@ -3428,6 +5102,10 @@ def test_modify(
                             "target_weight": None,
                             "target_rpe": None,
                             "automatic": False,
+                            "partial_reps": None,
+                            "to_failure": False,
+                            "side": None,
+                            "comment": None,
                         },
                     ],
                 },
@@ -3458,3 +5136,119 @@ def test_delete(
 
     assert resp.status_code == HTTPStatus.NOT_FOUND
     assert not resp.data
+
+
+def test_exercise_image(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.get("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.NOT_FOUND
+    assert not resp.data
+
+    resp = client.put("/api/exercises/1/image", json={"mime_type": "image/jpeg", "data": "AQID"})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json
+    digest = resp.json["image"]
+    assert isinstance(digest, str)
+    assert digest
+
+    resp = client.get("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {"mime_type": "image/jpeg", "data": "AQID"}
+
+    resp = client.get("/api/exercises")
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json
+    assert [e["image"] for e in resp.json] == [digest, None, None]
+
+    resp = client.put("/api/exercises/1/image", json={"mime_type": "image/gif", "data": "BAUG"})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json
+    assert resp.json["image"] not in {digest, None}
+
+    resp = client.get("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json == {"mime_type": "image/gif", "data": "BAUG"}
+
+    resp = client.put("/api/exercises/1", json={"name": "Exercise 1", "muscles": []})
+
+    assert resp.status_code == HTTPStatus.OK
+    assert resp.json
+    assert resp.json["image"]
+
+    resp = client.delete("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.NO_CONTENT
+    assert not resp.data
+
+    resp = client.get("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.NOT_FOUND
+
+    resp = client.delete("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.NOT_FOUND
+
+
+def test_exercise_image_of_other_user(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.put("/api/exercises/2/image", json={"mime_type": "image/jpeg", "data": "AQID"})
+
+    assert resp.status_code == HTTPStatus.NOT_FOUND
+
+    resp = client.put("/api/exercises/1/image", json={"mime_type": "image/jpeg", "data": "AQID"})
+
+    assert resp.status_code == HTTPStatus.OK
+
+    assert delete_session(client).status_code == HTTPStatus.NO_CONTENT
+    assert create_session(client, 2).status_code == HTTPStatus.OK
+
+    resp = client.get("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.NOT_FOUND
+
+    resp = client.delete("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.NOT_FOUND
+
+
+def test_exercise_image_too_large(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    data = base64.b64encode(bytes(2 * 1024 * 1024 + 1)).decode("ascii")
+    resp = client.put("/api/exercises/1/image", json={"mime_type": "image/jpeg", "data": data})
+
+    assert resp.status_code == HTTPStatus.REQUEST_ENTITY_TOO_LARGE
+    assert resp.json
+
+    resp = client.get("/api/exercises/1/image")
+
+    assert resp.status_code == HTTPStatus.NOT_FOUND
+
+
+def test_delete_exercise_with_image(client: Client) -> None:
+    tests.utils.init_db_data()
+
+    assert create_session(client).status_code == HTTPStatus.OK
+
+    resp = client.put("/api/exercises/1/image", json={"mime_type": "image/jpeg", "data": "AQID"})
+
+    assert resp.status_code == HTTPStatus.OK
+
+    resp = client.delete("/api/exercises/1")
+
+    assert resp.status_code == HTTPStatus.NO_CONTENT
+    assert db.session.execute(select(ExerciseImage)).scalars().all() == []
@@ -4,6 +4,7 @@ import datetime
 
 from valens.models import (
     BodyFat,
+    BodyMeasurement,
     BodyWeight,
     Exercise,
     ExerciseMuscle,
@@ -75,6 +76,28 @@ def users(today: datetime.date = datetime.date(2002, 3, 12)) -> list[User]:
                     midaxillary=None,
                 ),
             ],
+            body_measurement=[
+                BodyMeasurement(
+                    user_id=1,
+                    date=days_ago(20),
+                    waist=71.5,
+                    hips=96.0,
+                    neck=32.5,
+                    upper_arm=27.0,
+                    thigh=55.5,
+                    chest=88.0,
+                ),
+                BodyMeasurement(
+                    user_id=1,
+                    date=days_ago(19),
+                    waist=71.0,
+                    hips=None,
+                    neck=None,
+                    upper_arm=None,
+                    thigh=None,
+                    chest=None,
+                ),
+            ],
             period=[
                 Period(date=days_ago(20), intensity=2),
                 Period(date=days_ago(19), intensity=4),
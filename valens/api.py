@@ -1,6 +1,9 @@
 from __future__ import annotations
 
-from datetime import date
+import base64
+import binascii
+import hashlib
+from datetime import date, datetime, timedelta
 from functools import singledispatch, wraps
 from http import HTTPStatus
 from itertools import chain
@@ -11,18 +14,22 @@ from flask.typing import ResponseReturnValue
 from sqlalchemy import column, select
 from sqlalchemy.exc import IntegrityError, NoResultFound
 from sqlalchemy.orm import selectinload
+from werkzeug.security import check_password_hash, generate_password_hash
 
 from valens import database as db, version
 from valens.models import (
     BodyFat,
+    BodyMeasurement,
     BodyWeight,
     Exercise,
+    ExerciseImage,
     ExerciseMuscle,
     Period,
     Routine,
     RoutineActivity,
     RoutinePart,
     RoutineSection,
+    RoutineTag,
     Sex,
     User,
     Workout,
@@ -33,6 +40,17 @@ from valens.models import (
 
 bp = Blueprint("api", __name__, url_prefix="/api")
 
+MAX_PIN_FAILURES = 5
+PIN_FAILURE_DELAY = timedelta(seconds=30)
+WEEKDAYS = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+WEIGHT_CONVENTIONS = ["Total", "PerSide", "PerDumbbell"]
+LATERALITIES = ["Bilateral", "Unilateral"]
+SIDES = ["Left", "Right"]
+TAG_COLORS = ["Gray", "Turquoise", "Blue", "Cyan", "Green", "Yellow", "Red"]
+BODY_MEASUREMENT_PARTS = ["waist", "hips", "neck", "upper_arm", "thigh", "chest"]
+IMAGE_MIME_TYPES = ["image/jpeg", "image/png", "image/gif", "image/webp"]
+MAX_IMAGE_SIZE = 2 * 1024 * 1024
+
 
 class DeserializationError(Exception):
     pass
@@ -45,6 +63,14 @@ def to_dict(
     return model_to_dict(model, exclude, include)
 
 
+@to_dict.register
+def _(model: User) -> dict[str, object]:
+    return {
+        **model_to_dict(model, exclude=["pin_hash", "pin_failures", "pin_last_failure"]),
+        "has_pin": model.pin_hash is not None,
+    }
+
+
 @to_dict.register
 def _(model: Exercise) -> dict[str, object]:
     return {
@@ -53,14 +79,19 @@ def _(model: Exercise) -> dict[str, object]:
             to_dict(m, exclude=["user_id", "exercise_id"])
             for m in sorted(model.muscles, key=lambda x: x.muscle_id)
         ],
+        "image": model.image.digest if model.image else None,
     }
 
 
 @to_dict.register
 def _(model: Routine) -> dict[str, object]:
     return {
-        **model_to_dict(model),
+        **model_to_dict(model, exclude=["user_id", "schedule"]),
         "sections": [to_dict(s) for s in sorted(model.sections, key=lambda x: x.position)],
+        "schedule": from_schedule(model.schedule),
+        "tags": [
+            to_dict(t, exclude=["routine_id"]) for t in sorted(model.tags, key=lambda x: x.name)
+        ],
     }
 
 
@@ -75,7 +106,9 @@ def _(model: RoutineSection) -> dict[str, object]:
 @to_dict.register
 def _(model: RoutineActivity) -> dict[str, object]:
     return {
-        **model_to_dict(model, exclude=["id"]),
+        **model_to_dict(model, exclude=["id", "reps_max", "rpe_max"]),
+        "reps": from_reps_range(model.reps, model.reps_max),
+        "rpe": from_rpe_range(model.rpe, model.rpe_max),
     }
 
 
@@ -94,6 +127,19 @@ def _(model: WorkoutElement) -> dict[str, object]:
     }
 
 
+@to_dict.register
+def _(model: WorkoutSet) -> dict[str, object]:
+    return {
+        **model_to_dict(
+            model,
+            exclude=["workout_id", "position", "target_reps_max", "target_rpe_max"],
+            include=["automatic"],
+        ),
+        "target_reps": from_reps_range(model.target_reps, model.target_reps_max),
+        "target_rpe": from_rpe_range(model.target_rpe, model.target_rpe_max),
+    }
+
+
 def model_to_dict(
     model: object, exclude: Optional[list[str]] = None, include: Optional[list[str]] = None
 ) -> dict[str, object]:
@@ -108,6 +154,138 @@ def model_to_dict(
     }
 
 
+def to_height(json: object) -> Optional[float]:
+    if json is None:
+        return None
+    if isinstance(json, bool) or not isinstance(json, (int, float)) or json <= 0:
+        raise DeserializationError(f"invalid height: {json}")
+    return float(json)
+
+
+def to_birthdate(json: object) -> Optional[date]:
+    if json is None:
+        return None
+    if not isinstance(json, str):
+        raise DeserializationError(f"invalid birthdate: {json}")
+    birthdate = date.fromisoformat(json)
+    if birthdate > date.today():
+        raise DeserializationError(f"invalid birthdate: {json}")
+    return birthdate
+
+
+def to_schedule(json: list[str]) -> int:
+    if not isinstance(json, list) or not all(d in WEEKDAYS for d in json):
+        raise DeserializationError(f"invalid schedule: {json}")
+    return sum(1 << WEEKDAYS.index(d) for d in set(json))
+
+
+def from_schedule(schedule: int) -> list[str]:
+    return [d for i, d in enumerate(WEEKDAYS) if schedule & (1 << i)]
+
+
+def to_reps_range(json: object) -> tuple[Optional[int], Optional[int]]:
+    if json is None or (isinstance(json, int) and not isinstance(json, bool)):
+        return json, None
+    if isinstance(json, list) and len(json) == 2 and all(is_integer(v) for v in json):
+        reps, reps_max = json
+        if 0 < reps < reps_max < 1000:
+            return reps, reps_max
+    raise DeserializationError(f"invalid reps range: {json}")
+
+
+def from_reps_range(reps: Optional[int], reps_max: Optional[int]) -> object:
+    return reps if reps_max is None else [reps, reps_max]
+
+
+def to_rpe_range(json: object) -> tuple[Optional[float], Optional[float]]:
+    if json is None or (isinstance(json, (int, float)) and not isinstance(json, bool)):
+        return json, None
+    if isinstance(json, list) and len(json) == 2 and all(is_number(v) for v in json):
+        rpe, rpe_max = json
+        if 0 <= rpe < rpe_max <= 10:
+            return rpe, rpe_max
+    raise DeserializationError(f"invalid RPE range: {json}")
+
+
+def from_rpe_range(rpe: Optional[float], rpe_max: Optional[float]) -> object:
+    return rpe if rpe_max is None else [rpe, rpe_max]
+
+
+def to_tags(json: list[dict[str, str]]) -> dict[str, str]:
+    if not isinstance(json, list):
+        raise DeserializationError(f"invalid tags: {json}")
+    tags: dict[str, str] = {}
+    for tag in json:
+        if not isinstance(tag, dict) or not isinstance(tag.get("name"), str):
+            raise DeserializationError(f"invalid tag: {tag}")
+        name = tag["name"].strip()
+        if not name or tag.get("color") not in TAG_COLORS:
+            raise DeserializationError(f"invalid tag: {tag}")
+        tags[name] = tag["color"]
+    return tags
+
+
+def update_tags(routine: Routine, tags: dict[str, str]) -> None:
+    for t in list(routine.tags):
+        if t.name in tags:
+            t.color = tags[t.name]
+        else:
+            routine.tags.remove(t)
+
+    for name, color in tags.items():
+        if any(t.name == name for t in routine.tags):
+            continue
+        routine.tags.append(RoutineTag(name=name, color=color))
+
+
+def is_integer(value: object) -> bool:
+    return isinstance(value, int) and not isinstance(value, bool)
+
+
+def is_number(value: object) -> bool:
+    return isinstance(value, (int, float)) and not isinstance(value, bool)
+
+
+def to_weight_convention(json: Optional[str]) -> Optional[str]:
+    if json is not None and json not in WEIGHT_CONVENTIONS:
+        raise DeserializationError(f"invalid weight convention: {json}")
+    return json
+
+
+def to_laterality(json: object) -> str:
+    if not isinstance(json, str) or json not in LATERALITIES:
+        raise DeserializationError(f"invalid laterality: {json}")
+    return json
+
+
+def to_side(json: Optional[str]) -> Optional[str]:
+    if json is not None and json not in SIDES:
+        raise DeserializationError(f"invalid side: {json}")
+    return json
+
+
+def to_image_mime_type(json: object) -> str:
+    if not isinstance(json, str) or json not in IMAGE_MIME_TYPES:
+        raise DeserializationError(f"unsupported image type: {json}")
+    return json
+
+
+def to_image_data(json: object) -> bytes:
+    if not isinstance(json, str):
+        raise DeserializationError("invalid image data")
+    try:
+        data = base64.b64decode(json, validate=True)
+    except binascii.Error as e:
+        raise DeserializationError(f"invalid image data: {e}") from e
+    if not data:
+        raise DeserializationError("empty image data")
+    return data
+
+
+def to_datetime(json: Optional[str]) -> Optional[datetime]:
+    return None if json is None else datetime.fromisoformat(json)
+
+
 def to_routine_parts(json: list[dict[str, Any]]) -> list[RoutinePart]:  # type: ignore[misc]
     return [
         (
@@ -134,42 +312,54 @@ def to_routine_section(json: dict[str, Any], position: int) -> RoutineSection:
 def to_routine_activity(  # type: ignore[misc]
     json: dict[str, Any], position: int
 ) -> RoutineActivity:
+    reps, reps_max = to_reps_range(json["reps"])
+    rpe, rpe_max = to_rpe_range(json["rpe"])
     return RoutineActivity(
         position=position,
         exercise_id=json["exercise_id"],
-        reps=json["reps"],
+        reps=reps,
+        reps_max=reps_max,
         time=json["time"],
         weight=json["weight"],
-        rpe=json["rpe"],
+        rpe=rpe,
+        rpe_max=rpe_max,
         automatic=json["automatic"],
+        target_percent_1rm=json.get("target_percent_1rm"),
     )
 
 
 def to_workout_elements(json: list[dict[str, Any]]) -> list[WorkoutElement]:  # type: ignore[misc]
-    return [
-        (
-            WorkoutSet(
-                position=position,
-                exercise_id=element["exercise_id"],
-                reps=element["reps"],
-                time=element["time"],
-                weight=element["weight"],
-                rpe=element["rpe"],
-                target_reps=element["target_reps"],
-                target_time=element["target_time"],
-                target_weight=element["target_weight"],
-                target_rpe=element["target_rpe"],
-                automatic=element["automatic"],
-            )
-            if "exercise_id" in element
-            else WorkoutRest(
-                position=position,
-                target_time=element["target_time"],
-                automatic=element["automatic"],
-            )
+    return [to_workout_element(element, position) for position, element in enumerate(json, start=1)]
+
+
+def to_workout_element(json: dict[str, Any], position: int) -> WorkoutElement:  # type: ignore[misc]
+    if "exercise_id" in json:
+        target_reps, target_reps_max = to_reps_range(json["target_reps"])
+        target_rpe, target_rpe_max = to_rpe_range(json["target_rpe"])
+        return WorkoutSet(
+            position=position,
+            exercise_id=json["exercise_id"],
+            reps=json["reps"],
+            time=json["time"],
+            weight=json["weight"],
+            rpe=json["rpe"],
+            target_reps=target_reps,
+            target_reps_max=target_reps_max,
+            target_time=json["target_time"],
+            target_weight=json["target_weight"],
+            target_rpe=target_rpe,
+            target_rpe_max=target_rpe_max,
+            automatic=json["automatic"],
+            partial_reps=json.get("partial_reps"),
+            to_failure=json.get("to_failure", False),
+            side=to_side(json.get("side")),
+            comment=json.get("comment"),
         )
-        for position, element in enumerate(json, start=1)
-    ]
+    return WorkoutRest(
+        position=position,
+        target_time=json["target_time"],
+        automatic=json["automatic"],
+    )
 
 
 def json_expected(function: Callable) -> Callable:  # type: ignore[type-arg]
@@ -211,6 +401,7 @@ def create_session() -> ResponseReturnValue:
     try:
         assert isinstance(request.json, dict)
         user_id = request.json["id"]
+        pin = request.json.get("pin")
     except KeyError as e:
         return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
 
@@ -219,6 +410,26 @@ def create_session() -> ResponseReturnValue:
     except NoResultFound:
         return "", HTTPStatus.NOT_FOUND
 
+    if user.pin_hash is not None:
+        now = datetime.now()
+
+        if (
+            user.pin_failures >= MAX_PIN_FAILURES
+            and user.pin_last_failure is not None
+            and now - user.pin_last_failure < PIN_FAILURE_DELAY
+        ):
+            return "", HTTPStatus.TOO_MANY_REQUESTS
+
+        if not isinstance(pin, str) or not check_password_hash(user.pin_hash, pin):
+            user.pin_failures += 1
+            user.pin_last_failure = now
+            db.session.commit()
+            return "", HTTPStatus.UNAUTHORIZED
+
+        user.pin_failures = 0
+        user.pin_last_failure = None
+        db.session.commit()
+
     session["user_id"] = user.id
     session["username"] = user.name
     session["sex"] = user.sex
@@ -258,8 +469,13 @@ def create_user() -> ResponseReturnValue:
     assert isinstance(data, dict)
 
     try:
-        user = User(name=data["name"].strip(), sex=Sex(data["sex"]))
-    except (KeyError, ValueError) as e:
+        user = User(
+            name=data["name"].strip(),
+            sex=Sex(data["sex"]),
+            height=to_height(data.get("height")),
+            birthdate=to_birthdate(data.get("birthdate")),
+        )
+    except (KeyError, ValueError, DeserializationError) as e:
         return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
 
     db.session.add(user)
@@ -291,7 +507,11 @@ def replace_user(user_id: int) -> ResponseReturnValue:
     try:
         user.name = data["name"].strip()
         user.sex = Sex(data["sex"])
-    except (KeyError, ValueError) as e:
+        if "height" in data:
+            user.height = to_height(data["height"])
+        if "birthdate" in data:
+            user.birthdate = to_birthdate(data["birthdate"])
+    except (KeyError, ValueError, DeserializationError) as e:
         return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
 
     try:
@@ -302,6 +522,41 @@ def replace_user(user_id: int) -> ResponseReturnValue:
     return jsonify(to_dict(user)), HTTPStatus.OK
 
 
+@bp.route("/users/<int:user_id>/pin", methods=["PUT"])
+@session_required
+@json_expected
+def replace_user_pin(user_id: int) -> ResponseReturnValue:
+    if session["user_id"] != user_id:
+        return "", HTTPStatus.FORBIDDEN
+
+    try:
+        user = db.session.execute(select(User).where(User.id == user_id)).scalars().one()
+    except NoResultFound:
+        return "", HTTPStatus.NOT_FOUND
+
+    data = request.json
+
+    assert isinstance(data, dict)
+
+    try:
+        pin = data["pin"]
+    except KeyError as e:
+        return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
+
+    if pin is None:
+        user.pin_hash = None
+    elif isinstance(pin, str) and pin.isascii() and pin.isdigit() and 4 <= len(pin) <= 8:
+        user.pin_hash = generate_password_hash(pin)
+    else:
+        return jsonify({"details": "PIN must consist of 4 to 8 digits"}), HTTPStatus.BAD_REQUEST
+
+    user.pin_failures = 0
+    user.pin_last_failure = None
+    db.session.commit()
+
+    return jsonify(to_dict(user)), HTTPStatus.OK
+
+
 @bp.route("/users/<int:user_id>", methods=["DELETE"])
 def delete_user(user_id: int) -> ResponseReturnValue:
     try:
@@ -537,6 +792,113 @@ def delete_body_fat(date_: str) -> ResponseReturnValue:
     return "", HTTPStatus.NO_CONTENT
 
 
+@bp.route("/body_measurement")
+@session_required
+def read_body_measurement() -> ResponseReturnValue:
+    body_measurement = (
+        db.session.execute(
+            select(BodyMeasurement).where(BodyMeasurement.user_id == session["user_id"])
+        )
+        .scalars()
+        .all()
+    )
+    return jsonify([to_dict(bm) for bm in body_measurement])
+
+
+@bp.route("/body_measurement", methods=["POST"])
+@session_required
+@json_expected
+def create_body_measurement() -> ResponseReturnValue:
+    data = request.json
+
+    assert isinstance(data, dict)
+
+    try:
+        body_measurement = BodyMeasurement(
+            user_id=int(session["user_id"]),
+            date=date.fromisoformat(data["date"]),
+            **{
+                part: float(data[part]) if data[part] is not None else None
+                for part in BODY_MEASUREMENT_PARTS
+            },
+        )
+    except (KeyError, ValueError) as e:
+        return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
+
+    db.session.add(body_measurement)
+
+    try:
+        db.session.commit()
+    except IntegrityError as e:
+        return jsonify({"details": str(e)}), HTTPStatus.CONFLICT
+
+    return (
+        jsonify(to_dict(body_measurement)),
+        HTTPStatus.CREATED,
+        {"Location": f"/body_measurement/{body_measurement.date}"},
+    )
+
+
+@bp.route("/body_measurement/<date_>", methods=["PUT"])
+@session_required
+@json_expected
+def replace_body_measurement(date_: str) -> ResponseReturnValue:
+    try:
+        body_measurement = (
+            db.session.execute(
+                select(BodyMeasurement)
+                .where(BodyMeasurement.user_id == session["user_id"])
+                .where(BodyMeasurement.date == date.fromisoformat(date_))
+            )
+            .scalars()
+            .one()
+        )
+    except (NoResultFound, ValueError):
+        return "", HTTPStatus.NOT_FOUND
+
+    data = request.json
+
+    assert isinstance(data, dict)
+
+    try:
+        for part in BODY_MEASUREMENT_PARTS:
+            setattr(body_measurement, part, float(data[part]) if data[part] is not None else None)
+    except (KeyError, ValueError) as e:
+        return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
+
+    try:
+        db.session.commit()
+    except IntegrityError as e:
+        return jsonify({"details": str(e)}), HTTPStatus.CONFLICT
+
+    return (
+        jsonify(to_dict(body_measurement)),
+        HTTPStatus.OK,
+    )
+
+
+@bp.route("/body_measurement/<date_>", methods=["DELETE"])
+@session_required
+def delete_body_measurement(date_: str) -> ResponseReturnValue:
+    try:
+        body_measurement = (
+            db.session.execute(
+                select(BodyMeasurement)
+                .where(BodyMeasurement.user_id == session["user_id"])
+                .where(BodyMeasurement.date == date.fromisoformat(date_))
+            )
+            .scalars()
+            .one()
+        )
+    except (NoResultFound, ValueError):
+        return "", HTTPStatus.NOT_FOUND
+
+    db.session.delete(body_measurement)
+    db.session.commit()
+
+    return "", HTTPStatus.NO_CONTENT
+
+
 @bp.route("/period")
 @session_required
 def read_period() -> ResponseReturnValue:
@@ -642,7 +1004,11 @@ def delete_period(date_: str) -> ResponseReturnValue:
 @session_required
 def read_exercises() -> ResponseReturnValue:
     exercises = (
-        db.session.execute(select(Exercise).where(Exercise.user_id == session["user_id"]))
+        db.session.execute(
+            select(Exercise)
+            .where(Exercise.user_id == session["user_id"])
+            .options(selectinload(Exercise.image))
+        )
         .scalars()
         .all()
     )
@@ -661,6 +1027,10 @@ def create_exercise() -> ResponseReturnValue:
         exercise = Exercise(
             user_id=session["user_id"],
             name=data["name"],
+            weight_convention=to_weight_convention(data.get("weight_convention")),
+            base_weight=data.get("base_weight"),
+            laterality=to_laterality(data.get("laterality", "Bilateral")),
+            archived=data.get("archived", False),
             muscles=[
                 ExerciseMuscle(
                     user_id=session["user_id"],
@@ -670,7 +1040,7 @@ def create_exercise() -> ResponseReturnValue:
                 for muscle in data["muscles"]
             ],
         )
-    except (KeyError, ValueError) as e:
+    except (DeserializationError, KeyError, ValueError) as e:
         return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
 
     db.session.add(exercise)
@@ -710,6 +1080,10 @@ def replace_exercise(id_: int) -> ResponseReturnValue:
 
     try:
         exercise.name = data["name"]
+        exercise.weight_convention = to_weight_convention(data.get("weight_convention"))
+        exercise.base_weight = data.get("base_weight")
+        exercise.laterality = to_laterality(data.get("laterality", "Bilateral"))
+        exercise.archived = data.get("archived", False)
         muscle_stimulus = {m["muscle_id"]: m["stimulus"] for m in data["muscles"]}
 
         for m in exercise.muscles:
@@ -724,7 +1098,7 @@ def replace_exercise(id_: int) -> ResponseReturnValue:
             exercise.muscles.append(
                 ExerciseMuscle(user_id=session["user_id"], muscle_id=muscle_id, stimulus=stimulus)
             )
-    except (KeyError, ValueError, TypeError) as e:
+    except (DeserializationError, KeyError, ValueError, TypeError) as e:
         return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
 
     try:
@@ -760,6 +1134,104 @@ def delete_exercise(id_: int) -> ResponseReturnValue:
     return "", HTTPStatus.NO_CONTENT
 
 
+@bp.route("/exercises/<int:id_>/image")
+@session_required
+def read_exercise_image(id_: int) -> ResponseReturnValue:
+    try:
+        image = (
+            db.session.execute(
+                select(ExerciseImage)
+                .join(Exercise)
+                .where(ExerciseImage.exercise_id == id_)
+                .where(Exercise.user_id == session["user_id"])
+            )
+            .scalars()
+            .one()
+        )
+    except (NoResultFound, ValueError):
+        return "", HTTPStatus.NOT_FOUND
+
+    return jsonify(
+        {
+            "mime_type": image.mime_type,
+            "data": base64.b64encode(image.data).decode("ascii"),
+        }
+    )
+
+
+@bp.route("/exercises/<int:id_>/image", methods=["PUT"])
+@session_required
+@json_expected
+def replace_exercise_image(id_: int) -> ResponseReturnValue:
+    try:
+        exercise = (
+            db.session.execute(
+                select(Exercise)
+                .where(Exercise.id == id_)
+                .where(Exercise.user_id == session["user_id"])
+            )
+            .scalars()
+            .one()
+        )
+    except (NoResultFound, ValueError):
+        return "", HTTPStatus.NOT_FOUND
+
+    data = request.json
+
+    assert isinstance(data, dict)
+
+    try:
+        mime_type = to_image_mime_type(data["mime_type"])
+        image_data = to_image_data(data["data"])
+    except (DeserializationError, KeyError) as e:
+        return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
+
+    if len(image_data) > MAX_IMAGE_SIZE:
+        return (
+            jsonify({"details": f"image exceeds maximum size of {MAX_IMAGE_SIZE} bytes"}),
+            HTTPStatus.REQUEST_ENTITY_TOO_LARGE,
+        )
+
+    digest = hashlib.sha256(image_data).hexdigest()
+
+    if exercise.image:
+        exercise.image.mime_type = mime_type
+        exercise.image.digest = digest
+        exercise.image.data = image_data
+    else:
+        exercise.image = ExerciseImage(mime_type=mime_type, digest=digest, data=image_data)
+
+    db.session.commit()
+
+    return (
+        jsonify(to_dict(exercise)),
+        HTTPStatus.OK,
+    )
+
+
+@bp.route("/exercises/<int:id_>/image", methods=["DELETE"])
+@session_required
+def delete_exercise_image(id_: int) -> ResponseReturnValue:
+    try:
+        image = (
+            db.session.execute(
+                select(ExerciseImage)
+                .join(Exercise)
+                .where(ExerciseImage.exercise_id == id_)
+                .where(Exercise.user_id == session["user_id"])
+            )
+            .scalars()
+            .one()
+        )
+    except (NoResultFound, ValueError):
+        return "", HTTPStatus.NOT_FOUND
+
+    db.session.delete(image)
+    db.session.commit()
+
+    return "", HTTPStatus.NO_CONTENT
+
+
 @bp.route("/routines")
 @session_required
 def read_routines() -> ResponseReturnValue:
@@ -767,7 +1239,7 @@ def read_routines() -> ResponseReturnValue:
         db.session.execute(
             select(Routine)
             .where(Routine.user_id == session["user_id"])
-            .options(selectinload(Routine.sections))
+            .options(selectinload(Routine.sections), selectinload(Routine.tags))
         )
         .scalars()
         .all()
@@ -790,6 +1262,11 @@ def create_routine() -> ResponseReturnValue:
             notes=data["notes"],
             archived=data["archived"],
             sections=to_routine_sections(data["sections"]),
+            schedule=to_schedule(data.get("schedule", [])),
+            tags=[
+                RoutineTag(name=name, color=color)
+                for name, color in to_tags(data.get("tags", [])).items()
+            ],
         )
     except (DeserializationError, KeyError, ValueError) as e:
         return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
@@ -838,6 +1315,10 @@ def update_routine(id_: int) -> ResponseReturnValue:
             routine.archived = data["archived"]
         if "sections" in data or request.method == "PUT":
             routine.sections = to_routine_sections(data["sections"])
+        if "schedule" in data:
+            routine.schedule = to_schedule(data["schedule"])
+        if "tags" in data:
+            update_tags(routine, to_tags(data["tags"]))
     except (DeserializationError, KeyError, ValueError) as e:
         return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
 
@@ -877,15 +1358,21 @@ def delete_routine(id_: int) -> ResponseReturnValue:
 @bp.route("/workouts")
 @session_required
 def read_workouts() -> ResponseReturnValue:
-    workouts = (
-        db.session.execute(
-            select(Workout)
-            .where(Workout.user_id == session["user_id"])
-            .options(selectinload(Workout.elements))
-        )
-        .scalars()
-        .all()
+    query = (
+        select(Workout)
+        .where(Workout.user_id == session["user_id"])
+        .options(selectinload(Workout.elements))
     )
+
+    try:
+        if "first" in request.args:
+            query = query.where(Workout.date >= date.fromisoformat(request.args["first"]))
+        if "last" in request.args:
+            query = query.where(Workout.date <= date.fromisoformat(request.args["last"]))
+    except ValueError as e:
+        return jsonify({"details": str(e)}), HTTPStatus.BAD_REQUEST
+
+    workouts = db.session.execute(query).scalars().all()
     return jsonify([to_dict(w) for w in workouts])
 
 
@@ -917,6 +1404,8 @@ def create_workout() -> ResponseReturnValue:
             routine=routine,
             date=date.fromisoformat(data["date"]),
             notes=data["notes"],
+            start_time=to_datetime(data.get("start_time")),
+            end_time=to_datetime(data.get("end_time")),
             elements=to_workout_elements(data["elements"]),
         )
     except (DeserializationError, NoResultFound, KeyError, ValueError) as e:
@@ -965,6 +1454,10 @@ def update_workout(id_: int) -> ResponseReturnValue:
             workout.date = date.fromisoformat(data["date"])
         if "notes" in data or request.method == "PUT":
             workout.notes = data["notes"]
+        if "start_time" in data:
+            workout.start_time = to_datetime(data["start_time"])
+        if "end_time" in data:
+            workout.end_time = to_datetime(data["end_time"])
         if "elements" in data or request.method == "PUT":
             workout.elements = to_workout_elements(data["elements"])
     except (DeserializationError, KeyError, ValueError) as e:
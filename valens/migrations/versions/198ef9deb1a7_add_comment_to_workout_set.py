@@ -0,0 +1,26 @@
+"""
+Add comment to workout set.
+
+Revision ID: 198ef9deb1a7
+Revises: 5d3c8b1f2a47
+Create Date: 2024-07-12
+
+"""
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "198ef9deb1a7"
+down_revision = "5d3c8b1f2a47"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("comment", sa.String(), nullable=True))
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        batch_op.drop_column("comment")
@@ -0,0 +1,42 @@
+"""
+Add target_percent_1rm to routine_activity.
+
+Revision ID: 3f6a2c9e71d4
+Revises: 198ef9deb1a7
+Create Date: 2024-07-18
+
+"""
+
+from typing import Union
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "3f6a2c9e71d4"
+down_revision = "198ef9deb1a7"
+branch_labels = None
+depends_on = None
+
+
+check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    (
+        "target_percent_1rm_type_real_or_null",
+        "typeof(target_percent_1rm) = 'real' or typeof(target_percent_1rm) = 'null'",
+    ),
+    ("target_percent_1rm_gt_0", sa.column("target_percent_1rm") > 0),
+    ("target_percent_1rm_le_100", sa.column("target_percent_1rm") <= 100),
+]
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("routine_activity", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("target_percent_1rm", sa.Float(), nullable=True))
+        for constraint_name, condition in check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("routine_activity", schema=None) as batch_op:
+        for constraint_name, _ in check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("target_percent_1rm")
@@ -0,0 +1,71 @@
+"""
+Add body measurement.
+
+Revision ID: 5d3c8b1f2a47
+Revises: e4bc50b8ecb9
+Create Date: 2024-07-06
+
+"""
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "5d3c8b1f2a47"
+down_revision = "e4bc50b8ecb9"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    op.create_table(
+        "body_measurement",
+        sa.Column("user_id", sa.Integer(), nullable=False),
+        sa.Column("date", sa.Date(), nullable=False),
+        sa.Column("waist", sa.Float(), nullable=True),
+        sa.Column("hips", sa.Float(), nullable=True),
+        sa.Column("neck", sa.Float(), nullable=True),
+        sa.Column("upper_arm", sa.Float(), nullable=True),
+        sa.Column("thigh", sa.Float(), nullable=True),
+        sa.Column("chest", sa.Float(), nullable=True),
+        sa.CheckConstraint(
+            "typeof(waist) = 'real' or typeof(waist) = 'null'",
+            name=op.f("ck_body_measurement_waist_type_real_or_null"),
+        ),
+        sa.CheckConstraint(
+            "typeof(hips) = 'real' or typeof(hips) = 'null'",
+            name=op.f("ck_body_measurement_hips_type_real_or_null"),
+        ),
+        sa.CheckConstraint(
+            "typeof(neck) = 'real' or typeof(neck) = 'null'",
+            name=op.f("ck_body_measurement_neck_type_real_or_null"),
+        ),
+        sa.CheckConstraint(
+            "typeof(upper_arm) = 'real' or typeof(upper_arm) = 'null'",
+            name=op.f("ck_body_measurement_upper_arm_type_real_or_null"),
+        ),
+        sa.CheckConstraint(
+            "typeof(thigh) = 'real' or typeof(thigh) = 'null'",
+            name=op.f("ck_body_measurement_thigh_type_real_or_null"),
+        ),
+        sa.CheckConstraint(
+            "typeof(chest) = 'real' or typeof(chest) = 'null'",
+            name=op.f("ck_body_measurement_chest_type_real_or_null"),
+        ),
+        sa.CheckConstraint("waist > 0", name=op.f("ck_body_measurement_waist_gt_0")),
+        sa.CheckConstraint("hips > 0", name=op.f("ck_body_measurement_hips_gt_0")),
+        sa.CheckConstraint("neck > 0", name=op.f("ck_body_measurement_neck_gt_0")),
+        sa.CheckConstraint("upper_arm > 0", name=op.f("ck_body_measurement_upper_arm_gt_0")),
+        sa.CheckConstraint("thigh > 0", name=op.f("ck_body_measurement_thigh_gt_0")),
+        sa.CheckConstraint("chest > 0", name=op.f("ck_body_measurement_chest_gt_0")),
+        sa.ForeignKeyConstraint(
+            ["user_id"],
+            ["user.id"],
+            name=op.f("fk_body_measurement_user_id_user"),
+            ondelete="CASCADE",
+        ),
+        sa.PrimaryKeyConstraint("user_id", "date", name=op.f("pk_body_measurement")),
+    )
+
+
+def downgrade() -> None:
+    op.drop_table("body_measurement")
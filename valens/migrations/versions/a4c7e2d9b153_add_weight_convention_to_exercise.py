@@ -0,0 +1,44 @@
+"""
+Add weight convention to exercise.
+
+Revision ID: a4c7e2d9b153
+Revises: f2b8c4d61a93
+Create Date: 2024-08-11
+
+"""
+
+from typing import Union
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "a4c7e2d9b153"
+down_revision = "f2b8c4d61a93"
+branch_labels = None
+depends_on = None
+
+
+check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    ("weight_convention_valid", "weight_convention IN ('Total', 'PerSide', 'PerDumbbell')"),
+    (
+        "base_weight_type_real_or_null",
+        "typeof(base_weight) = 'real' or typeof(base_weight) = 'null'",
+    ),
+    ("base_weight_gt_0", sa.column("base_weight") > 0),
+]
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("exercise", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("weight_convention", sa.String(), nullable=True))
+        batch_op.add_column(sa.Column("base_weight", sa.Float(), nullable=True))
+        for constraint_name, condition in check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("exercise", schema=None) as batch_op:
+        for constraint_name, _ in check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("base_weight")
+        batch_op.drop_column("weight_convention")
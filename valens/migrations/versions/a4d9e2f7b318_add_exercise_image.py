@@ -0,0 +1,42 @@
+"""
+Add images to exercises.
+
+Revision ID: a4d9e2f7b318
+Revises: c58f1e0a9d34
+Create Date: 2024-09-16
+
+"""
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "a4d9e2f7b318"
+down_revision = "c58f1e0a9d34"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    op.create_table(
+        "exercise_image",
+        sa.Column("exercise_id", sa.Integer(), nullable=False),
+        sa.Column("mime_type", sa.String(), nullable=False),
+        sa.Column("digest", sa.String(), nullable=False),
+        sa.Column("data", sa.LargeBinary(), nullable=False),
+        sa.CheckConstraint(
+            "mime_type IN ('image/jpeg', 'image/png', 'image/gif', 'image/webp')",
+            name=op.f("ck_exercise_image_mime_type_valid"),
+        ),
+        sa.CheckConstraint("length(data) > 0", name=op.f("ck_exercise_image_data_not_empty")),
+        sa.ForeignKeyConstraint(
+            ["exercise_id"],
+            ["exercise.id"],
+            name=op.f("fk_exercise_image_exercise_id_exercise"),
+            ondelete="CASCADE",
+        ),
+        sa.PrimaryKeyConstraint("exercise_id", name=op.f("pk_exercise_image")),
+    )
+
+
+def downgrade() -> None:
+    op.drop_table("exercise_image")
@@ -0,0 +1,45 @@
+"""
+Add partial_reps and to_failure to workout_set.
+
+Revision ID: b5e8c3a1d7f4
+Revises: a4d9e2f7b318
+Create Date: 2024-09-22
+
+"""
+
+from typing import Union
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "b5e8c3a1d7f4"
+down_revision = "a4d9e2f7b318"
+branch_labels = None
+depends_on = None
+
+
+check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    (
+        "partial_reps_type_integer_or_null",
+        "typeof(partial_reps) = 'integer' or typeof(partial_reps) = 'null'",
+    ),
+    ("partial_reps_gt_0", sa.column("partial_reps") > 0),
+]
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("partial_reps", sa.Integer(), nullable=True))
+        batch_op.add_column(
+            sa.Column("to_failure", sa.Boolean(), nullable=False, server_default="0")
+        )
+        for constraint_name, condition in check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        for constraint_name, _ in check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("to_failure")
+        batch_op.drop_column("partial_reps")
@@ -0,0 +1,26 @@
+"""
+Add archived to exercise.
+
+Revision ID: b83e5f0c4d27
+Revises: a4c7e2d9b153
+Create Date: 2024-08-17
+
+"""
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "b83e5f0c4d27"
+down_revision = "a4c7e2d9b153"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("exercise", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("archived", sa.Boolean(), nullable=False, server_default="0"))
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("exercise", schema=None) as batch_op:
+        batch_op.drop_column("archived")
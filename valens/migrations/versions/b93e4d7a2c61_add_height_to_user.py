@@ -0,0 +1,41 @@
+"""
+Add height to user.
+
+Revision ID: b93e4d7a2c61
+Revises: e1f7a3c95d20
+Create Date: 2024-09-04
+
+"""
+
+from typing import Union
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "b93e4d7a2c61"
+down_revision = "e1f7a3c95d20"
+branch_labels = None
+depends_on = None
+
+
+check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    (
+        "height_type_real_or_null",
+        "typeof(height) = 'real' or typeof(height) = 'null'",
+    ),
+    ("height_gt_0", sa.column("height") > 0),
+]
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("user", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("height", sa.Float(), nullable=True))
+        for constraint_name, condition in check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("user", schema=None) as batch_op:
+        for constraint_name, _ in check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("height")
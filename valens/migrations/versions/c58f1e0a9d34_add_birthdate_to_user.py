@@ -0,0 +1,26 @@
+"""
+Add birthdate to user.
+
+Revision ID: c58f1e0a9d34
+Revises: b93e4d7a2c61
+Create Date: 2024-09-10
+
+"""
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "c58f1e0a9d34"
+down_revision = "b93e4d7a2c61"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("user", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("birthdate", sa.Date(), nullable=True))
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("user", schema=None) as batch_op:
+        batch_op.drop_column("birthdate")
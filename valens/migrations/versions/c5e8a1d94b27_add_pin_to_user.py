@@ -0,0 +1,44 @@
+"""
+Add PIN to user.
+
+Revision ID: c5e8a1d94b27
+Revises: 3f6a2c9e71d4
+Create Date: 2024-07-24
+
+"""
+
+from typing import Union
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "c5e8a1d94b27"
+down_revision = "3f6a2c9e71d4"
+branch_labels = None
+depends_on = None
+
+
+check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    ("pin_failures_type_integer", "typeof(pin_failures) = 'integer'"),
+    ("pin_failures_ge_0", sa.column("pin_failures") >= 0),
+]
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("user", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("pin_hash", sa.String(), nullable=True))
+        batch_op.add_column(
+            sa.Column("pin_failures", sa.Integer(), nullable=False, server_default="0")
+        )
+        batch_op.add_column(sa.Column("pin_last_failure", sa.DateTime(), nullable=True))
+        for constraint_name, condition in check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("user", schema=None) as batch_op:
+        for constraint_name, _ in check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("pin_last_failure")
+        batch_op.drop_column("pin_failures")
+        batch_op.drop_column("pin_hash")
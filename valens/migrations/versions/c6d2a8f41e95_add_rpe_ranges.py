@@ -0,0 +1,58 @@
+"""
+Add upper bound of RPE ranges to routine_activity and workout_set.
+
+Revision ID: c6d2a8f41e95
+Revises: b83e5f0c4d27
+Create Date: 2024-08-23
+
+"""
+
+from typing import Union
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "c6d2a8f41e95"
+down_revision = "b83e5f0c4d27"
+branch_labels = None
+depends_on = None
+
+
+routine_activity_check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    ("rpe_max_type_real_or_null", "typeof(rpe_max) = 'real' or typeof(rpe_max) = 'null'"),
+    ("rpe_max_gt_rpe", sa.column("rpe_max") > sa.column("rpe")),
+    ("rpe_max_le_10", sa.column("rpe_max") <= 10),
+]
+
+workout_set_check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    (
+        "target_rpe_max_type_real_or_null",
+        "typeof(target_rpe_max) = 'real' or typeof(target_rpe_max) = 'null'",
+    ),
+    ("target_rpe_max_gt_target_rpe", sa.column("target_rpe_max") > sa.column("target_rpe")),
+    ("target_rpe_max_le_10", sa.column("target_rpe_max") <= 10),
+]
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("routine_activity", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("rpe_max", sa.Float(), nullable=True))
+        for constraint_name, condition in routine_activity_check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("target_rpe_max", sa.Float(), nullable=True))
+        for constraint_name, condition in workout_set_check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        for constraint_name, _ in workout_set_check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("target_rpe_max")
+
+    with op.batch_alter_table("routine_activity", schema=None) as batch_op:
+        for constraint_name, _ in routine_activity_check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("rpe_max")
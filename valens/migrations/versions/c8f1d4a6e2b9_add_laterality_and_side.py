@@ -0,0 +1,40 @@
+"""
+Add laterality to exercise and side to workout_set.
+
+Revision ID: c8f1d4a6e2b9
+Revises: b5e8c3a1d7f4
+Create Date: 2024-09-28
+
+"""
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "c8f1d4a6e2b9"
+down_revision = "b5e8c3a1d7f4"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("exercise", schema=None) as batch_op:
+        batch_op.add_column(
+            sa.Column("laterality", sa.String(), nullable=False, server_default="Bilateral")
+        )
+        batch_op.create_check_constraint(
+            "laterality_valid", "laterality IN ('Bilateral', 'Unilateral')"
+        )
+
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("side", sa.String(), nullable=True))
+        batch_op.create_check_constraint("side_valid", "side IN ('Left', 'Right')")
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        batch_op.drop_constraint("side_valid", type_="check")
+        batch_op.drop_column("side")
+
+    with op.batch_alter_table("exercise", schema=None) as batch_op:
+        batch_op.drop_constraint("laterality_valid", type_="check")
+        batch_op.drop_column("laterality")
@@ -0,0 +1,64 @@
+"""
+Add upper bound of reps ranges to routine_activity and workout_set.
+
+Revision ID: d4a7b2e9c5f1
+Revises: c8f1d4a6e2b9
+Create Date: 2024-10-04
+
+"""
+
+from typing import Union
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "d4a7b2e9c5f1"
+down_revision = "c8f1d4a6e2b9"
+branch_labels = None
+depends_on = None
+
+
+routine_activity_check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    (
+        "reps_max_type_integer_or_null",
+        "typeof(reps_max) = 'integer' or typeof(reps_max) = 'null'",
+    ),
+    ("reps_max_gt_reps", sa.column("reps_max") > sa.column("reps")),
+    ("reps_max_lt_1000", sa.column("reps_max") < 1000),
+]
+
+workout_set_check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    (
+        "target_reps_max_type_integer_or_null",
+        "typeof(target_reps_max) = 'integer' or typeof(target_reps_max) = 'null'",
+    ),
+    (
+        "target_reps_max_gt_target_reps",
+        sa.column("target_reps_max") > sa.column("target_reps"),
+    ),
+    ("target_reps_max_lt_1000", sa.column("target_reps_max") < 1000),
+]
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("routine_activity", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("reps_max", sa.Integer(), nullable=True))
+        for constraint_name, condition in routine_activity_check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("target_reps_max", sa.Integer(), nullable=True))
+        for constraint_name, condition in workout_set_check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("workout_set", schema=None) as batch_op:
+        for constraint_name, _ in workout_set_check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("target_reps_max")
+
+    with op.batch_alter_table("routine_activity", schema=None) as batch_op:
+        for constraint_name, _ in routine_activity_check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("reps_max")
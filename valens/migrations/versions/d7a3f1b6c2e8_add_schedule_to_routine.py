@@ -0,0 +1,39 @@
+"""
+Add schedule to routine.
+
+Revision ID: d7a3f1b6c2e8
+Revises: c5e8a1d94b27
+Create Date: 2024-07-30
+
+"""
+
+from typing import Union
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "d7a3f1b6c2e8"
+down_revision = "c5e8a1d94b27"
+branch_labels = None
+depends_on = None
+
+
+check_constraints: list[tuple[str, Union[str, sa.ColumnElement[bool]]]] = [
+    ("schedule_type_integer", "typeof(schedule) = 'integer'"),
+    ("schedule_ge_0", sa.column("schedule") >= 0),
+    ("schedule_lt_128", sa.column("schedule") < 128),
+]
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("routine", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("schedule", sa.Integer(), nullable=False, server_default="0"))
+        for constraint_name, condition in check_constraints:
+            batch_op.create_check_constraint(constraint_name, condition)
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("routine", schema=None) as batch_op:
+        for constraint_name, _ in check_constraints:
+            batch_op.drop_constraint(constraint_name, type_="check")
+        batch_op.drop_column("schedule")
@@ -0,0 +1,42 @@
+"""
+Add tags to routines.
+
+Revision ID: e1f7a3c95d20
+Revises: c6d2a8f41e95
+Create Date: 2024-08-29
+
+"""
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "e1f7a3c95d20"
+down_revision = "c6d2a8f41e95"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    op.create_table(
+        "routine_tag",
+        sa.Column("routine_id", sa.Integer(), nullable=False),
+        sa.Column("name", sa.String(), nullable=False),
+        sa.Column("color", sa.String(), nullable=False),
+        sa.CheckConstraint("typeof(name) = 'text'", name=op.f("ck_routine_tag_name_type_text")),
+        sa.CheckConstraint("length(name) > 0", name=op.f("ck_routine_tag_name_not_empty")),
+        sa.CheckConstraint(
+            "color IN ('Gray', 'Turquoise', 'Blue', 'Cyan', 'Green', 'Yellow', 'Red')",
+            name=op.f("ck_routine_tag_color_valid"),
+        ),
+        sa.ForeignKeyConstraint(
+            ["routine_id"],
+            ["routine.id"],
+            name=op.f("fk_routine_tag_routine_id_routine"),
+            ondelete="CASCADE",
+        ),
+        sa.PrimaryKeyConstraint("routine_id", "name", name=op.f("pk_routine_tag")),
+    )
+
+
+def downgrade() -> None:
+    op.drop_table("routine_tag")
@@ -0,0 +1,28 @@
+"""
+Add start and end time to workout.
+
+Revision ID: f2b8c4d61a93
+Revises: d7a3f1b6c2e8
+Create Date: 2024-08-05
+
+"""
+
+import sqlalchemy as sa
+from alembic import op
+
+revision = "f2b8c4d61a93"
+down_revision = "d7a3f1b6c2e8"
+branch_labels = None
+depends_on = None
+
+
+def upgrade() -> None:
+    with op.batch_alter_table("workout", schema=None) as batch_op:
+        batch_op.add_column(sa.Column("start_time", sa.DateTime(), nullable=True))
+        batch_op.add_column(sa.Column("end_time", sa.DateTime(), nullable=True))
+
+
+def downgrade() -> None:
+    with op.batch_alter_table("workout", schema=None) as batch_op:
+        batch_op.drop_column("end_time")
+        batch_op.drop_column("start_time")
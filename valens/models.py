@@ -8,11 +8,13 @@ from sqlalchemy import (
     CheckConstraint,
     Constraint,
     Date,
+    DateTime,
     Enum,
     Float,
     ForeignKey,
     ForeignKeyConstraint,
     Integer,
+    LargeBinary,
     MetaData,
     String,
     UniqueConstraint,
@@ -47,10 +49,24 @@ class Sex(enum.IntEnum):
 
 class User(Base):
     __tablename__ = "user"
+    __table_args__ = (
+        CheckConstraint("typeof(pin_failures) = 'integer'", name="pin_failures_type_integer"),
+        CheckConstraint(column("pin_failures") >= 0, name="pin_failures_ge_0"),
+        CheckConstraint(
+            "typeof(height) = 'real' or typeof(height) = 'null'",
+            name="height_type_real_or_null",
+        ),
+        CheckConstraint(column("height") > 0, name="height_gt_0"),
+    )
 
     id: Mapped[int] = mapped_column(Integer, primary_key=True)
     name: Mapped[str] = mapped_column(String, unique=True, nullable=False)
     sex: Mapped[Sex] = mapped_column(Enum(Sex), nullable=False)
+    height: Mapped[Optional[float]] = mapped_column(Float, nullable=True)
+    birthdate: Mapped[Optional[datetime.date]] = mapped_column(Date, nullable=True)
+    pin_hash: Mapped[Optional[str]] = mapped_column(String, nullable=True)
+    pin_failures: Mapped[int] = mapped_column(Integer, nullable=False, default=0)
+    pin_last_failure: Mapped[Optional[datetime.datetime]] = mapped_column(DateTime, nullable=True)
 
     body_weight: Mapped[list[BodyWeight]] = relationship(
         "BodyWeight", backref="user", cascade="all, delete-orphan", passive_deletes=True
@@ -58,6 +74,9 @@ class User(Base):
     body_fat: Mapped[list[BodyFat]] = relationship(
         "BodyFat", backref="user", cascade="all, delete-orphan", passive_deletes=True
     )
+    body_measurement: Mapped[list[BodyMeasurement]] = relationship(
+        "BodyMeasurement", backref="user", cascade="all, delete-orphan", passive_deletes=True
+    )
     period: Mapped[list[Period]] = relationship(
         "Period", backref="user", cascade="all, delete-orphan", passive_deletes=True
     )
@@ -139,6 +158,53 @@ class BodyFat(Base):
     midaxillary: Mapped[Optional[int]] = mapped_column(Integer)
 
 
+class BodyMeasurement(Base):
+    __tablename__ = "body_measurement"
+    __table_args__ = (
+        CheckConstraint(
+            "typeof(waist) = 'real' or typeof(waist) = 'null'",
+            name="waist_type_real_or_null",
+        ),
+        CheckConstraint(
+            "typeof(hips) = 'real' or typeof(hips) = 'null'",
+            name="hips_type_real_or_null",
+        ),
+        CheckConstraint(
+            "typeof(neck) = 'real' or typeof(neck) = 'null'",
+            name="neck_type_real_or_null",
+        ),
+        CheckConstraint(
+            "typeof(upper_arm) = 'real' or typeof(upper_arm) = 'null'",
+            name="upper_arm_type_real_or_null",
+        ),
+        CheckConstraint(
+            "typeof(thigh) = 'real' or typeof(thigh) = 'null'",
+            name="thigh_type_real_or_null",
+        ),
+        CheckConstraint(
+            "typeof(chest) = 'real' or typeof(chest) = 'null'",
+            name="chest_type_real_or_null",
+        ),
+        CheckConstraint(column("waist") > 0, name="waist_gt_0"),
+        CheckConstraint(column("hips") > 0, name="hips_gt_0"),
+        CheckConstraint(column("neck") > 0, name="neck_gt_0"),
+        CheckConstraint(column("upper_arm") > 0, name="upper_arm_gt_0"),
+        CheckConstraint(column("thigh") > 0, name="thigh_gt_0"),
+        CheckConstraint(column("chest") > 0, name="chest_gt_0"),
+    )
+
+    user_id: Mapped[int] = mapped_column(
+        ForeignKey("user.id", ondelete="CASCADE"), primary_key=True
+    )
+    date: Mapped[datetime.date] = mapped_column(Date, primary_key=True)
+    waist: Mapped[Optional[float]] = mapped_column(Float)
+    hips: Mapped[Optional[float]] = mapped_column(Float)
+    neck: Mapped[Optional[float]] = mapped_column(Float)
+    upper_arm: Mapped[Optional[float]] = mapped_column(Float)
+    thigh: Mapped[Optional[float]] = mapped_column(Float)
+    chest: Mapped[Optional[float]] = mapped_column(Float)
+
+
 class Period(Base):
     __tablename__ = "period"
     __table_args__ = (
@@ -156,11 +222,30 @@ class Period(Base):
 
 class Exercise(Base):
     __tablename__ = "exercise"
-    __table_args__ = (UniqueConstraint("user_id", "name"),)
+    __table_args__ = (
+        UniqueConstraint("user_id", "name"),
+        CheckConstraint(
+            "weight_convention IN ('Total', 'PerSide', 'PerDumbbell')",
+            name="weight_convention_valid",
+        ),
+        CheckConstraint(
+            "typeof(base_weight) = 'real' or typeof(base_weight) = 'null'",
+            name="base_weight_type_real_or_null",
+        ),
+        CheckConstraint(column("base_weight") > 0, name="base_weight_gt_0"),
+        CheckConstraint(
+            "laterality IN ('Bilateral', 'Unilateral')",
+            name="laterality_valid",
+        ),
+    )
 
     id: Mapped[int] = mapped_column(Integer, primary_key=True)
     user_id: Mapped[int] = mapped_column(ForeignKey("user.id", ondelete="CASCADE"), nullable=False)
     name: Mapped[str] = mapped_column(String, nullable=False)
+    weight_convention: Mapped[Optional[str]] = mapped_column(String)
+    base_weight: Mapped[Optional[float]] = mapped_column(Float)
+    laterality: Mapped[str] = mapped_column(String, nullable=False, default="Bilateral")
+    archived: Mapped[bool] = mapped_column(default=False)
 
     muscles: Mapped[list[ExerciseMuscle]] = relationship(
         "ExerciseMuscle", backref="exercise", cascade="all, delete-orphan"
@@ -171,6 +256,31 @@ class Exercise(Base):
     routine_activities: Mapped[list[RoutineActivity]] = relationship(
         "RoutineActivity", back_populates="exercise", cascade="all, delete-orphan"
     )
+    image: Mapped[Optional[ExerciseImage]] = relationship(
+        "ExerciseImage", back_populates="exercise", cascade="all, delete-orphan"
+    )
+
+
+class ExerciseImage(Base):
+    __tablename__ = "exercise_image"
+    __table_args__ = (
+        CheckConstraint(
+            "mime_type IN ('image/jpeg', 'image/png', 'image/gif', 'image/webp')",
+            name="mime_type_valid",
+        ),
+        CheckConstraint("length(data) > 0", name="data_not_empty"),
+    )
+
+    exercise_id: Mapped[int] = mapped_column(
+        ForeignKey("exercise.id", ondelete="CASCADE"), nullable=False, primary_key=True
+    )
+    mime_type: Mapped[str] = mapped_column(String, nullable=False)
+    # Identifies the image content and enables clients to detect outdated cached images
+    digest: Mapped[str] = mapped_column(String, nullable=False)
+    # Loaded only when accessed, as the image is not part of the exercise representation
+    data: Mapped[bytes] = mapped_column(LargeBinary, nullable=False, deferred=True)
+
+    exercise: Mapped[Exercise] = relationship("Exercise", back_populates="image")
 
 
 class ExerciseMuscle(Base):
@@ -195,20 +305,49 @@ class ExerciseMuscle(Base):
 
 class Routine(Base):
     __tablename__ = "routine"
-    __table_args__ = (UniqueConstraint("user_id", "name"),)
+    __table_args__: tuple[Constraint, ...] = (
+        UniqueConstraint("user_id", "name"),
+        CheckConstraint("typeof(schedule) = 'integer'", name="schedule_type_integer"),
+        CheckConstraint(column("schedule") >= 0, name="schedule_ge_0"),
+        CheckConstraint(column("schedule") < 128, name="schedule_lt_128"),
+    )
 
     id: Mapped[int] = mapped_column(Integer, primary_key=True)
     user_id: Mapped[int] = mapped_column(ForeignKey("user.id", ondelete="CASCADE"), nullable=False)
     name: Mapped[str] = mapped_column(String, nullable=False)
     notes: Mapped[Optional[str]] = mapped_column(String)
     archived: Mapped[bool] = mapped_column(default=False)
+    schedule: Mapped[int] = mapped_column(Integer, nullable=False, default=0)
 
     sections: Mapped[list[RoutineSection]] = relationship(
         "RoutineSection", back_populates="routine", cascade="all, delete-orphan"
     )
+    tags: Mapped[list[RoutineTag]] = relationship(
+        "RoutineTag", back_populates="routine", cascade="all, delete-orphan"
+    )
     workouts: Mapped[list[Workout]] = relationship("Workout", back_populates="routine")
 
 
+class RoutineTag(Base):
+    __tablename__ = "routine_tag"
+    __table_args__ = (
+        CheckConstraint("typeof(name) = 'text'", name="name_type_text"),
+        CheckConstraint("length(name) > 0", name="name_not_empty"),
+        CheckConstraint(
+            "color IN ('Gray', 'Turquoise', 'Blue', 'Cyan', 'Green', 'Yellow', 'Red')",
+            name="color_valid",
+        ),
+    )
+
+    routine_id: Mapped[int] = mapped_column(
+        ForeignKey("routine.id", ondelete="CASCADE"), nullable=False, primary_key=True
+    )
+    name: Mapped[str] = mapped_column(String, nullable=False, primary_key=True)
+    color: Mapped[str] = mapped_column(String, nullable=False)
+
+    routine: Mapped[Routine] = relationship("Routine", back_populates="tags")
+
+
 class RoutinePart(Base):
     __tablename__ = "routine_part"
     __table_args__: tuple[CheckConstraint, ...] = (
@@ -266,6 +405,12 @@ class RoutineActivity(RoutinePart):
     __table_args__ = (
         CheckConstraint("typeof(reps) = 'integer'", name="reps_type_integer"),
         CheckConstraint(column("reps") >= 0, name="reps_ge_0"),
+        CheckConstraint(
+            "typeof(reps_max) = 'integer' or typeof(reps_max) = 'null'",
+            name="reps_max_type_integer_or_null",
+        ),
+        CheckConstraint(column("reps_max") > column("reps"), name="reps_max_gt_reps"),
+        CheckConstraint(column("reps_max") < 1000, name="reps_max_lt_1000"),
         CheckConstraint("typeof(time) = 'integer'", name="time_type_integer"),
         CheckConstraint(column("time") >= 0, name="time_ge_0"),
         CheckConstraint("typeof(weight) = 'real'", name="weight_type_real"),
@@ -273,6 +418,18 @@ class RoutineActivity(RoutinePart):
         CheckConstraint("typeof(rpe) = 'real'", name="rpe_type_real"),
         CheckConstraint(column("rpe") >= 0, name="rpe_ge_0"),
         CheckConstraint(column("rpe") <= 10, name="rpe_le_10"),
+        CheckConstraint(
+            "typeof(rpe_max) = 'real' or typeof(rpe_max) = 'null'",
+            name="rpe_max_type_real_or_null",
+        ),
+        CheckConstraint(column("rpe_max") > column("rpe"), name="rpe_max_gt_rpe"),
+        CheckConstraint(column("rpe_max") <= 10, name="rpe_max_le_10"),
+        CheckConstraint(
+            "typeof(target_percent_1rm) = 'real' or typeof(target_percent_1rm) = 'null'",
+            name="target_percent_1rm_type_real_or_null",
+        ),
+        CheckConstraint(column("target_percent_1rm") > 0, name="target_percent_1rm_gt_0"),
+        CheckConstraint(column("target_percent_1rm") <= 100, name="target_percent_1rm_le_100"),
     )
 
     id: Mapped[int] = mapped_column(Integer, ForeignKey("routine_part.id"), primary_key=True)
@@ -280,10 +437,13 @@ class RoutineActivity(RoutinePart):
         ForeignKey("exercise.id", ondelete="CASCADE")
     )
     reps: Mapped[int]
+    reps_max: Mapped[Optional[int]]
     time: Mapped[int]
     weight: Mapped[float]
     rpe: Mapped[float]
+    rpe_max: Mapped[Optional[float]]
     automatic: Mapped[bool]
+    target_percent_1rm: Mapped[Optional[float]]
 
     exercise: Mapped[Exercise] = relationship("Exercise", back_populates="routine_activities")
 
@@ -302,6 +462,8 @@ class Workout(Base):
     routine_id: Mapped[Optional[int]] = mapped_column(ForeignKey("routine.id", ondelete="CASCADE"))
     date: Mapped[datetime.date] = mapped_column(Date, nullable=False)
     notes: Mapped[Optional[str]] = mapped_column(String)
+    start_time: Mapped[Optional[datetime.datetime]] = mapped_column(DateTime)
+    end_time: Mapped[Optional[datetime.datetime]] = mapped_column(DateTime)
 
     routine: Mapped[Routine] = relationship("Routine", back_populates="workouts")
     elements: Mapped[list[WorkoutElement]] = relationship(
@@ -368,6 +530,10 @@ class WorkoutSet(WorkoutElement):
             "typeof(target_reps) = 'integer' or typeof(target_reps) = 'null'",
             name="target_reps_type_integer_or_null",
         ),
+        CheckConstraint(
+            "typeof(target_reps_max) = 'integer' or typeof(target_reps_max) = 'null'",
+            name="target_reps_max_type_integer_or_null",
+        ),
         CheckConstraint(
             "typeof(target_time) = 'integer' or typeof(target_time) = 'null'",
             name="target_time_type_integer_or_null",
@@ -380,6 +546,14 @@ class WorkoutSet(WorkoutElement):
             "typeof(target_rpe) = 'real' or typeof(target_rpe) = 'null'",
             name="target_rpe_type_real_or_null",
         ),
+        CheckConstraint(
+            "typeof(target_rpe_max) = 'real' or typeof(target_rpe_max) = 'null'",
+            name="target_rpe_max_type_real_or_null",
+        ),
+        CheckConstraint(
+            "typeof(partial_reps) = 'integer' or typeof(partial_reps) = 'null'",
+            name="partial_reps_type_integer_or_null",
+        ),
         CheckConstraint(column("position") > 0, name="position_gt_0"),
         CheckConstraint(column("reps") > 0, name="reps_gt_0"),
         CheckConstraint(column("time") > 0, name="time_gt_0"),
@@ -387,10 +561,21 @@ class WorkoutSet(WorkoutElement):
         CheckConstraint(column("rpe") >= 0, name="rpe_ge_0"),
         CheckConstraint(column("rpe") <= 10, name="rpe_le_10"),
         CheckConstraint(column("target_reps") > 0, name="target_reps_gt_0"),
+        CheckConstraint(
+            column("target_reps_max") > column("target_reps"),
+            name="target_reps_max_gt_target_reps",
+        ),
+        CheckConstraint(column("target_reps_max") < 1000, name="target_reps_max_lt_1000"),
         CheckConstraint(column("target_time") > 0, name="target_time_gt_0"),
         CheckConstraint(column("target_weight") > 0, name="target_weight_gt_0"),
         CheckConstraint(column("target_rpe") >= 0, name="target_rpe_ge_0"),
         CheckConstraint(column("target_rpe") <= 10, name="target_rpe_le_10"),
+        CheckConstraint(
+            column("target_rpe_max") > column("target_rpe"), name="target_rpe_max_gt_target_rpe"
+        ),
+        CheckConstraint(column("target_rpe_max") <= 10, name="target_rpe_max_le_10"),
+        CheckConstraint(column("partial_reps") > 0, name="partial_reps_gt_0"),
+        CheckConstraint("side IN ('Left', 'Right')", name="side_valid"),
         ForeignKeyConstraint(
             ["workout_id", "position"],
             [WorkoutElement.workout_id, WorkoutElement.position],
@@ -408,9 +593,15 @@ class WorkoutSet(WorkoutElement):
     weight: Mapped[Optional[float]]
     rpe: Mapped[Optional[float]]
     target_reps: Mapped[Optional[int]]
+    target_reps_max: Mapped[Optional[int]]
     target_time: Mapped[Optional[int]]
     target_weight: Mapped[Optional[float]]
     target_rpe: Mapped[Optional[float]]
+    target_rpe_max: Mapped[Optional[float]]
+    partial_reps: Mapped[Optional[int]]
+    to_failure: Mapped[bool] = mapped_column(default=False)
+    side: Mapped[Optional[str]] = mapped_column(String)
+    comment: Mapped[Optional[str]] = mapped_column(String)
 
     exercise: Mapped[Exercise] = relationship("Exercise", back_populates="sets")
 